@@ -86,6 +86,12 @@ pub enum Expr<'a> {
         expr: Box<Expr<'a>>,
         target_type: TypeName,
     },
+    /// `<expr> AT TIME ZONE '<timezone>'` expression
+    AtTimeZone {
+        span: &'a [Token<'a>],
+        expr: Box<Expr<'a>>,
+        timezone: String,
+    },
     /// EXTRACT(IntervalKind FROM <expr>)
     Extract {
         span: &'a [Token<'a>],
@@ -168,6 +174,8 @@ pub enum Literal {
     Interval(Interval),
     CurrentTimestamp,
     Null,
+    // Parameter placeholder `?` of a prepared statement
+    Placeholder,
 }
 
 /// The display style for a map access expression
@@ -268,6 +276,7 @@ impl<'a> Expr<'a> {
             Expr::UnaryOp { span, .. } => span,
             Expr::Cast { span, .. } => span,
             Expr::TryCast { span, .. } => span,
+            Expr::AtTimeZone { span, .. } => span,
             Expr::Extract { span, .. } => span,
             Expr::Position { span, .. } => span,
             Expr::Substring { span, .. } => span,
@@ -485,6 +494,9 @@ impl Display for Literal {
             Literal::Null => {
                 write!(f, "NULL")
             }
+            Literal::Placeholder => {
+                write!(f, "?")
+            }
         }
     }
 }
@@ -575,6 +587,9 @@ impl<'a> Display for Expr<'a> {
             } => {
                 write!(f, "TRY_CAST({expr} AS {target_type})")?;
             }
+            Expr::AtTimeZone { expr, timezone, .. } => {
+                write!(f, "{expr} AT TIME ZONE '{timezone}'")?;
+            }
             Expr::Extract {
                 kind: field, expr, ..
             } => {
@@ -25,6 +25,8 @@ use crate::parser::token::Token;
 #[derive(Debug, Clone, PartialEq)]
 pub struct Query<'a> {
     pub span: &'a [Token<'a>],
+    // `WITH` clause, common table expressions
+    pub with: Option<With<'a>>,
 
     // Set operator: SELECT or UNION / EXCEPT / INTERSECT
     pub body: SetExpr<'a>,
@@ -38,6 +40,21 @@ pub struct Query<'a> {
     pub offset: Option<Expr<'a>>,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct With<'a> {
+    pub span: &'a [Token<'a>],
+    pub recursive: bool,
+    pub ctes: Vec<Cte<'a>>,
+}
+
+// A common table expression, like `t(a, b) AS (SELECT ...)`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cte<'a> {
+    pub span: &'a [Token<'a>],
+    pub alias: TableAlias<'a>,
+    pub query: Query<'a>,
+}
+
 // A relational set expression, like `SELECT ... FROM ... {UNION|EXCEPT|INTERSECT} SELECT ... FROM ...`
 #[derive(Debug, Clone, PartialEq)]
 pub enum SetExpr<'a> {
@@ -73,11 +90,24 @@ pub struct SelectStmt<'a> {
     // `WHERE` clause
     pub selection: Option<Expr<'a>>,
     // `GROUP BY` clause
-    pub group_by: Vec<Expr<'a>>,
+    pub group_by: Option<GroupBy<'a>>,
     // `HAVING` clause
     pub having: Option<Expr<'a>>,
 }
 
+// `GROUP BY` clause
+#[derive(Debug, Clone, PartialEq)]
+pub enum GroupBy<'a> {
+    // `GROUP BY a, b`
+    Normal(Vec<Expr<'a>>),
+    // `GROUP BY GROUPING SETS ((a, b), (a), ())`
+    GroupingSets(Vec<Vec<Expr<'a>>>),
+    // `GROUP BY CUBE (a, b)`, same as the grouping sets of all the subsets of the expressions
+    Cube(Vec<Expr<'a>>),
+    // `GROUP BY ROLLUP (a, b)`, same as the grouping sets of all the prefixes of the expressions
+    Rollup(Vec<Expr<'a>>),
+}
+
 // `ORDER BY` clause
 #[derive(Debug, Clone, PartialEq)]
 pub struct OrderByExpr<'a> {
@@ -197,7 +227,7 @@ impl<'a> Display for TableAlias<'a> {
         write!(f, "{}", &self.name)?;
         if !self.columns.is_empty() {
             write!(f, " (")?;
-            write_period_separated_list(f, &self.columns)?;
+            write_comma_separated_list(f, &self.columns)?;
             write!(f, ")")?;
         }
         Ok(())
@@ -330,9 +360,8 @@ impl<'a> Display for SelectStmt<'a> {
         }
 
         // GROUP BY clause
-        if !self.group_by.is_empty() {
-            write!(f, " GROUP BY ")?;
-            write_comma_separated_list(f, &self.group_by)?;
+        if let Some(group_by) = &self.group_by {
+            write!(f, " GROUP BY {group_by}")?;
         }
 
         // HAVING clause
@@ -344,6 +373,36 @@ impl<'a> Display for SelectStmt<'a> {
     }
 }
 
+impl<'a> Display for GroupBy<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GroupBy::Normal(exprs) => write_comma_separated_list(f, exprs),
+            GroupBy::GroupingSets(sets) => {
+                write!(f, "GROUPING SETS (")?;
+                for (i, set) in sets.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "(")?;
+                    write_comma_separated_list(f, set)?;
+                    write!(f, ")")?;
+                }
+                write!(f, ")")
+            }
+            GroupBy::Cube(exprs) => {
+                write!(f, "CUBE (")?;
+                write_comma_separated_list(f, exprs)?;
+                write!(f, ")")
+            }
+            GroupBy::Rollup(exprs) => {
+                write!(f, "ROLLUP (")?;
+                write_comma_separated_list(f, exprs)?;
+                write!(f, ")")
+            }
+        }
+    }
+}
+
 impl<'a> Display for SetExpr<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -362,7 +421,7 @@ impl<'a> Display for SetExpr<'a> {
                 write!(f, "{left}")?;
                 match op {
                     SetOperator::Union => {
-                        write!(f, " UNION ")?;
+                        write!(f, " UNION")?;
                     }
                     SetOperator::Except => {
                         write!(f, " EXCEPT")?;
@@ -374,7 +433,7 @@ impl<'a> Display for SetExpr<'a> {
                 if *all {
                     write!(f, " ALL")?;
                 }
-                write!(f, "{right}")?;
+                write!(f, " {right}")?;
             }
         }
         Ok(())
@@ -383,6 +442,11 @@ impl<'a> Display for SetExpr<'a> {
 
 impl<'a> Display for Query<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        // WITH clause
+        if let Some(with) = &self.with {
+            write!(f, "{with} ")?;
+        }
+
         // Query body
         write!(f, "{}", self.body)?;
 
@@ -406,3 +470,19 @@ impl<'a> Display for Query<'a> {
         Ok(())
     }
 }
+
+impl<'a> Display for With<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "WITH ")?;
+        if self.recursive {
+            write!(f, "RECURSIVE ")?;
+        }
+        write_comma_separated_list(f, &self.ctes)
+    }
+}
+
+impl<'a> Display for Cte<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} AS ({})", self.alias, self.query)
+    }
+}
@@ -186,19 +186,46 @@ pub enum Statement<'a> {
     CreateUDF {
         if_not_exists: bool,
         udf_name: Identifier<'a>,
-        parameters: Vec<Identifier<'a>>,
-        definition: Box<Expr<'a>>,
+        kind: UDFKind<'a>,
         description: Option<String>,
     },
     DropUDF {
         if_exists: bool,
         udf_name: Identifier<'a>,
+        // Drop only the overload with these parameter types
+        parameter_types: Option<Vec<TypeName>>,
     },
     AlterUDF {
         udf_name: Identifier<'a>,
+        kind: UDFKind<'a>,
+        description: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UDFKind<'a> {
+    /// `AS (<parameter>, ...) -> <definition expr>`
+    Lambda {
         parameters: Vec<Identifier<'a>>,
         definition: Box<Expr<'a>>,
-        description: Option<String>,
+    },
+    /// `(<parameter> <type>, ...) RETURNS <type> AS <definition expr>`
+    Scalar {
+        parameters: Vec<(Identifier<'a>, TypeName)>,
+        return_type: TypeName,
+        definition: Box<Expr<'a>>,
+    },
+    /// `(<parameter> <type>, ...) RETURNS <type> LANGUAGE WASM AS '<base64 module>'`
+    Wasm {
+        parameters: Vec<(Identifier<'a>, TypeName)>,
+        return_type: TypeName,
+        module: String,
+    },
+    /// `(<parameter> <type>, ...) RETURNS TABLE (<column> <type>, ...) AS <query>`
+    Table {
+        parameters: Vec<(Identifier<'a>, TypeName)>,
+        return_columns: Vec<(Identifier<'a>, TypeName)>,
+        definition: Box<Query<'a>>,
     },
 }
 
@@ -301,6 +328,61 @@ impl<'a> Display for ShowLimit<'a> {
     }
 }
 
+impl<'a> Display for UDFKind<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        fn write_typed_list(
+            f: &mut Formatter<'_>,
+            items: &[(Identifier, TypeName)],
+        ) -> std::fmt::Result {
+            write!(f, "(")?;
+            for (i, (name, data_type)) in items.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{name} {data_type}")?;
+            }
+            write!(f, ")")
+        }
+
+        match self {
+            UDFKind::Lambda {
+                parameters,
+                definition,
+            } => {
+                write!(f, " AS (")?;
+                write_comma_separated_list(f, parameters)?;
+                write!(f, ") -> {definition}")
+            }
+            UDFKind::Scalar {
+                parameters,
+                return_type,
+                definition,
+            } => {
+                write_typed_list(f, parameters)?;
+                write!(f, " RETURNS {return_type} AS {definition}")
+            }
+            UDFKind::Wasm {
+                parameters,
+                return_type,
+                module,
+            } => {
+                write_typed_list(f, parameters)?;
+                write!(f, " RETURNS {return_type} LANGUAGE WASM AS '{module}'")
+            }
+            UDFKind::Table {
+                parameters,
+                return_columns,
+                definition,
+            } => {
+                write_typed_list(f, parameters)?;
+                write!(f, " RETURNS TABLE ")?;
+                write_typed_list(f, return_columns)?;
+                write!(f, " AS {definition}")
+            }
+        }
+    }
+}
+
 impl<'a> Display for ColumnDefinition<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{} {}", self.name, self.data_type)?;
@@ -715,17 +797,14 @@ impl<'a> Display for Statement<'a> {
             Statement::CreateUDF {
                 if_not_exists,
                 udf_name,
-                parameters,
-                definition,
+                kind,
                 description,
             } => {
                 write!(f, "CREATE FUNCTION")?;
                 if *if_not_exists {
                     write!(f, " IF NOT EXISTS")?;
                 }
-                write!(f, " {udf_name} AS (")?;
-                write_comma_separated_list(f, parameters)?;
-                write!(f, ") -> {definition}")?;
+                write!(f, " {udf_name}{kind}")?;
                 if let Some(description) = description {
                     write!(f, " DESC = '{description}'")?;
                 }
@@ -733,22 +812,25 @@ impl<'a> Display for Statement<'a> {
             Statement::DropUDF {
                 if_exists,
                 udf_name,
+                parameter_types,
             } => {
                 write!(f, "DROP FUNCTION")?;
                 if *if_exists {
                     write!(f, " IF EXISTS")?;
                 }
                 write!(f, " {udf_name}")?;
+                if let Some(parameter_types) = parameter_types {
+                    write!(f, "(")?;
+                    write_comma_separated_list(f, parameter_types)?;
+                    write!(f, ")")?;
+                }
             }
             Statement::AlterUDF {
                 udf_name,
-                parameters,
-                definition,
+                kind,
                 description,
             } => {
-                write!(f, "ALTER FUNCTION {udf_name} AS (")?;
-                write_comma_separated_list(f, parameters)?;
-                write!(f, ") -> {definition}")?;
+                write!(f, "ALTER FUNCTION {udf_name}{kind}")?;
                 if let Some(description) = description {
                     write!(f, " DESC = '{description}'")?;
                 }
@@ -197,6 +197,8 @@ pub enum ExprElement<'a> {
     },
     /// `::<type_name>` expression
     PgCast { target_type: TypeName },
+    /// `AT TIME ZONE '<timezone>'` expression
+    AtTimeZone { timezone: String },
     /// EXTRACT(IntervalKind FROM <expr>)
     Extract {
         field: IntervalKind,
@@ -305,6 +307,7 @@ impl<'a, I: Iterator<Item = WithSpan<'a>>> PrattParser<I> for ExprParser {
                 BinaryOperator::Modulo => Affix::Infix(Precedence(40), Associativity::Left),
                 BinaryOperator::StringConcat => Affix::Infix(Precedence(40), Associativity::Left),
             },
+            ExprElement::AtTimeZone { .. } => Affix::Postfix(Precedence(45)),
             ExprElement::PgCast { .. } => Affix::Postfix(Precedence(50)),
             _ => Affix::Nilfix,
         };
@@ -476,6 +479,11 @@ impl<'a, I: Iterator<Item = WithSpan<'a>>> PrattParser<I> for ExprParser {
                 target_type,
                 pg_style: true,
             },
+            ExprElement::AtTimeZone { timezone } => Expr::AtTimeZone {
+                span: elem.span.0,
+                expr: Box::new(lhs),
+                timezone,
+            },
             _ => unreachable!(),
         };
         Ok(expr)
@@ -570,6 +578,12 @@ pub fn expr_element(i: Input) -> IResult<WithSpan> {
         },
         |(_, target_type)| ExprElement::PgCast { target_type },
     );
+    let at_time_zone = map(
+        rule! {
+            AT ~ TIME ~ ZONE ~ ^#literal_string
+        },
+        |(_, _, _, timezone)| ExprElement::AtTimeZone { timezone },
+    );
     let extract = map(
         rule! {
             EXTRACT ~ ^"(" ~ ^#interval_kind ~ ^FROM ~ ^#subexpr(0) ~ ^")"
@@ -736,6 +750,7 @@ pub fn expr_element(i: Input) -> IResult<WithSpan> {
             | #unary_op : "<operator>"
             | #cast : "`CAST(... AS ...)`"
             | #pg_cast : "`::<type_name>`"
+            | #at_time_zone : "`AT TIME ZONE '<timezone>'`"
             | #extract : "`EXTRACT((YEAR | MONTH | DAY | HOUR | MINUTE | SECOND) FROM ...)`"
             | #position : "`POSITION(... IN ...)`"
             | #substring : "`SUBSTRING(... [FROM ...] [FOR ...])`"
@@ -820,6 +835,7 @@ pub fn literal(i: Input) -> IResult<Literal> {
     );
     let current_timestamp = value(Literal::CurrentTimestamp, rule! { CURRENT_TIMESTAMP });
     let null = value(Literal::Null, rule! { NULL });
+    let placeholder = value(Literal::Placeholder, rule! { Placeholder });
 
     rule!(
         #string
@@ -828,6 +844,7 @@ pub fn literal(i: Input) -> IResult<Literal> {
         | #interval : "`INTERVAL '...' (YEAR | MONTH | DAY | ...)`"
         | #current_timestamp
         | #null
+        | #placeholder
     )(i)
 }
 
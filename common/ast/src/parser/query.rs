@@ -26,16 +26,140 @@ use crate::rule;
 pub fn query(i: Input) -> IResult<Query> {
     map(
         consumed(rule! {
-            SELECT ~ DISTINCT? ~ #comma_separated_list1(select_target)
-            ~ ( FROM ~ ^#comma_separated_list1(table_reference) )?
-            ~ ( WHERE ~ ^#expr )?
-            ~ ( GROUP ~ ^BY ~ ^#comma_separated_list1(expr) )?
-            ~ ( HAVING ~ ^#expr )?
+            #with?
+            ~ #set_expr
             ~ ( ORDER ~ ^BY ~ ^#comma_separated_list1(order_by_expr) )?
             ~ ( LIMIT ~ ^#comma_separated_list1(expr) )?
             ~ ( OFFSET ~ ^#expr )?
             : "`SELECT ...`"
         }),
+        |(span, (with, body, opt_order_by_block, opt_limit_block, opt_offset_block))| {
+            let body = match body {
+                SetExpr::Select(mut select_stmt) => {
+                    // TODO(andylokandy): span should exclude order by
+                    select_stmt.span = span.0;
+                    SetExpr::Select(select_stmt)
+                }
+                body => body,
+            };
+            Query {
+                span: span.0,
+                with,
+                body,
+                order_by: opt_order_by_block
+                    .map(|(_, _, order_by)| order_by)
+                    .unwrap_or_default(),
+                limit: opt_limit_block.map(|(_, limit)| limit).unwrap_or_default(),
+                offset: opt_offset_block.map(|(_, offset)| offset),
+            }
+        },
+    )(i)
+}
+
+pub fn with(i: Input) -> IResult<With> {
+    let cte = map(
+        consumed(rule! {
+            #ident ~ ( "(" ~ ^#comma_separated_list1(ident) ~ ^")" )?
+            ~ AS ~ ^"(" ~ ^#query ~ ^")"
+        }),
+        |(span, (name, opt_columns, _, _, query, _))| Cte {
+            span: span.0,
+            alias: TableAlias {
+                name,
+                columns: opt_columns
+                    .map(|(_, columns, _)| columns)
+                    .unwrap_or_default(),
+            },
+            query,
+        },
+    );
+
+    map(
+        consumed(rule! {
+            WITH ~ RECURSIVE? ~ ^#comma_separated_list1(cte)
+        }),
+        |(span, (_, opt_recursive, ctes))| With {
+            span: span.0,
+            recursive: opt_recursive.is_some(),
+            ctes,
+        },
+    )(i)
+}
+
+/// Parses the `SELECT` statements combined with `UNION`, `EXCEPT` and `INTERSECT`.
+///
+/// `INTERSECT` binds tighter than `UNION` and `EXCEPT`, which are left associative.
+pub fn set_expr(i: Input) -> IResult<SetExpr> {
+    map(
+        rule! {
+            #set_operand ~ ( #set_operator ~ #set_operand )*
+        },
+        |(first, rest)| {
+            let combine = |left, (op, all), right| SetExpr::SetOperation {
+                op,
+                all,
+                left: Box::new(left),
+                right: Box::new(right),
+            };
+
+            // The left operand of the pending `UNION` or `EXCEPT`, and the operator.
+            let mut pending: Option<(SetExpr, (SetOperator, bool))> = None;
+            let mut term = first;
+            for ((op, all), operand) in rest {
+                if op == SetOperator::Intersect {
+                    term = combine(term, (op, all), operand);
+                } else {
+                    let left = match pending.take() {
+                        Some((left, pending_op)) => combine(left, pending_op, term),
+                        None => term,
+                    };
+                    pending = Some((left, (op, all)));
+                    term = operand;
+                }
+            }
+
+            match pending {
+                Some((left, pending_op)) => combine(left, pending_op, term),
+                None => term,
+            }
+        },
+    )(i)
+}
+
+pub fn set_operand(i: Input) -> IResult<SetExpr> {
+    alt((
+        map(select_stmt, |select_stmt| {
+            SetExpr::Select(Box::new(select_stmt))
+        }),
+        map(parenthesized_query, |query| SetExpr::Query(Box::new(query))),
+    ))(i)
+}
+
+pub fn set_operator(i: Input) -> IResult<(SetOperator, bool)> {
+    map(
+        rule! {
+            ( UNION | EXCEPT | INTERSECT ) ~ ( ALL | DISTINCT )?
+        },
+        |(op, opt_all)| {
+            let op = match op.kind {
+                UNION => SetOperator::Union,
+                EXCEPT => SetOperator::Except,
+                _ => SetOperator::Intersect,
+            };
+            (op, matches!(opt_all, Some(token) if token.kind == ALL))
+        },
+    )(i)
+}
+
+pub fn select_stmt(i: Input) -> IResult<SelectStmt> {
+    map(
+        consumed(rule! {
+            SELECT ~ DISTINCT? ~ #comma_separated_list1(select_target)
+            ~ ( FROM ~ ^#comma_separated_list1(table_reference) )?
+            ~ ( WHERE ~ ^#expr )?
+            ~ ( GROUP ~ ^BY ~ ^#group_by_items )?
+            ~ ( HAVING ~ ^#expr )?
+        }),
         |(
             span,
             (
@@ -46,47 +170,61 @@ pub fn query(i: Input) -> IResult<Query> {
                 opt_where_block,
                 opt_group_by_block,
                 opt_having_block,
-                opt_order_by_block,
-                opt_limit_block,
-                opt_offset_block,
             ),
-        )| {
-            Query {
-                span: span.0,
-                body: SetExpr::Select(Box::new(SelectStmt {
-                    // TODO(andylokandy): span should exclude order by
-                    span: span.0,
-                    distinct: opt_distinct.is_some(),
-                    select_list,
-                    from: opt_from_block.map(|(_, table_refs)| {
-                        table_refs
-                            .into_iter()
-                            .reduce(|left, right| {
-                                TableReference::Join(Join {
-                                    op: JoinOperator::CrossJoin,
-                                    condition: JoinCondition::None,
-                                    left: Box::new(left),
-                                    right: Box::new(right),
-                                })
-                            })
-                            .unwrap()
-                    }),
-                    selection: opt_where_block.map(|(_, selection)| selection),
-                    group_by: opt_group_by_block
-                        .map(|(_, _, group_by)| group_by)
-                        .unwrap_or_default(),
-                    having: opt_having_block.map(|(_, having)| having),
-                })),
-                order_by: opt_order_by_block
-                    .map(|(_, _, order_by)| order_by)
-                    .unwrap_or_default(),
-                limit: opt_limit_block.map(|(_, limit)| limit).unwrap_or_default(),
-                offset: opt_offset_block.map(|(_, offset)| offset),
-            }
+        )| SelectStmt {
+            span: span.0,
+            distinct: opt_distinct.is_some(),
+            select_list,
+            from: opt_from_block.map(|(_, table_refs)| {
+                table_refs
+                    .into_iter()
+                    .reduce(|left, right| {
+                        TableReference::Join(Join {
+                            op: JoinOperator::CrossJoin,
+                            condition: JoinCondition::None,
+                            left: Box::new(left),
+                            right: Box::new(right),
+                        })
+                    })
+                    .unwrap()
+            }),
+            selection: opt_where_block.map(|(_, selection)| selection),
+            group_by: opt_group_by_block.map(|(_, _, group_by)| group_by),
+            having: opt_having_block.map(|(_, having)| having),
         },
     )(i)
 }
 
+pub fn group_by_items(i: Input) -> IResult<GroupBy> {
+    let normal = map(rule! { #comma_separated_list1(expr) }, GroupBy::Normal);
+    let cube = map(
+        rule! { CUBE ~ "(" ~ ^#comma_separated_list1(expr) ~ ^")" },
+        |(_, _, exprs, _)| GroupBy::Cube(exprs),
+    );
+    let rollup = map(
+        rule! { ROLLUP ~ "(" ~ ^#comma_separated_list1(expr) ~ ^")" },
+        |(_, _, exprs, _)| GroupBy::Rollup(exprs),
+    );
+    let grouping_set = alt((
+        map(
+            rule! { "(" ~ #comma_separated_list0(expr) ~ ")" },
+            |(_, exprs, _)| exprs,
+        ),
+        map(expr, |expr| vec![expr]),
+    ));
+    let grouping_sets = map(
+        rule! { GROUPING ~ SETS ~ ^"(" ~ ^#comma_separated_list1(grouping_set) ~ ^")" },
+        |(_, _, _, sets, _)| GroupBy::GroupingSets(sets),
+    );
+
+    rule!(
+        #grouping_sets
+        | #cube
+        | #rollup
+        | #normal
+    )(i)
+}
+
 pub fn select_target(i: Input) -> IResult<SelectTarget> {
     let qualified_wildcard = map(
         rule! {
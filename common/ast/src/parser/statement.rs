@@ -351,57 +351,37 @@ pub fn statement(i: Input) -> IResult<Statement> {
     let create_udf = map(
         rule! {
             CREATE ~ FUNCTION ~ ( IF ~ NOT ~ EXISTS )?
-            ~ #ident
-            ~ AS ~ "(" ~ #comma_separated_list0(ident) ~ ")"
-            ~ "->" ~ #expr
+            ~ #ident ~ #udf_kind
             ~ ( DESC ~ ^"=" ~ ^#literal_string )?
         },
-        |(
-            _,
-            _,
-            opt_if_not_exists,
+        |(_, _, opt_if_not_exists, udf_name, kind, opt_description)| Statement::CreateUDF {
+            if_not_exists: opt_if_not_exists.is_some(),
             udf_name,
-            _,
-            _,
-            parameters,
-            _,
-            _,
-            definition,
-            opt_description,
-        )| {
-            Statement::CreateUDF {
-                if_not_exists: opt_if_not_exists.is_some(),
-                udf_name,
-                parameters,
-                definition: Box::new(definition),
-                description: opt_description.map(|(_, _, description)| description),
-            }
+            kind,
+            description: opt_description.map(|(_, _, description)| description),
         },
     );
     let drop_udf = map(
         rule! {
             DROP ~ FUNCTION ~ ( IF ~ EXISTS )? ~ #ident
+            ~ ( "(" ~ #comma_separated_list0(type_name) ~ ")" )?
         },
-        |(_, _, opt_if_exists, udf_name)| Statement::DropUDF {
+        |(_, _, opt_if_exists, udf_name, opt_parameter_types)| Statement::DropUDF {
             if_exists: opt_if_exists.is_some(),
             udf_name,
+            parameter_types: opt_parameter_types.map(|(_, parameter_types, _)| parameter_types),
         },
     );
     let alter_udf = map(
         rule! {
             ALTER ~ FUNCTION
-            ~ #ident
-            ~ AS ~ "(" ~ #comma_separated_list0(ident) ~ ")"
-            ~ "->" ~ #expr
+            ~ #ident ~ #udf_kind
             ~ ( DESC ~ ^"=" ~ ^#literal_string )?
         },
-        |(_, _, udf_name, _, _, parameters, _, _, definition, opt_description)| {
-            Statement::AlterUDF {
-                udf_name,
-                parameters,
-                definition: Box::new(definition),
-                description: opt_description.map(|(_, _, description)| description),
-            }
+        |(_, _, udf_name, kind, opt_description)| Statement::AlterUDF {
+            udf_name,
+            kind,
+            description: opt_description.map(|(_, _, description)| description),
         },
     );
 
@@ -440,13 +420,74 @@ pub fn statement(i: Input) -> IResult<Statement> {
             | #create_user : "`CREATE USER [IF NOT EXISTS] '<username>'@'hostname' IDENTIFIED [WITH <auth_type>] [BY <password>] [WITH <role_option> ...]`"
             | #alter_user : "`ALTER USER ('<username>'@'hostname' | USER()) [IDENTIFIED [WITH <auth_type>] [BY <password>]] [WITH <role_option> ...]`"
             | #drop_user : "`DROP USER [IF EXISTS] '<username>'@'hostname'`"
-            | #create_udf : "`CREATE FUNCTION [IF NOT EXISTS] <udf_name> (<parameter>, ...) -> <definition expr> [DESC = <description>]`"
-            | #drop_udf : "`DROP FUNCTION [IF EXISTS] <udf_name>`"
-            | #alter_udf : "`ALTER FUNCTION <udf_name> (<parameter>, ...) -> <definition_expr> [DESC = <description>]`"
+            | #create_udf : "`CREATE FUNCTION [IF NOT EXISTS] <udf_name> <udf_definition> [DESC = <description>]`"
+            | #drop_udf : "`DROP FUNCTION [IF EXISTS] <udf_name> [(<type>, ...)]`"
+            | #alter_udf : "`ALTER FUNCTION <udf_name> <udf_definition> [DESC = <description>]`"
         ),
     ))(i)
 }
 
+pub fn udf_kind(i: Input) -> IResult<UDFKind> {
+    let lambda = map(
+        rule! {
+            AS ~ "(" ~ #comma_separated_list0(ident) ~ ")"
+            ~ "->" ~ #expr
+        },
+        |(_, _, parameters, _, _, definition)| UDFKind::Lambda {
+            parameters,
+            definition: Box::new(definition),
+        },
+    );
+    let table = map(
+        rule! {
+            #typed_ident_list ~ RETURNS ~ TABLE ~ ^#typed_ident_list ~ ^AS ~ ^#query
+        },
+        |(parameters, _, _, return_columns, _, definition)| UDFKind::Table {
+            parameters,
+            return_columns,
+            definition: Box::new(definition),
+        },
+    );
+    let wasm = map(
+        rule! {
+            #typed_ident_list ~ RETURNS ~ #type_name ~ LANGUAGE ~ ^WASM ~ ^AS ~ ^#literal_string
+        },
+        |(parameters, _, return_type, _, _, _, module)| UDFKind::Wasm {
+            parameters,
+            return_type,
+            module,
+        },
+    );
+    let scalar = map(
+        rule! {
+            #typed_ident_list ~ ^RETURNS ~ ^#type_name ~ ^AS ~ ^#expr
+        },
+        |(parameters, _, return_type, _, definition)| UDFKind::Scalar {
+            parameters,
+            return_type,
+            definition: Box::new(definition),
+        },
+    );
+
+    rule!(
+        #lambda : "`AS (<parameter>, ...) -> <definition expr>`"
+        | #table : "`(<parameter> <type>, ...) RETURNS TABLE (<column> <type>, ...) AS <query>`"
+        | #wasm : "`(<parameter> <type>, ...) RETURNS <type> LANGUAGE WASM AS '<base64 module>'`"
+        | #scalar : "`(<parameter> <type>, ...) RETURNS <type> AS <definition expr>`"
+    )(i)
+}
+
+pub fn typed_ident_list(i: Input) -> IResult<Vec<(Identifier, TypeName)>> {
+    let typed_ident = rule! { #ident ~ #type_name };
+
+    map(
+        rule! {
+            "(" ~ #comma_separated_list0(typed_ident) ~ ^")"
+        },
+        |(_, list, _)| list,
+    )(i)
+}
+
 pub fn column_def(i: Input) -> IResult<ColumnDefinition> {
     #[derive(Clone)]
     enum ColumnConstraint<'a> {
@@ -220,6 +220,13 @@ pub enum TokenKind {
     /// A cube root math operator in PostgreSQL
     #[token("||/")]
     PGCubeRoot,
+    /// Question Mark `?` used as the parameter placeholder of prepared statements
+    #[token("?")]
+    Placeholder,
+    /// Numbered placeholder `$1`, `$2`, ... used as the parameter placeholder of
+    /// PostgreSQL prepared statements
+    #[regex(r"\$[0-9]+")]
+    NumberedPlaceholder,
 
     // Keywords
     //
@@ -242,6 +249,8 @@ pub enum TokenKind {
     AS,
     #[token("ASC", ignore(ascii_case))]
     ASC,
+    #[token("AT", ignore(ascii_case))]
+    AT,
     #[token("AWS_KEY_ID", ignore(ascii_case))]
     AWS_KEY_ID,
     #[token("AWS_SECRET_KEY", ignore(ascii_case))]
@@ -286,6 +295,8 @@ pub enum TokenKind {
     CREDENTIALS,
     #[token("CSV", ignore(ascii_case))]
     CSV,
+    #[token("CUBE", ignore(ascii_case))]
+    CUBE,
     #[token("CURRENT_TIMESTAMP", ignore(ascii_case))]
     CURRENT_TIMESTAMP,
     #[token("DATABASE", ignore(ascii_case))]
@@ -330,6 +341,8 @@ pub enum TokenKind {
     ENGINE,
     #[token("EPOCH", ignore(ascii_case))]
     EPOCH,
+    #[token("EXCEPT", ignore(ascii_case))]
+    EXCEPT,
     #[token("EXISTS", ignore(ascii_case))]
     EXISTS,
     #[token("EXPLAIN", ignore(ascii_case))]
@@ -370,6 +383,8 @@ pub enum TokenKind {
     GRAPH,
     #[token("GROUP", ignore(ascii_case))]
     GROUP,
+    #[token("GROUPING", ignore(ascii_case))]
+    GROUPING,
     #[token("HAVING", ignore(ascii_case))]
     HAVING,
     #[token("HOUR", ignore(ascii_case))]
@@ -396,6 +411,8 @@ pub enum TokenKind {
     INT8,
     #[token("INTEGER", ignore(ascii_case))]
     INTEGER,
+    #[token("INTERSECT", ignore(ascii_case))]
+    INTERSECT,
     #[token("INTERVAL", ignore(ascii_case))]
     INTERVAL,
     #[token("INTO", ignore(ascii_case))]
@@ -416,6 +433,8 @@ pub enum TokenKind {
     JWT,
     #[token("KILL", ignore(ascii_case))]
     KILL,
+    #[token("LANGUAGE", ignore(ascii_case))]
+    LANGUAGE,
     #[token("LEADING", ignore(ascii_case))]
     LEADING,
     #[token("LEFT", ignore(ascii_case))]
@@ -490,14 +509,20 @@ pub enum TokenKind {
     QUERY,
     #[token("RECORD_DELIMITER", ignore(ascii_case))]
     RECORD_DELIMITER,
+    #[token("RECURSIVE", ignore(ascii_case))]
+    RECURSIVE,
     #[token("REGEXP", ignore(ascii_case))]
     REGEXP,
     #[token("RENAME", ignore(ascii_case))]
     RENAME,
+    #[token("RETURNS", ignore(ascii_case))]
+    RETURNS,
     #[token("RIGHT", ignore(ascii_case))]
     RIGHT,
     #[token("RLIKE", ignore(ascii_case))]
     RLIKE,
+    #[token("ROLLUP", ignore(ascii_case))]
+    ROLLUP,
     #[token("SCHEMA", ignore(ascii_case))]
     SCHEMA,
     #[token("SCHEMAS", ignore(ascii_case))]
@@ -508,6 +533,8 @@ pub enum TokenKind {
     SELECT,
     #[token("SET", ignore(ascii_case))]
     SET,
+    #[token("SETS", ignore(ascii_case))]
+    SETS,
     #[token("SETTINGS", ignore(ascii_case))]
     SETTINGS,
     #[token("SHA256_PASSWORD", ignore(ascii_case))]
@@ -538,6 +565,8 @@ pub enum TokenKind {
     TENANTSETTING,
     #[token("THEN", ignore(ascii_case))]
     THEN,
+    #[token("TIME", ignore(ascii_case))]
+    TIME,
     #[token("TIMESTAMP", ignore(ascii_case))]
     TIMESTAMP,
     #[token("TIMEZONE_HOUR", ignore(ascii_case))]
@@ -570,6 +599,8 @@ pub enum TokenKind {
     UINT64,
     #[token("UINT8", ignore(ascii_case))]
     UINT8,
+    #[token("UNION", ignore(ascii_case))]
+    UNION,
     #[token("UNSIGNED", ignore(ascii_case))]
     UNSIGNED,
     #[token("URL", ignore(ascii_case))]
@@ -588,6 +619,8 @@ pub enum TokenKind {
     VARIANT,
     #[token("VIEW", ignore(ascii_case))]
     VIEW,
+    #[token("WASM", ignore(ascii_case))]
+    WASM,
     #[token("WEEK", ignore(ascii_case))]
     WEEK,
     #[token("WHEN", ignore(ascii_case))]
@@ -600,6 +633,8 @@ pub enum TokenKind {
     XOR,
     #[token("YEAR", ignore(ascii_case))]
     YEAR,
+    #[token("ZONE", ignore(ascii_case))]
+    ZONE,
 }
 
 // Reference: https://www.postgresql.org/docs/current/sql-keywords-appendix.html
@@ -654,6 +689,8 @@ impl TokenKind {
                 | AtSign
                 | PGSquareRoot
                 | PGCubeRoot
+                | Placeholder
+                | NumberedPlaceholder
                 | EOI
         )
     }
@@ -769,14 +806,14 @@ impl TokenKind {
             // | TokenKind::CHAR
             // | TokenKind::CHARACTER
             | TokenKind::CREATE
-            // | TokenKind::EXCEPT
+            | TokenKind::EXCEPT
             // | TokenKind::FETCH
             | TokenKind::FOR
             | TokenKind::FROM
             // | TokenKind::GRANT
             | TokenKind::GROUP
             | TokenKind::HAVING
-            // | TokenKind::INTERSECT
+            | TokenKind::INTERSECT
             | TokenKind::INTO
             | TokenKind::LIMIT
             | TokenKind::OFFSET
@@ -785,7 +822,7 @@ impl TokenKind {
             // | TokenKind::PRECISION
             // | TokenKind::RETURNING
             | TokenKind::TO
-            // | TokenKind::UNION
+            | TokenKind::UNION
             | TokenKind::WHERE
             // | TokenKind::WINDOW
             | TokenKind::WITH if !after_as => true,
@@ -872,14 +909,14 @@ impl TokenKind {
             | TokenKind::ARRAY
             | TokenKind::AS
             | TokenKind::CREATE
-            // | TokenKind::EXCEPT
+            | TokenKind::EXCEPT
             // | TokenKind::FETCH
             | TokenKind::FOR
             | TokenKind::FROM
             // | TokenKind::GRANT
             | TokenKind::GROUP
             | TokenKind::HAVING
-            // | TokenKind::INTERSECT
+            | TokenKind::INTERSECT
             | TokenKind::INTO
             // | TokenKind::ISNULL
             | TokenKind::LIMIT
@@ -890,7 +927,7 @@ impl TokenKind {
             // | TokenKind::OVERLAPS 
             // | TokenKind::RETURNING
             | TokenKind::TO
-            // | TokenKind::UNION
+            | TokenKind::UNION
             | TokenKind::WHERE
             // | TokenKind::WINDOW
             | TokenKind::WITH
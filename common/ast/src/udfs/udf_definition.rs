@@ -12,16 +12,40 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use sqlparser::ast::DataType;
 use sqlparser::ast::Expr;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct UDFDefinition {
     pub parameters: Vec<String>,
     pub expr: Expr,
+    /// Arguments are cast to the parameter types of typed UDFs.
+    pub parameter_types: Vec<DataType>,
+    /// The result is cast to the return type of typed UDFs.
+    pub return_type: Option<DataType>,
 }
 
 impl UDFDefinition {
     pub fn new(parameters: Vec<String>, expr: Expr) -> Self {
-        Self { parameters, expr }
+        Self {
+            parameters,
+            expr,
+            parameter_types: vec![],
+            return_type: None,
+        }
+    }
+
+    pub fn new_typed(
+        parameters: Vec<String>,
+        parameter_types: Vec<DataType>,
+        return_type: DataType,
+        expr: Expr,
+    ) -> Self {
+        Self {
+            parameters,
+            expr,
+            parameter_types,
+            return_type: Some(return_type),
+        }
     }
 }
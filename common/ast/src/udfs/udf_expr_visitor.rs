@@ -23,6 +23,7 @@ use sqlparser::ast::Function;
 use sqlparser::ast::FunctionArg;
 use sqlparser::ast::FunctionArgExpr;
 use sqlparser::ast::Ident;
+use sqlparser::ast::ObjectName;
 use sqlparser::ast::Query;
 use sqlparser::ast::TrimWhereField;
 use sqlparser::ast::UnaryOperator;
@@ -85,6 +86,7 @@ pub trait UDFExprVisitor: Sized + Send {
             Expr::MapAccess { column, keys } => self.visit_map_access(column, keys),
             Expr::Trim { expr, trim_where } => self.visit_trim(expr, trim_where),
             Expr::Array(exprs) => self.visit_array(exprs),
+            Expr::Collate { expr, collation } => self.visit_collate(expr, collation),
             other => Result::Err(ErrorCode::SyntaxException(format!(
                 "Unsupported expression: {}, type: {:?}",
                 expr, other
@@ -261,4 +263,8 @@ pub trait UDFExprVisitor: Sized + Send {
             }
         }
     }
+
+    fn visit_collate(&mut self, expr: &Expr, _collation: &ObjectName) -> Result<()> {
+        UDFExprTraverser::accept(expr, self)
+    }
 }
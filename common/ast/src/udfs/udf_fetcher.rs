@@ -14,10 +14,12 @@
 
 use async_trait::async_trait;
 use common_exception::Result;
+use sqlparser::ast::FunctionArg;
 
 use super::UDFDefinition;
 
 #[async_trait]
 pub trait UDFFetcher: Sized + Send {
-    fn get_udf_definition(&self, name: &str) -> Result<UDFDefinition>;
+    /// Get the definition of the UDF `name`, the arguments are used to choose among overloads.
+    fn get_udf_definition(&self, name: &str, args: &[FunctionArg]) -> Result<UDFDefinition>;
 }
@@ -28,7 +28,7 @@ pub struct UDFTransformer;
 
 impl UDFTransformer {
     pub fn transform_function<F: UDFFetcher>(function: &Function, fetcher: &F) -> Result<Expr> {
-        let definition = fetcher.get_udf_definition(&function.name.to_string())?;
+        let definition = fetcher.get_udf_definition(&function.name.to_string(), &function.args)?;
         let parameters = definition.parameters;
         let expr = definition.expr;
 
@@ -41,21 +41,39 @@ impl UDFTransformer {
         }
 
         let mut args_map = HashMap::new();
-        function.args.iter().enumerate().for_each(|(index, f_arg)| {
-            if let Some(param) = parameters.get(index) {
-                args_map.insert(param, match f_arg {
-                    FunctionArg::Named { arg, .. } => arg.clone(),
-                    FunctionArg::Unnamed(unnamed_arg) => unnamed_arg.clone(),
-                });
-            }
-        });
+        for (index, f_arg) in function.args.iter().enumerate() {
+            let arg = match f_arg {
+                FunctionArg::Named { arg, .. } => function_arg_as_expr(arg)?,
+                FunctionArg::Unnamed(unnamed_arg) => function_arg_as_expr(unnamed_arg)?,
+            };
+            let arg = match definition.parameter_types.get(index) {
+                Some(data_type) => Expr::Cast {
+                    expr: Box::new(arg),
+                    data_type: data_type.clone(),
+                    pg_style: false,
+                },
+                None => arg,
+            };
+            args_map.insert(parameters[index].clone(), arg);
+        }
+
+        let expr = Self::replace_parameters(&expr, &args_map)?;
+        match definition.return_type {
+            Some(data_type) => Ok(Expr::Cast {
+                expr: Box::new(expr),
+                data_type,
+                pg_style: false,
+            }),
+            None => Ok(expr),
+        }
+    }
 
-        Self::clone_expr_with_replacement(&expr, &|nest_expr| {
+    /// Replaces the identifiers which are parameters with the arguments.
+    pub fn replace_parameters(expr: &Expr, args_map: &HashMap<String, Expr>) -> Result<Expr> {
+        Self::clone_expr_with_replacement(expr, &|nest_expr| {
             if let Expr::Identifier(Ident { value, .. }) = nest_expr {
                 if let Some(arg) = args_map.get(value) {
-                    if let Ok(expr) = function_arg_as_expr(arg) {
-                        return Ok(Some(expr));
-                    }
+                    return Ok(Some(arg.clone()));
                 }
             }
 
@@ -261,9 +261,11 @@ fn test_expr() {
         r#"substring(a from b for c)"#,
         r#"substring(a, b, c)"#,
         r#"col1::UInt8"#,
+        r#"ts AT TIME ZONE 'Asia/Shanghai'"#,
         r#"(arr[0]:a).b"#,
         r#"arr[4]["k"]"#,
         r#"a rlike '^11'"#,
+        r#"a = ?"#,
         r#"G.E.B IS NOT NULL AND col1 not between col2 and (1 + col3) DIV sum(col4)"#,
         r#"sum(CASE WHEN n2.n_name = 'GERMANY' THEN ol_amount ELSE 0 END) / CASE WHEN sum(ol_amount) = 0 THEN 1 ELSE sum(ol_amount) END"#,
         r#"p_partkey = l_partkey
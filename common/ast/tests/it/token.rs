@@ -64,7 +64,22 @@ fn test_lexer() {
             (SemiColon, ";", 62..63),
             (EOI, "", 63..63),
         ],
-    )
+    );
+    assert_lex("select a$1 from t where b = $1 and c = ?", &[
+        (SELECT, "select", 0..6),
+        (Ident, "a$1", 7..10),
+        (FROM, "from", 11..15),
+        (Ident, "t", 16..17),
+        (WHERE, "where", 18..23),
+        (Ident, "b", 24..25),
+        (Eq, "=", 26..27),
+        (NumberedPlaceholder, "$1", 28..30),
+        (AND, "and", 31..34),
+        (Ident, "c", 35..36),
+        (Eq, "=", 37..38),
+        (Placeholder, "?", 39..40),
+        (EOI, "", 40..40),
+    ]);
 }
 
 #[test]
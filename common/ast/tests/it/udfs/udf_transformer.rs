@@ -17,6 +17,8 @@ use common_ast::udfs::*;
 use common_exception::ErrorCode;
 use common_exception::Result;
 use pretty_assertions::assert_eq;
+use sqlparser::ast::BinaryOperator;
+use sqlparser::ast::DataType;
 use sqlparser::ast::Expr;
 use sqlparser::ast::Function;
 use sqlparser::ast::FunctionArg;
@@ -24,17 +26,28 @@ use sqlparser::ast::FunctionArgExpr;
 use sqlparser::ast::Ident;
 use sqlparser::ast::ObjectName;
 use sqlparser::ast::UnaryOperator;
+use sqlparser::ast::Value;
 
 struct TestFetcher;
 
 #[async_trait]
 impl UDFFetcher for TestFetcher {
-    fn get_udf_definition(&self, name: &str) -> Result<UDFDefinition> {
+    fn get_udf_definition(&self, name: &str, _args: &[FunctionArg]) -> Result<UDFDefinition> {
         if name == "test_transformer" {
             let mut parser = UDFParser::default();
             let expr = parser.parse(name, &["p".to_string()], "not(is_null(p))")?;
 
             Ok(UDFDefinition::new(vec!["p".to_string()], expr))
+        } else if name == "test_typed_transformer" {
+            let mut parser = UDFParser::default();
+            let expr = parser.parse(name, &["p".to_string()], "p + 1")?;
+
+            Ok(UDFDefinition::new_typed(
+                vec!["p".to_string()],
+                vec![DataType::Int(None)],
+                DataType::BigInt(None),
+                expr,
+            ))
         } else {
             Err(ErrorCode::UnImplement("Unimplement error"))
         }
@@ -104,3 +117,45 @@ fn test_udf_transformer() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_typed_udf_transformer() -> Result<()> {
+    let fetcher = &TestFetcher {};
+    let result = UDFTransformer::transform_function(
+        &Function {
+            name: ObjectName(vec![Ident {
+                value: "test_typed_transformer".to_string(),
+                quote_style: None,
+            }]),
+            params: vec![],
+            args: vec![FunctionArg::Unnamed(FunctionArgExpr::Expr(
+                Expr::Identifier(Ident {
+                    value: "test".to_string(),
+                    quote_style: None,
+                }),
+            ))],
+            over: None,
+            distinct: false,
+        },
+        fetcher,
+    )?;
+
+    assert_eq!(result, Expr::Cast {
+        expr: Box::new(Expr::BinaryOp {
+            left: Box::new(Expr::Cast {
+                expr: Box::new(Expr::Identifier(Ident {
+                    value: "test".to_string(),
+                    quote_style: None,
+                })),
+                data_type: DataType::Int(None),
+                pg_style: false,
+            }),
+            op: BinaryOperator::Plus,
+            right: Box::new(Expr::Value(Value::Number("1".to_string(), false))),
+        }),
+        data_type: DataType::BigInt(None),
+        pg_style: false,
+    });
+
+    Ok(())
+}
@@ -22,6 +22,7 @@ use tokio::runtime::Handle;
 use tokio::sync::oneshot;
 use tokio::task::JoinHandle;
 
+use super::runtime_tracker::MemoryTracker;
 use super::runtime_tracker::RuntimeTracker;
 
 /// Methods to spawn tasks.
@@ -129,6 +130,20 @@ impl Runtime {
         Self::create(tracker, runtime_builder.worker_threads(workers))
     }
 
+    /// Spawns a new tokio runtime whose allocations are tracked by the memory tracker.
+    pub fn with_memory_tracker(
+        workers: usize,
+        thread_name: Option<String>,
+        memory_tracker: Arc<MemoryTracker>,
+    ) -> Result<Self> {
+        let tracker = RuntimeTracker::create_with_memory_tracker(memory_tracker);
+        let mut runtime_builder = Self::tracker_builder(tracker.clone());
+        if let Some(v) = thread_name {
+            runtime_builder.thread_name(v);
+        }
+        Self::create(tracker, runtime_builder.worker_threads(workers))
+    }
+
     pub fn inner(&self) -> tokio::runtime::Handle {
         self.handle.clone()
     }
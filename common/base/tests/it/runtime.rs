@@ -59,3 +59,32 @@ async fn test_runtime() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_memory_tracker_limit() -> Result<()> {
+    let parent = MemoryTracker::create(None);
+    let tracker = MemoryTracker::create(Some(parent.clone()));
+
+    tracker.alloc_memory(1024);
+    assert_eq!(parent.get_memory_usage(), 1024);
+    assert!(tracker.check_memory_limit().is_ok());
+
+    // Exceed the limit of the parent.
+    parent.set_memory_limit(512);
+    let cause = tracker.check_memory_limit().unwrap_err();
+    assert_eq!(cause.code(), 1104);
+    assert_eq!(
+        cause.message(),
+        "Memory limit exceeded: would use 1024 bytes, maximum: 512 bytes"
+    );
+
+    tracker.dealloc_memory(1024);
+    assert!(tracker.check_memory_limit().is_ok());
+
+    // Exceed the limit of the tracker.
+    tracker.set_memory_limit(256);
+    tracker.alloc_memory(512);
+    assert_eq!(tracker.check_memory_limit().unwrap_err().code(), 1104);
+
+    Ok(())
+}
@@ -46,6 +46,18 @@ pub struct QueryConfig {
     #[clap(long, default_value = "9000")]
     pub clickhouse_handler_port: u16,
 
+    #[clap(long, default_value = "127.0.0.1")]
+    pub postgres_handler_host: String,
+
+    #[clap(long, default_value = "5433")]
+    pub postgres_handler_port: u16,
+
+    #[clap(long, default_value = "127.0.0.1")]
+    pub flight_sql_handler_host: String,
+
+    #[clap(long, default_value = "8900")]
+    pub flight_sql_handler_port: u16,
+
     #[clap(long, default_value = "127.0.0.1")]
     pub http_handler_host: String,
 
@@ -55,6 +67,10 @@ pub struct QueryConfig {
     #[clap(long, default_value = "10000")]
     pub http_handler_result_timeout_millis: u64,
 
+    /// The seconds to keep the results of the asynchronous queries after they are finished.
+    #[clap(long, default_value = "86400")]
+    pub http_handler_async_result_retention_secs: u64,
+
     #[clap(long, default_value = "127.0.0.1:9090")]
     pub flight_api_address: String,
 
@@ -73,6 +89,24 @@ pub struct QueryConfig {
     #[clap(long, default_value_t)]
     pub http_handler_tls_server_root_ca_cert: String,
 
+    #[clap(long, default_value_t)]
+    pub mysql_handler_tls_server_cert: String,
+
+    #[clap(long, default_value_t)]
+    pub mysql_handler_tls_server_key: String,
+
+    #[clap(long, default_value_t)]
+    pub mysql_handler_tls_server_root_ca_cert: String,
+
+    #[clap(long, default_value_t)]
+    pub clickhouse_handler_tls_server_cert: String,
+
+    #[clap(long, default_value_t)]
+    pub clickhouse_handler_tls_server_key: String,
+
+    #[clap(long, default_value_t)]
+    pub clickhouse_handler_tls_server_root_ca_cert: String,
+
     #[clap(long, default_value_t)]
     pub api_tls_server_cert: String,
 
@@ -127,7 +161,7 @@ pub struct QueryConfig {
     #[clap(long, default_value = "102400")]
     pub table_cache_block_meta_count: u64,
 
-    /// Table memory cache size (mb)
+    /// Table memory cache size (mb) of the column chunks, 0 to disable
     #[clap(long, default_value = "256")]
     pub table_memory_cache_mb_size: u64,
 
@@ -135,16 +169,24 @@ pub struct QueryConfig {
     #[clap(long, default_value = "_cache")]
     pub table_disk_cache_root: String,
 
-    /// Table disk cache size (mb)
+    /// Table disk cache size (mb) of the column chunks, 0 to disable
     #[clap(long, default_value = "1024")]
     pub table_disk_cache_mb_size: u64,
 
+    /// Memory size (mb) of the query result cache, 0 to disable
+    #[clap(long, default_value = "64")]
+    pub query_result_cache_mb_size: u64,
+
     /// If in management mode, only can do some meta level operations(database/table/user/stage etc.) with metasrv.
     #[clap(long)]
     pub management_mode: bool,
 
     #[clap(long, default_value_t)]
     pub jwt_key_file: String,
+
+    /// Local folder to spill the intermediate data of the queries, the storage is used if empty
+    #[clap(long, default_value_t)]
+    pub spill_local_path: String,
 }
 
 impl Default for QueryConfig {
@@ -158,9 +200,14 @@ impl Default for QueryConfig {
             max_active_sessions: 256,
             clickhouse_handler_host: "127.0.0.1".to_string(),
             clickhouse_handler_port: 9000,
+            postgres_handler_host: "127.0.0.1".to_string(),
+            postgres_handler_port: 5433,
+            flight_sql_handler_host: "127.0.0.1".to_string(),
+            flight_sql_handler_port: 8900,
             http_handler_host: "127.0.0.1".to_string(),
             http_handler_port: 8000,
             http_handler_result_timeout_millis: 10000,
+            http_handler_async_result_retention_secs: 86400,
             flight_api_address: "127.0.0.1:9090".to_string(),
             admin_api_address: "127.0.0.1:8080".to_string(),
             metric_api_address: "127.0.0.1:7070".to_string(),
@@ -170,6 +217,12 @@ impl Default for QueryConfig {
             http_handler_tls_server_cert: "".to_string(),
             http_handler_tls_server_key: "".to_string(),
             http_handler_tls_server_root_ca_cert: "".to_string(),
+            mysql_handler_tls_server_cert: "".to_string(),
+            mysql_handler_tls_server_key: "".to_string(),
+            mysql_handler_tls_server_root_ca_cert: "".to_string(),
+            clickhouse_handler_tls_server_cert: "".to_string(),
+            clickhouse_handler_tls_server_key: "".to_string(),
+            clickhouse_handler_tls_server_root_ca_cert: "".to_string(),
             rpc_tls_server_cert: "".to_string(),
             rpc_tls_server_key: "".to_string(),
             rpc_tls_query_server_root_ca_cert: "".to_string(),
@@ -185,8 +238,10 @@ impl Default for QueryConfig {
             table_memory_cache_mb_size: 256,
             table_disk_cache_root: "_cache".to_string(),
             table_disk_cache_mb_size: 1024,
+            query_result_cache_mb_size: 64,
             management_mode: false,
             jwt_key_file: "".to_string(),
+            spill_local_path: "".to_string(),
         }
     }
 }
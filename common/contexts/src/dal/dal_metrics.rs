@@ -81,6 +81,12 @@ impl DalMetrics {
         }
     }
 
+    pub fn dec_partitions_scanned(&self, v: u64) {
+        if v > 0 {
+            self.partitions_scanned.fetch_sub(v, Ordering::Relaxed);
+        }
+    }
+
     pub fn get_partitions_scanned(&self) -> u64 {
         self.partitions_scanned.load(Ordering::Relaxed)
     }
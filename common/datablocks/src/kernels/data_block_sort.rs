@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::cmp::Ordering;
 use std::iter::once;
 use std::sync::Arc;
 
@@ -202,4 +203,110 @@ impl DataBlock {
             }
         }
     }
+
+    /// Merges the heads of several sorted streams, each head is the current block of a stream.
+    ///
+    /// Only the rows not greater than the smallest last row of the heads are merged, so the
+    /// merged block can be emitted before the streams are fully read. The remaining rows are put
+    /// back into the heads, and the fully consumed heads are set to `None`, the caller must refill
+    /// them with the next block of their streams (if any) before merging again.
+    pub fn merge_sort_heads(
+        heads: &mut [Option<DataBlock>],
+        sort_columns_descriptions: &[SortColumnDescription],
+    ) -> Result<Option<DataBlock>> {
+        let indices = heads
+            .iter()
+            .enumerate()
+            .filter(|(_, head)| matches!(head, Some(block) if block.num_rows() != 0))
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+
+        if indices.is_empty() {
+            return Ok(None);
+        }
+
+        let blocks = indices
+            .iter()
+            .map(|index| heads[*index].take().unwrap())
+            .collect::<Vec<_>>();
+
+        let sort_arrays = sort_columns_descriptions
+            .iter()
+            .map(|f| {
+                blocks
+                    .iter()
+                    .map(|block| Ok(block.try_column_by_name(&f.column_name)?.as_arrow_array()))
+                    .collect::<Result<Vec<_>>>()
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let sort_dyn_arrays = sort_arrays
+            .iter()
+            .map(|arrays| {
+                arrays
+                    .iter()
+                    .map(|array| array.as_ref())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let sort_options = sort_columns_descriptions
+            .iter()
+            .map(|f| arrow_sort::SortOptions {
+                descending: !f.asc,
+                nulls_first: f.nulls_first,
+            })
+            .collect::<Vec<_>>();
+
+        let sort_options_with_array = sort_dyn_arrays
+            .iter()
+            .zip(sort_options.iter())
+            .map(|(s, opt)| {
+                let paris: (&[&dyn Array], &SortOptions) = (s, opt);
+                paris
+            })
+            .collect::<Vec<_>>();
+
+        let comparator = build_comparator(&sort_options_with_array)?;
+
+        // The head with the smallest last row bounds the rows which can be merged.
+        let mut bound = 0;
+        for index in 1..blocks.len() {
+            let last = blocks[index].num_rows() - 1;
+            let bound_last = blocks[bound].num_rows() - 1;
+            if comparator(index, last, bound, bound_last) == Ordering::Less {
+                bound = index;
+            }
+        }
+
+        let bound_last = blocks[bound].num_rows() - 1;
+        let mut prefixes = Vec::with_capacity(blocks.len());
+        for (index, block) in blocks.iter().enumerate() {
+            // The rows of the block are sorted, find the first row greater than the bound row.
+            let (mut low, mut high) = (0, block.num_rows());
+            if index == bound {
+                low = high;
+            }
+
+            while low < high {
+                let middle = low + (high - low) / 2;
+                match comparator(index, middle, bound, bound_last) {
+                    Ordering::Greater => high = middle,
+                    _ => low = middle + 1,
+                }
+            }
+
+            if low != 0 {
+                prefixes.push(DataBlock::slice_block(block, 0, low));
+            }
+
+            if low != block.num_rows() {
+                let remain = DataBlock::slice_block(block, low, block.num_rows() - low);
+                heads[indices[index]] = Some(remain);
+            }
+        }
+
+        let block = DataBlock::merge_sort_blocks(&prefixes, sort_columns_descriptions, None)?;
+        Ok(Some(block))
+    }
 }
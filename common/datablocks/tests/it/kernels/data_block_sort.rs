@@ -116,3 +116,63 @@ fn test_data_block_merge_sort() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_data_block_merge_sort_heads() -> Result<()> {
+    let schema = DataSchemaRefExt::create(vec![
+        DataField::new("a", i64::to_data_type()),
+        DataField::new("b", Vu8::to_data_type()),
+    ]);
+
+    let raw1 = DataBlock::create(schema.clone(), vec![
+        Series::from_data(vec![3, 5, 7]),
+        Series::from_data(vec!["b1", "b2", "b3"]),
+    ]);
+
+    let raw2 = DataBlock::create(schema, vec![
+        Series::from_data(vec![2, 4, 6]),
+        Series::from_data(vec!["b4", "b5", "b6"]),
+    ]);
+
+    let options = vec![SortColumnDescription {
+        column_name: "a".to_owned(),
+        asc: true,
+        nulls_first: false,
+    }];
+
+    let mut heads = vec![Some(raw1), None, Some(raw2)];
+    let results = DataBlock::merge_sort_heads(&mut heads, &options)?;
+
+    let expected = vec![
+        "+---+----+",
+        "| a | b  |",
+        "+---+----+",
+        "| 2 | b4 |",
+        "| 3 | b1 |",
+        "| 4 | b5 |",
+        "| 5 | b2 |",
+        "| 6 | b6 |",
+        "+---+----+",
+    ];
+    common_datablocks::assert_blocks_eq(expected, &[results.unwrap()]);
+
+    // The rows greater than the smallest last row are kept in the heads.
+    assert!(heads[1].is_none());
+    assert!(heads[2].is_none());
+
+    let expected = vec![
+        "+---+----+",
+        "| a | b  |",
+        "+---+----+",
+        "| 7 | b3 |",
+        "+---+----+",
+    ];
+    common_datablocks::assert_blocks_eq(expected, &[heads[0].clone().unwrap()]);
+
+    let results = DataBlock::merge_sort_heads(&mut heads, &options)?;
+    common_datablocks::assert_blocks_eq(expected, &[results.unwrap()]);
+    assert!(heads.iter().all(|head| head.is_none()));
+    assert!(DataBlock::merge_sort_heads(&mut heads, &options)?.is_none());
+
+    Ok(())
+}
@@ -147,6 +147,12 @@ build_exceptions! {
 
     // Layout error code.
     LayoutError(1103),
+
+    // Memory error codes.
+    MemoryLimitExceeded(1104),
+
+    // Recursive CTE error codes.
+    RecursiveCteDepthExceeded(1105),
 }
 
 // Metasvr errors [2001, 3000].
@@ -186,6 +192,7 @@ build_exceptions! {
     IllegalUDFFormat(2601),
     UnknownUDF(2602),
     UdfAlreadyExists(2603),
+    UDFRuntimeError(2604),
 
     // Database error codes.
     UnknownDatabaseEngine(2701),
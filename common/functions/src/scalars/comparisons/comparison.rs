@@ -31,6 +31,8 @@ use crate::scalars::assert_string;
 use crate::scalars::cast_column_field;
 use crate::scalars::primitive_simd_op_boolean;
 use crate::scalars::scalar_binary_op;
+use crate::scalars::CollateFunction;
+use crate::scalars::CollationKeyFunction;
 use crate::scalars::ComparisonEqFunction;
 use crate::scalars::ComparisonGtEqFunction;
 use crate::scalars::ComparisonGtFunction;
@@ -69,6 +71,8 @@ impl ComparisonFunction {
         factory.register("not regexp", ComparisonNotRegexpFunction::desc("regexp"));
         factory.register("rlike", ComparisonRegexpFunction::desc("not regexp"));
         factory.register("not rlike", ComparisonNotRegexpFunction::desc("regexp"));
+        factory.register("collate", CollateFunction::desc());
+        factory.register("collation_key", CollationKeyFunction::desc());
     }
 
     pub fn try_create_func(
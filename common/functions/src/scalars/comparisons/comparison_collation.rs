@@ -0,0 +1,231 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use common_datavalues::prelude::*;
+use common_exception::ErrorCode;
+use common_exception::Result;
+
+use crate::scalars::assert_string;
+use crate::scalars::scalar_binary_op;
+use crate::scalars::EvalContext;
+use crate::scalars::Function;
+use crate::scalars::FunctionContext;
+use crate::scalars::FunctionDescription;
+use crate::scalars::FunctionFeatures;
+
+/// The rules to compare strings by, strings are compared by their collation keys.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Collation {
+    /// Compares the bytes, the default.
+    Binary,
+    /// Compares case-insensitively, e.g. `utf8mb4_0900_as_ci`.
+    CaseInsensitive,
+    /// Compares case- and accent-insensitively, e.g. `utf8_general_ci`.
+    CaseAccentInsensitive,
+}
+
+impl Collation {
+    pub fn try_from_name(name: &str) -> Result<Collation> {
+        match name.to_lowercase().as_str() {
+            "binary" | "utf8_bin" | "utf8mb4_bin" => Ok(Collation::Binary),
+            "utf8mb4_0900_as_ci" => Ok(Collation::CaseInsensitive),
+            "utf8_general_ci" | "utf8mb4_general_ci" | "utf8_unicode_ci" | "utf8mb4_unicode_ci"
+            | "utf8mb4_0900_ai_ci" => Ok(Collation::CaseAccentInsensitive),
+            _ => Err(ErrorCode::BadArguments(format!(
+                "Unknown collation: '{}'",
+                name
+            ))),
+        }
+    }
+
+    /// Writes the key of the value to `key`, two values are equal under the collation
+    /// if their keys are equal, and are ordered as their keys.
+    pub fn write_key(&self, value: &[u8], key: &mut Vec<u8>) {
+        if *self == Collation::Binary {
+            key.extend_from_slice(value);
+            return;
+        }
+
+        let value = String::from_utf8_lossy(value);
+        let mut buffer = [0; 4];
+        for c in value.chars() {
+            let folded = match self {
+                Collation::CaseAccentInsensitive => fold_accent(c),
+                _ => None,
+            };
+
+            match folded {
+                Some(folded) => key.extend_from_slice(folded.as_bytes()),
+                None => {
+                    for lower in c.to_lowercase() {
+                        key.extend_from_slice(lower.encode_utf8(&mut buffer).as_bytes());
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn key(&self, value: &[u8]) -> Vec<u8> {
+        let mut key = Vec::with_capacity(value.len());
+        self.write_key(value, &mut key);
+        key
+    }
+}
+
+// Maps the Latin letters with diacritics to their lowercase base letters.
+fn fold_accent(c: char) -> Option<&'static str> {
+    let folded = match c {
+        'À'..='Å' | 'à'..='å' | 'Ā'..='ą' => "a",
+        'Æ' | 'æ' => "ae",
+        'Ç' | 'ç' | 'Ć'..='č' => "c",
+        'Ð' | 'ð' | 'Ď'..='đ' => "d",
+        'È'..='Ë' | 'è'..='ë' | 'Ē'..='ě' => "e",
+        'Ĝ'..='ģ' => "g",
+        'Ĥ'..='ħ' => "h",
+        'Ì'..='Ï' | 'ì'..='ï' | 'Ĩ'..='ı' => "i",
+        'Ĳ' | 'ĳ' => "ij",
+        'Ĵ' | 'ĵ' => "j",
+        'Ķ'..='ĸ' => "k",
+        'Ĺ'..='ł' => "l",
+        'Ñ' | 'ñ' | 'Ń'..='ŋ' => "n",
+        'Ò'..='Ö' | 'Ø' | 'ò'..='ö' | 'ø' | 'Ō'..='ő' => "o",
+        'Œ' | 'œ' => "oe",
+        'Ŕ'..='ř' => "r",
+        'ß' => "ss",
+        'Ś'..='š' | 'ſ' => "s",
+        'Þ' | 'þ' => "th",
+        'Ţ'..='ŧ' => "t",
+        'Ù'..='Ü' | 'ù'..='ü' | 'Ũ'..='ų' => "u",
+        'Ŵ' | 'ŵ' => "w",
+        'Ý' | 'ý' | 'ÿ' | 'Ŷ'..='Ÿ' => "y",
+        'Ź'..='ž' => "z",
+        _ => return None,
+    };
+    Some(folded)
+}
+
+/// `collate(s, 'name')`, the result of `s COLLATE 'name'`.
+///
+/// It returns the string as is, the planner compares, sorts and groups the strings
+/// by `collation_key(s, 'name')` instead.
+#[derive(Clone)]
+pub struct CollateFunction {
+    display_name: String,
+}
+
+impl CollateFunction {
+    pub fn try_create(display_name: &str, args: &[&DataTypeImpl]) -> Result<Box<dyn Function>> {
+        assert_string(args[0])?;
+        assert_string(args[1])?;
+
+        Ok(Box::new(CollateFunction {
+            display_name: display_name.to_string(),
+        }))
+    }
+
+    pub fn desc() -> FunctionDescription {
+        FunctionDescription::creator(Box::new(Self::try_create))
+            .features(FunctionFeatures::default().deterministic().num_arguments(2))
+    }
+}
+
+impl Function for CollateFunction {
+    fn name(&self) -> &str {
+        &self.display_name
+    }
+
+    fn return_type(&self) -> DataTypeImpl {
+        StringType::new_impl()
+    }
+
+    fn eval(
+        &self,
+        _func_ctx: FunctionContext,
+        columns: &ColumnsWithField,
+        _input_rows: usize,
+    ) -> Result<ColumnRef> {
+        Ok(columns[0].column().clone())
+    }
+}
+
+impl fmt::Display for CollateFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.display_name)
+    }
+}
+
+/// `collation_key(s, 'name')` returns the key of `s` under the collation.
+#[derive(Clone)]
+pub struct CollationKeyFunction {
+    display_name: String,
+}
+
+impl CollationKeyFunction {
+    pub fn try_create(display_name: &str, args: &[&DataTypeImpl]) -> Result<Box<dyn Function>> {
+        assert_string(args[0])?;
+        assert_string(args[1])?;
+
+        Ok(Box::new(CollationKeyFunction {
+            display_name: display_name.to_string(),
+        }))
+    }
+
+    pub fn desc() -> FunctionDescription {
+        FunctionDescription::creator(Box::new(Self::try_create))
+            .features(FunctionFeatures::default().deterministic().num_arguments(2))
+    }
+}
+
+impl Function for CollationKeyFunction {
+    fn name(&self) -> &str {
+        &self.display_name
+    }
+
+    fn return_type(&self) -> DataTypeImpl {
+        StringType::new_impl()
+    }
+
+    fn eval(
+        &self,
+        _func_ctx: FunctionContext,
+        columns: &ColumnsWithField,
+        _input_rows: usize,
+    ) -> Result<ColumnRef> {
+        let func = |value: &[u8], name: &[u8], ctx: &mut EvalContext| match Collation::try_from_name(
+            &String::from_utf8_lossy(name),
+        ) {
+            Ok(collation) => collation.key(value),
+            Err(cause) => {
+                ctx.set_error(cause);
+                vec![]
+            }
+        };
+
+        let col = scalar_binary_op::<Vu8, Vu8, Vu8, _>(
+            columns[0].column(),
+            columns[1].column(),
+            func,
+            &mut EvalContext::default(),
+        )?;
+        Ok(col.arc())
+    }
+}
+
+impl fmt::Display for CollationKeyFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.display_name)
+    }
+}
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 mod comparison;
+mod comparison_collation;
 mod comparison_eq;
 mod comparison_gt;
 mod comparison_gt_eq;
@@ -24,6 +25,9 @@ mod comparison_regexp;
 mod utils;
 
 pub use comparison::ComparisonFunction;
+pub use comparison_collation::CollateFunction;
+pub use comparison_collation::Collation;
+pub use comparison_collation::CollationKeyFunction;
 pub use comparison_eq::ComparisonEqFunction;
 pub use comparison_gt::ComparisonGtFunction;
 pub use comparison_gt_eq::ComparisonGtEqFunction;
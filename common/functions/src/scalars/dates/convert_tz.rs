@@ -0,0 +1,156 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use chrono_tz::Tz;
+use common_datavalues::prelude::*;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use itertools::izip;
+
+use super::utils::local_to_micros;
+use super::utils::micros_to_datetime;
+use super::utils::parse_timezone;
+use crate::scalars::assert_string;
+use crate::scalars::Function;
+use crate::scalars::FunctionContext;
+use crate::scalars::FunctionDescription;
+use crate::scalars::FunctionFeatures;
+
+/// convert_tz(ts, [from_tz,] to_tz), converts the wall-clock time of `ts` from `from_tz` to `to_tz`.
+/// The wall-clock time of `ts` is the time shown in the session timezone, and `from_tz`
+/// defaults to the session timezone, which is what `ts AT TIME ZONE 'to_tz'` evaluates to.
+///
+/// Notes: https://dev.mysql.com/doc/refman/8.0/en/date-and-time-functions.html#function_convert-tz
+#[derive(Clone)]
+pub struct ConvertTzFunction {
+    display_name: String,
+    result_type: DataTypeImpl,
+}
+
+impl ConvertTzFunction {
+    pub fn try_create(display_name: &str, args: &[&DataTypeImpl]) -> Result<Box<dyn Function>> {
+        if args[0].data_type_id() != TypeID::Timestamp {
+            return Err(ErrorCode::BadDataValueType(format!(
+                "Function {} must have a Timestamp type as argument, but got {}",
+                display_name,
+                args[0].name(),
+            )));
+        }
+        for arg in &args[1..] {
+            assert_string(*arg)?;
+        }
+
+        Ok(Box::new(ConvertTzFunction {
+            display_name: display_name.to_string(),
+            result_type: args[0].clone(),
+        }))
+    }
+
+    pub fn desc() -> FunctionDescription {
+        FunctionDescription::creator(Box::new(Self::try_create)).features(
+            FunctionFeatures::default()
+                .deterministic()
+                .variadic_arguments(2, 3),
+        )
+    }
+
+    #[inline]
+    fn convert(micros: i64, session_tz: &Tz, from_tz: &Tz, to_tz: &Tz) -> i64 {
+        let session_time = micros_to_datetime(micros, session_tz);
+        let from_micros = local_to_micros(&session_time.naive_local(), from_tz, &session_time);
+        let to_time = micros_to_datetime(from_micros, to_tz);
+        local_to_micros(&to_time.naive_local(), session_tz, &session_time)
+    }
+
+    fn eval_from_session_tz(
+        &self,
+        func_ctx: FunctionContext,
+        columns: &ColumnsWithField,
+        input_rows: usize,
+    ) -> Result<ColumnRef> {
+        let values = i64::try_create_viewer(columns[0].column())?;
+        let to_tzs = Vu8::try_create_viewer(columns[1].column())?;
+
+        let mut last: Option<(&[u8], Tz)> = None;
+        let mut builder = ColumnBuilder::<i64>::with_capacity(input_rows);
+        for (value, to) in izip!(values.iter(), to_tzs.iter()) {
+            let to_tz = match last {
+                Some((last_to, to_tz)) if last_to == to => to_tz,
+                _ => {
+                    let to_tz = parse_timezone(&self.display_name, to)?;
+                    last = Some((to, to_tz));
+                    to_tz
+                }
+            };
+
+            builder.append(Self::convert(value, &func_ctx.tz, &func_ctx.tz, &to_tz));
+        }
+        Ok(builder.build(input_rows))
+    }
+}
+
+impl Function for ConvertTzFunction {
+    fn name(&self) -> &str {
+        &self.display_name
+    }
+
+    fn return_type(&self) -> DataTypeImpl {
+        self.result_type.clone()
+    }
+
+    fn eval(
+        &self,
+        func_ctx: FunctionContext,
+        columns: &ColumnsWithField,
+        input_rows: usize,
+    ) -> Result<ColumnRef> {
+        if columns.len() == 2 {
+            return self.eval_from_session_tz(func_ctx, columns, input_rows);
+        }
+
+        let values = i64::try_create_viewer(columns[0].column())?;
+        let from_tzs = Vu8::try_create_viewer(columns[1].column())?;
+        let to_tzs = Vu8::try_create_viewer(columns[2].column())?;
+
+        // The timezones are almost always constants, only parse them when they change.
+        let mut last: Option<(&[u8], &[u8], Tz, Tz)> = None;
+        let mut builder = ColumnBuilder::<i64>::with_capacity(input_rows);
+        for (value, from, to) in izip!(values.iter(), from_tzs.iter(), to_tzs.iter()) {
+            let (from_tz, to_tz) = match last {
+                Some((last_from, last_to, from_tz, to_tz))
+                    if last_from == from && last_to == to =>
+                {
+                    (from_tz, to_tz)
+                }
+                _ => {
+                    let from_tz = parse_timezone(&self.display_name, from)?;
+                    let to_tz = parse_timezone(&self.display_name, to)?;
+                    last = Some((from, to, from_tz, to_tz));
+                    (from_tz, to_tz)
+                }
+            };
+
+            builder.append(Self::convert(value, &func_ctx.tz, &from_tz, &to_tz));
+        }
+        Ok(builder.build(input_rows))
+    }
+}
+
+impl fmt::Display for ConvertTzFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.display_name)
+    }
+}
@@ -20,7 +20,13 @@ use super::AddDaysFunction;
 use super::AddMonthsFunction;
 use super::AddTimesFunction;
 use super::AddYearsFunction;
+use super::ConvertTzFunction;
+use super::DateDiffFunction;
+use super::DateFormatFunction;
+use super::DateTruncFunction;
 use super::RoundFunction;
+use super::StrToDateFunction;
+use super::StrftimeFunction;
 use super::ToDayOfMonthFunction;
 use super::ToDayOfWeekFunction;
 use super::ToDayOfYearFunction;
@@ -124,5 +130,18 @@ impl DateFunction {
         factory.register("subtractHours", AddTimesFunction::desc(-3600));
         factory.register("subtractMinutes", AddTimesFunction::desc(-60));
         factory.register("subtractSeconds", AddTimesFunction::desc(-1));
+
+        // formatting and parsing
+        factory.register("date_format", DateFormatFunction::desc());
+        factory.register("strftime", StrftimeFunction::desc());
+        factory.register("str_to_date", StrToDateFunction::desc());
+
+        // calendar arithmetic
+        factory.register("date_trunc", DateTruncFunction::desc());
+        factory.register("date_diff", DateDiffFunction::desc());
+        factory.register("dateDiff", DateDiffFunction::desc());
+
+        // timezone conversion
+        factory.register("convert_tz", ConvertTzFunction::desc());
     }
 }
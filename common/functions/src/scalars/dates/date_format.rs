@@ -0,0 +1,442 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::borrow::Cow;
+use std::fmt;
+use std::fmt::Write;
+
+use chrono_tz::Tz;
+use common_datavalues::chrono::format::parse;
+use common_datavalues::chrono::format::Item;
+use common_datavalues::chrono::format::Parsed;
+use common_datavalues::chrono::format::StrftimeItems;
+use common_datavalues::chrono::Datelike;
+use common_datavalues::chrono::LocalResult;
+use common_datavalues::chrono::NaiveDate;
+use common_datavalues::chrono::NaiveTime;
+use common_datavalues::chrono::TimeZone;
+use common_datavalues::prelude::*;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use itertools::izip;
+
+use super::utils::micros_to_datetime;
+use super::utils::timestamp_column_with_tz;
+use crate::scalars::assert_date_or_timestamp;
+use crate::scalars::assert_string;
+use crate::scalars::scalar_binary_op;
+use crate::scalars::EvalContext;
+use crate::scalars::Function;
+use crate::scalars::FunctionContext;
+use crate::scalars::FunctionDescription;
+use crate::scalars::FunctionFeatures;
+
+/// Translates a MySQL `DATE_FORMAT` pattern into a strftime pattern.
+///
+/// The specifiers without a strftime equivalent (`%D`, `%u`, `%V` and `%X`) are rejected, they
+/// should be replaced by [expand_mysql_specifiers] before when formatting a date.
+///
+/// Notes: https://dev.mysql.com/doc/refman/8.0/en/date-and-time-functions.html#function_date-format
+pub fn mysql_to_strftime(display_name: &str, format: &[u8]) -> Result<String> {
+    let format = String::from_utf8_lossy(format);
+    let mut result = String::with_capacity(format.len() + 8);
+    let mut chars = format.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            result.push(c);
+            continue;
+        }
+
+        let specifier = match chars.next() {
+            Some(specifier) => specifier,
+            None => {
+                result.push_str("%%");
+                break;
+            }
+        };
+
+        let translated = match specifier {
+            'a' => "%a",
+            'b' => "%b",
+            'c' => "%-m",
+            'd' => "%d",
+            'e' => "%-d",
+            'f' => "%6f",
+            'H' => "%H",
+            'h' | 'I' => "%I",
+            'i' => "%M",
+            'j' => "%j",
+            'k' => "%-H",
+            'l' => "%-I",
+            'M' => "%B",
+            'm' => "%m",
+            'p' => "%p",
+            'r' => "%I:%M:%S %p",
+            'S' | 's' => "%S",
+            'T' => "%H:%M:%S",
+            'U' => "%U",
+            'v' => "%V",
+            'W' => "%A",
+            'w' => "%w",
+            'x' => "%G",
+            'D' | 'u' | 'V' | 'X' => {
+                return Err(ErrorCode::BadArguments(format!(
+                    "Unsupported format specifier '%{}' of function {}",
+                    specifier, display_name
+                )));
+            }
+            'Y' => "%Y",
+            'y' => "%y",
+            '%' => "%%",
+            // MySQL outputs the character itself for unknown specifiers.
+            other => {
+                result.push(other);
+                continue;
+            }
+        };
+        result.push_str(translated);
+    }
+
+    Ok(result)
+}
+
+/// Replaces the MySQL specifiers without a strftime equivalent with their values of `date`:
+/// `%D` is the day of the month with an English suffix, `%u` is the week in mode 1 of `WEEK()`,
+/// `%V` and `%X` are the week in mode 2 of `WEEK()` and the year of that week.
+pub fn expand_mysql_specifiers(format: &[u8], date: &NaiveDate) -> Vec<u8> {
+    let mut result = Vec::with_capacity(format.len() + 8);
+    let mut bytes = format.iter();
+
+    while let Some(&c) = bytes.next() {
+        if c != b'%' {
+            result.push(c);
+            continue;
+        }
+
+        let specifier = match bytes.next() {
+            Some(&specifier) => specifier,
+            None => {
+                result.push(c);
+                break;
+            }
+        };
+
+        let expanded = match specifier {
+            b'D' => {
+                let day = date.day();
+                let suffix = match (day % 10, day % 100) {
+                    (_, 11..=13) => "th",
+                    (1, _) => "st",
+                    (2, _) => "nd",
+                    (3, _) => "rd",
+                    _ => "th",
+                };
+                format!("{}{}", day, suffix)
+            }
+            b'u' => format!("{:02}", week_monday_first(date)),
+            b'V' => format!("{:02}", week_sunday_first(date).1),
+            b'X' => format!("{:04}", week_sunday_first(date).0),
+            // Kept for the translation, including `%%`.
+            other => {
+                result.push(c);
+                result.push(other);
+                continue;
+            }
+        };
+        result.extend_from_slice(expanded.as_bytes());
+    }
+
+    result
+}
+
+/// Whether `format` has the MySQL specifiers that [expand_mysql_specifiers] replaces, which make
+/// the strftime pattern depend on the formatted date.
+fn has_date_specifiers(format: &[u8]) -> bool {
+    let mut bytes = format.iter();
+    while let Some(&c) = bytes.next() {
+        if c == b'%' && matches!(bytes.next(), Some(b'D' | b'u' | b'V' | b'X')) {
+            return true;
+        }
+    }
+    false
+}
+
+/// Week of the year in 0..=53, Monday is the first day of a week, and week 1 is the first week
+/// with 4 or more days in the year.
+fn week_monday_first(date: &NaiveDate) -> u32 {
+    let offset = NaiveDate::from_ymd(date.year(), 1, 1)
+        .weekday()
+        .num_days_from_monday();
+    let week = (date.ordinal0() + offset) / 7;
+    if offset <= 3 {
+        week + 1
+    } else {
+        week
+    }
+}
+
+/// Week of the year in 0..=53, Sunday is the first day of a week, and week 1 starts from the
+/// first Sunday of the year, same as `%U`.
+fn week_sunday_first_of_year(date: &NaiveDate) -> u32 {
+    let offset = NaiveDate::from_ymd(date.year(), 1, 1)
+        .weekday()
+        .num_days_from_sunday();
+    let first_sunday = (7 - offset) % 7;
+    (date.ordinal0() + 7 - first_sunday) / 7
+}
+
+/// The year and the week in 1..=53 of `date`, Sunday is the first day of a week, and the days
+/// before the first Sunday belong to the last week of the previous year.
+fn week_sunday_first(date: &NaiveDate) -> (i32, u32) {
+    match week_sunday_first_of_year(date) {
+        0 => {
+            let last_day = NaiveDate::from_ymd(date.year() - 1, 12, 31);
+            (last_day.year(), week_sunday_first_of_year(&last_day))
+        }
+        week => (date.year(), week),
+    }
+}
+
+/// Check the strftime pattern, chrono panics when formatting with an invalid pattern.
+pub fn check_strftime(display_name: &str, format: &str) -> Result<()> {
+    if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+        return Err(ErrorCode::BadArguments(format!(
+            "Invalid format string '{}' of function {}",
+            format, display_name
+        )));
+    }
+    Ok(())
+}
+
+pub type DateFormatFunction = DateFormatFunctionImpl<true>;
+
+pub type StrftimeFunction = DateFormatFunctionImpl<false>;
+
+#[derive(Clone)]
+pub struct DateFormatFunctionImpl<const MYSQL_STYLE: bool> {
+    display_name: String,
+}
+
+impl<const MYSQL_STYLE: bool> DateFormatFunctionImpl<MYSQL_STYLE> {
+    pub fn try_create(display_name: &str, args: &[&DataTypeImpl]) -> Result<Box<dyn Function>> {
+        assert_date_or_timestamp(args[0])?;
+        assert_string(args[1])?;
+
+        Ok(Box::new(Self {
+            display_name: display_name.to_string(),
+        }))
+    }
+
+    pub fn desc() -> FunctionDescription {
+        FunctionDescription::creator(Box::new(Self::try_create))
+            .features(FunctionFeatures::default().deterministic().num_arguments(2))
+    }
+
+    fn pattern(&self, format: &[u8], date: &NaiveDate) -> Result<String> {
+        let pattern = if MYSQL_STYLE {
+            let format = expand_mysql_specifiers(format, date);
+            mysql_to_strftime(&self.display_name, &format)?
+        } else {
+            String::from_utf8_lossy(format).to_string()
+        };
+        check_strftime(&self.display_name, &pattern)?;
+        Ok(pattern)
+    }
+}
+
+impl<const MYSQL_STYLE: bool> Function for DateFormatFunctionImpl<MYSQL_STYLE> {
+    fn name(&self) -> &str {
+        &self.display_name
+    }
+
+    fn return_type(&self) -> DataTypeImpl {
+        StringType::new_impl()
+    }
+
+    fn eval(
+        &self,
+        func_ctx: FunctionContext,
+        columns: &ColumnsWithField,
+        _input_rows: usize,
+    ) -> Result<ColumnRef> {
+        let (micros, tz) = timestamp_column_with_tz(&self.display_name, &columns[0], &func_ctx)?;
+
+        // A constant format is translated only once, unless the pattern depends on the date.
+        let mut const_pattern = None;
+        if columns[1].column().is_const() {
+            let formats = Vu8::try_create_viewer(columns[1].column())?;
+            let format = formats.value_at(0);
+            if !MYSQL_STYLE || !has_date_specifiers(format) {
+                let date = NaiveDate::from_ymd(1970, 1, 1);
+                const_pattern = Some(self.pattern(format, &date)?);
+            }
+        }
+
+        let func = |v: i64, format: &[u8], ctx: &mut EvalContext| {
+            let date_time = micros_to_datetime(v, &ctx.tz);
+            let pattern = match &const_pattern {
+                Some(pattern) => Cow::Borrowed(pattern.as_str()),
+                None => match self.pattern(format, &date_time.naive_local().date()) {
+                    Ok(pattern) => Cow::Owned(pattern),
+                    Err(e) => {
+                        ctx.set_error(e);
+                        return vec![];
+                    }
+                },
+            };
+
+            let mut buf = String::new();
+            if write!(buf, "{}", date_time.format(&pattern)).is_err() {
+                ctx.set_error(ErrorCode::BadArguments(format!(
+                    "Unable to format {} with '{}' in function {}",
+                    date_time, pattern, self.display_name
+                )));
+            }
+            buf.into_bytes()
+        };
+
+        let mut ctx = EvalContext {
+            tz,
+            ..Default::default()
+        };
+        let col =
+            scalar_binary_op::<i64, Vu8, Vu8, _>(&micros, columns[1].column(), func, &mut ctx)?;
+        Ok(col.arc())
+    }
+}
+
+impl<const MYSQL_STYLE: bool> fmt::Display for DateFormatFunctionImpl<MYSQL_STYLE> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.display_name)
+    }
+}
+
+/// Parses `value` with the strftime `pattern` in timezone `tz`.
+/// The time part defaults to midnight when the pattern has no time fields.
+pub fn parse_timestamp(value: &str, pattern: &str, tz: &Tz) -> Option<i64> {
+    let mut parsed = Parsed::new();
+    parse(&mut parsed, value, StrftimeItems::new(pattern)).ok()?;
+
+    let date = parsed.to_naive_date().ok()?;
+    let time = match parsed.to_naive_time() {
+        Ok(time) => time,
+        Err(_) if parsed.hour_div_12.is_none() && parsed.minute.is_none() => {
+            NaiveTime::from_hms(0, 0, 0)
+        }
+        Err(_) => return None,
+    };
+
+    match tz.from_local_datetime(&date.and_time(time)) {
+        LocalResult::Single(t) | LocalResult::Ambiguous(t, _) => Some(t.timestamp_micros()),
+        LocalResult::None => None,
+    }
+}
+
+pub type StrToDateFunction = StrToTimestampFunctionImpl<true>;
+
+pub type StrToTimestampFunction = StrToTimestampFunctionImpl<false>;
+
+/// Parses a string into a timestamp with a MySQL style format, in the session timezone.
+/// `str_to_date` returns NULL if the string can't be parsed, while
+/// `to_timestamp(str, format)` raises an error.
+#[derive(Clone)]
+pub struct StrToTimestampFunctionImpl<const SUPPRESS_PARSE_ERROR: bool> {
+    display_name: String,
+}
+
+impl<const SUPPRESS_PARSE_ERROR: bool> StrToTimestampFunctionImpl<SUPPRESS_PARSE_ERROR> {
+    pub fn try_create(display_name: &str, args: &[&DataTypeImpl]) -> Result<Box<dyn Function>> {
+        assert_string(args[0])?;
+        assert_string(args[1])?;
+
+        Ok(Box::new(Self {
+            display_name: display_name.to_string(),
+        }))
+    }
+
+    pub fn desc() -> FunctionDescription {
+        FunctionDescription::creator(Box::new(Self::try_create))
+            .features(FunctionFeatures::default().deterministic().num_arguments(2))
+    }
+}
+
+impl<const SUPPRESS_PARSE_ERROR: bool> Function
+    for StrToTimestampFunctionImpl<SUPPRESS_PARSE_ERROR>
+{
+    fn name(&self) -> &str {
+        &self.display_name
+    }
+
+    fn return_type(&self) -> DataTypeImpl {
+        if SUPPRESS_PARSE_ERROR {
+            NullableType::new_impl(TimestampType::new_impl(6))
+        } else {
+            TimestampType::new_impl(6)
+        }
+    }
+
+    fn eval(
+        &self,
+        func_ctx: FunctionContext,
+        columns: &ColumnsWithField,
+        input_rows: usize,
+    ) -> Result<ColumnRef> {
+        let values = Vu8::try_create_viewer(columns[0].column())?;
+        let formats = Vu8::try_create_viewer(columns[1].column())?;
+
+        // The format is almost always a constant, only translate it when it changes.
+        let mut last_format: Option<(&[u8], String)> = None;
+        let mut nullable_builder = NullableColumnBuilder::<i64>::with_capacity(input_rows);
+        let mut builder = ColumnBuilder::<i64>::with_capacity(input_rows);
+
+        for (value, format) in izip!(values.iter(), formats.iter()) {
+            if !matches!(&last_format, Some((last, _)) if *last == format) {
+                let pattern = mysql_to_strftime(&self.display_name, format)?;
+                check_strftime(&self.display_name, &pattern)?;
+                last_format = Some((format, pattern));
+            }
+            let pattern = &last_format.as_ref().unwrap().1;
+
+            let value = String::from_utf8_lossy(value);
+            match parse_timestamp(value.trim(), pattern, &func_ctx.tz) {
+                Some(micros) if SUPPRESS_PARSE_ERROR => nullable_builder.append(micros, true),
+                Some(micros) => builder.append(micros),
+                None if SUPPRESS_PARSE_ERROR => nullable_builder.append_null(),
+                None => {
+                    return Err(ErrorCode::StrParseError(format!(
+                        "Unable to parse '{}' with format '{}' in function {}",
+                        value,
+                        String::from_utf8_lossy(format),
+                        self.display_name
+                    )));
+                }
+            }
+        }
+
+        if SUPPRESS_PARSE_ERROR {
+            Ok(nullable_builder.build(input_rows))
+        } else {
+            Ok(builder.build(input_rows))
+        }
+    }
+}
+
+impl<const SUPPRESS_PARSE_ERROR: bool> fmt::Display
+    for StrToTimestampFunctionImpl<SUPPRESS_PARSE_ERROR>
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.display_name)
+    }
+}
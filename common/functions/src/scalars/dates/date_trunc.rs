@@ -0,0 +1,292 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use chrono_tz::Tz;
+use common_datavalues::chrono::Datelike;
+use common_datavalues::chrono::Duration;
+use common_datavalues::chrono::NaiveDate;
+use common_datavalues::chrono::NaiveDateTime;
+use common_datavalues::chrono::Timelike;
+use common_datavalues::prelude::*;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use itertools::izip;
+
+use super::utils::local_to_micros;
+use super::utils::micros_to_datetime;
+use super::utils::timestamp_column_with_tz;
+use super::utils::MICROSECONDS_PER_DAY;
+use crate::scalars::assert_date_or_timestamp;
+use crate::scalars::assert_string;
+use crate::scalars::Function;
+use crate::scalars::FunctionContext;
+use crate::scalars::FunctionDescription;
+use crate::scalars::FunctionFeatures;
+use crate::scalars::Monotonicity;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DateUnit {
+    Year,
+    Quarter,
+    Month,
+    Week,
+    Day,
+    Hour,
+    Minute,
+    Second,
+}
+
+impl DateUnit {
+    pub fn try_from_bytes(display_name: &str, unit: &[u8]) -> Result<DateUnit> {
+        let unit = String::from_utf8_lossy(unit).trim().to_lowercase();
+        match unit.as_str() {
+            "year" | "years" | "yyyy" | "yy" => Ok(DateUnit::Year),
+            "quarter" | "quarters" | "qq" => Ok(DateUnit::Quarter),
+            "month" | "months" | "mm" => Ok(DateUnit::Month),
+            "week" | "weeks" | "wk" => Ok(DateUnit::Week),
+            "day" | "days" | "dd" => Ok(DateUnit::Day),
+            "hour" | "hours" | "hh" => Ok(DateUnit::Hour),
+            "minute" | "minutes" | "mi" => Ok(DateUnit::Minute),
+            "second" | "seconds" | "ss" => Ok(DateUnit::Second),
+            _ => Err(ErrorCode::BadArguments(format!(
+                "Unsupported date unit '{}' of function {}, expected one of \
+                 year, quarter, month, week, day, hour, minute, second",
+                unit, display_name
+            ))),
+        }
+    }
+
+    /// Truncates the microseconds timestamp to the start of the unit, in timezone `tz`.
+    pub fn truncate(&self, micros: i64, tz: &Tz) -> i64 {
+        if let DateUnit::Second = self {
+            return micros - micros.rem_euclid(MICROSECONDS);
+        }
+
+        let date_time = micros_to_datetime(micros, tz);
+        let local = date_time.naive_local();
+        let truncated = self.truncate_local(&local);
+        local_to_micros(&truncated, tz, &date_time)
+    }
+
+    fn truncate_local(&self, local: &NaiveDateTime) -> NaiveDateTime {
+        let date = local.date();
+        match self {
+            DateUnit::Year => NaiveDate::from_ymd(date.year(), 1, 1).and_hms(0, 0, 0),
+            DateUnit::Quarter => {
+                NaiveDate::from_ymd(date.year(), date.month0() / 3 * 3 + 1, 1).and_hms(0, 0, 0)
+            }
+            DateUnit::Month => NaiveDate::from_ymd(date.year(), date.month(), 1).and_hms(0, 0, 0),
+            DateUnit::Week => {
+                let days = date.weekday().num_days_from_monday() as i64;
+                (date - Duration::days(days)).and_hms(0, 0, 0)
+            }
+            DateUnit::Day => date.and_hms(0, 0, 0),
+            DateUnit::Hour => date.and_hms(local.hour(), 0, 0),
+            DateUnit::Minute => date.and_hms(local.hour(), local.minute(), 0),
+            DateUnit::Second => date.and_hms(local.hour(), local.minute(), local.second()),
+        }
+    }
+
+    /// Counts the unit boundaries crossed between `start` and `end`, in timezone `tz`.
+    pub fn diff(&self, start: i64, end: i64, tz: &Tz) -> i64 {
+        let start = micros_to_datetime(start, tz).naive_local();
+        let end = micros_to_datetime(end, tz).naive_local();
+
+        let months = |d: &NaiveDateTime| d.year() as i64 * 12 + d.month0() as i64;
+        match self {
+            DateUnit::Year => end.year() as i64 - start.year() as i64,
+            DateUnit::Quarter => months(&end).div_euclid(3) - months(&start).div_euclid(3),
+            DateUnit::Month => months(&end) - months(&start),
+            DateUnit::Week => {
+                let end = self.truncate_local(&end);
+                let start = self.truncate_local(&start);
+                (end - start).num_days() / 7
+            }
+            DateUnit::Day => (end.date() - start.date()).num_days(),
+            DateUnit::Hour => end.timestamp().div_euclid(3600) - start.timestamp().div_euclid(3600),
+            DateUnit::Minute => end.timestamp().div_euclid(60) - start.timestamp().div_euclid(60),
+            DateUnit::Second => end.timestamp() - start.timestamp(),
+        }
+    }
+}
+
+/// date_trunc(unit, expr), truncates a date or timestamp to the start of the unit.
+#[derive(Clone)]
+pub struct DateTruncFunction {
+    display_name: String,
+    result_type: DataTypeImpl,
+}
+
+impl DateTruncFunction {
+    pub fn try_create(display_name: &str, args: &[&DataTypeImpl]) -> Result<Box<dyn Function>> {
+        assert_string(args[0])?;
+        assert_date_or_timestamp(args[1])?;
+
+        Ok(Box::new(DateTruncFunction {
+            display_name: display_name.to_string(),
+            result_type: args[1].clone(),
+        }))
+    }
+
+    pub fn desc() -> FunctionDescription {
+        FunctionDescription::creator(Box::new(Self::try_create)).features(
+            FunctionFeatures::default()
+                .deterministic()
+                .monotonicity()
+                .num_arguments(2),
+        )
+    }
+}
+
+impl Function for DateTruncFunction {
+    fn name(&self) -> &str {
+        &self.display_name
+    }
+
+    fn return_type(&self) -> DataTypeImpl {
+        self.result_type.clone()
+    }
+
+    fn eval(
+        &self,
+        func_ctx: FunctionContext,
+        columns: &ColumnsWithField,
+        input_rows: usize,
+    ) -> Result<ColumnRef> {
+        let (micros, tz) = timestamp_column_with_tz(&self.display_name, &columns[1], &func_ctx)?;
+        let units = Vu8::try_create_viewer(columns[0].column())?;
+        let values = i64::try_create_viewer(&micros)?;
+
+        let is_date = self.result_type.data_type_id() == TypeID::Date;
+        let mut last_unit: Option<(&[u8], DateUnit)> = None;
+
+        if is_date {
+            let mut builder = ColumnBuilder::<i32>::with_capacity(input_rows);
+            for (unit, value) in izip!(units.iter(), values.iter()) {
+                let unit = cached_unit(&self.display_name, &mut last_unit, unit)?;
+                let truncated = unit.truncate(value, &tz);
+                builder.append(truncated.div_euclid(MICROSECONDS_PER_DAY) as i32);
+            }
+            return Ok(builder.build(input_rows));
+        }
+
+        let mut builder = ColumnBuilder::<i64>::with_capacity(input_rows);
+        for (unit, value) in izip!(units.iter(), values.iter()) {
+            let unit = cached_unit(&self.display_name, &mut last_unit, unit)?;
+            builder.append(unit.truncate(value, &tz));
+        }
+        Ok(builder.build(input_rows))
+    }
+
+    // date_trunc is non-decreasing on the timestamp if the unit is constant.
+    fn get_monotonicity(&self, args: &[Monotonicity]) -> Result<Monotonicity> {
+        if args[0].is_constant {
+            return Ok(Monotonicity::clone_without_range(&args[1]));
+        }
+        Ok(Monotonicity::default())
+    }
+}
+
+impl fmt::Display for DateTruncFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.display_name)
+    }
+}
+
+/// date_diff(unit, start, end), counts the unit boundaries crossed between start and end.
+#[derive(Clone)]
+pub struct DateDiffFunction {
+    display_name: String,
+}
+
+impl DateDiffFunction {
+    pub fn try_create(display_name: &str, args: &[&DataTypeImpl]) -> Result<Box<dyn Function>> {
+        assert_string(args[0])?;
+        assert_date_or_timestamp(args[1])?;
+        assert_date_or_timestamp(args[2])?;
+
+        Ok(Box::new(DateDiffFunction {
+            display_name: display_name.to_string(),
+        }))
+    }
+
+    pub fn desc() -> FunctionDescription {
+        FunctionDescription::creator(Box::new(Self::try_create))
+            .features(FunctionFeatures::default().deterministic().num_arguments(3))
+    }
+}
+
+impl Function for DateDiffFunction {
+    fn name(&self) -> &str {
+        &self.display_name
+    }
+
+    fn return_type(&self) -> DataTypeImpl {
+        i64::to_data_type()
+    }
+
+    fn eval(
+        &self,
+        func_ctx: FunctionContext,
+        columns: &ColumnsWithField,
+        input_rows: usize,
+    ) -> Result<ColumnRef> {
+        // Mixing a date with a timestamp compares both in the session timezone.
+        let (start, start_tz) =
+            timestamp_column_with_tz(&self.display_name, &columns[1], &func_ctx)?;
+        let (end, end_tz) = timestamp_column_with_tz(&self.display_name, &columns[2], &func_ctx)?;
+        let tz = if start_tz == end_tz {
+            start_tz
+        } else {
+            func_ctx.tz
+        };
+
+        let units = Vu8::try_create_viewer(columns[0].column())?;
+        let starts = i64::try_create_viewer(&start)?;
+        let ends = i64::try_create_viewer(&end)?;
+
+        let mut last_unit: Option<(&[u8], DateUnit)> = None;
+        let mut builder = ColumnBuilder::<i64>::with_capacity(input_rows);
+        for (unit, start, end) in izip!(units.iter(), starts.iter(), ends.iter()) {
+            let unit = cached_unit(&self.display_name, &mut last_unit, unit)?;
+            builder.append(unit.diff(start, end, &tz));
+        }
+        Ok(builder.build(input_rows))
+    }
+}
+
+impl fmt::Display for DateDiffFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.display_name)
+    }
+}
+
+// The unit is almost always a constant, only parse it when it changes.
+#[inline]
+fn cached_unit<'a>(
+    display_name: &str,
+    last: &mut Option<(&'a [u8], DateUnit)>,
+    unit: &'a [u8],
+) -> Result<DateUnit> {
+    match last {
+        Some((last_unit, parsed)) if *last_unit == unit => Ok(*parsed),
+        _ => {
+            let parsed = DateUnit::try_from_bytes(display_name, unit)?;
+            *last = Some((unit, parsed));
+            Ok(parsed)
+        }
+    }
+}
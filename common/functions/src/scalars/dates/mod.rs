@@ -12,17 +12,29 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod convert_tz;
 mod date;
+mod date_format;
+mod date_trunc;
 mod interval_function;
 mod now;
 mod number_function;
 mod round_function;
 mod simple_date;
+pub(crate) mod utils;
 mod week_date;
 #[macro_use]
 mod macros;
 
+pub use convert_tz::ConvertTzFunction;
 pub use date::DateFunction;
+pub use date_format::DateFormatFunction;
+pub use date_format::StrToDateFunction;
+pub use date_format::StrToTimestampFunction;
+pub use date_format::StrftimeFunction;
+pub use date_trunc::DateDiffFunction;
+pub use date_trunc::DateTruncFunction;
+pub use date_trunc::DateUnit;
 pub use interval_function::AddDaysFunction;
 pub use interval_function::AddMonthsFunction;
 pub use interval_function::AddTimesFunction;
@@ -15,13 +15,13 @@
 use std::fmt;
 
 use chrono_tz::Tz;
-use common_datavalues::chrono::Datelike;
-use common_datavalues::chrono::TimeZone;
 use common_datavalues::chrono::Timelike;
 use common_datavalues::prelude::*;
 use common_exception::ErrorCode;
 use common_exception::Result;
 
+use super::utils::local_to_micros;
+use super::utils::micros_to_datetime;
 use crate::scalars::scalar_unary_op;
 use crate::scalars::EvalContext;
 use crate::scalars::Function;
@@ -68,43 +68,24 @@ impl RoundFunction {
         Ok(Box::new(s))
     }
 
-    // TODO: (sundy-li)
-    // Consider about the timezones/offsets
-    // Currently: assuming timezone offset is a multiple of round.
+    /// Rounds down the wall-clock time in `tz`, so that offsets which are not a multiple of
+    /// the round (e.g. `Asia/Kolkata`, +05:30) are still honored.
     #[inline]
     fn execute(&self, time: i64, tz: &Tz) -> i64 {
-        let dt = tz.timestamp(time / MICROSECONDS, 0_u32);
-        match self.round {
-            Round::Second => dt.timestamp_micros(),
-            Round::Minute => tz
-                .ymd(dt.year(), dt.month(), dt.day())
-                .and_hms_micro(dt.hour(), dt.minute(), 0, 0)
-                .timestamp_micros(),
-            Round::FiveMinutes => tz
-                .ymd(dt.year(), dt.month(), dt.day())
-                .and_hms_micro(dt.hour(), dt.minute() / 5 * 5, 0, 0)
-                .timestamp_micros(),
-            Round::TenMinutes => tz
-                .ymd(dt.year(), dt.month(), dt.day())
-                .and_hms_micro(dt.hour(), dt.minute() / 10 * 10, 0, 0)
-                .timestamp_micros(),
-            Round::FifteenMinutes => tz
-                .ymd(dt.year(), dt.month(), dt.day())
-                .and_hms_micro(dt.hour(), dt.minute() / 15 * 15, 0, 0)
-                .timestamp_micros(),
-            Round::TimeSlot => tz
-                .ymd(dt.year(), dt.month(), dt.day())
-                .and_hms_micro(dt.hour(), dt.minute() / 30 * 30, 0, 0)
-                .timestamp_micros(),
-            Round::Hour => tz
-                .ymd(dt.year(), dt.month(), dt.day())
-                .and_hms_micro(dt.hour(), 0, 0, 0)
-                .timestamp_micros(),
-            Round::Day => tz
-                .ymd(dt.year(), dt.month(), dt.day())
-                .and_hms_micro(0, 0, 0, 0)
-                .timestamp_micros(),
-        }
+        let dt = micros_to_datetime(time, tz);
+        let local = dt.naive_local();
+        let minute = match self.round {
+            Round::Second => return time - time.rem_euclid(MICROSECONDS),
+            Round::Minute => local.minute(),
+            Round::FiveMinutes => local.minute() / 5 * 5,
+            Round::TenMinutes => local.minute() / 10 * 10,
+            Round::FifteenMinutes => local.minute() / 15 * 15,
+            Round::TimeSlot => local.minute() / 30 * 30,
+            Round::Hour => 0,
+            Round::Day => return local_to_micros(&local.date().and_hms(0, 0, 0), tz, &dt),
+        };
+        let rounded = local.date().and_hms(local.hour(), minute, 0);
+        local_to_micros(&rounded, tz, &dt)
     }
 }
 
@@ -0,0 +1,89 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use chrono_tz::Tz;
+use common_datavalues::chrono::DateTime;
+use common_datavalues::chrono::LocalResult;
+use common_datavalues::chrono::NaiveDateTime;
+use common_datavalues::chrono::Offset;
+use common_datavalues::chrono::TimeZone;
+use common_datavalues::prelude::*;
+use common_exception::ErrorCode;
+use common_exception::Result;
+
+use crate::scalars::scalar_unary_op;
+use crate::scalars::EvalContext;
+use crate::scalars::FunctionContext;
+
+pub const MICROSECONDS_PER_DAY: i64 = 24 * 3600 * MICROSECONDS;
+
+/// Converts a Date or Timestamp column into a column of microseconds since the epoch,
+/// and returns the timezone the values should be interpreted in.
+///
+/// Dates carry no timezone, so they are always interpreted in UTC, while timestamps
+/// are interpreted in the session timezone.
+pub fn timestamp_column_with_tz(
+    display_name: &str,
+    column: &ColumnWithField,
+    func_ctx: &FunctionContext,
+) -> Result<(ColumnRef, Tz)> {
+    match column.data_type().data_type_id() {
+        TypeID::Date => {
+            let col = scalar_unary_op::<i32, i64, _>(
+                column.column(),
+                |v: i32, _ctx: &mut EvalContext| v as i64 * MICROSECONDS_PER_DAY,
+                &mut EvalContext::default(),
+            )?;
+            Ok((col.arc(), "UTC".parse::<Tz>().unwrap()))
+        }
+        TypeID::Timestamp => Ok((column.column().clone(), func_ctx.tz)),
+        other => Err(ErrorCode::IllegalDataType(format!(
+            "Illegal type {:?} of argument of function {}. Should be a date/timestamp",
+            other, display_name
+        ))),
+    }
+}
+
+#[inline]
+pub fn micros_to_datetime(micros: i64, tz: &Tz) -> DateTime<Tz> {
+    let secs = micros.div_euclid(MICROSECONDS);
+    let nanos = micros.rem_euclid(MICROSECONDS) as u32 * 1000;
+    tz.timestamp(secs, nanos)
+}
+
+/// Resolves a wall-clock time in `tz` back to microseconds since the epoch.
+///
+/// Ambiguous local times (clocks moved back) resolve to the earliest instant. Local
+/// times that do not exist (clocks moved ahead) reuse the offset of `reference`.
+#[inline]
+pub fn local_to_micros(local: &NaiveDateTime, tz: &Tz, reference: &DateTime<Tz>) -> i64 {
+    match tz.from_local_datetime(local) {
+        LocalResult::Single(t) | LocalResult::Ambiguous(t, _) => t.timestamp_micros(),
+        LocalResult::None => {
+            let offset = reference.offset().fix().local_minus_utc() as i64;
+            (local.timestamp() - offset) * MICROSECONDS + local.timestamp_subsec_micros() as i64
+        }
+    }
+}
+
+#[inline]
+pub fn parse_timezone(display_name: &str, tz: &[u8]) -> Result<Tz> {
+    let tz = String::from_utf8_lossy(tz);
+    tz.parse::<Tz>().map_err(|_| {
+        ErrorCode::InvalidTimezone(format!(
+            "Invalid timezone '{}' in function {}",
+            tz, display_name
+        ))
+    })
+}
@@ -17,15 +17,22 @@ use std::sync::Arc;
 use chrono_tz::Tz;
 use common_arrow::arrow::bitmap::Bitmap;
 use common_datavalues::chrono::DateTime;
+use common_datavalues::chrono::Datelike;
+use common_datavalues::chrono::NaiveDate;
 use common_datavalues::chrono::TimeZone;
 use common_datavalues::prelude::*;
 use common_exception::Result;
 
 use super::cast_with_type::arrow_cast_compute;
 use super::cast_with_type::CastOptions;
+use crate::scalars::dates::utils::local_to_micros;
+use crate::scalars::dates::utils::micros_to_datetime;
+use crate::scalars::dates::utils::MICROSECONDS_PER_DAY;
 use crate::scalars::FunctionContext;
 
 const DATE_FMT: &str = "%Y-%m-%d";
+// Days from 0001-01-01 (day 1 of the common era) to 1970-01-01.
+const EPOCH_DAYS_FROM_CE: i32 = 719_163;
 // const TIME_FMT: &str = "%Y-%m-%d %H:%M:%S";
 
 pub fn cast_from_date(
@@ -51,8 +58,14 @@ pub fn cast_from_date(
             Ok((builder.build(size), None))
         }
 
+        // A date becomes the midnight of that day in the session timezone.
         TypeID::Timestamp => {
-            let it = c.iter().map(|v| *v as i64 * 24 * 3600 * 1_000_000);
+            let tz = func_ctx.tz;
+            let it = c.iter().map(|v| {
+                let midnight = NaiveDate::from_num_days_from_ce(*v + EPOCH_DAYS_FROM_CE);
+                let reference = micros_to_datetime(*v as i64 * MICROSECONDS_PER_DAY, &tz);
+                local_to_micros(&midnight.and_hms(0, 0, 0), &tz, &reference)
+            });
             let result = Arc::new(Int64Column::from_iterator(it));
             Ok((result, None))
         }
@@ -80,7 +93,7 @@ pub fn cast_from_timestamp(
             let tz = func_ctx.tz;
             for v in c.iter() {
                 let s = timestamp_to_string(
-                    tz.timestamp(*v / 1_000_000, (*v % 1_000_000 * 1_000) as u32),
+                    micros_to_datetime(*v, &tz),
                     date_time64.format_string().as_str(),
                 );
                 builder.append_value(s.as_bytes());
@@ -88,10 +101,13 @@ pub fn cast_from_timestamp(
             Ok((builder.to_column(), None))
         }
 
+        // A timestamp becomes the day it falls on in the session timezone.
         TypeID::Date => {
-            let it = c
-                .iter()
-                .map(|v| (date_time64.to_seconds(*v) / 24 / 3600) as i32);
+            let tz = func_ctx.tz;
+            let it = c.iter().map(|v| {
+                let date = micros_to_datetime(*v, &tz).naive_local().date();
+                date.num_days_from_ce() - EPOCH_DAYS_FROM_CE
+            });
             let result = Arc::new(Int32Column::from_iterator(it));
             Ok((result, None))
         }
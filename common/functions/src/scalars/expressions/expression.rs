@@ -1,4 +1,3 @@
-use common_datavalues::TypeFactory;
 // Copyright 2021 Datafuse Labs.
 //
 // Licensed under the Apache License, Version 2.0 (the "License");
@@ -12,13 +11,18 @@ use common_datavalues::TypeFactory;
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
+
+use common_datavalues::remove_nullable;
+use common_datavalues::TypeFactory;
 use common_exception::Result;
 
 use crate::scalars::function_factory::FactoryCreator;
 use crate::scalars::function_factory::FunctionDescription;
 use crate::scalars::function_factory::FunctionFactory;
 use crate::scalars::CastFunction;
+use crate::scalars::FunctionAdapter;
 use crate::scalars::FunctionFeatures;
+use crate::scalars::StrToTimestampFunction;
 
 #[derive(Clone)]
 pub struct ToCastFunction;
@@ -37,6 +41,19 @@ impl ToCastFunction {
         };
 
         let function_creator: FactoryCreator = Box::new(move |display_name, args| {
+            // to_timestamp(str, format) parses the string with the format instead of casting.
+            if matches!(type_name, "Timestamp" | "DateTime")
+                && args.len() == 2
+                && args
+                    .iter()
+                    .all(|arg| remove_nullable(arg).data_type_id().is_string())
+            {
+                return FunctionAdapter::try_create(
+                    &StrToTimestampFunction::desc(),
+                    display_name,
+                    args,
+                );
+            }
             CastFunction::create(display_name, type_name, args[0].clone())
         });
 
@@ -14,7 +14,11 @@
 
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
 
+use common_base::infallible::Mutex;
+use common_base::infallible::RwLock;
 use common_datavalues::DataTypeImpl;
 use common_exception::ErrorCode;
 use common_exception::Result;
@@ -62,8 +66,16 @@ impl FunctionDescription {
     }
 }
 
+// A function registered at runtime, with the time it was last used.
+struct DynamicFunction {
+    desc: Arc<FunctionDescription>,
+    last_used: Mutex<Instant>,
+}
+
 pub struct FunctionFactory {
     case_insensitive_desc: HashMap<String, FunctionDescription>,
+    // Functions registered at runtime, e.g. the WebAssembly UDFs of the tenants.
+    dynamic_desc: RwLock<HashMap<String, DynamicFunction>>,
 }
 
 static FUNCTION_FACTORY: Lazy<Arc<FunctionFactory>> = Lazy::new(|| {
@@ -92,6 +104,7 @@ impl FunctionFactory {
     pub(in crate::scalars::function_factory) fn create() -> FunctionFactory {
         FunctionFactory {
             case_insensitive_desc: Default::default(),
+            dynamic_desc: Default::default(),
         }
     }
 
@@ -104,34 +117,66 @@ impl FunctionFactory {
         case_insensitive_desc.insert(name.to_lowercase(), desc);
     }
 
+    /// Registers a function at runtime, replaces the function registered with the same name.
+    /// The name must not conflict with the builtin functions.
+    pub fn register_dynamic(&self, name: &str, desc: FunctionDescription) -> Result<()> {
+        let lowercase_name = name.to_lowercase();
+        if self.case_insensitive_desc.contains_key(&lowercase_name) {
+            return Err(ErrorCode::LogicalError(format!(
+                "Cannot register function {}, it's a builtin function",
+                name
+            )));
+        }
+
+        self.dynamic_desc
+            .write()
+            .insert(lowercase_name, DynamicFunction {
+                desc: Arc::new(desc),
+                last_used: Mutex::new(Instant::now()),
+            });
+        Ok(())
+    }
+
+    /// Unregisters the functions registered at runtime whose names start with the prefix.
+    pub fn unregister_dynamic_with_prefix(&self, prefix: &str) {
+        let prefix = prefix.to_lowercase();
+        self.dynamic_desc
+            .write()
+            .retain(|name, _| !name.starts_with(&prefix));
+    }
+
+    /// Unregisters the functions registered at runtime which are not used for `idle`,
+    /// they are registered again when they are used.
+    pub fn evict_dynamic(&self, idle: Duration) {
+        self.dynamic_desc
+            .write()
+            .retain(|_, function| function.last_used.lock().elapsed() < idle);
+    }
+
     pub fn get(&self, name: impl AsRef<str>, args: &[&DataTypeImpl]) -> Result<Box<dyn Function>> {
         let origin_name = name.as_ref();
         let lowercase_name = origin_name.to_lowercase();
 
-        let desc = self
-            .case_insensitive_desc
-            .get(&lowercase_name)
-            .ok_or_else(|| {
-                // TODO(Winter): we should write similar function names into error message if function name is not found.
-                ErrorCode::UnknownFunction(format!("Unsupported Function: {}", origin_name))
-            })?;
-
-        FunctionAdapter::try_create(desc, origin_name, args)
+        match self.case_insensitive_desc.get(&lowercase_name) {
+            Some(desc) => FunctionAdapter::try_create(desc, origin_name, args),
+            None => {
+                let desc = self.get_dynamic(origin_name, &lowercase_name)?;
+                FunctionAdapter::try_create(&desc, origin_name, args)
+            }
+        }
     }
 
     pub fn get_features(&self, name: impl AsRef<str>) -> Result<FunctionFeatures> {
         let origin_name = name.as_ref();
         let lowercase_name = origin_name.to_lowercase();
 
-        let desc = self
-            .case_insensitive_desc
-            .get(&lowercase_name)
-            .ok_or_else(|| {
-                // TODO(Winter): we should write similar function names into error message if function name is not found.
-                ErrorCode::UnknownFunction(format!("Unsupported Function: {}", origin_name))
-            })?;
-
-        Ok(desc.features.clone())
+        match self.case_insensitive_desc.get(&lowercase_name) {
+            Some(desc) => Ok(desc.features.clone()),
+            None => Ok(self
+                .get_dynamic(origin_name, &lowercase_name)?
+                .features
+                .clone()),
+        }
     }
 
     pub fn check(&self, name: impl AsRef<str>) -> bool {
@@ -139,6 +184,25 @@ impl FunctionFactory {
         let lowercase_name = origin_name.to_lowercase();
 
         self.case_insensitive_desc.contains_key(&lowercase_name)
+            || self.dynamic_desc.read().contains_key(&lowercase_name)
+    }
+
+    fn get_dynamic(
+        &self,
+        origin_name: &str,
+        lowercase_name: &str,
+    ) -> Result<Arc<FunctionDescription>> {
+        self.dynamic_desc
+            .read()
+            .get(lowercase_name)
+            .map(|function| {
+                *function.last_used.lock() = Instant::now();
+                function.desc.clone()
+            })
+            .ok_or_else(|| {
+                // TODO(Winter): we should write similar function names into error message if function name is not found.
+                ErrorCode::UnknownFunction(format!("Unsupported Function: {}", origin_name))
+            })
     }
 
     pub fn registered_names(&self) -> Vec<String> {
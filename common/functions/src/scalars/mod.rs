@@ -33,6 +33,7 @@ mod semi_structureds;
 mod strings;
 mod tuples;
 mod uuids;
+mod wasm;
 
 pub use arithmetics::*;
 pub use commons::*;
@@ -55,3 +56,4 @@ pub use semi_structureds::*;
 pub use strings::*;
 pub use tuples::*;
 pub use uuids::*;
+pub use wasm::*;
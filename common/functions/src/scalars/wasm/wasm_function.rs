@@ -0,0 +1,308 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::io::Cursor;
+use std::time::Duration;
+
+use common_arrow::arrow::chunk::Chunk;
+use common_arrow::arrow::io::ipc::read::read_stream_metadata;
+use common_arrow::arrow::io::ipc::read::StreamReader;
+use common_arrow::arrow::io::ipc::read::StreamState;
+use common_arrow::arrow::io::ipc::write::StreamWriter;
+use common_arrow::arrow::io::ipc::write::WriteOptions;
+use common_datavalues::prelude::*;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use sha2::Digest;
+
+use super::WasmLimits;
+use super::WasmModule;
+use crate::scalars::Function;
+use crate::scalars::FunctionContext;
+use crate::scalars::FunctionDescription;
+use crate::scalars::FunctionFactory;
+use crate::scalars::FunctionFeatures;
+
+/// The registered functions not used for the time are unregistered, e.g. the dropped functions
+/// registered on the other nodes of the cluster.
+const WASM_FUNCTION_IDLE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// A scalar function implemented by a WebAssembly module, it runs over whole columns.
+///
+/// The input of the module is an Arrow IPC stream of one batch, with the argument columns named
+/// `arg0`, `arg1`, ... The output must be an Arrow IPC stream of one batch with one column of the
+/// return type and as many rows as the input. The Arrow type of a data type is the one of
+/// `DataTypeImpl::arrow_type`, e.g. `String` is `LargeBinary` and `Date` is `Int32`.
+/// Nulls never reach the module, the result of a row is null if any argument is null.
+#[derive(Clone)]
+pub struct WasmFunction {
+    display_name: String,
+    module: WasmModule,
+    return_type: DataTypeImpl,
+    limits: WasmLimits,
+}
+
+impl WasmFunction {
+    pub fn try_create(
+        display_name: &str,
+        module: WasmModule,
+        return_type: DataTypeImpl,
+        limits: WasmLimits,
+    ) -> Result<Box<dyn Function>> {
+        Ok(Box::new(WasmFunction {
+            display_name: display_name.to_string(),
+            module,
+            return_type: remove_nullable(&return_type),
+            limits,
+        }))
+    }
+
+    pub fn desc(
+        module: WasmModule,
+        num_arguments: usize,
+        return_type: DataTypeImpl,
+        limits: WasmLimits,
+    ) -> FunctionDescription {
+        FunctionDescription::creator(Box::new(move |display_name, args| {
+            if args.len() != num_arguments {
+                return Err(ErrorCode::NumberArgumentsNotMatch(format!(
+                    "Function {} expect to have {} arguments, but got {}",
+                    display_name,
+                    num_arguments,
+                    args.len()
+                )));
+            }
+            Self::try_create(display_name, module.clone(), return_type.clone(), limits)
+        }))
+        .features(
+            FunctionFeatures::default()
+                .deterministic()
+                .num_arguments(num_arguments),
+        )
+    }
+
+    /// The name under which the function of the tenant is registered in the `FunctionFactory`.
+    /// It changes with the definition and the limits, so a changed function is registered again.
+    ///
+    /// The name is sent to the other nodes of the cluster within the plans, the limits are kept
+    /// in it so the nodes register the function with the same limits, see
+    /// [WasmFunction::parse_registered_name].
+    pub fn registered_name(
+        tenant: &str,
+        name: &str,
+        parameter_types: &[DataTypeImpl],
+        return_type: &DataTypeImpl,
+        definition: &str,
+        limits: &WasmLimits,
+    ) -> String {
+        let mut hasher = sha2::Sha256::new();
+        for data_type in parameter_types {
+            hasher.update(data_type.name());
+            hasher.update([0]);
+        }
+        hasher.update(return_type.name());
+        hasher.update([0]);
+        hasher.update(definition);
+        let digest = hex::encode(&hasher.finalize()[..8]);
+
+        format!(
+            "{}{}::{}::{}",
+            Self::registered_prefix(tenant, name),
+            digest,
+            limits.max_memory,
+            limits.timeout.as_millis()
+        )
+    }
+
+    /// Returns the name of the UDF and the limits of a registered name of the tenant, `None` if
+    /// it's not the name of a WebAssembly function of the tenant.
+    pub fn parse_registered_name(
+        tenant: &str,
+        registered_name: &str,
+    ) -> Option<(String, WasmLimits)> {
+        let tenant_prefix = format!("wasm::{}::", tenant).to_lowercase();
+        if !registered_name.to_lowercase().starts_with(&tenant_prefix) {
+            return None;
+        }
+
+        let mut parts = registered_name[tenant_prefix.len()..].rsplitn(4, "::");
+        let timeout = parts.next()?.parse::<u64>().ok()?;
+        let max_memory = parts.next()?.parse::<usize>().ok()?;
+        let _digest = parts.next()?;
+        let name = parts.next()?;
+        Some((name.to_string(), WasmLimits {
+            max_memory,
+            timeout: Duration::from_millis(timeout),
+        }))
+    }
+
+    /// Checks that `name` is not a WebAssembly function of another tenant: the functions are
+    /// registered for all the tenants in the `FunctionFactory`, and only scoped by the names.
+    pub fn check_tenant(tenant: &str, name: &str) -> Result<()> {
+        if !name.to_lowercase().starts_with("wasm::") {
+            return Ok(());
+        }
+        match Self::parse_registered_name(tenant, name) {
+            Some((udf_name, _)) if !udf_name.contains("::") => Ok(()),
+            _ => Err(ErrorCode::UnknownFunction(format!(
+                "Unsupported Function: {}",
+                name
+            ))),
+        }
+    }
+
+    pub fn registered_prefix(tenant: &str, name: &str) -> String {
+        format!("wasm::{}::{}::", tenant, name)
+    }
+
+    /// Compiles the base64 encoded module and registers it in the `FunctionFactory`,
+    /// the module exports the function under the name of the UDF.
+    /// Returns the registered name.
+    pub fn register(
+        tenant: &str,
+        name: &str,
+        parameter_types: &[DataTypeImpl],
+        return_type: &DataTypeImpl,
+        definition: &str,
+        limits: &WasmLimits,
+    ) -> Result<String> {
+        let registered_name = Self::registered_name(
+            tenant,
+            name,
+            parameter_types,
+            return_type,
+            definition,
+            limits,
+        );
+
+        let factory = FunctionFactory::instance();
+        factory.evict_dynamic(WASM_FUNCTION_IDLE_TIMEOUT);
+        if !factory.check(&registered_name) {
+            let module = WasmModule::try_create_from_base64(name, definition)?;
+            let desc = Self::desc(module, parameter_types.len(), return_type.clone(), *limits);
+            factory.register_dynamic(&registered_name, desc)?;
+        }
+
+        Ok(registered_name)
+    }
+
+    /// Unregisters all the versions of the function of the tenant.
+    pub fn unregister(tenant: &str, name: &str) {
+        let prefix = Self::registered_prefix(tenant, name);
+        FunctionFactory::instance().unregister_dynamic_with_prefix(&prefix);
+    }
+
+    fn encode_input(columns: &ColumnsWithField) -> Result<Vec<u8>> {
+        let fields = columns
+            .iter()
+            .enumerate()
+            .map(|(index, column)| {
+                DataField::new(&format!("arg{}", index), column.data_type().clone())
+            })
+            .collect::<Vec<_>>();
+        let schema = DataSchema::new(fields).to_arrow();
+
+        let arrays = columns
+            .iter()
+            .map(|column| column.column().convert_full_column().as_arrow_array())
+            .collect::<Vec<_>>();
+        let chunk = Chunk::try_new(arrays)?;
+
+        let mut input = vec![];
+        {
+            let options = WriteOptions { compression: None };
+            let mut writer = StreamWriter::new(&mut input, options);
+            writer.start(&schema, None)?;
+            writer.write(&chunk, None)?;
+            writer.finish()?;
+        }
+        Ok(input)
+    }
+
+    fn decode_output(&self, output: Vec<u8>, input_rows: usize) -> Result<ColumnRef> {
+        let mut reader = Cursor::new(output);
+        let metadata = read_stream_metadata(&mut reader)?;
+        let stream = StreamReader::new(reader, metadata);
+
+        let mut chunks = vec![];
+        for state in stream {
+            match state? {
+                StreamState::Some(chunk) => chunks.push(chunk),
+                StreamState::Waiting => break,
+            }
+        }
+
+        if chunks.len() != 1 || chunks[0].columns().len() != 1 {
+            return Err(self.output_error(format!(
+                "expect one batch of one column, but got {} batches",
+                chunks.len()
+            )));
+        }
+
+        let array = &chunks[0].columns()[0];
+        let expected_type = self.return_type.arrow_type();
+        if array.data_type().to_logical_type() != expected_type.to_logical_type() {
+            return Err(self.output_error(format!(
+                "expect column of arrow type {:?}, but got {:?}",
+                expected_type,
+                array.data_type()
+            )));
+        }
+
+        if array.len() != input_rows {
+            return Err(self.output_error(format!(
+                "expect {} rows, but got {}",
+                input_rows,
+                array.len()
+            )));
+        }
+
+        Ok(array.into_column())
+    }
+
+    fn output_error(&self, message: String) -> ErrorCode {
+        ErrorCode::UDFRuntimeError(format!(
+            "Invalid output of WebAssembly function {}: {}",
+            self.display_name, message
+        ))
+    }
+}
+
+impl Function for WasmFunction {
+    fn name(&self) -> &str {
+        &self.display_name
+    }
+
+    fn return_type(&self) -> DataTypeImpl {
+        self.return_type.clone()
+    }
+
+    fn eval(
+        &self,
+        _func_ctx: FunctionContext,
+        columns: &ColumnsWithField,
+        input_rows: usize,
+    ) -> Result<ColumnRef> {
+        let input = Self::encode_input(columns)?;
+        let output = self.module.call(&input, &self.limits)?;
+        self.decode_output(output, input_rows)
+    }
+}
+
+impl fmt::Display for WasmFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.display_name)
+    }
+}
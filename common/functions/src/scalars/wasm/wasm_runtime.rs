@@ -0,0 +1,235 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use common_base::base::Thread;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use once_cell::sync::Lazy;
+use wasmtime::Config;
+use wasmtime::Engine;
+use wasmtime::ExternType;
+use wasmtime::Instance;
+use wasmtime::Module;
+use wasmtime::Store;
+use wasmtime::StoreLimits;
+use wasmtime::StoreLimitsBuilder;
+use wasmtime::TrapCode;
+use wasmtime::ValType;
+
+// The interval of the epoch ticks, the timeout of a call is rounded up to it.
+const EPOCH_TICK: Duration = Duration::from_millis(10);
+
+// The engine interrupts a running module once the epoch passes the deadline of its store,
+// a background thread advances the epoch.
+static WASM_ENGINE: Lazy<Engine> = Lazy::new(|| {
+    let mut config = Config::new();
+    config.epoch_interruption(true);
+    let engine = Engine::new(&config).expect("Cannot create the WebAssembly engine");
+
+    let ticker = engine.clone();
+    Thread::named_spawn(Some("wasm-epoch-ticker".to_string()), move || loop {
+        std::thread::sleep(EPOCH_TICK);
+        ticker.increment_epoch();
+    });
+
+    engine
+});
+
+/// The resources a single call of a WebAssembly module can use.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct WasmLimits {
+    /// The maximum size in bytes of the linear memory.
+    pub max_memory: usize,
+    /// The maximum running time of one call.
+    pub timeout: Duration,
+}
+
+impl Default for WasmLimits {
+    fn default() -> Self {
+        WasmLimits {
+            max_memory: 64 * 1024 * 1024,
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+struct WasmStoreState {
+    limits: StoreLimits,
+}
+
+/// A compiled WebAssembly module which implements a function.
+///
+/// The module must not import anything, and must export:
+/// - `memory`, the linear memory.
+/// - `alloc(len: i32) -> i32`, returns the offset of `len` free bytes in the memory.
+/// - `<entry>(ptr: i32, len: i32) -> i64`, takes the input bytes at `ptr` and returns the
+///   output bytes packed as `(offset << 32) | len`.
+#[derive(Clone)]
+pub struct WasmModule {
+    entry: String,
+    module: Module,
+}
+
+impl WasmModule {
+    pub fn try_create(entry: &str, binary: &[u8]) -> Result<WasmModule> {
+        let module = Module::new(&WASM_ENGINE, binary).map_err(|cause| {
+            ErrorCode::IllegalUDFFormat(format!("Invalid WebAssembly module: {}", cause))
+        })?;
+
+        if let Some(import) = module.imports().next() {
+            return Err(ErrorCode::IllegalUDFFormat(format!(
+                "WebAssembly module must not import anything, but it imports from {}",
+                import.module()
+            )));
+        }
+
+        match module.get_export("memory") {
+            Some(ExternType::Memory(_)) => {}
+            _ => {
+                return Err(ErrorCode::IllegalUDFFormat(
+                    "WebAssembly module must export memory",
+                ))
+            }
+        }
+        Self::check_func(&module, "alloc", &[ValType::I32], &[ValType::I32])?;
+        Self::check_func(&module, entry, &[ValType::I32, ValType::I32], &[
+            ValType::I64,
+        ])?;
+
+        Ok(WasmModule {
+            entry: entry.to_string(),
+            module,
+        })
+    }
+
+    /// Decodes and compiles the base64 encoded module.
+    pub fn try_create_from_base64(entry: &str, encoded: &str) -> Result<WasmModule> {
+        let binary = base64::decode(encoded.trim()).map_err(|cause| {
+            ErrorCode::IllegalUDFFormat(format!(
+                "WebAssembly module must be base64 encoded: {}",
+                cause
+            ))
+        })?;
+
+        Self::try_create(entry, &binary)
+    }
+
+    fn check_func(
+        module: &Module,
+        name: &str,
+        params: &[ValType],
+        results: &[ValType],
+    ) -> Result<()> {
+        match module.get_export(name) {
+            Some(ExternType::Func(func))
+                if func.params().eq(params.iter().cloned())
+                    && func.results().eq(results.iter().cloned()) =>
+            {
+                Ok(())
+            }
+            Some(_) => Err(ErrorCode::IllegalUDFFormat(format!(
+                "WebAssembly module export {} must be a function {:?} -> {:?}",
+                name, params, results
+            ))),
+            None => Err(ErrorCode::IllegalUDFFormat(format!(
+                "WebAssembly module must export {}",
+                name
+            ))),
+        }
+    }
+
+    /// Runs the entry function over the input in a new instance, so no state is kept between calls.
+    pub fn call(&self, input: &[u8], limits: &WasmLimits) -> Result<Vec<u8>> {
+        let state = WasmStoreState {
+            limits: StoreLimitsBuilder::new()
+                .memory_size(limits.max_memory)
+                .build(),
+        };
+
+        let mut store = Store::new(&WASM_ENGINE, state);
+        store.limiter(|state| &mut state.limits);
+        let ticks = limits.timeout.as_millis() / EPOCH_TICK.as_millis() + 1;
+        store.set_epoch_deadline(ticks as u64);
+
+        let instance = Instance::new(&mut store, &self.module, &[])
+            .map_err(|cause| self.runtime_error("instantiate", cause))?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| self.runtime_error("find", "memory is not exported"))?;
+        let alloc = instance
+            .get_typed_func::<i32, i32, _>(&mut store, "alloc")
+            .map_err(|cause| self.runtime_error("find", cause))?;
+        let entry = instance
+            .get_typed_func::<(i32, i32), i64, _>(&mut store, &self.entry)
+            .map_err(|cause| self.runtime_error("find", cause))?;
+
+        let input_len = i32::try_from(input.len())
+            .map_err(|_| self.runtime_error("call", "input exceeds 2GB"))?;
+        let input_ptr =
+            alloc
+                .call(&mut store, input_len)
+                .map_err(|trap| match trap.trap_code() {
+                    Some(TrapCode::Interrupt) => self.timeout_error(limits),
+                    _ => self.runtime_error("call alloc of", trap),
+                })?;
+        memory
+            .write(&mut store, input_ptr as u32 as usize, input)
+            .map_err(|cause| self.runtime_error("write input of", cause))?;
+
+        let packed = entry
+            .call(&mut store, (input_ptr, input_len))
+            .map_err(|trap| match trap.trap_code() {
+                Some(TrapCode::Interrupt) => self.timeout_error(limits),
+                _ => self.runtime_error("call", trap),
+            })? as u64;
+
+        let output_ptr = (packed >> 32) as usize;
+        let output_len = (packed & 0xFFFF_FFFF) as usize;
+        // The output is checked against the memory before allocating it, so the module can't
+        // make the host allocate more than its own memory.
+        let output_end = output_ptr.checked_add(output_len);
+        let output = match output_end.and_then(|end| memory.data(&store).get(output_ptr..end)) {
+            Some(output) => output.to_vec(),
+            None => {
+                return Err(self.runtime_error(
+                    "read output of",
+                    format!(
+                        "output of {} bytes at {} is out of the memory of {} bytes",
+                        output_len,
+                        output_ptr,
+                        memory.data_size(&store)
+                    ),
+                ));
+            }
+        };
+        Ok(output)
+    }
+
+    fn runtime_error(&self, action: &str, cause: impl std::fmt::Display) -> ErrorCode {
+        ErrorCode::UDFRuntimeError(format!(
+            "Cannot {} WebAssembly function {}: {}",
+            action, self.entry, cause
+        ))
+    }
+
+    fn timeout_error(&self, limits: &WasmLimits) -> ErrorCode {
+        ErrorCode::UDFRuntimeError(format!(
+            "WebAssembly function {} exceeded the time limit of {}ms",
+            self.entry,
+            limits.timeout.as_millis()
+        ))
+    }
+}
@@ -14,6 +14,7 @@
 
 use common_datavalues::prelude::*;
 use common_exception::Result;
+use common_functions::scalars::Collation;
 
 use super::scalar_function_test::test_scalar_functions;
 use super::scalar_function_test::ScalarFunctionTest;
@@ -167,3 +168,58 @@ fn test_not_regexp_comparison_function() -> Result<()> {
 
     test_scalar_functions("not regexp", &tests)
 }
+
+#[test]
+fn test_collation_key_function() -> Result<()> {
+    let tests = vec![
+        ScalarFunctionTest {
+            name: "general-ci-passed",
+            columns: vec![
+                Series::from_data(vec!["Straße", "ÉCOLE", "Ångström", "abc"]),
+                ConstColumn::new(Series::from_data(vec!["utf8_general_ci"]), 4).arc(),
+            ],
+            expect: Series::from_data(vec!["strasse", "ecole", "angstrom", "abc"]),
+            error: "",
+        },
+        ScalarFunctionTest {
+            name: "as-ci-passed",
+            columns: vec![
+                Series::from_data(vec!["ÉCOLE", "Abc"]),
+                ConstColumn::new(Series::from_data(vec!["utf8mb4_0900_as_ci"]), 2).arc(),
+            ],
+            expect: Series::from_data(vec!["école", "abc"]),
+            error: "",
+        },
+        ScalarFunctionTest {
+            name: "bin-passed",
+            columns: vec![
+                Series::from_data(vec!["ÉCOLE", "Abc"]),
+                ConstColumn::new(Series::from_data(vec!["utf8mb4_bin"]), 2).arc(),
+            ],
+            expect: Series::from_data(vec!["ÉCOLE", "Abc"]),
+            error: "",
+        },
+        ScalarFunctionTest {
+            name: "unknown-collation",
+            columns: vec![
+                Series::from_data(vec!["abc"]),
+                ConstColumn::new(Series::from_data(vec!["latin1_swedish_ci"]), 1).arc(),
+            ],
+            expect: Series::from_data(vec!["abc"]),
+            error: "Unknown collation: 'latin1_swedish_ci'",
+        },
+    ];
+
+    test_scalar_functions("collation_key", &tests)
+}
+
+#[test]
+fn test_collation_key_order() -> Result<()> {
+    let collation = Collation::try_from_name("utf8_general_ci")?;
+    assert_eq!(collation.key(b"Apple"), collation.key("apple".as_bytes()));
+    assert_eq!(collation.key("Éclair".as_bytes()), collation.key(b"eclair"));
+    assert!(collation.key(b"apple") < collation.key(b"Banana"));
+    assert!(collation.key(b"B") < collation.key(b"c"));
+    assert!(Collation::try_from_name("utf8_swedish_ci").is_err());
+    Ok(())
+}
@@ -0,0 +1,332 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_datavalues::prelude::*;
+use common_datavalues::ColumnWithField;
+use common_exception::Result;
+
+use crate::scalars::scalar_function_test::test_scalar_functions_with_type;
+use crate::scalars::scalar_function_test::ScalarFunctionWithFieldTest;
+
+// 2021-09-05 03:26:06 UTC, a Sunday.
+const TIMESTAMP: i64 = 1630812366000000;
+
+fn timestamp_column() -> ColumnWithField {
+    ColumnWithField::new(
+        Series::from_data(vec![TIMESTAMP]),
+        DataField::new("dummy_ts", TimestampType::new_impl(0)),
+    )
+}
+
+fn date_column(days: Vec<i32>) -> ColumnWithField {
+    ColumnWithField::new(
+        Series::from_data(days),
+        DataField::new("dummy_date", DateType::new_impl()),
+    )
+}
+
+fn string_column_n(name: &str, value: &str, n: usize) -> ColumnWithField {
+    ColumnWithField::new(
+        Series::from_data(vec![value; n]),
+        DataField::new(name, StringType::new_impl()),
+    )
+}
+
+fn const_string_column(name: &str, value: &str, n: usize) -> ColumnWithField {
+    ColumnWithField::new(
+        Arc::new(ConstColumn::new(Series::from_data(vec![value]), n)),
+        DataField::new(name, StringType::new_impl()),
+    )
+}
+
+fn string_column(name: &str, value: &str) -> ColumnWithField {
+    ColumnWithField::new(
+        Series::from_data(vec![value]),
+        DataField::new(name, StringType::new_impl()),
+    )
+}
+
+#[test]
+fn test_date_format_function() -> Result<()> {
+    let tests = vec![
+        ScalarFunctionWithFieldTest {
+            name: "date_format-datetime",
+            columns: vec![
+                timestamp_column(),
+                string_column("dummy_fmt", "%Y-%m-%d %H:%i:%s"),
+            ],
+            expect: Series::from_data(vec!["2021-09-05 03:26:06"]),
+            error: "",
+        },
+        ScalarFunctionWithFieldTest {
+            name: "date_format-names",
+            columns: vec![timestamp_column(), string_column("dummy_fmt", "%W %M %e")],
+            expect: Series::from_data(vec!["Sunday September 5"]),
+            error: "",
+        },
+        ScalarFunctionWithFieldTest {
+            name: "date_format-date",
+            columns: vec![
+                ColumnWithField::new(
+                    Series::from_data(vec![18875i32]),
+                    DataField::new("dummy_date", DateType::new_impl()),
+                ),
+                string_column("dummy_fmt", "%d/%m/%Y"),
+            ],
+            expect: Series::from_data(vec!["05/09/2021"]),
+            error: "",
+        },
+        ScalarFunctionWithFieldTest {
+            name: "date_format-week-monday-first",
+            columns: vec![
+                date_column(vec![18629, 18875, 18993]),
+                string_column_n("dummy_fmt", "%u %v %x", 3),
+            ],
+            // 2021-01-02, 2021-09-05, 2022-01-01
+            expect: Series::from_data(vec!["00 53 2020", "35 35 2021", "00 52 2021"]),
+            error: "",
+        },
+        ScalarFunctionWithFieldTest {
+            name: "date_format-week-sunday-first",
+            columns: vec![
+                date_column(vec![18629, 18875, 18993]),
+                string_column_n("dummy_fmt", "%U %V %X", 3),
+            ],
+            expect: Series::from_data(vec!["00 52 2020", "36 36 2021", "00 52 2021"]),
+            error: "",
+        },
+        ScalarFunctionWithFieldTest {
+            name: "date_format-day-suffix",
+            columns: vec![
+                // 2021-01-01, 2021-01-02, 2021-01-03, 2021-01-11, 2021-01-12, 2021-01-22
+                date_column(vec![18628, 18629, 18630, 18638, 18639, 18649]),
+                string_column_n("dummy_fmt", "%D %%D", 6),
+            ],
+            expect: Series::from_data(vec![
+                "1st %D", "2nd %D", "3rd %D", "11th %D", "12th %D", "22nd %D",
+            ]),
+            error: "",
+        },
+        ScalarFunctionWithFieldTest {
+            name: "date_format-const-format",
+            columns: vec![
+                date_column(vec![18628, 18875]),
+                const_string_column("dummy_fmt", "%d/%m/%Y", 2),
+            ],
+            expect: Series::from_data(vec!["01/01/2021", "05/09/2021"]),
+            error: "",
+        },
+        ScalarFunctionWithFieldTest {
+            name: "date_format-const-format-depends-on-date",
+            columns: vec![
+                date_column(vec![18628, 18629]),
+                const_string_column("dummy_fmt", "%D %X", 2),
+            ],
+            expect: Series::from_data(vec!["1st 2020", "2nd 2020"]),
+            error: "",
+        },
+    ];
+    test_scalar_functions_with_type("date_format", &tests)?;
+
+    let tests = vec![ScalarFunctionWithFieldTest {
+        name: "strftime",
+        columns: vec![
+            timestamp_column(),
+            string_column("dummy_fmt", "%Y/%m/%d %H:%M"),
+        ],
+        expect: Series::from_data(vec!["2021/09/05 03:26"]),
+        error: "",
+    }];
+    test_scalar_functions_with_type("strftime", &tests)
+}
+
+#[test]
+fn test_str_to_date_function() -> Result<()> {
+    let tests = vec![
+        ScalarFunctionWithFieldTest {
+            name: "str_to_date-datetime",
+            columns: vec![
+                ColumnWithField::new(
+                    Series::from_data(vec!["2022-01-02 03:04:05", "not a date"]),
+                    DataField::new("dummy_str", StringType::new_impl()),
+                ),
+                ColumnWithField::new(
+                    Series::from_data(vec!["%Y-%m-%d %H:%i:%s", "%Y-%m-%d %H:%i:%s"]),
+                    DataField::new("dummy_fmt", StringType::new_impl()),
+                ),
+            ],
+            expect: Series::from_data(vec![Some(1641092645000000i64), None]),
+            error: "",
+        },
+        ScalarFunctionWithFieldTest {
+            name: "str_to_date-date",
+            columns: vec![
+                string_column("dummy_str", "05/09/2021"),
+                string_column("dummy_fmt", "%d/%m/%Y"),
+            ],
+            expect: Series::from_data(vec![Some(1630800000000000i64)]),
+            error: "",
+        },
+    ];
+    test_scalar_functions_with_type("str_to_date", &tests)?;
+
+    let tests = vec![
+        ScalarFunctionWithFieldTest {
+            name: "to_timestamp-format",
+            columns: vec![
+                string_column("dummy_str", "2022/01/02 03:04:05"),
+                string_column("dummy_fmt", "%Y/%m/%d %H:%i:%s"),
+            ],
+            expect: Series::from_data(vec![1641092645000000i64]),
+            error: "",
+        },
+        ScalarFunctionWithFieldTest {
+            name: "to_timestamp-format-error",
+            columns: vec![
+                string_column("dummy_str", "2022-01-02"),
+                string_column("dummy_fmt", "%Y/%m/%d"),
+            ],
+            expect: Series::from_data(vec![0i64]),
+            error: "Unable to parse '2022-01-02' with format '%Y/%m/%d' in function to_timestamp",
+        },
+        ScalarFunctionWithFieldTest {
+            name: "to_timestamp-unsupported-specifier",
+            columns: vec![
+                string_column("dummy_str", "2022 01"),
+                string_column("dummy_fmt", "%X %V"),
+            ],
+            expect: Series::from_data(vec![0i64]),
+            error: "Unsupported format specifier '%X' of function to_timestamp",
+        },
+    ];
+    test_scalar_functions_with_type("to_timestamp", &tests)
+}
+
+#[test]
+fn test_date_trunc_function() -> Result<()> {
+    let units = vec![
+        ("year", 1609459200000000i64),
+        ("quarter", 1625097600000000),
+        ("month", 1630454400000000),
+        ("week", 1630281600000000),
+        ("day", 1630800000000000),
+        ("hour", 1630810800000000),
+        ("minute", 1630812360000000),
+        ("second", TIMESTAMP),
+    ];
+
+    for (unit, expect) in units {
+        test_scalar_functions_with_type("date_trunc", &[ScalarFunctionWithFieldTest {
+            name: "date_trunc",
+            columns: vec![string_column("dummy_unit", unit), timestamp_column()],
+            expect: Series::from_data(vec![expect]),
+            error: "",
+        }])?;
+    }
+
+    test_scalar_functions_with_type("date_trunc", &[ScalarFunctionWithFieldTest {
+        name: "date_trunc-date",
+        columns: vec![
+            string_column("dummy_unit", "MONTH"),
+            ColumnWithField::new(
+                Series::from_data(vec![18875i32]),
+                DataField::new("dummy_date", DateType::new_impl()),
+            ),
+        ],
+        expect: Series::from_data(vec![18871i32]),
+        error: "",
+    }])?;
+
+    test_scalar_functions_with_type("date_trunc", &[ScalarFunctionWithFieldTest {
+        name: "date_trunc-bad-unit",
+        columns: vec![string_column("dummy_unit", "decade"), timestamp_column()],
+        expect: Series::from_data(vec![0i64]),
+        error: "Unsupported date unit 'decade' of function date_trunc, expected one of year, quarter, month, week, day, hour, minute, second",
+    }])
+}
+
+#[test]
+fn test_date_diff_function() -> Result<()> {
+    // 2022-01-02 03:04:05 UTC
+    let end = ColumnWithField::new(
+        Series::from_data(vec![1641092645000000i64]),
+        DataField::new("dummy_end", TimestampType::new_impl(0)),
+    );
+
+    let units = vec![
+        ("year", 1i64),
+        ("quarter", 2),
+        ("month", 4),
+        ("week", 17),
+        ("day", 119),
+        ("hour", 2856),
+        ("minute", 171338),
+        ("second", 10280279),
+    ];
+
+    for (unit, expect) in units {
+        test_scalar_functions_with_type("date_diff", &[ScalarFunctionWithFieldTest {
+            name: "date_diff",
+            columns: vec![
+                string_column("dummy_unit", unit),
+                timestamp_column(),
+                end.clone(),
+            ],
+            expect: Series::from_data(vec![expect]),
+            error: "",
+        }])?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_convert_tz_function() -> Result<()> {
+    let tests = vec![
+        ScalarFunctionWithFieldTest {
+            name: "convert_tz",
+            columns: vec![
+                timestamp_column(),
+                string_column("dummy_from", "UTC"),
+                string_column("dummy_to", "Asia/Shanghai"),
+            ],
+            // 2021-09-05 11:26:06
+            expect: Series::from_data(vec![1630841166000000i64]),
+            error: "",
+        },
+        ScalarFunctionWithFieldTest {
+            name: "convert_tz-from-session-timezone",
+            columns: vec![
+                timestamp_column(),
+                string_column("dummy_to", "Asia/Shanghai"),
+            ],
+            // 2021-09-05 11:26:06
+            expect: Series::from_data(vec![1630841166000000i64]),
+            error: "",
+        },
+        ScalarFunctionWithFieldTest {
+            name: "convert_tz-bad-timezone",
+            columns: vec![
+                timestamp_column(),
+                string_column("dummy_from", "UTC"),
+                string_column("dummy_to", "Mars/Olympus"),
+            ],
+            expect: Series::from_data(vec![0i64]),
+            error: "Invalid timezone 'Mars/Olympus' in function convert_tz",
+        },
+    ];
+    test_scalar_functions_with_type("convert_tz", &tests)
+}
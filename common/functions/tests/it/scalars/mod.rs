@@ -29,3 +29,4 @@ mod strings;
 mod tuples;
 mod udfs;
 mod uuids;
+mod wasm;
@@ -0,0 +1,277 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use common_datavalues::prelude::*;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_functions::scalars::FunctionFactory;
+use common_functions::scalars::WasmFunction;
+use common_functions::scalars::WasmLimits;
+use common_functions::scalars::WasmModule;
+
+use crate::scalars::scalar_function_test::test_eval;
+use crate::scalars::scalar_function_test::test_scalar_functions;
+use crate::scalars::scalar_function_test::ScalarFunctionTest;
+
+// Returns the input as the output, so the result is the first argument.
+const ECHO_MODULE: &str = r#"
+(module
+  (memory (export "memory") 1)
+  (global $heap (mut i32) (i32.const 1024))
+  (func (export "alloc") (param $len i32) (result i32)
+    (local $ptr i32)
+    (local.set $ptr (global.get $heap))
+    (global.set $heap (i32.add (local.get $ptr) (local.get $len)))
+    (if (i32.gt_u (global.get $heap) (i32.mul (memory.size) (i32.const 65536)))
+      (then
+        (if (i32.lt_s
+              (memory.grow (i32.sub
+                (i32.add (i32.shr_u (global.get $heap) (i32.const 16)) (i32.const 1))
+                (memory.size)))
+              (i32.const 0))
+          (then unreachable))))
+    (local.get $ptr))
+  (func (export "echo") (param $ptr i32) (param $len i32) (result i64)
+    (i64.or
+      (i64.shl (i64.extend_i32_u (local.get $ptr)) (i64.const 32))
+      (i64.extend_i32_u (local.get $len)))))
+"#;
+
+const LOOP_MODULE: &str = r#"
+(module
+  (memory (export "memory") 1)
+  (func (export "alloc") (param $len i32) (result i32)
+    (i32.const 1024))
+  (func (export "spin") (param $ptr i32) (param $len i32) (result i64)
+    (loop $forever (br $forever))
+    (i64.const 0)))
+"#;
+
+// Returns an output of 4GB - 1 bytes, far beyond its memory of one page.
+const OVERSIZED_OUTPUT_MODULE: &str = r#"
+(module
+  (memory (export "memory") 1)
+  (func (export "alloc") (param $len i32) (result i32)
+    (i32.const 1024))
+  (func (export "oversized") (param $ptr i32) (param $len i32) (result i64)
+    (i64.const 0xFFFFFFFF)))
+"#;
+
+fn encode(wat: &str) -> String {
+    base64::encode(wat::parse_str(wat).unwrap())
+}
+
+fn register(name: &str, wat: &str, types: &[DataTypeImpl], limits: &WasmLimits) -> Result<String> {
+    let return_type = types[0].clone();
+    WasmFunction::register(
+        "test_tenant",
+        name,
+        types,
+        &return_type,
+        &encode(wat),
+        limits,
+    )
+}
+
+#[test]
+fn test_wasm_function() -> Result<()> {
+    let limits = WasmLimits::default();
+
+    let op = register("echo", ECHO_MODULE, &[i64::to_data_type()], &limits)?;
+    let tests = vec![
+        ScalarFunctionTest {
+            name: "echo-int64-passed",
+            columns: vec![Series::from_data(vec![1i64, 2, 3])],
+            expect: Series::from_data(vec![1i64, 2, 3]),
+            error: "",
+        },
+        ScalarFunctionTest {
+            name: "echo-nullable-int64-passed",
+            columns: vec![Series::from_data(vec![Some(1i64), None, Some(3)])],
+            expect: Series::from_data(vec![Some(1i64), None, Some(3)]),
+            error: "",
+        },
+    ];
+    test_scalar_functions(&op, &tests)?;
+
+    let op = register("echo", ECHO_MODULE, &[StringType::new_impl()], &limits)?;
+    let tests = vec![ScalarFunctionTest {
+        name: "echo-string-passed",
+        columns: vec![Series::from_data(vec!["Mozilla/5.0", ""])],
+        expect: Series::from_data(vec!["Mozilla/5.0", ""]),
+        error: "",
+    }];
+    test_scalar_functions(&op, &tests)
+}
+
+#[test]
+fn test_wasm_function_output_type() -> Result<()> {
+    let limits = WasmLimits::default();
+    let op = WasmFunction::register(
+        "test_tenant",
+        "echo",
+        &[i64::to_data_type()],
+        &StringType::new_impl(),
+        &encode(ECHO_MODULE),
+        &limits,
+    )?;
+
+    let result = test_eval(&op, &[Series::from_data(vec![1i64, 2, 3])]);
+    assert_eq!(
+        result.unwrap_err().code(),
+        ErrorCode::UDFRuntimeError("").code()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_wasm_function_limits() -> Result<()> {
+    let limits = WasmLimits {
+        max_memory: 64 * 1024 * 1024,
+        timeout: Duration::from_millis(50),
+    };
+    let op = register("spin", LOOP_MODULE, &[i64::to_data_type()], &limits)?;
+    let result = test_eval(&op, &[Series::from_data(vec![1i64])]);
+    let cause = result.unwrap_err();
+    assert_eq!(cause.code(), ErrorCode::UDFRuntimeError("").code());
+    assert_eq!(
+        cause.message(),
+        "WebAssembly function spin exceeded the time limit of 50ms"
+    );
+
+    // The initial memory of one page exceeds the limit.
+    let limits = WasmLimits {
+        max_memory: 1024,
+        timeout: Duration::from_secs(10),
+    };
+    let op = register("echo", ECHO_MODULE, &[i64::to_data_type()], &limits)?;
+    let result = test_eval(&op, &[Series::from_data(vec![1i64])]);
+    assert_eq!(
+        result.unwrap_err().code(),
+        ErrorCode::UDFRuntimeError("").code()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_wasm_function_output_out_of_memory() -> Result<()> {
+    let limits = WasmLimits::default();
+    let op = register(
+        "oversized",
+        OVERSIZED_OUTPUT_MODULE,
+        &[i64::to_data_type()],
+        &limits,
+    )?;
+    let result = test_eval(&op, &[Series::from_data(vec![1i64])]);
+    let cause = result.unwrap_err();
+    assert_eq!(cause.code(), ErrorCode::UDFRuntimeError("").code());
+    assert_eq!(
+        cause.message(),
+        "Cannot read output of WebAssembly function oversized: output of 4294967295 bytes at 0 \
+         is out of the memory of 65536 bytes"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_wasm_module_validation() -> Result<()> {
+    let illegal_format = ErrorCode::IllegalUDFFormat("").code();
+
+    let result = WasmModule::try_create_from_base64("echo", "not base64!");
+    assert_eq!(result.err().unwrap().code(), illegal_format);
+
+    let result = WasmModule::try_create_from_base64("unknown", &encode(ECHO_MODULE));
+    assert_eq!(
+        result.err().unwrap().message(),
+        "WebAssembly module must export unknown"
+    );
+
+    let result = WasmModule::try_create_from_base64(
+        "echo",
+        &encode(r#"(module (import "env" "f" (func)) (memory (export "memory") 1))"#),
+    );
+    assert_eq!(
+        result.err().unwrap().message(),
+        "WebAssembly module must not import anything, but it imports from env"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_wasm_function_registry() -> Result<()> {
+    let factory = FunctionFactory::instance();
+    let limits = WasmLimits::default();
+    let types = [i64::to_data_type()];
+
+    let op = register("registry_echo", ECHO_MODULE, &types, &limits)?;
+    assert!(factory.check(&op));
+    assert_eq!(op, register("registry_echo", ECHO_MODULE, &types, &limits)?);
+
+    // The function is registered again when the limits change.
+    let other_limits = WasmLimits {
+        max_memory: 1024 * 1024,
+        ..limits
+    };
+    let other_op = register("registry_echo", ECHO_MODULE, &types, &other_limits)?;
+    assert_ne!(op, other_op);
+
+    // The other nodes of the cluster register the function with the limits in the name.
+    assert_eq!(
+        WasmFunction::parse_registered_name("test_tenant", &other_op),
+        Some(("registry_echo".to_string(), other_limits))
+    );
+    assert_eq!(
+        WasmFunction::parse_registered_name("other_tenant", &other_op),
+        None
+    );
+    assert_eq!(
+        WasmFunction::parse_registered_name("test_tenant", "to_string"),
+        None
+    );
+
+    // The functions are only visible to the tenant.
+    assert!(WasmFunction::check_tenant("test_tenant", &op).is_ok());
+    assert!(WasmFunction::check_tenant("test_tenant", "to_string").is_ok());
+    assert_eq!(
+        WasmFunction::check_tenant("other_tenant", &op)
+            .unwrap_err()
+            .code(),
+        ErrorCode::UnknownFunction("").code()
+    );
+    assert!(WasmFunction::check_tenant("test", &op.replacen("test_tenant", "test::x", 1)).is_err());
+
+    WasmFunction::unregister("test_tenant", "registry_echo");
+    assert!(!factory.check(&op));
+    assert!(!factory.check(&other_op));
+    Ok(())
+}
+
+#[test]
+fn test_wasm_function_eviction() -> Result<()> {
+    let factory = FunctionFactory::instance();
+    let limits = WasmLimits::default();
+    let types = [i64::to_data_type()];
+
+    // The functions used recently are kept.
+    let op = register("eviction_echo", ECHO_MODULE, &types, &limits)?;
+    factory.evict_dynamic(Duration::from_secs(60));
+    assert!(factory.check(&op));
+
+    WasmFunction::unregister("test_tenant", "eviction_echo");
+    assert!(!factory.check(&op));
+    Ok(())
+}
@@ -24,12 +24,12 @@ pub trait UdfApi: Sync + Send {
     // Update a UDF to /tenant/udf-name.
     async fn update_udf(&self, udf: UserDefinedFunction, seq: Option<u64>) -> Result<u64>;
 
-    // Get UDF by name.
+    // Get UDF by name, typed UDFs are got by signature, like `add_one(Int32)`.
     async fn get_udf(&self, udf_name: &str, seq: Option<u64>) -> Result<SeqV<UserDefinedFunction>>;
 
     // Get all the UDFs for a tenant.
     async fn get_udfs(&self) -> Result<Vec<UserDefinedFunction>>;
 
-    // Drop the tenant's UDF by name.
+    // Drop the tenant's UDF by name, typed UDFs are dropped by signature.
     async fn drop_udf(&self, udf_name: &str, seq: Option<u64>) -> Result<()>;
 }
@@ -63,12 +63,15 @@ impl UdfApi for UdfMgr {
             )));
         }
 
-        let mut udf_parser = UDFParser::default();
-        udf_parser.parse(&info.name, &info.parameters, &info.definition)?;
+        // The query body of a table-valued function and WebAssembly modules are checked by the query planner.
+        if !info.is_table_function() && !info.is_wasm() {
+            let mut udf_parser = UDFParser::default();
+            udf_parser.parse(&info.name, &info.parameters, &info.definition)?;
+        }
 
         let seq = MatchSeq::Exact(0);
         let val = Operation::Update(serde_json::to_vec(&info)?);
-        let key = format!("{}/{}", self.udf_prefix, escape_for_key(&info.name)?);
+        let key = format!("{}/{}", self.udf_prefix, escape_for_key(&info.signature())?);
         let upsert_info = self
             .kv_api
             .upsert_kv(UpsertKVAction::new(&key, seq, val, None));
@@ -93,10 +96,11 @@ impl UdfApi for UdfMgr {
         }
 
         // Check if UDF is defined
-        let _ = self.get_udf(info.name.as_str(), seq).await?;
+        let signature = info.signature();
+        let _ = self.get_udf(signature.as_str(), seq).await?;
 
         let val = Operation::Update(serde_json::to_vec(&info)?);
-        let key = format!("{}/{}", self.udf_prefix, escape_for_key(&info.name)?);
+        let key = format!("{}/{}", self.udf_prefix, escape_for_key(&signature)?);
         let upsert_info =
             self.kv_api
                 .upsert_kv(UpsertKVAction::new(&key, MatchSeq::from(seq), val, None));
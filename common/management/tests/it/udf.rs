@@ -20,6 +20,7 @@ use common_management::*;
 use common_meta_api::KVApi;
 use common_meta_embedded::MetaEmbedded;
 use common_meta_types::SeqV;
+use common_meta_types::UDFReturnType;
 use common_meta_types::UserDefinedFunction;
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
@@ -103,6 +104,38 @@ async fn test_unknown_udf_drop_udf() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_overloaded_typed_udfs() -> Result<()> {
+    let (kv_api, udf_api) = new_udf_api().await?;
+
+    let int_udf = create_typed_test_udf("Int32");
+    let string_udf = create_typed_test_udf("String");
+    udf_api.add_udf(int_udf.clone()).await?;
+    udf_api.add_udf(string_udf.clone()).await?;
+
+    let value = kv_api.get_kv("__fd_udfs/admin/add_one%28Int32%29").await?;
+    assert!(value.is_some());
+
+    let udf = udf_api.get_udf("add_one(String)", None).await?;
+    assert_eq!(udf.data, string_udf);
+
+    udf_api.drop_udf(&int_udf.signature(), None).await?;
+    let udfs = udf_api.get_udfs().await?;
+    assert_eq!(udfs, vec![string_udf]);
+    Ok(())
+}
+
+fn create_typed_test_udf(parameter_type: &str) -> UserDefinedFunction {
+    UserDefinedFunction::new_typed(
+        "add_one",
+        vec!["a".to_string()],
+        vec![parameter_type.to_string()],
+        UDFReturnType::Scalar("String".to_string()),
+        "concat(to_varchar(a), '1')",
+        "",
+    )
+}
+
 fn create_test_udf() -> UserDefinedFunction {
     UserDefinedFunction::new(
         "isnotempty",
@@ -181,6 +181,8 @@ pub use table::UpsertTableOptionReq;
 pub use user_auth::AuthInfo;
 pub use user_auth::AuthType;
 pub use user_auth::PasswordHashMethod;
+pub use user_defined_function::UDFLanguage;
+pub use user_defined_function::UDFReturnType;
 pub use user_defined_function::UserDefinedFunction;
 pub use user_grant::GrantEntry;
 pub use user_grant::GrantObject;
@@ -19,11 +19,54 @@ use common_exception::Result;
 use serde::Deserialize;
 use serde::Serialize;
 
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub enum UDFReturnType {
+    /// `CREATE FUNCTION f AS (p) -> <expr>`, parameters and result are untyped.
+    Untyped,
+    /// `CREATE FUNCTION f(p <type>) RETURNS <type> AS <expr>`
+    Scalar(String),
+    /// `CREATE FUNCTION f(p <type>) RETURNS TABLE(<column> <type>, ...) AS <query>`
+    Table(Vec<(String, String)>),
+}
+
+impl Default for UDFReturnType {
+    fn default() -> Self {
+        UDFReturnType::Untyped
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum UDFLanguage {
+    /// The definition is a SQL expression or query.
+    Sql,
+    /// The definition is a base64 encoded WebAssembly module.
+    Wasm,
+}
+
+impl Default for UDFLanguage {
+    fn default() -> Self {
+        UDFLanguage::Sql
+    }
+}
+
+impl std::fmt::Display for UDFLanguage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UDFLanguage::Sql => write!(f, "SQL"),
+            UDFLanguage::Wasm => write!(f, "WASM"),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Default)]
 #[serde(default)]
 pub struct UserDefinedFunction {
     pub name: String,
     pub parameters: Vec<String>,
+    /// Data type names of the parameters, empty if the function is untyped.
+    pub parameter_types: Vec<String>,
+    pub return_type: UDFReturnType,
+    pub language: UDFLanguage,
 
     pub description: String,
     pub definition: String,
@@ -34,10 +77,63 @@ impl UserDefinedFunction {
         Self {
             name: name.to_string(),
             parameters,
+            parameter_types: vec![],
+            return_type: UDFReturnType::Untyped,
+            language: UDFLanguage::Sql,
             definition: definition.to_string(),
             description: description.to_string(),
         }
     }
+
+    pub fn new_typed(
+        name: &str,
+        parameters: Vec<String>,
+        parameter_types: Vec<String>,
+        return_type: UDFReturnType,
+        definition: &str,
+        description: &str,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            parameters,
+            parameter_types,
+            return_type,
+            language: UDFLanguage::Sql,
+            definition: definition.to_string(),
+            description: description.to_string(),
+        }
+    }
+
+    pub fn with_language(mut self, language: UDFLanguage) -> Self {
+        self.language = language;
+        self
+    }
+
+    pub fn is_wasm(&self) -> bool {
+        self.language == UDFLanguage::Wasm
+    }
+
+    pub fn is_typed(&self) -> bool {
+        !matches!(self.return_type, UDFReturnType::Untyped)
+    }
+
+    pub fn is_table_function(&self) -> bool {
+        matches!(self.return_type, UDFReturnType::Table(_))
+    }
+
+    /// The identity of the function, typed functions are overloaded by their parameter types,
+    /// e.g. `add_one(Int32)` and `add_one(String)`, while untyped functions are identified by name.
+    pub fn signature(&self) -> String {
+        if self.is_typed() {
+            Self::typed_signature(&self.name, &self.parameter_types)
+        } else {
+            self.name.clone()
+        }
+    }
+
+    pub fn typed_signature(name: &str, parameter_types: &[String]) -> String {
+        format!("{}({})", name, parameter_types.join(", "))
+    }
 }
 
 impl TryFrom<Vec<u8>> for UserDefinedFunction {
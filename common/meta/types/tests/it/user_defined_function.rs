@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use common_exception::exception::Result;
+use common_meta_types::UDFReturnType;
 use common_meta_types::UserDefinedFunction;
 
 #[test]
@@ -30,3 +31,30 @@ fn test_udf() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_typed_udf() -> Result<()> {
+    let udf = UserDefinedFunction::new_typed(
+        "add_one",
+        vec!["a".to_string()],
+        vec!["Int32".to_string()],
+        UDFReturnType::Scalar("Int64".to_string()),
+        "a + 1",
+        "",
+    );
+    assert!(udf.is_typed());
+    assert!(!udf.is_table_function());
+    assert_eq!(udf.signature(), "add_one(Int32)");
+
+    let ser = serde_json::to_string(&udf)?;
+    let de = UserDefinedFunction::try_from(ser.into_bytes())?;
+    assert_eq!(udf, de);
+
+    // Functions stored before the types were introduced are untyped.
+    let legacy = r#"{"name":"is_not_null","parameters":["p"],"description":"","definition":"not(is_null(p))"}"#;
+    let de = UserDefinedFunction::try_from(legacy.as_bytes().to_vec())?;
+    assert!(!de.is_typed());
+    assert_eq!(de.signature(), "is_not_null");
+
+    Ok(())
+}
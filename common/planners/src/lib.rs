@@ -42,6 +42,8 @@ mod plan_kill;
 mod plan_limit;
 mod plan_limit_by;
 mod plan_list;
+mod plan_materialized_view_create;
+mod plan_materialized_view_refresh;
 mod plan_node;
 mod plan_node_builder;
 mod plan_node_display;
@@ -158,6 +160,8 @@ pub use plan_kill::KillPlan;
 pub use plan_limit::LimitPlan;
 pub use plan_limit_by::LimitByPlan;
 pub use plan_list::ListPlan;
+pub use plan_materialized_view_create::CreateMaterializedViewPlan;
+pub use plan_materialized_view_refresh::RefreshMaterializedViewPlan;
 pub use plan_node::PlanNode;
 pub use plan_node_builder::PlanBuilder;
 pub use plan_node_extras::Extras;
@@ -25,6 +25,7 @@ use crate::BroadcastPlan;
 use crate::CallPlan;
 use crate::CopyPlan;
 use crate::CreateDatabasePlan;
+use crate::CreateMaterializedViewPlan;
 use crate::CreateRolePlan;
 use crate::CreateTablePlan;
 use crate::CreateUserPlan;
@@ -55,6 +56,7 @@ use crate::ListPlan;
 use crate::OptimizeTablePlan;
 use crate::ProjectionPlan;
 use crate::ReadDataSourcePlan;
+use crate::RefreshMaterializedViewPlan;
 use crate::RemotePlan;
 use crate::RenameDatabasePlan;
 use crate::RenameTablePlan;
@@ -134,6 +136,10 @@ pub enum PlanNode {
     DropView(DropViewPlan),
     AlterView(AlterViewPlan),
 
+    // Materialized view.
+    CreateMaterializedView(CreateMaterializedViewPlan),
+    RefreshMaterializedView(RefreshMaterializedViewPlan),
+
     // User.
     CreateUser(CreateUserPlan),
     AlterUser(AlterUserPlan),
@@ -231,6 +237,10 @@ impl PlanNode {
             PlanNode::AlterView(v) => v.schema(),
             PlanNode::DropView(v) => v.schema(),
 
+            // Materialized view.
+            PlanNode::CreateMaterializedView(v) => v.schema(),
+            PlanNode::RefreshMaterializedView(v) => v.schema(),
+
             // User.
             PlanNode::CreateUser(v) => v.schema(),
             PlanNode::AlterUser(v) => v.schema(),
@@ -330,6 +340,10 @@ impl PlanNode {
             PlanNode::AlterView(_) => "AlterViewPlan",
             PlanNode::DropView(_) => "DropViewPlan",
 
+            // Materialized view.
+            PlanNode::CreateMaterializedView(_) => "CreateMaterializedViewPlan",
+            PlanNode::RefreshMaterializedView(_) => "RefreshMaterializedViewPlan",
+
             // User.
             PlanNode::CreateUser(_) => "CreateUser",
             PlanNode::AlterUser(_) => "AlterUser",
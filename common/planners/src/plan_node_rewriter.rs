@@ -31,6 +31,7 @@ use crate::AlterViewPlan;
 use crate::CallPlan;
 use crate::CopyPlan;
 use crate::CreateDatabasePlan;
+use crate::CreateMaterializedViewPlan;
 use crate::CreateRolePlan;
 use crate::CreateTablePlan;
 use crate::CreateUserPlan;
@@ -66,6 +67,7 @@ use crate::PlanBuilder;
 use crate::PlanNode;
 use crate::ProjectionPlan;
 use crate::ReadDataSourcePlan;
+use crate::RefreshMaterializedViewPlan;
 use crate::RemotePlan;
 use crate::RenameDatabasePlan;
 use crate::RenameTablePlan;
@@ -160,6 +162,10 @@ pub trait PlanRewriter: Sized {
             PlanNode::AlterView(plan) => self.rewrite_alter_view(plan),
             PlanNode::DropView(plan) => self.rewrite_drop_view(plan),
 
+            // Materialized view.
+            PlanNode::CreateMaterializedView(plan) => self.rewrite_create_materialized_view(plan),
+            PlanNode::RefreshMaterializedView(plan) => self.rewrite_refresh_materialized_view(plan),
+
             // User.
             PlanNode::CreateUser(plan) => self.create_user(plan),
             PlanNode::AlterUser(plan) => self.alter_user(plan),
@@ -377,6 +383,20 @@ pub trait PlanRewriter: Sized {
         Ok(PlanNode::AlterView(plan.clone()))
     }
 
+    fn rewrite_create_materialized_view(
+        &mut self,
+        plan: &CreateMaterializedViewPlan,
+    ) -> Result<PlanNode> {
+        Ok(PlanNode::CreateMaterializedView(plan.clone()))
+    }
+
+    fn rewrite_refresh_materialized_view(
+        &mut self,
+        plan: &RefreshMaterializedViewPlan,
+    ) -> Result<PlanNode> {
+        Ok(PlanNode::RefreshMaterializedView(plan.clone()))
+    }
+
     fn rewrite_create_database(&mut self, plan: &CreateDatabasePlan) -> Result<PlanNode> {
         Ok(PlanNode::CreateDatabase(plan.clone()))
     }
@@ -24,6 +24,7 @@ use crate::AlterViewPlan;
 use crate::CallPlan;
 use crate::CopyPlan;
 use crate::CreateDatabasePlan;
+use crate::CreateMaterializedViewPlan;
 use crate::CreateRolePlan;
 use crate::CreateTablePlan;
 use crate::CreateUserPlan;
@@ -56,6 +57,7 @@ use crate::OptimizeTablePlan;
 use crate::PlanNode;
 use crate::ProjectionPlan;
 use crate::ReadDataSourcePlan;
+use crate::RefreshMaterializedViewPlan;
 use crate::RemotePlan;
 use crate::RenameDatabasePlan;
 use crate::RenameTablePlan;
@@ -172,6 +174,10 @@ pub trait PlanVisitor {
             PlanNode::AlterView(v) => self.visit_alter_view(v),
             PlanNode::DropView(v) => self.visit_drop_view(v),
 
+            // Materialized view.
+            PlanNode::CreateMaterializedView(v) => self.visit_create_materialized_view(v),
+            PlanNode::RefreshMaterializedView(v) => self.visit_refresh_materialized_view(v),
+
             // User.
             PlanNode::CreateUser(plan) => self.visit_create_user(plan),
             PlanNode::AlterUser(plan) => self.visit_alter_user(plan),
@@ -431,6 +437,14 @@ pub trait PlanVisitor {
         Ok(())
     }
 
+    fn visit_create_materialized_view(&mut self, _: &CreateMaterializedViewPlan) -> Result<()> {
+        Ok(())
+    }
+
+    fn visit_refresh_materialized_view(&mut self, _: &RefreshMaterializedViewPlan) -> Result<()> {
+        Ok(())
+    }
+
     fn visit_kill_query(&mut self, _: &KillPlan) -> Result<()> {
         Ok(())
     }
@@ -21,6 +21,8 @@ use common_datavalues::DataSchemaRef;
 pub struct DropUserUDFPlan {
     pub if_exists: bool,
     pub name: String,
+    // Drop only the overload with these parameter types, otherwise all the overloads.
+    pub parameter_types: Option<Vec<String>>,
 }
 
 impl DropUserUDFPlan {
@@ -21,6 +21,7 @@ pub use rpc::DatabendQueryFlightDispatcher;
 pub use rpc::DatabendQueryFlightService;
 pub use rpc::FlightAction;
 pub use rpc::FlightClient;
+pub use rpc::FlightScatter;
 pub use rpc::FlightTicket;
 pub use rpc::ShuffleAction;
 pub use rpc::StreamTicket;
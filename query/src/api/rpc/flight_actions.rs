@@ -28,6 +28,9 @@ pub struct ShuffleAction {
     pub plan: PlanNode,
     pub sinks: Vec<String>,
     pub scatters_expression: Expression,
+    /// Runs the plan on the new processor framework.
+    #[serde(default)]
+    pub new_processor_framework: bool,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
@@ -36,6 +39,9 @@ pub struct BroadcastAction {
     pub stage_id: String,
     pub plan: PlanNode,
     pub sinks: Vec<String>,
+    /// Runs the plan on the new processor framework.
+    #[serde(default)]
+    pub new_processor_framework: bool,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
@@ -155,6 +161,14 @@ impl FlightAction {
         }
     }
 
+    pub fn is_new_processor_framework(&self) -> bool {
+        match self {
+            FlightAction::BroadcastAction(action) => action.new_processor_framework,
+            FlightAction::PrepareShuffleAction(action) => action.new_processor_framework,
+            _ => unimplemented!(),
+        }
+    }
+
     pub fn get_scatter_expression(&self) -> Option<Expression> {
         match self {
             FlightAction::BroadcastAction(_) => None,
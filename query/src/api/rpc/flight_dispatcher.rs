@@ -19,6 +19,7 @@ use std::sync::Arc;
 
 use common_base::base::tokio::sync::mpsc::Sender;
 use common_base::base::tokio::sync::*;
+use common_base::base::Thread;
 use common_base::base::TrySpawn;
 use common_base::infallible::RwLock;
 use common_datablocks::DataBlock;
@@ -26,6 +27,7 @@ use common_datavalues::DataSchemaRef;
 use common_exception::ErrorCode;
 use common_exception::Result;
 use common_exception::ToErrorCode;
+use common_planners::SelectPlan;
 use common_tracing::tracing;
 use common_tracing::tracing::Instrument;
 use common_tracing::tracing::Span;
@@ -36,9 +38,15 @@ use crate::api::rpc::flight_scatter_broadcast::BroadcastFlightScatter;
 use crate::api::rpc::flight_scatter_hash::HashFlightScatter;
 use crate::api::rpc::flight_tickets::StreamTicket;
 use crate::api::FlightAction;
+use crate::pipelines::new::executor::PipelineCompleteExecutor;
+use crate::pipelines::new::processors::port::InputPort;
+use crate::pipelines::new::processors::ExchangeSink;
+use crate::pipelines::new::QueryPipelineBuilder;
+use crate::pipelines::new::SinkPipeBuilder;
 use crate::pipelines::processors::PipelineBuilder;
 use crate::sessions::QueryContext;
 use crate::sessions::SessionRef;
+use crate::sql::statements::register_plan_wasm_functions;
 
 struct StreamInfo {
     #[allow(unused)]
@@ -100,6 +108,10 @@ impl DatabendQueryFlightDispatcher {
 
         match action.get_sinks().len() {
             0 => Err(ErrorCode::LogicalError("")),
+            _ if action.is_new_processor_framework() => {
+                self.action_with_exchange::<BroadcastFlightScatter>(session, &action)
+                    .await
+            }
             1 => self.one_sink_action(session, &action).await,
             _ => {
                 self.action_with_scatter::<BroadcastFlightScatter>(session, &action)
@@ -118,6 +130,14 @@ impl DatabendQueryFlightDispatcher {
 
         match action.get_sinks().len() {
             0 => Err(ErrorCode::LogicalError("")),
+            1 if action.is_new_processor_framework() => {
+                self.action_with_exchange::<BroadcastFlightScatter>(session, &action)
+                    .await
+            }
+            _ if action.is_new_processor_framework() => {
+                self.action_with_exchange::<HashFlightScatter>(session, &action)
+                    .await
+            }
             1 => self.one_sink_action(session, &action).await,
             _ => {
                 self.action_with_scatter::<HashFlightScatter>(session, &action)
@@ -134,6 +154,7 @@ impl DatabendQueryFlightDispatcher {
 
         let query_plan = action.get_plan();
         action_context.attach_query_plan(&query_plan);
+        register_plan_wasm_functions(&action_context, &query_plan).await?;
         let mut pipeline = pipeline_builder.build(&query_plan)?;
 
         let action_sinks = action.get_sinks();
@@ -190,32 +211,14 @@ impl DatabendQueryFlightDispatcher {
 
         let query_plan = action.get_plan();
         action_context.attach_query_plan(&query_plan);
+        register_plan_wasm_functions(&action_context, &query_plan).await?;
         let mut pipeline = pipeline_builder.build(&query_plan)?;
 
         let action_query_id = action.get_query_id();
         let action_stage_id = action.get_stage_id();
 
-        let sinks_tx = {
-            let action_sinks = action.get_sinks();
-
-            assert!(action_sinks.len() > 1);
-            let mut sinks_tx = Vec::with_capacity(action_sinks.len());
-
-            for sink in &action_sinks {
-                let stream_name = format!("{}/{}/{}", action_query_id, action_stage_id, sink);
-                match self.streams.read().get(&stream_name) {
-                    Some(stream) => sinks_tx.push(stream.tx.clone()),
-                    None => {
-                        return Err(ErrorCode::NotFoundStream(format!(
-                            "Not found stream {}",
-                            stream_name
-                        )))
-                    }
-                }
-            }
-
-            Result::Ok(sinks_tx)
-        }?;
+        assert!(action.get_sinks().len() > 1);
+        let sinks_tx = self.get_sinks_tx(action)?;
 
         let stage_name = format!("{}/{}", action_query_id, action_stage_id);
         let stages_notify = self.stages_notify.clone();
@@ -268,6 +271,104 @@ impl DatabendQueryFlightDispatcher {
         Ok(())
     }
 
+    /// Runs the plan of the action on the new processor framework, the blocks are sent
+    /// to the stage streams by the exchange sinks.
+    #[tracing::instrument(level = "debug", skip_all, fields(session.id = session.get_id().as_str()))]
+    async fn action_with_exchange<T>(
+        &self,
+        session: SessionRef,
+        action: &FlightAction,
+    ) -> Result<()>
+    where
+        T: FlightScatter + Send + 'static,
+    {
+        let query_context = session.create_query_context().await?;
+        let action_context = QueryContext::create_from(query_context.clone());
+        let settings = action_context.get_settings();
+
+        let query_plan = action.get_plan();
+        action_context.attach_query_plan(&query_plan);
+        register_plan_wasm_functions(&action_context, &query_plan).await?;
+        let select_plan = SelectPlan {
+            input: Arc::new(query_plan.clone()),
+        };
+        let pipeline_builder = QueryPipelineBuilder::create(action_context.clone());
+        let mut pipeline = pipeline_builder.finalize(&select_plan)?;
+
+        let sinks_tx = self.get_sinks_tx(action)?;
+        let mut sink_pipeline_builder = SinkPipeBuilder::create();
+        for _ in 0..pipeline.output_len() {
+            let input_port = InputPort::create();
+            let flight_scatter = T::try_create(
+                query_context.clone(),
+                query_plan.schema(),
+                action.get_scatter_expression(),
+                sinks_tx.len(),
+            )?;
+
+            sink_pipeline_builder.add_sink(
+                input_port.clone(),
+                ExchangeSink::create(input_port, flight_scatter, sinks_tx.clone()),
+            );
+        }
+
+        pipeline.add_pipe(sink_pipeline_builder.finalize());
+        pipeline.set_max_threads(settings.get_max_threads()? as usize);
+
+        let async_runtime = action_context.get_storage_runtime();
+        let executor = PipelineCompleteExecutor::try_create(async_runtime, pipeline)?;
+
+        let stage_name = format!("{}/{}", action.get_query_id(), action.get_stage_id());
+        let stages_notify = self.stages_notify.clone();
+
+        query_context.try_spawn(
+            async move {
+                wait_start(stage_name, stages_notify).await;
+
+                // The executor blocks the current thread until the pipeline is finished.
+                Thread::spawn(move || {
+                    let _session = session;
+                    let executed = executor.execute();
+                    drop(executor);
+
+                    if let Err(error) = executed {
+                        for tx in &sinks_tx {
+                            if !tx.is_closed() {
+                                let _ignore_send_error = tx.blocking_send(Err(error.clone()));
+                            }
+                        }
+                    }
+                });
+            }
+            .instrument(Span::current()),
+        )?;
+
+        Ok(())
+    }
+
+    fn get_sinks_tx(&self, action: &FlightAction) -> Result<Vec<Sender<Result<DataBlock>>>> {
+        let action_query_id = action.get_query_id();
+        let action_stage_id = action.get_stage_id();
+
+        let action_sinks = action.get_sinks();
+        let mut sinks_tx = Vec::with_capacity(action_sinks.len());
+
+        for sink in &action_sinks {
+            let stream_name = format!("{}/{}/{}", action_query_id, action_stage_id, sink);
+            match self.streams.read().get(&stream_name) {
+                Some(stream) => sinks_tx.push(stream.tx.clone()),
+                None => {
+                    return Err(ErrorCode::NotFoundStream(format!(
+                        "Not found stream {}",
+                        stream_name
+                    )))
+                }
+            }
+        }
+
+        Ok(sinks_tx)
+    }
+
     fn create_stage_streams(
         &self,
         query_id: &str,
@@ -18,6 +18,7 @@ pub use flight_actions::FlightAction;
 pub use flight_actions::ShuffleAction;
 pub use flight_client::FlightClient;
 pub use flight_dispatcher::DatabendQueryFlightDispatcher;
+pub use flight_scatter::FlightScatter;
 pub use flight_service::DatabendQueryFlightService;
 pub use flight_tickets::FlightTicket;
 pub use flight_tickets::StreamTicket;
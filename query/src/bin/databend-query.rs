@@ -25,8 +25,10 @@ use databend_query::api::HttpService;
 use databend_query::api::RpcService;
 use databend_query::metrics::MetricService;
 use databend_query::servers::ClickHouseHandler;
+use databend_query::servers::FlightSqlHandler;
 use databend_query::servers::HttpHandler;
 use databend_query::servers::MySQLHandler;
+use databend_query::servers::PostgresHandler;
 use databend_query::servers::Server;
 use databend_query::servers::ShutdownHandle;
 use databend_query::sessions::SessionManager;
@@ -92,6 +94,41 @@ async fn main(_global_tracker: Arc<RuntimeTracker>) -> common_exception::Result<
             listening.port(),
         );
     }
+
+    // PostgreSQL handler.
+    {
+        let hostname = conf.query.postgres_handler_host.clone();
+        let listening = format!("{}:{}", hostname, conf.query.postgres_handler_port);
+
+        let mut srv = PostgresHandler::create(session_manager.clone());
+        let listening = srv.start(listening.parse()?).await?;
+        shutdown_handle.add_service(srv);
+
+        tracing::info!(
+            "PostgreSQL handler listening on {}, Usage: psql -h {} -p {} -U root -d default",
+            listening,
+            listening.ip(),
+            listening.port(),
+        );
+    }
+
+    // Arrow Flight SQL handler.
+    {
+        let hostname = conf.query.flight_sql_handler_host.clone();
+        let listening = format!("{}:{}", hostname, conf.query.flight_sql_handler_port);
+
+        let mut srv = FlightSqlHandler::create(session_manager.clone());
+        let listening = srv.start(listening.parse()?).await?;
+        shutdown_handle.add_service(srv);
+
+        tracing::info!(
+            "Flight SQL handler listening on {}, Usage: grpc://{}:{} with a Flight SQL client",
+            listening,
+            listening.ip(),
+            listening.port(),
+        );
+    }
+
     // HTTP handler.
     {
         let hostname = conf.query.http_handler_host.clone();
@@ -86,6 +86,17 @@ impl<Key: HashTableKeyable, Entity: HashTableEntity<Key>, Grower: HashTableGrowe
         self.size == 0
     }
 
+    /// The memory in bytes allocated for the entities.
+    #[inline(always)]
+    pub fn allocated_bytes(&self) -> usize {
+        let zero_entity_bytes = match self.zero_entity_raw {
+            None => 0,
+            Some(_) => mem::size_of::<Entity>(),
+        };
+
+        (self.grower.max_size() as usize) * mem::size_of::<Entity>() + zero_entity_bytes
+    }
+
     #[inline(always)]
     pub fn iter(&self) -> HashTableIteratorKind<Key, Entity> {
         HashTableIteratorKind::create_hash_table_iter(
@@ -62,6 +62,14 @@ impl<
         self.len() == 0
     }
 
+    #[inline(always)]
+    pub fn allocated_bytes(&self) -> usize {
+        match self {
+            HashTableKind::HashTable(data) => data.allocated_bytes(),
+            HashTableKind::TwoLevelHashTable(data) => data.allocated_bytes(),
+        }
+    }
+
     #[inline(always)]
     pub fn iter(&self) -> HashTableIteratorKind<Key, Entity> {
         match self {
@@ -138,6 +146,14 @@ impl<Key: HashTableKeyable, Entity: HashTableEntity<Key>, Grower: HashTableGrowe
         self.len() == 0
     }
 
+    #[inline(always)]
+    pub fn allocated_bytes(&self) -> usize {
+        self.hash_tables
+            .iter()
+            .map(|hash_table| hash_table.allocated_bytes())
+            .sum()
+    }
+
     #[inline(always)]
     pub fn iter(&self) -> HashTableIteratorKind<Key, Entity> {
         let mut iters = Vec::with_capacity(NUM_BUCKETS);
@@ -103,4 +103,14 @@ impl Config {
     pub fn tls_rpc_server_enabled(&self) -> bool {
         !self.query.rpc_tls_server_key.is_empty() && !self.query.rpc_tls_server_cert.is_empty()
     }
+
+    pub fn tls_mysql_handler_enabled(&self) -> bool {
+        !self.query.mysql_handler_tls_server_key.is_empty()
+            && !self.query.mysql_handler_tls_server_cert.is_empty()
+    }
+
+    pub fn tls_clickhouse_handler_enabled(&self) -> bool {
+        !self.query.clickhouse_handler_tls_server_key.is_empty()
+            && !self.query.clickhouse_handler_tls_server_cert.is_empty()
+    }
 }
@@ -54,6 +54,8 @@ impl SystemDatabase {
             )),
             system::EnginesTable::create(sys_db_meta.next_table_id()),
             system::RolesTable::create(sys_db_meta.next_table_id()),
+            system::AsyncQueriesTable::create(sys_db_meta.next_table_id()),
+            system::QueryCacheTable::create(sys_db_meta.next_table_id()),
         ];
 
         for tbl in table_list.into_iter() {
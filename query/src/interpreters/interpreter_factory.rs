@@ -31,6 +31,7 @@ use crate::interpreters::AlterUserUDFInterpreter;
 use crate::interpreters::CallInterpreter;
 use crate::interpreters::CopyInterpreter;
 use crate::interpreters::CreateDatabaseInterpreter;
+use crate::interpreters::CreateMaterializedViewInterpreter;
 use crate::interpreters::CreateRoleInterpreter;
 use crate::interpreters::CreateTableInterpreter;
 use crate::interpreters::CreateUserInterpreter;
@@ -52,6 +53,7 @@ use crate::interpreters::InterceptorInterpreter;
 use crate::interpreters::Interpreter;
 use crate::interpreters::KillInterpreter;
 use crate::interpreters::OptimizeTableInterpreter;
+use crate::interpreters::RefreshMaterializedViewInterpreter;
 use crate::interpreters::RenameDatabaseInterpreter;
 use crate::interpreters::RevokePrivilegeInterpreter;
 use crate::interpreters::RevokeRoleInterpreter;
@@ -143,6 +145,14 @@ impl InterpreterFactory {
             PlanNode::AlterView(v) => AlterViewInterpreter::try_create(ctx_clone, v),
             PlanNode::DropView(v) => DropViewInterpreter::try_create(ctx_clone, v),
 
+            // Materialized view related transforms
+            PlanNode::CreateMaterializedView(v) => {
+                CreateMaterializedViewInterpreter::try_create(ctx_clone, v)
+            }
+            PlanNode::RefreshMaterializedView(v) => {
+                RefreshMaterializedViewInterpreter::try_create(ctx_clone, v)
+            }
+
             // User related transforms
             PlanNode::CreateUser(v) => CreateUserInterpreter::try_create(ctx_clone, v),
             PlanNode::AlterUser(v) => AlterUserInterpreter::try_create(ctx_clone, v),
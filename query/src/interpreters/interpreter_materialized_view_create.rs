@@ -0,0 +1,162 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_meta_types::CreateTableReq;
+use common_meta_types::GrantObject;
+use common_meta_types::TableMeta;
+use common_meta_types::TableNameIdent;
+use common_meta_types::UserPrivilegeType;
+use common_planners::CreateMaterializedViewPlan;
+use common_planners::SourceInfo;
+use common_streams::DataBlockStream;
+use common_streams::SendableDataBlockStream;
+
+use crate::interpreters::Interpreter;
+use crate::interpreters::InterpreterPtr;
+use crate::sessions::QueryContext;
+use crate::sql::OPT_KEY_DATABASE_ID;
+use crate::sql::OPT_KEY_MATERIALIZED_VIEW_QUERY;
+use crate::sql::OPT_KEY_MATERIALIZED_VIEW_SOURCE;
+use crate::storages::fuse::FuseTable;
+
+/// Creates a fuse table kept with the result of the query over its source table, the rows
+/// inserted into the source table later are kept by the commits of the source table.
+pub struct CreateMaterializedViewInterpreter {
+    ctx: Arc<QueryContext>,
+    plan: CreateMaterializedViewPlan,
+}
+
+impl CreateMaterializedViewInterpreter {
+    pub fn try_create(
+        ctx: Arc<QueryContext>,
+        plan: CreateMaterializedViewPlan,
+    ) -> Result<InterpreterPtr> {
+        Ok(Arc::new(CreateMaterializedViewInterpreter { ctx, plan }))
+    }
+}
+
+#[async_trait::async_trait]
+impl Interpreter for CreateMaterializedViewInterpreter {
+    fn name(&self) -> &str {
+        "CreateMaterializedViewInterpreter"
+    }
+
+    async fn execute(&self, _: Option<SendableDataBlockStream>) -> Result<SendableDataBlockStream> {
+        self.ctx
+            .get_current_session()
+            .validate_privilege(
+                &GrantObject::Database(self.plan.catalog.clone(), self.plan.db.clone()),
+                UserPrivilegeType::Create,
+            )
+            .await?;
+
+        let catalog = self.ctx.get_catalog(&self.plan.catalog)?;
+        if catalog
+            .exists_table(&self.plan.tenant, &self.plan.db, &self.plan.viewname)
+            .await?
+        {
+            if self.plan.if_not_exists {
+                return Ok(Box::pin(DataBlockStream::create(
+                    self.plan.schema(),
+                    None,
+                    vec![],
+                )));
+            }
+            return Err(ErrorCode::ViewAlreadyExists(format!(
+                "{}.{} as materialized view Already Exists",
+                self.plan.db, self.plan.viewname
+            )));
+        }
+
+        self.create_materialized_view().await
+    }
+}
+
+impl CreateMaterializedViewInterpreter {
+    async fn create_materialized_view(&self) -> Result<SendableDataBlockStream> {
+        let tenant = self.plan.tenant.as_str();
+        let catalog = self.ctx.get_catalog(&self.plan.catalog)?;
+
+        let (query_plan, source) =
+            FuseTable::plan_materialized_view(self.ctx.clone(), &self.plan.subquery).await?;
+        let source_id = match &source.source_info {
+            SourceInfo::TableSource(table_info) => table_info.ident.table_id,
+            _ => unreachable!("the source of a materialized view is a table"),
+        };
+
+        let db = catalog.get_database(tenant, &self.plan.db).await?;
+        let mut options = BTreeMap::new();
+        options.insert(
+            OPT_KEY_DATABASE_ID.to_string(),
+            db.get_db_info().ident.db_id.to_string(),
+        );
+        options.insert(
+            OPT_KEY_MATERIALIZED_VIEW_QUERY.to_string(),
+            self.plan.subquery.clone(),
+        );
+        options.insert(
+            OPT_KEY_MATERIALIZED_VIEW_SOURCE.to_string(),
+            source_id.to_string(),
+        );
+        let req = CreateTableReq {
+            if_not_exists: self.plan.if_not_exists,
+            name_ident: TableNameIdent {
+                tenant: tenant.to_string(),
+                db_name: self.plan.db.clone(),
+                table_name: self.plan.viewname.clone(),
+            },
+            table_meta: TableMeta {
+                schema: query_plan.schema(),
+                engine: "FUSE".to_string(),
+                options,
+                ..Default::default()
+            },
+        };
+        catalog.create_table(req).await?;
+        let view = catalog
+            .get_table(tenant, &self.plan.db, &self.plan.viewname)
+            .await?;
+
+        // The rows committed to the source table after the linking are appended to the view by
+        // the commits, the view is filled with the rows before.
+        let source_info = FuseTable::link_materialized_view(
+            &self.ctx,
+            &self.plan.catalog,
+            source_id,
+            view.get_id(),
+        )
+        .await?;
+        let source = catalog.get_table_by_info(&source_info)?;
+        FuseTable::try_from_table(source.as_ref())?
+            .refresh_materialized_view(
+                self.ctx.clone(),
+                &self.plan.catalog,
+                view.as_ref(),
+                None,
+                false,
+            )
+            .await?;
+
+        Ok(Box::pin(DataBlockStream::create(
+            self.plan.schema(),
+            None,
+            vec![],
+        )))
+    }
+}
@@ -0,0 +1,101 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_meta_types::GrantObject;
+use common_meta_types::MetaId;
+use common_meta_types::TableInfo;
+use common_meta_types::UserPrivilegeType;
+use common_planners::RefreshMaterializedViewPlan;
+use common_streams::DataBlockStream;
+use common_streams::SendableDataBlockStream;
+
+use crate::interpreters::Interpreter;
+use crate::interpreters::InterpreterPtr;
+use crate::sessions::QueryContext;
+use crate::sql::OPT_KEY_MATERIALIZED_VIEW_SOURCE;
+use crate::storages::fuse::FuseTable;
+
+/// Rebuilds a materialized view with the query over the whole source table.
+///
+/// The rows inserted into the source table while refreshing may be lost by the view, which
+/// is overwritten at last.
+pub struct RefreshMaterializedViewInterpreter {
+    ctx: Arc<QueryContext>,
+    plan: RefreshMaterializedViewPlan,
+}
+
+impl RefreshMaterializedViewInterpreter {
+    pub fn try_create(
+        ctx: Arc<QueryContext>,
+        plan: RefreshMaterializedViewPlan,
+    ) -> Result<InterpreterPtr> {
+        Ok(Arc::new(RefreshMaterializedViewInterpreter { ctx, plan }))
+    }
+}
+
+#[async_trait::async_trait]
+impl Interpreter for RefreshMaterializedViewInterpreter {
+    fn name(&self) -> &str {
+        "RefreshMaterializedViewInterpreter"
+    }
+
+    async fn execute(&self, _: Option<SendableDataBlockStream>) -> Result<SendableDataBlockStream> {
+        let plan = &self.plan;
+        self.ctx
+            .get_current_session()
+            .validate_privilege(
+                &GrantObject::Table(plan.catalog.clone(), plan.db.clone(), plan.viewname.clone()),
+                UserPrivilegeType::Insert,
+            )
+            .await?;
+
+        let catalog = self.ctx.get_catalog(&plan.catalog)?;
+        let view = catalog
+            .get_table(&plan.tenant, &plan.db, &plan.viewname)
+            .await?;
+        let source_id = view
+            .get_table_info()
+            .options()
+            .get(OPT_KEY_MATERIALIZED_VIEW_SOURCE)
+            .and_then(|id| id.parse::<MetaId>().ok())
+            .ok_or_else(|| {
+                ErrorCode::BadArguments(format!(
+                    "{}.{} is not a materialized view",
+                    plan.db, plan.viewname
+                ))
+            })?;
+
+        let (ident, meta) = catalog.get_table_meta_by_id(source_id).await?;
+        let source_info = TableInfo {
+            ident,
+            desc: "".to_owned(),
+            name: "".to_owned(),
+            meta: meta.as_ref().clone(),
+        };
+        let source = catalog.get_table_by_info(&source_info)?;
+        FuseTable::try_from_table(source.as_ref())?
+            .refresh_materialized_view(self.ctx.clone(), &plan.catalog, view.as_ref(), None, true)
+            .await?;
+
+        Ok(Box::pin(DataBlockStream::create(
+            self.plan.schema(),
+            None,
+            vec![],
+        )))
+    }
+}
@@ -0,0 +1,261 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use async_stream::stream;
+use common_datavalues::DataSchemaRef;
+use common_datavalues::DataValue;
+use common_exception::Result;
+use common_functions::scalars::FunctionFactory;
+use common_planners::Expression;
+use common_planners::ExpressionVisitor;
+use common_planners::PlanNode;
+use common_planners::PlanVisitor;
+use common_planners::ReadDataSourcePlan;
+use common_planners::Recursion;
+use common_planners::SourceInfo;
+use common_streams::SendableDataBlockStream;
+use common_tracing::tracing;
+use futures::StreamExt;
+use sha2::Digest;
+use sha2::Sha256;
+
+use crate::sessions::QueryContext;
+use crate::storages::fuse::cache::QueryResult;
+use crate::storages::fuse::cache::QueryResultCache;
+use crate::storages::fuse::cache::QueryResultStorage;
+use crate::storages::fuse::FuseTable;
+
+/// The key of the cached result of a query.
+///
+/// A query is cached only if it reads fuse tables and calls deterministic functions only, so
+/// its result is decided by the query text, the settings and the snapshots of the tables, and
+/// any commit to the tables changes the key.
+pub struct QueryResultCacheKey {
+    pub key: String,
+    pub sql: String,
+    pub tables: Vec<String>,
+}
+
+impl QueryResultCacheKey {
+    /// Returns `None` if the result of the query cannot be cached.
+    pub fn try_create(ctx: &QueryContext, plan: &PlanNode) -> Result<Option<Self>> {
+        let sql = normalize_sql(&ctx.get_query_str());
+        if sql.is_empty() {
+            return Ok(None);
+        }
+
+        let mut visitor = CacheableVisitor {
+            cacheable: true,
+            sources: vec![],
+        };
+        visitor.visit_plan_node(plan)?;
+        // The queries without tables are cheap to run.
+        if !visitor.cacheable || visitor.sources.is_empty() {
+            return Ok(None);
+        }
+
+        let mut tables = Vec::with_capacity(visitor.sources.len());
+        let mut table_ids = Vec::with_capacity(visitor.sources.len());
+        for source in &visitor.sources {
+            let table = ctx.build_table_from_source_plan(source)?;
+            let fuse_table = match FuseTable::try_from_table(table.as_ref()) {
+                Ok(fuse_table) => fuse_table,
+                Err(_) => return Ok(None),
+            };
+            let table_info = &fuse_table.table_info;
+            let snapshot_id = fuse_table.snapshot_id().unwrap_or_default();
+            tables.push(format!("{}@{}", table_info.desc, snapshot_id));
+            table_ids.push(format!("{}@{}", table_info.ident.table_id, snapshot_id));
+        }
+        tables.sort();
+        tables.dedup();
+        table_ids.sort();
+        table_ids.dedup();
+
+        let mut hasher = Sha256::new();
+        hasher.update(ctx.get_tenant());
+        hasher.update("\n");
+        hasher.update(ctx.get_current_database());
+        hasher.update("\n");
+        for setting in ctx.get_settings().get_setting_values() {
+            // The settings of the query cache do not change the result.
+            if let DataValue::Struct(values) = setting {
+                let name = values[0].to_string();
+                if !name.contains("query_cache") {
+                    hasher.update(format!("{}={:?}\n", name, values[1]));
+                }
+            }
+        }
+        for table_id in &table_ids {
+            hasher.update(table_id);
+            hasher.update("\n");
+        }
+        hasher.update(&sql);
+
+        Ok(Some(QueryResultCacheKey {
+            key: format!("{:x}", hasher.finalize()),
+            sql,
+            tables,
+        }))
+    }
+
+    /// Passes through the result of the query, and caches it once the query succeeded, if it is
+    /// not larger than the `query_cache_max_result_bytes`.
+    pub fn cache_result(
+        self,
+        ctx: &QueryContext,
+        cache: Arc<QueryResultCache>,
+        schema: DataSchemaRef,
+        mut input: SendableDataBlockStream,
+    ) -> Result<SendableDataBlockStream> {
+        let settings = ctx.get_settings();
+        let ttl = Duration::from_secs(settings.get_query_cache_ttl_secs()?);
+        let max_bytes = settings.get_query_cache_max_result_bytes()? as usize;
+        let storage = match settings.get_query_cache_storage()?.as_slice() {
+            b"disk" => QueryResultStorage::Disk,
+            _ => QueryResultStorage::Memory,
+        };
+        let tenant = ctx.get_tenant();
+
+        Ok(Box::pin(stream! {
+            let mut blocks = vec![];
+            let mut bytes = 0;
+            let mut caching = true;
+            while let Some(res) = input.next().await {
+                match &res {
+                    Ok(block) if caching => {
+                        bytes += block.memory_size();
+                        if bytes > max_bytes {
+                            caching = false;
+                            blocks.clear();
+                        } else {
+                            blocks.push(block.clone());
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(_) => caching = false,
+                }
+                yield res;
+            }
+
+            if caching {
+                // The blocks are read back with their own schema.
+                let schema = blocks.first().map(|b| b.schema().clone()).unwrap_or(schema);
+                let result = QueryResult {
+                    key: self.key,
+                    tenant,
+                    sql: self.sql,
+                    tables: self.tables,
+                    schema,
+                    blocks,
+                };
+                let expire_time = SystemTime::now() + ttl;
+                if let Err(cause) = cache.put(result, storage, max_bytes, expire_time).await {
+                    tracing::warn!("Cannot cache the query result: {}", cause);
+                }
+            }
+        }))
+    }
+}
+
+struct CacheableVisitor {
+    cacheable: bool,
+    sources: Vec<ReadDataSourcePlan>,
+}
+
+impl PlanVisitor for CacheableVisitor {
+    fn visit_expr(&mut self, expr: &Expression) -> Result<()> {
+        let visitor = expr.accept(DeterministicVisitor {
+            deterministic: true,
+            subqueries: vec![],
+        })?;
+        if !visitor.deterministic {
+            self.cacheable = false;
+        }
+        for subquery in &visitor.subqueries {
+            self.visit_subquery_plan(subquery)?;
+        }
+        Ok(())
+    }
+
+    fn visit_read_data_source(&mut self, plan: &ReadDataSourcePlan) -> Result<()> {
+        // Table functions and stages are not cached.
+        match (&plan.source_info, &plan.tbl_args) {
+            (SourceInfo::TableSource(_), None) => self.sources.push(plan.clone()),
+            _ => self.cacheable = false,
+        }
+        Ok(())
+    }
+}
+
+struct DeterministicVisitor {
+    deterministic: bool,
+    subqueries: Vec<Arc<PlanNode>>,
+}
+
+impl ExpressionVisitor for DeterministicVisitor {
+    fn pre_visit(mut self, expr: &Expression) -> Result<Recursion<Self>> {
+        match expr {
+            Expression::ScalarFunction { op, .. }
+            | Expression::UnaryExpression { op, .. }
+            | Expression::BinaryExpression { op, .. } => {
+                let features = FunctionFactory::instance().get_features(op);
+                if !matches!(features, Ok(features) if features.is_deterministic) {
+                    self.deterministic = false;
+                }
+            }
+            Expression::Subquery { query_plan, .. }
+            | Expression::ScalarSubquery { query_plan, .. } => {
+                self.subqueries.push(query_plan.clone());
+            }
+            _ => {}
+        }
+        Ok(Recursion::Continue(self))
+    }
+}
+
+/// Collapses the whitespaces out of the quotes, and removes the trailing semicolons.
+pub fn normalize_sql(sql: &str) -> String {
+    let sql = sql
+        .trim()
+        .trim_end_matches(|c: char| c == ';' || c.is_whitespace());
+    let mut normalized = String::with_capacity(sql.len());
+    let mut quote = None;
+    let mut last_whitespace = false;
+    for c in sql.chars() {
+        match quote {
+            Some(q) => {
+                if c == q {
+                    quote = None;
+                }
+            }
+            None if c.is_whitespace() => {
+                last_whitespace = true;
+                continue;
+            }
+            None if c == '\'' || c == '"' || c == '`' => quote = Some(c),
+            None => {}
+        }
+        if last_whitespace {
+            normalized.push(' ');
+            last_whitespace = false;
+        }
+        normalized.push(c);
+    }
+    normalized
+}
@@ -18,6 +18,7 @@ use common_datavalues::DataSchemaRef;
 use common_exception::Result;
 use common_planners::PlanNode;
 use common_planners::SelectPlan;
+use common_streams::DataBlockStream;
 use common_streams::SendableDataBlockStream;
 use common_tracing::tracing;
 
@@ -25,6 +26,7 @@ use crate::interpreters::plan_schedulers;
 use crate::interpreters::stream::ProcessorExecutorStream;
 use crate::interpreters::Interpreter;
 use crate::interpreters::InterpreterPtr;
+use crate::interpreters::QueryResultCacheKey;
 use crate::optimizers::Optimizers;
 use crate::pipelines::new::executor::PipelinePullingExecutor;
 use crate::pipelines::new::NewPipeline;
@@ -43,6 +45,15 @@ impl SelectInterpreter {
         Ok(Arc::new(SelectInterpreter { ctx, select }))
     }
 
+    /// Executes the plan without the query result cache, for the queries run by the server
+    /// itself, such as the queries of the materialized views.
+    pub async fn execute_plan(
+        ctx: Arc<QueryContext>,
+        select: SelectPlan,
+    ) -> Result<SendableDataBlockStream> {
+        SelectInterpreter { ctx, select }.execute_query().await
+    }
+
     /// Call this method to optimize the logical plan before executing
     fn rewrite_plan(&self) -> Result<PlanNode> {
         plan_schedulers::apply_plan_rewrite(
@@ -50,6 +61,25 @@ impl SelectInterpreter {
             &self.select.input,
         )
     }
+
+    async fn execute_query(&self) -> Result<SendableDataBlockStream> {
+        let settings = self.ctx.get_settings();
+
+        if settings.get_enable_new_processor_framework()? != 0 {
+            if !self.ctx.get_cluster().is_empty() {
+                let optimized_plan = self.rewrite_plan()?;
+                return plan_schedulers::schedule_query_new(&self.ctx, &optimized_plan).await;
+            }
+
+            let async_runtime = self.ctx.get_storage_runtime();
+            let new_pipeline = self.create_new_pipeline()?;
+            let executor = PipelinePullingExecutor::try_create(async_runtime, new_pipeline)?;
+            let executor_stream = Box::pin(ProcessorExecutorStream::create(executor)?);
+            return Ok(Box::pin(self.ctx.try_create_abortable(executor_stream)?));
+        }
+        let optimized_plan = self.rewrite_plan()?;
+        plan_schedulers::schedule_query(&self.ctx, &optimized_plan).await
+    }
 }
 
 #[async_trait::async_trait]
@@ -67,23 +97,32 @@ impl Interpreter for SelectInterpreter {
     #[tracing::instrument(level = "debug", name = "select_interpreter_execute", skip(self, _input_stream), fields(ctx.id = self.ctx.get_id().as_str()))]
     /// Currently, the method has two sets of logic, if `get_enable_new_processor_framework` is turned on in the settings,
     /// the execution will use the new processor, otherwise the old processing logic will be executed.
+    /// In cluster mode, the stages of the query are exchanged between the nodes over the Flight service.
+    /// If `enable_query_cache` is set, the result is reused while the tables read are not changed.
     /// Note: there is an issue to track the progress of the new processor:  https://github.com/datafuselabs/databend/issues/3379
     async fn execute(
         &self,
         _input_stream: Option<SendableDataBlockStream>,
     ) -> Result<SendableDataBlockStream> {
-        let settings = self.ctx.get_settings();
+        if self.ctx.get_settings().get_enable_query_cache()? != 0 {
+            let cache_manager = self.ctx.get_storage_cache_manager();
+            if let Some(cache) = cache_manager.get_query_result_cache() {
+                if let Some(key) = QueryResultCacheKey::try_create(&self.ctx, &self.select.input)? {
+                    if let Some(blocks) = cache.get(&key.key).await? {
+                        return Ok(Box::pin(DataBlockStream::create(
+                            self.schema(),
+                            None,
+                            blocks,
+                        )));
+                    }
 
-        if settings.get_enable_new_processor_framework()? != 0 && self.ctx.get_cluster().is_empty()
-        {
-            let async_runtime = self.ctx.get_storage_runtime();
-            let new_pipeline = self.create_new_pipeline()?;
-            let executor = PipelinePullingExecutor::try_create(async_runtime, new_pipeline)?;
-            let executor_stream = Box::pin(ProcessorExecutorStream::create(executor)?);
-            return Ok(Box::pin(self.ctx.try_create_abortable(executor_stream)?));
+                    let stream = self.execute_query().await?;
+                    return key.cache_result(&self.ctx, cache, self.schema(), stream);
+                }
+            }
         }
-        let optimized_plan = self.rewrite_plan()?;
-        plan_schedulers::schedule_query(&self.ctx, &optimized_plan).await
+
+        self.execute_query().await
     }
 
     /// This method will create a new pipeline
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::sync::Arc;
+use std::time::SystemTime;
 
 use common_exception::Result;
 use common_streams::SendableDataBlockStream;
@@ -21,24 +22,48 @@ use common_tracing::tracing;
 use crate::interpreters::stream::ProcessorExecutorStream;
 use crate::interpreters::Interpreter;
 use crate::interpreters::InterpreterPtr;
+use crate::interpreters::InterpreterQueryLog;
 use crate::pipelines::new::executor::PipelineExecutor;
 use crate::pipelines::new::executor::PipelinePullingExecutor;
 use crate::sessions::QueryContext;
+use crate::sql::DfStatement;
 use crate::sql::Planner;
 
 /// Interpret SQL query with new SQL planner
 pub struct SelectInterpreterV2 {
     ctx: Arc<QueryContext>,
     query: String,
+    query_log: InterpreterQueryLog,
 }
 
 impl SelectInterpreterV2 {
     pub fn try_create(ctx: Arc<QueryContext>, query: &str) -> Result<InterpreterPtr> {
         Ok(Arc::new(SelectInterpreterV2 {
-            ctx,
+            ctx: ctx.clone(),
             query: query.to_string(),
+            query_log: InterpreterQueryLog::create(ctx, None),
         }))
     }
+
+    /// Whether the query is interpreted by the new planner, which is enabled by the
+    /// `enable_planner_v2` setting on a standalone node. Besides the `SELECT` statements,
+    /// the queries which only the new parser supports (e.g. `UNION`, `WITH` and
+    /// `GROUPING SETS`) go to the new planner, `stmts` is `None` if the old parser rejects
+    /// the query.
+    pub fn check(ctx: &QueryContext, stmts: Option<&[DfStatement]>, query: &str) -> Result<bool> {
+        let settings = ctx.get_settings();
+        if settings.get_enable_new_processor_framework()? == 0
+            || !ctx.get_cluster().is_empty()
+            || settings.get_enable_planner_v2()? == 0
+        {
+            return Ok(false);
+        }
+
+        Ok(match stmts {
+            Some(stmts) => matches!(stmts.first(), Some(DfStatement::Query(_))),
+            None => Planner::is_query(query),
+        })
+    }
 }
 
 #[async_trait::async_trait]
@@ -69,10 +94,11 @@ impl Interpreter for SelectInterpreterV2 {
     }
 
     async fn start(&self) -> Result<()> {
-        Ok(())
+        self.query_log.log_start(SystemTime::now(), None).await
     }
 
     async fn finish(&self) -> Result<()> {
-        Ok(())
+        let error = self.ctx.get_error_value();
+        self.query_log.log_finish(SystemTime::now(), error).await
     }
 }
@@ -62,6 +62,19 @@ impl Interpreter for SettingInterpreter {
                         .get_settings()
                         .set_settings(var.variable, tz.to_string(), false)?;
                 }
+                "query_cache_storage" => {
+                    let storage = var.value.trim_matches(|c| c == '\'' || c == '\"');
+                    let storage = storage.to_lowercase();
+                    if storage != "memory" && storage != "disk" {
+                        return Err(ErrorCode::BadArguments(format!(
+                            "Invalid query_cache_storage: {}, must be memory or disk",
+                            var.value
+                        )));
+                    }
+                    self.ctx
+                        .get_settings()
+                        .set_settings(var.variable, storage, false)?;
+                }
                 _ => {
                     self.ctx
                         .get_settings()
@@ -15,6 +15,7 @@
 use std::sync::Arc;
 
 use common_exception::Result;
+use common_functions::scalars::WasmFunction;
 use common_planners::AlterUserUDFPlan;
 use common_streams::DataBlockStream;
 use common_streams::SendableDataBlockStream;
@@ -51,7 +52,10 @@ impl Interpreter for AlterUserUDFInterpreter {
 
         let tenant = self.ctx.get_tenant();
         let user_mgr = self.ctx.get_user_manager();
+        let name = plan.udf.name.clone();
         user_mgr.update_udf(&tenant, plan.udf).await?;
+        // The compiled WebAssembly functions are registered again when they are used.
+        WasmFunction::unregister(&tenant, &name);
 
         Ok(Box::pin(DataBlockStream::create(
             self.plan.schema(),
@@ -15,6 +15,8 @@
 use std::sync::Arc;
 
 use common_exception::Result;
+use common_functions::scalars::WasmFunction;
+use common_meta_types::UserDefinedFunction;
 use common_planners::DropUserUDFPlan;
 use common_streams::DataBlockStream;
 use common_streams::SendableDataBlockStream;
@@ -50,9 +52,34 @@ impl Interpreter for DropUserUDFInterpreter {
         let plan = self.plan.clone();
         let tenant = self.ctx.get_tenant();
         let user_mgr = self.ctx.get_user_manager();
-        user_mgr
-            .drop_udf(&tenant, plan.name.as_str(), plan.if_exists)
-            .await?;
+
+        let signatures = match &plan.parameter_types {
+            Some(parameter_types) => vec![UserDefinedFunction::typed_signature(
+                &plan.name,
+                parameter_types,
+            )],
+            // Without the parameter types, all the overloads are dropped.
+            None => {
+                let udfs = user_mgr.get_udfs(&tenant).await?;
+                let signatures = udfs
+                    .iter()
+                    .filter(|udf| udf.name == plan.name)
+                    .map(|udf| udf.signature())
+                    .collect::<Vec<_>>();
+                match signatures.is_empty() {
+                    true => vec![plan.name.clone()],
+                    false => signatures,
+                }
+            }
+        };
+
+        for signature in signatures {
+            user_mgr
+                .drop_udf(&tenant, signature.as_str(), plan.if_exists)
+                .await?;
+        }
+        // The compiled WebAssembly functions are registered again when they are used.
+        WasmFunction::unregister(&tenant, &plan.name);
 
         Ok(Box::pin(DataBlockStream::create(
             self.plan.schema(),
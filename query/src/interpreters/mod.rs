@@ -29,8 +29,11 @@ mod interpreter_insert;
 mod interpreter_insert_with_stream;
 mod interpreter_kill;
 mod interpreter_list;
+mod interpreter_materialized_view_create;
+mod interpreter_materialized_view_refresh;
 mod interpreter_privilege_grant;
 mod interpreter_privilege_revoke;
+mod interpreter_query_cache;
 mod interpreter_query_log;
 mod interpreter_role_create;
 mod interpreter_role_drop;
@@ -89,8 +92,12 @@ pub use interpreter_factory_interceptor::InterceptorInterpreter;
 pub use interpreter_insert::InsertInterpreter;
 pub use interpreter_kill::KillInterpreter;
 pub use interpreter_list::ListInterpreter;
+pub use interpreter_materialized_view_create::CreateMaterializedViewInterpreter;
+pub use interpreter_materialized_view_refresh::RefreshMaterializedViewInterpreter;
 pub use interpreter_privilege_grant::GrantPrivilegeInterpreter;
 pub use interpreter_privilege_revoke::RevokePrivilegeInterpreter;
+pub use interpreter_query_cache::normalize_sql;
+pub use interpreter_query_cache::QueryResultCacheKey;
 pub use interpreter_query_log::InterpreterQueryLog;
 pub use interpreter_query_log::LogEvent;
 pub use interpreter_query_log::LogType;
@@ -70,6 +70,7 @@ pub struct PlanScheduler {
     running_mode: RunningMode,
     query_context: Arc<QueryContext>,
     subqueries_expressions: Vec<Expressions>,
+    new_processor_framework: bool,
 }
 
 impl PlanScheduler {
@@ -97,9 +98,16 @@ impl PlanScheduler {
             subqueries_expressions: vec![],
             cluster_nodes: cluster_nodes_name,
             running_mode: RunningMode::Standalone,
+            new_processor_framework: false,
         })
     }
 
+    /// The scheduled stages run on the new processor framework.
+    pub fn with_new_processor_framework(mut self) -> PlanScheduler {
+        self.new_processor_framework = true;
+        self
+    }
+
     /// Schedule the plan to Local or Remote mode.
     #[tracing::instrument(level = "debug", skip(self, plan))]
     pub fn reschedule(mut self, plan: &PlanNode) -> Result<Tasks> {
@@ -171,6 +179,7 @@ impl PlanScheduler {
             plan: input.clone(),
             sinks: self.cluster_nodes.clone(),
             scatters_expression: stage.scatters_expr.clone(),
+            new_processor_framework: self.new_processor_framework,
         }
     }
 
@@ -211,6 +220,7 @@ impl PlanScheduler {
             plan: input.clone(),
             sinks: self.cluster_nodes.clone(),
             scatters_expression: stage.scatters_expr.clone(),
+            new_processor_framework: self.new_processor_framework,
         }
     }
 
@@ -254,6 +264,7 @@ impl PlanScheduler {
             plan: input.clone(),
             sinks: vec![self.cluster_nodes[self.local_pos].clone()],
             scatters_expression: stage.scatters_expr.clone(),
+            new_processor_framework: self.new_processor_framework,
         }
     }
 
@@ -435,6 +446,7 @@ impl PlanScheduler {
             query_id: self.query_context.get_id(),
             plan: input.clone(),
             sinks: self.cluster_nodes.clone(),
+            new_processor_framework: self.new_processor_framework,
         }
     }
 
@@ -641,6 +653,7 @@ impl PlanScheduler {
     fn visit_subquery(&mut self, plan: &PlanNode, tasks: &mut Tasks) -> Result<Vec<PlanNode>> {
         let subquery_context = QueryContext::create_from(self.query_context.clone());
         let mut subquery_scheduler = PlanScheduler::try_create(subquery_context)?;
+        subquery_scheduler.new_processor_framework = self.new_processor_framework;
         subquery_scheduler.visit_plan_node(plan, tasks)?;
         Ok(subquery_scheduler.nodes_plan)
     }
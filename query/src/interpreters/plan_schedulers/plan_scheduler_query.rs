@@ -17,13 +17,18 @@ use std::sync::Arc;
 
 use common_exception::Result;
 use common_planners::PlanNode;
+use common_planners::SelectPlan;
 use common_streams::SendableDataBlockStream;
 use common_tracing::tracing;
 
 use crate::interpreters::plan_schedulers;
+use crate::interpreters::plan_schedulers::plan_scheduler::Tasks;
 use crate::interpreters::plan_schedulers::Scheduled;
 use crate::interpreters::plan_schedulers::ScheduledStream;
+use crate::interpreters::stream::ProcessorExecutorStream;
 use crate::interpreters::PlanScheduler;
+use crate::pipelines::new::executor::PipelinePullingExecutor;
+use crate::pipelines::new::QueryPipelineBuilder;
 use crate::pipelines::processors::PipelineBuilder;
 use crate::sessions::QueryContext;
 
@@ -34,7 +39,62 @@ pub async fn schedule_query(
 ) -> Result<SendableDataBlockStream> {
     let scheduler = PlanScheduler::try_create(ctx.clone())?;
     let scheduled_tasks = scheduler.reschedule(plan)?;
-    let remote_stage_actions = scheduled_tasks.get_tasks()?;
+    let scheduled = schedule_remote_tasks(ctx, &scheduled_tasks).await?;
+
+    let pipeline_builder = PipelineBuilder::create(ctx.clone());
+    let mut in_local_pipeline = pipeline_builder.build(&scheduled_tasks.get_local_task())?;
+
+    match in_local_pipeline.execute().await {
+        Ok(stream) => Ok(ScheduledStream::create(ctx.clone(), scheduled, stream)),
+        Err(error) => {
+            let timeout = ctx.get_settings().get_flight_client_timeout()?;
+            plan_schedulers::handle_error(ctx, scheduled, timeout).await;
+            Err(error)
+        }
+    }
+}
+
+/// Schedules the query on the new processor framework.
+///
+/// The plan is split into one fragment for each node of the cluster, the fragments are
+/// connected by the exchange sources and sinks which transfer the blocks over the Flight service.
+#[tracing::instrument(level = "debug", skip(ctx), fields(ctx.id = ctx.get_id().as_str()))]
+pub async fn schedule_query_new(
+    ctx: &Arc<QueryContext>,
+    plan: &PlanNode,
+) -> Result<SendableDataBlockStream> {
+    let scheduler = PlanScheduler::try_create(ctx.clone())?.with_new_processor_framework();
+    let scheduled_tasks = scheduler.reschedule(plan)?;
+    let scheduled = schedule_remote_tasks(ctx, &scheduled_tasks).await?;
+
+    let create_local_stream = || -> Result<SendableDataBlockStream> {
+        let settings = ctx.get_settings();
+        let local_plan = SelectPlan {
+            input: Arc::new(scheduled_tasks.get_local_task()),
+        };
+
+        let pipeline_builder = QueryPipelineBuilder::create(ctx.clone());
+        let mut local_pipeline = pipeline_builder.finalize(&local_plan)?;
+        local_pipeline.set_max_threads(settings.get_max_threads()? as usize);
+
+        let async_runtime = ctx.get_storage_runtime();
+        let executor = PipelinePullingExecutor::try_create(async_runtime, local_pipeline)?;
+        let executor_stream = Box::pin(ProcessorExecutorStream::create(executor)?);
+        Ok(Box::pin(ctx.try_create_abortable(executor_stream)?))
+    };
+
+    match create_local_stream() {
+        Ok(stream) => Ok(ScheduledStream::create(ctx.clone(), scheduled, stream)),
+        Err(error) => {
+            let timeout = ctx.get_settings().get_flight_client_timeout()?;
+            plan_schedulers::handle_error(ctx, scheduled, timeout).await;
+            Err(error)
+        }
+    }
+}
+
+async fn schedule_remote_tasks(ctx: &Arc<QueryContext>, tasks: &Tasks) -> Result<Scheduled> {
+    let remote_stage_actions = tasks.get_tasks()?;
 
     let config = ctx.get_config();
     let cluster = ctx.get_cluster();
@@ -48,14 +108,5 @@ pub async fn schedule_query(
         scheduled.insert(node.id.clone(), node.clone());
     }
 
-    let pipeline_builder = PipelineBuilder::create(ctx.clone());
-    let mut in_local_pipeline = pipeline_builder.build(&scheduled_tasks.get_local_task())?;
-
-    match in_local_pipeline.execute().await {
-        Ok(stream) => Ok(ScheduledStream::create(ctx.clone(), scheduled, stream)),
-        Err(error) => {
-            plan_schedulers::handle_error(ctx, scheduled, timeout).await;
-            Err(error)
-        }
-    }
+    Ok(scheduled)
 }
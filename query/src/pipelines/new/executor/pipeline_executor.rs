@@ -16,6 +16,7 @@ use std::collections::VecDeque;
 use std::sync::Arc;
 use std::thread::JoinHandle;
 
+use common_base::base::MemoryTracker;
 use common_base::base::Runtime;
 use common_base::base::Thread;
 use common_exception::ErrorCode;
@@ -113,6 +114,10 @@ impl PipelineExecutor {
         let workers_notify = self.workers_notify.clone();
         let mut context = ExecutorWorkerContext::create(thread_num, workers_notify);
 
+        // The executor threads inherit the memory tracker of the query which starts them,
+        // the query is stopped once it uses more memory than its limits.
+        let memory_tracker = MemoryTracker::current();
+
         while !self.global_tasks_queue.is_finished() {
             // When there are not enough tasks, the thread will be blocked, so we need loop check.
             while !self.global_tasks_queue.is_finished() && !context.has_task() {
@@ -120,6 +125,10 @@ impl PipelineExecutor {
             }
 
             while context.has_task() {
+                if let Some(memory_tracker) = &memory_tracker {
+                    memory_tracker.check_memory_limit()?;
+                }
+
                 if let Some(executed_pid) = context.execute_task(self)? {
                     // We immediately schedule the processor again.
                     let schedule_queue = self.graph.schedule_queue(executed_pid)?;
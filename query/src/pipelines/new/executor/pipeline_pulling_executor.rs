@@ -17,6 +17,7 @@ use std::sync::mpsc::SyncSender;
 use std::sync::Arc;
 
 use common_base::base::Runtime;
+use common_base::base::Thread;
 use common_datablocks::DataBlock;
 use common_exception::ErrorCode;
 use common_exception::Result;
@@ -88,7 +89,7 @@ impl PipelinePullingExecutor {
         let state = self.state.clone();
         let threads_executor = self.executor.clone();
         let thread_function = Self::thread_function(state, threads_executor);
-        std::thread::spawn(thread_function);
+        Thread::spawn(thread_function);
     }
 
     fn thread_function(state: Arc<State>, executor: Arc<PipelineExecutor>) -> impl Fn() {
@@ -18,6 +18,7 @@ use common_exception::ErrorCode;
 use common_exception::Result;
 use common_planners::AggregatorFinalPlan;
 use common_planners::AggregatorPartialPlan;
+use common_planners::BroadcastPlan;
 use common_planners::ExpressionPlan;
 use common_planners::FilterPlan;
 use common_planners::HavingPlan;
@@ -27,14 +28,20 @@ use common_planners::PlanNode;
 use common_planners::PlanVisitor;
 use common_planners::ProjectionPlan;
 use common_planners::ReadDataSourcePlan;
+use common_planners::RemotePlan;
 use common_planners::SelectPlan;
 use common_planners::SortPlan;
+use common_planners::StagePlan;
 use common_planners::SubQueriesSetPlan;
 
 use super::processors::SortMergeCompactor;
+use crate::api::FlightTicket;
 use crate::pipelines::new::pipeline::NewPipeline;
+use crate::pipelines::new::processors::port::OutputPort;
+use crate::pipelines::new::processors::processor::ProcessorPtr;
 use crate::pipelines::new::processors::AggregatorParams;
 use crate::pipelines::new::processors::AggregatorTransformParams;
+use crate::pipelines::new::processors::ExchangeSource;
 use crate::pipelines::new::processors::ExpressionTransform;
 use crate::pipelines::new::processors::ProjectionTransform;
 use crate::pipelines::new::processors::SubQueriesPuller;
@@ -44,8 +51,12 @@ use crate::pipelines::new::processors::TransformFilter;
 use crate::pipelines::new::processors::TransformHaving;
 use crate::pipelines::new::processors::TransformLimit;
 use crate::pipelines::new::processors::TransformLimitBy;
+use crate::pipelines::new::processors::TransformMultiSortMerge;
 use crate::pipelines::new::processors::TransformSortMerge;
 use crate::pipelines::new::processors::TransformSortPartial;
+use crate::pipelines::new::processors::TransformSortSpill;
+use crate::pipelines::new::NewPipe;
+use crate::pipelines::new::SourcePipeBuilder;
 use crate::pipelines::transforms::get_sort_descriptions;
 use crate::sessions::QueryContext;
 /// Builder for query pipeline
@@ -94,6 +105,9 @@ impl PlanVisitor for QueryPipelineBuilder {
             PlanNode::ReadSource(n) => self.visit_read_data_source(n),
             PlanNode::Select(n) => self.visit_select(n),
             PlanNode::SubQueryExpression(n) => self.visit_sub_queries_sets(n),
+            PlanNode::Stage(n) => self.visit_stage(n),
+            PlanNode::Broadcast(n) => self.visit_broadcast(n),
+            PlanNode::Remote(n) => self.visit_remote(n),
             _ => Err(ErrorCode::UnImplement("")),
         }
     }
@@ -254,6 +268,10 @@ impl PlanVisitor for QueryPipelineBuilder {
                 )
             })?;
 
+        if self.ctx.get_settings().get_sort_spill_threshold()? != 0 {
+            return self.build_sort_spill(plan, rows_limit);
+        }
+
         // processor 1: [sorted blocks ...] ---> merge to one sorted block
         // processor 2: [sorted blocks ...] ---> merge to one sorted block
         // processor 3: [sorted blocks ...] ---> merge to one sorted block
@@ -288,6 +306,47 @@ impl PlanVisitor for QueryPipelineBuilder {
             })
     }
 
+    /// Sorts with bounded memory, the sorted runs above `sort_spill_threshold` are spilled.
+    fn build_sort_spill(&mut self, plan: &SortPlan, rows_limit: Option<usize>) -> Result<()> {
+        // processor 1: [sorted blocks ...] ---> sorted runs (spilled) ---> sorted stream
+        // processor 2: [sorted blocks ...] ---> sorted runs (spilled) ---> sorted stream
+        // processor 3: [sorted blocks ...] ---> sorted runs (spilled) ---> sorted stream
+        self.pipeline
+            .add_transform(|transform_input_port, transform_output_port| {
+                TransformSortSpill::try_create(
+                    self.ctx.clone(),
+                    transform_input_port,
+                    transform_output_port,
+                    rows_limit,
+                    get_sort_descriptions(&plan.schema, &plan.order_by)?,
+                )
+            })?;
+
+        // processor1 sorted stream --
+        //                              \
+        // processor2 sorted stream ----> processor  --> k-way merge to one sorted stream
+        //                              /
+        // processor3 sorted stream --
+        let streams = self.pipeline.output_len();
+        if streams > 1 {
+            let processor = TransformMultiSortMerge::create(
+                streams,
+                rows_limit,
+                get_sort_descriptions(&plan.schema, &plan.order_by)?,
+            );
+
+            let inputs_port = processor.get_inputs().to_vec();
+            let outputs_port = vec![processor.get_output().clone()];
+            self.pipeline.add_pipe(NewPipe::ResizePipe {
+                inputs_port,
+                outputs_port,
+                processor: ProcessorPtr::create(Box::new(processor)),
+            });
+        }
+
+        Ok(())
+    }
+
     fn visit_limit_by(&mut self, plan: &LimitByPlan) -> Result<()> {
         self.visit_plan_node(&plan.input)?;
 
@@ -303,6 +362,41 @@ impl PlanVisitor for QueryPipelineBuilder {
             })
     }
 
+    fn visit_stage(&mut self, _: &StagePlan) -> Result<()> {
+        Err(ErrorCode::LogicalError(
+            "Logical Error: stage plan must be scheduled before building the pipeline",
+        ))
+    }
+
+    fn visit_broadcast(&mut self, _: &BroadcastPlan) -> Result<()> {
+        Err(ErrorCode::LogicalError(
+            "Logical Error: broadcast plan must be scheduled before building the pipeline",
+        ))
+    }
+
+    // Fetches the stream of the stage from each node, the nodes run the stage in parallel.
+    fn visit_remote(&mut self, plan: &RemotePlan) -> Result<()> {
+        let mut source_builder = SourcePipeBuilder::create();
+
+        for fetch_node in &plan.fetch_nodes {
+            let output_port = OutputPort::create();
+            let ticket = FlightTicket::stream(&plan.query_id, &plan.stage_id, &plan.stream_id);
+            source_builder.add_source(
+                output_port.clone(),
+                ExchangeSource::create(
+                    self.ctx.clone(),
+                    output_port,
+                    ticket,
+                    fetch_node.clone(),
+                    plan.schema(),
+                )?,
+            );
+        }
+
+        self.pipeline.add_pipe(source_builder.finalize());
+        Ok(())
+    }
+
     fn visit_read_data_source(&mut self, plan: &ReadDataSourcePlan) -> Result<()> {
         // Bind plan partitions to context.
         self.ctx.try_set_partitions(plan.parts.clone())?;
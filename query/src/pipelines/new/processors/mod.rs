@@ -31,13 +31,16 @@ pub use resize_processor::ResizeProcessor;
 pub use sinks::AsyncSink;
 pub use sinks::AsyncSinker;
 pub use sinks::EmptySink;
+pub use sinks::ExchangeSink;
 pub use sinks::Sink;
 pub use sinks::Sinker;
 pub use sinks::SyncSenderSink;
 pub use sources::AsyncSource;
 pub use sources::AsyncSourcer;
 pub use sources::BlocksSource;
+pub use sources::CteSource;
 pub use sources::EmptySource;
+pub use sources::ExchangeSource;
 pub use sources::StreamSource;
 pub use sources::StreamSourceV2;
 pub use sources::SyncReceiverCkSource;
@@ -48,10 +51,16 @@ pub use transforms::AggregatorParams;
 pub use transforms::AggregatorTransformParams;
 pub use transforms::BlockCompactor;
 pub use transforms::ChainingHashTable;
+pub use transforms::CteState;
 pub use transforms::ExpressionTransform;
 pub use transforms::HashJoinState;
 pub use transforms::ProjectionTransform;
+pub use transforms::RecursiveIteration;
+pub use transforms::RuntimeFilter;
+pub use transforms::SetOperationState;
 pub use transforms::SinkBuildHashTable;
+pub use transforms::SinkCte;
+pub use transforms::SinkSetOperation;
 pub use transforms::SortMergeCompactor;
 pub use transforms::SubQueriesPuller;
 pub use transforms::TransformAddOn;
@@ -60,11 +69,18 @@ pub use transforms::TransformBlockCompact;
 pub use transforms::TransformCastSchema;
 pub use transforms::TransformCompact;
 pub use transforms::TransformCreateSets;
+pub use transforms::TransformDistinct;
 pub use transforms::TransformDummy;
+pub use transforms::TransformExpandGroupingSets;
 pub use transforms::TransformFilter;
 pub use transforms::TransformHashJoinProbe;
 pub use transforms::TransformHaving;
+pub use transforms::TransformIntersectExcept;
+pub use transforms::TransformRecursiveCte;
 pub use transforms::TransformLimit;
 pub use transforms::TransformLimitBy;
+pub use transforms::TransformMultiSortMerge;
 pub use transforms::TransformSortMerge;
 pub use transforms::TransformSortPartial;
+pub use transforms::TransformSortSpill;
+pub use transforms::TransformUnionAll;
@@ -0,0 +1,79 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_base::base::tokio::sync::mpsc::Sender;
+use common_datablocks::DataBlock;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_exception::ToErrorCode;
+use futures::Future;
+
+use crate::api::FlightScatter;
+use crate::pipelines::new::processors::port::InputPort;
+use crate::pipelines::new::processors::processor::ProcessorPtr;
+use crate::pipelines::new::processors::AsyncSink;
+use crate::pipelines::new::processors::AsyncSinker;
+
+/// Sends the blocks of a stage to the streams fetched by the nodes of the cluster,
+/// the block is split into one block for each stream by the scatter.
+pub struct ExchangeSink<T: FlightScatter + Send + 'static> {
+    scatter: T,
+    senders: Vec<Sender<Result<DataBlock>>>,
+}
+
+impl<T: FlightScatter + Send + 'static> ExchangeSink<T> {
+    pub fn create(
+        input: Arc<InputPort>,
+        scatter: T,
+        senders: Vec<Sender<Result<DataBlock>>>,
+    ) -> ProcessorPtr {
+        AsyncSinker::create(input, ExchangeSink { scatter, senders })
+    }
+}
+
+impl<T: FlightScatter + Send + 'static> AsyncSink for ExchangeSink<T> {
+    const NAME: &'static str = "ExchangeSink";
+
+    type ConsumeFuture<'a> = impl Future<Output = Result<()>> where Self: 'a;
+
+    fn consume(&mut self, data_block: DataBlock) -> Self::ConsumeFuture<'_> {
+        async move {
+            let scattered_blocks = self.scatter.execute(&data_block)?;
+            if scattered_blocks.len() != self.senders.len() {
+                return Err(ErrorCode::LogicalError(format!(
+                    "Logical error: scattered {} blocks for {} streams",
+                    scattered_blocks.len(),
+                    self.senders.len()
+                )));
+            }
+
+            for (sender, block) in self.senders.iter().zip(scattered_blocks.into_iter()) {
+                if block.is_empty() {
+                    continue;
+                }
+
+                sender
+                    .send(Ok(block))
+                    .await
+                    .map_err_to_code(ErrorCode::LogicalError, || {
+                        "Cannot push data to the exchange stream"
+                    })?;
+            }
+
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,54 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_datablocks::DataBlock;
+use common_exception::Result;
+
+use crate::pipelines::new::processors::port::OutputPort;
+use crate::pipelines::new::processors::processor::ProcessorPtr;
+use crate::pipelines::new::processors::CteState;
+use crate::pipelines::new::processors::SyncSource;
+use crate::pipelines::new::processors::SyncSourcer;
+use crate::sessions::QueryContext;
+
+/// Reads all the rows of a CTE, the rows are kept by the state for the other references.
+pub struct CteSource {
+    state: Arc<CteState>,
+    next_block: usize,
+}
+
+impl CteSource {
+    pub fn create(
+        ctx: Arc<QueryContext>,
+        output: Arc<OutputPort>,
+        state: Arc<CteState>,
+    ) -> Result<ProcessorPtr> {
+        SyncSourcer::create(ctx, output, CteSource {
+            state,
+            next_block: 0,
+        })
+    }
+}
+
+impl SyncSource for CteSource {
+    const NAME: &'static str = "CteSource";
+
+    fn generate(&mut self) -> Result<Option<DataBlock>> {
+        let data_block = self.state.get_block(self.next_block);
+        self.next_block += 1;
+        Ok(data_block)
+    }
+}
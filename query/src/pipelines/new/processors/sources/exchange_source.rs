@@ -0,0 +1,128 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_datablocks::DataBlock;
+use common_datavalues::DataSchemaRef;
+use common_exception::Result;
+use common_streams::SendableDataBlockStream;
+use futures::StreamExt;
+
+use crate::api::FlightTicket;
+use crate::pipelines::new::processors::port::OutputPort;
+use crate::pipelines::new::processors::processor::Event;
+use crate::pipelines::new::processors::processor::ProcessorPtr;
+use crate::pipelines::new::processors::Processor;
+use crate::sessions::QueryContext;
+
+/// Receives the blocks of a stage from a node of the cluster over the Flight service.
+///
+/// Unlike the other sources, it does not count the scan progress, which has been counted
+/// by the node that scanned the data.
+pub struct ExchangeSource {
+    ctx: Arc<QueryContext>,
+    ticket: FlightTicket,
+    fetch_node: String,
+    schema: DataSchemaRef,
+
+    is_finish: bool,
+    output: Arc<OutputPort>,
+    stream: Option<SendableDataBlockStream>,
+    fetched_data: Option<DataBlock>,
+}
+
+impl ExchangeSource {
+    pub fn create(
+        ctx: Arc<QueryContext>,
+        output: Arc<OutputPort>,
+        ticket: FlightTicket,
+        fetch_node: String,
+        schema: DataSchemaRef,
+    ) -> Result<ProcessorPtr> {
+        Ok(ProcessorPtr::create(Box::new(ExchangeSource {
+            ctx,
+            ticket,
+            fetch_node,
+            schema,
+            output,
+            is_finish: false,
+            stream: None,
+            fetched_data: None,
+        })))
+    }
+
+    async fn fetch_stream(&self) -> Result<SendableDataBlockStream> {
+        let config = self.ctx.get_config();
+        let timeout = self.ctx.get_settings().get_flight_client_timeout()?;
+        let cluster = self.ctx.get_cluster();
+
+        let mut flight_client = cluster.create_node_conn(&self.fetch_node, &config).await?;
+        let fetch_stream = flight_client
+            .fetch_stream(self.ticket.clone(), self.schema.clone(), timeout)
+            .await?;
+        Ok(Box::pin(self.ctx.try_create_abortable(fetch_stream)?))
+    }
+}
+
+#[async_trait::async_trait]
+impl Processor for ExchangeSource {
+    fn name(&self) -> &'static str {
+        "ExchangeSource"
+    }
+
+    fn event(&mut self) -> Result<Event> {
+        if self.is_finish {
+            self.output.finish();
+            return Ok(Event::Finished);
+        }
+
+        if self.output.is_finished() {
+            return Ok(Event::Finished);
+        }
+
+        if !self.output.can_push() {
+            return Ok(Event::NeedConsume);
+        }
+
+        match self.fetched_data.take() {
+            None => Ok(Event::Async),
+            Some(data_block) => {
+                self.output.push_data(Ok(data_block));
+                Ok(Event::NeedConsume)
+            }
+        }
+    }
+
+    async fn async_process(&mut self) -> Result<()> {
+        // Connects to the node at the first time the data is needed.
+        if self.stream.is_none() {
+            self.stream = Some(self.fetch_stream().await?);
+        }
+
+        if let Some(stream) = self.stream.as_mut() {
+            match stream.next().await {
+                None => self.is_finish = true,
+                Some(data_block) => {
+                    let data_block = data_block?;
+                    if !data_block.is_empty() {
+                        self.fetched_data = Some(data_block);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
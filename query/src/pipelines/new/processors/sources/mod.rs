@@ -14,7 +14,9 @@
 
 mod async_source;
 mod blocks_source;
+mod cte_source;
 mod empty_source;
+mod exchange_source;
 mod stream_source;
 mod stream_source_v2;
 mod sync_ck_source_receiver;
@@ -24,7 +26,9 @@ mod sync_source_receiver;
 pub use async_source::AsyncSource;
 pub use async_source::AsyncSourcer;
 pub use blocks_source::BlocksSource;
+pub use cte_source::CteSource;
 pub use empty_source::EmptySource;
+pub use exchange_source::ExchangeSource;
 pub use stream_source::StreamSource;
 pub use stream_source_v2::StreamSourceV2;
 pub use sync_ck_source_receiver::SyncReceiverCkSource;
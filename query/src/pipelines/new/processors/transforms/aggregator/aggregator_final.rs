@@ -23,6 +23,8 @@ use common_datablocks::HashMethodKeysU64;
 use common_datablocks::HashMethodKeysU8;
 use common_datablocks::HashMethodSerializer;
 use common_datablocks::HashMethodSingleString;
+use common_datavalues::DataField;
+use common_datavalues::DataSchemaRefExt;
 use common_datavalues::DataType;
 use common_datavalues::MutableColumn;
 use common_datavalues::ScalarColumn;
@@ -32,6 +34,10 @@ use common_exception::Result;
 use common_functions::aggregates::StateAddr;
 use common_functions::aggregates::StateAddrs;
 
+use crate::pipelines::new::processors::transforms::aggregator::aggregator_spiller::serialize_states;
+use crate::pipelines::new::processors::transforms::aggregator::aggregator_spiller::state_buckets;
+use crate::pipelines::new::processors::transforms::aggregator::aggregator_spiller::SPILL_BUCKETS;
+use crate::pipelines::new::processors::transforms::spiller::Spiller;
 use crate::pipelines::new::processors::transforms::transform_aggregator::Aggregator;
 use crate::pipelines::new::processors::AggregatorParams;
 use crate::pipelines::transforms::group_by::AggregatorState;
@@ -64,6 +70,9 @@ pub struct FinalAggregator<
     // used for deserialization only, so we can reuse it during the loop
     temp_place: Option<StateAddr>,
     ctx: Arc<QueryContext>,
+    spiller: Option<Spiller>,
+    // the next spilled bucket to merge
+    spilled_bucket: usize,
 }
 
 impl<const HAS_AGG: bool, Method: HashMethod + PolymorphicKeysHelper<Method> + Send>
@@ -89,8 +98,113 @@ impl<const HAS_AGG: bool, Method: HashMethod + PolymorphicKeysHelper<Method> + S
             params,
             temp_place,
             ctx,
+            spiller: None,
+            spilled_bucket: 0,
         })
     }
+
+    /// Spills the states to disk if their memory exceeds the threshold. The spilled states are
+    /// partitioned into buckets by the group keys, and merged bucket by bucket when generating.
+    fn spill_if_needed(&mut self) -> Result<()> {
+        let spill_threshold = self.ctx.get_settings().get_group_by_spill_threshold()? as usize;
+        match spill_threshold != 0 && self.state.allocated_bytes() >= spill_threshold {
+            true => self.spill_states(),
+            false => Ok(()),
+        }
+    }
+
+    fn spill_states(&mut self) -> Result<()> {
+        if self.spiller.is_none() {
+            self.spiller = Some(Spiller::try_create(&self.ctx, "aggregate")?);
+        }
+
+        if self.state.len() != 0 {
+            let columns = serialize_states(&self.method, &self.state, &self.params)?;
+            let fields = columns
+                .iter()
+                .enumerate()
+                .map(|(index, column)| {
+                    DataField::new(&format!("_spilled_{}", index), column.data_type())
+                })
+                .collect::<Vec<_>>();
+            let block = DataBlock::create(DataSchemaRefExt::create(fields), columns);
+
+            if let Some(spiller) = self.spiller.as_mut() {
+                let indices = state_buckets::<Method>(&self.state, SPILL_BUCKETS);
+                let scattered_blocks = DataBlock::scatter_block(&block, &indices, SPILL_BUCKETS)?;
+
+                for (bucket, scattered_block) in scattered_blocks.into_iter().enumerate() {
+                    if !scattered_block.is_empty() {
+                        spiller.spill(bucket, scattered_block)?;
+                    }
+                }
+            }
+        }
+
+        self.reset_state();
+        Ok(())
+    }
+
+    fn reset_state(&mut self) {
+        self.drop_states();
+        self.state = self.method.aggregate_state();
+        self.temp_place = match self.params.aggregate_functions.is_empty() {
+            true => None,
+            false => self.state.alloc_layout2(&self.params),
+        };
+        self.states_dropped = false;
+    }
+
+    fn restore_spilled_data(&mut self, bucket: usize) -> Result<Option<DataBlock>> {
+        match self.spiller.as_mut() {
+            None => Ok(None),
+            Some(spiller) => spiller.restore(bucket),
+        }
+    }
+
+    fn has_pending_io(&self) -> bool {
+        matches!(&self.spiller, Some(spiller) if spiller.has_pending_io())
+    }
+
+    /// Merges the spilled states bucket by bucket, generates one block for each bucket. Returns
+    /// `None` while waiting for the pending IO of the spiller.
+    fn generate_spilled(
+        &mut self,
+        merge: fn(&mut Self, DataBlock) -> Result<()>,
+        generate_states: fn(&mut Self) -> Result<DataBlock>,
+    ) -> Result<Option<DataBlock>> {
+        if !self.is_generated {
+            self.is_generated = true;
+            self.spill_states()?;
+        }
+
+        let num_buckets = match &self.spiller {
+            None => 0,
+            Some(spiller) => spiller.num_buckets(),
+        };
+
+        while self.spilled_bucket < num_buckets {
+            let bucket = self.spilled_bucket;
+            while let Some(data_block) = self.restore_spilled_data(bucket)? {
+                merge(self, data_block)?;
+            }
+
+            if self.has_pending_io() {
+                return Ok(None);
+            }
+
+            self.spilled_bucket += 1;
+
+            if self.state.len() != 0 {
+                let data_block = generate_states(self)?;
+                self.reset_state();
+                return Ok(Some(data_block));
+            }
+        }
+
+        self.drop_states();
+        Ok(None)
+    }
 }
 
 impl<Method: HashMethod + PolymorphicKeysHelper<Method> + Send> FinalAggregator<true, Method> {
@@ -122,14 +236,8 @@ impl<Method: HashMethod + PolymorphicKeysHelper<Method> + Send> FinalAggregator<
         }
         places
     }
-}
 
-impl<Method: HashMethod + PolymorphicKeysHelper<Method> + Send> Aggregator
-    for FinalAggregator<true, Method>
-{
-    const NAME: &'static str = "";
-
-    fn consume(&mut self, block: DataBlock) -> Result<()> {
+    fn merge(&mut self, block: DataBlock) -> Result<()> {
         // 1.1 and 1.2.
         let aggregate_function_len = self.params.aggregate_functions.len();
         let keys_column = block.column(aggregate_function_len);
@@ -172,65 +280,83 @@ impl<Method: HashMethod + PolymorphicKeysHelper<Method> + Send> Aggregator
         Ok(())
     }
 
-    fn generate(&mut self) -> Result<Option<DataBlock>> {
-        match self.state.len() == 0 || self.is_generated {
-            true => {
-                self.drop_states();
-                Ok(None)
-            }
-            false => {
-                self.is_generated = true;
-                let mut group_columns_builder = self
-                    .method
-                    .group_columns_builder(self.state.len(), &self.params);
-
-                let aggregate_functions = &self.params.aggregate_functions;
-                let offsets_aggregate_states = &self.params.offsets_aggregate_states;
-
-                let mut aggregates_column_builder: Vec<Box<dyn MutableColumn>> = {
-                    let mut values = vec![];
-                    for aggregate_function in aggregate_functions {
-                        let builder = aggregate_function.return_type()?.create_mutable(1024);
-                        values.push(builder)
-                    }
-                    values
-                };
+    fn generate_states(&mut self) -> Result<DataBlock> {
+        let mut group_columns_builder = self
+            .method
+            .group_columns_builder(self.state.len(), &self.params);
 
-                for group_entity in self.state.iter() {
-                    let place: StateAddr = (*group_entity.get_state_value()).into();
+        let aggregate_functions = &self.params.aggregate_functions;
+        let offsets_aggregate_states = &self.params.offsets_aggregate_states;
 
-                    for (idx, aggregate_function) in aggregate_functions.iter().enumerate() {
-                        let arg_place = place.next(offsets_aggregate_states[idx]);
-                        let builder: &mut dyn MutableColumn =
-                            aggregates_column_builder[idx].borrow_mut();
-                        aggregate_function.merge_result(arg_place, builder)?;
-                    }
+        let mut aggregates_column_builder: Vec<Box<dyn MutableColumn>> = {
+            let mut values = vec![];
+            for aggregate_function in aggregate_functions {
+                let builder = aggregate_function.return_type()?.create_mutable(1024);
+                values.push(builder)
+            }
+            values
+        };
 
-                    group_columns_builder.append_value(group_entity.get_state_key());
-                }
+        for group_entity in self.state.iter() {
+            let place: StateAddr = (*group_entity.get_state_value()).into();
 
-                // Build final state block.
-                let fields_len = self.params.schema.fields().len();
-                let mut columns = Vec::with_capacity(fields_len);
+            for (idx, aggregate_function) in aggregate_functions.iter().enumerate() {
+                let arg_place = place.next(offsets_aggregate_states[idx]);
+                let builder: &mut dyn MutableColumn = aggregates_column_builder[idx].borrow_mut();
+                aggregate_function.merge_result(arg_place, builder)?;
+            }
 
-                for mut array in aggregates_column_builder {
-                    columns.push(array.to_column());
-                }
+            group_columns_builder.append_value(group_entity.get_state_key());
+        }
 
-                columns.extend_from_slice(&group_columns_builder.finish()?);
+        // Build final state block.
+        let fields_len = self.params.schema.fields().len();
+        let mut columns = Vec::with_capacity(fields_len);
 
-                Ok(Some(DataBlock::create(self.params.schema.clone(), columns)))
-            }
+        for mut array in aggregates_column_builder {
+            columns.push(array.to_column());
         }
+
+        columns.extend_from_slice(&group_columns_builder.finish()?);
+
+        Ok(DataBlock::create(self.params.schema.clone(), columns))
     }
 }
 
 impl<Method: HashMethod + PolymorphicKeysHelper<Method> + Send> Aggregator
-    for FinalAggregator<false, Method>
+    for FinalAggregator<true, Method>
 {
     const NAME: &'static str = "";
 
     fn consume(&mut self, block: DataBlock) -> Result<()> {
+        self.merge(block)?;
+        self.spill_if_needed()
+    }
+
+    fn spiller(&mut self) -> Option<&mut Spiller> {
+        self.spiller.as_mut()
+    }
+
+    fn generate(&mut self) -> Result<Option<DataBlock>> {
+        if self.spiller.is_some() {
+            return self.generate_spilled(Self::merge, Self::generate_states);
+        }
+
+        match self.state.len() == 0 || self.is_generated {
+            true => {
+                self.drop_states();
+                Ok(None)
+            }
+            false => {
+                self.is_generated = true;
+                Ok(Some(self.generate_states()?))
+            }
+        }
+    }
+}
+
+impl<Method: HashMethod + PolymorphicKeysHelper<Method> + Send> FinalAggregator<false, Method> {
+    fn merge(&mut self, block: DataBlock) -> Result<()> {
         let key_array = block.column(0);
         let keys_iter = self.method.keys_iter_from_column(key_array)?;
 
@@ -248,20 +374,43 @@ impl<Method: HashMethod + PolymorphicKeysHelper<Method> + Send> Aggregator
         Ok(())
     }
 
+    fn generate_states(&mut self) -> Result<DataBlock> {
+        let mut columns_builder = self
+            .method
+            .group_columns_builder(self.state.len(), &self.params);
+        for group_entity in self.state.iter() {
+            columns_builder.append_value(group_entity.get_state_key());
+        }
+
+        let columns = columns_builder.finish()?;
+        Ok(DataBlock::create(self.params.schema.clone(), columns))
+    }
+}
+
+impl<Method: HashMethod + PolymorphicKeysHelper<Method> + Send> Aggregator
+    for FinalAggregator<false, Method>
+{
+    const NAME: &'static str = "";
+
+    fn consume(&mut self, block: DataBlock) -> Result<()> {
+        self.merge(block)?;
+        self.spill_if_needed()
+    }
+
+    fn spiller(&mut self) -> Option<&mut Spiller> {
+        self.spiller.as_mut()
+    }
+
     fn generate(&mut self) -> Result<Option<DataBlock>> {
+        if self.spiller.is_some() {
+            return self.generate_spilled(Self::merge, Self::generate_states);
+        }
+
         match self.state.len() == 0 || self.is_generated {
             true => Ok(None),
             false => {
                 self.is_generated = true;
-                let mut columns_builder = self
-                    .method
-                    .group_columns_builder(self.state.len(), &self.params);
-                for group_entity in self.state.iter() {
-                    columns_builder.append_value(group_entity.get_state_key());
-                }
-
-                let columns = columns_builder.finish()?;
-                Ok(Some(DataBlock::create(self.params.schema.clone(), columns)))
+                Ok(Some(self.generate_states()?))
             }
         }
     }
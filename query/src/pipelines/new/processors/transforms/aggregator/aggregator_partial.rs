@@ -14,7 +14,6 @@
 
 use std::sync::Arc;
 
-use bytes::BytesMut;
 use common_datablocks::DataBlock;
 use common_datablocks::HashMethod;
 use common_datablocks::HashMethodKeysU16;
@@ -24,17 +23,15 @@ use common_datablocks::HashMethodKeysU8;
 use common_datablocks::HashMethodSerializer;
 use common_datablocks::HashMethodSingleString;
 use common_datavalues::ColumnRef;
-use common_datavalues::MutableColumn;
-use common_datavalues::MutableStringColumn;
-use common_datavalues::ScalarColumnBuilder;
 use common_exception::Result;
 use common_functions::aggregates::StateAddr;
 use common_functions::aggregates::StateAddrs;
 
+use crate::pipelines::new::processors::transforms::aggregator::aggregator_spiller::serialize_states;
+use crate::pipelines::new::processors::transforms::spiller::Spiller;
 use crate::pipelines::new::processors::transforms::transform_aggregator::Aggregator;
 use crate::pipelines::new::processors::AggregatorParams;
 use crate::pipelines::transforms::group_by::AggregatorState;
-use crate::pipelines::transforms::group_by::KeysColumnBuilder;
 use crate::pipelines::transforms::group_by::PolymorphicKeysHelper;
 use crate::pipelines::transforms::group_by::StateEntity;
 use crate::sessions::QueryContext;
@@ -63,6 +60,7 @@ pub struct PartialAggregator<
     state: Method::State,
     params: Arc<AggregatorParams>,
     ctx: Arc<QueryContext>,
+    spiller: Option<Spiller>,
 }
 
 impl<const HAS_AGG: bool, Method: HashMethod + PolymorphicKeysHelper<Method> + Send>
@@ -77,6 +75,7 @@ impl<const HAS_AGG: bool, Method: HashMethod + PolymorphicKeysHelper<Method> + S
             method,
             params,
             ctx,
+            spiller: None,
         }
     }
 
@@ -180,41 +179,44 @@ impl<const HAS_AGG: bool, Method: HashMethod + PolymorphicKeysHelper<Method> + S
         }
 
         self.is_generated = true;
-        let state_groups_len = self.state.len();
-        let aggregator_params = self.params.as_ref();
-        let funcs = &aggregator_params.aggregate_functions;
-        let aggr_len = funcs.len();
-        let offsets_aggregate_states = &aggregator_params.offsets_aggregate_states;
-
-        // Builders.
-        let mut state_builders: Vec<MutableStringColumn> = (0..aggr_len)
-            .map(|_| MutableStringColumn::with_capacity(state_groups_len * 4))
-            .collect();
-
-        let mut group_key_builder = self.method.keys_column_builder(state_groups_len);
-
-        let mut bytes = BytesMut::new();
-        for group_entity in self.state.iter() {
-            let place: StateAddr = (*group_entity.get_state_value()).into();
-
-            for (idx, func) in funcs.iter().enumerate() {
-                let arg_place = place.next(offsets_aggregate_states[idx]);
-                func.serialize(arg_place, &mut bytes)?;
-                state_builders[idx].append_value(&bytes[..]);
-                bytes.clear();
-            }
+        let columns = serialize_states(&self.method, &self.state, &self.params)?;
+        Ok(Some(DataBlock::create(self.params.schema.clone(), columns)))
+    }
+
+    /// Spills the states to disk if their memory exceeds the threshold, the spilled states are
+    /// generated after the states in memory, so the memory of the partial aggregator is bounded.
+    fn spill_if_needed(&mut self) -> Result<()> {
+        let spill_threshold = self.ctx.get_settings().get_group_by_spill_threshold()? as usize;
+        if spill_threshold == 0 || self.state.allocated_bytes() < spill_threshold {
+            return Ok(());
+        }
+
+        let columns = serialize_states(&self.method, &self.state, &self.params)?;
+        let block = DataBlock::create(self.params.schema.clone(), columns);
+
+        if self.spiller.is_none() {
+            self.spiller = Some(Spiller::try_create(&self.ctx, "aggregate")?);
+        }
 
-            group_key_builder.append_value(group_entity.get_state_key());
+        if let Some(spiller) = self.spiller.as_mut() {
+            spiller.spill(0, block)?;
         }
 
-        let schema = &self.params.schema;
-        let mut columns: Vec<ColumnRef> = Vec::with_capacity(schema.fields().len());
-        for mut builder in state_builders {
-            columns.push(builder.to_column());
+        self.drop_states();
+        self.state = self.method.aggregate_state();
+        self.states_dropped = false;
+        Ok(())
+    }
+
+    fn restore_spilled_data(&mut self) -> Result<Option<DataBlock>> {
+        match self.spiller.as_mut() {
+            None => Ok(None),
+            Some(spiller) => spiller.restore(0),
         }
+    }
 
-        columns.push(group_key_builder.finish());
-        Ok(Some(DataBlock::create(schema.clone(), columns)))
+    fn has_pending_io(&self) -> bool {
+        matches!(&self.spiller, Some(spiller) if spiller.has_pending_io())
     }
 }
 
@@ -235,11 +237,19 @@ impl<Method: HashMethod + PolymorphicKeysHelper<Method> + Send> Aggregator
         }
 
         let places = Self::lookup_state(&self.params, group_keys, &mut self.state);
-        Self::execute(&self.params, &block, &places)
+        Self::execute(&self.params, &block, &places)?;
+        self.spill_if_needed()
+    }
+
+    fn spiller(&mut self) -> Option<&mut Spiller> {
+        self.spiller.as_mut()
     }
 
     fn generate(&mut self) -> Result<Option<DataBlock>> {
-        self.generate_data()
+        match self.generate_data()? {
+            Some(data_block) => Ok(Some(data_block)),
+            None => self.restore_spilled_data(),
+        }
     }
 }
 
@@ -260,27 +270,25 @@ impl<Method: HashMethod + PolymorphicKeysHelper<Method> + Send> Aggregator
         }
 
         Self::lookup_key(group_keys, &mut self.state);
-        Ok(())
+        self.spill_if_needed()
+    }
+
+    fn spiller(&mut self) -> Option<&mut Spiller> {
+        self.spiller.as_mut()
     }
 
     fn generate(&mut self) -> Result<Option<DataBlock>> {
-        match self.state.len() == 0 || self.is_generated {
-            true => {
+        if let Some(data_block) = self.generate_data()? {
+            return Ok(Some(data_block));
+        }
+
+        match self.restore_spilled_data()? {
+            Some(data_block) => Ok(Some(data_block)),
+            None if self.has_pending_io() => Ok(None),
+            None => {
                 self.drop_states();
                 Ok(None)
             }
-            false => {
-                self.is_generated = true;
-                let mut keys_column_builder = self.method.keys_column_builder(self.state.len());
-                for group_entity in self.state.iter() {
-                    keys_column_builder.append_value(group_entity.get_state_key());
-                }
-
-                let columns = keys_column_builder.finish();
-                Ok(Some(DataBlock::create(self.params.schema.clone(), vec![
-                    columns,
-                ])))
-            }
         }
     }
 }
@@ -0,0 +1,85 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bytes::BytesMut;
+use common_datablocks::HashMethod;
+use common_datavalues::ColumnRef;
+use common_datavalues::MutableColumn;
+use common_datavalues::MutableStringColumn;
+use common_datavalues::ScalarColumnBuilder;
+use common_exception::Result;
+use common_functions::aggregates::StateAddr;
+
+use crate::common::HashTableKeyable;
+use crate::pipelines::new::processors::AggregatorParams;
+use crate::pipelines::transforms::group_by::AggregatorState;
+use crate::pipelines::transforms::group_by::KeysColumnBuilder;
+use crate::pipelines::transforms::group_by::PolymorphicKeysHelper;
+use crate::pipelines::transforms::group_by::StateEntity;
+
+/// Number of buckets the spilled states are partitioned into by the final aggregator.
+pub const SPILL_BUCKETS: usize = 64;
+
+/// Serializes the aggregate function states and the group keys, in the format of the
+/// blocks generated by the partial aggregator.
+pub fn serialize_states<Method>(
+    method: &Method,
+    state: &Method::State,
+    params: &AggregatorParams,
+) -> Result<Vec<ColumnRef>>
+where
+    Method: HashMethod + PolymorphicKeysHelper<Method>,
+{
+    let state_groups_len = state.len();
+    let funcs = &params.aggregate_functions;
+    let offsets_aggregate_states = &params.offsets_aggregate_states;
+
+    // Builders.
+    let mut state_builders: Vec<MutableStringColumn> = (0..funcs.len())
+        .map(|_| MutableStringColumn::with_capacity(state_groups_len * 4))
+        .collect();
+
+    let mut group_key_builder = method.keys_column_builder(state_groups_len);
+
+    let mut bytes = BytesMut::new();
+    for group_entity in state.iter() {
+        let place: StateAddr = (*group_entity.get_state_value()).into();
+
+        for (idx, func) in funcs.iter().enumerate() {
+            let arg_place = place.next(offsets_aggregate_states[idx]);
+            func.serialize(arg_place, &mut bytes)?;
+            state_builders[idx].append_value(&bytes[..]);
+            bytes.clear();
+        }
+
+        group_key_builder.append_value(group_entity.get_state_key());
+    }
+
+    let mut columns: Vec<ColumnRef> = Vec::with_capacity(funcs.len() + 1);
+    for mut builder in state_builders {
+        columns.push(builder.to_column());
+    }
+
+    columns.push(group_key_builder.finish());
+    Ok(columns)
+}
+
+/// The bucket of each group, in the iteration order of the state.
+pub fn state_buckets<Method>(state: &Method::State, num_buckets: usize) -> Vec<usize>
+where Method: HashMethod + PolymorphicKeysHelper<Method> {
+    state
+        .iter()
+        .map(|group_entity| (group_entity.get_state_key().fast_hash() as usize) % num_buckets)
+        .collect()
+}
@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::sync::Mutex;
 use std::sync::RwLock;
@@ -23,48 +25,177 @@ use common_datavalues::ConstColumn;
 use common_datavalues::DataSchemaRef;
 use common_exception::Result;
 use common_planners::Expression;
+use common_tracing::tracing;
 
 use crate::common::ExpressionEvaluator;
+use crate::pipelines::new::processors::transforms::hash_join::grace_hash_join::GracePartitions;
 use crate::pipelines::new::processors::transforms::hash_join::hash::HashUtil;
 use crate::pipelines::new::processors::transforms::hash_join::hash::HashVector;
 use crate::pipelines::new::processors::transforms::hash_join::row::compare_and_combine;
 use crate::pipelines::new::processors::transforms::hash_join::row::RowPtr;
 use crate::pipelines::new::processors::transforms::hash_join::row::RowSpace;
+use crate::pipelines::new::processors::transforms::hash_join::RuntimeFilter;
 use crate::pipelines::new::processors::HashJoinState;
 use crate::sessions::QueryContext;
+use crate::sql::plans::JoinType;
 
 pub struct ChainingHashTable {
     /// Reference count
     ref_count: Mutex<usize>,
     is_finished: Mutex<bool>,
+    /// Reference count of the probe side
+    probe_ref_count: Mutex<usize>,
 
+    join_type: JoinType,
     build_expressions: Vec<Expression>,
     probe_expressions: Vec<Expression>,
+    /// Filters pushed down to the probe side scan after the hash table is built
+    runtime_filters: Vec<RuntimeFilter>,
 
     ctx: Arc<QueryContext>,
 
     /// A shared big hash table stores all the rows from build side
     hash_table: RwLock<Vec<Vec<RowPtr>>>,
     row_space: RowSpace,
+    probe_data_schema: DataSchemaRef,
+
+    /// Both sides are spilled to disk once the build side exceeds the threshold, 0 disables
+    /// spilling
+    spill_threshold: usize,
+    build_bytes: AtomicUsize,
+    grace_partitions: Mutex<Option<GracePartitions>>,
 }
 
 impl ChainingHashTable {
     pub fn try_create(
+        join_type: JoinType,
         build_expressions: Vec<Expression>,
         probe_expressions: Vec<Expression>,
         build_data_schema: DataSchemaRef,
-        _probe_data_schema: DataSchemaRef,
+        probe_data_schema: DataSchemaRef,
+        runtime_filters: Vec<RuntimeFilter>,
         ctx: Arc<QueryContext>,
     ) -> Result<Self> {
-        Ok(Self {
+        let spill_threshold = ctx.get_settings().get_join_spill_threshold()? as usize;
+        Ok(Self::create(
+            join_type,
+            build_expressions,
+            probe_expressions,
+            build_data_schema,
+            probe_data_schema,
+            runtime_filters,
+            spill_threshold,
+            ctx,
+        ))
+    }
+
+    fn create(
+        join_type: JoinType,
+        build_expressions: Vec<Expression>,
+        probe_expressions: Vec<Expression>,
+        build_data_schema: DataSchemaRef,
+        probe_data_schema: DataSchemaRef,
+        runtime_filters: Vec<RuntimeFilter>,
+        spill_threshold: usize,
+        ctx: Arc<QueryContext>,
+    ) -> Self {
+        Self {
             row_space: RowSpace::new(build_data_schema),
+            probe_data_schema,
             ref_count: Mutex::new(0),
             is_finished: Mutex::new(false),
+            probe_ref_count: Mutex::new(0),
+            join_type,
             build_expressions,
             probe_expressions,
+            runtime_filters,
             ctx,
             hash_table: RwLock::new(vec![]),
-        })
+            spill_threshold,
+            build_bytes: AtomicUsize::new(0),
+            grace_partitions: Mutex::new(None),
+        }
+    }
+
+    /// The in memory hash table of a spilled partition.
+    fn create_partition_table(&self) -> Result<ChainingHashTable> {
+        Ok(Self::create(
+            self.join_type,
+            self.build_expressions.clone(),
+            self.probe_expressions.clone(),
+            self.row_space.data_schema.clone(),
+            self.probe_data_schema.clone(),
+            vec![],
+            0,
+            self.ctx.clone(),
+        ))
+    }
+
+    /// Moves the rows of the build side to the spilled partitions.
+    fn spill_row_space(&self) -> Result<GracePartitions> {
+        tracing::info!(
+            "Hash join build side exceeds {} bytes, spill to disk",
+            self.spill_threshold
+        );
+
+        let mut grace_partitions = GracePartitions::try_create(&self.ctx, self.join_type)?;
+        let chunks = std::mem::take(&mut *self.row_space.chunks.write().unwrap());
+        for chunk in chunks {
+            grace_partitions.spill_build(&chunk.data_block, &chunk.hash_values)?;
+        }
+        Ok(grace_partitions)
+    }
+
+    /// Joins the `i`-th probe row with the candidate rows of the same hash, returns true if
+    /// any of them matches. The joined rows are only produced by inner join.
+    fn probe_row(
+        &self,
+        input: &DataBlock,
+        i: usize,
+        probe_keys: &[ColumnRef],
+        probe_result_ptrs: &[RowPtr],
+        results: &mut Vec<DataBlock>,
+    ) -> Result<bool> {
+        let result_block = self.row_space.gather(probe_result_ptrs)?;
+
+        let probe_block = DataBlock::block_take_by_indices(input, &[i as u32])?;
+        let mut replicated_probe_block = DataBlock::empty();
+        for (i, col) in probe_block.columns().iter().enumerate() {
+            let replicated_col = ConstColumn::new(col.clone(), result_block.num_rows()).arc();
+
+            replicated_probe_block = replicated_probe_block
+                .add_column(replicated_col, probe_block.schema().field(i).clone())?;
+        }
+
+        let build_keys = self
+            .build_expressions
+            .iter()
+            .map(|expr| {
+                ExpressionEvaluator::eval(self.ctx.try_get_function_context()?, expr, &result_block)
+            })
+            .collect::<Result<Vec<ColumnRef>>>()?;
+
+        let current_probe_keys: Vec<ColumnRef> = probe_keys
+            .iter()
+            .map(|col| {
+                let column = col.slice(i, 1);
+                ConstColumn::new(column, result_block.num_rows()).arc()
+            })
+            .collect();
+
+        let output = compare_and_combine(
+            replicated_probe_block,
+            result_block,
+            &build_keys,
+            &current_probe_keys,
+            self.ctx.clone(),
+        )?;
+
+        let matched = output.num_rows() != 0;
+        if self.join_type == JoinType::Inner {
+            results.push(output);
+        }
+        Ok(matched)
     }
 
     fn hash(&self, columns: &[ColumnRef], row_count: usize) -> Result<HashVector> {
@@ -93,6 +224,7 @@ impl ChainingHashTable {
     }
 }
 
+#[async_trait::async_trait]
 impl HashJoinState for ChainingHashTable {
     fn build(&self, input: DataBlock) -> Result<()> {
         let build_keys = self
@@ -103,8 +235,27 @@ impl HashJoinState for ChainingHashTable {
             })
             .collect::<Result<Vec<ColumnRef>>>()?;
 
+        for runtime_filter in &self.runtime_filters {
+            runtime_filter.accumulate(&build_keys[runtime_filter.key_index()])?;
+        }
+
         let hash_values = self.hash(&build_keys, input.num_rows())?;
 
+        if self.spill_threshold != 0 {
+            let mut grace_partitions = self.grace_partitions.lock().unwrap();
+            if grace_partitions.is_none() {
+                let input_bytes = input.memory_size();
+                let build_bytes = self.build_bytes.fetch_add(input_bytes, Ordering::Relaxed);
+                if build_bytes + input_bytes >= self.spill_threshold {
+                    *grace_partitions = Some(self.spill_row_space()?);
+                }
+            }
+
+            if let Some(grace_partitions) = grace_partitions.as_mut() {
+                return grace_partitions.spill_build(&input, &hash_values);
+            }
+        }
+
         self.row_space.push(input, hash_values)?;
 
         Ok(())
@@ -119,57 +270,36 @@ impl HashJoinState for ChainingHashTable {
             })
             .collect::<Result<Vec<ColumnRef>>>()?;
 
+        if self.spill_threshold != 0 {
+            if let Some(grace_partitions) = self.grace_partitions.lock().unwrap().as_mut() {
+                let hash_values = self.hash(&probe_keys, input.num_rows())?;
+                grace_partitions.spill_probe(input, &hash_values)?;
+                return Ok(vec![]);
+            }
+        }
+
         let hash_table = self.hash_table.read().unwrap();
         let hash_values = self.hash(&probe_keys, input.num_rows())?;
         let hash_values =
             ChainingHashTable::apply_bitmask(&hash_values, (hash_table.len() - 1) as u64);
 
         let mut results: Vec<DataBlock> = vec![];
+        // Indices of the probe rows which are matched by semi join, or unmatched by anti join
+        let mut probe_indices: Vec<u32> = vec![];
         for (i, hash_value) in hash_values.iter().enumerate().take(input.num_rows()) {
             let probe_result_ptrs = hash_table[*hash_value as usize].as_slice();
-            if probe_result_ptrs.is_empty() {
-                // No matched row for current probe row
-                continue;
-            }
-            let result_block = self.row_space.gather(probe_result_ptrs)?;
-
-            let probe_block = DataBlock::block_take_by_indices(input, &[i as u32])?;
-            let mut replicated_probe_block = DataBlock::empty();
-            for (i, col) in probe_block.columns().iter().enumerate() {
-                let replicated_col = ConstColumn::new(col.clone(), result_block.num_rows()).arc();
+            let matched = !probe_result_ptrs.is_empty()
+                && self.probe_row(input, i, &probe_keys, probe_result_ptrs, &mut results)?;
 
-                replicated_probe_block = replicated_probe_block
-                    .add_column(replicated_col, probe_block.schema().field(i).clone())?;
+            match self.join_type {
+                JoinType::Semi if matched => probe_indices.push(i as u32),
+                JoinType::Anti if !matched => probe_indices.push(i as u32),
+                _ => {}
             }
+        }
 
-            let build_keys = self
-                .build_expressions
-                .iter()
-                .map(|expr| {
-                    ExpressionEvaluator::eval(
-                        self.ctx.try_get_function_context()?,
-                        expr,
-                        &result_block,
-                    )
-                })
-                .collect::<Result<Vec<ColumnRef>>>()?;
-
-            let current_probe_keys: Vec<ColumnRef> = probe_keys
-                .iter()
-                .map(|col| {
-                    let column = col.slice(i, 1);
-                    ConstColumn::new(column, result_block.num_rows()).arc()
-                })
-                .collect();
-
-            let output = compare_and_combine(
-                replicated_probe_block,
-                result_block,
-                &build_keys,
-                &current_probe_keys,
-                self.ctx.clone(),
-            )?;
-            results.push(output);
+        if !probe_indices.is_empty() {
+            results.push(DataBlock::block_take_by_indices(input, &probe_indices)?);
         }
 
         Ok(results)
@@ -198,6 +328,70 @@ impl HashJoinState for ChainingHashTable {
         Ok(*self.is_finished.lock().unwrap())
     }
 
+    fn attach_probe(&self) -> Result<()> {
+        let mut count = self.probe_ref_count.lock().unwrap();
+        *count += 1;
+        Ok(())
+    }
+
+    fn detach_probe(&self) -> Result<bool> {
+        let mut count = self.probe_ref_count.lock().unwrap();
+        *count -= 1;
+        Ok(*count == 0 && self.grace_partitions.lock().unwrap().is_some())
+    }
+
+    fn has_pending_io(&self) -> bool {
+        match self.grace_partitions.lock().unwrap().as_ref() {
+            None => false,
+            Some(grace_partitions) => grace_partitions.has_pending_io(),
+        }
+    }
+
+    async fn flush_spilled(&self) -> Result<()> {
+        let pending_io = {
+            let mut grace_partitions = self.grace_partitions.lock().unwrap();
+            match grace_partitions.as_mut() {
+                None => None,
+                Some(grace_partitions) => grace_partitions.take_pending_io(),
+            }
+        };
+
+        if let Some(pending_io) = pending_io {
+            // Nothing is read back while the blocks are spilled
+            pending_io.execute().await?;
+        }
+        Ok(())
+    }
+
+    async fn join_spilled(&self) -> Result<Option<Vec<DataBlock>>> {
+        loop {
+            let pending_io = {
+                let mut grace_partitions = self.grace_partitions.lock().unwrap();
+                let grace_partitions = match grace_partitions.as_mut() {
+                    None => return Ok(None),
+                    Some(grace_partitions) => grace_partitions,
+                };
+
+                if let Some(blocks) =
+                    grace_partitions.join_next(|| self.create_partition_table())?
+                {
+                    return Ok(Some(blocks));
+                }
+
+                match grace_partitions.take_pending_io() {
+                    None => return Ok(None),
+                    Some(pending_io) => pending_io,
+                }
+            };
+
+            let result = pending_io.execute().await?;
+            let mut grace_partitions = self.grace_partitions.lock().unwrap();
+            if let Some(grace_partitions) = grace_partitions.as_mut() {
+                grace_partitions.complete_io(result);
+            }
+        }
+    }
+
     fn finish(&self) -> Result<()> {
         let mut hash_table = self.hash_table.write().unwrap();
         hash_table.resize(
@@ -225,6 +419,10 @@ impl HashJoinState for ChainingHashTable {
             }
         }
 
+        for runtime_filter in &self.runtime_filters {
+            runtime_filter.push_down()?;
+        }
+
         Ok(())
     }
 }
@@ -0,0 +1,204 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_datablocks::DataBlock;
+use common_exception::Result;
+
+use crate::pipelines::new::processors::transforms::hash_join::hash::HashVector;
+use crate::pipelines::new::processors::transforms::hash_join::ChainingHashTable;
+use crate::pipelines::new::processors::transforms::spiller::SpillIo;
+use crate::pipelines::new::processors::transforms::spiller::SpillIoResult;
+use crate::pipelines::new::processors::transforms::spiller::Spiller;
+use crate::pipelines::new::processors::HashJoinState;
+use crate::sessions::QueryContext;
+use crate::sql::plans::JoinType;
+
+/// Number of partitions the rows of a spilled hash join are partitioned into.
+pub const GRACE_PARTITIONS: usize = 32;
+
+/// The spilled partitions of a grace hash join.
+///
+/// The build and probe rows are partitioned by the hash of the join keys, so the matched rows
+/// are always in the partitions of the same index. Once both sides are spilled, the partitions
+/// are joined one by one, with an in memory hash table of the build partition.
+///
+/// The partitions are shared by the processors of the join, so the pending IO of the spillers
+/// is taken out of the lock to be executed, see `take_pending_io`.
+pub struct GracePartitions {
+    join_type: JoinType,
+    build_spiller: Spiller,
+    probe_spiller: Spiller,
+    build_rows: Vec<usize>,
+
+    next_partition: usize,
+    // The hash table of the build partition being read back
+    building_table: Option<(usize, ChainingHashTable)>,
+    partition_table: Option<(usize, ChainingHashTable)>,
+}
+
+/// The pending IO of the build and probe spillers.
+pub struct GracePendingIo {
+    build: Option<SpillIo>,
+    probe: Option<SpillIo>,
+}
+
+pub struct GraceIoResult {
+    build: Option<SpillIoResult>,
+    probe: Option<SpillIoResult>,
+}
+
+impl GracePendingIo {
+    pub async fn execute(self) -> Result<GraceIoResult> {
+        let build = match self.build {
+            None => None,
+            Some(io) => Some(io.execute().await?),
+        };
+        let probe = match self.probe {
+            None => None,
+            Some(io) => Some(io.execute().await?),
+        };
+        Ok(GraceIoResult { build, probe })
+    }
+}
+
+impl GracePartitions {
+    pub fn try_create(ctx: &Arc<QueryContext>, join_type: JoinType) -> Result<GracePartitions> {
+        Ok(GracePartitions {
+            join_type,
+            build_spiller: Spiller::try_create(ctx, "join_build")?,
+            probe_spiller: Spiller::try_create(ctx, "join_probe")?,
+            build_rows: vec![0; GRACE_PARTITIONS],
+            next_partition: 0,
+            building_table: None,
+            partition_table: None,
+        })
+    }
+
+    pub fn spill_build(&mut self, block: &DataBlock, hash_values: &HashVector) -> Result<()> {
+        let scattered = Self::scatter(block, hash_values)?;
+        for (partition, block) in scattered.into_iter().enumerate() {
+            if block.num_rows() != 0 {
+                self.build_rows[partition] += block.num_rows();
+                self.build_spiller.spill(partition, block)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn spill_probe(&mut self, block: &DataBlock, hash_values: &HashVector) -> Result<()> {
+        let scattered = Self::scatter(block, hash_values)?;
+        for (partition, block) in scattered.into_iter().enumerate() {
+            // The probe rows never match if the build partition is empty, which are only
+            // returned by anti join.
+            if block.num_rows() != 0
+                && (self.build_rows[partition] != 0 || self.join_type == JoinType::Anti)
+            {
+                self.probe_spiller.spill(partition, block)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn has_pending_io(&self) -> bool {
+        self.build_spiller.has_pending_io() || self.probe_spiller.has_pending_io()
+    }
+
+    pub fn take_pending_io(&mut self) -> Option<GracePendingIo> {
+        match self.has_pending_io() {
+            false => None,
+            true => Some(GracePendingIo {
+                build: self.build_spiller.take_pending_io(),
+                probe: self.probe_spiller.take_pending_io(),
+            }),
+        }
+    }
+
+    pub fn complete_io(&mut self, result: GraceIoResult) {
+        if let Some(result) = result.build {
+            self.build_spiller.complete_io(result);
+        }
+        if let Some(result) = result.probe {
+            self.probe_spiller.complete_io(result);
+        }
+    }
+
+    /// Joins the next spilled probe block, returns `None` if all the partitions are joined, or
+    /// if it's waiting for a spilled block to be read back, in which case `has_pending_io`
+    /// is true.
+    ///
+    /// `create_table` creates the in memory hash table of a build partition.
+    pub fn join_next<F>(&mut self, create_table: F) -> Result<Option<Vec<DataBlock>>>
+    where F: Fn() -> Result<ChainingHashTable> {
+        loop {
+            if self.partition_table.is_none() {
+                if self.building_table.is_none() {
+                    match self.next_build_partition() {
+                        None => return Ok(None),
+                        Some(partition) => self.building_table = Some((partition, create_table()?)),
+                    }
+                }
+
+                if let Some((partition, table)) = &self.building_table {
+                    while let Some(block) = self.build_spiller.restore(*partition)? {
+                        table.build(block)?;
+                    }
+                }
+
+                if self.build_spiller.has_pending_io() {
+                    return Ok(None);
+                }
+
+                if let Some((partition, table)) = self.building_table.take() {
+                    table.finish()?;
+                    self.partition_table = Some((partition, table));
+                }
+            }
+
+            if let Some((partition, table)) = &self.partition_table {
+                if let Some(block) = self.probe_spiller.restore(*partition)? {
+                    return Ok(Some(table.probe(&block)?));
+                }
+
+                if self.probe_spiller.has_pending_io() {
+                    return Ok(None);
+                }
+            }
+
+            self.partition_table = None;
+        }
+    }
+
+    fn next_build_partition(&mut self) -> Option<usize> {
+        while self.next_partition < GRACE_PARTITIONS {
+            let partition = self.next_partition;
+            self.next_partition += 1;
+
+            if self.build_rows[partition] != 0 || self.join_type == JoinType::Anti {
+                return Some(partition);
+            }
+        }
+        None
+    }
+
+    fn scatter(block: &DataBlock, hash_values: &HashVector) -> Result<Vec<DataBlock>> {
+        // The low bits of the hash are used by the hash table of the partition.
+        let indices = hash_values
+            .iter()
+            .map(|hash| ((hash >> 32) as usize) % GRACE_PARTITIONS)
+            .collect::<Vec<_>>();
+        DataBlock::scatter_block(block, &indices, GRACE_PARTITIONS)
+    }
+}
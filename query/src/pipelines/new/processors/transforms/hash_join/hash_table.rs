@@ -16,6 +16,7 @@ use common_datablocks::DataBlock;
 use common_exception::Result;
 
 /// Concurrent hash table for hash join.
+#[async_trait::async_trait]
 pub trait HashJoinState: Send + Sync {
     /// Build hash table with input DataBlock
     fn build(&self, input: DataBlock) -> Result<()>;
@@ -35,4 +36,21 @@ pub trait HashJoinState: Send + Sync {
     /// Finish building hash table, will be called only once as soon as all handles
     /// have been detached from current state.
     fn finish(&self) -> Result<()>;
+
+    /// Attach a probe handle to state
+    fn attach_probe(&self) -> Result<()>;
+
+    /// Detach a probe handle from state, returns true if it's the last probe handle and
+    /// there are spilled partitions to join.
+    fn detach_probe(&self) -> Result<bool>;
+
+    /// Returns true if the blocks spilled by `build` or `probe` are waiting to be written.
+    fn has_pending_io(&self) -> bool;
+
+    /// Write the blocks spilled by `build` and `probe`, a handle must flush the blocks it
+    /// spilled before detaching from state.
+    async fn flush_spilled(&self) -> Result<()>;
+
+    /// Join the next block of the spilled partitions, returns `None` if all of them are joined.
+    async fn join_spilled(&self) -> Result<Option<Vec<DataBlock>>>;
 }
@@ -13,9 +13,12 @@
 // limitations under the License.
 
 mod chaining_hash_table;
+mod grace_hash_join;
 mod hash;
 mod hash_table;
 mod row;
+mod runtime_filter;
 
 pub use chaining_hash_table::ChainingHashTable;
 pub use hash_table::HashJoinState;
+pub use runtime_filter::RuntimeFilter;
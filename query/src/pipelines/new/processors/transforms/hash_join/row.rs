@@ -144,20 +144,6 @@ pub fn compare_and_combine(
         compare_exprs.push(compare_expr);
     }
 
-    let predicate = compare_exprs
-        .into_iter()
-        .reduce(|prev, next| Expression::BinaryExpression {
-            left: Box::new(prev),
-            op: "and".to_string(),
-            right: Box::new(next),
-        })
-        .unwrap();
-
-    let data_block = DataBlock::create(Arc::new(DataSchema::new(data_fields)), columns);
-
-    let filter =
-        ExpressionEvaluator::eval(ctx.try_get_function_context()?, &predicate, &data_block)?;
-
     let mut produce_block = probe_input;
     for (col, field) in probe_result
         .columns()
@@ -166,7 +152,22 @@ pub fn compare_and_combine(
     {
         produce_block = produce_block.add_column(col.clone(), field.clone())?;
     }
-    produce_block = DataBlock::filter_block(&produce_block, &filter)?;
+
+    // All the rows are matched if there is no join key
+    let predicate = compare_exprs
+        .into_iter()
+        .reduce(|prev, next| Expression::BinaryExpression {
+            left: Box::new(prev),
+            op: "and".to_string(),
+            right: Box::new(next),
+        });
+
+    if let Some(predicate) = predicate {
+        let data_block = DataBlock::create(Arc::new(DataSchema::new(data_fields)), columns);
+        let filter =
+            ExpressionEvaluator::eval(ctx.try_get_function_context()?, &predicate, &data_block)?;
+        produce_block = DataBlock::filter_block(&produce_block, &filter)?;
+    }
 
     Ok(produce_block)
 }
@@ -0,0 +1,111 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cmp::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use common_datavalues::prelude::*;
+use common_exception::Result;
+use common_functions::aggregates::eval_aggr;
+use common_planners::col;
+use common_planners::lit;
+use common_planners::Expression;
+use common_tracing::tracing;
+
+use crate::sessions::QueryContext;
+
+/// The min/max range of a join key on the build side, pushed down to the scan of the
+/// probe side column once the hash table is built.
+///
+/// The probe side scan skips the blocks whose statistics don't overlap the range.
+pub struct RuntimeFilter {
+    /// Index of the key in the build expressions
+    key_index: usize,
+    /// Name of the probe side column in the scanned table
+    column_name: String,
+    /// The context of the probe side scan
+    scan_ctx: Arc<QueryContext>,
+    range: Mutex<Option<(DataValue, DataValue)>>,
+}
+
+impl RuntimeFilter {
+    pub fn create(key_index: usize, column_name: String, scan_ctx: Arc<QueryContext>) -> Self {
+        RuntimeFilter {
+            key_index,
+            column_name,
+            scan_ctx,
+            range: Mutex::new(None),
+        }
+    }
+
+    pub fn key_index(&self) -> usize {
+        self.key_index
+    }
+
+    /// Accumulates the range of the build keys.
+    pub fn accumulate(&self, keys: &ColumnRef) -> Result<()> {
+        let rows = keys.len();
+        if rows == 0 {
+            return Ok(());
+        }
+
+        let field = DataField::new("key", keys.data_type());
+        let column_field = ColumnWithField::new(keys.clone(), field);
+        let mins = eval_aggr("min", vec![], &[column_field.clone()], rows)?;
+        let maxs = eval_aggr("max", vec![], &[column_field], rows)?;
+
+        if mins.len() == 0 || maxs.len() == 0 {
+            return Ok(());
+        }
+
+        let (min, max) = (mins.get(0), maxs.get(0));
+        if min.is_null() || max.is_null() {
+            // All the keys are null, which never match.
+            return Ok(());
+        }
+
+        let mut range = self.range.lock().unwrap();
+        *range = match range.take() {
+            None => Some((min, max)),
+            Some((range_min, range_max)) => {
+                let min = match min.partial_cmp(&range_min) {
+                    Some(Ordering::Less) => min,
+                    _ => range_min,
+                };
+                let max = match max.partial_cmp(&range_max) {
+                    Some(Ordering::Greater) => max,
+                    _ => range_max,
+                };
+                Some((min, max))
+            }
+        };
+        Ok(())
+    }
+
+    /// Pushes the accumulated range to the probe side scan.
+    pub fn push_down(&self) -> Result<()> {
+        let filter = match self.range.lock().unwrap().take() {
+            // No key on the build side, nothing can be matched.
+            None => lit(false),
+            Some((min, max)) => col(&self.column_name)
+                .gt_eq(Expression::create_literal(min))
+                .and(col(&self.column_name).lt_eq(Expression::create_literal(max))),
+        };
+
+        tracing::debug!("Push down runtime filter {:?}", filter);
+        self.scan_ctx.push_runtime_filter(filter);
+        Ok(())
+    }
+}
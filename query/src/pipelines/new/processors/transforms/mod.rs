@@ -14,6 +14,7 @@
 
 mod aggregator;
 mod hash_join;
+mod spiller;
 mod transform;
 mod transform_addon;
 mod transform_aggregator;
@@ -21,19 +22,25 @@ mod transform_block_compact;
 mod transform_cast_schema;
 mod transform_compact;
 mod transform_create_sets;
+mod transform_cte;
 mod transform_dummy;
+mod transform_expand_grouping_sets;
 mod transform_expression;
 mod transform_filter;
 mod transform_hash_join;
 mod transform_limit;
 mod transform_limit_by;
+mod transform_multi_sort_merge;
+mod transform_set_operation;
 mod transform_sort_merge;
 mod transform_sort_partial;
+mod transform_sort_spill;
 
 pub use aggregator::AggregatorParams;
 pub use aggregator::AggregatorTransformParams;
 pub use hash_join::ChainingHashTable;
 pub use hash_join::HashJoinState;
+pub use hash_join::RuntimeFilter;
 pub use transform_addon::TransformAddOn;
 pub use transform_aggregator::TransformAggregator;
 pub use transform_block_compact::BlockCompactor;
@@ -43,7 +50,12 @@ pub use transform_compact::Compactor;
 pub use transform_compact::TransformCompact;
 pub use transform_create_sets::SubQueriesPuller;
 pub use transform_create_sets::TransformCreateSets;
+pub use transform_cte::CteState;
+pub use transform_cte::RecursiveIteration;
+pub use transform_cte::SinkCte;
+pub use transform_cte::TransformRecursiveCte;
 pub use transform_dummy::TransformDummy;
+pub use transform_expand_grouping_sets::TransformExpandGroupingSets;
 pub use transform_expression::ExpressionTransform;
 pub use transform_expression::ProjectionTransform;
 pub use transform_filter::TransformFilter;
@@ -52,6 +64,13 @@ pub use transform_hash_join::SinkBuildHashTable;
 pub use transform_hash_join::TransformHashJoinProbe;
 pub use transform_limit::TransformLimit;
 pub use transform_limit_by::TransformLimitBy;
+pub use transform_multi_sort_merge::TransformMultiSortMerge;
+pub use transform_set_operation::SetOperationState;
+pub use transform_set_operation::SinkSetOperation;
+pub use transform_set_operation::TransformDistinct;
+pub use transform_set_operation::TransformIntersectExcept;
+pub use transform_set_operation::TransformUnionAll;
 pub use transform_sort_merge::SortMergeCompactor;
 pub use transform_sort_merge::TransformSortMerge;
 pub use transform_sort_partial::TransformSortPartial;
+pub use transform_sort_spill::TransformSortSpill;
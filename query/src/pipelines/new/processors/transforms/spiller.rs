@@ -0,0 +1,253 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::VecDeque;
+use std::io::Cursor;
+use std::sync::Arc;
+
+use common_arrow::arrow::chunk::Chunk;
+use common_arrow::arrow::io::ipc::read::read_stream_metadata;
+use common_arrow::arrow::io::ipc::read::StreamReader;
+use common_arrow::arrow::io::ipc::read::StreamState;
+use common_arrow::arrow::io::ipc::write::StreamWriter;
+use common_arrow::arrow::io::ipc::write::WriteOptions;
+use common_base::base::Runtime;
+use common_base::base::TrySpawn;
+use common_datablocks::DataBlock;
+use common_datavalues::DataSchemaRef;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_tracing::tracing;
+use opendal::Operator;
+use uuid::Uuid;
+
+use crate::sessions::QueryContext;
+
+/// Spills the blocks of the intermediate data to the spill operator, which is the local disk
+/// if `spill_local_path` is configured, or the storage otherwise.
+///
+/// The blocks are written into buckets, and read back in the written order of each bucket.
+/// Each spilled file is removed after it is read back, the remaining files are removed when
+/// the spiller is dropped.
+///
+/// `spill` and `restore` never wait for the storage, they leave the writes and the read to the
+/// pending IO, which the processors execute in `async_process` once `has_pending_io` is true.
+pub struct Spiller {
+    operator: Operator,
+    runtime: Arc<Runtime>,
+    location_prefix: String,
+    schema: Option<DataSchemaRef>,
+    buckets: Vec<VecDeque<String>>,
+    spilled_files: usize,
+
+    pending_writes: Vec<(String, Vec<u8>)>,
+    // The bucket whose next block is waiting to be read
+    restoring: Option<usize>,
+    // The block read back but not taken by `restore` yet
+    restored: Option<(usize, Vec<u8>)>,
+}
+
+impl Spiller {
+    /// Creates the spiller of the processor, `kind` is the kind of the spilled data.
+    pub fn try_create(ctx: &Arc<QueryContext>, kind: &str) -> Result<Spiller> {
+        Ok(Spiller {
+            operator: ctx.get_spill_operator()?,
+            runtime: ctx.get_storage_runtime(),
+            location_prefix: format!("_spill/{}/{}/{}", kind, ctx.get_id(), Uuid::new_v4()),
+            schema: None,
+            buckets: vec![],
+            spilled_files: 0,
+            pending_writes: vec![],
+            restoring: None,
+            restored: None,
+        })
+    }
+
+    pub fn num_buckets(&self) -> usize {
+        self.buckets.len()
+    }
+
+    /// Spills the block into the bucket, the block is written by the next pending IO.
+    pub fn spill(&mut self, bucket: usize, block: DataBlock) -> Result<()> {
+        if self.schema.is_none() {
+            self.schema = Some(block.schema().clone());
+        }
+
+        if bucket >= self.buckets.len() {
+            self.buckets.resize(bucket + 1, VecDeque::new());
+        }
+
+        let location = format!("{}/{}_{}", self.location_prefix, bucket, self.spilled_files);
+        let data = Self::serialize_block(block)?;
+        self.spilled_files += 1;
+        self.buckets[bucket].push_back(location.clone());
+        self.pending_writes.push((location, data));
+        Ok(())
+    }
+
+    /// Takes the next spilled block of the bucket if it has been read back.
+    ///
+    /// Returns `None` if all the blocks of the bucket have been read back, or if the next block
+    /// is not read back yet, in which case `has_pending_io` becomes true.
+    pub fn restore(&mut self, bucket: usize) -> Result<Option<DataBlock>> {
+        if let Some((restored_bucket, data)) = self.restored.take() {
+            if restored_bucket == bucket {
+                return match &self.schema {
+                    Some(schema) => Ok(Some(Self::deserialize_block(schema, data)?)),
+                    None => Err(ErrorCode::LogicalError(
+                        "Logical error: spilled blocks without schema",
+                    )),
+                };
+            }
+            self.restored = Some((restored_bucket, data));
+        }
+
+        if matches!(self.buckets.get(bucket), Some(locations) if !locations.is_empty()) {
+            self.restoring = Some(bucket);
+        }
+        Ok(None)
+    }
+
+    pub fn has_pending_io(&self) -> bool {
+        !self.pending_writes.is_empty() || self.restoring.is_some()
+    }
+
+    /// Takes the pending writes and read, they are executed out of the spiller so that the
+    /// spiller shared by the processors is not locked while waiting for the storage.
+    pub fn take_pending_io(&mut self) -> Option<SpillIo> {
+        if !self.has_pending_io() {
+            return None;
+        }
+
+        let read = match self.restoring.take() {
+            None => None,
+            Some(bucket) => self.buckets[bucket]
+                .pop_front()
+                .map(|location| (bucket, location)),
+        };
+
+        Some(SpillIo {
+            operator: self.operator.clone(),
+            writes: std::mem::take(&mut self.pending_writes),
+            read,
+        })
+    }
+
+    pub fn complete_io(&mut self, result: SpillIoResult) {
+        if let Some(restored) = result.restored {
+            self.restored = Some(restored);
+        }
+    }
+
+    /// Executes the pending IO of a spiller owned by the processor.
+    pub async fn process_io(&mut self) -> Result<()> {
+        if let Some(io) = self.take_pending_io() {
+            let result = io.execute().await?;
+            self.complete_io(result);
+        }
+        Ok(())
+    }
+
+    fn serialize_block(block: DataBlock) -> Result<Vec<u8>> {
+        let schema = block.schema().to_arrow();
+        let chunk = Chunk::try_from(block)?;
+
+        let mut data = vec![];
+        {
+            let options = WriteOptions { compression: None };
+            let mut writer = StreamWriter::new(&mut data, options);
+            writer.start(&schema, None)?;
+            writer.write(&chunk, None)?;
+            writer.finish()?;
+        }
+        Ok(data)
+    }
+
+    fn deserialize_block(schema: &DataSchemaRef, data: Vec<u8>) -> Result<DataBlock> {
+        let mut reader = Cursor::new(data);
+        let metadata = read_stream_metadata(&mut reader)?;
+        let stream = StreamReader::new(reader, metadata);
+
+        let mut blocks = vec![];
+        for state in stream {
+            match state? {
+                StreamState::Some(chunk) => blocks.push(DataBlock::from_chunk(schema, &chunk)?),
+                StreamState::Waiting => break,
+            }
+        }
+
+        DataBlock::concat_blocks(&blocks)
+    }
+}
+
+/// The writes and the read taken from a spiller, the writes are executed before the read.
+pub struct SpillIo {
+    operator: Operator,
+    writes: Vec<(String, Vec<u8>)>,
+    read: Option<(usize, String)>,
+}
+
+pub struct SpillIoResult {
+    restored: Option<(usize, Vec<u8>)>,
+}
+
+impl SpillIo {
+    pub async fn execute(self) -> Result<SpillIoResult> {
+        for (location, data) in self.writes {
+            self.operator.object(&location).write(data).await?;
+            tracing::debug!("Spilled block to {}", location);
+        }
+
+        let restored = match self.read {
+            None => None,
+            Some((bucket, location)) => {
+                let object = self.operator.object(&location);
+                let data = object.range_read(..).await?;
+                object.delete().await?;
+                Some((bucket, data))
+            }
+        };
+
+        Ok(SpillIoResult { restored })
+    }
+}
+
+impl Drop for Spiller {
+    fn drop(&mut self) {
+        // The pending writes have not been written yet
+        let locations = self
+            .buckets
+            .iter()
+            .flatten()
+            .filter(|location| {
+                !self
+                    .pending_writes
+                    .iter()
+                    .any(|(pending, _)| pending == *location)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if !locations.is_empty() {
+            let operator = self.operator.clone();
+            let _ = self.runtime.try_spawn(async move {
+                for location in locations {
+                    if let Err(cause) = operator.object(&location).delete().await {
+                        tracing::warn!("Cannot remove spilled file {}: {}", location, cause);
+                    }
+                }
+            });
+        }
+    }
+}
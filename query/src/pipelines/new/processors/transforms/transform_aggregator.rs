@@ -24,6 +24,7 @@ use crate::pipelines::new::processors::port::OutputPort;
 use crate::pipelines::new::processors::processor::Event;
 use crate::pipelines::new::processors::processor::ProcessorPtr;
 use crate::pipelines::new::processors::transforms::aggregator::*;
+use crate::pipelines::new::processors::transforms::spiller::Spiller;
 use crate::pipelines::new::processors::AggregatorTransformParams;
 use crate::pipelines::new::processors::Processor;
 use crate::sessions::QueryContext;
@@ -205,6 +206,15 @@ pub trait Aggregator: Sized + Send {
 
     fn consume(&mut self, data: DataBlock) -> Result<()>;
     fn generate(&mut self) -> Result<Option<DataBlock>>;
+
+    /// The spiller of the aggregator, its pending IO is executed in `async_process`.
+    fn spiller(&mut self) -> Option<&mut Spiller> {
+        None
+    }
+}
+
+fn has_pending_io<TAggregator: Aggregator>(inner: &mut TAggregator) -> bool {
+    matches!(inner.spiller(), Some(spiller) if spiller.has_pending_io())
 }
 
 enum AggregatorTransform<TAggregator: Aggregator> {
@@ -246,6 +256,7 @@ impl<TAggregator: Aggregator + 'static> AggregatorTransform<TAggregator> {
     }
 }
 
+#[async_trait::async_trait]
 impl<TAggregator: Aggregator + 'static> Processor for AggregatorTransform<TAggregator> {
     fn name(&self) -> &'static str {
         TAggregator::NAME
@@ -266,12 +277,29 @@ impl<TAggregator: Aggregator + 'static> Processor for AggregatorTransform<TAggre
             AggregatorTransform::Generate(state) => state.generate(),
         }
     }
+
+    async fn async_process(&mut self) -> Result<()> {
+        let spiller = match self {
+            AggregatorTransform::Finished => None,
+            AggregatorTransform::ConsumeData(state) => state.inner.spiller(),
+            AggregatorTransform::Generate(state) => state.inner.spiller(),
+        };
+
+        match spiller {
+            None => Ok(()),
+            Some(spiller) => spiller.process_io().await,
+        }
+    }
 }
 
 impl<TAggregator: Aggregator + 'static> AggregatorTransform<TAggregator> {
     #[inline(always)]
     fn consume_event(&mut self) -> Result<Event> {
         if let AggregatorTransform::ConsumeData(state) = self {
+            if has_pending_io(&mut state.inner) {
+                return Ok(Event::Async);
+            }
+
             if state.input_data_block.is_some() {
                 return Ok(Event::Sync);
             }
@@ -318,6 +346,10 @@ impl<TAggregator: Aggregator + 'static> AggregatorTransform<TAggregator> {
                 return Ok(Event::NeedConsume);
             }
 
+            if has_pending_io(&mut state.inner) {
+                return Ok(Event::Async);
+            }
+
             if state.is_finished {
                 if !state.output_port.is_finished() {
                     state.output_port.finish();
@@ -363,7 +395,7 @@ impl<TAggregator: Aggregator> GenerateState<TAggregator> {
     pub fn generate(&mut self) -> Result<()> {
         let generate_data = self.inner.generate()?;
 
-        if generate_data.is_none() {
+        if generate_data.is_none() && !has_pending_io(&mut self.inner) {
             self.is_finished = true;
         }
 
@@ -32,6 +32,8 @@ use crate::pipelines::new::processors::port::OutputPort;
 use crate::pipelines::new::processors::processor::ProcessorPtr;
 use crate::pipelines::new::processors::transforms::transform::Transform;
 use crate::pipelines::new::processors::transforms::transform::Transformer;
+use crate::pipelines::new::NewPipeline;
+use crate::pipelines::new::QueryPipelineBuilder;
 use crate::sessions::QueryContext;
 
 pub struct TransformCreateSets {
@@ -99,13 +101,25 @@ impl SubQueriesPuller {
         })
     }
 
+    fn create_pipeline(ctx: Arc<QueryContext>, plan: SelectPlan) -> Result<NewPipeline> {
+        match ctx.get_cluster().is_empty() {
+            true => SelectInterpreter::try_create(ctx, plan)?.create_new_pipeline(),
+            false => {
+                // The subqueries have been optimized and scheduled with the query.
+                let max_threads = ctx.get_settings().get_max_threads()? as usize;
+                let mut pipeline = QueryPipelineBuilder::create(ctx).finalize(&plan)?;
+                pipeline.set_max_threads(max_threads);
+                Ok(pipeline)
+            }
+        }
+    }
+
     fn receive_subquery(&self, plan: SelectPlan) -> Result<DataValue> {
         let schema = plan.schema();
         let subquery_ctx = QueryContext::create_from(self.ctx.clone());
         let async_runtime = subquery_ctx.get_storage_runtime();
 
-        let interpreter = SelectInterpreter::try_create(subquery_ctx, plan)?;
-        let query_pipeline = interpreter.create_new_pipeline()?;
+        let query_pipeline = Self::create_pipeline(subquery_ctx, plan)?;
         let mut query_executor =
             PipelinePullingExecutor::try_create(async_runtime, query_pipeline)?;
 
@@ -142,8 +156,7 @@ impl SubQueriesPuller {
         let subquery_ctx = QueryContext::create_from(self.ctx.clone());
         let async_runtime = subquery_ctx.get_storage_runtime();
 
-        let interpreter = SelectInterpreter::try_create(subquery_ctx, plan)?;
-        let query_pipeline = interpreter.create_new_pipeline()?;
+        let query_pipeline = Self::create_pipeline(subquery_ctx, plan)?;
 
         let mut query_executor =
             PipelinePullingExecutor::try_create(async_runtime, query_pipeline)?;
@@ -0,0 +1,252 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use common_base::infallible::RwLock;
+use common_datablocks::DataBlock;
+use common_datavalues::Series;
+use common_datavalues::SeriesFrom;
+use common_exception::ErrorCode;
+use common_exception::Result;
+
+use crate::pipelines::new::processors::port::InputPort;
+use crate::pipelines::new::processors::port::OutputPort;
+use crate::pipelines::new::processors::processor::Event;
+use crate::pipelines::new::processors::processor::ProcessorPtr;
+use crate::pipelines::new::processors::transforms::transform_set_operation::build_row_keys;
+use crate::pipelines::new::processors::Processor;
+use crate::pipelines::new::processors::Sink;
+use crate::sessions::QueryContext;
+
+/// The rows of a materialized CTE, or the work table of a recursive CTE.
+///
+/// The rows are collected by a sub-pipeline which is finished before they are read, every
+/// reference to the CTE reads all the blocks.
+pub struct CteState {
+    blocks: RwLock<Vec<DataBlock>>,
+}
+
+impl CteState {
+    pub fn create() -> Arc<CteState> {
+        Arc::new(CteState {
+            blocks: RwLock::new(vec![]),
+        })
+    }
+
+    pub fn push_block(&self, data_block: DataBlock) {
+        self.blocks.write().push(data_block);
+    }
+
+    pub fn get_block(&self, index: usize) -> Option<DataBlock> {
+        self.blocks.read().get(index).cloned()
+    }
+
+    pub fn take_blocks(&self) -> Vec<DataBlock> {
+        std::mem::take(&mut *self.blocks.write())
+    }
+}
+
+pub struct SinkCte {
+    state: Arc<CteState>,
+}
+
+impl SinkCte {
+    pub fn create(state: Arc<CteState>) -> Self {
+        SinkCte { state }
+    }
+}
+
+impl Sink for SinkCte {
+    const NAME: &'static str = "CteSink";
+
+    fn consume(&mut self, data_block: DataBlock) -> Result<()> {
+        if data_block.num_rows() != 0 {
+            self.state.push_block(data_block);
+        }
+        Ok(())
+    }
+}
+
+/// Executes the recursive term of a recursive CTE once, it reads the rows of the work table,
+/// the first argument, and collects its rows into the second argument.
+pub type RecursiveIteration = Arc<dyn Fn(Arc<CteState>, Arc<CteState>) -> Result<()> + Send + Sync>;
+
+/// Returns the rows of a recursive CTE.
+///
+/// The rows of the anchor are passed through and fill the work table. Once the anchor is
+/// finished, the recursive term is executed with the rows of the previous iteration as the
+/// work table, until an iteration returns no rows. Without `ALL`, the duplicated rows are
+/// removed before they are returned, so the recursion ends once no new row is found.
+/// The query fails once the recursive term is executed more than `max_recursive_cte_depth`
+/// times.
+pub struct TransformRecursiveCte {
+    ctx: Arc<QueryContext>,
+    input: Arc<InputPort>,
+    output: Arc<OutputPort>,
+
+    all: bool,
+    keys: HashSet<Vec<u8>>,
+    iteration: RecursiveIteration,
+    work_table: Arc<CteState>,
+    work_table_rows: usize,
+    depth: u64,
+    max_depth: u64,
+
+    input_data: Option<DataBlock>,
+    output_data: VecDeque<DataBlock>,
+    input_finished: bool,
+    is_finished: bool,
+}
+
+impl TransformRecursiveCte {
+    pub fn try_create(
+        ctx: Arc<QueryContext>,
+        input: Arc<InputPort>,
+        output: Arc<OutputPort>,
+        all: bool,
+        iteration: RecursiveIteration,
+    ) -> Result<ProcessorPtr> {
+        let max_depth = ctx.get_settings().get_max_recursive_cte_depth()?;
+        Ok(ProcessorPtr::create(Box::new(TransformRecursiveCte {
+            ctx,
+            input,
+            output,
+            all,
+            keys: HashSet::new(),
+            iteration,
+            work_table: CteState::create(),
+            work_table_rows: 0,
+            depth: 0,
+            max_depth,
+            input_data: None,
+            output_data: VecDeque::new(),
+            input_finished: false,
+            is_finished: false,
+        })))
+    }
+
+    /// Adds the new rows of the block to the work table and the output.
+    fn add_block(&mut self, data_block: DataBlock) -> Result<()> {
+        let data_block = match self.all {
+            true => data_block,
+            false => {
+                let filter = build_row_keys(&data_block)?
+                    .into_iter()
+                    .map(|key| self.keys.insert(key))
+                    .collect::<Vec<_>>();
+                DataBlock::filter_block(&data_block, &Series::from_data(filter))?
+            }
+        };
+
+        if data_block.num_rows() != 0 {
+            self.work_table_rows += data_block.num_rows();
+            self.work_table.push_block(data_block.clone());
+            self.output_data.push_back(data_block);
+        }
+        Ok(())
+    }
+
+    /// Stops the recursion if the query is killed, or it's deeper than the limit.
+    fn check_iteration(&mut self) -> Result<()> {
+        if let Some(error) = self.ctx.get_error_value() {
+            return Err(error);
+        }
+
+        if self.ctx.get_current_session().is_aborting() {
+            return Err(ErrorCode::AbortedQuery(
+                "Aborted query, because the server is shutting down or the query was killed",
+            ));
+        }
+
+        self.depth += 1;
+        if self.max_depth != 0 && self.depth > self.max_depth {
+            return Err(ErrorCode::RecursiveCteDepthExceeded(format!(
+                "Recursive CTE aborted after {} iterations, try increasing max_recursive_cte_depth",
+                self.max_depth
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Processor for TransformRecursiveCte {
+    fn name(&self) -> &'static str {
+        "RecursiveCteTransform"
+    }
+
+    fn event(&mut self) -> Result<Event> {
+        if self.output.is_finished() {
+            self.input.finish();
+            return Ok(Event::Finished);
+        }
+
+        if !self.output.can_push() {
+            self.input.set_not_need_data();
+            return Ok(Event::NeedConsume);
+        }
+
+        if let Some(data_block) = self.output_data.pop_front() {
+            self.output.push_data(Ok(data_block));
+            return Ok(Event::NeedConsume);
+        }
+
+        if self.is_finished {
+            self.input.finish();
+            self.output.finish();
+            return Ok(Event::Finished);
+        }
+
+        if self.input_data.is_some() || self.input_finished {
+            return Ok(Event::Sync);
+        }
+
+        if self.input.has_data() {
+            self.input_data = Some(self.input.pull_data().unwrap()?);
+            return Ok(Event::Sync);
+        }
+
+        if self.input.is_finished() {
+            self.input_finished = true;
+            return Ok(Event::Sync);
+        }
+
+        self.input.set_need_data();
+        Ok(Event::NeedData)
+    }
+
+    fn process(&mut self) -> Result<()> {
+        if let Some(data_block) = self.input_data.take() {
+            return self.add_block(data_block);
+        }
+
+        if self.work_table_rows == 0 {
+            self.is_finished = true;
+            return Ok(());
+        }
+
+        self.check_iteration()?;
+        let work_table = std::mem::replace(&mut self.work_table, CteState::create());
+        self.work_table_rows = 0;
+
+        let result = CteState::create();
+        (self.iteration)(work_table, result.clone())?;
+        for data_block in result.take_blocks() {
+            self.add_block(data_block)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_datablocks::DataBlock;
+use common_datavalues::prelude::*;
+use common_exception::Result;
+
+use crate::pipelines::new::processors::port::InputPort;
+use crate::pipelines::new::processors::port::OutputPort;
+use crate::pipelines::new::processors::processor::ProcessorPtr;
+use crate::pipelines::new::processors::transforms::transform::Transform;
+use crate::pipelines::new::processors::transforms::transform::Transformer;
+
+/// Expands each input block into one block per grouping set for `GROUPING SETS`, `ROLLUP`
+/// and `CUBE`. The nullable grouping columns and the grouping id are appended to the input
+/// columns, and the grouping columns out of the grouping set are `NULL`.
+pub struct TransformExpandGroupingSets {
+    // positions of the grouping columns in the input block
+    grouping_columns: Vec<usize>,
+    // positions of the grouping columns of each grouping set
+    sets: Vec<Vec<usize>>,
+    output_schema: DataSchemaRef,
+}
+
+impl TransformExpandGroupingSets {
+    pub fn try_create(
+        input: Arc<InputPort>,
+        output: Arc<OutputPort>,
+        grouping_columns: Vec<usize>,
+        sets: Vec<Vec<usize>>,
+        output_schema: DataSchemaRef,
+    ) -> Result<ProcessorPtr> {
+        Ok(Transformer::create(
+            input,
+            output,
+            TransformExpandGroupingSets {
+                grouping_columns,
+                sets,
+                output_schema,
+            },
+        ))
+    }
+
+    fn grouping_id(&self, set: &[usize]) -> u32 {
+        (0..self.grouping_columns.len())
+            .filter(|position| !set.contains(position))
+            .fold(0, |grouping_id, position| grouping_id | (1 << position))
+    }
+}
+
+#[async_trait::async_trait]
+impl Transform for TransformExpandGroupingSets {
+    const NAME: &'static str = "ExpandGroupingSetsTransform";
+
+    fn transform(&mut self, block: DataBlock) -> Result<DataBlock> {
+        let num_rows = block.num_rows();
+        let offset = block.num_columns();
+        let mut blocks = Vec::with_capacity(self.sets.len());
+        for set in self.sets.iter() {
+            let mut columns = block.columns().to_vec();
+            for (position, input_column) in self.grouping_columns.iter().enumerate() {
+                let column = if set.contains(&position) {
+                    NullableColumn::wrap_inner(block.column(*input_column).clone(), None)
+                } else {
+                    let data_type = self.output_schema.field(offset + position).data_type();
+                    data_type
+                        .create_constant_column(&DataValue::Null, num_rows)?
+                        .convert_full_column()
+                };
+                columns.push(column);
+            }
+            columns.push(Series::from_data(vec![self.grouping_id(set); num_rows]));
+            blocks.push(DataBlock::create(self.output_schema.clone(), columns));
+        }
+        DataBlock::concat_blocks(&blocks)
+    }
+}
@@ -17,14 +17,15 @@ use std::sync::Arc;
 use common_datablocks::DataBlock;
 use common_datavalues::DataSchemaRef;
 use common_exception::Result;
+use futures::Future;
 
 use crate::pipelines::new::processors::port::InputPort;
 use crate::pipelines::new::processors::port::OutputPort;
 use crate::pipelines::new::processors::processor::Event;
 use crate::pipelines::new::processors::processor::ProcessorPtr;
 use crate::pipelines::new::processors::transforms::hash_join::HashJoinState;
+use crate::pipelines::new::processors::AsyncSink;
 use crate::pipelines::new::processors::Processor;
-use crate::pipelines::new::processors::Sink;
 use crate::sessions::QueryContext;
 
 pub struct SinkBuildHashTable {
@@ -38,21 +39,32 @@ impl SinkBuildHashTable {
     }
 }
 
-impl Sink for SinkBuildHashTable {
+#[async_trait::async_trait]
+impl AsyncSink for SinkBuildHashTable {
     const NAME: &'static str = "BuildHashTable";
 
-    fn on_finish(&mut self) -> Result<()> {
+    type ConsumeFuture<'a> = impl Future<Output = Result<()>> where Self: 'a;
+
+    async fn on_finish(&mut self) -> Result<()> {
         self.join_state.detach()
     }
 
-    fn consume(&mut self, data_block: DataBlock) -> Result<()> {
-        self.join_state.build(data_block)
+    fn consume(&mut self, data_block: DataBlock) -> Self::ConsumeFuture<'_> {
+        async move {
+            self.join_state.build(data_block)?;
+            if self.join_state.has_pending_io() {
+                self.join_state.flush_spilled().await?;
+            }
+            Ok(())
+        }
     }
 }
 
 enum HashJoinStep {
     Build,
     Probe,
+    // Join the spilled partitions after all the probe handles finished
+    ProbeSpilled,
     Finished,
 }
 
@@ -64,6 +76,8 @@ pub struct TransformHashJoinProbe {
     output_port: Arc<OutputPort>,
     step: HashJoinStep,
     join_state: Arc<dyn HashJoinState>,
+    // The probed blocks are spilled, they must be flushed before the next block is probed
+    need_flush: bool,
 }
 
 impl TransformHashJoinProbe {
@@ -73,24 +87,28 @@ impl TransformHashJoinProbe {
         output_port: Arc<OutputPort>,
         join_state: Arc<dyn HashJoinState>,
         _output_schema: DataSchemaRef,
-    ) -> ProcessorPtr {
-        ProcessorPtr::create(Box::new(TransformHashJoinProbe {
+    ) -> Result<ProcessorPtr> {
+        join_state.attach_probe()?;
+        Ok(ProcessorPtr::create(Box::new(TransformHashJoinProbe {
             input_data: None,
             output_data_blocks: vec![],
             input_port,
             output_port,
             step: HashJoinStep::Build,
             join_state,
-        }))
+            need_flush: false,
+        })))
     }
 
     fn probe(&mut self, block: &DataBlock) -> Result<()> {
         self.output_data_blocks
             .append(&mut self.join_state.probe(block)?);
+        self.need_flush = self.join_state.has_pending_io();
         Ok(())
     }
 }
 
+#[async_trait::async_trait]
 impl Processor for TransformHashJoinProbe {
     fn name(&self) -> &'static str {
         static NAME: &str = "TransformHashJoin";
@@ -108,9 +126,17 @@ impl Processor for TransformHashJoinProbe {
                     Ok(Event::NeedData)
                 }
             }
-            HashJoinStep::Probe => {
+            HashJoinStep::Probe | HashJoinStep::ProbeSpilled => {
+                if self.need_flush {
+                    return Ok(Event::Async);
+                }
+
                 if self.output_port.is_finished() {
                     self.input_port.finish();
+                    if matches!(self.step, HashJoinStep::Probe) {
+                        self.join_state.detach_probe()?;
+                    }
+                    self.step = HashJoinStep::Finished;
                     return Ok(Event::Finished);
                 }
 
@@ -124,11 +150,20 @@ impl Processor for TransformHashJoinProbe {
                     return Ok(Event::NeedConsume);
                 }
 
+                if matches!(self.step, HashJoinStep::ProbeSpilled) {
+                    return Ok(Event::Async);
+                }
+
                 if self.input_data.is_some() {
                     return Ok(Event::Sync);
                 }
 
                 if self.input_port.is_finished() {
+                    if self.join_state.detach_probe()? {
+                        self.step = HashJoinStep::ProbeSpilled;
+                        return Ok(Event::Async);
+                    }
+
                     self.output_port.finish();
                     self.step = HashJoinStep::Finished;
                     return Ok(Event::Finished);
@@ -142,7 +177,10 @@ impl Processor for TransformHashJoinProbe {
                 self.input_port.set_need_data();
                 Ok(Event::NeedData)
             }
-            HashJoinStep::Finished => Ok(Event::Finished),
+            HashJoinStep::Finished => {
+                self.output_port.finish();
+                Ok(Event::Finished)
+            }
         }
     }
 
@@ -156,6 +194,22 @@ impl Processor for TransformHashJoinProbe {
                 }
                 Ok(())
             }
+            HashJoinStep::ProbeSpilled => Ok(()),
+        }
+    }
+
+    async fn async_process(&mut self) -> Result<()> {
+        if self.need_flush {
+            self.need_flush = false;
+            return self.join_state.flush_spilled().await;
         }
+
+        if let HashJoinStep::ProbeSpilled = self.step {
+            match self.join_state.join_spilled().await? {
+                None => self.step = HashJoinStep::Finished,
+                Some(mut blocks) => self.output_data_blocks.append(&mut blocks),
+            }
+        }
+        Ok(())
     }
 }
@@ -0,0 +1,156 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_datablocks::DataBlock;
+use common_datablocks::SortColumnDescription;
+use common_exception::Result;
+
+use crate::pipelines::new::processors::port::InputPort;
+use crate::pipelines::new::processors::port::OutputPort;
+use crate::pipelines::new::processors::processor::Event;
+use crate::pipelines::new::processors::Processor;
+
+/// K-way merge of several sorted streams into one sorted stream.
+///
+/// Each input must be a sorted stream of blocks. Only the head block of each input is kept in
+/// memory, the merged rows are pushed to the output as soon as all the inputs have a head.
+pub struct TransformMultiSortMerge {
+    inputs: Vec<Arc<InputPort>>,
+    output: Arc<OutputPort>,
+
+    limit: Option<usize>,
+    sort_columns_descriptions: Vec<SortColumnDescription>,
+
+    heads: Vec<Option<DataBlock>>,
+    output_data: Option<DataBlock>,
+    output_rows: usize,
+}
+
+impl TransformMultiSortMerge {
+    pub fn create(
+        inputs: usize,
+        limit: Option<usize>,
+        sort_columns_descriptions: Vec<SortColumnDescription>,
+    ) -> Self {
+        let mut inputs_port = Vec::with_capacity(inputs);
+        for _index in 0..inputs {
+            inputs_port.push(InputPort::create());
+        }
+
+        TransformMultiSortMerge {
+            inputs: inputs_port,
+            output: OutputPort::create(),
+            limit,
+            sort_columns_descriptions,
+            heads: vec![None; inputs],
+            output_data: None,
+            output_rows: 0,
+        }
+    }
+
+    pub fn get_inputs(&self) -> &[Arc<InputPort>] {
+        &self.inputs
+    }
+
+    pub fn get_output(&self) -> &Arc<OutputPort> {
+        &self.output
+    }
+
+    fn finish(&mut self) -> Result<Event> {
+        for input in &self.inputs {
+            input.finish();
+        }
+
+        self.output.finish();
+        Ok(Event::Finished)
+    }
+}
+
+impl Processor for TransformMultiSortMerge {
+    fn name(&self) -> &'static str {
+        "MultiSortMergeTransform"
+    }
+
+    fn event(&mut self) -> Result<Event> {
+        if self.output.is_finished() {
+            return self.finish();
+        }
+
+        if !self.output.can_push() {
+            for input in &self.inputs {
+                input.set_not_need_data();
+            }
+
+            return Ok(Event::NeedConsume);
+        }
+
+        if let Some(data_block) = self.output_data.take() {
+            self.output.push_data(Ok(data_block));
+            return Ok(Event::NeedConsume);
+        }
+
+        if matches!(self.limit, Some(limit) if self.output_rows >= limit) {
+            return self.finish();
+        }
+
+        let mut need_data = false;
+        for (index, input) in self.inputs.iter().enumerate() {
+            if self.heads[index].is_some() {
+                continue;
+            }
+
+            if input.has_data() {
+                let data_block = input.pull_data().unwrap()?;
+                if data_block.num_rows() != 0 {
+                    self.heads[index] = Some(data_block);
+                    continue;
+                }
+            }
+
+            if !input.is_finished() {
+                input.set_need_data();
+                need_data = true;
+            }
+        }
+
+        if need_data {
+            return Ok(Event::NeedData);
+        }
+
+        match self.heads.iter().all(Option::is_none) {
+            true => self.finish(),
+            false => Ok(Event::Sync),
+        }
+    }
+
+    fn process(&mut self) -> Result<()> {
+        if let Some(data_block) =
+            DataBlock::merge_sort_heads(&mut self.heads, &self.sort_columns_descriptions)?
+        {
+            let data_block = match self.limit {
+                Some(limit) if self.output_rows + data_block.num_rows() > limit => {
+                    DataBlock::slice_block(&data_block, 0, limit - self.output_rows)
+                }
+                _ => data_block,
+            };
+
+            self.output_rows += data_block.num_rows();
+            self.output_data = Some(data_block);
+        }
+
+        Ok(())
+    }
+}
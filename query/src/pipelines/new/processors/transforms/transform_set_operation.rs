@@ -0,0 +1,265 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use common_datablocks::DataBlock;
+use common_datablocks::HashMethod;
+use common_datablocks::HashMethodSerializer;
+use common_datavalues::Series;
+use common_datavalues::SeriesFrom;
+use common_exception::Result;
+
+use crate::pipelines::new::processors::port::InputPort;
+use crate::pipelines::new::processors::port::OutputPort;
+use crate::pipelines::new::processors::processor::Event;
+use crate::pipelines::new::processors::processor::ProcessorPtr;
+use crate::pipelines::new::processors::transforms::transform::Transform;
+use crate::pipelines::new::processors::transforms::transform::Transformer;
+use crate::pipelines::new::processors::Processor;
+use crate::pipelines::new::processors::Sink;
+use crate::sql::plans::SetOperationType;
+
+/// The blocks of the right side of a set operation.
+///
+/// The right side is executed by a sub-pipeline, which is finished before the pipeline of the
+/// left side starts, so the blocks are complete once they are read.
+pub struct SetOperationState {
+    blocks: Mutex<VecDeque<DataBlock>>,
+}
+
+impl SetOperationState {
+    pub fn create() -> Arc<SetOperationState> {
+        Arc::new(SetOperationState {
+            blocks: Mutex::new(VecDeque::new()),
+        })
+    }
+
+    pub fn push_block(&self, data_block: DataBlock) {
+        self.blocks.lock().unwrap().push_back(data_block);
+    }
+
+    /// Takes the next block of the right side, every block is only taken once.
+    pub fn take_block(&self) -> Option<DataBlock> {
+        self.blocks.lock().unwrap().pop_front()
+    }
+}
+
+/// Serializes the rows of a block into keys, two rows are equal if their keys are equal,
+/// including the `NULL` values.
+pub fn build_row_keys(data_block: &DataBlock) -> Result<Vec<Vec<u8>>> {
+    let columns = data_block.columns().iter().collect::<Vec<_>>();
+    let method = HashMethodSerializer::default();
+    let keys = method.build_keys(&columns, data_block.num_rows())?;
+    Ok(keys.into_iter().map(|key| key.to_vec()).collect())
+}
+
+pub struct SinkSetOperation {
+    state: Arc<SetOperationState>,
+}
+
+impl SinkSetOperation {
+    pub fn create(state: Arc<SetOperationState>) -> Self {
+        SinkSetOperation { state }
+    }
+}
+
+impl Sink for SinkSetOperation {
+    const NAME: &'static str = "SetOperationSink";
+
+    fn consume(&mut self, data_block: DataBlock) -> Result<()> {
+        if data_block.num_rows() != 0 {
+            self.state.push_block(data_block);
+        }
+        Ok(())
+    }
+}
+
+/// Concatenates the rows of the left side with the rows of the right side.
+///
+/// The blocks of the left side are passed through, once the input is finished, the blocks of
+/// the right side are taken from the shared state, so the right side is split among all the
+/// parallel processors.
+pub struct TransformUnionAll {
+    input: Arc<InputPort>,
+    output: Arc<OutputPort>,
+    state: Arc<SetOperationState>,
+}
+
+impl TransformUnionAll {
+    pub fn try_create(
+        input: Arc<InputPort>,
+        output: Arc<OutputPort>,
+        state: Arc<SetOperationState>,
+    ) -> Result<ProcessorPtr> {
+        Ok(ProcessorPtr::create(Box::new(TransformUnionAll {
+            input,
+            output,
+            state,
+        })))
+    }
+}
+
+impl Processor for TransformUnionAll {
+    fn name(&self) -> &'static str {
+        "UnionAllTransform"
+    }
+
+    fn event(&mut self) -> Result<Event> {
+        if self.output.is_finished() {
+            self.input.finish();
+            return Ok(Event::Finished);
+        }
+
+        if !self.output.can_push() {
+            self.input.set_not_need_data();
+            return Ok(Event::NeedConsume);
+        }
+
+        if self.input.has_data() {
+            self.output.push_data(self.input.pull_data().unwrap());
+            return Ok(Event::NeedConsume);
+        }
+
+        if !self.input.is_finished() {
+            self.input.set_need_data();
+            return Ok(Event::NeedData);
+        }
+
+        match self.state.take_block() {
+            Some(data_block) => {
+                self.output.push_data(Ok(data_block));
+                Ok(Event::NeedConsume)
+            }
+            None => {
+                self.output.finish();
+                Ok(Event::Finished)
+            }
+        }
+    }
+}
+
+/// Removes the duplicated rows, the stream must not be split since the seen rows are kept by
+/// the processor.
+pub struct TransformDistinct {
+    keys: HashSet<Vec<u8>>,
+}
+
+impl TransformDistinct {
+    pub fn try_create(input: Arc<InputPort>, output: Arc<OutputPort>) -> Result<ProcessorPtr> {
+        Ok(Transformer::create(input, output, TransformDistinct {
+            keys: HashSet::new(),
+        }))
+    }
+}
+
+impl Transform for TransformDistinct {
+    const NAME: &'static str = "DistinctTransform";
+    const SKIP_EMPTY_DATA_BLOCK: bool = true;
+
+    fn transform(&mut self, data_block: DataBlock) -> Result<DataBlock> {
+        let filter = build_row_keys(&data_block)?
+            .into_iter()
+            .map(|key| self.keys.insert(key))
+            .collect::<Vec<_>>();
+        DataBlock::filter_block(&data_block, &Series::from_data(filter))
+    }
+}
+
+/// `INTERSECT` or `EXCEPT` of the rows of the left side with the rows of the right side.
+///
+/// The rows of the right side are counted in a hash table, which is built on the first
+/// block of the left side. With `ALL`, a row of the left side matches at most one row of
+/// the right side, so the duplicated rows are kept as many times as the difference or the
+/// minimum of their counts.
+pub struct TransformIntersectExcept {
+    op: SetOperationType,
+    all: bool,
+    state: Arc<SetOperationState>,
+    right_keys: Option<HashMap<Vec<u8>, usize>>,
+}
+
+impl TransformIntersectExcept {
+    pub fn try_create(
+        input: Arc<InputPort>,
+        output: Arc<OutputPort>,
+        op: SetOperationType,
+        all: bool,
+        state: Arc<SetOperationState>,
+    ) -> Result<ProcessorPtr> {
+        Ok(Transformer::create(
+            input,
+            output,
+            TransformIntersectExcept {
+                op,
+                all,
+                state,
+                right_keys: None,
+            },
+        ))
+    }
+
+    fn build_right_keys(&self) -> Result<HashMap<Vec<u8>, usize>> {
+        let mut right_keys = HashMap::new();
+        while let Some(data_block) = self.state.take_block() {
+            for key in build_row_keys(&data_block)? {
+                *right_keys.entry(key).or_default() += 1;
+            }
+        }
+        Ok(right_keys)
+    }
+}
+
+impl Transform for TransformIntersectExcept {
+    const NAME: &'static str = "IntersectExceptTransform";
+    const SKIP_EMPTY_DATA_BLOCK: bool = true;
+
+    fn transform(&mut self, data_block: DataBlock) -> Result<DataBlock> {
+        if self.right_keys.is_none() {
+            self.right_keys = Some(self.build_right_keys()?);
+        }
+
+        let (op, all) = (self.op, self.all);
+        let right_keys = self.right_keys.as_mut().unwrap();
+        let mut filter = Vec::with_capacity(data_block.num_rows());
+        for key in build_row_keys(&data_block)? {
+            let matched = match right_keys.get_mut(&key) {
+                Some(count) if *count > 0 => {
+                    // Without `ALL`, the equal rows are only matched once.
+                    *count = if all { *count - 1 } else { 0 };
+                    true
+                }
+                // Without `ALL`, the equal rows which are already returned by except are
+                // removed as well.
+                Some(_) => op == SetOperationType::Except && !all,
+                None => {
+                    if op == SetOperationType::Except && !all {
+                        right_keys.insert(key, 0);
+                    }
+                    false
+                }
+            };
+            filter.push(match op {
+                SetOperationType::Except => !matched,
+                _ => matched,
+            });
+        }
+
+        DataBlock::filter_block(&data_block, &Series::from_data(filter))
+    }
+}
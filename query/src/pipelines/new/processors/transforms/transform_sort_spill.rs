@@ -0,0 +1,261 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use common_datablocks::DataBlock;
+use common_datablocks::SortColumnDescription;
+use common_exception::Result;
+
+use crate::pipelines::new::processors::port::InputPort;
+use crate::pipelines::new::processors::port::OutputPort;
+use crate::pipelines::new::processors::processor::Event;
+use crate::pipelines::new::processors::processor::ProcessorPtr;
+use crate::pipelines::new::processors::transforms::spiller::Spiller;
+use crate::pipelines::new::processors::Processor;
+use crate::sessions::QueryContext;
+
+/// Merges the sorted blocks of a stream into one sorted stream.
+///
+/// The blocks are buffered in memory until they exceed the spill threshold, then they are merged
+/// into a sorted run which is spilled to disk. When the input is finished, the spilled runs are
+/// read back block by block and merged with a k-way merge, so only the head block of each run
+/// is kept in memory. The spilled blocks are written and read back in `async_process`.
+pub struct TransformSortSpill {
+    ctx: Arc<QueryContext>,
+    input: Arc<InputPort>,
+    output: Arc<OutputPort>,
+
+    limit: Option<usize>,
+    sort_columns_descriptions: Vec<SortColumnDescription>,
+    spill_threshold: usize,
+    max_block_size: usize,
+
+    input_data: Option<DataBlock>,
+    blocks: Vec<DataBlock>,
+    blocks_size: usize,
+
+    spiller: Option<Spiller>,
+    num_runs: usize,
+    merging_runs: bool,
+    heads: Vec<Option<DataBlock>>,
+    finished_runs: Vec<bool>,
+
+    output_data: VecDeque<DataBlock>,
+    output_rows: usize,
+    input_finished: bool,
+    is_finished: bool,
+}
+
+impl TransformSortSpill {
+    pub fn try_create(
+        ctx: Arc<QueryContext>,
+        input: Arc<InputPort>,
+        output: Arc<OutputPort>,
+        limit: Option<usize>,
+        sort_columns_descriptions: Vec<SortColumnDescription>,
+    ) -> Result<ProcessorPtr> {
+        let settings = ctx.get_settings();
+        let spill_threshold = settings.get_sort_spill_threshold()? as usize;
+        let max_block_size = settings.get_max_block_size()? as usize;
+
+        Ok(ProcessorPtr::create(Box::new(TransformSortSpill {
+            ctx,
+            input,
+            output,
+            limit,
+            sort_columns_descriptions,
+            spill_threshold,
+            max_block_size,
+            input_data: None,
+            blocks: vec![],
+            blocks_size: 0,
+            spiller: None,
+            num_runs: 0,
+            merging_runs: false,
+            heads: vec![],
+            finished_runs: vec![],
+            output_data: VecDeque::new(),
+            output_rows: 0,
+            input_finished: false,
+            is_finished: false,
+        })))
+    }
+
+    fn merge_blocks(&mut self) -> Result<Option<DataBlock>> {
+        if self.blocks.is_empty() {
+            return Ok(None);
+        }
+
+        let blocks = std::mem::take(&mut self.blocks);
+        self.blocks_size = 0;
+        let block =
+            DataBlock::merge_sort_blocks(&blocks, &self.sort_columns_descriptions, self.limit)?;
+        Ok(Some(block))
+    }
+
+    /// Merges the buffered blocks into a sorted run and spills it.
+    fn spill_run(&mut self) -> Result<()> {
+        if self.spiller.is_none() {
+            self.spiller = Some(Spiller::try_create(&self.ctx, "sort")?);
+        }
+
+        if let Some(block) = self.merge_blocks()? {
+            let run = self.num_runs;
+            self.num_runs += 1;
+
+            if let Some(spiller) = self.spiller.as_mut() {
+                for block in DataBlock::split_block_by_size(&block, self.max_block_size)? {
+                    spiller.spill(run, block)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn has_pending_io(&self) -> bool {
+        matches!(&self.spiller, Some(spiller) if spiller.has_pending_io())
+    }
+
+    /// Generates the next block of the k-way merge of the spilled runs, returns `None` while
+    /// waiting for the head block of a run to be read back.
+    fn merge_runs(&mut self) -> Result<Option<DataBlock>> {
+        if let Some(spiller) = self.spiller.as_mut() {
+            for run in 0..self.num_runs {
+                if self.heads[run].is_none() && !self.finished_runs[run] {
+                    match spiller.restore(run)? {
+                        Some(block) => self.heads[run] = Some(block),
+                        None if spiller.has_pending_io() => return Ok(None),
+                        None => self.finished_runs[run] = true,
+                    }
+                }
+            }
+        }
+
+        DataBlock::merge_sort_heads(&mut self.heads, &self.sort_columns_descriptions)
+    }
+
+    fn push_output(&mut self, block: DataBlock) {
+        let block = match self.limit {
+            Some(limit) if self.output_rows + block.num_rows() >= limit => {
+                self.is_finished = true;
+                DataBlock::slice_block(&block, 0, limit - self.output_rows)
+            }
+            _ => block,
+        };
+
+        self.output_rows += block.num_rows();
+        if block.num_rows() != 0 {
+            self.output_data.push_back(block);
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Processor for TransformSortSpill {
+    fn name(&self) -> &'static str {
+        "SortSpillTransform"
+    }
+
+    fn event(&mut self) -> Result<Event> {
+        if self.output.is_finished() {
+            self.input.finish();
+            return Ok(Event::Finished);
+        }
+
+        if !self.output.can_push() {
+            self.input.set_not_need_data();
+            return Ok(Event::NeedConsume);
+        }
+
+        if let Some(data_block) = self.output_data.pop_front() {
+            self.output.push_data(Ok(data_block));
+            return Ok(Event::NeedConsume);
+        }
+
+        if self.is_finished {
+            self.input.finish();
+            self.output.finish();
+            return Ok(Event::Finished);
+        }
+
+        if self.has_pending_io() {
+            return Ok(Event::Async);
+        }
+
+        if self.input_data.is_some() || self.input_finished {
+            return Ok(Event::Sync);
+        }
+
+        if self.input.has_data() {
+            self.input_data = Some(self.input.pull_data().unwrap()?);
+            return Ok(Event::Sync);
+        }
+
+        if self.input.is_finished() {
+            self.input_finished = true;
+            return Ok(Event::Sync);
+        }
+
+        self.input.set_need_data();
+        Ok(Event::NeedData)
+    }
+
+    fn process(&mut self) -> Result<()> {
+        if let Some(data_block) = self.input_data.take() {
+            self.blocks_size += data_block.memory_size();
+            self.blocks.push(data_block);
+
+            if self.spill_threshold != 0 && self.blocks_size >= self.spill_threshold {
+                self.spill_run()?;
+            }
+
+            return Ok(());
+        }
+
+        if self.spiller.is_none() {
+            self.is_finished = true;
+            if let Some(data_block) = self.merge_blocks()? {
+                self.output_data.push_back(data_block);
+            }
+
+            return Ok(());
+        }
+
+        if !self.merging_runs {
+            // Spill the remaining blocks as the last run, then merge all the runs.
+            self.merging_runs = true;
+            self.spill_run()?;
+            self.heads = vec![None; self.num_runs];
+            self.finished_runs = vec![false; self.num_runs];
+        }
+
+        match self.merge_runs()? {
+            None if self.has_pending_io() => {}
+            None => self.is_finished = true,
+            Some(data_block) => self.push_output(data_block),
+        }
+
+        Ok(())
+    }
+
+    async fn async_process(&mut self) -> Result<()> {
+        match self.spiller.as_mut() {
+            None => Ok(()),
+            Some(spiller) => spiller.process_io().await,
+        }
+    }
+}
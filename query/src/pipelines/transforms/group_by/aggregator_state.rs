@@ -44,12 +44,15 @@ use crate::pipelines::transforms::group_by::AggregatorParams;
 ///     - Group by key data memory pool (if necessary)
 #[allow(clippy::len_without_is_empty)]
 pub trait AggregatorState<Method: HashMethod>: Sync + Send {
-    type Key;
+    type Key: HashTableKeyable;
     type Entity: StateEntity<Self::Key>;
     type Iterator: Iterator<Item = *mut Self::Entity>;
 
     fn len(&self) -> usize;
 
+    /// The memory in bytes allocated for the keys and the aggregate function states.
+    fn allocated_bytes(&self) -> usize;
+
     fn iter(&self) -> Self::Iterator;
 
     fn alloc_place(&self, layout: Layout) -> StateAddr;
@@ -141,7 +144,7 @@ impl<T: ShortFixedKeyable> Drop for ShortFixedKeysAggregatorState<T> {
 
 impl<T> AggregatorState<HashMethodFixedKeys<T>> for ShortFixedKeysAggregatorState<T>
 where
-    T: PrimitiveType + ShortFixedKeyable,
+    T: PrimitiveType + ShortFixedKeyable + HashTableKeyable,
     for<'a> HashMethodFixedKeys<T>: HashMethod<HashKey<'a> = T>,
     for<'a> <HashMethodFixedKeys<T> as HashMethod>::HashKey<'a>: HashTableKeyable,
 {
@@ -154,6 +157,12 @@ where
         self.size
     }
 
+    #[inline(always)]
+    fn allocated_bytes(&self) -> usize {
+        let entities_bytes = self.max_size * std::mem::size_of::<ShortFixedKeysStateEntity<T>>();
+        entities_bytes + self.area.allocated_bytes()
+    }
+
     #[inline(always)]
     fn iter(&self) -> Self::Iterator {
         Self::Iterator::create(self.data, self.max_size as isize)
@@ -231,6 +240,11 @@ where
         self.data.len()
     }
 
+    #[inline(always)]
+    fn allocated_bytes(&self) -> usize {
+        self.data.allocated_bytes() + self.area.allocated_bytes()
+    }
+
     #[inline(always)]
     fn iter(&self) -> Self::Iterator {
         self.data.iter()
@@ -291,6 +305,12 @@ impl AggregatorState<HashMethodSerializer> for SerializedKeysAggregatorState {
     fn len(&self) -> usize {
         self.data_state_map.len()
     }
+
+    fn allocated_bytes(&self) -> usize {
+        self.data_state_map.allocated_bytes()
+            + self.keys_area.allocated_bytes()
+            + self.state_area.allocated_bytes()
+    }
     fn iter(&self) -> Self::Iterator {
         self.data_state_map.iter()
     }
@@ -362,6 +382,12 @@ impl AggregatorState<HashMethodSingleString> for SerializedKeysAggregatorState {
         self.data_state_map.len()
     }
 
+    fn allocated_bytes(&self) -> usize {
+        self.data_state_map.allocated_bytes()
+            + self.keys_area.allocated_bytes()
+            + self.state_area.allocated_bytes()
+    }
+
     fn iter(&self) -> Self::Iterator {
         self.data_state_map.iter()
     }
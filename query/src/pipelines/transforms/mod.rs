@@ -33,6 +33,7 @@ mod streams;
 mod transform_sink;
 
 pub use streams::AddOnStream;
+pub use streams::MemoryLimitStream;
 pub use transform_aggregator_final::AggregatorFinalTransform;
 pub use transform_aggregator_partial::AggregatorPartialTransform;
 pub use transform_create_sets::CreateSetsTransform;
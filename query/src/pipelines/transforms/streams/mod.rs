@@ -13,5 +13,7 @@
 // limitations under the License.
 
 mod stream_addon;
+mod stream_memory_limit;
 
 pub use stream_addon::AddOnStream;
+pub use stream_memory_limit::MemoryLimitStream;
@@ -0,0 +1,54 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::task::Context;
+use std::task::Poll;
+
+use common_datablocks::DataBlock;
+use common_exception::Result;
+use common_streams::SendableDataBlockStream;
+use futures::Stream;
+use futures::StreamExt;
+
+use crate::sessions::QueryContext;
+
+/// Stops the block stream once the query uses more memory than its limits.
+pub struct MemoryLimitStream {
+    input: SendableDataBlockStream,
+    ctx: Arc<QueryContext>,
+}
+
+impl MemoryLimitStream {
+    pub fn create(input: SendableDataBlockStream, ctx: Arc<QueryContext>) -> Self {
+        MemoryLimitStream { input, ctx }
+    }
+}
+
+impl Stream for MemoryLimitStream {
+    type Item = Result<DataBlock>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        self.input.poll_next_unpin(ctx).map(|x| match x {
+            Some(Ok(v)) => match self.ctx.check_memory_limit() {
+                Ok(_) => Some(Ok(v)),
+                Err(cause) => Some(Err(cause)),
+            },
+            other => other,
+        })
+    }
+}
@@ -25,6 +25,7 @@ use common_tracing::tracing;
 
 use crate::pipelines::processors::EmptyProcessor;
 use crate::pipelines::processors::Processor;
+use crate::pipelines::transforms::MemoryLimitStream;
 use crate::sessions::QueryContext;
 
 pub struct SourceTransform {
@@ -44,8 +45,14 @@ impl SourceTransform {
         let progress_stream =
             ProgressStream::try_create(table_stream.await?, self.ctx.get_scan_progress())?;
 
+        // The old processors are not driven by the pipeline executor, the memory limits of
+        // the query are checked whenever a block is read from the table.
+        let memory_limit_stream =
+            MemoryLimitStream::create(Box::pin(progress_stream), self.ctx.clone());
+
         Ok(Box::pin(
-            self.ctx.try_create_abortable(Box::pin(progress_stream))?,
+            self.ctx
+                .try_create_abortable(Box::pin(memory_limit_stream))?,
         ))
     }
 }
@@ -28,12 +28,15 @@ use futures::future::AbortRegistration;
 use futures::stream::Abortable;
 use futures::Future;
 use futures::StreamExt;
+use tokio_rustls::TlsAcceptor;
 use tokio_stream::wrappers::TcpListenerStream;
 
 use crate::servers::clickhouse::clickhouse_session::ClickHouseConnection;
 use crate::servers::clickhouse::reject_connection::RejectCHConnection;
 use crate::servers::server::ListeningStream;
 use crate::servers::server::Server;
+use crate::servers::tls::build_tls_acceptor;
+use crate::servers::tls::relay_tls_on_loopback;
 use crate::sessions::SessionManager;
 use crate::sessions::SessionType;
 
@@ -64,22 +67,53 @@ impl ClickHouseHandler {
         Ok((TcpListenerStream::new(listener), listener_addr))
     }
 
-    fn listen_loop(&self, stream: ListeningStream, r: Arc<Runtime>) -> impl Future<Output = ()> {
+    fn listen_loop(
+        &self,
+        stream: ListeningStream,
+        r: Arc<Runtime>,
+        tls: Option<TlsAcceptor>,
+    ) -> impl Future<Output = ()> {
         let sessions = self.sessions.clone();
         stream.for_each(move |accept_socket| {
             let executor = r.clone();
             let sessions = sessions.clone();
+            let tls = tls.clone();
             async move {
                 match accept_socket {
                     Err(error) => tracing::error!("Broken session connection: {}", error),
-                    Ok(socket) => ClickHouseHandler::accept_socket(sessions, executor, socket),
+                    Ok(socket) => ClickHouseHandler::accept_socket(sessions, executor, socket, tls),
                 };
             }
         })
     }
 
-    async fn reject_connection(stream: TcpStream, error: ErrorCode) {
-        if let Err(error) = RejectCHConnection::reject(stream, error).await {
+    fn build_tls(sessions: &SessionManager) -> Result<Option<TlsAcceptor>> {
+        let config = sessions.get_conf();
+        match config.tls_clickhouse_handler_enabled() {
+            false => Ok(None),
+            true => {
+                tracing::info!("ClickHouse Handler TLS enabled");
+                Ok(Some(build_tls_acceptor(
+                    &config.query.clickhouse_handler_tls_server_cert,
+                    &config.query.clickhouse_handler_tls_server_key,
+                    &config.query.clickhouse_handler_tls_server_root_ca_cert,
+                )?))
+            }
+        }
+    }
+
+    async fn reject_connection(stream: TcpStream, error: ErrorCode, tls: Option<TlsAcceptor>) {
+        let stream = match tls {
+            None => Ok(stream),
+            Some(acceptor) => relay_tls_on_loopback(acceptor, stream).await,
+        };
+
+        let rejected = match stream {
+            Ok(stream) => RejectCHConnection::reject(stream, error).await,
+            Err(cause) => Err(cause),
+        };
+
+        if let Err(error) = rejected {
             tracing::error!(
                 "Unexpected error occurred during reject connection: {:?}",
                 error
@@ -87,13 +121,18 @@ impl ClickHouseHandler {
         }
     }
 
-    fn accept_socket(sessions: Arc<SessionManager>, executor: Arc<Runtime>, socket: TcpStream) {
+    fn accept_socket(
+        sessions: Arc<SessionManager>,
+        executor: Arc<Runtime>,
+        socket: TcpStream,
+        tls: Option<TlsAcceptor>,
+    ) {
         executor.spawn(async move {
             match sessions.create_session(SessionType::Clickhouse).await {
-                Err(error) => Self::reject_connection(socket, error).await,
+                Err(error) => Self::reject_connection(socket, error, tls).await,
                 Ok(session) => {
                     tracing::info!("ClickHouse connection coming: {:?}", socket.peer_addr());
-                    if let Err(error) = ClickHouseConnection::run_on_stream(session, socket) {
+                    if let Err(error) = ClickHouseConnection::run_on_stream(session, socket, tls) {
                         tracing::error!("Unexpected error occurred during query: {:?}", error);
                     }
                 }
@@ -126,13 +165,14 @@ impl Server for ClickHouseHandler {
                 "ClickHouseHandler already running.",
             )),
             Some(registration) => {
+                let tls = Self::build_tls(&self.sessions)?;
                 let rejected_rt = Arc::new(Runtime::with_worker_threads(
                     1,
                     Some("clickhouse-handler".to_string()),
                 )?);
                 let (stream, listener) = Self::listener_tcp(listening).await?;
                 let stream = Abortable::new(stream, registration);
-                self.join_handle = Some(tokio::spawn(self.listen_loop(stream, rejected_rt)));
+                self.join_handle = Some(tokio::spawn(self.listen_loop(stream, rejected_rt, tls)));
                 Ok(listener)
             }
         }
@@ -23,14 +23,20 @@ use common_exception::Result;
 use common_exception::ToErrorCode;
 use common_tracing::tracing;
 use opensrv_clickhouse::ClickHouseServer;
+use tokio_rustls::TlsAcceptor;
 
 use crate::servers::clickhouse::interactive_worker::InteractiveWorker;
+use crate::servers::tls::relay_tls_on_loopback;
 use crate::sessions::SessionRef;
 
 pub struct ClickHouseConnection;
 
 impl ClickHouseConnection {
-    pub fn run_on_stream(session: SessionRef, stream: TcpStream) -> Result<()> {
+    pub fn run_on_stream(
+        session: SessionRef,
+        stream: TcpStream,
+        tls: Option<TlsAcceptor>,
+    ) -> Result<()> {
         let blocking_stream = Self::convert_stream(stream)?;
         ClickHouseConnection::attach_session(&session, &blocking_stream)?;
         let non_blocking_stream = TcpStream::from_std(blocking_stream)?;
@@ -40,7 +46,21 @@ impl ClickHouseConnection {
         Thread::spawn(move || {
             let join_handle = query_executor.spawn(async move {
                 let interactive_worker = InteractiveWorker::create(session);
-                ClickHouseServer::run_on_stream(interactive_worker, non_blocking_stream).await
+                match tls {
+                    None => {
+                        ClickHouseServer::run_on_stream(interactive_worker, non_blocking_stream)
+                            .await
+                    }
+                    Some(acceptor) => match Self::accept_tls(acceptor, non_blocking_stream).await {
+                        Ok(stream) => {
+                            ClickHouseServer::run_on_stream(interactive_worker, stream).await
+                        }
+                        Err(error) => {
+                            tracing::error!("ClickHouse TLS handshake failed: {:?}", error);
+                            Ok(())
+                        }
+                    },
+                }
             });
 
             let _ = futures::executor::block_on(join_handle);
@@ -49,6 +69,12 @@ impl ClickHouseConnection {
         Ok(())
     }
 
+    // The server runs on the loopback stream, in the same blocking mode as a plain connection.
+    async fn accept_tls(acceptor: TlsAcceptor, stream: TcpStream) -> Result<TcpStream> {
+        let stream = relay_tls_on_loopback(acceptor, stream).await?;
+        Ok(TcpStream::from_std(Self::convert_stream(stream)?)?)
+    }
+
     fn attach_session(session: &SessionRef, blocking_stream: &std::net::TcpStream) -> Result<()> {
         let host = blocking_stream.peer_addr().ok();
         let blocking_stream_ref = blocking_stream.try_clone()?;
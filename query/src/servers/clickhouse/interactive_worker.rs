@@ -106,15 +106,20 @@ impl ClickHouseSession for InteractiveWorker {
 
     async fn authenticate(&self, user: &str, password: &[u8], client_addr: &str) -> bool {
         // Here we don't handle the create context error.
-        let client_ip = client_addr.split(':').collect::<Vec<_>>()[0];
-        let credential = Credential::Password {
-            name: user.to_string(),
-            password: Some(password.to_owned()),
-            hostname: Some(client_ip.to_string()),
-        };
         let ctx = self.session.create_query_context().await;
         match ctx {
             Ok(c) => {
+                // The server sees the loopback address of a TLS connection, the real one is
+                // attached to the session.
+                let client_ip = match c.get_client_address() {
+                    Some(address) => address.ip().to_string(),
+                    None => client_addr.split(':').collect::<Vec<_>>()[0].to_string(),
+                };
+                let credential = Credential::Password {
+                    name: user.to_string(),
+                    password: Some(password.to_owned()),
+                    hostname: Some(client_ip),
+                };
                 let user_info_auth = c.get_auth_manager().auth(&credential).await;
                 match user_info_auth {
                     Ok((tenant_id, user_info)) => {
@@ -0,0 +1,189 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_arrow::arrow::array::ArrayRef;
+use common_arrow::arrow::array::BinaryArray;
+use common_arrow::arrow::array::Utf8Array;
+use common_arrow::arrow::chunk::Chunk;
+use common_arrow::arrow::datatypes::DataType as ArrowType;
+use common_arrow::arrow::datatypes::Field as ArrowField;
+use common_arrow::arrow::datatypes::Schema as ArrowSchema;
+use common_arrow::arrow::io::flight::serialize_schema_to_info;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_functions::scalars::like_pattern_to_regex;
+use regex::Regex;
+
+use crate::servers::flight_sql::flight_sql_messages::CommandGetDbSchemas;
+use crate::servers::flight_sql::flight_sql_messages::CommandGetTables;
+use crate::sessions::QueryContext;
+use crate::storages::view::view_table::VIEW_ENGINE;
+
+// The databases of Databend are the database schemas of Flight SQL, in the current catalog.
+
+const TABLE_TYPE_TABLE: &str = "BASE TABLE";
+const TABLE_TYPE_VIEW: &str = "VIEW";
+
+pub fn catalogs_schema() -> ArrowSchema {
+    ArrowSchema::from(vec![ArrowField::new(
+        "catalog_name",
+        ArrowType::Utf8,
+        false,
+    )])
+}
+
+pub fn db_schemas_schema() -> ArrowSchema {
+    ArrowSchema::from(vec![
+        ArrowField::new("catalog_name", ArrowType::Utf8, true),
+        ArrowField::new("db_schema_name", ArrowType::Utf8, false),
+    ])
+}
+
+pub fn tables_schema(include_schema: bool) -> ArrowSchema {
+    let mut fields = vec![
+        ArrowField::new("catalog_name", ArrowType::Utf8, true),
+        ArrowField::new("db_schema_name", ArrowType::Utf8, true),
+        ArrowField::new("table_name", ArrowType::Utf8, false),
+        ArrowField::new("table_type", ArrowType::Utf8, false),
+    ];
+    if include_schema {
+        fields.push(ArrowField::new("table_schema", ArrowType::Binary, false));
+    }
+    ArrowSchema::from(fields)
+}
+
+pub fn table_types_schema() -> ArrowSchema {
+    ArrowSchema::from(vec![ArrowField::new("table_type", ArrowType::Utf8, false)])
+}
+
+pub fn get_catalogs(ctx: &Arc<QueryContext>) -> Result<Chunk<ArrayRef>> {
+    Ok(Chunk::try_new(vec![utf8_array(&[
+        ctx.get_current_catalog()
+    ])])?)
+}
+
+pub async fn get_db_schemas(
+    ctx: &Arc<QueryContext>,
+    command: &CommandGetDbSchemas,
+) -> Result<Chunk<ArrayRef>> {
+    let databases = list_databases(
+        ctx,
+        command.catalog.as_deref(),
+        command.db_schema_filter_pattern.as_deref(),
+    )
+    .await?;
+    let catalogs = vec![ctx.get_current_catalog(); databases.len()];
+    Ok(Chunk::try_new(vec![
+        utf8_array(&catalogs),
+        utf8_array(&databases),
+    ])?)
+}
+
+pub async fn get_tables(
+    ctx: &Arc<QueryContext>,
+    command: &CommandGetTables,
+) -> Result<Chunk<ArrayRef>> {
+    let databases = list_databases(
+        ctx,
+        command.catalog.as_deref(),
+        command.db_schema_filter_pattern.as_deref(),
+    )
+    .await?;
+    let table_name_filter = like_filter(command.table_name_filter_pattern.as_deref())?;
+
+    let catalog = ctx.get_catalog(ctx.get_current_catalog())?;
+    let tenant = ctx.get_tenant();
+    let mut db_schema_names = vec![];
+    let mut table_names = vec![];
+    let mut table_types = vec![];
+    let mut table_schemas = vec![];
+    for database in databases {
+        let mut tables = catalog.list_tables(&tenant, &database).await?;
+        tables.sort_by(|a, b| a.name().cmp(b.name()));
+        for table in tables {
+            let table_type = match table.engine() {
+                VIEW_ENGINE => TABLE_TYPE_VIEW,
+                _ => TABLE_TYPE_TABLE,
+            };
+            if !table_name_filter.is_match(table.name())
+                || !(command.table_types.is_empty()
+                    || command.table_types.iter().any(|t| t == table_type))
+            {
+                continue;
+            }
+            if command.include_schema {
+                table_schemas.push(serialize_schema_to_info(&table.schema().to_arrow(), None)?);
+            }
+            db_schema_names.push(database.clone());
+            table_names.push(table.name().to_string());
+            table_types.push(table_type);
+        }
+    }
+
+    let catalogs = vec![ctx.get_current_catalog(); table_names.len()];
+    let mut columns = vec![
+        utf8_array(&catalogs),
+        utf8_array(&db_schema_names),
+        utf8_array(&table_names),
+        utf8_array(&table_types),
+    ];
+    if command.include_schema {
+        columns.push(Arc::new(BinaryArray::<i32>::from_slice(&table_schemas)));
+    }
+    Ok(Chunk::try_new(columns)?)
+}
+
+pub fn get_table_types() -> Result<Chunk<ArrayRef>> {
+    Ok(Chunk::try_new(vec![utf8_array(&[
+        TABLE_TYPE_TABLE,
+        TABLE_TYPE_VIEW,
+    ])])?)
+}
+
+// Names of the databases in the catalog, which match the pattern of `LIKE`.
+async fn list_databases(
+    ctx: &Arc<QueryContext>,
+    catalog_name: Option<&str>,
+    pattern: Option<&str>,
+) -> Result<Vec<String>> {
+    let current_catalog = ctx.get_current_catalog();
+    if matches!(catalog_name, Some(name) if name != current_catalog) {
+        return Ok(vec![]);
+    }
+
+    let filter = like_filter(pattern)?;
+    let catalog = ctx.get_catalog(&current_catalog)?;
+    let mut databases = catalog
+        .list_databases(&ctx.get_tenant())
+        .await?
+        .iter()
+        .map(|database| database.name().to_string())
+        .filter(|name| filter.is_match(name))
+        .collect::<Vec<_>>();
+    databases.sort();
+    Ok(databases)
+}
+
+fn like_filter(pattern: Option<&str>) -> Result<Regex> {
+    let regex = like_pattern_to_regex(pattern.unwrap_or("%"));
+    Regex::new(&regex).map_err(|e| {
+        ErrorCode::BadArguments(format!("Invalid filter pattern {:?}: {}", pattern, e))
+    })
+}
+
+fn utf8_array<T: AsRef<str>>(values: &[T]) -> ArrayRef {
+    Arc::new(Utf8Array::<i32>::from_slice(values))
+}
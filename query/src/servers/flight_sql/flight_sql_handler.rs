@@ -0,0 +1,102 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use common_arrow::arrow_format::flight::service::flight_service_server::FlightServiceServer;
+use common_base::base::tokio;
+use common_base::base::tokio::sync::oneshot;
+use common_base::base::tokio::task::JoinHandle;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_tracing::tracing;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::transport::Server as TonicServer;
+
+use crate::servers::flight_sql::flight_sql_service::FlightSqlService;
+use crate::servers::server::Server;
+use crate::sessions::SessionManager;
+
+/// The Arrow Flight SQL handler for the clients, e.g. the drivers of ADBC and JDBC.
+pub struct FlightSqlHandler {
+    sessions: Arc<SessionManager>,
+    shutdown_tx: Option<oneshot::Sender<()>>,
+    join_handle: Option<JoinHandle<()>>,
+}
+
+impl FlightSqlHandler {
+    pub fn create(sessions: Arc<SessionManager>) -> Box<dyn Server> {
+        Box::new(FlightSqlHandler {
+            sessions,
+            shutdown_tx: None,
+            join_handle: None,
+        })
+    }
+
+    async fn listener_tcp(listening: SocketAddr) -> Result<(TcpListenerStream, SocketAddr)> {
+        let listener = tokio::net::TcpListener::bind(listening)
+            .await
+            .map_err(|e| {
+                ErrorCode::TokioError(format!("{{{}:{}}} {}", listening.ip(), listening.port(), e))
+            })?;
+        let listener_addr = listener.local_addr()?;
+        Ok((TcpListenerStream::new(listener), listener_addr))
+    }
+}
+
+#[async_trait::async_trait]
+impl Server for FlightSqlHandler {
+    async fn shutdown(&mut self, graceful: bool) {
+        if !graceful {
+            return;
+        }
+
+        if let Some(shutdown_tx) = self.shutdown_tx.take() {
+            let _ = shutdown_tx.send(());
+        }
+
+        if let Some(join_handle) = self.join_handle.take() {
+            if let Err(error) = join_handle.await {
+                tracing::error!(
+                    "Unexpected error during shutdown FlightSqlHandler. cause {}",
+                    error
+                );
+            }
+        }
+    }
+
+    async fn start(&mut self, listening: SocketAddr) -> Result<SocketAddr> {
+        if self.join_handle.is_some() {
+            return Err(ErrorCode::LogicalError("FlightSqlHandler already running."));
+        }
+
+        let (stream, listener) = Self::listener_tcp(listening).await?;
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        let service = FlightSqlService::create(self.sessions.clone());
+        let server = TonicServer::builder()
+            .add_service(FlightServiceServer::new(service))
+            .serve_with_incoming_shutdown(stream, async move {
+                let _ = shutdown_rx.await;
+            });
+
+        self.shutdown_tx = Some(shutdown_tx);
+        self.join_handle = Some(tokio::spawn(async move {
+            if let Err(error) = server.await {
+                tracing::error!("Flight SQL handler stopped with error: {}", error);
+            }
+        }));
+        Ok(listener)
+    }
+}
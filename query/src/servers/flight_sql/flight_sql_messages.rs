@@ -0,0 +1,243 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// The messages of Flight SQL, from `FlightSql.proto` of Apache Arrow. A command is sent as
+// a `google.protobuf.Any` in the `cmd` of a `FlightDescriptor`, the `ticket` of a `Ticket`
+// or the `body` of an `Action`.
+
+use prost::Message;
+use tonic::Status;
+
+pub const TYPE_URL_PREFIX: &str = "type.googleapis.com/arrow.flight.protocol.sql.";
+
+pub const CREATE_PREPARED_STATEMENT: &str = "CreatePreparedStatement";
+pub const CLOSE_PREPARED_STATEMENT: &str = "ClosePreparedStatement";
+
+#[derive(Clone, PartialEq, Message)]
+pub struct Any {
+    #[prost(string, tag = "1")]
+    pub type_url: String,
+    #[prost(bytes = "vec", tag = "2")]
+    pub value: Vec<u8>,
+}
+
+/// A message of Flight SQL which is packed in an `Any`.
+pub trait FlightSqlMessage: Message + Default + Sized {
+    const TYPE_NAME: &'static str;
+
+    fn type_url() -> String {
+        format!("{}{}", TYPE_URL_PREFIX, Self::TYPE_NAME)
+    }
+
+    fn to_any_bytes(&self) -> Vec<u8> {
+        Any {
+            type_url: Self::type_url(),
+            value: self.encode_to_vec(),
+        }
+        .encode_to_vec()
+    }
+
+    fn from_any_bytes(bytes: &[u8]) -> Result<Self, Status> {
+        let any = decode_any(bytes)?;
+        if any.type_url != Self::type_url() {
+            return Err(Status::invalid_argument(format!(
+                "Expect a message {}, but got {}",
+                Self::TYPE_NAME,
+                any.type_url
+            )));
+        }
+        decode_message(&any.value)
+    }
+}
+
+macro_rules! flight_sql_message {
+    ($name: ident) => {
+        impl FlightSqlMessage for $name {
+            const TYPE_NAME: &'static str = stringify!($name);
+        }
+    };
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct CommandStatementQuery {
+    #[prost(string, tag = "1")]
+    pub query: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct TicketStatementQuery {
+    #[prost(bytes = "vec", tag = "1")]
+    pub statement_handle: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct CommandStatementUpdate {
+    #[prost(string, tag = "1")]
+    pub query: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct CommandStatementIngest {
+    #[prost(string, tag = "2")]
+    pub table: String,
+    #[prost(string, optional, tag = "3")]
+    pub schema: Option<String>,
+    #[prost(string, optional, tag = "4")]
+    pub catalog: Option<String>,
+    #[prost(bool, tag = "5")]
+    pub temporary: bool,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct CommandPreparedStatementQuery {
+    #[prost(bytes = "vec", tag = "1")]
+    pub prepared_statement_handle: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct CommandPreparedStatementUpdate {
+    #[prost(bytes = "vec", tag = "1")]
+    pub prepared_statement_handle: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct DoPutUpdateResult {
+    #[prost(int64, tag = "1")]
+    pub record_count: i64,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct CommandGetCatalogs {}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct CommandGetDbSchemas {
+    #[prost(string, optional, tag = "1")]
+    pub catalog: Option<String>,
+    #[prost(string, optional, tag = "2")]
+    pub db_schema_filter_pattern: Option<String>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct CommandGetTables {
+    #[prost(string, optional, tag = "1")]
+    pub catalog: Option<String>,
+    #[prost(string, optional, tag = "2")]
+    pub db_schema_filter_pattern: Option<String>,
+    #[prost(string, optional, tag = "3")]
+    pub table_name_filter_pattern: Option<String>,
+    #[prost(string, repeated, tag = "4")]
+    pub table_types: Vec<String>,
+    #[prost(bool, tag = "5")]
+    pub include_schema: bool,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct CommandGetTableTypes {}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ActionCreatePreparedStatementRequest {
+    #[prost(string, tag = "1")]
+    pub query: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ActionCreatePreparedStatementResult {
+    #[prost(bytes = "vec", tag = "1")]
+    pub prepared_statement_handle: Vec<u8>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub dataset_schema: Vec<u8>,
+    #[prost(bytes = "vec", tag = "3")]
+    pub parameter_schema: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ActionClosePreparedStatementRequest {
+    #[prost(bytes = "vec", tag = "1")]
+    pub prepared_statement_handle: Vec<u8>,
+}
+
+flight_sql_message!(CommandStatementQuery);
+flight_sql_message!(TicketStatementQuery);
+flight_sql_message!(CommandStatementUpdate);
+flight_sql_message!(CommandStatementIngest);
+flight_sql_message!(CommandPreparedStatementQuery);
+flight_sql_message!(CommandPreparedStatementUpdate);
+flight_sql_message!(DoPutUpdateResult);
+flight_sql_message!(CommandGetCatalogs);
+flight_sql_message!(CommandGetDbSchemas);
+flight_sql_message!(CommandGetTables);
+flight_sql_message!(CommandGetTableTypes);
+flight_sql_message!(ActionCreatePreparedStatementRequest);
+flight_sql_message!(ActionCreatePreparedStatementResult);
+flight_sql_message!(ActionClosePreparedStatementRequest);
+
+/// The commands of the descriptors and the tickets.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FlightSqlCommand {
+    StatementQuery(CommandStatementQuery),
+    TicketStatementQuery(TicketStatementQuery),
+    StatementUpdate(CommandStatementUpdate),
+    StatementIngest(CommandStatementIngest),
+    PreparedStatementQuery(CommandPreparedStatementQuery),
+    PreparedStatementUpdate(CommandPreparedStatementUpdate),
+    GetCatalogs(CommandGetCatalogs),
+    GetDbSchemas(CommandGetDbSchemas),
+    GetTables(CommandGetTables),
+    GetTableTypes(CommandGetTableTypes),
+}
+
+impl FlightSqlCommand {
+    pub fn decode(bytes: &[u8]) -> Result<FlightSqlCommand, Status> {
+        let any = decode_any(bytes)?;
+        let name = any
+            .type_url
+            .strip_prefix(TYPE_URL_PREFIX)
+            .unwrap_or(&any.type_url);
+        let value = &any.value;
+        match name {
+            CommandStatementQuery::TYPE_NAME => Ok(Self::StatementQuery(decode_message(value)?)),
+            TicketStatementQuery::TYPE_NAME => {
+                Ok(Self::TicketStatementQuery(decode_message(value)?))
+            }
+            CommandStatementUpdate::TYPE_NAME => Ok(Self::StatementUpdate(decode_message(value)?)),
+            CommandStatementIngest::TYPE_NAME => Ok(Self::StatementIngest(decode_message(value)?)),
+            CommandPreparedStatementQuery::TYPE_NAME => {
+                Ok(Self::PreparedStatementQuery(decode_message(value)?))
+            }
+            CommandPreparedStatementUpdate::TYPE_NAME => {
+                Ok(Self::PreparedStatementUpdate(decode_message(value)?))
+            }
+            CommandGetCatalogs::TYPE_NAME => Ok(Self::GetCatalogs(decode_message(value)?)),
+            CommandGetDbSchemas::TYPE_NAME => Ok(Self::GetDbSchemas(decode_message(value)?)),
+            CommandGetTables::TYPE_NAME => Ok(Self::GetTables(decode_message(value)?)),
+            CommandGetTableTypes::TYPE_NAME => Ok(Self::GetTableTypes(decode_message(value)?)),
+            _ => Err(Status::unimplemented(format!(
+                "Flight SQL command {} is not supported",
+                any.type_url
+            ))),
+        }
+    }
+}
+
+fn decode_any(bytes: &[u8]) -> Result<Any, Status> {
+    Any::decode(bytes).map_err(|e| {
+        Status::invalid_argument(format!("Cannot decode the Flight SQL command: {}", e))
+    })
+}
+
+fn decode_message<T: Message + Default>(bytes: &[u8]) -> Result<T, Status> {
+    T::decode(bytes).map_err(|e| {
+        Status::invalid_argument(format!("Cannot decode the Flight SQL message: {}", e))
+    })
+}
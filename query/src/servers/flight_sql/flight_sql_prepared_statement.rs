@@ -0,0 +1,175 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::ops::Range;
+
+use common_arrow::arrow::datatypes::DataType as ArrowType;
+use common_arrow::arrow::datatypes::Field as ArrowField;
+use common_arrow::arrow::datatypes::Schema as ArrowSchema;
+use common_ast::parser::token::TokenKind;
+use common_ast::parser::tokenize_sql;
+use common_datablocks::DataBlock;
+use common_datavalues::prelude::*;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_io::prelude::FormatSettings;
+
+use crate::servers::sql_literal::number_literal;
+use crate::servers::sql_literal::quote_string;
+
+/// A prepared statement of Flight SQL, created by the action `CreatePreparedStatement`.
+///
+/// The parameters are bound by `DoPut` with record batches, each row of which binds the
+/// placeholders `?` with literals, and the bound query is planned and executed like a
+/// statement.
+pub struct FlightSqlPreparedStatement {
+    query: String,
+    // spans of the placeholders in the query
+    placeholders: Vec<Range<usize>>,
+    // literals of the bound parameters, a row for each execution
+    params: Vec<Vec<String>>,
+}
+
+impl FlightSqlPreparedStatement {
+    pub fn try_create(query: &str) -> Result<FlightSqlPreparedStatement> {
+        let placeholders = tokenize_sql(query)?
+            .into_iter()
+            .filter(|token| token.kind == TokenKind::Placeholder)
+            .map(|token| token.span)
+            .collect();
+        Ok(FlightSqlPreparedStatement {
+            query: query.to_string(),
+            placeholders,
+            params: vec![],
+        })
+    }
+
+    /// The query with all the placeholders bound to `NULL`, which is used to describe the
+    /// result columns when preparing.
+    pub fn null_bound_query(&self) -> String {
+        self.replace_placeholders(vec!["NULL".to_string(); self.placeholders.len()])
+    }
+
+    /// Schema of the parameters. The types are unknown until execution, so they are
+    /// described as strings and the clients send the values with their own types.
+    pub fn parameter_schema(&self) -> Option<ArrowSchema> {
+        match self.placeholders.is_empty() {
+            true => None,
+            false => Some(ArrowSchema::from(
+                (0..self.placeholders.len())
+                    .map(|index| ArrowField::new(&format!("${}", index + 1), ArrowType::Utf8, true))
+                    .collect::<Vec<_>>(),
+            )),
+        }
+    }
+
+    /// Bind the rows of the parameter batches of `DoPut`, replacing the previous ones.
+    pub fn bind(&mut self, blocks: &[DataBlock], format: &FormatSettings) -> Result<()> {
+        let mut params = vec![];
+        for block in blocks {
+            if block.num_columns() != self.placeholders.len() {
+                return Err(ErrorCode::BadArguments(format!(
+                    "Prepared statement expects {} parameters, but got {}",
+                    self.placeholders.len(),
+                    block.num_columns()
+                )));
+            }
+            for row_index in 0..block.num_rows() {
+                let row = block
+                    .schema()
+                    .fields()
+                    .iter()
+                    .zip(block.columns())
+                    .map(|(field, column)| {
+                        param_literal(field.data_type(), column.get_checked(row_index)?, format)
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                params.push(row);
+            }
+        }
+        self.params = params;
+        Ok(())
+    }
+
+    /// The queries bound with each row of the parameters, the query itself if it has no
+    /// placeholders.
+    pub fn bound_queries(&self) -> Result<Vec<String>> {
+        if self.placeholders.is_empty() {
+            return Ok(vec![self.query.clone()]);
+        }
+        if self.params.is_empty() {
+            return Err(ErrorCode::BadArguments(format!(
+                "Prepared statement expects {} parameters, but none is bound",
+                self.placeholders.len()
+            )));
+        }
+        Ok(self
+            .params
+            .iter()
+            .map(|row| self.replace_placeholders(row.clone()))
+            .collect())
+    }
+
+    fn replace_placeholders(&self, literals: Vec<String>) -> String {
+        let mut query = String::with_capacity(self.query.len());
+        let mut position = 0;
+        for (span, literal) in self.placeholders.iter().zip(literals) {
+            query.push_str(&self.query[position..span.start]);
+            query.push_str(&literal);
+            position = span.end;
+        }
+        query.push_str(&self.query[position..]);
+        query
+    }
+}
+
+fn param_literal(
+    data_type: &DataTypeImpl,
+    value: DataValue,
+    format: &FormatSettings,
+) -> Result<String> {
+    let data_type = remove_nullable(data_type);
+    match value {
+        DataValue::Null => Ok("NULL".to_string()),
+        // Dates and timestamps are numbers in the values, they are bound by their texts.
+        value
+            if matches!(
+                data_type,
+                DataTypeImpl::Date(_) | DataTypeImpl::Timestamp(_)
+            ) =>
+        {
+            let text = data_type
+                .create_serializer()
+                .serialize_value(&value, format)?;
+            Ok(format!(
+                "CAST({} AS {})",
+                quote_string(&text),
+                data_type.name()
+            ))
+        }
+        DataValue::Boolean(v) => Ok(v.to_string().to_uppercase()),
+        DataValue::Int64(v) => Ok(number_literal(v.to_string())),
+        DataValue::UInt64(v) => Ok(v.to_string()),
+        DataValue::Float64(v) if !v.is_finite() => Err(ErrorCode::BadArguments(format!(
+            "Invalid parameter value {}",
+            v
+        ))),
+        DataValue::Float64(v) => Ok(number_literal(v.to_string())),
+        DataValue::String(v) => Ok(quote_string(&String::from_utf8_lossy(&v))),
+        _ => Err(ErrorCode::BadArguments(format!(
+            "Unsupported parameter type {}",
+            data_type.name()
+        ))),
+    }
+}
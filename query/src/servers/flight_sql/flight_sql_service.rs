@@ -0,0 +1,839 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use async_stream::stream;
+use common_arrow::arrow::array::ArrayRef;
+use common_arrow::arrow::chunk::Chunk;
+use common_arrow::arrow::datatypes::Schema as ArrowSchema;
+use common_arrow::arrow::io::flight::deserialize_batch;
+use common_arrow::arrow::io::flight::deserialize_schemas;
+use common_arrow::arrow::io::flight::serialize_batch;
+use common_arrow::arrow::io::flight::serialize_schema;
+use common_arrow::arrow::io::flight::serialize_schema_to_info;
+use common_arrow::arrow::io::ipc::write::default_ipc_fields;
+use common_arrow::arrow::io::ipc::write::WriteOptions;
+use common_arrow::arrow::io::ipc::IpcField;
+use common_arrow::arrow::io::ipc::IpcSchema;
+use common_arrow::arrow_format::flight::data::Action;
+use common_arrow::arrow_format::flight::data::ActionType;
+use common_arrow::arrow_format::flight::data::Criteria;
+use common_arrow::arrow_format::flight::data::Empty;
+use common_arrow::arrow_format::flight::data::FlightData;
+use common_arrow::arrow_format::flight::data::FlightDescriptor;
+use common_arrow::arrow_format::flight::data::FlightEndpoint;
+use common_arrow::arrow_format::flight::data::FlightInfo;
+use common_arrow::arrow_format::flight::data::HandshakeRequest;
+use common_arrow::arrow_format::flight::data::HandshakeResponse;
+use common_arrow::arrow_format::flight::data::PutResult;
+use common_arrow::arrow_format::flight::data::Result as FlightResult;
+use common_arrow::arrow_format::flight::data::SchemaResult;
+use common_arrow::arrow_format::flight::data::Ticket;
+use common_arrow::arrow_format::flight::service::flight_service_server::FlightService;
+use common_base::base::tokio::sync::mpsc;
+use common_base::base::TrySpawn;
+use common_base::infallible::Mutex;
+use common_datablocks::DataBlock;
+use common_datavalues::ColumnWithField;
+use common_datavalues::DataSchema;
+use common_datavalues::DataSchemaRef;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_functions::scalars::cast_column_field;
+use common_functions::scalars::FunctionContext;
+use common_meta_types::UserInfo;
+use common_planners::InsertInputSource;
+use common_planners::InsertPlan;
+use common_planners::PlanNode;
+use common_streams::SendableDataBlockStream;
+use common_tracing::tracing;
+use futures::StreamExt;
+use headers::authorization::Basic;
+use headers::authorization::Bearer;
+use headers::authorization::Credentials;
+use http::header::AUTHORIZATION;
+use prost::Message;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::Stream;
+use tonic::metadata::MetadataValue;
+use tonic::Request;
+use tonic::Response as RawResponse;
+use tonic::Status;
+use tonic::Streaming;
+use uuid::Uuid;
+
+use crate::interpreters::Interpreter;
+use crate::interpreters::InterpreterFactory;
+use crate::pipelines::new::processors::port::OutputPort;
+use crate::pipelines::new::processors::StreamSource;
+use crate::pipelines::new::SourcePipeBuilder;
+use crate::servers::flight_sql::flight_sql_catalog::*;
+use crate::servers::flight_sql::flight_sql_messages::*;
+use crate::servers::flight_sql::flight_sql_prepared_statement::FlightSqlPreparedStatement;
+use crate::sessions::QueryContext;
+use crate::sessions::SessionManager;
+use crate::sessions::SessionRef;
+use crate::sessions::SessionType;
+use crate::sql::PlanParser;
+use crate::users::auth::auth_mgr::Credential;
+
+pub type FlightStream<T> =
+    Pin<Box<dyn Stream<Item = std::result::Result<T, Status>> + Send + Sync + 'static>>;
+
+type Response<T> = std::result::Result<RawResponse<T>, Status>;
+type StreamReq<T> = Request<Streaming<T>>;
+
+// The bearer tokens returned by the handshake expire if unused for this duration.
+const TOKEN_IDLE_TIMEOUT: Duration = Duration::from_secs(3600);
+// The prepared statements are closed if unused for this duration.
+const STATEMENT_IDLE_TIMEOUT: Duration = Duration::from_secs(3600);
+
+#[derive(Clone)]
+struct Identity {
+    tenant: Option<String>,
+    user: UserInfo,
+}
+
+impl Identity {
+    fn is_same(&self, other: &Identity) -> bool {
+        self.tenant == other.tenant && self.user.identity() == other.user.identity()
+    }
+}
+
+struct BearerToken {
+    identity: Identity,
+    last_used: Instant,
+}
+
+// A prepared statement can only be executed or closed by the user who created it.
+struct PreparedStatement {
+    owner: Identity,
+    statement: FlightSqlPreparedStatement,
+    last_used: Instant,
+}
+
+/// The Flight SQL service. Every call is authenticated by the header `authorization`, with
+/// the user and password of `Basic`, or a `Bearer` token returned by the handshake or JWT.
+///
+/// A statement is executed in a new session of the user for each call, the result blocks
+/// are sent as record batches of Arrow IPC directly.
+pub struct FlightSqlService {
+    sessions: Arc<SessionManager>,
+    tokens: Mutex<HashMap<String, BearerToken>>,
+    statements: Mutex<HashMap<Vec<u8>, PreparedStatement>>,
+}
+
+impl FlightSqlService {
+    pub fn create(sessions: Arc<SessionManager>) -> FlightSqlService {
+        FlightSqlService {
+            sessions,
+            tokens: Mutex::new(HashMap::new()),
+            statements: Mutex::new(HashMap::new()),
+        }
+    }
+
+    async fn authenticate<T>(&self, request: &Request<T>) -> std::result::Result<Identity, Status> {
+        let headers = request.metadata().clone().into_headers();
+        let auth_headers = headers.get_all(AUTHORIZATION).iter().collect::<Vec<_>>();
+        let value = match auth_headers.as_slice() {
+            [value] => *value,
+            [] => return Err(Status::unauthenticated("No authorization header detected")),
+            _ => {
+                return Err(Status::unauthenticated(
+                    "Multiple authorization headers detected",
+                ))
+            }
+        };
+
+        let credential = if value.as_bytes().starts_with(b"Basic ") {
+            let basic = Basic::decode(value)
+                .ok_or_else(|| Status::unauthenticated("bad Basic auth header"))?;
+            let password = basic.password().as_bytes().to_vec();
+            Credential::Password {
+                name: basic.username().to_string(),
+                password: (!password.is_empty()).then_some(password),
+                hostname: request.remote_addr().map(|addr| addr.ip().to_string()),
+            }
+        } else if value.as_bytes().starts_with(b"Bearer ") {
+            let bearer = Bearer::decode(value)
+                .ok_or_else(|| Status::unauthenticated("bad Bearer auth header"))?;
+            if let Some(token) = self.tokens.lock().get_mut(bearer.token()) {
+                if token.last_used.elapsed() < TOKEN_IDLE_TIMEOUT {
+                    token.last_used = Instant::now();
+                    return Ok(token.identity.clone());
+                }
+            }
+            Credential::Jwt {
+                token: bearer.token().to_string(),
+            }
+        } else {
+            return Err(Status::unauthenticated("bad auth header"));
+        };
+
+        let auth_manager = self.sessions.get_auth_manager();
+        match auth_manager.auth(&credential).await {
+            Ok((tenant, user)) => Ok(Identity { tenant, user }),
+            Err(error) => Err(Status::unauthenticated(error.message())),
+        }
+    }
+
+    async fn create_session(&self, identity: &Identity) -> Result<SessionRef> {
+        let session = self.sessions.create_session(SessionType::FlightSQL).await?;
+        session.set_current_user(identity.user.clone());
+        if let Some(tenant) = identity.tenant.clone() {
+            session.set_current_tenant(tenant);
+        }
+        Ok(session)
+    }
+
+    async fn query_schema(&self, identity: &Identity, query: &str) -> Result<ArrowSchema> {
+        let session = self.create_session(identity).await?;
+        let ctx = session.create_query_context().await?;
+        let plan = PlanParser::parse(ctx, query).await?;
+        Ok(plan.schema().to_arrow())
+    }
+
+    // The prepared statement of the handle, which must be created by the same user and not
+    // expired yet.
+    fn with_prepared_statement<T>(
+        &self,
+        identity: &Identity,
+        handle: &[u8],
+        f: impl FnOnce(&mut FlightSqlPreparedStatement) -> Result<T>,
+    ) -> Result<T> {
+        let mut statements = self.statements.lock();
+        match statements.get_mut(handle) {
+            Some(prepared)
+                if prepared.owner.is_same(identity)
+                    && prepared.last_used.elapsed() < STATEMENT_IDLE_TIMEOUT =>
+            {
+                prepared.last_used = Instant::now();
+                f(&mut prepared.statement)
+            }
+            _ => Err(ErrorCode::BadArguments("Unknown prepared statement handle")),
+        }
+    }
+
+    // The queries of a prepared statement bound with the parameters.
+    fn prepared_queries(&self, identity: &Identity, handle: &[u8]) -> Result<Vec<String>> {
+        self.with_prepared_statement(identity, handle, |statement| statement.bound_queries())
+    }
+
+    fn prepared_query(&self, identity: &Identity, handle: &[u8]) -> Result<String> {
+        let mut queries = self.prepared_queries(identity, handle)?;
+        match queries.len() {
+            1 => Ok(queries.remove(0)),
+            n => Err(ErrorCode::BadArguments(format!(
+                "A prepared query is executed with one row of parameters, but got {}",
+                n
+            ))),
+        }
+    }
+
+    async fn flight_info(
+        &self,
+        identity: &Identity,
+        descriptor: FlightDescriptor,
+    ) -> std::result::Result<FlightInfo, Status> {
+        let (schema, ticket) = match FlightSqlCommand::decode(&descriptor.cmd)? {
+            FlightSqlCommand::StatementQuery(command) => {
+                let schema = self.query_schema(identity, &command.query).await?;
+                let ticket = TicketStatementQuery {
+                    statement_handle: command.query.into_bytes(),
+                };
+                (schema, ticket.to_any_bytes())
+            }
+            FlightSqlCommand::PreparedStatementQuery(command) => {
+                let handle = &command.prepared_statement_handle;
+                let query = self.prepared_query(identity, handle)?;
+                let schema = self.query_schema(identity, &query).await?;
+                (schema, command.to_any_bytes())
+            }
+            FlightSqlCommand::GetCatalogs(_) => (catalogs_schema(), descriptor.cmd.clone()),
+            FlightSqlCommand::GetDbSchemas(_) => (db_schemas_schema(), descriptor.cmd.clone()),
+            FlightSqlCommand::GetTables(command) => (
+                tables_schema(command.include_schema),
+                descriptor.cmd.clone(),
+            ),
+            FlightSqlCommand::GetTableTypes(_) => (table_types_schema(), descriptor.cmd.clone()),
+            command => {
+                return Err(Status::invalid_argument(format!(
+                    "Flight SQL command {:?} has no result",
+                    command
+                )))
+            }
+        };
+
+        Ok(FlightInfo {
+            schema: serialize_schema_to_info(&schema, None).map_err(ErrorCode::from)?,
+            flight_descriptor: Some(descriptor),
+            endpoint: vec![FlightEndpoint {
+                ticket: Some(Ticket { ticket }),
+                location: vec![],
+            }],
+            total_records: -1,
+            total_bytes: -1,
+        })
+    }
+
+    // Execute the query and stream the result blocks, the query is planned before responding
+    // and executed in the runtime of the query.
+    async fn query_stream(
+        &self,
+        identity: &Identity,
+        query: &str,
+    ) -> Result<FlightStream<FlightData>> {
+        let session = self.create_session(identity).await?;
+        let ctx = session.create_query_context().await?;
+        ctx.attach_query_str(query);
+        let plan = PlanParser::parse(ctx.clone(), query).await?;
+
+        let (tx, rx) = mpsc::channel(2);
+        let query_ctx = ctx.clone();
+        ctx.try_spawn(async move {
+            // The session is kept until the query is finished.
+            let _session = session;
+            let (interpreter, mut data_stream) = match execute(&query_ctx, plan, None).await {
+                Ok(executed) => executed,
+                Err(error) => {
+                    let _ = tx.send(Err(Status::from(error))).await;
+                    return;
+                }
+            };
+
+            let mut encoder = FlightDataEncoder::default();
+            while let Some(block) = data_stream.next().await {
+                match block.and_then(|block| encoder.encode_block(block)) {
+                    Ok(messages) => {
+                        for message in messages {
+                            if tx.send(Ok(message)).await.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                    Err(error) => {
+                        let _ = tx.send(Err(Status::from(error))).await;
+                        return;
+                    }
+                }
+            }
+
+            let _ = interpreter
+                .finish()
+                .await
+                .map_err(|e| tracing::error!("interpreter.finish error: {:?}", e));
+            if let Some(message) = encoder.finish(&interpreter.schema().to_arrow()) {
+                let _ = tx.send(Ok(message)).await;
+            }
+        })?;
+
+        Ok(Box::pin(ReceiverStream::new(rx)))
+    }
+
+    async fn metadata_stream(
+        &self,
+        identity: &Identity,
+        command: FlightSqlCommand,
+    ) -> Result<FlightStream<FlightData>> {
+        let session = self.create_session(identity).await?;
+        let ctx = session.create_query_context().await?;
+        let (schema, chunk) = match &command {
+            FlightSqlCommand::GetCatalogs(_) => (catalogs_schema(), get_catalogs(&ctx)?),
+            FlightSqlCommand::GetDbSchemas(command) => {
+                (db_schemas_schema(), get_db_schemas(&ctx, command).await?)
+            }
+            FlightSqlCommand::GetTables(command) => (
+                tables_schema(command.include_schema),
+                get_tables(&ctx, command).await?,
+            ),
+            FlightSqlCommand::GetTableTypes(_) => (table_types_schema(), get_table_types()?),
+            _ => {
+                return Err(ErrorCode::LogicalError(format!(
+                    "Flight SQL command {:?} is not a metadata command",
+                    command
+                )))
+            }
+        };
+
+        let mut encoder = FlightDataEncoder::default();
+        let messages = encoder.encode_chunk(&schema, &chunk);
+        Ok(Box::pin(tokio_stream::iter(
+            messages.into_iter().map(Ok::<_, Status>),
+        )))
+    }
+
+    // Execute a statement without result, e.g. `INSERT` and DDL, the number of written rows
+    // is returned.
+    async fn execute_update(&self, identity: &Identity, query: &str) -> Result<i64> {
+        let session = self.create_session(identity).await?;
+        let ctx = session.create_query_context().await?;
+        ctx.attach_query_str(query);
+        let plan = PlanParser::parse(ctx.clone(), query).await?;
+        execute_to_end(&ctx, plan, None).await
+    }
+
+    async fn bind_prepared_statement(
+        &self,
+        identity: &Identity,
+        handle: &[u8],
+        blocks: &[DataBlock],
+    ) -> Result<()> {
+        let session = self.create_session(identity).await?;
+        let ctx = session.create_query_context().await?;
+        let format = ctx.get_format_settings()?;
+
+        self.with_prepared_statement(identity, handle, |statement| {
+            statement.bind(blocks, &format)
+        })
+    }
+
+    // Ingest the record batches into the table, the columns are matched by names and cast
+    // to the types of the table.
+    async fn ingest(
+        &self,
+        identity: &Identity,
+        command: CommandStatementIngest,
+        first: FlightData,
+        mut input: Streaming<FlightData>,
+    ) -> Result<i64> {
+        if command.temporary {
+            return Err(ErrorCode::UnImplement(
+                "Ingestion into a temporary table is not supported",
+            ));
+        }
+
+        let session = self.create_session(identity).await?;
+        let ctx = session.create_query_context().await?;
+        let catalog_name = command.catalog.unwrap_or_else(|| ctx.get_current_catalog());
+        let database_name = command.schema.unwrap_or_else(|| ctx.get_current_database());
+        let table_name = command.table;
+        let table = ctx
+            .get_table(&catalog_name, &database_name, &table_name)
+            .await?;
+        ctx.attach_query_str(&format!(
+            "INSERT INTO {}.{} FORMAT Arrow",
+            database_name, table_name
+        ));
+
+        let (arrow_schema, ipc_schema) = deserialize_schemas(&first.data_header)?;
+        let source_schema = Arc::new(DataSchema::from(&arrow_schema));
+        let target_schema = table.schema();
+        let func_ctx = ctx.try_get_function_context()?;
+        let input_schema = target_schema.clone();
+        let input_stream = stream! {
+            loop {
+                let data = match input.message().await {
+                    Ok(Some(data)) => data,
+                    Ok(None) => break,
+                    Err(status) => {
+                        yield Err(ErrorCode::BadBytes(status.message()));
+                        break;
+                    }
+                };
+                if data.data_body.is_empty() {
+                    continue;
+                }
+                yield decode_block(&data, &arrow_schema, &ipc_schema, &source_schema)
+                    .and_then(|block| cast_block(&block, &input_schema, &func_ctx));
+            }
+        };
+
+        let plan = PlanNode::Insert(InsertPlan {
+            catalog_name,
+            database_name,
+            table_name,
+            table_id: table.get_id(),
+            schema: target_schema,
+            overwrite: false,
+            source: InsertInputSource::StreamingWithFormat("Arrow".to_string()),
+        });
+        let input_stream: SendableDataBlockStream = Box::pin(input_stream);
+        execute_to_end(&ctx, plan, Some(input_stream)).await
+    }
+}
+
+#[async_trait::async_trait]
+impl FlightService for FlightSqlService {
+    type HandshakeStream = FlightStream<HandshakeResponse>;
+
+    async fn handshake(
+        &self,
+        request: StreamReq<HandshakeRequest>,
+    ) -> Response<Self::HandshakeStream> {
+        let identity = self.authenticate(&request).await?;
+        let token = Uuid::new_v4().to_string();
+        {
+            let mut tokens = self.tokens.lock();
+            tokens.retain(|_, token| token.last_used.elapsed() < TOKEN_IDLE_TIMEOUT);
+            tokens.insert(token.clone(), BearerToken {
+                identity,
+                last_used: Instant::now(),
+            });
+        }
+
+        let authorization = MetadataValue::from_str(&format!("Bearer {}", token))
+            .map_err(|e| Status::internal(e.to_string()))?;
+        let output = tokio_stream::once(Ok(HandshakeResponse {
+            protocol_version: 0,
+            payload: token.into_bytes(),
+        }));
+        let mut response = RawResponse::new(Box::pin(output) as Self::HandshakeStream);
+        response
+            .metadata_mut()
+            .insert("authorization", authorization);
+        Ok(response)
+    }
+
+    type ListFlightsStream = FlightStream<FlightInfo>;
+
+    async fn list_flights(&self, _: Request<Criteria>) -> Response<Self::ListFlightsStream> {
+        Err(Status::unimplemented(
+            "Flight SQL does not implement list_flights.",
+        ))
+    }
+
+    async fn get_flight_info(&self, request: Request<FlightDescriptor>) -> Response<FlightInfo> {
+        let identity = self.authenticate(&request).await?;
+        let info = self.flight_info(&identity, request.into_inner()).await?;
+        Ok(RawResponse::new(info))
+    }
+
+    async fn get_schema(&self, request: Request<FlightDescriptor>) -> Response<SchemaResult> {
+        let identity = self.authenticate(&request).await?;
+        let info = self.flight_info(&identity, request.into_inner()).await?;
+        Ok(RawResponse::new(SchemaResult {
+            schema: info.schema,
+        }))
+    }
+
+    type DoGetStream = FlightStream<FlightData>;
+
+    async fn do_get(&self, request: Request<Ticket>) -> Response<Self::DoGetStream> {
+        let identity = self.authenticate(&request).await?;
+        let stream = match FlightSqlCommand::decode(&request.into_inner().ticket)? {
+            FlightSqlCommand::TicketStatementQuery(ticket) => {
+                let query = String::from_utf8(ticket.statement_handle)
+                    .map_err(|_| Status::invalid_argument("Invalid statement handle"))?;
+                self.query_stream(&identity, &query).await?
+            }
+            FlightSqlCommand::PreparedStatementQuery(command) => {
+                let handle = &command.prepared_statement_handle;
+                let query = self.prepared_query(&identity, handle)?;
+                self.query_stream(&identity, &query).await?
+            }
+            command => self.metadata_stream(&identity, command).await?,
+        };
+        Ok(RawResponse::new(stream))
+    }
+
+    type DoPutStream = FlightStream<PutResult>;
+
+    async fn do_put(&self, request: StreamReq<FlightData>) -> Response<Self::DoPutStream> {
+        let identity = self.authenticate(&request).await?;
+        let mut input = request.into_inner();
+        let first = input
+            .message()
+            .await?
+            .ok_or_else(|| Status::invalid_argument("Empty DoPut stream"))?;
+        let descriptor = first
+            .flight_descriptor
+            .clone()
+            .ok_or_else(|| Status::invalid_argument("DoPut requires a flight descriptor"))?;
+
+        let app_metadata = match FlightSqlCommand::decode(&descriptor.cmd)? {
+            FlightSqlCommand::StatementUpdate(command) => {
+                let record_count = self.execute_update(&identity, &command.query).await?;
+                DoPutUpdateResult { record_count }.encode_to_vec()
+            }
+            FlightSqlCommand::PreparedStatementQuery(command) => {
+                let handle = &command.prepared_statement_handle;
+                let blocks = read_blocks(first, input).await?;
+                self.bind_prepared_statement(&identity, handle, &blocks)
+                    .await?;
+                vec![]
+            }
+            FlightSqlCommand::PreparedStatementUpdate(command) => {
+                let handle = &command.prepared_statement_handle;
+                let blocks = read_blocks(first, input).await?;
+                if !blocks.is_empty() {
+                    self.bind_prepared_statement(&identity, handle, &blocks)
+                        .await?;
+                }
+                let mut record_count = 0;
+                for query in self.prepared_queries(&identity, handle)? {
+                    record_count += self.execute_update(&identity, &query).await?;
+                }
+                DoPutUpdateResult { record_count }.encode_to_vec()
+            }
+            FlightSqlCommand::StatementIngest(command) => {
+                let record_count = self.ingest(&identity, command, first, input).await?;
+                DoPutUpdateResult { record_count }.encode_to_vec()
+            }
+            command => {
+                return Err(Status::invalid_argument(format!(
+                    "Flight SQL command {:?} is not supported by DoPut",
+                    command
+                )))
+            }
+        };
+
+        Ok(RawResponse::new(
+            Box::pin(tokio_stream::once(Ok(PutResult { app_metadata }))) as Self::DoPutStream,
+        ))
+    }
+
+    type DoExchangeStream = FlightStream<FlightData>;
+
+    async fn do_exchange(&self, _: StreamReq<FlightData>) -> Response<Self::DoExchangeStream> {
+        Err(Status::unimplemented(
+            "Flight SQL does not implement do_exchange.",
+        ))
+    }
+
+    type DoActionStream = FlightStream<FlightResult>;
+
+    async fn do_action(&self, request: Request<Action>) -> Response<Self::DoActionStream> {
+        let identity = self.authenticate(&request).await?;
+        let action = request.into_inner();
+        let body = match action.r#type.as_str() {
+            CREATE_PREPARED_STATEMENT => {
+                let request = ActionCreatePreparedStatementRequest::from_any_bytes(&action.body)?;
+                let statement = FlightSqlPreparedStatement::try_create(&request.query)?;
+                let dataset_schema = self
+                    .query_schema(&identity, &statement.null_bound_query())
+                    .await?;
+                let parameter_schema = match statement.parameter_schema() {
+                    Some(schema) => {
+                        serialize_schema_to_info(&schema, None).map_err(ErrorCode::from)?
+                    }
+                    None => vec![],
+                };
+
+                let handle = Uuid::new_v4().to_string().into_bytes();
+                {
+                    let mut statements = self.statements.lock();
+                    statements.retain(|_, prepared| {
+                        prepared.last_used.elapsed() < STATEMENT_IDLE_TIMEOUT
+                    });
+                    statements.insert(handle.clone(), PreparedStatement {
+                        owner: identity,
+                        statement,
+                        last_used: Instant::now(),
+                    });
+                }
+                ActionCreatePreparedStatementResult {
+                    prepared_statement_handle: handle,
+                    dataset_schema: serialize_schema_to_info(&dataset_schema, None)
+                        .map_err(ErrorCode::from)?,
+                    parameter_schema,
+                }
+                .to_any_bytes()
+            }
+            CLOSE_PREPARED_STATEMENT => {
+                let request = ActionClosePreparedStatementRequest::from_any_bytes(&action.body)?;
+                let handle = &request.prepared_statement_handle;
+                let mut statements = self.statements.lock();
+                match statements.get(handle) {
+                    Some(prepared) if prepared.owner.is_same(&identity) => {
+                        statements.remove(handle);
+                    }
+                    _ => {
+                        return Err(Status::invalid_argument(
+                            "Unknown prepared statement handle",
+                        ))
+                    }
+                }
+                vec![]
+            }
+            action_type => {
+                return Err(Status::unimplemented(format!(
+                    "Flight SQL does not implement action {}.",
+                    action_type
+                )))
+            }
+        };
+
+        Ok(RawResponse::new(
+            Box::pin(tokio_stream::once(Ok(FlightResult { body }))) as Self::DoActionStream,
+        ))
+    }
+
+    type ListActionsStream = FlightStream<ActionType>;
+
+    async fn list_actions(&self, _: Request<Empty>) -> Response<Self::ListActionsStream> {
+        Ok(RawResponse::new(Box::pin(tokio_stream::iter(vec![
+            Ok(ActionType {
+                r#type: CREATE_PREPARED_STATEMENT.to_string(),
+                description: "Create a prepared statement of a query".to_string(),
+            }),
+            Ok(ActionType {
+                r#type: CLOSE_PREPARED_STATEMENT.to_string(),
+                description: "Close a prepared statement".to_string(),
+            }),
+        ])) as Self::ListActionsStream))
+    }
+}
+
+async fn execute(
+    ctx: &Arc<QueryContext>,
+    plan: PlanNode,
+    input_stream: Option<SendableDataBlockStream>,
+) -> Result<(Arc<dyn Interpreter>, SendableDataBlockStream)> {
+    let interpreter = InterpreterFactory::get(ctx.clone(), plan)?;
+    let _ = interpreter
+        .start()
+        .await
+        .map_err(|e| tracing::error!("interpreter.start.error: {:?}", e));
+    let data_stream: SendableDataBlockStream =
+        if ctx.get_settings().get_enable_new_processor_framework()? != 0
+            && ctx.get_cluster().is_empty()
+        {
+            let output_port = OutputPort::create();
+            let stream_source =
+                StreamSource::create(ctx.clone(), input_stream, output_port.clone())?;
+            let mut source_pipe_builder = SourcePipeBuilder::create();
+            source_pipe_builder.add_source(output_port, stream_source);
+            let _ = interpreter
+                .set_source_pipe_builder(Option::from(source_pipe_builder))
+                .map_err(|e| tracing::error!("interpreter.set_source_pipe_builder.error: {:?}", e));
+            interpreter.execute(None).await?
+        } else {
+            interpreter.execute(input_stream).await?
+        };
+    let data_stream = ctx.try_create_abortable(data_stream)?;
+    Ok((interpreter, Box::pin(data_stream)))
+}
+
+// Execute to the end and ignore the result, the number of written rows is returned.
+async fn execute_to_end(
+    ctx: &Arc<QueryContext>,
+    plan: PlanNode,
+    input_stream: Option<SendableDataBlockStream>,
+) -> Result<i64> {
+    let (interpreter, mut data_stream) = execute(ctx, plan, input_stream).await?;
+    while let Some(block) = data_stream.next().await {
+        block?;
+    }
+    let _ = interpreter
+        .finish()
+        .await
+        .map_err(|e| tracing::error!("interpreter.finish error: {:?}", e));
+    Ok(ctx.get_write_progress_value().rows as i64)
+}
+
+// Read the record batches of `DoPut`, the schema is in the first message.
+async fn read_blocks(
+    first: FlightData,
+    mut input: Streaming<FlightData>,
+) -> Result<Vec<DataBlock>> {
+    if first.data_header.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let (arrow_schema, ipc_schema) = deserialize_schemas(&first.data_header)?;
+    let schema = Arc::new(DataSchema::from(&arrow_schema));
+    let mut blocks = vec![];
+    while let Some(data) = input
+        .message()
+        .await
+        .map_err(|status| ErrorCode::BadBytes(status.message()))?
+    {
+        if data.data_body.is_empty() {
+            continue;
+        }
+        blocks.push(decode_block(&data, &arrow_schema, &ipc_schema, &schema)?);
+    }
+    Ok(blocks)
+}
+
+fn decode_block(
+    data: &FlightData,
+    arrow_schema: &ArrowSchema,
+    ipc_schema: &IpcSchema,
+    schema: &DataSchemaRef,
+) -> Result<DataBlock> {
+    let chunk = deserialize_batch(data, &arrow_schema.fields, ipc_schema, &Default::default())?;
+    DataBlock::from_chunk(schema, &chunk)
+}
+
+// Select the columns of the schema by names and cast them to the types of the schema.
+fn cast_block(
+    block: &DataBlock,
+    schema: &DataSchemaRef,
+    func_ctx: &FunctionContext,
+) -> Result<DataBlock> {
+    let columns = schema
+        .fields()
+        .iter()
+        .map(|field| {
+            let column = block.try_column_by_name(field.name())?;
+            let from = block.schema().field_with_name(field.name())?;
+            let column_with_field = ColumnWithField::new(column.clone(), from.clone());
+            cast_column_field(
+                &column_with_field,
+                from.data_type(),
+                field.data_type(),
+                func_ctx,
+            )
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(DataBlock::create(schema.clone(), columns))
+}
+
+/// Encode the blocks to the messages of Arrow IPC, the schema is sent before the first
+/// record batch.
+#[derive(Default)]
+struct FlightDataEncoder {
+    ipc_fields: Option<Vec<IpcField>>,
+}
+
+impl FlightDataEncoder {
+    fn encode_block(&mut self, block: DataBlock) -> Result<Vec<FlightData>> {
+        let schema = block.schema().to_arrow();
+        let chunk = Chunk::try_from(block)?;
+        Ok(self.encode_chunk(&schema, &chunk))
+    }
+
+    fn encode_chunk(&mut self, schema: &ArrowSchema, chunk: &Chunk<ArrayRef>) -> Vec<FlightData> {
+        let mut messages = vec![];
+        if let Some(message) = self.finish(schema) {
+            messages.push(message);
+        }
+
+        if !chunk.is_empty() {
+            let options = WriteOptions { compression: None };
+            let ipc_fields = self.ipc_fields.as_deref().unwrap_or(&[]);
+            let (dicts, batch) = serialize_batch(chunk, ipc_fields, &options);
+            messages.extend(dicts);
+            messages.push(batch);
+        }
+        messages
+    }
+
+    // The schema if no block is encoded.
+    fn finish(&mut self, schema: &ArrowSchema) -> Option<FlightData> {
+        if self.ipc_fields.is_some() {
+            return None;
+        }
+        let ipc_fields = default_ipc_fields(&schema.fields);
+        let message = serialize_schema(schema, Some(&ipc_fields));
+        self.ipc_fields = Some(ipc_fields);
+        Some(message)
+    }
+}
@@ -12,7 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Instant;
 
 use async_stream::stream;
 use common_exception::ErrorCode;
@@ -20,6 +22,7 @@ use common_exception::Result;
 use common_exception::ToErrorCode;
 use common_io::prelude::FormatSettings;
 use common_planners::PlanNode;
+use common_streams::CsvSourceBuilder;
 use common_streams::NDJsonSourceBuilder;
 use common_streams::SendableDataBlockStream;
 use common_streams::SourceStream;
@@ -33,15 +36,20 @@ use poem::web::Query;
 use poem::Body;
 use poem::Endpoint;
 use poem::EndpointExt;
+use poem::Response;
 use poem::Route;
 use serde::Deserialize;
+use serde_json::json;
 
 use crate::interpreters::InterpreterFactory;
 use crate::pipelines::new::processors::port::OutputPort;
 use crate::pipelines::new::processors::StreamSource;
 use crate::pipelines::new::SourcePipeBuilder;
-use crate::servers::http::formats::tsv_output::block_to_tsv;
+use crate::servers::http::clickhouse_query_params::bind_query_params;
+use crate::servers::http::clickhouse_query_params::split_format_clause;
 use crate::servers::http::formats::Format;
+use crate::servers::http::formats::OutputFormat;
+use crate::servers::http::formats::OutputFormatter;
 use crate::servers::http::v1::HttpQueryContext;
 use crate::sessions::QueryContext;
 use crate::sessions::SessionType;
@@ -52,21 +60,104 @@ use crate::sql::PlanParser;
 // https://clickhouse.com/docs/en/interfaces/http/
 
 const FORMAT_JSON_EACH_ROW: &str = "JSONEachRow";
+const FORMAT_CSV: &str = "CSV";
+const FORMAT_CSV_WITH_NAMES: &str = "CSVWithNames";
+const FORMAT_TSV: &str = "TSV";
+const FORMAT_TSV_WITH_NAMES: &str = "TSVWithNames";
+const FORMAT_TAB_SEPARATED: &str = "TabSeparated";
+const FORMAT_TAB_SEPARATED_WITH_NAMES: &str = "TabSeparatedWithNames";
+
+const QUERY_PARAM_PREFIX: &str = "param_";
 
 #[derive(Deserialize)]
 pub struct StatementHandlerParams {
+    #[serde(default)]
     query: String,
+    // The other URL parameters: `database`, `query_id`, `default_format`, the typed query
+    // parameters `param_<name>` and the settings of the query.
+    #[serde(flatten)]
+    params: HashMap<String, String>,
+}
+
+struct HandlerOptions {
+    default_format: OutputFormat,
+    wait_end_of_query: bool,
+    send_progress_in_http_headers: bool,
+    query_params: HashMap<String, String>,
 }
 
 fn supported_formats() -> String {
-    vec![FORMAT_JSON_EACH_ROW].join("|")
+    vec![
+        FORMAT_JSON_EACH_ROW,
+        FORMAT_CSV,
+        FORMAT_CSV_WITH_NAMES,
+        FORMAT_TAB_SEPARATED,
+        FORMAT_TAB_SEPARATED_WITH_NAMES,
+    ]
+    .join("|")
+}
+
+async fn apply_params(
+    ctx: &Arc<QueryContext>,
+    params: &HashMap<String, String>,
+) -> Result<HandlerOptions> {
+    let mut options = HandlerOptions {
+        default_format: OutputFormat::TabSeparated,
+        wait_end_of_query: false,
+        send_progress_in_http_headers: false,
+        query_params: HashMap::new(),
+    };
+
+    let settings = ctx.get_settings();
+    for (key, value) in params {
+        match key.as_str() {
+            "database" => ctx.set_current_database(value.clone()).await?,
+            "query_id" if !value.is_empty() => ctx.set_id(value.clone()),
+            "default_format" => options.default_format = OutputFormat::try_create(value)?,
+            "wait_end_of_query" => options.wait_end_of_query = value == "1",
+            "send_progress_in_http_headers" => options.send_progress_in_http_headers = value == "1",
+            key if key.starts_with(QUERY_PARAM_PREFIX) => {
+                let name = &key[QUERY_PARAM_PREFIX.len()..];
+                options.query_params.insert(name.to_string(), value.clone());
+            }
+            key if settings.has_setting(key) => {
+                settings.set_settings(key.to_string(), value.clone(), false)?
+            }
+            // `user` and `password` are taken by the `HTTPSessionMiddleware`, e.g. `compress`.
+            key => tracing::debug!("ignore the URL parameter {} of the ClickHouse handler", key),
+        }
+    }
+    Ok(options)
+}
+
+// The output format of a query is the `FORMAT` clause, or else the `default_format`.
+fn output_format(format_clause: Option<&str>, options: &HandlerOptions) -> Result<OutputFormat> {
+    match format_clause {
+        Some(name) => OutputFormat::try_create(name),
+        None => Ok(options.default_format),
+    }
+}
+
+fn progress_header(ctx: &Arc<QueryContext>) -> String {
+    let scan_progress = ctx.get_scan_progress_value();
+    let write_progress = ctx.get_write_progress_value();
+    json!({
+        "read_rows": scan_progress.rows.to_string(),
+        "read_bytes": scan_progress.bytes.to_string(),
+        "written_rows": write_progress.rows.to_string(),
+        "written_bytes": write_progress.bytes.to_string(),
+    })
+    .to_string()
 }
 
 async fn execute(
     ctx: Arc<QueryContext>,
     plan: PlanNode,
+    format: OutputFormat,
+    options: &HandlerOptions,
     input_stream: Option<SendableDataBlockStream>,
-) -> Result<Body> {
+) -> Result<Response> {
+    let start = Instant::now();
     let interpreter = InterpreterFactory::get(ctx.clone(), plan.clone())?;
     let _ = interpreter
         .start()
@@ -89,12 +180,14 @@ async fn execute(
             interpreter.execute(input_stream).await?
         };
     let mut data_stream = ctx.try_create_abortable(data_stream)?;
-    let format = ctx.get_format_settings()?;
+    let mut formatter = OutputFormatter::create(format, ctx.get_format_settings()?);
+    let schema = interpreter.schema();
+    let stream_ctx = ctx.clone();
     let stream = stream! {
         while let Some(block) = data_stream.next().await {
             match block{
                 Ok(block) => {
-                    yield(block_to_tsv(&block, &format))
+                    yield(formatter.serialize_block(&block))
                 },
                 Err(err) => yield(Err(err)),
             };
@@ -104,16 +197,45 @@ async fn execute(
             .finish()
             .await
             .map_err(|e| tracing::error!("interpreter.finish error: {:?}", e));
+
+        let progress = stream_ctx.get_scan_progress_value();
+        yield(formatter.finish(&schema, &progress, start.elapsed()))
     };
 
-    Ok(Body::from_bytes_stream(stream))
+    let timezone = String::from_utf8_lossy(&ctx.get_settings().get_timezone()?).to_string();
+    let builder = Response::builder()
+        .header("X-ClickHouse-Query-Id", ctx.get_id())
+        .header("X-ClickHouse-Format", format.name())
+        .header("X-ClickHouse-Timezone", timezone)
+        .content_type(format.content_type());
+
+    // The headers of a streaming response are sent before the first block, so the summary is
+    // only sent if the result is buffered until the end of the query.
+    let (builder, body) = match options.wait_end_of_query {
+        true => {
+            let mut buf = vec![];
+            let mut stream = Box::pin(stream);
+            while let Some(bytes) = stream.next().await {
+                buf.extend(bytes?);
+            }
+            let builder = builder.header("X-ClickHouse-Summary", progress_header(&ctx));
+            (builder, Body::from(buf))
+        }
+        false => (builder, Body::from_bytes_stream(stream)),
+    };
+
+    let builder = match options.send_progress_in_http_headers {
+        true => builder.header("X-ClickHouse-Progress", progress_header(&ctx)),
+        false => builder,
+    };
+    Ok(builder.body(body))
 }
 
 #[poem::handler]
 pub async fn clickhouse_handler_get(
     ctx: &HttpQueryContext,
     Query(params): Query<StatementHandlerParams>,
-) -> PoemResult<Body> {
+) -> PoemResult<Response> {
     let session = ctx
         .create_session(SessionType::ClickHouseHttpHandler)
         .await
@@ -124,8 +246,14 @@ pub async fn clickhouse_handler_get(
         .await
         .map_err(InternalServerError)?;
 
-    let mut sql = params.query;
-    let plan = PlanParser::parse(context.clone(), &sql)
+    let options = apply_params(&context, &params.params)
+        .await
+        .map_err(BadRequest)?;
+    let sql = bind_query_params(&params.query, &options.query_params).map_err(BadRequest)?;
+    let (sql, format_clause) = split_format_clause(&sql);
+    let format = output_format(format_clause, &options).map_err(BadRequest)?;
+
+    let plan = PlanParser::parse(context.clone(), sql)
         .await
         .map_err(BadRequest)?;
 
@@ -134,8 +262,8 @@ pub async fn clickhouse_handler_get(
             "not allow insert in GET",
         )));
     }
-    context.attach_query_str(&sql);
-    execute(context, plan, None)
+    context.attach_query_str(sql);
+    execute(context, plan, format, &options, None)
         .await
         .map_err(InternalServerError)
 }
@@ -154,10 +282,16 @@ fn try_parse_insert_formatted(
         let statement = &statements[0];
         if let DfStatement::InsertQuery(ref insert) = statement {
             if let Some(format) = &insert.format {
-                match format.as_str() {
-                    FORMAT_JSON_EACH_ROW => return Ok(Some((Format::NDJson, statements))),
+                let format = match format.as_str() {
+                    FORMAT_JSON_EACH_ROW => Format::NDJson,
+                    FORMAT_CSV => Format::Csv { skip_header: false },
+                    FORMAT_CSV_WITH_NAMES => Format::Csv { skip_header: true },
+                    FORMAT_TSV | FORMAT_TAB_SEPARATED => Format::Tsv { skip_header: false },
+                    FORMAT_TSV_WITH_NAMES | FORMAT_TAB_SEPARATED_WITH_NAMES => {
+                        Format::Tsv { skip_header: true }
+                    }
                     // "" for "insert into my_table values;"
-                    "" => {}
+                    "" => return Ok(None),
                     _ => {
                         return Err(ErrorCode::SyntaxException(format!(
                             "format {} not supported; only support: {}",
@@ -166,6 +300,7 @@ fn try_parse_insert_formatted(
                         )))
                     }
                 };
+                return Ok(Some((format, statements)));
             }
         };
     }
@@ -177,7 +312,7 @@ pub async fn clickhouse_handler_post(
     ctx: &HttpQueryContext,
     body: Body,
     Query(params): Query<StatementHandlerParams>,
-) -> PoemResult<Body> {
+) -> PoemResult<Response> {
     let session = ctx
         .create_session(SessionType::ClickHouseHttpHandler)
         .await
@@ -188,10 +323,13 @@ pub async fn clickhouse_handler_post(
         .await
         .map_err(InternalServerError)?;
 
-    let mut sql = params.query;
+    let options = apply_params(&ctx, &params.params)
+        .await
+        .map_err(BadRequest)?;
+    let sql = bind_query_params(&params.query, &options.query_params).map_err(BadRequest)?;
 
     // Insert into format sql
-    let (plan, input_stream) = if let Some((format, statements)) =
+    let (plan, format, input_stream) = if let Some((format, statements)) =
         try_parse_insert_formatted(&sql, ctx.get_current_session().get_type())
             .map_err(BadRequest)?
     {
@@ -200,26 +338,37 @@ pub async fn clickhouse_handler_post(
             .map_err(InternalServerError)?;
         ctx.attach_query_str(&sql);
 
+        let format_settings = ctx.get_format_settings().map_err(InternalServerError)?;
         let input_stream = match format {
-            Format::NDJson => build_ndjson_stream(&plan, body).await.map_err(BadRequest)?,
-        };
-        (plan, Some(input_stream))
+            Format::NDJson => build_ndjson_stream(&plan, body).await,
+            Format::Csv { skip_header } => {
+                build_csv_stream(&plan, body, format_settings, ",", skip_header).await
+            }
+            Format::Tsv { skip_header } => {
+                build_csv_stream(&plan, body, format_settings, "\t", skip_header).await
+            }
+        }
+        .map_err(BadRequest)?;
+        (plan, options.default_format, Some(input_stream))
     } else {
         // Other sql
         let body = body.into_string().await.map_err(BadRequest)?;
-        let sql = format!("{}\n{}", sql, body);
+        let sql = format!("{}\n{}", params.query, body);
+        let sql = bind_query_params(&sql, &options.query_params).map_err(BadRequest)?;
+        let (sql, format_clause) = split_format_clause(&sql);
+        let format = output_format(format_clause, &options).map_err(BadRequest)?;
         let (statements, _) =
-            DfParser::parse_sql(&sql, ctx.get_current_session().get_type()).map_err(BadRequest)?;
+            DfParser::parse_sql(sql, ctx.get_current_session().get_type()).map_err(BadRequest)?;
 
         let plan = PlanParser::build_plan(statements, ctx.clone())
             .await
             .map_err(InternalServerError)?;
-        ctx.attach_query_str(&sql);
+        ctx.attach_query_str(sql);
 
-        (plan, None)
+        (plan, format, None)
     };
 
-    execute(ctx, plan, input_stream)
+    execute(ctx, plan, format, &options, input_stream)
         .await
         .map_err(InternalServerError)
 }
@@ -236,6 +385,27 @@ async fn build_ndjson_stream(plan: &PlanNode, body: Body) -> Result<SendableData
     SourceStream::new(Box::new(source)).execute().await
 }
 
+async fn build_csv_stream(
+    plan: &PlanNode,
+    body: Body,
+    format_settings: FormatSettings,
+    field_delimiter: &str,
+    skip_header: bool,
+) -> Result<SendableDataBlockStream> {
+    let mut builder = CsvSourceBuilder::create(plan.schema(), format_settings);
+    builder
+        .field_delimiter(field_delimiter)
+        .record_delimiter("\n")
+        .skip_header(skip_header);
+    let cursor = futures::io::Cursor::new(
+        body.into_vec()
+            .await
+            .map_err_to_code(ErrorCode::BadBytes, || "fail to read body")?,
+    );
+    let source = builder.build(cursor)?;
+    SourceStream::new(Box::new(source)).execute().await
+}
+
 pub fn clickhouse_router() -> impl Endpoint {
     Route::new()
         .at(
@@ -0,0 +1,188 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use common_ast::parser::token::TokenKind;
+use common_ast::parser::tokenize_sql;
+use common_exception::ErrorCode;
+use common_exception::Result;
+
+use crate::servers::sql_literal::number_literal;
+use crate::servers::sql_literal::quote_string;
+
+// https://clickhouse.com/docs/en/interfaces/cli/#cli-queries-with-parameters
+
+/// Bind the typed query parameters `{name:Type}` in the query to the values of the URL
+/// parameters `param_<name>`. The values are bound as literals of the types, so nothing but
+/// a literal can be injected into the query.
+pub fn bind_query_params(query: &str, params: &HashMap<String, String>) -> Result<String> {
+    let bytes = query.as_bytes();
+    let mut bound = String::with_capacity(query.len());
+    let mut position = 0;
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            quote @ (b'\'' | b'"' | b'`') => index = skip_quoted(bytes, index, quote),
+            b'-' if bytes.get(index + 1) == Some(&b'-') => {
+                index = skip_until(bytes, index, "\n");
+            }
+            b'/' if bytes.get(index + 1) == Some(&b'*') => {
+                index = skip_until(bytes, index, "*/");
+            }
+            b'{' => match query[index..].find('}') {
+                Some(len) => {
+                    let substitution = &query[index + 1..index + len];
+                    if let Some((name, data_type)) = parse_substitution(substitution) {
+                        let value = params.get(name).ok_or_else(|| {
+                            ErrorCode::BadArguments(format!(
+                                "Substitution `{}` is not set, the URL parameter param_{} is required",
+                                name, name
+                            ))
+                        })?;
+                        bound.push_str(&query[position..index]);
+                        bound.push_str(&param_literal(name, data_type, value)?);
+                        position = index + len + 1;
+                    }
+                    index += len + 1;
+                }
+                None => index += 1,
+            },
+            _ => index += 1,
+        }
+    }
+    bound.push_str(&query[position..]);
+    Ok(bound)
+}
+
+/// Split the trailing `FORMAT <name>` clause from a query, which is the output format of a
+/// query other than `INSERT`. Some format names, e.g. `CSV` and `JSON`, are keywords.
+pub fn split_format_clause(query: &str) -> (&str, Option<&str>) {
+    let tokens = match tokenize_sql(query) {
+        Ok(tokens) => tokens,
+        Err(_) => return (query, None),
+    };
+    let tokens = tokens
+        .iter()
+        .filter(|token| !matches!(token.kind, TokenKind::EOI | TokenKind::SemiColon))
+        .collect::<Vec<_>>();
+
+    match tokens.as_slice() {
+        [first, .., format, name]
+            if first.kind != TokenKind::INSERT
+                && format.kind == TokenKind::FORMAT
+                && (name.kind == TokenKind::Ident || name.kind.is_keyword()) =>
+        {
+            (&query[..format.span.start], Some(name.text()))
+        }
+        _ => (query, None),
+    }
+}
+
+fn skip_quoted(bytes: &[u8], start: usize, quote: u8) -> usize {
+    let mut index = start + 1;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'\\' => index += 2,
+            b if b == quote => return index + 1,
+            _ => index += 1,
+        }
+    }
+    bytes.len()
+}
+
+fn skip_until(bytes: &[u8], start: usize, end: &str) -> usize {
+    let end = end.as_bytes();
+    match bytes[start..].windows(end.len()).position(|w| w == end) {
+        Some(position) => start + position + end.len(),
+        None => bytes.len(),
+    }
+}
+
+// `name:Type`, the name is an identifier.
+fn parse_substitution(substitution: &str) -> Option<(&str, &str)> {
+    let (name, data_type) = substitution.split_once(':')?;
+    let (name, data_type) = (name.trim(), data_type.trim());
+    let mut chars = name.chars();
+    let valid_name = matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    match valid_name && !data_type.is_empty() {
+        true => Some((name, data_type)),
+        false => None,
+    }
+}
+
+fn param_literal(name: &str, data_type: &str, value: &str) -> Result<String> {
+    let invalid = || {
+        ErrorCode::BadArguments(format!(
+            "Invalid value of parameter {} of type {}: {}",
+            name, data_type, value
+        ))
+    };
+
+    if let Some(inner) = data_type
+        .strip_prefix("Nullable(")
+        .and_then(|t| t.strip_suffix(')'))
+    {
+        return match value {
+            "\\N" => Ok("NULL".to_string()),
+            _ => param_literal(name, inner.trim(), value),
+        };
+    }
+
+    match data_type {
+        "Int8" | "Int16" | "Int32" | "Int64" | "UInt8" | "UInt16" | "UInt32" | "UInt64" => {
+            match value.trim().parse::<i128>() {
+                Ok(v) => Ok(number_literal(v.to_string())),
+                Err(_) => Err(invalid()),
+            }
+        }
+        "Float32" | "Float64" => match value.trim().parse::<f64>() {
+            Ok(v) if v.is_finite() => Ok(number_literal(value.trim().to_string())),
+            _ => Err(invalid()),
+        },
+        "Bool" | "Boolean" => match value.trim().to_lowercase().as_str() {
+            "true" | "1" => Ok("TRUE".to_string()),
+            "false" | "0" => Ok("FALSE".to_string()),
+            _ => Err(invalid()),
+        },
+        "String" => Ok(quote_string(&unescape(value))),
+        _ => Ok(format!(
+            "CAST({} AS {})",
+            quote_string(&unescape(value)),
+            data_type
+        )),
+    }
+}
+
+// The values of the parameters are escaped as in the `TabSeparated` format.
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => unescaped.push('\t'),
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some('0') => unescaped.push('\0'),
+            Some(c) => unescaped.push(c),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
@@ -0,0 +1,104 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_datablocks::DataBlock;
+use common_datavalues::remove_nullable;
+use common_datavalues::DataType;
+use common_datavalues::DataTypeImpl;
+use common_datavalues::TypeSerializer;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_io::prelude::FormatSettings;
+
+const FIELD_DELIMITER: u8 = b',';
+const ROW_DELIMITER: u8 = b'\n';
+const NULL: &[u8] = b"\\N";
+
+pub fn block_to_csv(block: &DataBlock, format: &FormatSettings) -> Result<Vec<u8>> {
+    let mut col_table = Vec::with_capacity(block.num_columns());
+    for (column, field) in block.columns().iter().zip(block.schema().fields()) {
+        let column = column.convert_full_column();
+        let serializer = field.data_type().create_serializer();
+        let values = serializer.serialize_column(&column, format).map_err(|e| {
+            ErrorCode::UnexpectedError(format!(
+                "fail to serialize filed {}, error = {}",
+                field.name(),
+                e
+            ))
+        })?;
+        col_table.push((column, quoted(field.data_type()), values));
+    }
+
+    let mut buf = vec![];
+    for row_index in 0..block.num_rows() {
+        for (col_index, (column, quoted, values)) in col_table.iter().enumerate() {
+            if col_index > 0 {
+                buf.push(FIELD_DELIMITER);
+            }
+            if column.null_at(row_index) {
+                buf.extend_from_slice(NULL);
+            } else if *quoted {
+                write_quoted(&mut buf, &values[row_index]);
+            } else {
+                buf.extend_from_slice(values[row_index].as_bytes());
+            }
+        }
+        buf.push(ROW_DELIMITER);
+    }
+    Ok(buf)
+}
+
+/// The header row of `CSVWithNames`.
+pub fn row_to_csv(values: &[String]) -> Vec<u8> {
+    let mut buf = vec![];
+    for (index, value) in values.iter().enumerate() {
+        if index > 0 {
+            buf.push(FIELD_DELIMITER);
+        }
+        write_quoted(&mut buf, value);
+    }
+    buf.push(ROW_DELIMITER);
+    buf
+}
+
+// Numbers and booleans are written as they are, the other values are quoted.
+fn quoted(data_type: &DataTypeImpl) -> bool {
+    !matches!(
+        remove_nullable(data_type),
+        DataTypeImpl::Null(_)
+            | DataTypeImpl::Boolean(_)
+            | DataTypeImpl::Int8(_)
+            | DataTypeImpl::Int16(_)
+            | DataTypeImpl::Int32(_)
+            | DataTypeImpl::Int64(_)
+            | DataTypeImpl::UInt8(_)
+            | DataTypeImpl::UInt16(_)
+            | DataTypeImpl::UInt32(_)
+            | DataTypeImpl::UInt64(_)
+            | DataTypeImpl::Float32(_)
+            | DataTypeImpl::Float64(_)
+            | DataTypeImpl::Interval(_)
+    )
+}
+
+fn write_quoted(buf: &mut Vec<u8>, value: &str) {
+    buf.push(b'"');
+    for byte in value.bytes() {
+        if byte == b'"' {
+            buf.push(b'"');
+        }
+        buf.push(byte);
+    }
+    buf.push(b'"');
+}
@@ -0,0 +1,53 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_datablocks::DataBlock;
+use common_exception::Result;
+use common_io::prelude::FormatSettings;
+use serde_json::Value as JsonValue;
+
+use crate::servers::http::v1::JsonBlock;
+
+/// Serialize each row of the block to a JSON text: an object keyed by the column names, or an
+/// array of the values if `compact`.
+pub fn block_to_json_rows(
+    block: &DataBlock,
+    format: &FormatSettings,
+    compact: bool,
+) -> Result<Vec<String>> {
+    let names = block
+        .schema()
+        .fields()
+        .iter()
+        .map(|field| JsonValue::String(field.name().clone()).to_string())
+        .collect::<Vec<_>>();
+
+    let json_block = JsonBlock::new(block, format)?;
+    let rows = json_block
+        .data()
+        .iter()
+        .map(|values| match compact {
+            true => JsonValue::Array(values.clone()).to_string(),
+            false => {
+                let entries = names
+                    .iter()
+                    .zip(values)
+                    .map(|(name, value)| format!("{}:{}", name, value))
+                    .collect::<Vec<_>>();
+                format!("{{{}}}", entries.join(","))
+            }
+        })
+        .collect();
+    Ok(rows)
+}
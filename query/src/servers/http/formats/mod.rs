@@ -12,8 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod csv_output;
+pub mod json_output;
+pub mod output_format;
+pub mod row_binary_output;
 pub mod tsv_output;
 
+pub use output_format::OutputFormat;
+pub use output_format::OutputFormatter;
+
 pub enum Format {
     NDJson,
+    Csv { skip_header: bool },
+    Tsv { skip_header: bool },
 }
@@ -0,0 +1,216 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use common_base::base::ProgressValues;
+use common_datablocks::DataBlock;
+use common_datavalues::DataSchema;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_io::prelude::FormatSettings;
+use serde_json::json;
+
+use crate::servers::http::formats::csv_output::block_to_csv;
+use crate::servers::http::formats::csv_output::row_to_csv;
+use crate::servers::http::formats::json_output::block_to_json_rows;
+use crate::servers::http::formats::row_binary_output::block_to_row_binary;
+use crate::servers::http::formats::row_binary_output::clickhouse_type_name;
+use crate::servers::http::formats::tsv_output::block_to_tsv;
+use crate::servers::http::formats::tsv_output::row_to_tsv;
+
+/// The output formats of the ClickHouse HTTP handler.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OutputFormat {
+    TabSeparated,
+    TabSeparatedWithNames,
+    TabSeparatedWithNamesAndTypes,
+    Csv,
+    CsvWithNames,
+    Json,
+    JsonCompact,
+    JsonEachRow,
+    RowBinary,
+}
+
+impl OutputFormat {
+    pub fn try_create(name: &str) -> Result<OutputFormat> {
+        match name {
+            "TabSeparated" | "TSV" => Ok(OutputFormat::TabSeparated),
+            "TabSeparatedWithNames" | "TSVWithNames" => Ok(OutputFormat::TabSeparatedWithNames),
+            "TabSeparatedWithNamesAndTypes" | "TSVWithNamesAndTypes" => {
+                Ok(OutputFormat::TabSeparatedWithNamesAndTypes)
+            }
+            "CSV" => Ok(OutputFormat::Csv),
+            "CSVWithNames" => Ok(OutputFormat::CsvWithNames),
+            "JSON" => Ok(OutputFormat::Json),
+            "JSONCompact" => Ok(OutputFormat::JsonCompact),
+            "JSONEachRow" => Ok(OutputFormat::JsonEachRow),
+            "RowBinary" => Ok(OutputFormat::RowBinary),
+            _ => Err(ErrorCode::UnknownFormat(format!(
+                "Unknown output format {}",
+                name
+            ))),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            OutputFormat::TabSeparated => "TabSeparated",
+            OutputFormat::TabSeparatedWithNames => "TabSeparatedWithNames",
+            OutputFormat::TabSeparatedWithNamesAndTypes => "TabSeparatedWithNamesAndTypes",
+            OutputFormat::Csv => "CSV",
+            OutputFormat::CsvWithNames => "CSVWithNames",
+            OutputFormat::Json => "JSON",
+            OutputFormat::JsonCompact => "JSONCompact",
+            OutputFormat::JsonEachRow => "JSONEachRow",
+            OutputFormat::RowBinary => "RowBinary",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            OutputFormat::TabSeparated
+            | OutputFormat::TabSeparatedWithNames
+            | OutputFormat::TabSeparatedWithNamesAndTypes => {
+                "text/tab-separated-values; charset=UTF-8"
+            }
+            OutputFormat::Csv | OutputFormat::CsvWithNames => "text/csv; charset=UTF-8",
+            OutputFormat::Json | OutputFormat::JsonCompact => "application/json; charset=UTF-8",
+            OutputFormat::JsonEachRow => "application/x-ndjson; charset=UTF-8",
+            OutputFormat::RowBinary => "application/octet-stream",
+        }
+    }
+}
+
+/// Serialize the result of a query in an output format, block by block.
+///
+/// The prefix of the format, e.g. the header rows, is written with the first block. Nothing
+/// is written for a result without columns, e.g. the result of `CREATE TABLE`.
+pub struct OutputFormatter {
+    format: OutputFormat,
+    settings: FormatSettings,
+    prefix_written: bool,
+    rows: usize,
+}
+
+impl OutputFormatter {
+    pub fn create(format: OutputFormat, settings: FormatSettings) -> OutputFormatter {
+        OutputFormatter {
+            format,
+            settings,
+            prefix_written: false,
+            rows: 0,
+        }
+    }
+
+    pub fn serialize_block(&mut self, block: &DataBlock) -> Result<Vec<u8>> {
+        if block.num_columns() == 0 {
+            return Ok(vec![]);
+        }
+
+        let mut buf = self.serialize_prefix(block.schema())?;
+        match self.format {
+            OutputFormat::TabSeparated
+            | OutputFormat::TabSeparatedWithNames
+            | OutputFormat::TabSeparatedWithNamesAndTypes => {
+                buf.extend(block_to_tsv(block, &self.settings)?)
+            }
+            OutputFormat::Csv | OutputFormat::CsvWithNames => {
+                buf.extend(block_to_csv(block, &self.settings)?)
+            }
+            OutputFormat::Json | OutputFormat::JsonCompact => {
+                let compact = self.format == OutputFormat::JsonCompact;
+                for row in block_to_json_rows(block, &self.settings, compact)? {
+                    if self.rows > 0 {
+                        buf.push(b',');
+                    }
+                    buf.extend(row.as_bytes());
+                    self.rows += 1;
+                }
+            }
+            OutputFormat::JsonEachRow => {
+                for row in block_to_json_rows(block, &self.settings, false)? {
+                    buf.extend(row.as_bytes());
+                    buf.push(b'\n');
+                }
+            }
+            OutputFormat::RowBinary => buf.extend(block_to_row_binary(block, &self.settings)?),
+        }
+        Ok(buf)
+    }
+
+    /// Serialize the end of the output, `schema` is the schema of the result which is used if
+    /// no block has been serialized. `JSON` and `JSONCompact` end with the statistics.
+    pub fn finish(
+        &mut self,
+        schema: &DataSchema,
+        progress: &ProgressValues,
+        elapsed: Duration,
+    ) -> Result<Vec<u8>> {
+        if !self.prefix_written && schema.fields().is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut buf = self.serialize_prefix(schema)?;
+        if matches!(self.format, OutputFormat::Json | OutputFormat::JsonCompact) {
+            let statistics = json!({
+                "elapsed": elapsed.as_secs_f64(),
+                "rows_read": progress.rows,
+                "bytes_read": progress.bytes,
+            });
+            buf.extend(
+                format!("],\"rows\":{},\"statistics\":{}}}\n", self.rows, statistics).as_bytes(),
+            );
+        }
+        Ok(buf)
+    }
+
+    fn serialize_prefix(&mut self, schema: &DataSchema) -> Result<Vec<u8>> {
+        if self.prefix_written {
+            return Ok(vec![]);
+        }
+        self.prefix_written = true;
+
+        let names = schema
+            .fields()
+            .iter()
+            .map(|field| field.name().clone())
+            .collect::<Vec<_>>();
+        let types = schema
+            .fields()
+            .iter()
+            .map(|field| clickhouse_type_name(field.data_type()))
+            .collect::<Vec<_>>();
+
+        match self.format {
+            OutputFormat::TabSeparatedWithNames => Ok(row_to_tsv(&names)),
+            OutputFormat::TabSeparatedWithNamesAndTypes => {
+                let mut buf = row_to_tsv(&names);
+                buf.extend(row_to_tsv(&types));
+                Ok(buf)
+            }
+            OutputFormat::CsvWithNames => Ok(row_to_csv(&names)),
+            OutputFormat::Json | OutputFormat::JsonCompact => {
+                let meta = names
+                    .iter()
+                    .zip(types.iter())
+                    .map(|(name, data_type)| json!({"name": name, "type": data_type}))
+                    .collect::<Vec<_>>();
+                Ok(format!("{{\"meta\":{},\"data\":[", serde_json::to_string(&meta)?).into_bytes())
+            }
+            _ => Ok(vec![]),
+        }
+    }
+}
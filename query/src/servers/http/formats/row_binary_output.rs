@@ -0,0 +1,141 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_datablocks::DataBlock;
+use common_datavalues::DataType;
+use common_datavalues::DataTypeImpl;
+use common_datavalues::DataValue;
+use common_datavalues::TypeSerializer;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_io::prelude::FormatSettings;
+
+/// Serialize the rows in the `RowBinary` format of ClickHouse, the values are written in the
+/// types named by `clickhouse_type_name`.
+pub fn block_to_row_binary(block: &DataBlock, format: &FormatSettings) -> Result<Vec<u8>> {
+    let columns = block
+        .columns()
+        .iter()
+        .map(|column| column.convert_full_column())
+        .collect::<Vec<_>>();
+
+    let mut buf = vec![];
+    for row_index in 0..block.num_rows() {
+        for (column, field) in columns.iter().zip(block.schema().fields()) {
+            write_value(&mut buf, field.data_type(), column.get(row_index), format)?;
+        }
+    }
+    Ok(buf)
+}
+
+/// The name of the ClickHouse type which a column is sent as.
+pub fn clickhouse_type_name(data_type: &DataTypeImpl) -> String {
+    match data_type {
+        DataTypeImpl::Null(_) => "Nullable(Nothing)".to_string(),
+        DataTypeImpl::Nullable(t) => format!("Nullable({})", clickhouse_type_name(t.inner_type())),
+        DataTypeImpl::Boolean(_) => "Bool".to_string(),
+        DataTypeImpl::Date(_) => "Date32".to_string(),
+        DataTypeImpl::Timestamp(t) => format!("DateTime64({})", t.precision()),
+        DataTypeImpl::Array(t) => format!("Array({})", clickhouse_type_name(t.inner_type())),
+        DataTypeImpl::Struct(t) => {
+            let types = t.types().iter().map(clickhouse_type_name);
+            format!("Tuple({})", types.collect::<Vec<_>>().join(", "))
+        }
+        DataTypeImpl::Variant(_)
+        | DataTypeImpl::VariantArray(_)
+        | DataTypeImpl::VariantObject(_) => "String".to_string(),
+        DataTypeImpl::Interval(_) => "Int64".to_string(),
+        _ => data_type.name(),
+    }
+}
+
+fn write_value(
+    buf: &mut Vec<u8>,
+    data_type: &DataTypeImpl,
+    value: DataValue,
+    format: &FormatSettings,
+) -> Result<()> {
+    match data_type {
+        DataTypeImpl::Null(_) => buf.push(1),
+        DataTypeImpl::Nullable(t) => match value {
+            DataValue::Null => buf.push(1),
+            value => {
+                buf.push(0);
+                write_value(buf, t.inner_type(), value, format)?;
+            }
+        },
+        DataTypeImpl::Boolean(_) => buf.push(value.as_bool()? as u8),
+        DataTypeImpl::Int8(_) => buf.extend((value.as_i64()? as i8).to_le_bytes()),
+        DataTypeImpl::Int16(_) => buf.extend((value.as_i64()? as i16).to_le_bytes()),
+        DataTypeImpl::Int32(_) | DataTypeImpl::Date(_) => {
+            buf.extend((value.as_i64()? as i32).to_le_bytes())
+        }
+        DataTypeImpl::Int64(_) | DataTypeImpl::Interval(_) => {
+            buf.extend(value.as_i64()?.to_le_bytes())
+        }
+        DataTypeImpl::UInt8(_) => buf.extend((value.as_u64()? as u8).to_le_bytes()),
+        DataTypeImpl::UInt16(_) => buf.extend((value.as_u64()? as u16).to_le_bytes()),
+        DataTypeImpl::UInt32(_) => buf.extend((value.as_u64()? as u32).to_le_bytes()),
+        DataTypeImpl::UInt64(_) => buf.extend(value.as_u64()?.to_le_bytes()),
+        DataTypeImpl::Float32(_) => buf.extend((value.as_f64()? as f32).to_le_bytes()),
+        DataTypeImpl::Float64(_) => buf.extend(value.as_f64()?.to_le_bytes()),
+        // Timestamps are stored in microseconds, ticks of `DateTime64` are in the precision.
+        DataTypeImpl::Timestamp(t) => {
+            let micros = value.as_i64()?;
+            let ticks = match t.precision() {
+                p if p <= 6 => micros / 10_i64.pow(6 - p as u32),
+                p => micros * 10_i64.pow(p as u32 - 6),
+            };
+            buf.extend(ticks.to_le_bytes())
+        }
+        DataTypeImpl::String(_) => write_string(buf, &value.as_string()?),
+        DataTypeImpl::Array(t) => match value {
+            DataValue::Array(values) => {
+                write_varint(buf, values.len() as u64);
+                for value in values {
+                    write_value(buf, t.inner_type(), value, format)?;
+                }
+            }
+            _ => return Err(ErrorCode::BadBytes("Incorrect Array value")),
+        },
+        DataTypeImpl::Struct(t) => match value {
+            DataValue::Struct(values) if values.len() == t.types().len() => {
+                for (value, data_type) in values.into_iter().zip(t.types()) {
+                    write_value(buf, data_type, value, format)?;
+                }
+            }
+            _ => return Err(ErrorCode::BadBytes("Incorrect Struct value")),
+        },
+        DataTypeImpl::Variant(_)
+        | DataTypeImpl::VariantArray(_)
+        | DataTypeImpl::VariantObject(_) => {
+            let serializer = data_type.create_serializer();
+            write_string(buf, serializer.serialize_value(&value, format)?.as_bytes())
+        }
+    }
+    Ok(())
+}
+
+fn write_string(buf: &mut Vec<u8>, value: &[u8]) {
+    write_varint(buf, value.len() as u64);
+    buf.extend_from_slice(value);
+}
+
+fn write_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
@@ -21,35 +21,62 @@ use common_io::prelude::FormatSettings;
 
 const FIELD_DELIMITER: u8 = b'\t';
 const ROW_DELIMITER: u8 = b'\n';
+const NULL: &[u8] = b"\\N";
 
 pub fn block_to_tsv(block: &DataBlock, format: &FormatSettings) -> Result<Vec<u8>> {
-    let rows_size = block.column(0).len();
-    let columns_size = block.num_columns();
-
-    let mut col_table = Vec::new();
-    for col_index in 0..columns_size {
-        let column = block.column(col_index);
+    let mut col_table = Vec::with_capacity(block.num_columns());
+    for (column, field) in block.columns().iter().zip(block.schema().fields()) {
         let column = column.convert_full_column();
-        let field = block.schema().field(col_index);
-        let data_type = field.data_type();
-        let serializer = data_type.create_serializer();
-        // todo(youngsofun): escape
-        col_table.push(serializer.serialize_column(&column, format).map_err(|e| {
+        let serializer = field.data_type().create_serializer();
+        let values = serializer.serialize_column(&column, format).map_err(|e| {
             ErrorCode::UnexpectedError(format!(
                 "fail to serialize filed {}, error = {}",
                 field.name(),
                 e
             ))
-        })?);
+        })?;
+        col_table.push((column, values));
     }
+
     let mut buf = vec![];
-    for row_index in 0..rows_size {
-        for col in col_table.iter().take(columns_size - 1) {
-            buf.extend_from_slice(col[row_index].as_bytes());
-            buf.push(FIELD_DELIMITER);
+    for row_index in 0..block.num_rows() {
+        for (col_index, (column, values)) in col_table.iter().enumerate() {
+            if col_index > 0 {
+                buf.push(FIELD_DELIMITER);
+            }
+            if column.null_at(row_index) {
+                buf.extend_from_slice(NULL);
+            } else {
+                write_escaped(&mut buf, &values[row_index]);
+            }
         }
-        buf.extend_from_slice(col_table[columns_size - 1][row_index].as_bytes());
         buf.push(ROW_DELIMITER);
     }
     Ok(buf)
 }
+
+/// A header row of `TabSeparatedWithNames` and `TabSeparatedWithNamesAndTypes`.
+pub fn row_to_tsv(values: &[String]) -> Vec<u8> {
+    let mut buf = vec![];
+    for (index, value) in values.iter().enumerate() {
+        if index > 0 {
+            buf.push(FIELD_DELIMITER);
+        }
+        write_escaped(&mut buf, value);
+    }
+    buf.push(ROW_DELIMITER);
+    buf
+}
+
+// The delimiters and backslashes in the values are escaped, so each row stays on one line.
+fn write_escaped(buf: &mut Vec<u8>, value: &str) {
+    for byte in value.bytes() {
+        match byte {
+            b'\t' => buf.extend_from_slice(b"\\t"),
+            b'\n' => buf.extend_from_slice(b"\\n"),
+            b'\r' => buf.extend_from_slice(b"\\r"),
+            b'\\' => buf.extend_from_slice(b"\\\\"),
+            _ => buf.push(byte),
+        }
+    }
+}
@@ -28,6 +28,7 @@ use poem::Route;
 use super::v1::upload_to_stage;
 use crate::common::service::HttpShutdownHandler;
 use crate::servers::http::middleware::HTTPSessionMiddleware;
+use crate::servers::http::v1::async_query_route;
 use crate::servers::http::v1::clickhouse_router;
 use crate::servers::http::v1::query_route;
 use crate::servers::http::v1::streaming_load;
@@ -66,6 +67,7 @@ echo '{}' | curl '{:?}/clickhouse/?query=INSERT%20INTO%20test%20FORMAT%20JSONEac
             )
             .nest("/clickhouse", clickhouse_router())
             .nest("/v1/query", query_route())
+            .nest("/v1/async_query", async_query_route())
             .at("/v1/streaming_load", put(streaming_load))
             .at("/v1/upload_to_stage", put(upload_to_stage))
             .with(HTTPSessionMiddleware {
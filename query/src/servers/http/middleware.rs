@@ -29,11 +29,15 @@ use poem::Addr;
 use poem::Endpoint;
 use poem::Middleware;
 use poem::Request;
+use url::form_urlencoded;
 
 use super::v1::HttpQueryContext;
 use crate::sessions::SessionManager;
 use crate::users::auth::auth_mgr::Credential;
 
+const CLICKHOUSE_USER: &str = "X-ClickHouse-User";
+const CLICKHOUSE_KEY: &str = "X-ClickHouse-Key";
+
 pub struct HTTPSessionMiddleware {
     pub session_manager: Arc<SessionManager>,
 }
@@ -44,16 +48,19 @@ fn get_credential(req: &Request) -> Result<Credential> {
         let msg = &format!("Multiple {} headers detected", AUTHORIZATION);
         return Err(ErrorCode::AuthenticateFailure(msg));
     }
-    if auth_headers.is_empty() {
-        return Err(ErrorCode::AuthenticateFailure(
-            "No authorization header detected",
-        ));
-    }
     let client_ip = match req.remote_addr().0 {
         Addr::SocketAddr(addr) => Some(addr.ip().to_string()),
         Addr::Custom(..) => Some("127.0.0.1".to_string()),
         _ => None,
     };
+    if auth_headers.is_empty() {
+        return match get_clickhouse_credential(req, client_ip) {
+            Some(c) => Ok(c),
+            None => Err(ErrorCode::AuthenticateFailure(
+                "No authorization header detected",
+            )),
+        };
+    }
     let value = auth_headers[0];
     if value.as_bytes().starts_with(b"Basic ") {
         match Basic::decode(value) {
@@ -82,6 +89,40 @@ fn get_credential(req: &Request) -> Result<Credential> {
     }
 }
 
+// The ClickHouse clients send the credential by the `X-ClickHouse-User` and `X-ClickHouse-Key`
+// headers, or by the `user` and `password` URL parameters.
+fn get_clickhouse_credential(req: &Request, client_ip: Option<String>) -> Option<Credential> {
+    let header = |name: &str| {
+        req.headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string())
+    };
+    let (name, password) = match header(CLICKHOUSE_USER) {
+        Some(name) => (name, header(CLICKHOUSE_KEY)),
+        None => {
+            let params = form_urlencoded::parse(req.uri().query().unwrap_or_default().as_bytes());
+            let (mut name, mut password) = (None, None);
+            for (key, value) in params {
+                match key.as_ref() {
+                    "user" => name = Some(value.to_string()),
+                    "password" => password = Some(value.to_string()),
+                    _ => {}
+                }
+            }
+            (name?, password)
+        }
+    };
+    let password = password
+        .filter(|p| !p.is_empty())
+        .map(|p| p.as_bytes().to_vec());
+    Some(Credential::Password {
+        name,
+        password,
+        hostname: client_ip,
+    })
+}
+
 impl<E: Endpoint> Middleware<E> for HTTPSessionMiddleware {
     type Output = HTTPSessionEndpoint<E>;
     fn transform(&self, ep: E) -> Self::Output {
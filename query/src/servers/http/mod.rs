@@ -13,6 +13,7 @@
 // limitations under the License.
 
 mod clickhouse_handler;
+mod clickhouse_query_params;
 pub mod formats;
 mod http_services;
 pub mod middleware;
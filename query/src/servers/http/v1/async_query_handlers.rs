@@ -0,0 +1,285 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use async_stream::stream;
+use common_exception::ErrorCode;
+use common_tracing::tracing;
+use poem::error::Error as PoemError;
+use poem::error::InternalServerError;
+use poem::error::Result as PoemResult;
+use poem::get;
+use poem::http::StatusCode;
+use poem::post;
+use poem::web::Json;
+use poem::web::Path;
+use poem::web::Query;
+use poem::Body;
+use poem::IntoResponse;
+use poem::Response;
+use poem::Route;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::http_query_stream::StreamEncoder;
+use super::query::AsyncQuery;
+use super::query::AsyncQueryRequest;
+use super::query::ExecuteStateKind;
+use super::query::StreamFormat;
+use crate::servers::http::v1::HttpQueryContext;
+use crate::servers::http::v1::QueryError;
+use crate::servers::http::v1::QueryProgress;
+use crate::servers::http::v1::QueryStats;
+use crate::servers::http::v1::HEADER_QUERY_ID;
+
+pub fn make_async_state_uri(query_id: &str) -> String {
+    format!("/v1/async_query/{}", query_id)
+}
+
+pub fn make_async_result_uri(query_id: &str) -> String {
+    format!("/v1/async_query/{}/result", query_id)
+}
+
+pub fn make_async_final_uri(query_id: &str) -> String {
+    format!("/v1/async_query/{}/kill?delete=true", query_id)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AsyncQueryResponse {
+    pub id: String,
+    pub state: ExecuteStateKind,
+    pub error: Option<QueryError>,
+    pub stats: QueryStats,
+    pub result_rows: usize,
+    pub result_bytes: usize,
+    pub stats_uri: Option<String>,
+    // only after the query succeeded
+    pub result_uri: Option<String>,
+    // remove the query and its result before the retention ends
+    pub final_uri: Option<String>,
+}
+
+impl AsyncQueryResponse {
+    fn from_query(query: &AsyncQuery) -> AsyncQueryResponse {
+        let state = query.get_state();
+        let result_uri = match state.state {
+            ExecuteStateKind::Succeeded => Some(make_async_result_uri(&query.id)),
+            _ => None,
+        };
+        AsyncQueryResponse {
+            id: query.id.clone(),
+            state: state.state,
+            error: state.error.as_ref().map(QueryError::from_error_code),
+            stats: QueryStats {
+                scan_progress: state.scan_progress,
+                running_time_ms: state.running_time_ms,
+            },
+            result_rows: state.result_rows,
+            result_bytes: state.result_bytes,
+            stats_uri: Some(make_async_state_uri(&query.id)),
+            result_uri,
+            final_uri: Some(make_async_final_uri(&query.id)),
+        }
+    }
+
+    fn fail_to_start_sql(err: &ErrorCode) -> AsyncQueryResponse {
+        AsyncQueryResponse {
+            id: "".to_string(),
+            state: ExecuteStateKind::Failed,
+            error: Some(QueryError::from_error_code(err)),
+            stats: QueryStats::default(),
+            result_rows: 0,
+            result_bytes: 0,
+            stats_uri: None,
+            result_uri: None,
+            final_uri: None,
+        }
+    }
+}
+
+/// Submit a query to run without the client, the response is returned once the query is
+/// started, and the state and the result are fetched by the id of the query.
+#[poem::handler]
+async fn async_query_handler(
+    ctx: &HttpQueryContext,
+    Json(req): Json<AsyncQueryRequest>,
+) -> Json<AsyncQueryResponse> {
+    tracing::info!("receive async query: {:?}", req);
+    let async_query_manager = ctx.session_mgr.get_async_query_manager();
+    match async_query_manager.submit(ctx, req).await {
+        Ok(query) => Json(AsyncQueryResponse::from_query(&query)),
+        Err(e) => {
+            tracing::error!("Fail to start async sql, Error: {:?}", e);
+            Json(AsyncQueryResponse::fail_to_start_sql(&e))
+        }
+    }
+}
+
+#[poem::handler]
+async fn async_query_state_handler(
+    ctx: &HttpQueryContext,
+    Path(query_id): Path<String>,
+) -> PoemResult<Json<AsyncQueryResponse>> {
+    let query = get_query(ctx, &query_id).await?;
+    Ok(Json(AsyncQueryResponse::from_query(&query)))
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct ResultParams {
+    format: Option<StreamFormat>,
+    page_token: Option<String>,
+}
+
+/// Stream the result of a succeeded query from the storage, in the formats and pages of
+/// `/v1/query/:id/stream`, each page is a part of the stored result.
+#[poem::handler]
+async fn async_query_result_handler(
+    ctx: &HttpQueryContext,
+    Query(params): Query<ResultParams>,
+    Path(query_id): Path<String>,
+) -> PoemResult<Response> {
+    let query = get_query(ctx, &query_id).await?;
+    let state = query.get_state();
+    match state.state {
+        ExecuteStateKind::Succeeded => {}
+        ExecuteStateKind::Running => {
+            return Err(PoemError::from_string(
+                format!("query {} is still running", query_id),
+                StatusCode::CONFLICT,
+            ));
+        }
+        ExecuteStateKind::Failed => {
+            let message = state.error.map(|e| e.message()).unwrap_or_default();
+            return Err(PoemError::from_string(
+                format!("query {} failed: {}", query_id, message),
+                StatusCode::CONFLICT,
+            ));
+        }
+    }
+
+    let page_no = match &params.page_token {
+        None => 0,
+        Some(token) => match token.parse::<usize>() {
+            Ok(page_no) if page_no == 0 || page_no < state.result_parts => page_no,
+            _ => {
+                return Err(PoemError::from_string(
+                    format!("invalid page token {}", token),
+                    StatusCode::BAD_REQUEST,
+                ))
+            }
+        },
+    };
+
+    let format = params.format.unwrap_or(StreamFormat::NDJson);
+    let mut encoder = StreamEncoder::create(
+        format,
+        query.get_schema(),
+        query.get_format_settings().clone(),
+    );
+    let progress = QueryProgress {
+        state: state.state,
+        error: None,
+        stats: QueryStats {
+            scan_progress: state.scan_progress,
+            running_time_ms: state.running_time_ms,
+        },
+    };
+    let result_parts = state.result_parts;
+    let stream = stream! {
+        yield encoder.start();
+
+        let mut failed = false;
+        for part in page_no..result_parts {
+            let next_page_no = Some(part + 1).filter(|n| *n < result_parts);
+            match query.read_part(part).await {
+                Ok(blocks) => yield encoder.encode_page(&blocks, next_page_no),
+                Err(err) => {
+                    failed = true;
+                    yield Err(err);
+                    break;
+                }
+            }
+        }
+        if !failed {
+            yield encoder.finish(&progress);
+        }
+    };
+
+    Ok(Response::builder()
+        .header(HEADER_QUERY_ID, query_id)
+        .content_type(format.content_type())
+        .body(Body::from_bytes_stream(stream)))
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct CancelParams {
+    delete: Option<bool>,
+}
+
+#[poem::handler]
+async fn async_query_cancel_handler(
+    ctx: &HttpQueryContext,
+    Query(params): Query<CancelParams>,
+    Path(query_id): Path<String>,
+) -> PoemResult<impl IntoResponse> {
+    let query = get_query(ctx, &query_id).await?;
+    if params.delete.unwrap_or(false) {
+        ctx.session_mgr
+            .get_async_query_manager()
+            .remove_query(&query)
+            .await;
+    } else {
+        query.kill();
+    }
+    Ok(StatusCode::OK)
+}
+
+pub fn async_query_route() -> Route {
+    Route::new()
+        .at("/", post(async_query_handler))
+        .at("/:id", get(async_query_state_handler))
+        .at("/:id/result", get(async_query_result_handler))
+        .at(
+            "/:id/kill",
+            get(async_query_cancel_handler).post(async_query_cancel_handler),
+        )
+}
+
+// The queries are only visible to the users who submitted them.
+async fn get_query(ctx: &HttpQueryContext, query_id: &str) -> PoemResult<Arc<AsyncQuery>> {
+    // The tenant of the session, see `SessionContext::get_current_tenant`.
+    let conf = ctx.session_mgr.get_conf();
+    let tenant = match &ctx.tenant_id {
+        Some(tenant) if conf.query.management_mode && !tenant.is_empty() => tenant.clone(),
+        _ => conf.query.tenant_id,
+    };
+    let user = ctx.user_info.identity().to_string();
+    ctx.session_mgr
+        .get_async_query_manager()
+        .get_query(
+            ctx.session_mgr.get_storage_operator(),
+            &tenant,
+            &user,
+            query_id,
+        )
+        .await
+        .map_err(InternalServerError)?
+        .ok_or_else(|| {
+            PoemError::from_string(
+                format!("query id not found {}", query_id),
+                StatusCode::NOT_FOUND,
+            )
+        })
+}
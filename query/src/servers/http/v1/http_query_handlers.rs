@@ -15,7 +15,6 @@
 use common_base::base::ProgressValues;
 use common_datavalues::DataSchemaRef;
 use common_exception::ErrorCode;
-use common_io::prelude::FormatSettings;
 use common_tracing::tracing;
 use poem::error::Error as PoemError;
 use poem::error::Result as PoemResult;
@@ -26,6 +25,7 @@ use poem::web::Json;
 use poem::web::Path;
 use poem::web::Query;
 use poem::IntoResponse;
+use poem::Response;
 use poem::Route;
 use serde::Deserialize;
 use serde::Serialize;
@@ -34,6 +34,9 @@ use serde_json::Value as JsonValue;
 use super::query::ExecuteStateKind;
 use super::query::HttpQueryRequest;
 use super::query::HttpQueryResponseInternal;
+use crate::servers::http::v1::http_query_stream::query_progress_handler;
+use crate::servers::http::v1::http_query_stream::query_stream_handler;
+use crate::servers::http::v1::http_query_stream::stream_response;
 use crate::servers::http::v1::HttpQueryContext;
 use crate::servers::http::v1::JsonBlock;
 
@@ -56,7 +59,7 @@ pub struct QueryError {
 }
 
 impl QueryError {
-    fn from_error_code(e: &ErrorCode) -> Self {
+    pub(crate) fn from_error_code(e: &ErrorCode) -> Self {
         QueryError {
             code: e.code(),
             message: e.message(),
@@ -176,11 +179,9 @@ async fn query_page_handler(
     let http_query_manager = ctx.session_mgr.get_http_query_manager();
     match http_query_manager.get_query(&query_id).await {
         Some(query) => {
-            // TODO(veeupup): get query_ctx here to get format_settings
-            let format = FormatSettings::default();
             query.clear_expire_time().await;
             let resp = query
-                .get_response_page(page_no, &format)
+                .get_response_page(page_no)
                 .await
                 .map_err(|err| poem::Error::from_string(err.message(), StatusCode::NOT_FOUND))?;
             query.update_expire_time().await;
@@ -194,28 +195,27 @@ async fn query_page_handler(
 pub(crate) async fn query_handler(
     ctx: &HttpQueryContext,
     Json(req): Json<HttpQueryRequest>,
-) -> PoemResult<Json<QueryResponse>> {
+) -> PoemResult<Response> {
     tracing::info!("receive http query: {:?}", req);
+    let stream_format = req.stream_format;
     let http_query_manager = ctx.session_mgr.get_http_query_manager();
     let query = http_query_manager.try_create_query(ctx, req).await;
 
-    // TODO(veeupup): get global query_ctx's format_settings, because we cann't set session settings now
-    let format = FormatSettings::default();
     match query {
         Ok(query) => {
+            if let Some(format) = stream_format {
+                return stream_response(query, format, 0).await;
+            }
             let resp = query
-                .get_response_page(0, &format)
+                .get_response_page(0)
                 .await
                 .map_err(|err| poem::Error::from_string(err.message(), StatusCode::NOT_FOUND))?;
             query.update_expire_time().await;
-            Ok(Json(QueryResponse::from_internal(
-                query.id.to_string(),
-                resp,
-            )))
+            Ok(Json(QueryResponse::from_internal(query.id.to_string(), resp)).into_response())
         }
         Err(e) => {
             tracing::error!("Fail to start sql, Error: {:?}", e);
-            Ok(Json(QueryResponse::fail_to_start_sql(&e)))
+            Ok(Json(QueryResponse::fail_to_start_sql(&e)).into_response())
         }
     }
 }
@@ -226,6 +226,8 @@ pub fn query_route() -> Route {
         .at("/", post(query_handler))
         .at("/:id", get(query_state_handler))
         .at("/:id/page/:page_no", get(query_page_handler))
+        .at("/:id/stream", get(query_stream_handler))
+        .at("/:id/progress", get(query_progress_handler))
         .at(
             "/:id/kill",
             get(query_cancel_handler).post(query_cancel_handler),
@@ -0,0 +1,374 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_stream::stream;
+use common_arrow::arrow::chunk::Chunk;
+use common_arrow::arrow::io::ipc::write::StreamWriter;
+use common_arrow::arrow::io::ipc::write::WriteOptions;
+use common_base::base::tokio;
+use common_base::infallible::Mutex;
+use common_datablocks::DataBlock;
+use common_datavalues::DataSchemaRef;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_io::prelude::FormatSettings;
+use poem::error::Error as PoemError;
+use poem::error::Result as PoemResult;
+use poem::http::StatusCode;
+use poem::web::Path;
+use poem::web::Query;
+use poem::Body;
+use poem::Response;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::json;
+
+use super::query::ExecuteStateKind;
+use super::query::HttpQuery;
+use super::query::StreamFormat;
+use crate::servers::http::v1::HttpQueryContext;
+use crate::servers::http::v1::JsonBlock;
+use crate::servers::http::v1::QueryError;
+use crate::servers::http::v1::QueryStats;
+
+pub const HEADER_QUERY_ID: &str = "X-Databend-Query-Id";
+
+const DEFAULT_PROGRESS_INTERVAL_MILLIS: u64 = 1000;
+
+// The pages kept while streaming, the older ones are dropped as the stream goes, so a stream
+// holds a bounded part of the result.
+const STREAM_KEPT_PAGES: usize = 2;
+
+pub fn make_stream_uri(query_id: &str) -> String {
+    format!("/v1/query/{}/stream", query_id)
+}
+
+pub fn make_progress_uri(query_id: &str) -> String {
+    format!("/v1/query/{}/progress", query_id)
+}
+
+/// The state of a query, which ends a streamed result in NDJSON and is sent as the server-sent
+/// events of the progress.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct QueryProgress {
+    pub state: ExecuteStateKind,
+    pub error: Option<QueryError>,
+    pub stats: QueryStats,
+}
+
+impl QueryProgress {
+    async fn of_query(query: &HttpQuery) -> QueryProgress {
+        let state = query.get_response_state_only().await.state;
+        QueryProgress {
+            state: state.state,
+            error: state.error.as_ref().map(QueryError::from_error_code),
+            stats: QueryStats {
+                scan_progress: state.scan_progress.clone(),
+                running_time_ms: state.running_time_ms,
+            },
+        }
+    }
+}
+
+/// Stream the result of the query from the page `page_no` in a single chunked response.
+///
+/// The result is sent page by page, the number of the page after the last received one is
+/// the token to resume the stream after a disconnection. Only the last `STREAM_KEPT_PAGES` pages
+/// sent are kept, so the stream can be resumed from the last tokens received. In NDJSON each
+/// row is an array of the values, and the lines of objects are:
+/// `{"schema": ...}` first, `{"next_page_token": "<n>"}` after each page, and the state of
+/// the query at the end. In Arrow IPC each page is a record batch.
+pub(crate) async fn stream_response(
+    query: Arc<HttpQuery>,
+    format: StreamFormat,
+    page_no: usize,
+) -> PoemResult<Response> {
+    query.clear_expire_time().await;
+    let expire_guard = ExpireGuard(query.clone());
+
+    // The first page is fetched before responding, so a wrong page token is answered with 404.
+    query.ack_pages(page_no).await;
+    let first_page = query
+        .get_block_page(page_no)
+        .await
+        .map_err(|err| PoemError::from_string(err.message(), StatusCode::NOT_FOUND))?;
+
+    let schema = query.get_schema().await;
+    let mut encoder = StreamEncoder::create(format, schema, query.get_format_settings().clone());
+    let query_id = query.id.clone();
+    let stream = stream! {
+        let _expire_guard = expire_guard;
+        yield encoder.start();
+
+        let (mut page, mut next_page_no) = first_page;
+        loop {
+            yield encoder.encode_page(&page.blocks, next_page_no);
+            let page_no = match next_page_no {
+                Some(page_no) => page_no,
+                None => break,
+            };
+            query.ack_pages((page_no + 1).saturating_sub(STREAM_KEPT_PAGES)).await;
+            match query.get_block_page(page_no).await {
+                Ok((next_page, no)) => {
+                    page = next_page;
+                    next_page_no = no;
+                }
+                Err(err) => {
+                    yield Err(err);
+                    break;
+                }
+            }
+        }
+
+        let progress = QueryProgress::of_query(&query).await;
+        yield encoder.finish(&progress);
+    };
+
+    Ok(Response::builder()
+        .header(HEADER_QUERY_ID, query_id)
+        .content_type(format.content_type())
+        .body(Body::from_bytes_stream(stream)))
+}
+
+// The query does not expire while its result is streamed, the expiry is restored when the
+// stream is dropped, whether it is finished or the client is disconnected.
+struct ExpireGuard(Arc<HttpQuery>);
+
+impl Drop for ExpireGuard {
+    fn drop(&mut self) {
+        let query = self.0.clone();
+        tokio::spawn(async move { query.update_expire_time().await });
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct StreamParams {
+    format: Option<StreamFormat>,
+    page_token: Option<String>,
+}
+
+#[poem::handler]
+pub(crate) async fn query_stream_handler(
+    ctx: &HttpQueryContext,
+    Query(params): Query<StreamParams>,
+    Path(query_id): Path<String>,
+) -> PoemResult<Response> {
+    let page_no = match &params.page_token {
+        None => 0,
+        Some(token) => token.parse::<usize>().map_err(|_| {
+            PoemError::from_string(
+                format!("invalid page token {}", token),
+                StatusCode::BAD_REQUEST,
+            )
+        })?,
+    };
+
+    let http_query_manager = ctx.session_mgr.get_http_query_manager();
+    match http_query_manager.get_query(&query_id).await {
+        Some(query) => {
+            let format = params.format.unwrap_or(StreamFormat::NDJson);
+            stream_response(query, format, page_no).await
+        }
+        None => Err(PoemError::from_string(
+            format!("query id not found {}", query_id),
+            StatusCode::NOT_FOUND,
+        )),
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct ProgressParams {
+    interval_ms: Option<u64>,
+}
+
+/// The progress of the query as server-sent events, an event `progress` is sent every
+/// `interval_ms` until the query is stopped, and the last event is the final state.
+#[poem::handler]
+pub(crate) async fn query_progress_handler(
+    ctx: &HttpQueryContext,
+    Query(params): Query<ProgressParams>,
+    Path(query_id): Path<String>,
+) -> PoemResult<Response> {
+    let http_query_manager = ctx.session_mgr.get_http_query_manager();
+    let query = http_query_manager
+        .get_query(&query_id)
+        .await
+        .ok_or_else(|| {
+            PoemError::from_string(
+                format!("query id not found {}", query_id),
+                StatusCode::NOT_FOUND,
+            )
+        })?;
+
+    let interval = params
+        .interval_ms
+        .unwrap_or(DEFAULT_PROGRESS_INTERVAL_MILLIS)
+        .max(1);
+    let stream = stream! {
+        loop {
+            let progress = QueryProgress::of_query(&query).await;
+            let running = progress.state == ExecuteStateKind::Running;
+            yield serde_json::to_string(&progress)
+                .map(|data| format!("event: progress\ndata: {}\n\n", data))
+                .map_err(ErrorCode::from);
+            if !running {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(interval)).await;
+        }
+    };
+
+    Ok(Response::builder()
+        .content_type("text/event-stream")
+        .header("Cache-Control", "no-cache")
+        .body(Body::from_bytes_stream(stream)))
+}
+
+pub(crate) enum StreamEncoder {
+    NDJson {
+        schema: DataSchemaRef,
+        format: FormatSettings,
+    },
+    Arrow {
+        schema: DataSchemaRef,
+        buffer: SharedBuffer,
+        writer: StreamWriter<SharedBuffer>,
+    },
+}
+
+impl StreamEncoder {
+    pub(crate) fn create(
+        format: StreamFormat,
+        schema: DataSchemaRef,
+        settings: FormatSettings,
+    ) -> Self {
+        match format {
+            StreamFormat::NDJson => StreamEncoder::NDJson {
+                schema,
+                format: settings,
+            },
+            StreamFormat::Arrow => {
+                let buffer = SharedBuffer::default();
+                let options = WriteOptions { compression: None };
+                let writer = StreamWriter::new(buffer.clone(), options);
+                StreamEncoder::Arrow {
+                    schema,
+                    buffer,
+                    writer,
+                }
+            }
+        }
+    }
+
+    pub(crate) fn start(&mut self) -> Result<Vec<u8>> {
+        match self {
+            StreamEncoder::NDJson { schema, .. } => {
+                Ok(format!("{}\n", json!({ "schema": schema })).into_bytes())
+            }
+            StreamEncoder::Arrow {
+                schema,
+                buffer,
+                writer,
+            } => {
+                writer.start(&schema.to_arrow(), None)?;
+                Ok(buffer.take())
+            }
+        }
+    }
+
+    pub(crate) fn encode_page(
+        &mut self,
+        blocks: &[DataBlock],
+        next_page_no: Option<usize>,
+    ) -> Result<Vec<u8>> {
+        match self {
+            StreamEncoder::NDJson { format, .. } => {
+                let mut buf = vec![];
+                for block in blocks {
+                    for row in JsonBlock::new(block, format)?.data() {
+                        serde_json::to_writer(&mut buf, row)?;
+                        buf.push(b'\n');
+                    }
+                }
+                if let Some(page_no) = next_page_no {
+                    let token = json!({ "next_page_token": page_no.to_string() });
+                    buf.extend(format!("{}\n", token).as_bytes());
+                }
+                Ok(buf)
+            }
+            StreamEncoder::Arrow { buffer, writer, .. } => {
+                let blocks = blocks
+                    .iter()
+                    .filter(|block| !block.is_empty())
+                    .cloned()
+                    .collect::<Vec<_>>();
+                if !blocks.is_empty() {
+                    let chunk = Chunk::try_from(DataBlock::concat_blocks(&blocks)?)?;
+                    writer.write(&chunk, None)?;
+                }
+                Ok(buffer.take())
+            }
+        }
+    }
+
+    // The state of the query ends the NDJSON stream, an Arrow IPC stream is aborted if the
+    // query failed.
+    pub(crate) fn finish(&mut self, progress: &QueryProgress) -> Result<Vec<u8>> {
+        match self {
+            StreamEncoder::NDJson { .. } => {
+                Ok(format!("{}\n", serde_json::to_string(progress)?).into_bytes())
+            }
+            StreamEncoder::Arrow { buffer, writer, .. } => match &progress.error {
+                Some(error) => Err(ErrorCode::create(
+                    error.code,
+                    error.message.clone(),
+                    None,
+                    None,
+                )),
+                None => {
+                    writer.finish()?;
+                    Ok(buffer.take())
+                }
+            },
+        }
+    }
+}
+
+// The Arrow IPC stream is written to a buffer shared with the encoder, which takes the bytes
+// of each page out.
+#[derive(Clone, Default)]
+struct SharedBuffer {
+    buf: Arc<Mutex<Vec<u8>>>,
+}
+
+impl SharedBuffer {
+    fn take(&self) -> Vec<u8> {
+        std::mem::take(&mut *self.buf.lock())
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buf.lock().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
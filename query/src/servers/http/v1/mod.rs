@@ -12,28 +12,45 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod async_query_handlers;
 mod http_query_handlers;
+mod http_query_stream;
 pub mod json_block;
 mod load;
 mod multipart_format;
 mod query;
 mod stage;
 
+pub use async_query_handlers::async_query_route;
+pub use async_query_handlers::make_async_final_uri;
+pub use async_query_handlers::make_async_result_uri;
+pub use async_query_handlers::make_async_state_uri;
+pub use async_query_handlers::AsyncQueryResponse;
 pub use http_query_handlers::make_final_uri;
 pub use http_query_handlers::make_page_uri;
 pub use http_query_handlers::make_state_uri;
 pub use http_query_handlers::query_route;
+pub use http_query_handlers::QueryError;
 pub use http_query_handlers::QueryResponse;
 pub use http_query_handlers::QueryStats;
+pub use http_query_stream::make_progress_uri;
+pub use http_query_stream::make_stream_uri;
+pub use http_query_stream::QueryProgress;
+pub use http_query_stream::HEADER_QUERY_ID;
 pub(crate) use json_block::JsonBlock;
 pub use load::streaming_load;
 pub use load::LoadResponse;
+pub use query::AsyncQuery;
+pub use query::AsyncQueryManager;
+pub use query::AsyncQueryManifest;
+pub use query::AsyncQueryState;
 pub use query::ExecuteStateKind;
 pub use query::HttpQueryContext;
 pub use query::HttpQueryHandle;
 pub use query::HttpQueryManager;
 pub use query::HttpSession;
 pub use query::HttpSessionConf;
+pub use query::StreamFormat;
 pub use stage::upload_to_stage;
 pub use stage::UploadToStageResponse;
 
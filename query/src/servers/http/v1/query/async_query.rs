@@ -0,0 +1,642 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::io::ErrorKind;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
+
+use common_arrow::arrow::chunk::Chunk;
+use common_arrow::arrow::io::ipc::read::read_stream_metadata;
+use common_arrow::arrow::io::ipc::read::StreamReader;
+use common_arrow::arrow::io::ipc::read::StreamState;
+use common_arrow::arrow::io::ipc::write::StreamWriter;
+use common_arrow::arrow::io::ipc::write::WriteOptions;
+use common_base::base::ProgressValues;
+use common_base::base::TrySpawn;
+use common_base::infallible::Mutex;
+use common_base::infallible::RwLock;
+use common_datablocks::DataBlock;
+use common_datavalues::DataSchema;
+use common_datavalues::DataSchemaRef;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_exception::SerializedError;
+use common_io::prelude::FormatSettings;
+use common_tracing::tracing;
+use futures::StreamExt;
+use opendal::ObjectMode;
+use opendal::Operator;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::HttpQueryContext;
+use crate::interpreters::Interpreter;
+use crate::interpreters::InterpreterFactory;
+use crate::interpreters::InterpreterQueryLog;
+use crate::servers::http::v1::query::ExecuteStateKind;
+use crate::servers::http::v1::query::HttpSessionConf;
+use crate::sessions::QueryContext;
+use crate::sessions::SessionRef;
+use crate::sessions::SessionType;
+use crate::sql::PlanParser;
+use crate::Config;
+
+// The results are kept in the storage like a hidden stage, under `<prefix>/<tenant>/<query id>/`,
+// with the manifest of the query next to the parts.
+const RESULT_LOCATION_PREFIX: &str = "_async_query_result";
+const MANIFEST_NAME: &str = "manifest.json";
+// The buffered blocks are written as a part of the result once they reach the size.
+const RESULT_PART_BYTES: usize = 16 * 1024 * 1024;
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct AsyncQueryRequest {
+    // only the database is used, the session ends with the query.
+    #[serde(default)]
+    pub session: HttpSessionConf,
+    pub sql: String,
+}
+
+/// The state of an asynchronous query at the moment.
+#[derive(Debug, Clone)]
+pub struct AsyncQueryState {
+    pub state: ExecuteStateKind,
+    pub error: Option<ErrorCode>,
+    pub scan_progress: Option<ProgressValues>,
+    pub running_time_ms: f64,
+    pub result_parts: usize,
+    pub result_rows: usize,
+    pub result_bytes: usize,
+    pub finish_time: Option<SystemTime>,
+    pub expire_time: Option<SystemTime>,
+}
+
+/// The manifest of an asynchronous query, which is written next to its result when the query
+/// is submitted and again when it is stopped. The queries not running on this node are looked
+/// up by their manifests, and removed with their results once they are expired.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AsyncQueryManifest {
+    pub id: String,
+    pub tenant: String,
+    pub user: String,
+    pub sql: String,
+    pub create_time: SystemTime,
+    // the timezone of the session, the result is serialized in it
+    pub timezone: String,
+    pub state: ExecuteStateKind,
+    pub error: Option<SerializedError>,
+    pub scan_progress: Option<ProgressValues>,
+    pub running_time_ms: f64,
+    pub schema: DataSchema,
+    pub result_parts: usize,
+    pub result_rows: usize,
+    pub result_bytes: usize,
+    pub finish_time: Option<SystemTime>,
+    pub expire_time: Option<SystemTime>,
+}
+
+impl AsyncQueryManifest {
+    pub fn location(tenant: &str, id: &str) -> String {
+        format!(
+            "{}/{}/{}/{}",
+            RESULT_LOCATION_PREFIX, tenant, id, MANIFEST_NAME
+        )
+    }
+
+    fn is_expired(&self) -> bool {
+        matches!(self.expire_time, Some(expire_time) if expire_time <= SystemTime::now())
+    }
+}
+
+struct ExecuteRunning {
+    // used to kill query
+    session: SessionRef,
+    // mainly used to get progress for now
+    ctx: Arc<QueryContext>,
+}
+
+struct Execution {
+    running: Option<ExecuteRunning>,
+    error: Option<ErrorCode>,
+    scan_progress: Option<ProgressValues>,
+    // the running time in milliseconds and the finish time, once the query is stopped
+    stop_time: Option<(f64, SystemTime)>,
+    schema: DataSchemaRef,
+    result_parts: usize,
+    result_rows: usize,
+    result_bytes: usize,
+    // the query is removed with its result once it is stopped
+    removed: bool,
+}
+
+/// A query submitted to run without a connected client, its result is written to the storage
+/// and kept for the retention after it is stopped.
+pub struct AsyncQuery {
+    pub id: String,
+    pub tenant: String,
+    pub user: String,
+    pub sql: String,
+    pub create_time: SystemTime,
+
+    retention: Duration,
+    operator: Operator,
+    location: String,
+    // Result is serialized with the session settings, e.g. the timezone.
+    format: FormatSettings,
+    execution: RwLock<Execution>,
+}
+
+impl AsyncQuery {
+    fn from_manifest(manifest: AsyncQueryManifest, operator: Operator) -> Result<AsyncQuery> {
+        let timezone = manifest.timezone.parse().map_err(|_| {
+            ErrorCode::InvalidTimezone(format!("Invalid timezone: {}", manifest.timezone))
+        })?;
+        let retention = match (manifest.finish_time, manifest.expire_time) {
+            (Some(finish_time), Some(expire_time)) => {
+                expire_time.duration_since(finish_time).unwrap_or_default()
+            }
+            _ => Duration::ZERO,
+        };
+        // A query running on another node is still running in its manifest.
+        let stop_time = match manifest.state {
+            ExecuteStateKind::Running => None,
+            _ => manifest
+                .finish_time
+                .map(|finish_time| (manifest.running_time_ms, finish_time)),
+        };
+        Ok(AsyncQuery {
+            location: format!(
+                "{}/{}/{}",
+                RESULT_LOCATION_PREFIX, manifest.tenant, manifest.id
+            ),
+            id: manifest.id,
+            tenant: manifest.tenant,
+            user: manifest.user,
+            sql: manifest.sql,
+            create_time: manifest.create_time,
+            retention,
+            operator,
+            format: FormatSettings {
+                timezone,
+                ..Default::default()
+            },
+            execution: RwLock::new(Execution {
+                running: None,
+                error: manifest.error.map(ErrorCode::from),
+                scan_progress: manifest.scan_progress,
+                stop_time,
+                schema: Arc::new(manifest.schema),
+                result_parts: manifest.result_parts,
+                result_rows: manifest.result_rows,
+                result_bytes: manifest.result_bytes,
+                removed: false,
+            }),
+        })
+    }
+
+    pub fn get_state(&self) -> AsyncQueryState {
+        let execution = self.execution.read();
+        let state = match (&execution.stop_time, &execution.error) {
+            (None, _) => ExecuteStateKind::Running,
+            (Some(_), Some(_)) => ExecuteStateKind::Failed,
+            (Some(_), None) => ExecuteStateKind::Succeeded,
+        };
+        let scan_progress = match &execution.running {
+            Some(running) => Some(running.ctx.get_scan_progress_value()),
+            None => execution.scan_progress.clone(),
+        };
+        let running_time_ms = match &execution.stop_time {
+            Some((running_time_ms, _)) => *running_time_ms,
+            None => self.create_time.elapsed().unwrap_or_default().as_secs_f64() * 1000.0,
+        };
+        let finish_time = execution.stop_time.map(|(_, finish_time)| finish_time);
+        AsyncQueryState {
+            state,
+            error: execution.error.clone(),
+            scan_progress,
+            running_time_ms,
+            result_parts: execution.result_parts,
+            result_rows: execution.result_rows,
+            result_bytes: execution.result_bytes,
+            finish_time,
+            expire_time: finish_time.map(|t| t + self.retention),
+        }
+    }
+
+    pub fn get_schema(&self) -> DataSchemaRef {
+        self.execution.read().schema.clone()
+    }
+
+    pub fn get_format_settings(&self) -> &FormatSettings {
+        &self.format
+    }
+
+    pub fn kill(&self) {
+        if let Some(running) = &self.execution.read().running {
+            running.session.force_kill_query();
+        }
+    }
+
+    /// Read back the blocks of a part of the result.
+    pub async fn read_part(&self, part: usize) -> Result<Vec<DataBlock>> {
+        let location = self.part_location(part);
+        let data = self.operator.object(&location).range_read(..).await?;
+
+        let schema = self.get_schema();
+        let mut reader = Cursor::new(data);
+        let metadata = read_stream_metadata(&mut reader)?;
+        let mut blocks = vec![];
+        for state in StreamReader::new(reader, metadata) {
+            match state? {
+                StreamState::Some(chunk) => blocks.push(DataBlock::from_chunk(&schema, &chunk)?),
+                StreamState::Waiting => break,
+            }
+        }
+        Ok(blocks)
+    }
+
+    async fn write_part(&self, blocks: Vec<DataBlock>) -> Result<()> {
+        let block = DataBlock::concat_blocks(&blocks)?;
+        let (rows, bytes) = (block.num_rows(), block.memory_size());
+        let schema = block.schema().clone();
+
+        let mut data = vec![];
+        {
+            let options = WriteOptions { compression: None };
+            let mut writer = StreamWriter::new(&mut data, options);
+            writer.start(&schema.to_arrow(), None)?;
+            writer.write(&Chunk::try_from(block)?, None)?;
+            writer.finish()?;
+        }
+
+        let part = self.execution.read().result_parts;
+        let location = self.part_location(part);
+        self.operator.object(&location).write(data).await?;
+
+        let mut execution = self.execution.write();
+        // The parts are read back with the schema of the blocks.
+        if part == 0 {
+            execution.schema = schema;
+        }
+        execution.result_parts += 1;
+        execution.result_rows += rows;
+        execution.result_bytes += bytes;
+        Ok(())
+    }
+
+    async fn write_manifest(&self) -> Result<()> {
+        let state = self.get_state();
+        let manifest = AsyncQueryManifest {
+            id: self.id.clone(),
+            tenant: self.tenant.clone(),
+            user: self.user.clone(),
+            sql: self.sql.clone(),
+            create_time: self.create_time,
+            timezone: self.format.timezone.name().to_string(),
+            state: state.state,
+            error: state.error.map(SerializedError::from),
+            scan_progress: state.scan_progress,
+            running_time_ms: state.running_time_ms,
+            schema: self.get_schema().as_ref().clone(),
+            result_parts: state.result_parts,
+            result_rows: state.result_rows,
+            result_bytes: state.result_bytes,
+            finish_time: state.finish_time,
+            expire_time: state.expire_time,
+        };
+        let data = serde_json::to_vec(&manifest)?;
+        let location = AsyncQueryManifest::location(&self.tenant, &self.id);
+        self.operator.object(&location).write(data).await?;
+        Ok(())
+    }
+
+    // The manifest is removed at last, so the parts left by a failure are still collected
+    // after the query is expired.
+    async fn remove_result(&self) {
+        let result_parts = self.execution.read().result_parts;
+        let locations = (0..result_parts)
+            .map(|part| self.part_location(part))
+            .chain(Some(AsyncQueryManifest::location(&self.tenant, &self.id)));
+        for location in locations {
+            if let Err(cause) = self.operator.object(&location).delete().await {
+                tracing::warn!("Cannot remove async query result {}: {}", location, cause);
+                return;
+            }
+        }
+    }
+
+    fn stop(&self, reason: Result<()>) {
+        let mut execution = self.execution.write();
+        if let Some(running) = execution.running.take() {
+            execution.scan_progress = Some(running.ctx.get_scan_progress_value());
+        }
+        if let Err(e) = reason {
+            if e.code() != ErrorCode::aborted_session_code()
+                && e.code() != ErrorCode::aborted_query_code()
+            {
+                tracing::error!("Async query {} error: {:?}", self.id, e);
+            }
+            execution.error = Some(e);
+        }
+        let running_time = self.create_time.elapsed().unwrap_or_default();
+        execution.stop_time = Some((running_time.as_secs_f64() * 1000.0, SystemTime::now()));
+    }
+
+    fn part_location(&self, part: usize) -> String {
+        format!("{}/{}", self.location, part)
+    }
+}
+
+// The least interval of collecting the expired results of a tenant, it's shorter if the
+// retention is.
+const COLLECT_EXPIRED_INTERVAL: Duration = Duration::from_secs(600);
+
+pub struct AsyncQueryManager {
+    // The queries running on this node, the others are looked up by their manifests.
+    running: RwLock<HashMap<String, Arc<AsyncQuery>>>,
+    retention: Duration,
+    // The last time the expired results of each tenant are collected on this node.
+    collected_at: Mutex<HashMap<String, Instant>>,
+}
+
+impl AsyncQueryManager {
+    pub fn create_global(cfg: &Config) -> Arc<AsyncQueryManager> {
+        Arc::new(AsyncQueryManager {
+            running: RwLock::new(HashMap::new()),
+            retention: Duration::from_secs(cfg.query.http_handler_async_result_retention_secs),
+            collected_at: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Start the query, it is removed with its result after the retention since it is stopped.
+    pub(crate) async fn submit(
+        self: &Arc<Self>,
+        ctx: &HttpQueryContext,
+        request: AsyncQueryRequest,
+    ) -> Result<Arc<AsyncQuery>> {
+        let session = ctx
+            .create_session(SessionType::HTTPAPI("AsyncQuery".to_string()))
+            .await?;
+        if let Some(db) = &request.session.database {
+            session.set_current_database(db.clone());
+        }
+
+        let query_ctx = session.create_query_context().await?;
+        let sql = request.sql;
+        query_ctx.attach_query_str(&sql);
+        let plan = match PlanParser::parse(query_ctx.clone(), &sql).await {
+            Ok(p) => p,
+            Err(e) => {
+                InterpreterQueryLog::fail_to_start(query_ctx, e.clone()).await;
+                return Err(e);
+            }
+        };
+
+        let interpreter = InterpreterFactory::get(query_ctx.clone(), plan)?;
+        // Write Start to query log table.
+        let _ = interpreter
+            .start()
+            .await
+            .map_err(|e| tracing::error!("interpreter.start.error: {:?}", e));
+
+        let id = query_ctx.get_id();
+        let tenant = query_ctx.get_tenant();
+        let query = Arc::new(AsyncQuery {
+            id: id.clone(),
+            user: ctx.user_info.identity().to_string(),
+            location: format!("{}/{}/{}", RESULT_LOCATION_PREFIX, tenant, id),
+            tenant,
+            sql,
+            create_time: SystemTime::now(),
+            retention: self.retention,
+            operator: query_ctx.get_storage_operator()?,
+            format: query_ctx.get_format_settings()?,
+            execution: RwLock::new(Execution {
+                running: Some(ExecuteRunning {
+                    session,
+                    ctx: query_ctx.clone(),
+                }),
+                error: None,
+                scan_progress: None,
+                stop_time: None,
+                schema: interpreter.schema(),
+                result_parts: 0,
+                result_rows: 0,
+                result_bytes: 0,
+                removed: false,
+            }),
+        });
+        query.write_manifest().await?;
+        self.running.write().insert(id, query.clone());
+
+        let manager = self.clone();
+        let query_clone = query.clone();
+        let ctx_clone = query_ctx.clone();
+        query_ctx.try_spawn(async move {
+            let reason = execute(&query_clone, interpreter.clone(), ctx_clone).await;
+            // Write Finish to query log table.
+            let _ = interpreter
+                .finish()
+                .await
+                .map_err(|e| tracing::error!("interpreter.finish error: {:?}", e));
+            query_clone.stop(reason);
+            manager.finish(&query_clone).await;
+        })?;
+
+        Ok(query)
+    }
+
+    // The stopped query is written to its manifest and is no longer running on this node, then
+    // the expired results of the tenant are collected, at most once per interval.
+    async fn finish(&self, query: &AsyncQuery) {
+        if let Err(cause) = query.write_manifest().await {
+            tracing::warn!(
+                "Cannot write the manifest of async query {}: {}",
+                query.id,
+                cause
+            );
+        }
+        let removed = {
+            let mut running = self.running.write();
+            running.remove(&query.id);
+            query.execution.read().removed
+        };
+        if removed {
+            query.remove_result().await;
+        }
+
+        if !self.start_collecting(&query.tenant) {
+            return;
+        }
+        if let Err(cause) = Self::collect_expired(&query.operator, &query.tenant).await {
+            tracing::warn!("Cannot collect the expired async query results: {}", cause);
+        }
+    }
+
+    // Whether to collect the expired results of the tenant, which is not done in the interval.
+    fn start_collecting(&self, tenant: &str) -> bool {
+        let interval = self.retention.min(COLLECT_EXPIRED_INTERVAL);
+        let mut collected_at = self.collected_at.lock();
+        match collected_at.get(tenant) {
+            Some(at) if at.elapsed() < interval => false,
+            _ => {
+                collected_at.insert(tenant.to_string(), Instant::now());
+                true
+            }
+        }
+    }
+
+    /// Get the query submitted by the user of the tenant, which is running on this node, or
+    /// else looked up by its manifest in the storage.
+    pub async fn get_query(
+        &self,
+        operator: Operator,
+        tenant: &str,
+        user: &str,
+        id: &str,
+    ) -> Result<Option<Arc<AsyncQuery>>> {
+        let running = self.running.read().get(id).cloned();
+        if let Some(query) = running {
+            return Ok(Some(query).filter(|query| query.tenant == tenant && query.user == user));
+        }
+
+        // The id is a part of the location of the manifest.
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return Ok(None);
+        }
+        match read_manifest(&operator, &AsyncQueryManifest::location(tenant, id)).await? {
+            Some(manifest) if manifest.user == user && !manifest.is_expired() => Ok(Some(
+                Arc::new(AsyncQuery::from_manifest(manifest, operator)?),
+            )),
+            _ => Ok(None),
+        }
+    }
+
+    /// The queries of the tenant which are not expired.
+    pub async fn list_queries(
+        &self,
+        operator: Operator,
+        tenant: &str,
+    ) -> Result<Vec<Arc<AsyncQuery>>> {
+        let mut queries = self
+            .running
+            .read()
+            .values()
+            .filter(|query| query.tenant == tenant)
+            .cloned()
+            .collect::<Vec<_>>();
+        for manifest in list_manifests(&operator, tenant).await? {
+            if !manifest.is_expired() && !queries.iter().any(|query| query.id == manifest.id) {
+                queries.push(Arc::new(AsyncQuery::from_manifest(
+                    manifest,
+                    operator.clone(),
+                )?));
+            }
+        }
+        queries.sort_by_key(|query| query.create_time);
+        Ok(queries)
+    }
+
+    /// Kill the query if it is running, and remove it with its result at once after it is
+    /// stopped.
+    pub async fn remove_query(&self, query: &AsyncQuery) {
+        {
+            let running = self.running.read();
+            if let Some(running_query) = running.get(&query.id) {
+                running_query.execution.write().removed = true;
+                running_query.kill();
+                return;
+            }
+        }
+        query.remove_result().await;
+    }
+
+    async fn collect_expired(operator: &Operator, tenant: &str) -> Result<()> {
+        for manifest in list_manifests(operator, tenant).await? {
+            if manifest.is_expired() {
+                AsyncQuery::from_manifest(manifest, operator.clone())?
+                    .remove_result()
+                    .await;
+            }
+        }
+        Ok(())
+    }
+}
+
+async fn read_manifest(operator: &Operator, location: &str) -> Result<Option<AsyncQueryManifest>> {
+    match operator.object(location).range_read(..).await {
+        Ok(data) => Ok(Some(serde_json::from_slice(&data)?)),
+        Err(cause) if cause.kind() == ErrorKind::NotFound => Ok(None),
+        Err(cause) => Err(cause.into()),
+    }
+}
+
+// The manifests of the queries of the tenant, the results without manifests are skipped.
+async fn list_manifests(operator: &Operator, tenant: &str) -> Result<Vec<AsyncQueryManifest>> {
+    let prefix = format!("{}/{}/", RESULT_LOCATION_PREFIX, tenant);
+    let mut objects = match operator.object(&prefix).list().await {
+        Ok(objects) => objects,
+        Err(cause) if cause.kind() == ErrorKind::NotFound => return Ok(vec![]),
+        Err(cause) => return Err(cause.into()),
+    };
+
+    let mut manifests = vec![];
+    while let Some(object) = objects.next().await {
+        let mut object = object?;
+        let meta = object.metadata_cached().await?;
+        if meta.mode() != ObjectMode::DIR {
+            continue;
+        }
+        let location = format!("{}/{}", meta.path().trim_end_matches('/'), MANIFEST_NAME);
+        match read_manifest(operator, &location).await {
+            Ok(Some(manifest)) => manifests.push(manifest),
+            Ok(None) => {}
+            Err(cause) => {
+                tracing::warn!("Cannot read async query manifest {}: {}", location, cause)
+            }
+        }
+    }
+    Ok(manifests)
+}
+
+async fn execute(
+    query: &AsyncQuery,
+    interpreter: Arc<dyn Interpreter>,
+    ctx: Arc<QueryContext>,
+) -> Result<()> {
+    let data_stream = interpreter.execute(None).await?;
+    let mut data_stream = ctx.try_create_abortable(data_stream)?;
+
+    let mut blocks = vec![];
+    let mut buffered_bytes = 0;
+    while let Some(block) = data_stream.next().await {
+        let block = block?;
+        if block.is_empty() {
+            continue;
+        }
+        buffered_bytes += block.memory_size();
+        blocks.push(block);
+        if buffered_bytes >= RESULT_PART_BYTES {
+            query.write_part(std::mem::take(&mut blocks)).await?;
+            buffered_bytes = 0;
+        }
+    }
+    if !blocks.is_empty() {
+        query.write_part(blocks).await?;
+    }
+    Ok(())
+}
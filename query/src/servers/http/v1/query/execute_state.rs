@@ -22,6 +22,7 @@ use common_base::base::tokio::sync::RwLock;
 use common_base::base::ProgressValues;
 use common_base::base::TrySpawn;
 use common_datablocks::DataBlock;
+use common_datavalues::DataSchemaRef;
 use common_exception::ErrorCode;
 use common_exception::Result;
 use common_tracing::tracing;
@@ -78,6 +79,8 @@ pub(crate) struct ExecuteStopped {
 
 pub(crate) struct Executor {
     start_time: Instant,
+    // the schema of the result, which is kept after the query is stopped
+    pub(crate) schema: DataSchemaRef,
     pub(crate) state: ExecuteState,
 }
 
@@ -178,6 +181,7 @@ impl ExecuteState {
         };
         let executor = Arc::new(RwLock::new(Executor {
             start_time,
+            schema: interpreter.schema(),
             state: Running(running_state),
         }));
 
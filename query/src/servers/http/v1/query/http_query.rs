@@ -21,6 +21,7 @@ use common_base::base::tokio::sync::mpsc;
 use common_base::base::tokio::sync::Mutex as TokioMutex;
 use common_base::base::tokio::sync::RwLock;
 use common_base::base::ProgressValues;
+use common_datavalues::DataSchemaRef;
 use common_exception::ErrorCode;
 use common_exception::Result;
 use common_io::prelude::FormatSettings;
@@ -30,6 +31,7 @@ use super::HttpQueryContext;
 use crate::servers::http::v1::query::expirable::Expirable;
 use crate::servers::http::v1::query::expirable::ExpiringState;
 use crate::servers::http::v1::query::http_query_manager::HttpQueryConfig;
+use crate::servers::http::v1::query::BlockPage;
 use crate::servers::http::v1::query::ExecuteState;
 use crate::servers::http::v1::query::ExecuteStateKind;
 use crate::servers::http::v1::query::Executor;
@@ -46,6 +48,27 @@ pub struct HttpQueryRequest {
     pub sql: String,
     #[serde(default)]
     pub pagination: PaginationConf,
+    // stream all the result in the response instead of paginating it in JSON.
+    #[serde(default)]
+    pub stream_format: Option<StreamFormat>,
+}
+
+/// The formats of the result streamed in a single chunked response.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum StreamFormat {
+    #[serde(rename = "ndjson")]
+    NDJson,
+    #[serde(rename = "arrow")]
+    Arrow,
+}
+
+impl StreamFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            StreamFormat::NDJson => "application/x-ndjson",
+            StreamFormat::Arrow => "application/vnd.apache.arrow.stream",
+        }
+    }
 }
 
 #[derive(Deserialize, Debug)]
@@ -114,6 +137,8 @@ pub struct HttpQuery {
     data: Arc<TokioMutex<ResultDataManager>>,
     config: HttpQueryConfig,
     expire_at: Arc<TokioMutex<Option<Instant>>>,
+    // Result pages are serialized with the session settings, e.g. the timezone.
+    format: FormatSettings,
 }
 
 impl HttpQuery {
@@ -155,6 +180,7 @@ impl HttpQuery {
 
         let ctx = session.create_query_context().await?;
         let id = ctx.get_id();
+        let format = ctx.get_format_settings()?;
 
         //TODO(youngsofun): support config/set channel size
         let (block_tx, block_rx) = mpsc::channel(10);
@@ -169,6 +195,7 @@ impl HttpQuery {
             data,
             config,
             expire_at: Arc::new(TokioMutex::new(None)),
+            format,
         };
         let query = Arc::new(query);
         Ok(query)
@@ -178,13 +205,9 @@ impl HttpQuery {
         self.request.pagination.wait_time_secs == 0
     }
 
-    pub async fn get_response_page(
-        &self,
-        page_no: usize,
-        format: &FormatSettings,
-    ) -> Result<HttpQueryResponseInternal> {
+    pub async fn get_response_page(&self, page_no: usize) -> Result<HttpQueryResponseInternal> {
         Ok(HttpQueryResponseInternal {
-            data: Some(self.get_page(page_no, format).await?),
+            data: Some(self.get_page(page_no).await?),
             session_id: self.session_id.clone(),
             state: self.get_state().await,
         })
@@ -209,10 +232,40 @@ impl HttpQuery {
         }
     }
 
-    async fn get_page(&self, page_no: usize, format: &FormatSettings) -> Result<ResponseData> {
+    pub async fn get_schema(&self) -> DataSchemaRef {
+        self.state.read().await.schema.clone()
+    }
+
+    pub fn get_format_settings(&self) -> &FormatSettings {
+        &self.format
+    }
+
+    /// Get a page of the result blocks to stream, waiting until the page is full or the end of
+    /// the result. Returns the page and the number of the next page.
+    pub async fn get_block_page(&self, page_no: usize) -> Result<(BlockPage, Option<usize>)> {
+        let mut data = self.data.lock().await;
+        let page = data.get_a_block_page(page_no, &Wait::Sync).await?;
+        Ok((page, data.next_page_no()))
+    }
+
+    /// Drop the result pages before `page_no`, the client has received them.
+    pub async fn ack_pages(&self, page_no: usize) {
+        self.data.lock().await.ack_pages(page_no);
+    }
+
+    pub async fn num_kept_pages(&self) -> usize {
+        self.data.lock().await.num_kept_pages()
+    }
+
+    async fn get_page(&self, page_no: usize) -> Result<ResponseData> {
         let mut data = self.data.lock().await;
+        data.ack_pages(page_no);
         let page = data
-            .get_a_page(page_no, &self.request.pagination.get_wait_type(), format)
+            .get_a_page(
+                page_no,
+                &self.request.pagination.get_wait_type(),
+                &self.format,
+            )
             .await?;
         let response = ResponseData {
             page,
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod async_query;
 mod execute_state;
 mod expirable;
 mod expiring_map;
@@ -20,6 +21,11 @@ mod http_query_context;
 mod http_query_manager;
 mod result_data_manager;
 
+pub use async_query::AsyncQuery;
+pub use async_query::AsyncQueryManager;
+pub use async_query::AsyncQueryManifest;
+pub use async_query::AsyncQueryRequest;
+pub use async_query::AsyncQueryState;
 pub(crate) use execute_state::ExecuteState;
 pub use execute_state::ExecuteStateKind;
 pub(crate) use execute_state::Executor;
@@ -31,8 +37,10 @@ pub use http_query::HttpSession;
 pub use http_query::HttpSessionConf;
 pub use http_query::PaginationConf;
 pub use http_query::ResponseState;
+pub use http_query::StreamFormat;
 pub use http_query_context::HttpQueryContext;
 pub use http_query_manager::HttpQueryManager;
+pub use result_data_manager::BlockPage;
 pub use result_data_manager::Page;
 pub use result_data_manager::ResponseData;
 pub use result_data_manager::ResultDataManager;
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::VecDeque;
 use std::time::Instant;
 
 use common_base::base::tokio;
@@ -40,6 +41,14 @@ pub struct Page {
     pub total_rows: usize,
 }
 
+/// A page of the result blocks, which is serialized to JSON rows by `get_a_page`, or streamed
+/// in the requested format.
+#[derive(Clone)]
+pub struct BlockPage {
+    pub blocks: Vec<DataBlock>,
+    pub total_rows: usize,
+}
+
 pub struct ResponseData {
     pub page: Page,
     pub next_page_no: Option<usize>,
@@ -48,7 +57,9 @@ pub struct ResponseData {
 pub struct ResultDataManager {
     total_rows: usize,
     total_pages: usize,
-    last_page: Option<Page>,
+    // The pages from `first_page_no` which are not acknowledged yet.
+    pages: VecDeque<BlockPage>,
+    first_page_no: usize,
     pub(crate) block_rx: mpsc::Receiver<DataBlock>,
     end: bool,
 }
@@ -58,7 +69,8 @@ impl ResultDataManager {
         ResultDataManager {
             block_rx,
             total_rows: 0,
-            last_page: None,
+            pages: VecDeque::new(),
+            first_page_no: 0,
             total_pages: 0,
             end: false,
         }
@@ -72,35 +84,55 @@ impl ResultDataManager {
         }
     }
 
+    /// The request of the page `page_no` acknowledges the pages before it, which are dropped.
+    pub fn ack_pages(&mut self, page_no: usize) {
+        while self.first_page_no < page_no && !self.pages.is_empty() {
+            self.pages.pop_front();
+            self.first_page_no += 1;
+        }
+    }
+
+    /// The number of the pages kept, which are not acknowledged yet.
+    pub fn num_kept_pages(&self) -> usize {
+        self.pages.len()
+    }
+
     pub async fn get_a_page(
         &mut self,
         page_no: usize,
         tp: &Wait,
         format: &FormatSettings,
     ) -> Result<Page> {
+        let page = self.get_a_block_page(page_no, tp).await?;
+        let blocks = page
+            .blocks
+            .iter()
+            .map(|block| JsonBlock::new(block, format))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Page {
+            data: JsonBlock::concat(blocks),
+            total_rows: page.total_rows,
+        })
+    }
+
+    pub async fn get_a_block_page(&mut self, page_no: usize, tp: &Wait) -> Result<BlockPage> {
         let next_no = self.total_pages;
         if page_no == next_no && !self.end {
-            let (block, end) = self.collect_new_page(tp, format).await?;
-            let num_row = block.num_rows();
+            let (blocks, end) = self.collect_new_page(tp).await;
+            let num_row = blocks.iter().map(|block| block.num_rows()).sum::<usize>();
             self.total_rows += num_row;
-            let page = Page {
-                data: block,
+            let page = BlockPage {
+                blocks,
                 total_rows: self.total_rows,
             };
             if num_row > 0 {
                 self.total_pages += 1;
-                self.last_page = Some(page.clone());
+                self.pages.push_back(page.clone());
             }
             self.end = end;
             Ok(page)
-        } else if page_no == next_no - 1 {
-            // later, there may be other ways to ack and drop the last page except collect_new_page.
-            // but for now, last_page always exists in this branch, since page_no is unsigned.
-            Ok(self
-                .last_page
-                .as_ref()
-                .ok_or_else(|| ErrorCode::UnexpectedError("last_page is None"))?
-                .clone())
+        } else if page_no >= self.first_page_no && page_no < next_no {
+            Ok(self.pages[page_no - self.first_page_no].clone())
         } else {
             let message = format!("wrong page number {}", page_no,);
             Err(ErrorCode::HttpNotFound(message))
@@ -126,12 +158,8 @@ impl ResultDataManager {
         }
     }
 
-    pub async fn collect_new_page(
-        &mut self,
-        tp: &Wait,
-        format: &FormatSettings,
-    ) -> Result<(JsonBlock, bool)> {
-        let mut results: Vec<JsonBlock> = Vec::new();
+    pub async fn collect_new_page(&mut self, tp: &Wait) -> (Vec<DataBlock>, bool) {
+        let mut results: Vec<DataBlock> = Vec::new();
         let mut rows = 0;
         let block_rx = &mut self.block_rx;
 
@@ -140,7 +168,7 @@ impl ResultDataManager {
             match ResultDataManager::receive(block_rx, tp).await {
                 Ok(block) => {
                     rows += block.num_rows();
-                    results.push(JsonBlock::new(&block, format)?);
+                    results.push(block);
                     // TODO(youngsofun):  set it in post if needed
                     if rows >= TARGET_ROWS_PER_PAGE {
                         break;
@@ -154,6 +182,6 @@ impl ResultDataManager {
                 }
             }
         }
-        Ok((JsonBlock::concat(results), end))
+        (results, end)
     }
 }
@@ -18,12 +18,18 @@ pub use clickhouse::ClickHouseHandler;
 pub use server::Server;
 pub use server::ShutdownHandle;
 
+pub use self::flight_sql::FlightSqlHandler;
 pub use self::http::HttpHandler;
 pub use self::mysql::MySQLConnection;
 pub use self::mysql::MySQLFederated;
 pub use self::mysql::MySQLHandler;
+pub use self::postgres::PostgresHandler;
 
 pub(crate) mod clickhouse;
+pub mod flight_sql;
 pub mod http;
 mod mysql;
+mod postgres;
 pub(crate) mod server;
+mod sql_literal;
+mod tls;
@@ -16,7 +16,9 @@ mod mysql_federated;
 mod mysql_handler;
 mod mysql_interactive_worker;
 mod mysql_metrics;
+mod mysql_prepared_statement;
 mod mysql_session;
+mod mysql_tls;
 #[allow(clippy::unused_io_amount)]
 mod reject_connection;
 mod writers;
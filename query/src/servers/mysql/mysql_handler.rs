@@ -29,12 +29,14 @@ use futures::future::AbortRegistration;
 use futures::future::Abortable;
 use futures::StreamExt;
 use opensrv_mysql::*;
+use tokio_rustls::TlsAcceptor;
 use tokio_stream::wrappers::TcpListenerStream;
 
 use crate::servers::mysql::mysql_session::MySQLConnection;
 use crate::servers::mysql::reject_connection::RejectConnection;
 use crate::servers::server::ListeningStream;
 use crate::servers::server::Server;
+use crate::servers::tls::build_tls_acceptor;
 use crate::sessions::SessionManager;
 use crate::sessions::SessionType;
 
@@ -66,27 +68,53 @@ impl MySQLHandler {
         Ok((TcpListenerStream::new(listener), listener_addr))
     }
 
-    fn listen_loop(&self, stream: ListeningStream, rt: Arc<Runtime>) -> impl Future<Output = ()> {
+    fn listen_loop(
+        &self,
+        stream: ListeningStream,
+        rt: Arc<Runtime>,
+        tls: Option<TlsAcceptor>,
+    ) -> impl Future<Output = ()> {
         let sessions = self.sessions.clone();
         stream.for_each(move |accept_socket| {
             let executor = rt.clone();
             let sessions = sessions.clone();
+            let tls = tls.clone();
             async move {
                 match accept_socket {
                     Err(error) => tracing::error!("Broken session connection: {}", error),
-                    Ok(socket) => MySQLHandler::accept_socket(sessions, executor, socket),
+                    Ok(socket) => MySQLHandler::accept_socket(sessions, executor, socket, tls),
                 };
             }
         })
     }
 
-    fn accept_socket(sessions: Arc<SessionManager>, executor: Arc<Runtime>, socket: TcpStream) {
+    fn build_tls(sessions: &SessionManager) -> Result<Option<TlsAcceptor>> {
+        let config = sessions.get_conf();
+        match config.tls_mysql_handler_enabled() {
+            false => Ok(None),
+            true => {
+                tracing::info!("MySQL Handler TLS enabled");
+                Ok(Some(build_tls_acceptor(
+                    &config.query.mysql_handler_tls_server_cert,
+                    &config.query.mysql_handler_tls_server_key,
+                    &config.query.mysql_handler_tls_server_root_ca_cert,
+                )?))
+            }
+        }
+    }
+
+    fn accept_socket(
+        sessions: Arc<SessionManager>,
+        executor: Arc<Runtime>,
+        socket: TcpStream,
+        tls: Option<TlsAcceptor>,
+    ) {
         executor.spawn(async move {
             match sessions.create_session(SessionType::MySQL).await {
-                Err(error) => Self::reject_session(socket, error).await,
+                Err(error) => Self::reject_session(socket, error, tls).await,
                 Ok(session) => {
                     tracing::info!("MySQL connection coming: {:?}", socket.peer_addr());
-                    if let Err(error) = MySQLConnection::run_on_stream(session, socket) {
+                    if let Err(error) = MySQLConnection::run_on_stream(session, socket, tls) {
                         tracing::error!("Unexpected error occurred during query: {:?}", error);
                     };
                 }
@@ -94,13 +122,15 @@ impl MySQLHandler {
         });
     }
 
-    async fn reject_session(stream: TcpStream, error: ErrorCode) {
+    async fn reject_session(stream: TcpStream, error: ErrorCode, tls: Option<TlsAcceptor>) {
         let (kind, message) = match error.code() {
             41 => (ErrorKind::ER_TOO_MANY_USER_CONNECTIONS, error.message()),
             _ => (ErrorKind::ER_INTERNAL_ERROR, error.message()),
         };
 
-        if let Err(error) = RejectConnection::reject_mysql_connection(stream, kind, message).await {
+        let rejected = RejectConnection::reject_mysql_connection(stream, kind, message, tls).await;
+
+        if let Err(error) = rejected {
             tracing::error!(
                 "Unexpected error occurred during reject connection: {:?}",
                 error
@@ -132,13 +162,14 @@ impl Server for MySQLHandler {
         match self.abort_registration.take() {
             None => Err(ErrorCode::LogicalError("MySQLHandler already running.")),
             Some(registration) => {
+                let tls = Self::build_tls(&self.sessions)?;
                 let rejected_rt = Arc::new(Runtime::with_worker_threads(
                     1,
                     Some("mysql-handler".to_string()),
                 )?);
                 let (stream, listener) = Self::listener_tcp(listening).await?;
                 let stream = Abortable::new(stream, registration);
-                self.join_handle = Some(tokio::spawn(self.listen_loop(stream, rejected_rt)));
+                self.join_handle = Some(tokio::spawn(self.listen_loop(stream, rejected_rt, tls)));
                 Ok(listener)
             }
         }
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::marker::PhantomData;
 use std::sync::Arc;
 use std::time::Instant;
@@ -22,10 +23,12 @@ use common_exception::ErrorCode;
 use common_exception::Result;
 use common_exception::ToErrorCode;
 use common_io::prelude::*;
+use common_planners::PlanNode;
 use common_tracing::tracing;
 use common_tracing::tracing::Instrument;
 use metrics::histogram;
 use opensrv_mysql::AsyncMysqlShim;
+use opensrv_mysql::Column;
 use opensrv_mysql::ErrorKind;
 use opensrv_mysql::InitWriter;
 use opensrv_mysql::ParamParser;
@@ -38,6 +41,8 @@ use crate::interpreters::Interpreter;
 use crate::interpreters::InterpreterFactory;
 use crate::interpreters::InterpreterQueryLog;
 use crate::interpreters::SelectInterpreterV2;
+use crate::servers::mysql::mysql_prepared_statement::MySQLPreparedStatement;
+use crate::servers::mysql::writers::convert_schema;
 use crate::servers::mysql::writers::DFInitResultWriter;
 use crate::servers::mysql::writers::DFQueryResultWriter;
 use crate::servers::mysql::MySQLFederated;
@@ -45,12 +50,14 @@ use crate::servers::mysql::MYSQL_VERSION;
 use crate::sessions::QueryContext;
 use crate::sessions::SessionRef;
 use crate::sql::DfParser;
-use crate::sql::DfStatement;
 use crate::sql::PlanParser;
 use crate::users::CertifiedInfo;
 
 struct InteractiveWorkerBase<W: std::io::Write> {
     session: SessionRef,
+    // prepared statements of the connection, keyed by statement id
+    prepared_statements: HashMap<u32, MySQLPreparedStatement>,
+    next_statement_id: u32,
     generic_hold: PhantomData<W>,
 }
 
@@ -238,28 +245,89 @@ impl<W: std::io::Write> InteractiveWorkerBase<W> {
         Ok(authed)
     }
 
-    async fn do_prepare(&mut self, _: &str, writer: StatementMetaWriter<'_, W>) -> Result<()> {
-        writer.error(
-            ErrorKind::ER_UNKNOWN_ERROR,
-            "Prepare is not support in Databend.".as_bytes(),
-        )?;
+    async fn do_prepare(&mut self, query: &str, writer: StatementMetaWriter<'_, W>) -> Result<()> {
+        let statement = match MySQLPreparedStatement::try_create(query) {
+            Ok(statement) => statement,
+            Err(error) => {
+                writer.error(ErrorKind::ER_PARSE_ERROR, error.to_string().as_bytes())?;
+                return Ok(());
+            }
+        };
+
+        let columns = self.describe_columns(&statement).await;
+        self.next_statement_id = self.next_statement_id.wrapping_add(1);
+        let id = self.next_statement_id;
+        writer.reply(id, &statement.params_columns(), &columns)?;
+        self.prepared_statements.insert(id, statement);
         Ok(())
     }
 
+    // Describe the result columns of a prepared `SELECT` statement by planning it with the
+    // placeholders bound to `NULL`. The columns are left empty if the statement cannot be
+    // planned that way, and will be sent with the result set on execution.
+    async fn describe_columns(&self, statement: &MySQLPreparedStatement) -> Vec<Column> {
+        let query = statement.null_bound_query();
+        if self.federated_server_command_check(&query).is_some() {
+            return vec![];
+        }
+
+        let plan = match self.session.create_query_context().await {
+            Ok(context) => PlanParser::parse(context, &query).await,
+            Err(error) => Err(error),
+        };
+        match plan {
+            Ok(plan @ PlanNode::Select(_)) => convert_schema(&plan.schema()).unwrap_or_default(),
+            _ => vec![],
+        }
+    }
+
     async fn do_execute(
         &mut self,
-        _: u32,
-        _: ParamParser<'_>,
+        id: u32,
+        params: ParamParser<'_>,
         writer: QueryResultWriter<'_, W>,
     ) -> Result<()> {
-        writer.error(
-            ErrorKind::ER_UNKNOWN_ERROR,
-            "Execute is not support in Databend.".as_bytes(),
-        )?;
-        Ok(())
+        let mut writer = DFQueryResultWriter::create(writer);
+
+        let instant = Instant::now();
+        let query = match self.prepared_statements.get(&id) {
+            Some(statement) => statement.bind(params),
+            None => Err(ErrorCode::BadArguments(format!(
+                "Unknown prepared statement handler: {}",
+                id
+            ))),
+        };
+        let blocks = match &query {
+            Ok(query) => self.do_query(query).await,
+            Err(error) => Err(error.clone()),
+        };
+
+        let format = self
+            .session
+            .get_shared_query_context()
+            .await?
+            .get_format_settings()?;
+        let mut write_result = writer.write(blocks, &format);
+
+        if let Err(cause) = write_result {
+            let suffix = match &query {
+                Ok(query) => format!("(while in query {})", query),
+                Err(_) => format!("(while in prepared statement {})", id),
+            };
+            write_result = Err(cause.add_message_back(suffix));
+        }
+
+        histogram!(
+            super::mysql_metrics::METRIC_MYSQL_PROCESSOR_REQUEST_DURATION,
+            instant.elapsed()
+        );
+
+        write_result
     }
 
-    async fn do_close(&mut self, _: u32) {}
+    async fn do_close(&mut self, id: u32) {
+        self.prepared_statements.remove(&id);
+    }
 
     // Check the query is a federated or driver setup command.
     // Here we fake some values for the command which Databend not supported.
@@ -283,20 +351,24 @@ impl<W: std::io::Write> InteractiveWorkerBase<W> {
                 let context = self.session.create_query_context().await?;
                 context.attach_query_str(query);
 
-                let settings = context.get_settings();
-
-                let (stmts, hints) =
-                    DfParser::parse_sql(query, context.get_current_session().get_type())?;
+                let stmts_hints =
+                    DfParser::parse_sql(query, context.get_current_session().get_type());
+                let stmts = stmts_hints.as_ref().ok().map(|(stmts, _)| stmts.as_slice());
+                let hints = match &stmts_hints {
+                    Ok((_, hints)) => hints.clone(),
+                    Err(_) => {
+                        DfParser::parse_hints(query, context.get_current_session().get_type())?
+                    }
+                };
 
                 let interpreter: Arc<dyn Interpreter> =
-                    if settings.get_enable_new_processor_framework()? != 0
-                        && context.get_cluster().is_empty()
-                        && settings.get_enable_planner_v2()? != 0
-                        && matches!(stmts.get(0), Some(DfStatement::Query(_)))
-                    {
+                    if SelectInterpreterV2::check(&context, stmts, query)? {
                         // New planner is enabled, and the statement is ensured to be `SELECT` statement.
                         SelectInterpreterV2::try_create(context.clone(), query)?
                     } else {
+                        // The statement can't be parsed by any planner.
+                        stmts_hints?;
+
                         let (plan, _) = PlanParser::parse_with_hint(query, context.clone()).await;
                         if let (Some(hint_error_code), Err(error_code)) = (
                             hints
@@ -436,6 +508,8 @@ impl<W: std::io::Write> InteractiveWorker<W> {
             session: session.clone(),
             base: InteractiveWorkerBase::<W> {
                 session,
+                prepared_statements: HashMap::new(),
+                next_statement_id: 0,
                 generic_hold: PhantomData::default(),
             },
             salt: scramble,
@@ -0,0 +1,167 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::ops::Range;
+
+use common_ast::parser::token::TokenKind;
+use common_ast::parser::tokenize_sql;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use opensrv_mysql::Column;
+use opensrv_mysql::ColumnFlags;
+use opensrv_mysql::ColumnType;
+use opensrv_mysql::ParamValue;
+use opensrv_mysql::ValueInner;
+
+use crate::servers::sql_literal::number_literal;
+use crate::servers::sql_literal::quote_string;
+
+/// A server-side prepared statement of a MySQL connection, created by `COM_STMT_PREPARE`.
+///
+/// The values sent by `COM_STMT_EXECUTE` are bound by replacing the placeholders `?` with
+/// literals, and the bound query is planned and executed like a text query.
+pub struct MySQLPreparedStatement {
+    query: String,
+    // spans of the placeholders in the query
+    placeholders: Vec<Range<usize>>,
+}
+
+impl MySQLPreparedStatement {
+    pub fn try_create(query: &str) -> Result<MySQLPreparedStatement> {
+        let placeholders = tokenize_sql(query)?
+            .into_iter()
+            .filter(|token| token.kind == TokenKind::Placeholder)
+            .map(|token| token.span)
+            .collect();
+        Ok(MySQLPreparedStatement {
+            query: query.to_string(),
+            placeholders,
+        })
+    }
+
+    /// The query with all the placeholders bound to `NULL`, which is used to describe the
+    /// result columns when preparing.
+    pub fn null_bound_query(&self) -> String {
+        self.replace_placeholders(vec!["NULL".to_string(); self.placeholders.len()])
+    }
+
+    /// Metadata of the parameters. The types are unknown until execution, so they are
+    /// described as strings and the clients send the values with their own types.
+    pub fn params_columns(&self) -> Vec<Column> {
+        (0..self.placeholders.len())
+            .map(|_| Column {
+                table: "".to_string(),
+                column: "?".to_string(),
+                coltype: ColumnType::MYSQL_TYPE_VAR_STRING,
+                colflags: ColumnFlags::empty(),
+            })
+            .collect()
+    }
+
+    /// Bind the parameters of `COM_STMT_EXECUTE` into the query.
+    pub fn bind<'a>(&self, params: impl IntoIterator<Item = ParamValue<'a>>) -> Result<String> {
+        let literals = params
+            .into_iter()
+            .map(Self::param_literal)
+            .collect::<Result<Vec<_>>>()?;
+        if literals.len() != self.placeholders.len() {
+            return Err(ErrorCode::BadArguments(format!(
+                "Prepared statement expects {} parameters, but got {}",
+                self.placeholders.len(),
+                literals.len()
+            )));
+        }
+        Ok(self.replace_placeholders(literals))
+    }
+
+    fn replace_placeholders(&self, literals: Vec<String>) -> String {
+        let mut query = String::with_capacity(self.query.len());
+        let mut position = 0;
+        for (span, literal) in self.placeholders.iter().zip(literals) {
+            query.push_str(&self.query[position..span.start]);
+            query.push_str(&literal);
+            position = span.end;
+        }
+        query.push_str(&self.query[position..]);
+        query
+    }
+
+    fn param_literal(param: ParamValue) -> Result<String> {
+        match param.value.into_inner() {
+            ValueInner::NULL => Ok("NULL".to_string()),
+            ValueInner::Int(v) => Ok(number_literal(v.to_string())),
+            ValueInner::UInt(v) => Ok(v.to_string()),
+            ValueInner::Double(v) => Ok(number_literal(v.to_string())),
+            ValueInner::Bytes(v) => Ok(quote_string(&String::from_utf8_lossy(v))),
+            ValueInner::Date(v) => decode_datetime(v, param.coltype).map(|v| quote_string(&v)),
+            ValueInner::Time(v) => decode_time(v).map(|v| quote_string(&v)),
+        }
+    }
+}
+
+// Decode a value of the binary protocol for `DATE`, `DATETIME` and `TIMESTAMP`, which is
+// encoded as `year(2) month(1) day(1) [hour(1) minute(1) second(1) [microsecond(4)]]`.
+fn decode_datetime(v: &[u8], coltype: ColumnType) -> Result<String> {
+    let (year, month, day) = match v.len() {
+        0 => (0, 0, 0),
+        4 | 7 | 11 => (u16::from_le_bytes([v[0], v[1]]), v[2], v[3]),
+        len => {
+            return Err(ErrorCode::BadBytes(format!(
+                "Invalid length of datetime parameter: {}",
+                len
+            )))
+        }
+    };
+    if matches!(coltype, ColumnType::MYSQL_TYPE_DATE) {
+        return Ok(format!("{:04}-{:02}-{:02}", year, month, day));
+    }
+
+    let (hour, minute, second) = match v.len() {
+        7 | 11 => (v[4], v[5], v[6]),
+        _ => (0, 0, 0),
+    };
+    let mut datetime = format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year, month, day, hour, minute, second
+    );
+    if v.len() == 11 {
+        let microsecond = u32::from_le_bytes([v[7], v[8], v[9], v[10]]);
+        datetime.push_str(&format!(".{:06}", microsecond));
+    }
+    Ok(datetime)
+}
+
+// Decode a value of the binary protocol for `TIME`, which is encoded as
+// `is_negative(1) days(4) hour(1) minute(1) second(1) [microsecond(4)]`.
+fn decode_time(v: &[u8]) -> Result<String> {
+    if v.is_empty() {
+        return Ok("00:00:00".to_string());
+    }
+    if v.len() != 8 && v.len() != 12 {
+        return Err(ErrorCode::BadBytes(format!(
+            "Invalid length of time parameter: {}",
+            v.len()
+        )));
+    }
+
+    let sign = if v[0] == 1 { "-" } else { "" };
+    let days = u32::from_le_bytes([v[1], v[2], v[3], v[4]]);
+    let hours = days as u64 * 24 + v[5] as u64;
+    let mut time = format!("{}{:02}:{:02}:{:02}", sign, hours, v[6], v[7]);
+    if v.len() == 12 {
+        let microsecond = u32::from_le_bytes([v[8], v[9], v[10], v[11]]);
+        time.push_str(&format!(".{:06}", microsecond));
+    }
+    Ok(time)
+}
@@ -24,14 +24,20 @@ use common_exception::ToErrorCode;
 use common_tracing::tracing;
 use opensrv_mysql::AsyncMysqlIntermediary;
 use opensrv_mysql::IntermediaryOptions;
+use tokio_rustls::TlsAcceptor;
 
 use crate::servers::mysql::mysql_interactive_worker::InteractiveWorker;
+use crate::servers::mysql::mysql_tls::MySQLTlsUpgrade;
 use crate::sessions::SessionRef;
 
 pub struct MySQLConnection;
 
 impl MySQLConnection {
-    pub fn run_on_stream(session: SessionRef, stream: TcpStream) -> Result<()> {
+    pub fn run_on_stream(
+        session: SessionRef,
+        stream: TcpStream,
+        tls: Option<TlsAcceptor>,
+    ) -> Result<()> {
         let blocking_stream = Self::convert_stream(stream)?;
         MySQLConnection::attach_session(&session, &blocking_stream)?;
 
@@ -41,16 +47,20 @@ impl MySQLConnection {
         Thread::spawn(move || {
             let join_handle = query_executor.spawn(async move {
                 let client_addr = non_blocking_stream.peer_addr().unwrap().to_string();
+                let stream = match tls {
+                    None => non_blocking_stream,
+                    Some(acceptor) => {
+                        let stream =
+                            MySQLTlsUpgrade::relay_on_loopback(acceptor, non_blocking_stream)
+                                .await?;
+                        TcpStream::from_std(Self::convert_stream(stream)?)?
+                    }
+                };
                 let interactive_worker = InteractiveWorker::create(session, client_addr);
                 let opts = IntermediaryOptions {
                     process_use_statement_on_query: true,
                 };
-                AsyncMysqlIntermediary::run_with_options(
-                    interactive_worker,
-                    non_blocking_stream,
-                    &opts,
-                )
-                .await
+                AsyncMysqlIntermediary::run_with_options(interactive_worker, stream, &opts).await
             });
             let _ = futures::executor::block_on(join_handle);
         });
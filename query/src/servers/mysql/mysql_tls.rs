@@ -0,0 +1,193 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_base::base::tokio;
+use common_base::base::tokio::io::AsyncRead;
+use common_base::base::tokio::io::AsyncReadExt;
+use common_base::base::tokio::io::AsyncWrite;
+use common_base::base::tokio::io::AsyncWriteExt;
+use common_base::base::tokio::net::TcpStream;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_tracing::tracing;
+use tokio_rustls::server::TlsStream;
+use tokio_rustls::TlsAcceptor;
+
+use crate::servers::tls::loopback_pair;
+use crate::servers::tls::non_blocking;
+use crate::servers::tls::relay;
+
+const CLIENT_SSL: u32 = 0x0000_0800;
+const SSL_REQUEST_LENGTH: usize = 32;
+const ER_SECURE_TRANSPORT_REQUIRED: u16 = 3159;
+// A payload of this length is continued by the next packet.
+const MAX_PACKET_PAYLOAD: usize = 0xFF_FFFF;
+
+/// Upgrade a MySQL connection to TLS by the `SSLRequest` of the client.
+///
+/// The pinned opensrv-mysql neither negotiates TLS nor serves anything but a plain
+/// `TcpStream`, so it is connected over loopback and the packets of the connection phase are
+/// rewritten in between:
+/// - `CLIENT_SSL` is advertised in the initial handshake of the server.
+/// - The `SSLRequest` is consumed here and the TLS is accepted. Clients which do not request
+///   TLS are refused, their handshake response would carry the credentials in plaintext.
+/// - The server never sees the `SSLRequest`, so the sequence ids are shifted by one until
+///   the authentication is completed, then the packets are relayed as they are.
+///
+/// The packets are relayed as whole payloads, including those split into several packets
+/// of 16MB.
+pub struct MySQLTlsUpgrade;
+
+impl MySQLTlsUpgrade {
+    /// Start the upgrade of the client stream, the returned loopback stream is served as the
+    /// plaintext connection.
+    pub async fn relay_on_loopback(acceptor: TlsAcceptor, stream: TcpStream) -> Result<TcpStream> {
+        let stream = non_blocking(stream)?;
+        let (relay_stream, server_stream) = loopback_pair().await?;
+        tokio::spawn(async move {
+            if let Err(error) = Self::upgrade(acceptor, stream, relay_stream).await {
+                tracing::warn!("MySQL TLS upgrade failed: {:?}", error);
+            }
+        });
+        Ok(server_stream)
+    }
+
+    /// Read the `SSLRequest` which follows the initial handshake and accept the TLS, the
+    /// clients which do not request TLS are refused.
+    pub async fn accept_ssl_request(
+        acceptor: &TlsAcceptor,
+        mut client: TcpStream,
+    ) -> Result<TlsStream<TcpStream>> {
+        let (seq, ssl_request) = read_packet(&mut client).await?;
+        if ssl_request.len() != SSL_REQUEST_LENGTH || capabilities(&ssl_request) & CLIENT_SSL == 0 {
+            let message = "Connections using insecure transport are prohibited";
+            let mut error = vec![0xFF];
+            error.extend(ER_SECURE_TRANSPORT_REQUIRED.to_le_bytes());
+            error.extend(b"#HY000");
+            error.extend(message.as_bytes());
+            write_packet(&mut client, seq.wrapping_add(1), &error).await?;
+            return Err(ErrorCode::AuthenticateFailure(message));
+        }
+
+        Ok(acceptor.accept(client).await?)
+    }
+
+    async fn upgrade(
+        acceptor: TlsAcceptor,
+        mut client: TcpStream,
+        mut server: TcpStream,
+    ) -> Result<()> {
+        let (seq, mut handshake) = read_packet(&mut server).await?;
+        advertise_ssl(&mut handshake)?;
+        write_packet(&mut client, seq, &handshake).await?;
+
+        let mut client = Self::accept_ssl_request(&acceptor, client).await?;
+
+        // The handshake response, `CLIENT_SSL` is cleared since the server side is plaintext.
+        let (seq, mut response) = read_packet(&mut client).await?;
+        if response.len() >= 4 {
+            let flags = capabilities(&response) & !CLIENT_SSL;
+            response[..4].copy_from_slice(&flags.to_le_bytes());
+        }
+        write_packet(&mut server, seq.wrapping_sub(1), &response).await?;
+
+        loop {
+            let (seq, packet) = read_packet(&mut server).await?;
+            write_packet(&mut client, seq.wrapping_add(1), &packet).await?;
+            match packet.as_slice() {
+                // OK or ERR, the authentication is completed.
+                [0x00, ..] | [0xFF, ..] => break,
+                // Fast authentication succeeded, OK follows without a client packet.
+                [0x01, 0x03] => continue,
+                _ => {
+                    let (seq, packet) = read_packet(&mut client).await?;
+                    write_packet(&mut server, seq.wrapping_sub(1), &packet).await?;
+                }
+            }
+        }
+
+        relay(client, server).await;
+        Ok(())
+    }
+}
+
+fn capabilities(payload: &[u8]) -> u32 {
+    match payload {
+        [a, b, c, d, ..] => u32::from_le_bytes([*a, *b, *c, *d]),
+        _ => 0,
+    }
+}
+
+// Initial handshake v10: protocol version, NUL-terminated server version, connection id (4),
+// auth plugin data part 1 (8), filler (1), then the lower 2 bytes of the capability flags.
+pub fn advertise_ssl(handshake: &mut [u8]) -> Result<()> {
+    let position = match handshake.iter().skip(1).position(|b| *b == 0) {
+        Some(version_len) => 1 + version_len + 1 + 4 + 8 + 1,
+        None => handshake.len(),
+    };
+    if handshake.first() != Some(&10) || position + 2 > handshake.len() {
+        return Err(ErrorCode::BadBytes(
+            "Unexpected MySQL initial handshake packet",
+        ));
+    }
+    handshake[position + 1] |= (CLIENT_SSL >> 8) as u8;
+    Ok(())
+}
+
+/// Read a whole payload, returns the sequence id of its first packet.
+pub async fn read_packet<S: AsyncRead + Unpin>(stream: &mut S) -> Result<(u8, Vec<u8>)> {
+    let mut first_seq = None;
+    let mut payload = vec![];
+    loop {
+        let mut header = [0; 4];
+        stream.read_exact(&mut header).await?;
+        let len = u32::from_le_bytes([header[0], header[1], header[2], 0]) as usize;
+        first_seq.get_or_insert(header[3]);
+
+        let offset = payload.len();
+        payload.resize(offset + len, 0);
+        stream.read_exact(&mut payload[offset..]).await?;
+
+        if len < MAX_PACKET_PAYLOAD {
+            return Ok((first_seq.unwrap_or_default(), payload));
+        }
+    }
+}
+
+/// Write a whole payload from the sequence id, it's split into packets of 16MB, with an empty
+/// packet at last if the length is a multiple of 16MB.
+pub async fn write_packet<S: AsyncWrite + Unpin>(
+    stream: &mut S,
+    mut seq: u8,
+    payload: &[u8],
+) -> Result<()> {
+    let mut offset = 0;
+    loop {
+        let len = (payload.len() - offset).min(MAX_PACKET_PAYLOAD);
+        let header = (len as u32).to_le_bytes();
+        stream
+            .write_all(&[header[0], header[1], header[2], seq])
+            .await?;
+        stream.write_all(&payload[offset..offset + len]).await?;
+
+        offset += len;
+        seq = seq.wrapping_add(1);
+        if len < MAX_PACKET_PAYLOAD {
+            break;
+        }
+    }
+
+    stream.flush().await?;
+    Ok(())
+}
@@ -12,63 +12,75 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use common_base::base::tokio::io::AsyncReadExt;
-use common_base::base::tokio::io::AsyncWriteExt;
+use common_base::base::tokio::io::AsyncRead;
+use common_base::base::tokio::io::AsyncWrite;
 use common_base::base::tokio::net::TcpStream;
 use common_exception::Result;
 use opensrv_mysql::ErrorKind;
+use tokio_rustls::TlsAcceptor;
+
+use crate::servers::mysql::mysql_tls::advertise_ssl;
+use crate::servers::mysql::mysql_tls::read_packet;
+use crate::servers::mysql::mysql_tls::write_packet;
+use crate::servers::mysql::mysql_tls::MySQLTlsUpgrade;
+
+// Handshake, packet from opensrv-mysql.
+const HANDSHAKE: [u8; 69] = [
+    10, 53, 46, 49, 46, 49, 48, 45, 97, 108, 112, 104, 97, 45, 109, 115, 113, 108, 45, 112, 114,
+    111, 120, 121, 0, 8, 0, 0, 0, 59, 88, 44, 112, 111, 95, 107, 125, 0, 0, 66, 33, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 62, 111, 54, 94, 87, 122, 33, 47, 107, 77, 125, 78, 0,
+];
 
 pub struct RejectConnection;
 
 impl RejectConnection {
+    /// Reject the connection after its handshake, over TLS if the handler requires it.
     pub async fn reject_mysql_connection(
         mut stream: TcpStream,
         code: ErrorKind,
         error_message: impl Into<String>,
+        tls: Option<TlsAcceptor>,
     ) -> Result<()> {
-        RejectConnection::send_handshake(&mut stream).await?;
-        RejectConnection::receive_handshake_response(&mut stream).await?;
+        match tls {
+            None => {
+                RejectConnection::send_handshake(&mut stream, false).await?;
+                // Ignore handshake response. Packet[seq = 1]
+                read_packet(&mut stream).await?;
+                // Send error. Packet[seq = 2]
+                RejectConnection::send_error(&mut stream, 2, code, error_message).await
+            }
+            Some(acceptor) => {
+                RejectConnection::send_handshake(&mut stream, true).await?;
+                // SSLRequest. Packet[seq = 1]
+                let mut stream = MySQLTlsUpgrade::accept_ssl_request(&acceptor, stream).await?;
+                // Ignore handshake response. Packet[seq = 2]
+                read_packet(&mut stream).await?;
+                // Send error. Packet[seq = 3]
+                RejectConnection::send_error(&mut stream, 3, code, error_message).await
+            }
+        }
+    }
+
+    async fn send_handshake(stream: &mut TcpStream, ssl: bool) -> Result<()> {
+        // Packet[seq = 0]
+        let mut handshake = HANDSHAKE.to_vec();
+        if ssl {
+            advertise_ssl(&mut handshake)?;
+        }
+        write_packet(stream, 0, &handshake).await
+    }
 
-        // Send error. Packet[seq = 2]
+    async fn send_error<S: AsyncRead + AsyncWrite + Unpin>(
+        stream: &mut S,
+        seq: u8,
+        code: ErrorKind,
+        error_message: impl Into<String>,
+    ) -> Result<()> {
         let mut buffer = vec![0xFF_u8];
         buffer.extend(&(code as u16).to_le_bytes());
         buffer.extend(&vec![b'#']);
         buffer.extend(code.sqlstate());
         buffer.extend(error_message.into().as_bytes());
-
-        let size = buffer.len().to_le_bytes();
-        buffer.splice(0..0, [size[0], size[1], size[2], 2].iter().cloned());
-        stream.write_all(&buffer).await?;
-        stream.flush().await?;
-
-        Ok(())
-    }
-
-    async fn send_handshake(stream: &mut TcpStream) -> Result<()> {
-        // Send handshake, packet from opensrv-mysql. Packet[seq = 0]
-        stream
-            .write_all(&[
-                69, 00, 00, 00, 10, 53, 46, 49, 46, 49, 48, 45, 97, 108, 112, 104, 97, 45, 109,
-                115, 113, 108, 45, 112, 114, 111, 120, 121, 0, 8, 0, 0, 0, 59, 88, 44, 112, 111,
-                95, 107, 125, 0, 0, 66, 33, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 62, 111,
-                54, 94, 87, 122, 33, 47, 107, 77, 125, 78, 0,
-            ])
-            .await?;
-
-        stream.flush().await?;
-
-        Ok(())
-    }
-
-    async fn receive_handshake_response(stream: &mut TcpStream) -> Result<()> {
-        let mut buffer = vec![0; 4];
-        stream.read_exact(&mut buffer).await?;
-
-        // Ignore handshake response. Packet[seq = 1]
-        let len = u32::from_le_bytes([buffer[0], buffer[1], buffer[2], 0]);
-        buffer.resize(len as usize, 0);
-        stream.read_exact(&mut buffer).await?;
-
-        Ok(())
+        write_packet(stream, seq, &buffer).await
     }
 }
@@ -70,49 +70,6 @@ impl<'a, W: std::io::Write> DFQueryResultWriter<'a, W> {
             return Ok(());
         }
 
-        fn convert_field_type(field: &DataField) -> Result<ColumnType> {
-            match remove_nullable(field.data_type()).data_type_id() {
-                TypeID::Int8 => Ok(ColumnType::MYSQL_TYPE_LONG),
-                TypeID::Int16 => Ok(ColumnType::MYSQL_TYPE_LONG),
-                TypeID::Int32 => Ok(ColumnType::MYSQL_TYPE_LONG),
-                TypeID::Int64 => Ok(ColumnType::MYSQL_TYPE_LONG),
-                TypeID::UInt8 => Ok(ColumnType::MYSQL_TYPE_LONG),
-                TypeID::UInt16 => Ok(ColumnType::MYSQL_TYPE_LONG),
-                TypeID::UInt32 => Ok(ColumnType::MYSQL_TYPE_LONG),
-                TypeID::UInt64 => Ok(ColumnType::MYSQL_TYPE_LONG),
-                TypeID::Float32 => Ok(ColumnType::MYSQL_TYPE_FLOAT),
-                TypeID::Float64 => Ok(ColumnType::MYSQL_TYPE_FLOAT),
-                TypeID::String => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
-                TypeID::Boolean => Ok(ColumnType::MYSQL_TYPE_SHORT),
-                TypeID::Date => Ok(ColumnType::MYSQL_TYPE_DATE),
-                TypeID::Timestamp => Ok(ColumnType::MYSQL_TYPE_DATETIME),
-                TypeID::Null => Ok(ColumnType::MYSQL_TYPE_NULL),
-                TypeID::Interval => Ok(ColumnType::MYSQL_TYPE_LONG),
-                TypeID::Array => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
-                TypeID::Struct => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
-                TypeID::Variant => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
-                TypeID::VariantArray => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
-                TypeID::VariantObject => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
-                _ => Err(ErrorCode::UnImplement(format!(
-                    "Unsupported column type:{:?}",
-                    field.data_type()
-                ))),
-            }
-        }
-
-        fn make_column_from_field(field: &DataField) -> Result<Column> {
-            convert_field_type(field).map(|column_type| Column {
-                table: "".to_string(),
-                column: field.name().to_string(),
-                coltype: column_type,
-                colflags: ColumnFlags::empty(),
-            })
-        }
-
-        fn convert_schema(schema: &DataSchemaRef) -> Result<Vec<Column>> {
-            schema.fields().iter().map(make_column_from_field).collect()
-        }
-
         let block = blocks[0].clone();
         let tz = format.timezone;
         match convert_schema(block.schema()) {
@@ -217,3 +174,46 @@ impl<'a, W: std::io::Write> DFQueryResultWriter<'a, W> {
         Ok(())
     }
 }
+
+fn convert_field_type(field: &DataField) -> Result<ColumnType> {
+    match remove_nullable(field.data_type()).data_type_id() {
+        TypeID::Int8 => Ok(ColumnType::MYSQL_TYPE_LONG),
+        TypeID::Int16 => Ok(ColumnType::MYSQL_TYPE_LONG),
+        TypeID::Int32 => Ok(ColumnType::MYSQL_TYPE_LONG),
+        TypeID::Int64 => Ok(ColumnType::MYSQL_TYPE_LONG),
+        TypeID::UInt8 => Ok(ColumnType::MYSQL_TYPE_LONG),
+        TypeID::UInt16 => Ok(ColumnType::MYSQL_TYPE_LONG),
+        TypeID::UInt32 => Ok(ColumnType::MYSQL_TYPE_LONG),
+        TypeID::UInt64 => Ok(ColumnType::MYSQL_TYPE_LONG),
+        TypeID::Float32 => Ok(ColumnType::MYSQL_TYPE_FLOAT),
+        TypeID::Float64 => Ok(ColumnType::MYSQL_TYPE_FLOAT),
+        TypeID::String => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
+        TypeID::Boolean => Ok(ColumnType::MYSQL_TYPE_SHORT),
+        TypeID::Date => Ok(ColumnType::MYSQL_TYPE_DATE),
+        TypeID::Timestamp => Ok(ColumnType::MYSQL_TYPE_DATETIME),
+        TypeID::Null => Ok(ColumnType::MYSQL_TYPE_NULL),
+        TypeID::Interval => Ok(ColumnType::MYSQL_TYPE_LONG),
+        TypeID::Array => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
+        TypeID::Struct => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
+        TypeID::Variant => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
+        TypeID::VariantArray => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
+        TypeID::VariantObject => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
+        _ => Err(ErrorCode::UnImplement(format!(
+            "Unsupported column type:{:?}",
+            field.data_type()
+        ))),
+    }
+}
+
+fn make_column_from_field(field: &DataField) -> Result<Column> {
+    convert_field_type(field).map(|column_type| Column {
+        table: "".to_string(),
+        column: field.name().to_string(),
+        coltype: column_type,
+        colflags: ColumnFlags::empty(),
+    })
+}
+
+pub fn convert_schema(schema: &DataSchemaRef) -> Result<Vec<Column>> {
+    schema.fields().iter().map(make_column_from_field).collect()
+}
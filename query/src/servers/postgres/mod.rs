@@ -0,0 +1,26 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod postgres_handler;
+mod postgres_interactive_worker;
+mod postgres_messages;
+mod postgres_metrics;
+mod postgres_prepared_statement;
+mod postgres_session;
+mod postgres_types;
+
+pub use self::postgres_handler::PostgresHandler;
+
+// The version reported to the clients, which decides the protocol features they use.
+const POSTGRES_VERSION: &str = "14.0";
@@ -0,0 +1,676 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Instant;
+
+use common_ast::parser::token::TokenKind;
+use common_ast::parser::tokenize_sql;
+use common_base::base::tokio::io::AsyncRead;
+use common_base::base::tokio::io::AsyncWrite;
+use common_base::base::TrySpawn;
+use common_datablocks::DataBlock;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_exception::ToErrorCode;
+use common_meta_types::AuthInfo;
+use common_planners::PlanNode;
+use common_tracing::tracing;
+use common_tracing::tracing::Instrument;
+use metrics::histogram;
+use tokio_stream::StreamExt;
+
+use crate::interpreters::Interpreter;
+use crate::interpreters::InterpreterFactory;
+use crate::interpreters::InterpreterQueryLog;
+use crate::interpreters::SelectInterpreterV2;
+use crate::servers::postgres::postgres_messages::*;
+use crate::servers::postgres::postgres_prepared_statement::PostgresPreparedStatement;
+use crate::servers::postgres::postgres_types::encode_text;
+use crate::servers::postgres::postgres_types::field_description;
+use crate::servers::postgres::POSTGRES_VERSION;
+use crate::sessions::QueryContext;
+use crate::sessions::SessionRef;
+use crate::sql::DfParser;
+use crate::sql::PlanParser;
+use crate::users::Credential;
+
+// Parameters sent by the drivers with `SET` which are meaningless to Databend.
+const IGNORED_SET_PARAMETERS: [&str; 9] = [
+    "application_name",
+    "client_encoding",
+    "client_min_messages",
+    "datestyle",
+    "extra_float_digits",
+    "intervalstyle",
+    "search_path",
+    "standard_conforming_strings",
+    "statement_timeout",
+];
+
+/// The result of a query in the text format.
+struct QueryResult {
+    fields: Vec<FieldDescription>,
+    rows: VecDeque<Vec<Option<Vec<u8>>>>,
+    tag: String,
+}
+
+/// A prepared statement bound with parameters by `Bind`, which is executed on the first
+/// `Describe` or `Execute` and the result rows are sent by `Execute`.
+struct Portal {
+    query: String,
+    result: Option<QueryResult>,
+}
+
+pub struct InteractiveWorker {
+    session: SessionRef,
+    client_ip: String,
+    statements: HashMap<String, PostgresPreparedStatement>,
+    portals: HashMap<String, Portal>,
+    // After an error in the extended query protocol, the messages are discarded until `Sync`.
+    skip_until_sync: bool,
+}
+
+impl InteractiveWorker {
+    pub fn create(session: SessionRef, client_ip: String) -> InteractiveWorker {
+        InteractiveWorker {
+            session,
+            client_ip,
+            statements: HashMap::new(),
+            portals: HashMap::new(),
+            skip_until_sync: false,
+        }
+    }
+
+    pub async fn run<S: AsyncRead + AsyncWrite + Unpin>(mut self, stream: S) -> Result<()> {
+        let mut codec = PostgresCodec::create(stream);
+        if self.startup(&mut codec).await? {
+            self.serve(&mut codec).await?;
+        }
+        Ok(())
+    }
+
+    /// Reject a connection before the startup, e.g. there are too many sessions.
+    pub async fn reject<S: AsyncRead + AsyncWrite + Unpin>(
+        stream: S,
+        error: ErrorCode,
+    ) -> Result<()> {
+        let mut codec = PostgresCodec::create(stream);
+        if Self::read_startup_parameters(&mut codec).await?.is_some() {
+            codec.write_message(error_response(&error)).await?;
+            codec.flush().await?;
+        }
+        Ok(())
+    }
+
+    async fn read_startup_parameters<S: AsyncRead + AsyncWrite + Unpin>(
+        codec: &mut PostgresCodec<S>,
+    ) -> Result<Option<HashMap<String, String>>> {
+        loop {
+            match codec.read_startup_message().await? {
+                FrontendMessage::SSLRequest | FrontendMessage::GSSENCRequest => {
+                    codec.refuse_encryption().await?
+                }
+                FrontendMessage::Startup { parameters } => return Ok(Some(parameters)),
+                // Running queries can't be cancelled by the key of `BackendKeyData` yet.
+                _ => return Ok(None),
+            }
+        }
+    }
+
+    async fn startup<S: AsyncRead + AsyncWrite + Unpin>(
+        &mut self,
+        codec: &mut PostgresCodec<S>,
+    ) -> Result<bool> {
+        let parameters = match Self::read_startup_parameters(codec).await? {
+            Some(parameters) => parameters,
+            None => return Ok(false),
+        };
+
+        let user_name = parameters.get("user").cloned().unwrap_or_default();
+        if let Err(failure) = self.authenticate(codec, &user_name).await {
+            tracing::error!(
+                "PostgreSQL handler authenticate failed, \
+                    user_name: {}, \
+                    client_address: {}, \
+                    failure_cause: {}",
+                user_name,
+                self.client_ip,
+                failure
+            );
+            codec.write_message(error_response(&failure)).await?;
+            codec.flush().await?;
+            return Ok(false);
+        }
+        codec
+            .write_message(BackendMessage::AuthenticationOk)
+            .await?;
+
+        if let Some(database) = parameters.get("database").filter(|v| !v.is_empty()) {
+            if let Err(error) = self.do_init(database).await {
+                codec.write_message(error_response(&error)).await?;
+                codec.flush().await?;
+                return Ok(false);
+            }
+        }
+
+        let application_name = parameters.get("application_name").cloned();
+        for (name, value) in self.parameter_status(application_name)? {
+            let message = BackendMessage::ParameterStatus {
+                name: name.to_string(),
+                value,
+            };
+            codec.write_message(message).await?;
+        }
+        codec
+            .write_message(BackendMessage::BackendKeyData {
+                process_id: rand::random(),
+                secret_key: rand::random(),
+            })
+            .await?;
+        codec.write_message(BackendMessage::ReadyForQuery).await?;
+        codec.flush().await?;
+        Ok(true)
+    }
+
+    async fn authenticate<S: AsyncRead + AsyncWrite + Unpin>(
+        &mut self,
+        codec: &mut PostgresCodec<S>,
+        user_name: &str,
+    ) -> Result<()> {
+        if user_name.is_empty() {
+            return Err(ErrorCode::AuthenticateFailure(
+                "no PostgreSQL user name specified in startup packet",
+            ));
+        }
+
+        let ctx = self.session.create_query_context().await?;
+        let user_info = ctx
+            .get_user_manager()
+            .get_user_with_client_ip(&ctx.get_tenant(), user_name, &self.client_ip)
+            .await?;
+
+        // The password is sent in cleartext, only ask for it if the user has one.
+        let password = match user_info.auth_info {
+            AuthInfo::None => None,
+            _ => {
+                codec
+                    .write_message(BackendMessage::AuthenticationCleartextPassword)
+                    .await?;
+                codec.flush().await?;
+                match codec.read_message().await? {
+                    Some(FrontendMessage::Password(password)) => Some(password),
+                    _ => return Err(ErrorCode::AuthenticateFailure("password required")),
+                }
+            }
+        };
+
+        let credential = Credential::Password {
+            name: user_name.to_string(),
+            password,
+            hostname: Some(self.client_ip.clone()),
+        };
+        let auth_manager = self.session.get_session_manager().get_auth_manager();
+        let (_, user_info) = auth_manager.auth(&credential).await?;
+        self.session.set_current_user(user_info);
+        Ok(())
+    }
+
+    fn parameter_status(
+        &self,
+        application_name: Option<String>,
+    ) -> Result<Vec<(&'static str, String)>> {
+        let timezone = self.session.get_settings().get_timezone()?;
+        Ok(vec![
+            ("server_version", POSTGRES_VERSION.to_string()),
+            ("server_encoding", "UTF8".to_string()),
+            ("client_encoding", "UTF8".to_string()),
+            ("DateStyle", "ISO, MDY".to_string()),
+            ("IntervalStyle", "postgres".to_string()),
+            ("TimeZone", String::from_utf8_lossy(&timezone).to_string()),
+            ("integer_datetimes", "on".to_string()),
+            ("standard_conforming_strings", "on".to_string()),
+            ("application_name", application_name.unwrap_or_default()),
+        ])
+    }
+
+    async fn serve<S: AsyncRead + AsyncWrite + Unpin>(
+        &mut self,
+        codec: &mut PostgresCodec<S>,
+    ) -> Result<()> {
+        while let Some(message) = codec.read_message().await? {
+            if self.session.is_aborting() {
+                let error = ErrorCode::AbortedSession(
+                    "Aborting this connection. because we are try aborting server.",
+                );
+                codec.write_message(error_response(&error)).await?;
+                codec.flush().await?;
+                return Err(error);
+            }
+
+            match message {
+                FrontendMessage::Query(query) => {
+                    let instant = Instant::now();
+                    for response in self.on_query(&query).await {
+                        codec.write_message(response).await?;
+                    }
+                    codec.write_message(BackendMessage::ReadyForQuery).await?;
+                    codec.flush().await?;
+                    histogram!(
+                        super::postgres_metrics::METRIC_POSTGRES_PROCESSOR_REQUEST_DURATION,
+                        instant.elapsed()
+                    );
+                }
+                FrontendMessage::Sync => {
+                    self.skip_until_sync = false;
+                    codec.write_message(BackendMessage::ReadyForQuery).await?;
+                    codec.flush().await?;
+                }
+                FrontendMessage::Flush => codec.flush().await?,
+                FrontendMessage::Terminate => return Ok(()),
+                _ if self.skip_until_sync => {}
+                message => match self.on_extended_query(message).await {
+                    Ok(responses) => {
+                        for response in responses {
+                            codec.write_message(response).await?;
+                        }
+                    }
+                    Err(error) => {
+                        codec.write_message(error_response(&error)).await?;
+                        self.skip_until_sync = true;
+                    }
+                },
+            }
+        }
+        Ok(())
+    }
+
+    // The simple query protocol, the query may contain several statements.
+    async fn on_query(&mut self, query: &str) -> Vec<BackendMessage> {
+        let statements = split_statements(query);
+        if statements.is_empty() {
+            return vec![BackendMessage::EmptyQueryResponse];
+        }
+
+        let mut responses = vec![];
+        for statement in statements {
+            match Self::do_query(&self.session, statement).await {
+                Ok(result) => {
+                    if !result.fields.is_empty() {
+                        responses.push(BackendMessage::RowDescription(result.fields));
+                    }
+                    responses.extend(result.rows.into_iter().map(BackendMessage::DataRow));
+                    responses.push(BackendMessage::CommandComplete(result.tag));
+                }
+                Err(error) => {
+                    responses.push(error_response(
+                        &error.add_message_back(format!("(while in query {})", statement)),
+                    ));
+                    break;
+                }
+            }
+        }
+        responses
+    }
+
+    // The extended query protocol, `Sync`, `Flush` and `Terminate` are handled by the caller.
+    async fn on_extended_query(&mut self, message: FrontendMessage) -> Result<Vec<BackendMessage>> {
+        match message {
+            FrontendMessage::Parse {
+                name,
+                query,
+                param_types,
+            } => {
+                if !name.is_empty() && self.statements.contains_key(&name) {
+                    return Err(ErrorCode::BadArguments(format!(
+                        "Prepared statement \"{}\" already exists",
+                        name
+                    )));
+                }
+                let statement = PostgresPreparedStatement::try_create(&query, param_types)?;
+                self.statements.insert(name, statement);
+                Ok(vec![BackendMessage::ParseComplete])
+            }
+            FrontendMessage::Bind {
+                portal,
+                statement,
+                param_formats,
+                params,
+                result_formats,
+            } => {
+                if result_formats.iter().any(|format| *format != 0) {
+                    return Err(ErrorCode::UnImplement(
+                        "Binary format of result columns is not supported",
+                    ));
+                }
+                let query = self
+                    .get_statement(&statement)?
+                    .bind(&param_formats, &params)?;
+                self.portals.insert(portal, Portal {
+                    query,
+                    result: None,
+                });
+                Ok(vec![BackendMessage::BindComplete])
+            }
+            FrontendMessage::Describe {
+                target: Target::Statement,
+                name,
+            } => {
+                let statement = self.get_statement(&name)?;
+                let param_types = statement.param_types();
+                let query = statement.null_bound_query();
+                let row_description = match self.describe_fields(&query).await {
+                    Some(fields) if !fields.is_empty() => BackendMessage::RowDescription(fields),
+                    _ => BackendMessage::NoData,
+                };
+                Ok(vec![
+                    BackendMessage::ParameterDescription(param_types),
+                    row_description,
+                ])
+            }
+            FrontendMessage::Describe {
+                target: Target::Portal,
+                name,
+            } => {
+                let result = self.get_portal_result(&name).await?;
+                if result.fields.is_empty() {
+                    Ok(vec![BackendMessage::NoData])
+                } else {
+                    Ok(vec![BackendMessage::RowDescription(result.fields.clone())])
+                }
+            }
+            FrontendMessage::Execute { portal, max_rows } => {
+                let instant = Instant::now();
+                let result = self.get_portal_result(&portal).await?;
+                let num_rows = match max_rows {
+                    max_rows if max_rows > 0 => result.rows.len().min(max_rows as usize),
+                    _ => result.rows.len(),
+                };
+                let mut responses = result
+                    .rows
+                    .drain(..num_rows)
+                    .map(BackendMessage::DataRow)
+                    .collect::<Vec<_>>();
+                if result.rows.is_empty() {
+                    responses.push(BackendMessage::CommandComplete(result.tag.clone()));
+                } else {
+                    responses.push(BackendMessage::PortalSuspended);
+                }
+                histogram!(
+                    super::postgres_metrics::METRIC_POSTGRES_PROCESSOR_REQUEST_DURATION,
+                    instant.elapsed()
+                );
+                Ok(responses)
+            }
+            FrontendMessage::Close { target, name } => {
+                match target {
+                    Target::Statement => {
+                        self.statements.remove(&name);
+                    }
+                    Target::Portal => {
+                        self.portals.remove(&name);
+                    }
+                }
+                Ok(vec![BackendMessage::CloseComplete])
+            }
+            message => Err(ErrorCode::BadBytes(format!(
+                "Unexpected message: {:?}",
+                message
+            ))),
+        }
+    }
+
+    fn get_statement(&self, name: &str) -> Result<&PostgresPreparedStatement> {
+        self.statements.get(name).ok_or_else(|| {
+            ErrorCode::BadArguments(format!("Prepared statement \"{}\" does not exist", name))
+        })
+    }
+
+    async fn get_portal_result(&mut self, name: &str) -> Result<&mut QueryResult> {
+        let portal = self.portals.get_mut(name).ok_or_else(|| {
+            ErrorCode::BadArguments(format!("Portal \"{}\" does not exist", name))
+        })?;
+        let result = match portal.result.take() {
+            Some(result) => result,
+            None => Self::do_query(&self.session, &portal.query).await?,
+        };
+        Ok(portal.result.insert(result))
+    }
+
+    // Describe the result columns of a prepared `SELECT` statement by planning it with the
+    // placeholders bound to `NULL`, `None` if the statement cannot be planned that way.
+    async fn describe_fields(&self, query: &str) -> Option<Vec<FieldDescription>> {
+        let plan = match self.session.create_query_context().await {
+            Ok(context) => PlanParser::parse(context, query).await,
+            Err(error) => Err(error),
+        };
+        match plan {
+            Ok(plan @ PlanNode::Select(_)) => Some(
+                plan.schema()
+                    .fields()
+                    .iter()
+                    .map(field_description)
+                    .collect(),
+            ),
+            _ => None,
+        }
+    }
+
+    async fn do_init(&mut self, database_name: &str) -> Result<()> {
+        let context = self.session.create_query_context().await?;
+        context
+            .set_current_database(database_name.to_string())
+            .await
+    }
+
+    #[tracing::instrument(level = "debug", skip(session))]
+    async fn do_query(session: &SessionRef, query: &str) -> Result<QueryResult> {
+        if let Some(tag) = ignored_command_tag(query) {
+            tracing::info!("Ignored query: {}", query);
+            return Ok(QueryResult {
+                fields: vec![],
+                rows: VecDeque::new(),
+                tag,
+            });
+        }
+
+        if let Some(error) = unsupported_command(query) {
+            return Err(error);
+        }
+
+        tracing::info!("Normal query: {}", query);
+        let context = session.create_query_context().await?;
+        context.attach_query_str(query);
+
+        let stmts_hints = DfParser::parse_sql(query, context.get_current_session().get_type());
+        let stmts = stmts_hints.as_ref().ok().map(|(stmts, _)| stmts.as_slice());
+        let interpreter: Arc<dyn Interpreter> =
+            if SelectInterpreterV2::check(&context, stmts, query)? {
+                // New planner is enabled, and the statement is ensured to be `SELECT` statement.
+                SelectInterpreterV2::try_create(context.clone(), query)?
+            } else {
+                // The statement can't be parsed by any planner.
+                stmts_hints?;
+
+                let plan = match PlanParser::parse(context.clone(), query).await {
+                    Ok(plan) => plan,
+                    Err(error) => {
+                        InterpreterQueryLog::fail_to_start(context, error.clone()).await;
+                        return Err(error);
+                    }
+                };
+                tracing::debug!("Get logic plan:\n{:?}", plan);
+                InterpreterFactory::get(context.clone(), plan)?
+            };
+
+        let schema = interpreter.schema();
+        let blocks = Self::exec_query(interpreter, &context).await?;
+        let schema = match blocks.first() {
+            Some(block) => block.schema().clone(),
+            None => schema,
+        };
+
+        let format = context.get_format_settings()?;
+        let mut rows = VecDeque::new();
+        for block in &blocks {
+            for row_index in 0..block.num_rows() {
+                let row = schema
+                    .fields()
+                    .iter()
+                    .enumerate()
+                    .map(|(col_index, field)| {
+                        let value = block.column(col_index).get_checked(row_index)?;
+                        encode_text(field.data_type(), value, &format)
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                rows.push_back(row);
+            }
+        }
+
+        let fields = schema
+            .fields()
+            .iter()
+            .map(field_description)
+            .collect::<Vec<_>>();
+        let tag = if fields.is_empty() {
+            command_tag(query, &context)
+        } else {
+            format!("SELECT {}", rows.len())
+        };
+        Ok(QueryResult { fields, rows, tag })
+    }
+
+    #[tracing::instrument(level = "debug", skip(interpreter, context))]
+    async fn exec_query(
+        interpreter: Arc<dyn Interpreter>,
+        context: &Arc<QueryContext>,
+    ) -> Result<Vec<DataBlock>> {
+        let instant = Instant::now();
+
+        let query_result = context.try_spawn(
+            async move {
+                // Write start query log.
+                let _ = interpreter
+                    .start()
+                    .await
+                    .map_err(|e| tracing::error!("interpreter.start.error: {:?}", e));
+                let data_stream = interpreter.execute(None).await?;
+                histogram!(
+                    super::postgres_metrics::METRIC_INTERPRETER_USEDTIME,
+                    instant.elapsed()
+                );
+
+                let collector = data_stream.collect::<Result<Vec<DataBlock>>>();
+                let query_result = collector.await?;
+                // Write finish query log.
+                let _ = interpreter
+                    .finish()
+                    .await
+                    .map_err(|e| tracing::error!("interpreter.finish.error: {:?}", e));
+
+                Ok::<Vec<DataBlock>, ErrorCode>(query_result)
+            }
+            .in_current_span(),
+        )?;
+
+        query_result
+            .await
+            .map_err_to_code(ErrorCode::TokioError, || {
+                "Cannot join handle from context's runtime"
+            })?
+    }
+}
+
+fn error_response(error: &ErrorCode) -> BackendMessage {
+    BackendMessage::ErrorResponse {
+        code: error_sqlstate(error).to_string(),
+        message: error.message(),
+    }
+}
+
+// Split the query of the simple query protocol into statements by `;`. The query is executed
+// as a single statement if it cannot be tokenized, and the error is reported by the parser.
+fn split_statements(query: &str) -> Vec<&str> {
+    let tokens = match tokenize_sql(query) {
+        Ok(tokens) => tokens,
+        Err(_) => return vec![query],
+    };
+
+    let mut statements = vec![];
+    let mut start = None;
+    for token in tokens {
+        match token.kind {
+            TokenKind::SemiColon | TokenKind::EOI => {
+                if let Some(start) = start.take() {
+                    statements.push(&query[start..token.span.start]);
+                }
+            }
+            _ => {
+                start.get_or_insert(token.span.start);
+            }
+        }
+    }
+    statements
+}
+
+fn leading_words(query: &str, num: usize) -> Vec<String> {
+    query
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .filter(|word| !word.is_empty())
+        .take(num)
+        .map(|word| word.to_ascii_uppercase())
+        .collect()
+}
+
+// The transaction and session commands sent by the drivers and tools, which Databend doesn't
+// support, are answered with their command tags without being executed.
+fn ignored_command_tag(query: &str) -> Option<String> {
+    let words = leading_words(query, 3);
+    match words.iter().map(|word| word.as_str()).collect::<Vec<_>>()[..] {
+        ["BEGIN", ..] | ["START", "TRANSACTION", ..] => Some("BEGIN".to_string()),
+        ["COMMIT", ..] | ["END", ..] => Some("COMMIT".to_string()),
+        ["DISCARD", "ALL", ..] => Some("DISCARD ALL".to_string()),
+        ["SET", "SESSION" | "LOCAL", name, ..] if is_ignored_parameter(name) => {
+            Some("SET".to_string())
+        }
+        ["SET", name, ..] if is_ignored_parameter(name) => Some("SET".to_string()),
+        _ => None,
+    }
+}
+
+// The statements are committed once they are executed, a rollback can't be done, so it's
+// rejected rather than answered as if the changes were discarded.
+fn unsupported_command(query: &str) -> Option<ErrorCode> {
+    match leading_words(query, 1).first().map(|word| word.as_str()) {
+        Some("ROLLBACK" | "ABORT") => Some(ErrorCode::UnImplement(
+            "ROLLBACK is not supported, the statements are committed once they are executed",
+        )),
+        _ => None,
+    }
+}
+
+fn is_ignored_parameter(name: &str) -> bool {
+    IGNORED_SET_PARAMETERS.contains(&name.to_ascii_lowercase().as_str())
+}
+
+// The command tag of `CommandComplete` for a statement without result columns.
+fn command_tag(query: &str, context: &Arc<QueryContext>) -> String {
+    match leading_words(query, 1).first().map(|word| word.as_str()) {
+        None => "OK".to_string(),
+        Some("INSERT") => format!("INSERT 0 {}", context.get_write_progress_value().rows),
+        Some(word) => word.to_string(),
+    }
+}
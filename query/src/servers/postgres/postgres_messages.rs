@@ -0,0 +1,425 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use bytes::Buf;
+use bytes::BufMut;
+use bytes::BytesMut;
+use common_base::base::tokio::io::AsyncRead;
+use common_base::base::tokio::io::AsyncReadExt;
+use common_base::base::tokio::io::AsyncWrite;
+use common_base::base::tokio::io::AsyncWriteExt;
+use common_base::base::tokio::io::BufStream;
+use common_exception::ErrorCode;
+use common_exception::Result;
+
+// Codes of the special messages sent instead of a `StartupMessage`.
+const PROTOCOL_VERSION_3: i32 = 196608;
+const SSL_REQUEST_CODE: i32 = 80877103;
+const GSSENC_REQUEST_CODE: i32 = 80877104;
+const CANCEL_REQUEST_CODE: i32 = 80877102;
+
+// Protect the server from a broken or malicious length prefix.
+const MAX_STARTUP_MESSAGE_LENGTH: usize = 10 * 1024;
+const MAX_MESSAGE_LENGTH: usize = 1024 * 1024 * 1024;
+
+/// Messages sent by the client, see https://www.postgresql.org/docs/current/protocol-message-formats.html
+#[derive(Debug)]
+pub enum FrontendMessage {
+    SSLRequest,
+    GSSENCRequest,
+    CancelRequest,
+    Startup {
+        parameters: HashMap<String, String>,
+    },
+    Password(Vec<u8>),
+    Query(String),
+    Parse {
+        name: String,
+        query: String,
+        param_types: Vec<i32>,
+    },
+    Bind {
+        portal: String,
+        statement: String,
+        param_formats: Vec<i16>,
+        params: Vec<Option<Vec<u8>>>,
+        result_formats: Vec<i16>,
+    },
+    Describe {
+        target: Target,
+        name: String,
+    },
+    Execute {
+        portal: String,
+        max_rows: i32,
+    },
+    Close {
+        target: Target,
+        name: String,
+    },
+    Sync,
+    Flush,
+    Terminate,
+}
+
+/// The object described or closed by `Describe` and `Close`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Statement,
+    Portal,
+}
+
+/// Description of a result column in `RowDescription`.
+#[derive(Debug, Clone)]
+pub struct FieldDescription {
+    pub name: String,
+    pub type_oid: i32,
+    pub type_size: i16,
+}
+
+/// Messages sent by the server. Only the text format is used for result columns.
+#[derive(Debug)]
+pub enum BackendMessage {
+    AuthenticationOk,
+    AuthenticationCleartextPassword,
+    ParameterStatus { name: String, value: String },
+    BackendKeyData { process_id: i32, secret_key: i32 },
+    ReadyForQuery,
+    ParameterDescription(Vec<i32>),
+    RowDescription(Vec<FieldDescription>),
+    DataRow(Vec<Option<Vec<u8>>>),
+    CommandComplete(String),
+    EmptyQueryResponse,
+    ParseComplete,
+    BindComplete,
+    CloseComplete,
+    NoData,
+    PortalSuspended,
+    ErrorResponse { code: String, message: String },
+}
+
+pub struct PostgresCodec<S: AsyncRead + AsyncWrite + Unpin> {
+    stream: BufStream<S>,
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> PostgresCodec<S> {
+    pub fn create(stream: S) -> PostgresCodec<S> {
+        PostgresCodec {
+            stream: BufStream::new(stream),
+        }
+    }
+
+    /// Read a message of the startup phase, which has no message type byte.
+    pub async fn read_startup_message(&mut self) -> Result<FrontendMessage> {
+        let len = self.stream.read_i32().await? as usize;
+        if !(8..=MAX_STARTUP_MESSAGE_LENGTH).contains(&len) {
+            return Err(ErrorCode::BadBytes(format!(
+                "Invalid length of startup message: {}",
+                len
+            )));
+        }
+
+        let mut body = self.read_body(len - 4).await?;
+        match body.get_i32() {
+            SSL_REQUEST_CODE => Ok(FrontendMessage::SSLRequest),
+            GSSENC_REQUEST_CODE => Ok(FrontendMessage::GSSENCRequest),
+            CANCEL_REQUEST_CODE => Ok(FrontendMessage::CancelRequest),
+            PROTOCOL_VERSION_3 => {
+                let mut parameters = HashMap::new();
+                loop {
+                    let name = get_cstring(&mut body)?;
+                    if name.is_empty() {
+                        break;
+                    }
+                    parameters.insert(name, get_cstring(&mut body)?);
+                }
+                Ok(FrontendMessage::Startup { parameters })
+            }
+            code => Err(ErrorCode::UnImplement(format!(
+                "Unsupported frontend protocol {}.{}",
+                code >> 16,
+                code & 0xffff
+            ))),
+        }
+    }
+
+    /// Read a message after the startup phase, returns `None` if the client closed the connection.
+    pub async fn read_message(&mut self) -> Result<Option<FrontendMessage>> {
+        let tag = match self.stream.read_u8().await {
+            Ok(tag) => tag,
+            Err(error) if error.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(error) => return Err(error.into()),
+        };
+        let len = self.stream.read_i32().await? as usize;
+        if !(4..=MAX_MESSAGE_LENGTH).contains(&len) {
+            return Err(ErrorCode::BadBytes(format!(
+                "Invalid length of message '{}': {}",
+                tag as char, len
+            )));
+        }
+
+        let mut body = self.read_body(len - 4).await?;
+        let message = match tag {
+            b'p' => FrontendMessage::Password(get_cstring(&mut body)?.into_bytes()),
+            b'Q' => FrontendMessage::Query(get_cstring(&mut body)?),
+            b'P' => {
+                let name = get_cstring(&mut body)?;
+                let query = get_cstring(&mut body)?;
+                let num_types = get_i16(&mut body)?;
+                let param_types = (0..num_types)
+                    .map(|_| get_i32(&mut body))
+                    .collect::<Result<_>>()?;
+                FrontendMessage::Parse {
+                    name,
+                    query,
+                    param_types,
+                }
+            }
+            b'B' => {
+                let portal = get_cstring(&mut body)?;
+                let statement = get_cstring(&mut body)?;
+                let num_formats = get_i16(&mut body)?;
+                let param_formats = (0..num_formats)
+                    .map(|_| get_i16(&mut body))
+                    .collect::<Result<_>>()?;
+                let num_params = get_i16(&mut body)?;
+                let params = (0..num_params)
+                    .map(|_| match get_i32(&mut body)? {
+                        len if len < 0 => Ok(None),
+                        len => get_bytes(&mut body, len as usize).map(Some),
+                    })
+                    .collect::<Result<_>>()?;
+                let num_formats = get_i16(&mut body)?;
+                let result_formats = (0..num_formats)
+                    .map(|_| get_i16(&mut body))
+                    .collect::<Result<_>>()?;
+                FrontendMessage::Bind {
+                    portal,
+                    statement,
+                    param_formats,
+                    params,
+                    result_formats,
+                }
+            }
+            b'D' => FrontendMessage::Describe {
+                target: get_target(&mut body)?,
+                name: get_cstring(&mut body)?,
+            },
+            b'E' => FrontendMessage::Execute {
+                portal: get_cstring(&mut body)?,
+                max_rows: get_i32(&mut body)?,
+            },
+            b'C' => FrontendMessage::Close {
+                target: get_target(&mut body)?,
+                name: get_cstring(&mut body)?,
+            },
+            b'S' => FrontendMessage::Sync,
+            b'H' => FrontendMessage::Flush,
+            b'X' => FrontendMessage::Terminate,
+            tag => {
+                return Err(ErrorCode::UnImplement(format!(
+                    "Unsupported frontend message type '{}'",
+                    tag as char
+                )))
+            }
+        };
+        Ok(Some(message))
+    }
+
+    /// Answer `SSLRequest` and `GSSENCRequest` with `N`, the client continues in plaintext.
+    pub async fn refuse_encryption(&mut self) -> Result<()> {
+        self.stream.write_u8(b'N').await?;
+        self.flush().await
+    }
+
+    pub async fn write_message(&mut self, message: BackendMessage) -> Result<()> {
+        let mut body = BytesMut::new();
+        let tag = match message {
+            BackendMessage::AuthenticationOk => {
+                body.put_i32(0);
+                b'R'
+            }
+            BackendMessage::AuthenticationCleartextPassword => {
+                body.put_i32(3);
+                b'R'
+            }
+            BackendMessage::ParameterStatus { name, value } => {
+                put_cstring(&mut body, &name);
+                put_cstring(&mut body, &value);
+                b'S'
+            }
+            BackendMessage::BackendKeyData {
+                process_id,
+                secret_key,
+            } => {
+                body.put_i32(process_id);
+                body.put_i32(secret_key);
+                b'K'
+            }
+            BackendMessage::ReadyForQuery => {
+                // Always idle, transactions are not supported.
+                body.put_u8(b'I');
+                b'Z'
+            }
+            BackendMessage::ParameterDescription(types) => {
+                body.put_i16(types.len() as i16);
+                for type_oid in types {
+                    body.put_i32(type_oid);
+                }
+                b't'
+            }
+            BackendMessage::RowDescription(fields) => {
+                body.put_i16(fields.len() as i16);
+                for field in fields {
+                    put_cstring(&mut body, &field.name);
+                    // table oid and column attribute number
+                    body.put_i32(0);
+                    body.put_i16(0);
+                    body.put_i32(field.type_oid);
+                    body.put_i16(field.type_size);
+                    // type modifier and text format
+                    body.put_i32(-1);
+                    body.put_i16(0);
+                }
+                b'T'
+            }
+            BackendMessage::DataRow(values) => {
+                body.put_i16(values.len() as i16);
+                for value in values {
+                    match value {
+                        None => body.put_i32(-1),
+                        Some(value) => {
+                            body.put_i32(value.len() as i32);
+                            body.put_slice(&value);
+                        }
+                    }
+                }
+                b'D'
+            }
+            BackendMessage::CommandComplete(tag) => {
+                put_cstring(&mut body, &tag);
+                b'C'
+            }
+            BackendMessage::EmptyQueryResponse => b'I',
+            BackendMessage::ParseComplete => b'1',
+            BackendMessage::BindComplete => b'2',
+            BackendMessage::CloseComplete => b'3',
+            BackendMessage::NoData => b'n',
+            BackendMessage::PortalSuspended => b's',
+            BackendMessage::ErrorResponse { code, message } => {
+                for (field, value) in [
+                    (b'S', "ERROR"),
+                    (b'V', "ERROR"),
+                    (b'C', code.as_str()),
+                    (b'M', message.as_str()),
+                ] {
+                    body.put_u8(field);
+                    put_cstring(&mut body, value);
+                }
+                body.put_u8(0);
+                b'E'
+            }
+        };
+
+        self.stream.write_u8(tag).await?;
+        self.stream.write_i32(body.len() as i32 + 4).await?;
+        self.stream.write_all(&body).await?;
+        Ok(())
+    }
+
+    pub async fn flush(&mut self) -> Result<()> {
+        self.stream.flush().await?;
+        Ok(())
+    }
+
+    async fn read_body(&mut self, len: usize) -> Result<BytesMut> {
+        let mut body = BytesMut::with_capacity(len);
+        body.resize(len, 0);
+        self.stream.read_exact(&mut body).await?;
+        Ok(body)
+    }
+}
+
+/// Map an error to the SQLSTATE code of `ErrorResponse`.
+pub fn error_sqlstate(error: &ErrorCode) -> &'static str {
+    match error.code() {
+        code if code == ErrorCode::SyntaxExceptionCode() => "42601",
+        code if code == ErrorCode::UnknownDatabaseCode() => "3D000",
+        code if code == ErrorCode::UnknownTableCode() => "42P01",
+        code if code == ErrorCode::UnknownColumnCode() => "42703",
+        code if code == ErrorCode::UnknownUserCode() => "28000",
+        code if code == ErrorCode::AuthenticateFailureCode() => "28P01",
+        code if code == ErrorCode::PermissionDeniedCode() => "42501",
+        code if code == ErrorCode::TooManyUserConnectionsCode() => "53300",
+        code if code == ErrorCode::UnImplementCode() => "0A000",
+        code if code == ErrorCode::AbortedQueryCode() => "57014",
+        code if code == ErrorCode::AbortedSessionCode() => "57P01",
+        _ => "XX000",
+    }
+}
+
+fn get_cstring(buf: &mut BytesMut) -> Result<String> {
+    match buf.iter().position(|b| *b == 0) {
+        None => Err(ErrorCode::BadBytes(
+            "Invalid message: missing string terminator",
+        )),
+        Some(pos) => {
+            let bytes = buf.split_to(pos + 1);
+            String::from_utf8(bytes[..pos].to_vec())
+                .map_err(|_| ErrorCode::BadBytes("Invalid message: invalid UTF-8 string"))
+        }
+    }
+}
+
+fn get_bytes(buf: &mut BytesMut, len: usize) -> Result<Vec<u8>> {
+    if buf.remaining() < len {
+        return Err(ErrorCode::BadBytes("Invalid message: unexpected end"));
+    }
+    Ok(buf.split_to(len).to_vec())
+}
+
+fn get_i16(buf: &mut BytesMut) -> Result<i16> {
+    if buf.remaining() < 2 {
+        return Err(ErrorCode::BadBytes("Invalid message: unexpected end"));
+    }
+    Ok(buf.get_i16())
+}
+
+fn get_i32(buf: &mut BytesMut) -> Result<i32> {
+    if buf.remaining() < 4 {
+        return Err(ErrorCode::BadBytes("Invalid message: unexpected end"));
+    }
+    Ok(buf.get_i32())
+}
+
+fn get_target(buf: &mut BytesMut) -> Result<Target> {
+    if !buf.has_remaining() {
+        return Err(ErrorCode::BadBytes("Invalid message: unexpected end"));
+    }
+    match buf.get_u8() {
+        b'S' => Ok(Target::Statement),
+        b'P' => Ok(Target::Portal),
+        target => Err(ErrorCode::BadBytes(format!(
+            "Invalid message: unknown target '{}'",
+            target as char
+        ))),
+    }
+}
+
+fn put_cstring(buf: &mut BytesMut, value: &str) {
+    buf.put_slice(value.as_bytes());
+    buf.put_u8(0);
+}
@@ -0,0 +1,206 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::ops::Range;
+
+use common_ast::parser::token::TokenKind;
+use common_ast::parser::tokenize_sql;
+use common_exception::ErrorCode;
+use common_exception::Result;
+
+use crate::servers::postgres::postgres_types::*;
+use crate::servers::sql_literal::number_literal;
+use crate::servers::sql_literal::quote_string;
+
+/// A prepared statement of a PostgreSQL connection, created by the `Parse` message.
+///
+/// The values sent by `Bind` are bound by replacing the placeholders `$n` with literals,
+/// and the bound query is planned and executed like a simple query.
+pub struct PostgresPreparedStatement {
+    query: String,
+    // spans of the placeholders in the query, with the zero-based index of the parameter
+    placeholders: Vec<(Range<usize>, usize)>,
+    // type oids of the parameters, `0` if unspecified by the client
+    param_types: Vec<i32>,
+}
+
+impl PostgresPreparedStatement {
+    pub fn try_create(query: &str, mut param_types: Vec<i32>) -> Result<PostgresPreparedStatement> {
+        let mut placeholders = vec![];
+        for token in tokenize_sql(query)? {
+            if token.kind == TokenKind::NumberedPlaceholder {
+                let index = match token.text()[1..].parse::<usize>() {
+                    Ok(index) if index > 0 => index - 1,
+                    _ => {
+                        return Err(ErrorCode::SyntaxException(format!(
+                            "Invalid parameter placeholder {}",
+                            token.text()
+                        )))
+                    }
+                };
+                placeholders.push((token.span, index));
+            }
+        }
+
+        let num_params = placeholders
+            .iter()
+            .map(|(_, index)| index + 1)
+            .max()
+            .unwrap_or(0);
+        if param_types.len() < num_params {
+            param_types.resize(num_params, 0);
+        }
+        Ok(PostgresPreparedStatement {
+            query: query.to_string(),
+            placeholders,
+            param_types,
+        })
+    }
+
+    /// The query with all the placeholders bound to `NULL`, which is used to describe the
+    /// result columns of the statement.
+    pub fn null_bound_query(&self) -> String {
+        self.replace_placeholders(&vec!["NULL".to_string(); self.param_types.len()])
+    }
+
+    /// Type oids of the parameters for `ParameterDescription`. The parameters of unspecified
+    /// types are described as `text`, the clients send them as strings.
+    pub fn param_types(&self) -> Vec<i32> {
+        self.param_types
+            .iter()
+            .map(|oid| if *oid == 0 { TEXT_OID } else { *oid })
+            .collect()
+    }
+
+    /// Bind the parameters of `Bind` into the query, `formats` are the format codes of the
+    /// parameters: `0` for text and `1` for binary.
+    pub fn bind(&self, formats: &[i16], params: &[Option<Vec<u8>>]) -> Result<String> {
+        if params.len() != self.param_types.len() {
+            return Err(ErrorCode::BadArguments(format!(
+                "Prepared statement expects {} parameters, but got {}",
+                self.param_types.len(),
+                params.len()
+            )));
+        }
+        if formats.len() > 1 && formats.len() != params.len() {
+            return Err(ErrorCode::BadArguments(format!(
+                "Bind message has {} parameter formats but {} parameters",
+                formats.len(),
+                params.len()
+            )));
+        }
+
+        let literals = params
+            .iter()
+            .enumerate()
+            .map(|(index, param)| {
+                let format = match formats.len() {
+                    0 => 0,
+                    1 => formats[0],
+                    _ => formats[index],
+                };
+                match param {
+                    None => Ok("NULL".to_string()),
+                    Some(v) if format == 0 => text_literal(self.param_types[index], v),
+                    Some(v) => binary_literal(self.param_types[index], v),
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(self.replace_placeholders(&literals))
+    }
+
+    fn replace_placeholders(&self, literals: &[String]) -> String {
+        let mut query = String::with_capacity(self.query.len());
+        let mut position = 0;
+        for (span, index) in &self.placeholders {
+            query.push_str(&self.query[position..span.start]);
+            query.push_str(&literals[*index]);
+            position = span.end;
+        }
+        query.push_str(&self.query[position..]);
+        query
+    }
+}
+
+// Convert a parameter in text format to a literal, numbers and booleans are checked so that
+// nothing but a literal can be bound into the query.
+fn text_literal(type_oid: i32, v: &[u8]) -> Result<String> {
+    let text = String::from_utf8_lossy(v);
+    let invalid = || {
+        ErrorCode::BadArguments(format!(
+            "Invalid input for parameter of type {}: {}",
+            type_oid, text
+        ))
+    };
+    match type_oid {
+        INT2_OID | INT4_OID | INT8_OID => match text.trim().parse::<i64>() {
+            Ok(v) => Ok(number_literal(v.to_string())),
+            Err(_) => Err(invalid()),
+        },
+        FLOAT4_OID | FLOAT8_OID | NUMERIC_OID => match text.trim().parse::<f64>() {
+            Ok(v) if v.is_finite() => Ok(number_literal(text.trim().to_string())),
+            _ => Err(invalid()),
+        },
+        BOOL_OID => match text.trim().to_lowercase().as_str() {
+            "t" | "true" | "y" | "yes" | "on" | "1" => Ok("TRUE".to_string()),
+            "f" | "false" | "n" | "no" | "off" | "0" => Ok("FALSE".to_string()),
+            _ => Err(invalid()),
+        },
+        _ => Ok(quote_string(&text)),
+    }
+}
+
+// Convert a parameter in binary format to a literal, which is only supported for the
+// fixed-length numeric types and the string types.
+fn binary_literal(type_oid: i32, v: &[u8]) -> Result<String> {
+    let invalid = || {
+        ErrorCode::BadBytes(format!(
+            "Invalid length of binary parameter of type {}: {}",
+            type_oid,
+            v.len()
+        ))
+    };
+    match type_oid {
+        BOOL_OID => match v {
+            [0] => Ok("FALSE".to_string()),
+            [_] => Ok("TRUE".to_string()),
+            _ => Err(invalid()),
+        },
+        INT2_OID => v
+            .try_into()
+            .map(|v| number_literal(i16::from_be_bytes(v).to_string()))
+            .map_err(|_| invalid()),
+        INT4_OID => v
+            .try_into()
+            .map(|v| number_literal(i32::from_be_bytes(v).to_string()))
+            .map_err(|_| invalid()),
+        INT8_OID => v
+            .try_into()
+            .map(|v| number_literal(i64::from_be_bytes(v).to_string()))
+            .map_err(|_| invalid()),
+        FLOAT4_OID => v
+            .try_into()
+            .map(|v| number_literal(f32::from_be_bytes(v).to_string()))
+            .map_err(|_| invalid()),
+        FLOAT8_OID => v
+            .try_into()
+            .map(|v| number_literal(f64::from_be_bytes(v).to_string()))
+            .map_err(|_| invalid()),
+        0 | TEXT_OID | VARCHAR_OID => Ok(quote_string(&String::from_utf8_lossy(v))),
+        _ => Err(ErrorCode::UnImplement(format!(
+            "Unsupported binary format of parameter type {}",
+            type_oid
+        ))),
+    }
+}
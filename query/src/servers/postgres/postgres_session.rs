@@ -0,0 +1,70 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::net::Shutdown;
+
+use common_base::base::tokio::net::TcpStream;
+use common_base::base::Runtime;
+use common_base::base::Thread;
+use common_base::base::TrySpawn;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_exception::ToErrorCode;
+use common_tracing::tracing;
+
+use crate::servers::postgres::postgres_interactive_worker::InteractiveWorker;
+use crate::sessions::SessionRef;
+
+pub struct PostgresConnection;
+
+impl PostgresConnection {
+    pub fn run_on_stream(session: SessionRef, stream: TcpStream) -> Result<()> {
+        let stream = stream
+            .into_std()
+            .map_err_to_code(ErrorCode::TokioError, || {
+                "Cannot to convert Tokio TcpStream to Std TcpStream"
+            })?;
+        PostgresConnection::attach_session(&session, &stream)?;
+
+        let client_ip = match stream.peer_addr() {
+            Ok(addr) => addr.ip().to_string(),
+            Err(_) => "%".to_string(),
+        };
+        let stream = TcpStream::from_std(stream)?;
+        let query_executor =
+            Runtime::with_worker_threads(1, Some("postgres-query-executor".to_string()))?;
+        Thread::spawn(move || {
+            let join_handle = query_executor.spawn(async move {
+                let interactive_worker = InteractiveWorker::create(session, client_ip);
+                if let Err(error) = interactive_worker.run(stream).await {
+                    tracing::error!("Unexpected error occurred during query: {:?}", error);
+                }
+            });
+            let _ = futures::executor::block_on(join_handle);
+        });
+        Ok(())
+    }
+
+    fn attach_session(session: &SessionRef, stream: &std::net::TcpStream) -> Result<()> {
+        let host = stream.peer_addr().ok();
+        let stream_ref = stream.try_clone()?;
+        session.attach(host, move || {
+            if let Err(error) = stream_ref.shutdown(Shutdown::Both) {
+                tracing::error!("Cannot shutdown PostgreSQL session io {}", error);
+            }
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,155 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_datavalues::prelude::TypeID;
+use common_datavalues::remove_nullable;
+use common_datavalues::DataField;
+use common_datavalues::DataType;
+use common_datavalues::DataTypeImpl;
+use common_datavalues::DataValue;
+use common_datavalues::DateConverter;
+use common_datavalues::IntervalKind;
+use common_datavalues::TypeSerializer;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_io::prelude::FormatSettings;
+
+use crate::servers::postgres::postgres_messages::FieldDescription;
+
+// Type OIDs of the builtin types, see `pg_type.dat` of PostgreSQL.
+pub const BOOL_OID: i32 = 16;
+pub const INT8_OID: i32 = 20;
+pub const INT2_OID: i32 = 21;
+pub const INT4_OID: i32 = 23;
+pub const TEXT_OID: i32 = 25;
+pub const JSON_OID: i32 = 114;
+pub const FLOAT4_OID: i32 = 700;
+pub const FLOAT8_OID: i32 = 701;
+pub const VARCHAR_OID: i32 = 1043;
+pub const DATE_OID: i32 = 1082;
+pub const TIMESTAMP_OID: i32 = 1114;
+pub const INTERVAL_OID: i32 = 1186;
+pub const NUMERIC_OID: i32 = 1700;
+
+/// Map a data type to the type OID and the type size (`-1` for variable length) of PostgreSQL.
+/// Unsigned integers are mapped to the next wider signed type since PostgreSQL has none.
+pub fn type_oid(data_type: &DataTypeImpl) -> (i32, i16) {
+    match remove_nullable(data_type).data_type_id() {
+        TypeID::Boolean => (BOOL_OID, 1),
+        TypeID::Int8 | TypeID::Int16 | TypeID::UInt8 => (INT2_OID, 2),
+        TypeID::Int32 | TypeID::UInt16 => (INT4_OID, 4),
+        TypeID::Int64 | TypeID::UInt32 => (INT8_OID, 8),
+        TypeID::UInt64 => (NUMERIC_OID, -1),
+        TypeID::Float32 => (FLOAT4_OID, 4),
+        TypeID::Float64 => (FLOAT8_OID, 8),
+        TypeID::Date => (DATE_OID, 4),
+        TypeID::Timestamp => (TIMESTAMP_OID, 8),
+        TypeID::Interval => (INTERVAL_OID, 16),
+        TypeID::Variant | TypeID::VariantArray | TypeID::VariantObject => (JSON_OID, -1),
+        _ => (TEXT_OID, -1),
+    }
+}
+
+pub fn field_description(field: &DataField) -> FieldDescription {
+    let (type_oid, type_size) = type_oid(field.data_type());
+    FieldDescription {
+        name: field.name().to_string(),
+        type_oid,
+        type_size,
+    }
+}
+
+/// Encode a value in the text format of PostgreSQL, `None` for `NULL`.
+pub fn encode_text(
+    data_type: &DataTypeImpl,
+    value: DataValue,
+    format: &FormatSettings,
+) -> Result<Option<Vec<u8>>> {
+    if value.is_null() {
+        return Ok(None);
+    }
+
+    let data_type = remove_nullable(data_type);
+    let text = match (&data_type, value) {
+        (DataTypeImpl::Boolean(_), DataValue::Boolean(v)) => {
+            if v {
+                "t".to_string()
+            } else {
+                "f".to_string()
+            }
+        }
+        (DataTypeImpl::Timestamp(data_type), DataValue::Int64(v)) => v
+            .to_timestamp(&format.timezone)
+            .naive_local()
+            .format(data_type.format_string().as_str())
+            .to_string(),
+        (DataTypeImpl::Interval(data_type), DataValue::Int64(v)) => {
+            interval_text(data_type.kind(), v)
+        }
+        (DataTypeImpl::String(_), DataValue::String(v)) => return Ok(Some(v)),
+        (
+            DataTypeImpl::Date(_)
+            | DataTypeImpl::Array(_)
+            | DataTypeImpl::Struct(_)
+            | DataTypeImpl::Variant(_)
+            | DataTypeImpl::VariantArray(_)
+            | DataTypeImpl::VariantObject(_),
+            v,
+        ) => data_type.create_serializer().serialize_value(&v, format)?,
+        (_, DataValue::Int64(v)) => v.to_string(),
+        (_, DataValue::UInt64(v)) => v.to_string(),
+        (_, DataValue::Float64(v)) if v.is_nan() => "NaN".to_string(),
+        (_, DataValue::Float64(v)) if v.is_infinite() && v > 0.0 => "Infinity".to_string(),
+        (_, DataValue::Float64(v)) if v.is_infinite() => "-Infinity".to_string(),
+        (_, DataValue::Float64(v)) => v.to_string(),
+        (_, v) => {
+            return Err(ErrorCode::BadDataValueType(format!(
+                "Unsupported column type:{:?}, expected type in schema: {:?}",
+                v.data_type(),
+                data_type
+            )));
+        }
+    };
+    Ok(Some(text.into_bytes()))
+}
+
+/// Format an interval in the `postgres` style of `IntervalStyle`, e.g. `3 days` or `-01:30:00`.
+fn interval_text(kind: &IntervalKind, v: i64) -> String {
+    let units = |singular: &str, plural: &str| match v {
+        1 => format!("{} {}", v, singular),
+        _ => format!("{} {}", v, plural),
+    };
+
+    match kind {
+        IntervalKind::Year => units("year", "years"),
+        IntervalKind::Month => units("mon", "mons"),
+        IntervalKind::Day | IntervalKind::Doy | IntervalKind::Dow => units("day", "days"),
+        IntervalKind::Hour | IntervalKind::Minute | IntervalKind::Second => {
+            let seconds = match kind {
+                IntervalKind::Hour => v.saturating_mul(3600),
+                IntervalKind::Minute => v.saturating_mul(60),
+                _ => v,
+            };
+            let sign = if seconds < 0 { "-" } else { "" };
+            let seconds = seconds.unsigned_abs();
+            format!(
+                "{}{:02}:{:02}:{:02}",
+                sign,
+                seconds / 3600,
+                seconds / 60 % 60,
+                seconds % 60
+            )
+        }
+    }
+}
@@ -0,0 +1,32 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The literals of the parameters which are bound into the queries by the prepared statements
+//! and the query parameters of the handlers.
+
+/// Quote a string literal, both `\` and `'` are escaped.
+pub fn quote_string(v: &str) -> String {
+    format!("'{}'", v.replace('\\', "\\\\").replace('\'', "''"))
+}
+
+/// A literal of the text of a number.
+///
+/// Negative numbers are parenthesized, e.g. `a-?` must not be bound as `a--1`.
+pub fn number_literal(v: String) -> String {
+    if v.starts_with('-') {
+        format!("({})", v)
+    } else {
+        v
+    }
+}
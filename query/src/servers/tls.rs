@@ -0,0 +1,147 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::sync::Arc;
+
+use common_base::base::tokio;
+use common_base::base::tokio::io::AsyncRead;
+use common_base::base::tokio::io::AsyncWrite;
+use common_base::base::tokio::net::TcpListener;
+use common_base::base::tokio::net::TcpStream;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_tracing::tracing;
+use tokio_rustls::rustls::server::AllowAnyAnonymousOrAuthenticatedClient;
+use tokio_rustls::rustls::server::AllowAnyAuthenticatedClient;
+use tokio_rustls::rustls::Certificate;
+use tokio_rustls::rustls::PrivateKey;
+use tokio_rustls::rustls::RootCertStore;
+use tokio_rustls::rustls::ServerConfig;
+use tokio_rustls::TlsAcceptor;
+
+/// Build the TLS acceptor of a handler from the PEM files of the server certificate and key.
+/// If `root_ca_cert` exists, the clients must present a certificate signed by it, otherwise
+/// the client certificate is optional.
+///
+/// The client certificate only gates the transport, it is not mapped to a user: the clients
+/// still authenticate as a user by the protocol of the handler.
+pub fn build_tls_acceptor(
+    server_cert: &str,
+    server_key: &str,
+    root_ca_cert: &str,
+) -> Result<TlsAcceptor> {
+    let certs = load_certs(server_cert)?;
+    let key = load_private_key(server_key)?;
+
+    let builder = ServerConfig::builder().with_safe_defaults();
+    let builder = match Path::new(root_ca_cert).exists() {
+        false => builder.with_client_cert_verifier(AllowAnyAnonymousOrAuthenticatedClient::new(
+            RootCertStore::empty(),
+        )),
+        true => {
+            let mut roots = RootCertStore::empty();
+            for cert in load_certs(root_ca_cert)? {
+                roots.add(&cert).map_err(|e| {
+                    ErrorCode::TLSConfigurationFailure(format!(
+                        "Invalid root ca cert {}: {}",
+                        root_ca_cert, e
+                    ))
+                })?;
+            }
+            builder.with_client_cert_verifier(AllowAnyAuthenticatedClient::new(roots))
+        }
+    };
+
+    let config = builder.with_single_cert(certs, key).map_err(|e| {
+        ErrorCode::TLSConfigurationFailure(format!("Invalid server cert or key: {}", e))
+    })?;
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+/// Create a connected pair of loopback streams.
+///
+/// The protocol servers of the pinned opensrv revisions only run on a `TcpStream`, so the TLS
+/// of a connection is terminated in process and the decrypted traffic is relayed to the server
+/// over loopback. The streams we serve ourselves, e.g. the rejected connections of MySQL, are
+/// served on the `TlsStream` directly.
+/// Any local process can connect to the listener, so only the stream whose peer is our own
+/// connecting end is accepted, the others are dropped.
+pub async fn loopback_pair() -> Result<(TcpStream, TcpStream)> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let connected = TcpStream::connect(listener.local_addr()?).await?;
+    let expected = connected.local_addr()?;
+    loop {
+        let (accepted, peer) = listener.accept().await?;
+        if peer == expected {
+            return Ok((connected, accepted));
+        }
+        tracing::warn!("Reject unexpected loopback connection from {}", peer);
+    }
+}
+
+/// Accept the TLS of the stream and relay it to a loopback stream, which is returned to be
+/// served as the plaintext connection.
+pub async fn relay_tls_on_loopback(acceptor: TlsAcceptor, stream: TcpStream) -> Result<TcpStream> {
+    let tls_stream = acceptor.accept(non_blocking(stream)?).await?;
+    let (relay_stream, server_stream) = loopback_pair().await?;
+    tokio::spawn(relay(tls_stream, relay_stream));
+    Ok(server_stream)
+}
+
+/// Copy the data between the two streams until both of them are closed.
+pub async fn relay<A, B>(mut a: A, mut b: B)
+where
+    A: AsyncRead + AsyncWrite + Unpin,
+    B: AsyncRead + AsyncWrite + Unpin,
+{
+    if let Err(error) = tokio::io::copy_bidirectional(&mut a, &mut b).await {
+        tracing::debug!("TLS relay closed: {}", error);
+    }
+}
+
+/// The sessions hand over their streams in blocking mode, which would stall the relay.
+pub fn non_blocking(stream: TcpStream) -> Result<TcpStream> {
+    let stream = stream.into_std()?;
+    stream.set_nonblocking(true)?;
+    Ok(TcpStream::from_std(stream)?)
+}
+
+fn load_certs(path: &str) -> Result<Vec<Certificate>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let certs = rustls_pemfile::certs(&mut reader)?;
+    if certs.is_empty() {
+        return Err(ErrorCode::TLSConfigurationFailure(format!(
+            "No certificate found in {}",
+            path
+        )));
+    }
+    Ok(certs.into_iter().map(Certificate).collect())
+}
+
+fn load_private_key(path: &str) -> Result<PrivateKey> {
+    let mut keys = rustls_pemfile::pkcs8_private_keys(&mut BufReader::new(File::open(path)?))?;
+    if keys.is_empty() {
+        keys = rustls_pemfile::rsa_private_keys(&mut BufReader::new(File::open(path)?))?;
+    }
+    match keys.into_iter().next() {
+        Some(key) => Ok(PrivateKey(key)),
+        None => Err(ErrorCode::TLSConfigurationFailure(format!(
+            "No private key found in {}",
+            path
+        ))),
+    }
+}
@@ -71,6 +71,7 @@ pub struct QueryContext {
     version: String,
     statistics: Arc<RwLock<Statistics>>,
     partition_queue: Arc<RwLock<VecDeque<PartInfoPtr>>>,
+    runtime_filters: Arc<RwLock<Vec<Expression>>>,
     shared: Arc<QueryContextShared>,
     precommit_blocks: Arc<RwLock<Vec<DataBlock>>>,
 }
@@ -88,6 +89,7 @@ impl QueryContext {
         Arc::new(QueryContext {
             statistics: Arc::new(RwLock::new(Statistics::default())),
             partition_queue: Arc::new(RwLock::new(VecDeque::new())),
+            runtime_filters: Arc::new(RwLock::new(Vec::new())),
             version: format!("DatabendQuery {}", *crate::version::DATABEND_COMMIT_VERSION),
             shared,
             precommit_blocks: Arc::new(RwLock::new(Vec::new())),
@@ -206,6 +208,15 @@ impl QueryContext {
         Ok(())
     }
 
+    // Push a filter of the partitions generated at runtime, e.g. the key range of the hash join build side.
+    pub fn push_runtime_filter(&self, filter: Expression) {
+        self.runtime_filters.write().push(filter);
+    }
+
+    pub fn get_runtime_filters(&self) -> Vec<Expression> {
+        self.runtime_filters.read().clone()
+    }
+
     pub fn try_get_statistics(&self) -> Result<Statistics> {
         let statistics = self.statistics.read();
         Ok((*statistics).clone())
@@ -262,6 +273,21 @@ impl QueryContext {
         self.shared.init_query_id.as_ref().read().clone()
     }
 
+    /// Use an id given by the client as the id of the query, e.g. the `query_id` of the
+    /// ClickHouse HTTP handler.
+    pub fn set_id(&self, id: String) {
+        *self.shared.init_query_id.as_ref().write() = id;
+    }
+
+    /// Checks the memory usage of the query against the limits of the query and the user.
+    pub fn check_memory_limit(&self) -> Result<()> {
+        let runtime = self.shared.try_get_runtime()?;
+        runtime
+            .get_tracker()
+            .get_memory_tracker()
+            .check_memory_limit()
+    }
+
     pub fn try_create_abortable(&self, input: SendableDataBlockStream) -> Result<AbortStream> {
         let (abort_handle, abort_stream) = AbortStream::try_create(input)?;
         self.shared.add_source_abort_handle(abort_handle);
@@ -387,6 +413,13 @@ impl QueryContext {
         Ok(operator.layer(self.shared.dal_ctx.as_ref().clone()))
     }
 
+    // Get the operator to spill the intermediate data of the query.
+    pub fn get_spill_operator(&self) -> Result<Operator> {
+        let operator = self.shared.session.session_mgr.get_spill_operator();
+
+        Ok(operator.layer(self.shared.dal_ctx.as_ref().clone()))
+    }
+
     pub fn get_dal_context(&self) -> &DalContext {
         self.shared.dal_ctx.as_ref()
     }
@@ -18,6 +18,7 @@ use std::sync::atomic::AtomicUsize;
 use std::sync::Arc;
 
 use chrono_tz::Tz;
+use common_base::base::MemoryTracker;
 use common_base::base::Progress;
 use common_base::base::Runtime;
 use common_base::infallible::Mutex;
@@ -231,9 +232,10 @@ impl QueryContextShared {
             None => {
                 let settings = self.get_settings();
                 let max_threads = settings.get_max_threads()? as usize;
-                let runtime = Arc::new(Runtime::with_worker_threads(
+                let runtime = Arc::new(Runtime::with_memory_tracker(
                     max_threads,
                     Some("query-ctx".to_string()),
+                    self.create_memory_tracker()?,
                 )?);
                 *query_runtime = Some(runtime.clone());
                 Ok(runtime)
@@ -241,6 +243,24 @@ impl QueryContextShared {
         }
     }
 
+    /// The memory tracker of the query is limited by `max_memory_usage`, and its parent is
+    /// the memory tracker of the user, which is limited by the user quota.
+    fn create_memory_tracker(&self) -> Result<Arc<MemoryTracker>> {
+        let parent_memory_tracker = match self.get_current_user() {
+            Err(_) => MemoryTracker::current(),
+            Ok(user) => {
+                let session_manager = self.session.get_session_manager();
+                let tenant = self.get_tenant();
+                Some(session_manager.get_user_memory_tracker(&tenant, &user))
+            }
+        };
+
+        let memory_tracker = MemoryTracker::create(parent_memory_tracker);
+        let max_memory_usage = self.get_settings().get_max_memory_usage()?;
+        memory_tracker.set_memory_limit(max_memory_usage as i64);
+        Ok(memory_tracker)
+    }
+
     pub fn attach_http_query_handle(&self, handle: HttpQueryHandle) {
         let mut http_query = self.http_query.write();
         *http_query = Some(handle);
@@ -15,16 +15,21 @@
 use std::collections::HashMap;
 use std::future::Future;
 use std::sync::Arc;
+use std::sync::Weak;
 use std::time::Duration;
 
 use common_base::base::tokio;
+use common_base::base::MemoryTracker;
 use common_base::base::Runtime;
 use common_base::base::SignalStream;
 use common_base::infallible::RwLock;
+use common_configs::FsStorageConfig;
 use common_contexts::DalRuntime;
 use common_exception::ErrorCode;
 use common_exception::Result;
+use common_io::prelude::init_fs_operator;
 use common_io::prelude::init_operator;
+use common_meta_types::UserInfo;
 use common_metrics::label_counter;
 use common_tracing::init_query_logger;
 use common_tracing::tracing;
@@ -35,6 +40,7 @@ use opendal::Operator;
 
 use crate::catalogs::CatalogManager;
 use crate::clusters::ClusterDiscovery;
+use crate::servers::http::v1::AsyncQueryManager;
 use crate::servers::http::v1::HttpQueryManager;
 use crate::sessions::session::Session;
 use crate::sessions::session_ref::SessionRef;
@@ -53,6 +59,7 @@ pub struct SessionManager {
     pub(in crate::sessions) user_manager: RwLock<Arc<UserApiProvider>>,
     pub(in crate::sessions) auth_manager: RwLock<Arc<AuthMgr>>,
     pub(in crate::sessions) http_query_manager: Arc<HttpQueryManager>,
+    pub(in crate::sessions) async_query_manager: Arc<AsyncQueryManager>,
 
     pub(in crate::sessions) max_sessions: usize,
     pub(in crate::sessions) active_sessions: Arc<RwLock<HashMap<String, Arc<Session>>>>,
@@ -61,7 +68,9 @@ pub struct SessionManager {
         RwLock<Option<Arc<dyn tracing::Subscriber + Send + Sync>>>,
     pub status: Arc<RwLock<SessionManagerStatus>>,
     storage_operator: RwLock<Operator>,
+    spill_operator: RwLock<Operator>,
     storage_runtime: Arc<Runtime>,
+    user_memory_trackers: RwLock<HashMap<String, Weak<MemoryTracker>>>,
     _guards: Vec<WorkerGuard>,
 }
 
@@ -86,11 +95,16 @@ impl SessionManager {
         let storage_operator = Self::init_storage_operator(&conf)
             .await?
             .layer(DalRuntime::new(storage_runtime.inner()));
+        let spill_operator = match Self::init_spill_operator(&conf).await? {
+            Some(operator) => operator.layer(DalRuntime::new(storage_runtime.inner())),
+            None => storage_operator.clone(),
+        };
 
         // User manager and init the default users.
         let user = UserApiProvider::create_global(conf.clone()).await?;
         let auth_manager = Arc::new(AuthMgr::create(conf.clone(), user.clone()).await?);
         let http_query_manager = HttpQueryManager::create_global(conf.clone()).await?;
+        let async_query_manager = AsyncQueryManager::create_global(&conf);
         let max_sessions = conf.query.max_active_sessions as usize;
         let active_sessions = Arc::new(RwLock::new(HashMap::with_capacity(max_sessions)));
         let status = Arc::new(RwLock::new(Default::default()));
@@ -108,6 +122,7 @@ impl SessionManager {
             discovery: RwLock::new(discovery),
             user_manager: RwLock::new(user),
             http_query_manager,
+            async_query_manager,
             max_sessions,
             active_sessions,
             auth_manager: RwLock::new(auth_manager),
@@ -115,7 +130,9 @@ impl SessionManager {
             query_logger: RwLock::new(query_logger),
             status,
             storage_operator: RwLock::new(storage_operator),
+            spill_operator: RwLock::new(spill_operator),
             storage_runtime: Arc::new(storage_runtime),
+            user_memory_trackers: RwLock::new(HashMap::new()),
             _guards,
         }))
     }
@@ -132,6 +149,10 @@ impl SessionManager {
         self.http_query_manager.clone()
     }
 
+    pub fn get_async_query_manager(self: &Arc<Self>) -> Arc<AsyncQueryManager> {
+        self.async_query_manager.clone()
+    }
+
     pub fn get_auth_manager(self: &Arc<Self>) -> Arc<AuthMgr> {
         self.auth_manager.read().clone()
     }
@@ -149,6 +170,10 @@ impl SessionManager {
         self.storage_operator.read().clone()
     }
 
+    pub fn get_spill_operator(self: &Arc<Self>) -> Operator {
+        self.spill_operator.read().clone()
+    }
+
     pub fn get_storage_cache_manager(&self) -> Arc<CacheManager> {
         self.storage_cache_manager.read().clone()
     }
@@ -157,6 +182,29 @@ impl SessionManager {
         self.storage_runtime.clone()
     }
 
+    /// Get the memory tracker shared by the running queries of the user, which is limited
+    /// by the `max_memory_in_bytes` of the user quota.
+    pub fn get_user_memory_tracker(&self, tenant: &str, user: &UserInfo) -> Arc<MemoryTracker> {
+        let key = format!("{}/{}", tenant, user.identity());
+        let mut user_memory_trackers = self.user_memory_trackers.write();
+
+        let memory_tracker = match user_memory_trackers.get(&key).and_then(Weak::upgrade) {
+            Some(memory_tracker) => memory_tracker,
+            None => {
+                // Remove the trackers of the users without running queries.
+                user_memory_trackers.retain(|_, tracker| tracker.strong_count() != 0);
+
+                let memory_tracker = MemoryTracker::create(MemoryTracker::current());
+                user_memory_trackers.insert(key, Arc::downgrade(&memory_tracker));
+                memory_tracker
+            }
+        };
+
+        // The quota may be altered since the tracker is created.
+        memory_tracker.set_memory_limit(user.quota.max_memory_in_bytes as i64);
+        memory_tracker
+    }
+
     pub async fn create_session(self: &Arc<Self>, typ: SessionType) -> Result<SessionRef> {
         // TODO: maybe deadlock
         let config = self.get_conf();
@@ -325,6 +373,19 @@ impl SessionManager {
         Ok(op.with_backoff(backon::ExponentialBackoff::default()))
     }
 
+    // Init the local spill operator by config, the data is spilled to the storage if no local path.
+    async fn init_spill_operator(conf: &Config) -> Result<Option<Operator>> {
+        match conf.query.spill_local_path.is_empty() {
+            true => Ok(None),
+            false => {
+                let fs_conf = FsStorageConfig {
+                    data_path: conf.query.spill_local_path.clone(),
+                };
+                Ok(Some(init_fs_operator(&fs_conf).await?))
+            }
+        }
+    }
+
     pub async fn reload_config(&self) -> Result<()> {
         let config = {
             let mut config = self.conf.write();
@@ -347,7 +408,12 @@ impl SessionManager {
             let operator = Self::init_storage_operator(&config)
                 .await?
                 .layer(DalRuntime::new(self.storage_runtime.inner()));
+            let spill_operator = match Self::init_spill_operator(&config).await? {
+                Some(operator) => operator.layer(DalRuntime::new(self.storage_runtime.inner())),
+                None => operator.clone(),
+            };
             *self.storage_operator.write() = operator;
+            *self.spill_operator.write() = spill_operator;
         }
 
         {
@@ -79,6 +79,14 @@ impl Settings {
                 desc: "The maximum number of threads to execute the request. By default, it is determined automatically.",
             },
 
+            // max_memory_usage
+            SettingValue {
+                default_value: DataValue::UInt64(0),
+                user_setting: UserSetting::create("max_memory_usage", DataValue::UInt64(0)),
+                level: ScopeLevel::Session,
+                desc: "The maximum memory in bytes of a query, 0 is no limited, default value: 0",
+            },
+
             // flight_client_timeout
             SettingValue {
                 default_value: DataValue::UInt64(60),
@@ -109,6 +117,13 @@ impl Settings {
                 level: ScopeLevel::Session,
                 desc: "Enable planner v2 by setting this variable to 1, default value: 0",
             },
+            // max_recursive_cte_depth
+            SettingValue {
+                default_value: DataValue::UInt64(1000),
+                user_setting: UserSetting::create("max_recursive_cte_depth", DataValue::UInt64(1000)),
+                level: ScopeLevel::Session,
+                desc: "The maximum number of iterations of a recursive CTE, 0 is no limited, default value: 1000",
+            },
             SettingValue {
                 default_value: DataValue::String("\n".as_bytes().to_vec()),
                 user_setting: UserSetting::create("record_delimiter", DataValue::String("\n".as_bytes().to_vec())),
@@ -145,6 +160,69 @@ impl Settings {
                 level: ScopeLevel::Session,
                 desc: "The threshold of keys to open two-level aggregation, default value: 10000",
             },
+            // group_by_spill_threshold
+            SettingValue {
+                default_value: DataValue::UInt64(0),
+                user_setting: UserSetting::create("group_by_spill_threshold", DataValue::UInt64(0)),
+                level: ScopeLevel::Session,
+                desc: "Spill aggregation states to disk above this memory in bytes, 0 disables spilling, default value: 0",
+            },
+            // sort_spill_threshold
+            SettingValue {
+                default_value: DataValue::UInt64(0),
+                user_setting: UserSetting::create("sort_spill_threshold", DataValue::UInt64(0)),
+                level: ScopeLevel::Session,
+                desc: "Spill sorted blocks to disk above this memory in bytes, 0 disables spilling, default value: 0",
+            },
+            // join_spill_threshold
+            SettingValue {
+                default_value: DataValue::UInt64(0),
+                user_setting: UserSetting::create("join_spill_threshold", DataValue::UInt64(0)),
+                level: ScopeLevel::Session,
+                desc: "Spill hash join to disk above this memory in bytes, 0 disables spilling, default value: 0",
+            },
+            // wasm_udf_max_memory_mb
+            SettingValue {
+                default_value: DataValue::UInt64(64),
+                user_setting: UserSetting::create("wasm_udf_max_memory_mb", DataValue::UInt64(64)),
+                level: ScopeLevel::Session,
+                desc: "The maximum memory in MB of a WebAssembly UDF call, default value: 64",
+            },
+            // wasm_udf_timeout_ms
+            SettingValue {
+                default_value: DataValue::UInt64(10000),
+                user_setting: UserSetting::create("wasm_udf_timeout_ms", DataValue::UInt64(10000)),
+                level: ScopeLevel::Session,
+                desc: "The maximum running time in milliseconds of a WebAssembly UDF call, default value: 10000",
+            },
+            // enable_query_cache
+            SettingValue {
+                default_value: DataValue::UInt64(0),
+                user_setting: UserSetting::create("enable_query_cache", DataValue::UInt64(0)),
+                level: ScopeLevel::Session,
+                desc: "Reuse the cached results of the same SELECT on the same table snapshots, default value: 0",
+            },
+            // query_cache_ttl_secs
+            SettingValue {
+                default_value: DataValue::UInt64(300),
+                user_setting: UserSetting::create("query_cache_ttl_secs", DataValue::UInt64(300)),
+                level: ScopeLevel::Session,
+                desc: "The seconds to keep a cached query result, default value: 300",
+            },
+            // query_cache_max_result_bytes
+            SettingValue {
+                default_value: DataValue::UInt64(1024 * 1024),
+                user_setting: UserSetting::create("query_cache_max_result_bytes", DataValue::UInt64(1024 * 1024)),
+                level: ScopeLevel::Session,
+                desc: "The maximum bytes of a query result to cache, default value: 1048576",
+            },
+            // query_cache_storage
+            SettingValue {
+                default_value: DataValue::String("memory".as_bytes().to_vec()),
+                user_setting: UserSetting::create("query_cache_storage", DataValue::String("memory".as_bytes().to_vec())),
+                level: ScopeLevel::Session,
+                desc: "Where to cache the query results, memory or disk, default value: memory",
+            },
         ];
 
         let settings = Arc::new(RwLock::new(HashMap::default()));
@@ -180,6 +258,18 @@ impl Settings {
         self.try_get_u64(key)
     }
 
+    // Get max_memory_usage.
+    pub fn get_max_memory_usage(&self) -> Result<u64> {
+        let key = "max_memory_usage";
+        self.try_get_u64(key)
+    }
+
+    // Set max_memory_usage.
+    pub fn set_max_memory_usage(&self, val: u64) -> Result<()> {
+        let key = "max_memory_usage";
+        self.try_set_u64(key, val, false)
+    }
+
     // Get max_threads.
     pub fn get_max_threads(&self) -> Result<u64> {
         let key = "max_threads";
@@ -214,6 +304,11 @@ impl Settings {
         self.try_get_u64(KEY)
     }
 
+    pub fn get_max_recursive_cte_depth(&self) -> Result<u64> {
+        let key = "max_recursive_cte_depth";
+        self.try_get_u64(key)
+    }
+
     pub fn get_field_delimiter(&self) -> Result<Vec<u8>> {
         let key = "field_delimiter";
         self.check_and_get_setting_value(key)
@@ -254,6 +349,77 @@ impl Settings {
         self.try_set_u64(key, val, false)
     }
 
+    // Get group by spill threshold
+    pub fn get_group_by_spill_threshold(&self) -> Result<u64> {
+        let key = "group_by_spill_threshold";
+        self.try_get_u64(key)
+    }
+
+    // Set group by spill threshold
+    pub fn set_group_by_spill_threshold(&self, val: u64) -> Result<()> {
+        let key = "group_by_spill_threshold";
+        self.try_set_u64(key, val, false)
+    }
+
+    // Get sort spill threshold
+    pub fn get_sort_spill_threshold(&self) -> Result<u64> {
+        let key = "sort_spill_threshold";
+        self.try_get_u64(key)
+    }
+
+    // Set sort spill threshold
+    pub fn set_sort_spill_threshold(&self, val: u64) -> Result<()> {
+        let key = "sort_spill_threshold";
+        self.try_set_u64(key, val, false)
+    }
+
+    // Get hash join spill threshold
+    pub fn get_join_spill_threshold(&self) -> Result<u64> {
+        let key = "join_spill_threshold";
+        self.try_get_u64(key)
+    }
+
+    // Set hash join spill threshold
+    pub fn set_join_spill_threshold(&self, val: u64) -> Result<()> {
+        let key = "join_spill_threshold";
+        self.try_set_u64(key, val, false)
+    }
+
+    // Get the maximum memory in MB of a WebAssembly UDF call.
+    pub fn get_wasm_udf_max_memory_mb(&self) -> Result<u64> {
+        let key = "wasm_udf_max_memory_mb";
+        self.try_get_u64(key)
+    }
+
+    // Get the maximum running time in milliseconds of a WebAssembly UDF call.
+    pub fn get_wasm_udf_timeout_ms(&self) -> Result<u64> {
+        let key = "wasm_udf_timeout_ms";
+        self.try_get_u64(key)
+    }
+
+    pub fn get_enable_query_cache(&self) -> Result<u64> {
+        let key = "enable_query_cache";
+        self.try_get_u64(key)
+    }
+
+    // Get the seconds to keep a cached query result.
+    pub fn get_query_cache_ttl_secs(&self) -> Result<u64> {
+        let key = "query_cache_ttl_secs";
+        self.try_get_u64(key)
+    }
+
+    // Get the maximum bytes of a query result to cache.
+    pub fn get_query_cache_max_result_bytes(&self) -> Result<u64> {
+        let key = "query_cache_max_result_bytes";
+        self.try_get_u64(key)
+    }
+
+    pub fn get_query_cache_storage(&self) -> Result<Vec<u8>> {
+        let key = "query_cache_storage";
+        self.check_and_get_setting_value(key)
+            .and_then(|v| v.user_setting.value.as_string())
+    }
+
     pub fn has_setting(&self, key: &str) -> bool {
         let settings = self.settings.read();
         settings.get(key).is_some()
@@ -22,6 +22,7 @@ use common_planners::Expression;
 use crate::sql::exec::util::format_field_name;
 use crate::sql::plans::PhysicalScan;
 use crate::sql::plans::ProjectPlan;
+use crate::sql::plans::SetOperationPlan;
 use crate::sql::IndexType;
 use crate::sql::Metadata;
 
@@ -68,6 +69,23 @@ impl<'a> DataSchemaBuilder<'a> {
         Ok(DataSchemaRefExt::create(fields))
     }
 
+    pub fn build_set_operation(&self, plan: &SetOperationPlan) -> Result<DataSchemaRef> {
+        self.build_columns(&plan.output_columns)
+    }
+
+    /// Builds the schema of the given columns in order.
+    pub fn build_columns(&self, columns: &[IndexType]) -> Result<DataSchemaRef> {
+        let mut fields: Vec<DataField> = vec![];
+        for index in columns.iter() {
+            let column_entry = self.metadata.column(*index);
+            let field_name = format_field_name(column_entry.name.as_str(), *index);
+            let field = DataField::new(field_name.as_str(), column_entry.data_type.clone());
+            fields.push(field);
+        }
+
+        Ok(DataSchemaRefExt::create(fields))
+    }
+
     pub fn build_physical_scan(&self, plan: &PhysicalScan) -> Result<DataSchemaRef> {
         let mut fields: Vec<DataField> = vec![];
         for index in plan.columns.iter() {
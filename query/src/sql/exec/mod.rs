@@ -16,6 +16,7 @@ mod data_schema_builder;
 mod expression_builder;
 mod util;
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use common_datavalues::DataField;
@@ -31,24 +32,40 @@ pub use util::decode_field_name;
 pub use util::format_field_name;
 
 use super::plans::BasePlan;
+use crate::pipelines::new::executor::PipelineExecutor;
 use crate::pipelines::new::processors::port::InputPort;
+use crate::pipelines::new::processors::port::OutputPort;
 use crate::pipelines::new::processors::AggregatorParams;
 use crate::pipelines::new::processors::AggregatorTransformParams;
+use crate::pipelines::new::processors::AsyncSinker;
 use crate::pipelines::new::processors::ChainingHashTable;
+use crate::pipelines::new::processors::CteSource;
+use crate::pipelines::new::processors::CteState;
 use crate::pipelines::new::processors::ExpressionTransform;
 use crate::pipelines::new::processors::HashJoinState;
 use crate::pipelines::new::processors::ProjectionTransform;
+use crate::pipelines::new::processors::RecursiveIteration;
+use crate::pipelines::new::processors::RuntimeFilter;
+use crate::pipelines::new::processors::SetOperationState;
 use crate::pipelines::new::processors::SinkBuildHashTable;
+use crate::pipelines::new::processors::SinkCte;
+use crate::pipelines::new::processors::SinkSetOperation;
 use crate::pipelines::new::processors::Sinker;
 use crate::pipelines::new::processors::SortMergeCompactor;
 use crate::pipelines::new::processors::TransformAggregator;
+use crate::pipelines::new::processors::TransformDistinct;
+use crate::pipelines::new::processors::TransformExpandGroupingSets;
 use crate::pipelines::new::processors::TransformFilter;
 use crate::pipelines::new::processors::TransformHashJoinProbe;
+use crate::pipelines::new::processors::TransformIntersectExcept;
 use crate::pipelines::new::processors::TransformLimit;
+use crate::pipelines::new::processors::TransformRecursiveCte;
 use crate::pipelines::new::processors::TransformSortMerge;
 use crate::pipelines::new::processors::TransformSortPartial;
+use crate::pipelines::new::processors::TransformUnionAll;
 use crate::pipelines::new::NewPipeline;
 use crate::pipelines::new::SinkPipeBuilder;
+use crate::pipelines::new::SourcePipeBuilder;
 use crate::pipelines::transforms::get_sort_descriptions;
 use crate::sessions::QueryContext;
 use crate::sql::exec::data_schema_builder::DataSchemaBuilder;
@@ -57,12 +74,21 @@ use crate::sql::exec::util::check_physical;
 use crate::sql::optimizer::SExpr;
 use crate::sql::plans::AggregatePlan;
 use crate::sql::plans::AndExpr;
+use crate::sql::plans::BoundColumnRef;
+use crate::sql::plans::CteScanPlan;
 use crate::sql::plans::FilterPlan;
+use crate::sql::plans::GroupingSets;
+use crate::sql::plans::JoinType;
 use crate::sql::plans::LimitPlan;
+use crate::sql::plans::MaterializedCtePlan;
 use crate::sql::plans::PhysicalHashJoin;
 use crate::sql::plans::PhysicalScan;
 use crate::sql::plans::PlanType;
 use crate::sql::plans::ProjectPlan;
+use crate::sql::plans::RecursiveCtePlan;
+use crate::sql::plans::Scalar;
+use crate::sql::plans::SetOperationPlan;
+use crate::sql::plans::SetOperationType;
 use crate::sql::plans::SortPlan;
 use crate::sql::IndexType;
 use crate::sql::Metadata;
@@ -75,6 +101,8 @@ pub struct PipelineBuilder {
     expression: SExpr,
 
     pipelines: Vec<NewPipeline>,
+    /// The rows of the materialized CTEs and the work tables of the recursive CTEs
+    cte_states: HashMap<IndexType, Arc<CteState>>,
 }
 
 impl PipelineBuilder {
@@ -91,6 +119,7 @@ impl PipelineBuilder {
             expression,
 
             pipelines: vec![],
+            cte_states: HashMap::new(),
         }
     }
 
@@ -157,7 +186,7 @@ impl PipelineBuilder {
         match plan.plan_type() {
             PlanType::PhysicalScan => {
                 let physical_scan: PhysicalScan = plan.try_into()?;
-                self.build_physical_scan(&physical_scan, pipeline)
+                self.build_physical_scan(context, &physical_scan, pipeline)
             }
             PlanType::Project => {
                 let project: ProjectPlan = plan.try_into()?;
@@ -183,14 +212,17 @@ impl PipelineBuilder {
                     self.build_pipeline(context.clone(), &expression.children()[0], pipeline)?;
                 let mut child_pipeline = NewPipeline::create();
                 let build_schema = self.build_pipeline(
-                    QueryContext::create_from(context),
+                    QueryContext::create_from(context.clone()),
                     &expression.children()[1],
                     &mut child_pipeline,
                 )?;
+                let runtime_filters =
+                    self.build_runtime_filters(context, &hash_join, &expression.children()[0]);
                 self.build_hash_join(
                     &hash_join,
                     build_schema,
                     probe_schema,
+                    runtime_filters,
                     child_pipeline,
                     pipeline,
                 )
@@ -207,6 +239,51 @@ impl PipelineBuilder {
                     self.build_pipeline(context, &expression.children()[0], pipeline)?;
                 self.build_limit(&limit_plan, input_schema, pipeline)
             }
+            PlanType::SetOperation => {
+                let set_operation: SetOperationPlan = plan.try_into()?;
+                let left_schema =
+                    self.build_pipeline(context.clone(), &expression.children()[0], pipeline)?;
+                let mut right_pipeline = NewPipeline::create();
+                let right_schema = self.build_pipeline(
+                    QueryContext::create_from(context),
+                    &expression.children()[1],
+                    &mut right_pipeline,
+                )?;
+                self.build_set_operation(
+                    &set_operation,
+                    left_schema,
+                    right_schema,
+                    right_pipeline,
+                    pipeline,
+                )
+            }
+            PlanType::CteScan => {
+                let cte_scan: CteScanPlan = plan.try_into()?;
+                self.build_cte_scan(context, &cte_scan, pipeline)
+            }
+            PlanType::MaterializedCte => {
+                let materialized_cte: MaterializedCtePlan = plan.try_into()?;
+                let mut cte_pipeline = NewPipeline::create();
+                let cte_schema = self.build_pipeline(
+                    QueryContext::create_from(context.clone()),
+                    &expression.children()[0],
+                    &mut cte_pipeline,
+                )?;
+                self.build_materialized_cte(&materialized_cte, cte_schema, cte_pipeline)?;
+                self.build_pipeline(context, &expression.children()[1], pipeline)
+            }
+            PlanType::RecursiveCte => {
+                let recursive_cte: RecursiveCtePlan = plan.try_into()?;
+                let anchor_schema =
+                    self.build_pipeline(context.clone(), &expression.children()[0], pipeline)?;
+                self.build_recursive_cte(
+                    context,
+                    &recursive_cte,
+                    anchor_schema,
+                    expression.children()[1].clone(),
+                    pipeline,
+                )
+            }
             _ => Err(ErrorCode::LogicalError("Invalid physical plan")),
         }
     }
@@ -280,15 +357,16 @@ impl PipelineBuilder {
 
     fn build_physical_scan(
         &mut self,
+        context: Arc<QueryContext>,
         scan: &PhysicalScan,
         pipeline: &mut NewPipeline,
     ) -> Result<DataSchemaRef> {
         let table_entry = self.metadata.table(scan.table_index);
         let plan = table_entry.source.clone();
 
-        let table = self.ctx.build_table_from_source_plan(&plan)?;
-        self.ctx.try_set_partitions(plan.parts.clone())?;
-        table.read2(self.ctx.clone(), &plan, pipeline)?;
+        let table = context.build_table_from_source_plan(&plan)?;
+        context.try_set_partitions(plan.parts.clone())?;
+        table.read2(context.clone(), &plan, pipeline)?;
         let columns: Vec<IndexType> = scan.columns.iter().cloned().collect();
         let projections: Vec<Expression> = columns
             .iter()
@@ -343,6 +421,15 @@ impl PipelineBuilder {
             ));
         }
 
+        // Expand each row into one row per grouping set, which appends the nullable grouping
+        // columns and the grouping id referenced by the group items.
+        let input_schema = match &aggregate.grouping_sets {
+            Some(grouping_sets) => {
+                self.build_expand_grouping_sets(grouping_sets, input_schema, pipeline)?
+            }
+            None => input_schema,
+        };
+
         // Process group by with non-column expression, such as `a+1`
         // TODO(xudong963): move to aggregate transform
         let schema_builder = DataSchemaBuilder::new(&self.metadata);
@@ -434,16 +521,60 @@ impl PipelineBuilder {
         Ok(final_schema)
     }
 
+    fn build_expand_grouping_sets(
+        &self,
+        grouping_sets: &GroupingSets,
+        input_schema: DataSchemaRef,
+        pipeline: &mut NewPipeline,
+    ) -> Result<DataSchemaRef> {
+        let mut grouping_columns = Vec::with_capacity(grouping_sets.columns.len());
+        let mut output_columns = Vec::with_capacity(grouping_sets.columns.len() + 1);
+        for (column, nullable_column) in grouping_sets.columns.iter() {
+            let column_entry = self.metadata.column(*column);
+            let field_name = format_field_name(column_entry.name.as_str(), *column);
+            grouping_columns.push(input_schema.index_of(field_name.as_str())?);
+            output_columns.push(*nullable_column);
+        }
+        output_columns.push(grouping_sets.grouping_id);
+
+        let schema_builder = DataSchemaBuilder::new(&self.metadata);
+        let mut output_fields = input_schema.fields().clone();
+        output_fields.extend(
+            schema_builder
+                .build_columns(&output_columns)?
+                .fields()
+                .iter()
+                .cloned(),
+        );
+        let output_schema = Arc::new(DataSchema::new(output_fields));
+
+        pipeline.add_transform(|transform_input_port, transform_output_port| {
+            TransformExpandGroupingSets::try_create(
+                transform_input_port,
+                transform_output_port,
+                grouping_columns.clone(),
+                grouping_sets.sets.clone(),
+                output_schema.clone(),
+            )
+        })?;
+        Ok(output_schema)
+    }
+
     fn build_hash_join(
         &mut self,
         hash_join: &PhysicalHashJoin,
         build_schema: DataSchemaRef,
         probe_schema: DataSchemaRef,
+        runtime_filters: Vec<RuntimeFilter>,
         mut child_pipeline: NewPipeline,
         pipeline: &mut NewPipeline,
     ) -> Result<DataSchemaRef> {
         let builder = DataSchemaBuilder::new(&self.metadata);
-        let output_schema = builder.build_join(probe_schema.clone(), build_schema.clone());
+        let output_schema = match hash_join.join_type {
+            JoinType::Inner => builder.build_join(probe_schema.clone(), build_schema.clone()),
+            // Semi join and anti join only return the rows of probe side
+            JoinType::Semi | JoinType::Anti => probe_schema.clone(),
+        };
 
         let eb = ExpressionBuilder::create(&self.metadata);
         let build_expressions = hash_join
@@ -458,10 +589,12 @@ impl PipelineBuilder {
             .collect::<Result<Vec<Expression>>>()?;
 
         let hash_join_state = Arc::new(ChainingHashTable::try_create(
+            hash_join.join_type,
             build_expressions,
             probe_expressions,
             build_schema,
             probe_schema,
+            runtime_filters,
             self.ctx.clone(),
         )?);
 
@@ -470,13 +603,13 @@ impl PipelineBuilder {
 
         // Probe side
         pipeline.add_transform(|input, output| {
-            Ok(TransformHashJoinProbe::create(
+            TransformHashJoinProbe::create(
                 self.ctx.clone(),
                 input,
                 output,
                 hash_join_state.clone(),
                 output_schema.clone(),
-            ))
+            )
         })?;
 
         self.pipelines.push(child_pipeline);
@@ -484,6 +617,56 @@ impl PipelineBuilder {
         Ok(output_schema)
     }
 
+    /// Creates the runtime filters of the probe keys which are columns of the probe side scan,
+    /// the scan reads with the same context as the join.
+    fn build_runtime_filters(
+        &self,
+        context: Arc<QueryContext>,
+        hash_join: &PhysicalHashJoin,
+        probe: &SExpr,
+    ) -> Vec<RuntimeFilter> {
+        // The probe rows out of the build key ranges are returned by anti join
+        if hash_join.join_type == JoinType::Anti {
+            return vec![];
+        }
+
+        let scan_table_index = match Self::probe_scan_table_index(probe) {
+            None => return vec![],
+            Some(table_index) => table_index,
+        };
+
+        let mut runtime_filters = vec![];
+        for (key_index, probe_key) in hash_join.probe_keys.iter().enumerate() {
+            if let Scalar::BoundColumnRef(BoundColumnRef { column }) = probe_key {
+                let column_entry = self.metadata.column(column.index);
+                if column_entry.table_index == Some(scan_table_index) {
+                    runtime_filters.push(RuntimeFilter::create(
+                        key_index,
+                        column_entry.name.clone(),
+                        context.clone(),
+                    ));
+                }
+            }
+        }
+        runtime_filters
+    }
+
+    /// The table of the scan which reads the rows of the probe side, if the rows can be
+    /// filtered at the scan without changing the join result.
+    fn probe_scan_table_index(expression: &SExpr) -> Option<IndexType> {
+        let plan = expression.plan();
+        match plan.plan_type() {
+            PlanType::PhysicalScan => {
+                let physical_scan: PhysicalScan = plan.try_into().ok()?;
+                Some(physical_scan.table_index)
+            }
+            PlanType::Filter | PlanType::Project | PlanType::PhysicalHashJoin => {
+                Self::probe_scan_table_index(&expression.children()[0])
+            }
+            _ => None,
+        }
+    }
+
     fn build_sink_hash_table(
         &mut self,
         state: Arc<dyn HashJoinState>,
@@ -494,7 +677,7 @@ impl PipelineBuilder {
             let input_port = InputPort::create();
             sink_pipeline_builder.add_sink(
                 input_port.clone(),
-                Sinker::<SinkBuildHashTable>::create(
+                AsyncSinker::<SinkBuildHashTable>::create(
                     input_port,
                     SinkBuildHashTable::try_create(state.clone())?,
                 ),
@@ -505,6 +688,287 @@ impl PipelineBuilder {
         Ok(())
     }
 
+    fn build_set_operation(
+        &mut self,
+        set_operation: &SetOperationPlan,
+        left_schema: DataSchemaRef,
+        right_schema: DataSchemaRef,
+        mut right_pipeline: NewPipeline,
+        pipeline: &mut NewPipeline,
+    ) -> Result<DataSchemaRef> {
+        let schema_builder = DataSchemaBuilder::new(&self.metadata);
+        let output_schema = schema_builder.build_set_operation(set_operation)?;
+        self.build_project_columns(
+            &set_operation.left_columns,
+            &set_operation.output_columns,
+            left_schema,
+            output_schema.clone(),
+            pipeline,
+        )?;
+        self.build_project_columns(
+            &set_operation.right_columns,
+            &set_operation.output_columns,
+            right_schema,
+            output_schema.clone(),
+            &mut right_pipeline,
+        )?;
+
+        // The right side is collected by a sub-pipeline, which is executed before the left side.
+        let state = SetOperationState::create();
+        let mut sink_pipeline_builder = SinkPipeBuilder::create();
+        for _ in 0..right_pipeline.output_len() {
+            let input_port = InputPort::create();
+            sink_pipeline_builder.add_sink(
+                input_port.clone(),
+                Sinker::<SinkSetOperation>::create(
+                    input_port,
+                    SinkSetOperation::create(state.clone()),
+                ),
+            );
+        }
+        right_pipeline.add_pipe(sink_pipeline_builder.finalize());
+        self.pipelines.push(right_pipeline);
+
+        match set_operation.op {
+            SetOperationType::Union => {
+                pipeline.add_transform(|transform_input_port, transform_output_port| {
+                    TransformUnionAll::try_create(
+                        transform_input_port,
+                        transform_output_port,
+                        state.clone(),
+                    )
+                })?;
+
+                if !set_operation.all {
+                    pipeline.resize(1)?;
+                    pipeline.add_transform(TransformDistinct::try_create)?;
+                }
+            }
+            SetOperationType::Intersect | SetOperationType::Except => {
+                pipeline.resize(1)?;
+                pipeline.add_transform(|transform_input_port, transform_output_port| {
+                    TransformIntersectExcept::try_create(
+                        transform_input_port,
+                        transform_output_port,
+                        set_operation.op,
+                        set_operation.all,
+                        state.clone(),
+                    )
+                })?;
+            }
+        }
+
+        Ok(output_schema)
+    }
+
+    /// Projects the columns to the output columns by position, like the columns of a side of
+    /// the set operation.
+    fn build_project_columns(
+        &mut self,
+        columns: &[IndexType],
+        output_columns: &[IndexType],
+        input_schema: DataSchemaRef,
+        output_schema: DataSchemaRef,
+        pipeline: &mut NewPipeline,
+    ) -> Result<()> {
+        let projections: Vec<Expression> = columns
+            .iter()
+            .zip(output_columns.iter())
+            .map(|(index, output_index)| {
+                let column_entry = self.metadata.column(*index);
+                let output_column_entry = self.metadata.column(*output_index);
+                Expression::Alias(
+                    format_field_name(output_column_entry.name.as_str(), *output_index),
+                    Box::new(Expression::Column(format_field_name(
+                        column_entry.name.as_str(),
+                        *index,
+                    ))),
+                )
+            })
+            .collect();
+
+        pipeline.add_transform(|transform_input_port, transform_output_port| {
+            ProjectionTransform::try_create(
+                transform_input_port,
+                transform_output_port,
+                input_schema.clone(),
+                output_schema.clone(),
+                projections.clone(),
+                self.ctx.clone(),
+            )
+        })
+    }
+
+    fn build_cte_scan(
+        &mut self,
+        context: Arc<QueryContext>,
+        cte_scan: &CteScanPlan,
+        pipeline: &mut NewPipeline,
+    ) -> Result<DataSchemaRef> {
+        let state = self
+            .cte_states
+            .get(&cte_scan.cte_index)
+            .cloned()
+            .ok_or_else(|| {
+                ErrorCode::LogicalError(format!(
+                    "CTE {} is referenced before it's materialized",
+                    cte_scan.cte_index
+                ))
+            })?;
+
+        let cte_columns = self.metadata.cte(cte_scan.cte_index).columns.clone();
+        let schema_builder = DataSchemaBuilder::new(&self.metadata);
+        let input_schema = schema_builder.build_columns(&cte_columns)?;
+        let output_schema = schema_builder.build_columns(&cte_scan.columns)?;
+
+        let mut source_builder = SourcePipeBuilder::create();
+        let output = OutputPort::create();
+        source_builder.add_source(output.clone(), CteSource::create(context, output, state)?);
+        pipeline.add_pipe(source_builder.finalize());
+
+        self.build_project_columns(
+            &cte_columns,
+            &cte_scan.columns,
+            input_schema,
+            output_schema.clone(),
+            pipeline,
+        )?;
+        Ok(output_schema)
+    }
+
+    /// The rows of the CTE are collected by a sub-pipeline, which is executed before the
+    /// pipelines referencing the CTE.
+    fn build_materialized_cte(
+        &mut self,
+        materialized_cte: &MaterializedCtePlan,
+        cte_schema: DataSchemaRef,
+        mut cte_pipeline: NewPipeline,
+    ) -> Result<()> {
+        let cte_columns = self
+            .metadata
+            .cte(materialized_cte.cte_index)
+            .columns
+            .clone();
+        let schema_builder = DataSchemaBuilder::new(&self.metadata);
+        let output_schema = schema_builder.build_columns(&cte_columns)?;
+        self.build_project_columns(
+            &cte_columns,
+            &cte_columns,
+            cte_schema,
+            output_schema,
+            &mut cte_pipeline,
+        )?;
+
+        let state = CteState::create();
+        self.build_sink_cte(state.clone(), &mut cte_pipeline)?;
+        self.pipelines.push(cte_pipeline);
+        self.cte_states.insert(materialized_cte.cte_index, state);
+        Ok(())
+    }
+
+    fn build_recursive_cte(
+        &mut self,
+        context: Arc<QueryContext>,
+        recursive_cte: &RecursiveCtePlan,
+        anchor_schema: DataSchemaRef,
+        recursive_term: SExpr,
+        pipeline: &mut NewPipeline,
+    ) -> Result<DataSchemaRef> {
+        let schema_builder = DataSchemaBuilder::new(&self.metadata);
+        let output_schema = schema_builder.build_columns(&recursive_cte.output_columns)?;
+        self.build_project_columns(
+            &recursive_cte.anchor_columns,
+            &recursive_cte.output_columns,
+            anchor_schema,
+            output_schema.clone(),
+            pipeline,
+        )?;
+
+        // The work table is filled by a single processor, which executes the iterations.
+        pipeline.resize(1)?;
+        let iteration =
+            self.build_recursive_iteration(context.clone(), recursive_cte, recursive_term);
+        pipeline.add_transform(|transform_input_port, transform_output_port| {
+            TransformRecursiveCte::try_create(
+                context.clone(),
+                transform_input_port,
+                transform_output_port,
+                recursive_cte.all,
+                iteration.clone(),
+            )
+        })?;
+
+        Ok(output_schema)
+    }
+
+    /// Every iteration builds the pipelines of the recursive term with the work table, and
+    /// executes them to completion.
+    fn build_recursive_iteration(
+        &self,
+        context: Arc<QueryContext>,
+        recursive_cte: &RecursiveCtePlan,
+        recursive_term: SExpr,
+    ) -> RecursiveIteration {
+        let ctx = self.ctx.clone();
+        let metadata = self.metadata.clone();
+        let cte_states = self.cte_states.clone();
+        let recursive_cte = recursive_cte.clone();
+
+        Arc::new(move |work_table: Arc<CteState>, result: Arc<CteState>| {
+            let mut builder = PipelineBuilder::new(
+                ctx.clone(),
+                vec![],
+                metadata.clone(),
+                recursive_term.clone(),
+            );
+            builder.cte_states = cte_states.clone();
+            builder
+                .cte_states
+                .insert(recursive_cte.cte_index, work_table);
+
+            // The tables are scanned again by every iteration.
+            let mut pipeline = NewPipeline::create();
+            let recursive_schema = builder.build_pipeline(
+                QueryContext::create_from(context.clone()),
+                &recursive_term,
+                &mut pipeline,
+            )?;
+            let schema_builder = DataSchemaBuilder::new(&metadata);
+            let output_schema = schema_builder.build_columns(&recursive_cte.output_columns)?;
+            builder.build_project_columns(
+                &recursive_cte.recursive_columns,
+                &recursive_cte.output_columns,
+                recursive_schema,
+                output_schema,
+                &mut pipeline,
+            )?;
+            builder.build_sink_cte(result, &mut pipeline)?;
+            builder.pipelines.push(pipeline);
+
+            let max_threads = ctx.get_settings().get_max_threads()? as usize;
+            for mut pipeline in builder.pipelines {
+                pipeline.set_max_threads(max_threads);
+                let executor = PipelineExecutor::create(ctx.get_storage_runtime(), pipeline)?;
+                executor.execute()?;
+            }
+            Ok(())
+        })
+    }
+
+    fn build_sink_cte(&mut self, state: Arc<CteState>, pipeline: &mut NewPipeline) -> Result<()> {
+        let mut sink_pipeline_builder = SinkPipeBuilder::create();
+        for _ in 0..pipeline.output_len() {
+            let input_port = InputPort::create();
+            sink_pipeline_builder.add_sink(
+                input_port.clone(),
+                Sinker::<SinkCte>::create(input_port, SinkCte::create(state.clone())),
+            );
+        }
+
+        pipeline.add_pipe(sink_pipeline_builder.finalize());
+        Ok(())
+    }
+
     fn build_order_by(
         &mut self,
         sort_plan: &SortPlan,
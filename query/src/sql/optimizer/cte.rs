@@ -0,0 +1,153 @@
+// Copyright 2021 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_exception::Result;
+
+use crate::sql::optimizer::optimize;
+use crate::sql::optimizer::ColumnSet;
+use crate::sql::optimizer::OptimizeContext;
+use crate::sql::optimizer::RequiredProperty;
+use crate::sql::optimizer::SExpr;
+use crate::sql::planner::binder::ColumnBinding;
+use crate::sql::plans::BasePlanImpl;
+use crate::sql::plans::BoundColumnRef;
+use crate::sql::plans::CteScanPlan;
+use crate::sql::plans::MaterializedCtePlan;
+use crate::sql::plans::ProjectItem;
+use crate::sql::plans::ProjectPlan;
+use crate::sql::plans::Scalar;
+use crate::sql::IndexType;
+use crate::sql::Metadata;
+
+/// Resolves the references to the common table expressions of an optimized expression.
+///
+/// A CTE referenced only once is inlined like a derived table. The CTEs referenced more than
+/// once are materialized before the expression is executed, and their references read the
+/// materialized rows. The references to a recursive CTE in its own recursive term read the
+/// work table of the CTE, they are kept as is.
+pub fn resolve_ctes(expression: SExpr, metadata: &Metadata) -> Result<SExpr> {
+    if metadata.ctes().is_empty() {
+        return Ok(expression);
+    }
+
+    let mut resolver = CteResolver {
+        metadata,
+        work_tables: vec![],
+        materialized: vec![],
+    };
+    let mut result = resolver.resolve(&expression)?;
+
+    // The definition of a materialized CTE may reference other materialized CTEs, which are
+    // collected while it's resolved.
+    let mut definitions = Vec::new();
+    let mut index = 0;
+    while index < resolver.materialized.len() {
+        let cte_index = resolver.materialized[index];
+        let definition = resolver.resolve_definition(cte_index)?;
+        definitions.push((cte_index, definition));
+        index += 1;
+    }
+
+    // A CTE can only reference the CTEs declared before it, so the CTEs are materialized in
+    // the order of declaration.
+    definitions.sort_by_key(|(cte_index, _)| *cte_index);
+    for (cte_index, definition) in definitions.into_iter().rev() {
+        result = SExpr::create_binary(MaterializedCtePlan { cte_index }.into(), definition, result);
+    }
+
+    Ok(result)
+}
+
+struct CteResolver<'a> {
+    metadata: &'a Metadata,
+    /// Recursive CTEs whose recursive term is being resolved
+    work_tables: Vec<IndexType>,
+    /// CTEs to be materialized, in the order they are referenced
+    materialized: Vec<IndexType>,
+}
+
+impl<'a> CteResolver<'a> {
+    fn resolve(&mut self, s_expr: &SExpr) -> Result<SExpr> {
+        let plan = s_expr.plan();
+        match &plan {
+            BasePlanImpl::CteScan(scan) if !self.work_tables.contains(&scan.cte_index) => {
+                self.resolve_cte_scan(scan)
+            }
+            BasePlanImpl::RecursiveCte(recursive_cte) => {
+                let anchor = self.resolve(&s_expr.children()[0])?;
+                self.work_tables.push(recursive_cte.cte_index);
+                let recursive_term = self.resolve(&s_expr.children()[1]);
+                self.work_tables.pop();
+                Ok(SExpr::create_binary(plan.clone(), anchor, recursive_term?))
+            }
+            _ => {
+                let mut children = Vec::with_capacity(s_expr.arity());
+                for child in s_expr.children() {
+                    children.push(self.resolve(child)?);
+                }
+                Ok(SExpr::create(plan.clone(), children, None))
+            }
+        }
+    }
+
+    fn resolve_cte_scan(&mut self, scan: &CteScanPlan) -> Result<SExpr> {
+        let entry = self.metadata.cte(scan.cte_index);
+        if entry.references > 1 {
+            if !self.materialized.contains(&scan.cte_index) {
+                self.materialized.push(scan.cte_index);
+            }
+            return Ok(SExpr::create_leaf(scan.clone().into()));
+        }
+
+        // Inline the definition, the columns of the reference are mapped to the output
+        // columns of the definition by position.
+        let definition = self.resolve_definition(scan.cte_index)?;
+        let items = entry
+            .columns
+            .iter()
+            .zip(scan.columns.iter())
+            .map(|(column, index)| {
+                let column_entry = self.metadata.column(*column);
+                ProjectItem {
+                    expr: Scalar::BoundColumnRef(BoundColumnRef {
+                        column: ColumnBinding {
+                            table_name: None,
+                            column_name: column_entry.name.clone(),
+                            index: *column,
+                            data_type: column_entry.data_type.clone(),
+                            visible: true,
+                            scalar: None,
+                        },
+                    }),
+                    index: *index,
+                }
+            })
+            .collect();
+        Ok(SExpr::create_unary(
+            ProjectPlan { items }.into(),
+            definition,
+        ))
+    }
+
+    /// Optimizes and resolves the definition of a CTE.
+    fn resolve_definition(&mut self, cte_index: IndexType) -> Result<SExpr> {
+        let entry = self.metadata.cte(cte_index);
+        let required_columns: ColumnSet = entry.columns.iter().cloned().collect();
+        let optimize_context =
+            OptimizeContext::create(RequiredProperty::create(required_columns), vec![]);
+        // The definition is always set once the CTE is bound.
+        let definition = optimize(entry.s_expr.clone().unwrap(), optimize_context)?;
+        self.resolve(&definition)
+    }
+}
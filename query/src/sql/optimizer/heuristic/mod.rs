@@ -19,6 +19,7 @@ use common_exception::Result;
 
 use crate::sql::optimizer::heuristic::implement::HeuristicImplementor;
 use crate::sql::optimizer::heuristic::rule_list::RuleList;
+use crate::sql::optimizer::rule::RuleID;
 use crate::sql::optimizer::rule::TransformState;
 use crate::sql::optimizer::SExpr;
 
@@ -32,7 +33,7 @@ pub struct HeuristicOptimizer {
 impl HeuristicOptimizer {
     pub fn create() -> Result<Self> {
         Ok(HeuristicOptimizer {
-            rules: RuleList::create(vec![])?,
+            rules: RuleList::create(vec![RuleID::DecorrelateSubquery])?,
             implementor: HeuristicImplementor::new(),
         })
     }
@@ -43,12 +44,16 @@ impl HeuristicOptimizer {
     }
 
     fn optimize_expression(&self, s_expr: &SExpr) -> Result<SExpr> {
-        let mut optimized_children = Vec::with_capacity(s_expr.arity());
-        for expr in s_expr.children() {
+        // Transform rules are applied top-down, so the expressions produced by a rule
+        // (e.g. the subquery joined by decorrelation) are optimized as children.
+        let transformed_expr = self.apply_transform_rules(s_expr, &self.rules)?;
+
+        let mut optimized_children = Vec::with_capacity(transformed_expr.arity());
+        for expr in transformed_expr.children() {
             optimized_children.push(self.optimize_expression(expr)?);
         }
-        let optimized_expr = SExpr::create(s_expr.plan(), optimized_children, None);
-        let result = self.apply_transform_rules(&optimized_expr, &self.rules)?;
+        let optimized_expr = SExpr::create(transformed_expr.plan(), optimized_children, None);
+        let result = self.implement_expression(&optimized_expr)?;
 
         Ok(result)
     }
@@ -57,6 +62,10 @@ impl HeuristicOptimizer {
         let mut result = s_expr.clone();
 
         for rule in rule_list.iter() {
+            if !result.match_pattern(rule.pattern()) {
+                continue;
+            }
+
             let mut state = TransformState::new();
             rule.apply(&result, &mut state)?;
             if !state.results().is_empty() {
@@ -64,13 +73,16 @@ impl HeuristicOptimizer {
             }
         }
 
-        // Implement expression with Implementor
+        Ok(result)
+    }
+
+    fn implement_expression(&self, s_expr: &SExpr) -> Result<SExpr> {
         let mut state = TransformState::new();
         self.implementor.implement(s_expr, &mut state)?;
         if !state.results().is_empty() {
-            result = state.results()[0].clone();
+            return Ok(state.results()[0].clone());
         }
 
-        Ok(result)
+        Ok(s_expr.clone())
     }
 }
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 mod cascades;
+mod cte;
 mod group;
 mod heuristic;
 mod m_expr;
@@ -24,6 +25,7 @@ mod rule;
 mod s_expr;
 
 use common_exception::Result;
+pub use cte::resolve_ctes;
 pub use heuristic::HeuristicOptimizer;
 pub use m_expr::MExpr;
 pub use memo::Memo;
@@ -14,6 +14,7 @@
 
 use common_exception::Result;
 
+use crate::sql::optimizer::rule::rule_decorrelate_subquery::RuleDecorrelateSubquery;
 use crate::sql::optimizer::rule::rule_implement_get::RuleImplementGet;
 use crate::sql::optimizer::rule::rule_implement_hash_join::RuleImplementHashJoin;
 use crate::sql::optimizer::rule::RuleID;
@@ -30,6 +31,7 @@ impl RuleFactory {
         match id {
             RuleID::ImplementGet => Ok(Box::new(RuleImplementGet::create())),
             RuleID::ImplementHashJoin => Ok(Box::new(RuleImplementHashJoin::create())),
+            RuleID::DecorrelateSubquery => Ok(Box::new(RuleDecorrelateSubquery::create())),
         }
     }
 }
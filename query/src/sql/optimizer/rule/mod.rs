@@ -17,6 +17,7 @@ use common_exception::Result;
 use crate::sql::optimizer::SExpr;
 
 mod factory;
+mod rule_decorrelate_subquery;
 mod rule_implement_get;
 mod rule_implement_hash_join;
 mod rule_set;
@@ -40,6 +41,7 @@ pub trait Rule {
 pub enum RuleID {
     ImplementGet,
     ImplementHashJoin,
+    DecorrelateSubquery,
 }
 
 impl RuleID {
@@ -47,6 +49,7 @@ impl RuleID {
         match self {
             RuleID::ImplementGet => "ImplementGet",
             RuleID::ImplementHashJoin => "ImplementHashJoin",
+            RuleID::DecorrelateSubquery => "DecorrelateSubquery",
         }
     }
 
@@ -57,6 +60,7 @@ impl RuleID {
         match self {
             RuleID::ImplementGet => 0,
             RuleID::ImplementHashJoin => 1,
+            RuleID::DecorrelateSubquery => 2,
         }
     }
 }
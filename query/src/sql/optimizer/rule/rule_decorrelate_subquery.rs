@@ -0,0 +1,460 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_datavalues::type_coercion::merge_types;
+use common_datavalues::DataType;
+use common_exception::ErrorCode;
+use common_exception::Result;
+
+use crate::sql::binder::scalar_common::split_conjunctions;
+use crate::sql::binder::scalar_common::split_equivalent_predicate;
+use crate::sql::binder::scalar_common::wrap_cast_if_needed;
+use crate::sql::optimizer::rule::transform_state::TransformState;
+use crate::sql::optimizer::rule::Rule;
+use crate::sql::optimizer::rule::RuleID;
+use crate::sql::optimizer::ColumnSet;
+use crate::sql::optimizer::SExpr;
+use crate::sql::plans::AggregateFunction;
+use crate::sql::plans::AggregatePlan;
+use crate::sql::plans::BoundColumnRef;
+use crate::sql::plans::ComparisonExpr;
+use crate::sql::plans::FilterPlan;
+use crate::sql::plans::FunctionCall;
+use crate::sql::plans::JoinType;
+use crate::sql::plans::LogicalInnerJoin;
+use crate::sql::plans::PatternPlan;
+use crate::sql::plans::PlanType;
+use crate::sql::plans::ProjectPlan;
+use crate::sql::plans::Scalar;
+use crate::sql::plans::ScalarExpr;
+use crate::sql::plans::SubqueryExpr;
+use crate::sql::plans::SubqueryType;
+
+/// Rewrites the subqueries in the predicates of a `Filter` into joins:
+///
+/// - `EXISTS` and `IN` subqueries are turned into semi joins, `NOT EXISTS` and `NOT IN` into
+///   anti joins.
+/// - Scalar subqueries of an aggregation without `GROUP BY` are turned into inner joins with
+///   the aggregation grouped by the correlated columns, and the subquery is replaced with
+///   its output column.
+///
+/// The correlated predicates of a subquery must be equivalences between outer and inner
+/// columns, which are pulled up from the `Filter`s of the subquery as the join keys.
+pub struct RuleDecorrelateSubquery {
+    id: RuleID,
+    pattern: SExpr,
+}
+
+impl RuleDecorrelateSubquery {
+    pub fn create() -> Self {
+        RuleDecorrelateSubquery {
+            id: RuleID::DecorrelateSubquery,
+            pattern: SExpr::create_unary(
+                PatternPlan {
+                    plan_type: PlanType::Filter,
+                }
+                .into(),
+                SExpr::create_leaf(
+                    PatternPlan {
+                        plan_type: PlanType::Pattern,
+                    }
+                    .into(),
+                ),
+            ),
+        }
+    }
+
+    /// Joins the subquery of `EXISTS` or `IN` to the input with a semi join, or an anti join if
+    /// the subquery is negated.
+    fn decorrelate_exists_or_in(
+        input: SExpr,
+        subquery: &SubqueryExpr,
+        negated: bool,
+    ) -> Result<SExpr> {
+        let (subquery_expr, mut left_conditions, mut right_conditions) =
+            pull_up_correlated_predicates(&subquery.subquery, &subquery.outer_columns)?;
+
+        if subquery.typ == SubqueryType::In {
+            let child_expr = subquery.child_expr.as_ref().ok_or_else(|| {
+                ErrorCode::LogicalError("IN subquery must have a child expression")
+            })?;
+            let output_column = BoundColumnRef {
+                column: subquery.output_context.columns[0].clone(),
+            };
+
+            // `NULL` in either side makes `NOT IN` unknown instead of true, which can't be
+            // evaluated with an anti join.
+            if negated
+                && (child_expr.data_type().is_nullable() || output_column.data_type().is_nullable())
+            {
+                return Err(ErrorCode::UnImplement(
+                    "Unsupported NOT IN subquery with nullable columns",
+                ));
+            }
+
+            let (left, right) = coerce_join_keys(*child_expr.clone(), output_column.into())?;
+            left_conditions.push(left);
+            right_conditions.push(right);
+        }
+
+        let join_type = if negated {
+            JoinType::Anti
+        } else {
+            JoinType::Semi
+        };
+        Ok(SExpr::create_binary(
+            LogicalInnerJoin {
+                left_conditions,
+                right_conditions,
+                join_type,
+            }
+            .into(),
+            input,
+            subquery_expr,
+        ))
+    }
+
+    /// Joins the scalar subquery to the input with an inner join, returns the join and the output
+    /// column of the subquery.
+    fn decorrelate_scalar(input: SExpr, subquery: &SubqueryExpr) -> Result<(SExpr, Scalar)> {
+        let (subquery_expr, left_conditions, right_conditions) =
+            decorrelate_aggregate(&subquery.subquery, &subquery.outer_columns)?;
+        let output_column = BoundColumnRef {
+            column: subquery.output_context.columns[0].clone(),
+        };
+
+        let join = SExpr::create_binary(
+            LogicalInnerJoin {
+                left_conditions,
+                right_conditions,
+                join_type: JoinType::Inner,
+            }
+            .into(),
+            input,
+            subquery_expr,
+        );
+        Ok((join, output_column.into()))
+    }
+}
+
+impl Rule for RuleDecorrelateSubquery {
+    fn id(&self) -> RuleID {
+        self.id
+    }
+
+    fn apply(&self, expression: &SExpr, state: &mut TransformState) -> Result<()> {
+        let filter: FilterPlan = expression.plan().try_into()?;
+        if !filter.predicates.iter().any(contains_subquery) {
+            return Ok(());
+        }
+
+        let mut plain_predicates = vec![];
+        let mut subquery_predicates = vec![];
+        for predicate in filter.predicates.into_iter() {
+            if contains_subquery(&predicate) {
+                subquery_predicates.push(predicate);
+            } else {
+                plain_predicates.push(predicate);
+            }
+        }
+
+        // Evaluate the predicates without subquery before joining the subqueries
+        let mut input = expression.children()[0].clone();
+        if !plain_predicates.is_empty() {
+            input = SExpr::create_unary(
+                FilterPlan {
+                    predicates: plain_predicates,
+                    is_having: filter.is_having,
+                }
+                .into(),
+                input,
+            );
+        }
+
+        let mut rewritten_predicates = vec![];
+        for predicate in subquery_predicates.into_iter() {
+            match &predicate {
+                Scalar::SubqueryExpr(subquery) if subquery.typ != SubqueryType::Scalar => {
+                    input = Self::decorrelate_exists_or_in(input, subquery, false)?;
+                }
+                Scalar::ComparisonExpr(ComparisonExpr { op, left, right }) => {
+                    // Comparison never matches the `NULL` produced by an empty subquery, so
+                    // the unmatched rows of the inner join can be discarded.
+                    let (left, right) = match (&**left, &**right) {
+                        (Scalar::SubqueryExpr(subquery), other)
+                            if subquery.typ == SubqueryType::Scalar
+                                && !contains_subquery(other) =>
+                        {
+                            let (join, column) = Self::decorrelate_scalar(input, subquery)?;
+                            input = join;
+                            (column, other.clone())
+                        }
+                        (other, Scalar::SubqueryExpr(subquery))
+                            if subquery.typ == SubqueryType::Scalar
+                                && !contains_subquery(other) =>
+                        {
+                            let (join, column) = Self::decorrelate_scalar(input, subquery)?;
+                            input = join;
+                            (other.clone(), column)
+                        }
+                        _ => {
+                            return Err(ErrorCode::UnImplement(format!(
+                                "Unsupported subquery in predicate: {:?}",
+                                predicate
+                            )));
+                        }
+                    };
+                    rewritten_predicates.push(
+                        ComparisonExpr {
+                            op: op.clone(),
+                            left: Box::new(left),
+                            right: Box::new(right),
+                        }
+                        .into(),
+                    );
+                }
+                _ => match negated_subquery(&predicate) {
+                    Some(subquery) => {
+                        input = Self::decorrelate_exists_or_in(input, subquery, true)?;
+                    }
+                    None => {
+                        return Err(ErrorCode::UnImplement(format!(
+                            "Unsupported subquery in predicate: {:?}",
+                            predicate
+                        )));
+                    }
+                },
+            }
+        }
+
+        if !rewritten_predicates.is_empty() {
+            input = SExpr::create_unary(
+                FilterPlan {
+                    predicates: rewritten_predicates,
+                    is_having: filter.is_having,
+                }
+                .into(),
+                input,
+            );
+        }
+
+        state.add_result(input);
+        Ok(())
+    }
+
+    fn pattern(&self) -> &SExpr {
+        &self.pattern
+    }
+}
+
+fn contains_subquery(scalar: &Scalar) -> bool {
+    match scalar {
+        Scalar::SubqueryExpr(_) => true,
+        Scalar::BoundColumnRef(_) | Scalar::ConstantExpr(_) => false,
+        Scalar::AndExpr(expr) => contains_subquery(&expr.left) || contains_subquery(&expr.right),
+        Scalar::OrExpr(expr) => contains_subquery(&expr.left) || contains_subquery(&expr.right),
+        Scalar::ComparisonExpr(expr) => {
+            contains_subquery(&expr.left) || contains_subquery(&expr.right)
+        }
+        Scalar::AggregateFunction(expr) => expr.args.iter().any(contains_subquery),
+        Scalar::FunctionCall(expr) => expr.arguments.iter().any(contains_subquery),
+        Scalar::Cast(expr) => contains_subquery(&expr.argument),
+    }
+}
+
+/// Returns the subquery of `NOT EXISTS` or `NOT IN`.
+fn negated_subquery(scalar: &Scalar) -> Option<&SubqueryExpr> {
+    match scalar {
+        Scalar::FunctionCall(FunctionCall {
+            func_name,
+            arguments,
+            ..
+        }) if func_name == "not" && arguments.len() == 1 => match &arguments[0] {
+            Scalar::SubqueryExpr(subquery) if subquery.typ != SubqueryType::Scalar => {
+                Some(subquery)
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Casts the join keys of both sides to their common super type, so they have the same hash.
+fn coerce_join_keys(left: Scalar, right: Scalar) -> Result<(Scalar, Scalar)> {
+    let least_super_type = merge_types(&left.data_type(), &right.data_type())?;
+    Ok((
+        wrap_cast_if_needed(left, &least_super_type),
+        wrap_cast_if_needed(right, &least_super_type),
+    ))
+}
+
+/// Pulls the correlated predicates up from the `Filter`s on top of the subquery.
+///
+/// Returns the subquery without these predicates, and the outer and inner join keys of them.
+/// Fails if any other part of the subquery still references the outer columns.
+fn pull_up_correlated_predicates(
+    subquery: &SExpr,
+    outer_columns: &ColumnSet,
+) -> Result<(SExpr, Vec<Scalar>, Vec<Scalar>)> {
+    let mut outer_keys = vec![];
+    let mut inner_keys = vec![];
+    let result = pull_up_predicates(subquery, outer_columns, &mut outer_keys, &mut inner_keys)?;
+
+    if !result.outer_columns().is_empty() {
+        return Err(ErrorCode::UnImplement(
+            "Unsupported correlated subquery, outer columns can only be referenced by equi-predicates in WHERE clause",
+        ));
+    }
+
+    Ok((result, outer_keys, inner_keys))
+}
+
+fn pull_up_predicates(
+    s_expr: &SExpr,
+    outer_columns: &ColumnSet,
+    outer_keys: &mut Vec<Scalar>,
+    inner_keys: &mut Vec<Scalar>,
+) -> Result<SExpr> {
+    let plan = s_expr.plan();
+    match plan.plan_type() {
+        PlanType::Project => {
+            let child =
+                pull_up_predicates(&s_expr.children()[0], outer_columns, outer_keys, inner_keys)?;
+            Ok(SExpr::create_unary(plan, child))
+        }
+        PlanType::Filter => {
+            let filter: FilterPlan = plan.try_into()?;
+            let child =
+                pull_up_predicates(&s_expr.children()[0], outer_columns, outer_keys, inner_keys)?;
+
+            let mut predicates = vec![];
+            for predicate in filter.predicates.iter().flat_map(split_conjunctions) {
+                if predicate.used_columns().is_disjoint(outer_columns) {
+                    predicates.push(predicate);
+                    continue;
+                }
+
+                let (outer_key, inner_key) = match split_equivalent_predicate(&predicate) {
+                    Some((left, right))
+                        if left.used_columns().is_subset(outer_columns)
+                            && right.used_columns().is_disjoint(outer_columns) =>
+                    {
+                        (left, right)
+                    }
+                    Some((left, right))
+                        if right.used_columns().is_subset(outer_columns)
+                            && left.used_columns().is_disjoint(outer_columns) =>
+                    {
+                        (right, left)
+                    }
+                    _ => {
+                        return Err(ErrorCode::UnImplement(format!(
+                            "Unsupported correlated predicate: {:?}",
+                            predicate
+                        )));
+                    }
+                };
+
+                let (outer_key, inner_key) = coerce_join_keys(outer_key, inner_key)?;
+                outer_keys.push(outer_key);
+                inner_keys.push(inner_key);
+            }
+
+            if predicates.is_empty() {
+                return Ok(child);
+            }
+            Ok(SExpr::create_unary(
+                FilterPlan {
+                    predicates,
+                    is_having: filter.is_having,
+                }
+                .into(),
+                child,
+            ))
+        }
+        _ => Ok(s_expr.clone()),
+    }
+}
+
+/// Decorrelates a scalar subquery of an aggregation without `GROUP BY`, the correlated
+/// predicates below the aggregation are pulled up and the aggregation is grouped by the inner
+/// join keys, so there is one row for each outer row.
+fn decorrelate_aggregate(
+    s_expr: &SExpr,
+    outer_columns: &ColumnSet,
+) -> Result<(SExpr, Vec<Scalar>, Vec<Scalar>)> {
+    let plan = s_expr.plan();
+    match plan.plan_type() {
+        PlanType::Project => {
+            let project: ProjectPlan = plan.try_into()?;
+            let (child, outer_keys, inner_keys) =
+                decorrelate_aggregate(&s_expr.children()[0], outer_columns)?;
+            Ok((
+                SExpr::create_unary(project.into(), child),
+                outer_keys,
+                inner_keys,
+            ))
+        }
+        PlanType::Aggregate => {
+            let mut aggregate: AggregatePlan = plan.try_into()?;
+            if !aggregate.group_items.is_empty() {
+                return Err(ErrorCode::UnImplement(
+                    "Unsupported scalar subquery with GROUP BY",
+                ));
+            }
+
+            let (child, outer_keys, inner_keys) =
+                pull_up_correlated_predicates(&s_expr.children()[0], outer_columns)?;
+
+            // `COUNT` of an empty group is 0 instead of `NULL`, which is lost by inner join.
+            let has_count = aggregate.aggregate_functions.iter().any(|agg| {
+                matches!(agg, Scalar::AggregateFunction(AggregateFunction { func_name, .. })
+                    if func_name.eq_ignore_ascii_case("count"))
+            });
+            if !inner_keys.is_empty() && has_count {
+                return Err(ErrorCode::UnImplement(
+                    "Unsupported correlated scalar subquery with COUNT",
+                ));
+            }
+
+            // Group by the inner columns, the casts of the join keys are evaluated on the
+            // output of the aggregation.
+            let group_items = inner_keys
+                .iter()
+                .map(|key| match key {
+                    Scalar::Cast(cast) => *cast.argument.clone(),
+                    _ => key.clone(),
+                })
+                .collect::<Vec<Scalar>>();
+            if !group_items
+                .iter()
+                .all(|item| matches!(item, Scalar::BoundColumnRef(_)))
+            {
+                return Err(ErrorCode::UnImplement(
+                    "Unsupported correlated scalar subquery, the correlated predicates must compare columns",
+                ));
+            }
+
+            aggregate.group_items = group_items;
+            Ok((
+                SExpr::create_unary(aggregate.into(), child),
+                outer_keys,
+                inner_keys,
+            ))
+        }
+        _ => Err(ErrorCode::UnImplement(
+            "Unsupported scalar subquery, only aggregation without GROUP BY is supported",
+        )),
+    }
+}
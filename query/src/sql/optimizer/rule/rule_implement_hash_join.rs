@@ -67,6 +67,7 @@ impl Rule for RuleImplementHashJoin {
             PhysicalHashJoin {
                 build_keys: logical_inner_join.right_conditions,
                 probe_keys: logical_inner_join.left_conditions,
+                join_type: logical_inner_join.join_type,
             }
             .into(),
             expression.children().to_vec(),
@@ -12,9 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::sql::optimizer::ColumnSet;
 use crate::sql::plans::BasePlan;
 use crate::sql::plans::BasePlanImpl;
 use crate::sql::plans::PlanType;
+use crate::sql::plans::Scalar;
+use crate::sql::plans::ScalarExpr;
 use crate::sql::IndexType;
 
 /// `SExpr` is abbreviation of single expression, which is a tree of relational operators.
@@ -94,6 +97,85 @@ impl SExpr {
         true
     }
 
+    /// Columns referenced by the expression but not produced by any operator of it, which are
+    /// the outer columns if the expression is a correlated subquery.
+    pub fn outer_columns(&self) -> ColumnSet {
+        let mut used_columns = ColumnSet::new();
+        let mut produced_columns = ColumnSet::new();
+        self.collect_columns(&mut used_columns, &mut produced_columns);
+        used_columns
+            .difference(&produced_columns)
+            .cloned()
+            .collect()
+    }
+
+    fn collect_columns(&self, used_columns: &mut ColumnSet, produced_columns: &mut ColumnSet) {
+        let scalars: Vec<&Scalar> = match &self.plan {
+            BasePlanImpl::LogicalGet(get) => {
+                produced_columns.extend(get.columns.iter());
+                vec![]
+            }
+            BasePlanImpl::PhysicalScan(scan) => {
+                produced_columns.extend(scan.columns.iter());
+                vec![]
+            }
+            BasePlanImpl::Project(project) => {
+                produced_columns.extend(project.items.iter().map(|item| item.index));
+                project.items.iter().map(|item| &item.expr).collect()
+            }
+            BasePlanImpl::Filter(filter) => filter.predicates.iter().collect(),
+            BasePlanImpl::Aggregate(aggregate) => {
+                if let Some(grouping_sets) = &aggregate.grouping_sets {
+                    // The nullable grouping columns and grouping id are produced by the
+                    // expansion of grouping sets.
+                    for (column, nullable_column) in grouping_sets.columns.iter() {
+                        used_columns.insert(*column);
+                        produced_columns.insert(*nullable_column);
+                    }
+                    produced_columns.insert(grouping_sets.grouping_id);
+                }
+                aggregate
+                    .group_items
+                    .iter()
+                    .chain(aggregate.aggregate_functions.iter())
+                    .collect()
+            }
+            BasePlanImpl::Sort(sort) => sort.items.iter().map(|item| &item.expr).collect(),
+            BasePlanImpl::LogicalInnerJoin(join) => join
+                .left_conditions
+                .iter()
+                .chain(join.right_conditions.iter())
+                .collect(),
+            BasePlanImpl::PhysicalHashJoin(join) => join
+                .build_keys
+                .iter()
+                .chain(join.probe_keys.iter())
+                .collect(),
+            BasePlanImpl::SetOperation(set_operation) => {
+                produced_columns.extend(set_operation.output_columns.iter());
+                vec![]
+            }
+            BasePlanImpl::CteScan(scan) => {
+                produced_columns.extend(scan.columns.iter());
+                vec![]
+            }
+            BasePlanImpl::RecursiveCte(recursive_cte) => {
+                produced_columns.extend(recursive_cte.output_columns.iter());
+                vec![]
+            }
+            BasePlanImpl::Limit(_)
+            | BasePlanImpl::MaterializedCte(_)
+            | BasePlanImpl::Pattern(_) => vec![],
+        };
+
+        for scalar in scalars {
+            used_columns.extend(scalar.used_columns());
+        }
+        for child in self.children.iter() {
+            child.collect_columns(used_columns, produced_columns);
+        }
+    }
+
     // pub fn compute_relational_prop(&self) -> RelationalProperty {
     //     if self.plan.is_logical() {
     //         self.plan.compute_relational_prop(self).unwrap()
@@ -15,7 +15,9 @@
 // Borrow from apache/arrow/rust/datafusion/src/sql/sql_parser
 // See notice.md
 
+use sqlparser::ast::DataType;
 use sqlparser::keywords::Keyword;
+use sqlparser::parser::Parser;
 use sqlparser::parser::ParserError;
 use sqlparser::tokenizer::Token;
 use sqlparser::tokenizer::Word;
@@ -24,9 +26,19 @@ use crate::parser_err;
 use crate::sql::statements::DfAlterUDF;
 use crate::sql::statements::DfCreateUDF;
 use crate::sql::statements::DfDropUDF;
+use crate::sql::statements::DfUDFLanguage;
+use crate::sql::statements::DfUDFReturnType;
 use crate::sql::DfParser;
 use crate::sql::DfStatement;
 
+struct DfUDFBody {
+    parameters: Vec<String>,
+    parameter_types: Vec<DataType>,
+    return_type: DfUDFReturnType,
+    language: DfUDFLanguage,
+    definition: String,
+}
+
 impl<'a> DfParser<'a> {
     fn parse_udf_parameters(&mut self) -> Result<Vec<String>, ParserError> {
         let mut params = vec![];
@@ -107,24 +119,124 @@ impl<'a> DfParser<'a> {
         Ok(definition)
     }
 
+    // `(<name> <type>, ...)`
+    fn parse_udf_typed_list(&mut self, kind: &str) -> Result<Vec<(String, DataType)>, ParserError> {
+        let mut list: Vec<(String, DataType)> = vec![];
+
+        self.parser.expect_token(&Token::LParen)?;
+        if self.parser.consume_token(&Token::RParen) {
+            return Ok(list);
+        }
+
+        loop {
+            let name = self.parser.parse_identifier()?.value;
+            if list.iter().any(|(exists, _)| exists == &name) {
+                return parser_err!(format!(
+                    "Duplicate {} is not allowed, keep only one: {}",
+                    kind, &name
+                ));
+            }
+
+            let data_type = self.parser.parse_data_type()?;
+            list.push((name, data_type));
+
+            if !self.parser.consume_token(&Token::Comma) {
+                break;
+            }
+        }
+
+        self.parser.expect_token(&Token::RParen)?;
+        Ok(list)
+    }
+
+    // `LANGUAGE { SQL | WASM }`, defaults to SQL.
+    fn parse_udf_language(&mut self) -> Result<DfUDFLanguage, ParserError> {
+        if !self.consume_token("LANGUAGE") {
+            return Ok(DfUDFLanguage::Sql);
+        }
+
+        let language = self.parser.parse_identifier()?;
+        match language.value.to_uppercase().as_str() {
+            "SQL" => Ok(DfUDFLanguage::Sql),
+            "WASM" => Ok(DfUDFLanguage::Wasm),
+            _ => parser_err!(format!(
+                "Unsupported function language: {}, expected SQL or WASM",
+                language.value
+            )),
+        }
+    }
+
+    // Either the untyped `AS (<parameter>, ...) -> <definition expr>`,
+    // or the typed `(<parameter> <type>, ...) RETURNS <type> AS <definition expr>`,
+    // or the WebAssembly `(<parameter> <type>, ...) RETURNS <type> LANGUAGE WASM AS '<base64 module>'`,
+    // or the table-valued `(<parameter> <type>, ...) RETURNS TABLE (<column> <type>, ...) AS <query>`.
+    fn parse_udf_body(&mut self, desc_token: &str) -> Result<DfUDFBody, ParserError> {
+        if self.parser.parse_keyword(Keyword::AS) {
+            let parameters = self.parse_udf_parameters()?;
+            let definition = self.parse_udf_definition_expr(vec![desc_token])?;
+            return Ok(DfUDFBody {
+                parameters,
+                parameter_types: vec![],
+                return_type: DfUDFReturnType::Untyped,
+                language: DfUDFLanguage::Sql,
+                definition,
+            });
+        }
+
+        let (parameters, parameter_types) =
+            self.parse_udf_typed_list("parameter")?.into_iter().unzip();
+        if !self.consume_token("RETURNS") {
+            return self.expected("RETURNS or AS", self.parser.peek_token());
+        }
+
+        if self.consume_token("TABLE") {
+            let columns = self.parse_udf_typed_list("column")?;
+            self.parser.expect_keyword(Keyword::AS)?;
+            let definition = self.parser.parse_query()?.to_string();
+            Ok(DfUDFBody {
+                parameters,
+                parameter_types,
+                return_type: DfUDFReturnType::Table(columns),
+                language: DfUDFLanguage::Sql,
+                definition,
+            })
+        } else {
+            let return_type = self.parser.parse_data_type()?;
+            let language = self.parse_udf_language()?;
+            self.parser.expect_keyword(Keyword::AS)?;
+            let definition = match language {
+                DfUDFLanguage::Sql => self.parser.parse_expr()?.to_string(),
+                DfUDFLanguage::Wasm => self.parser.parse_literal_string()?,
+            };
+            Ok(DfUDFBody {
+                parameters,
+                parameter_types,
+                return_type: DfUDFReturnType::Scalar(return_type),
+                language,
+                definition,
+            })
+        }
+    }
+
     pub(crate) fn parse_create_udf(&mut self) -> Result<DfStatement<'a>, ParserError> {
         let if_not_exists =
             self.parser
                 .parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
 
         let udf_name = self.parser.parse_literal_string()?;
-        self.parser.expect_keyword(Keyword::AS)?;
 
         let desc_token = "DESC";
-        let parameters = self.parse_udf_parameters()?;
-        let definition = self.parse_udf_definition_expr(vec![desc_token])?;
+        let body = self.parse_udf_body(desc_token)?;
 
         let description = self.parse_udf_desc(desc_token)?;
         let create_udf = DfCreateUDF {
             if_not_exists,
             udf_name,
-            parameters,
-            definition,
+            parameters: body.parameters,
+            parameter_types: body.parameter_types,
+            return_type: body.return_type,
+            language: body.language,
+            definition: body.definition,
             description,
         };
 
@@ -133,18 +245,18 @@ impl<'a> DfParser<'a> {
 
     pub(crate) fn parse_alter_udf(&mut self) -> Result<DfStatement<'a>, ParserError> {
         let udf_name = self.parser.parse_literal_string()?;
-        let as_token = Token::make_keyword("AS");
-        self.parser.expect_token(&as_token)?;
 
         let desc_token = "DESC";
-        let parameters = self.parse_udf_parameters()?;
-        let definition = self.parse_udf_definition_expr(vec![desc_token])?;
+        let body = self.parse_udf_body(desc_token)?;
 
         let description = self.parse_udf_desc(desc_token)?;
         let update_udf = DfAlterUDF {
             udf_name,
-            parameters,
-            definition,
+            parameters: body.parameters,
+            parameter_types: body.parameter_types,
+            return_type: body.return_type,
+            language: body.language,
+            definition: body.definition,
             description,
         };
 
@@ -155,9 +267,22 @@ impl<'a> DfParser<'a> {
         let if_exists = self.parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
         let udf_name = self.parser.parse_literal_string()?;
 
+        // `DROP FUNCTION f(<type>, ...)` drops only one overload of the typed function.
+        let parameter_types = if self.parser.consume_token(&Token::LParen) {
+            let mut parameter_types = vec![];
+            if !self.parser.consume_token(&Token::RParen) {
+                parameter_types = self.parser.parse_comma_separated(Parser::parse_data_type)?;
+                self.parser.expect_token(&Token::RParen)?;
+            }
+            Some(parameter_types)
+        } else {
+            None
+        };
+
         let drop_udf = DfDropUDF {
             if_exists,
             udf_name,
+            parameter_types,
         };
         Ok(DfStatement::DropUDF(drop_udf))
     }
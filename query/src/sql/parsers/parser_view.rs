@@ -20,9 +20,11 @@ use sqlparser::parser::ParserError;
 
 use crate::parser_err;
 use crate::sql::statements::DfAlterView;
+use crate::sql::statements::DfCreateMaterializedView;
 use crate::sql::statements::DfCreateView;
 use crate::sql::statements::DfDropView;
 use crate::sql::statements::DfQueryStatement;
+use crate::sql::statements::DfRefreshMaterializedView;
 use crate::sql::DfParser;
 use crate::sql::DfStatement;
 
@@ -79,4 +81,44 @@ impl<'a> DfParser<'a> {
             parser_err!("need `AS` after VIEW NAME")
         }
     }
+
+    // Create materialized view.
+    // syntax: "CREATE MATERIALIZED VIEW [IF NOT EXISTS] mv AS SELECT ..."
+    pub(crate) fn parse_create_materialized_view(
+        &mut self,
+    ) -> Result<DfStatement<'a>, ParserError> {
+        self.expect_token("VIEW")?;
+        let if_not_exists =
+            self.parser
+                .parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
+        let name = self.parser.parse_object_name()?;
+
+        if self.consume_token("AS") {
+            let subquery = self.parser.parse_query()?;
+            let query = DfQueryStatement::try_from(subquery.clone())?;
+            let create = DfCreateMaterializedView {
+                if_not_exists,
+                name,
+                subquery,
+                query,
+            };
+            Ok(DfStatement::CreateMaterializedView(create))
+        } else {
+            parser_err!("need `AS` after MATERIALIZED VIEW NAME")
+        }
+    }
+
+    // Rebuild materialized view.
+    // syntax: "REFRESH MATERIALIZED VIEW mv"
+    pub(crate) fn parse_refresh_materialized_view(
+        &mut self,
+    ) -> Result<DfStatement<'a>, ParserError> {
+        self.expect_token("REFRESH")?;
+        self.expect_token("MATERIALIZED")?;
+        self.expect_token("VIEW")?;
+        let name = self.parser.parse_object_name()?;
+        Ok(DfStatement::RefreshMaterializedView(
+            DfRefreshMaterializedView { name },
+        ))
+    }
 }
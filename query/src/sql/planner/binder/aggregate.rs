@@ -13,7 +13,10 @@
 // limitations under the License.
 
 use common_ast::ast::Expr;
+use common_ast::ast::GroupBy;
+use common_ast::ast::SelectTarget;
 use common_ast::parser::error::DisplayError;
+use common_datavalues::prelude::*;
 use common_exception::ErrorCode;
 use common_exception::Result;
 
@@ -24,6 +27,8 @@ use crate::sql::binder::Binder;
 use crate::sql::binder::ColumnBinding;
 use crate::sql::optimizer::SExpr;
 use crate::sql::plans::AggregatePlan;
+use crate::sql::plans::BoundColumnRef;
+use crate::sql::plans::GroupingSets;
 use crate::sql::plans::Scalar;
 use crate::sql::plans::ScalarExpr;
 use crate::sql::BindContext;
@@ -37,6 +42,29 @@ pub struct AggregateInfo {
     pub available_aliases: Vec<ColumnBinding>,
 }
 
+/// Grouping columns of `GROUPING SETS`, `ROLLUP` or `CUBE`.
+///
+/// Out of aggregate functions, a grouping column is replaced by a nullable column, which is
+/// `NULL` in the rows of the grouping sets that don't contain the grouping column.
+#[derive(Clone, PartialEq, Debug)]
+pub struct GroupingSetsInfo {
+    /// Grouping columns and their nullable counterparts
+    pub columns: Vec<(ColumnBinding, ColumnBinding)>,
+
+    /// Grouping id of each row, the i-th bit is set if the i-th grouping column
+    /// isn't contained by the grouping set of the row
+    pub grouping_id: ColumnBinding,
+
+    /// Positions of grouping columns of each grouping set
+    pub sets: Vec<Vec<usize>>,
+}
+
+/// `CUBE` of n items is expanded into 2^n grouping sets, so the number of items is limited.
+const MAX_CUBE_ITEMS: usize = 12;
+
+/// The grouping id is a `UInt32` bit mask of grouping columns.
+const MAX_GROUPING_COLUMNS: usize = 32;
+
 impl<'a> Binder {
     /// We have supported two kinds of `group by` items:
     ///
@@ -85,7 +113,8 @@ impl<'a> Binder {
         group_by_expr: &[Expr<'a>],
         agg_info: &AggregateInfo,
     ) -> Result<(SExpr, BindContext)> {
-        let scalar_binder = ScalarBinder::new(input_context, self.ctx.clone());
+        let scalar_binder =
+            ScalarBinder::new(input_context, self.ctx.clone(), self.metadata.clone());
         let mut group_expr = Vec::with_capacity(group_by_expr.len());
         for expr in group_by_expr.iter() {
             let (scalar_expr, _) = scalar_binder.bind_expr(expr).await.or_else(|e| {
@@ -115,11 +144,200 @@ impl<'a> Binder {
             group_expr.push(scalar_expr);
         }
 
+        // Group by the nullable grouping columns and the grouping id of grouping sets, which
+        // are produced by expanding the input rows.
+        let grouping_sets = input_context.grouping_sets.as_ref().map(|info| {
+            for (_, nullable_column) in info.columns.iter() {
+                group_expr.push(
+                    BoundColumnRef {
+                        column: nullable_column.clone(),
+                    }
+                    .into(),
+                );
+            }
+            group_expr.push(
+                BoundColumnRef {
+                    column: info.grouping_id.clone(),
+                }
+                .into(),
+            );
+            GroupingSets {
+                grouping_id: info.grouping_id.index,
+                columns: info
+                    .columns
+                    .iter()
+                    .map(|(column, nullable_column)| (column.index, nullable_column.index))
+                    .collect(),
+                sets: info.sets.clone(),
+            }
+        });
+
         let aggregate_plan = AggregatePlan {
             group_items: group_expr,
             aggregate_functions: agg_info.aggregate_functions.clone(),
+            grouping_sets,
         };
         let new_expr = SExpr::create_unary(aggregate_plan.into(), child);
         Ok((new_expr, output_context))
     }
+
+    /// Bind `GROUPING SETS`, `ROLLUP` and `CUBE`, which are normalized into grouping sets:
+    ///
+    ///   - `ROLLUP (a, b)` is equivalent to `GROUPING SETS ((a, b), (a), ())`.
+    ///   - `CUBE (a, b)` is equivalent to `GROUPING SETS ((a, b), (a), (b), ())`.
+    ///
+    /// Only columns(or aliases of columns) are supported as grouping items. The grouping columns
+    /// are registered in `input_context` before normalizing the select list, so they can be
+    /// replaced by their nullable counterparts out of aggregate functions.
+    pub(super) async fn bind_grouping_sets(
+        &mut self,
+        input_context: &mut BindContext,
+        select_list: &[SelectTarget<'a>],
+        group_by: &GroupBy<'a>,
+    ) -> Result<()> {
+        let expr_sets: Vec<Vec<&Expr<'a>>> = match group_by {
+            GroupBy::Normal(exprs) => vec![exprs.iter().collect()],
+            GroupBy::GroupingSets(sets) => sets.iter().map(|set| set.iter().collect()).collect(),
+            GroupBy::Rollup(exprs) => (0..=exprs.len())
+                .rev()
+                .map(|len| exprs[..len].iter().collect())
+                .collect(),
+            GroupBy::Cube(exprs) => {
+                if exprs.len() > MAX_CUBE_ITEMS {
+                    return Err(ErrorCode::SemanticError(format!(
+                        "CUBE supports at most {} grouping items",
+                        MAX_CUBE_ITEMS
+                    )));
+                }
+                (0..1usize << exprs.len())
+                    .rev()
+                    .map(|mask| {
+                        exprs
+                            .iter()
+                            .enumerate()
+                            .filter(|(i, _)| mask & (1 << (exprs.len() - 1 - i)) != 0)
+                            .map(|(_, expr)| expr)
+                            .collect()
+                    })
+                    .collect()
+            }
+        };
+
+        let mut grouping_columns: Vec<ColumnBinding> = vec![];
+        let mut sets = Vec::with_capacity(expr_sets.len());
+        {
+            let scalar_binder =
+                ScalarBinder::new(input_context, self.ctx.clone(), self.metadata.clone());
+            for expr_set in expr_sets.iter() {
+                let mut set = vec![];
+                for expr in expr_set.iter() {
+                    let column =
+                        Self::bind_grouping_column(&scalar_binder, expr, select_list).await?;
+                    let position = match grouping_columns
+                        .iter()
+                        .position(|grouping_column| grouping_column.index == column.index)
+                    {
+                        Some(position) => position,
+                        None => {
+                            grouping_columns.push(column);
+                            grouping_columns.len() - 1
+                        }
+                    };
+                    if !set.contains(&position) {
+                        set.push(position);
+                    }
+                }
+                set.sort_unstable();
+                sets.push(set);
+            }
+        }
+
+        if grouping_columns.len() > MAX_GROUPING_COLUMNS {
+            return Err(ErrorCode::SemanticError(format!(
+                "GROUPING SETS, ROLLUP and CUBE support at most {} grouping columns",
+                MAX_GROUPING_COLUMNS
+            )));
+        }
+
+        let mut columns = Vec::with_capacity(grouping_columns.len());
+        for column in grouping_columns.into_iter() {
+            let data_type = wrap_nullable(&column.data_type);
+            let index = self.metadata.write().add_column(
+                column.column_name.clone(),
+                data_type.clone(),
+                None,
+            );
+            let nullable_column = ColumnBinding {
+                table_name: column.table_name.clone(),
+                column_name: column.column_name.clone(),
+                index,
+                data_type,
+                visible: column.visible,
+                scalar: None,
+            };
+            columns.push((column, nullable_column));
+        }
+
+        let grouping_id_index =
+            self.metadata
+                .write()
+                .add_column("_grouping_id".to_string(), u32::to_data_type(), None);
+        let grouping_id = ColumnBinding {
+            table_name: None,
+            column_name: "_grouping_id".to_string(),
+            index: grouping_id_index,
+            data_type: u32::to_data_type(),
+            visible: false,
+            scalar: None,
+        };
+
+        input_context.grouping_sets = Some(GroupingSetsInfo {
+            columns,
+            grouping_id,
+            sets,
+        });
+        Ok(())
+    }
+
+    /// Resolve a grouping item of grouping sets into a column, which can be an alias of a
+    /// column in select list if it cannot be resolved in current scope.
+    async fn bind_grouping_column<'b>(
+        scalar_binder: &ScalarBinder<'b>,
+        expr: &Expr<'b>,
+        select_list: &[SelectTarget<'b>],
+    ) -> Result<ColumnBinding> {
+        let scalar = match scalar_binder.bind_expr(expr).await {
+            Ok((scalar, _)) => scalar,
+            Err(e) => {
+                let name = expr.to_string().to_lowercase();
+                let aliased_exprs: Vec<&Expr> = select_list
+                    .iter()
+                    .filter_map(|target| match target {
+                        SelectTarget::AliasedExpr {
+                            expr,
+                            alias: Some(alias),
+                        } if alias.name.to_lowercase() == name => Some(expr.as_ref()),
+                        _ => None,
+                    })
+                    .collect();
+                match aliased_exprs.as_slice() {
+                    [] => return Err(e),
+                    [aliased_expr] => scalar_binder.bind_expr(aliased_expr).await?.0,
+                    _ => {
+                        return Err(ErrorCode::SemanticError(
+                            expr.span()
+                                .display_error(format!("GROUP BY \"{}\" is ambiguous", expr)),
+                        ))
+                    }
+                }
+            }
+        };
+
+        match scalar {
+            Scalar::BoundColumnRef(BoundColumnRef { column }) => Ok(column),
+            _ => Err(ErrorCode::SemanticError(expr.span().display_error(
+                "GROUPING SETS, ROLLUP and CUBE only support columns".to_string(),
+            ))),
+        }
+    }
 }
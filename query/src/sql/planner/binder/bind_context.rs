@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+
 use common_ast::ast::Identifier;
 use common_ast::ast::TableAlias;
 use common_ast::parser::error::DisplayError as _;
@@ -19,6 +21,7 @@ use common_datavalues::prelude::*;
 use common_exception::ErrorCode;
 use common_exception::Result;
 
+use crate::sql::binder::aggregate::GroupingSetsInfo;
 use crate::sql::common::IndexType;
 use crate::sql::plans::Scalar;
 
@@ -49,8 +52,14 @@ pub struct ColumnBinding {
 /// `BindContext` stores all the free variables in a query and tracks the context of binding procedure.
 #[derive(Clone, Default, Debug)]
 pub struct BindContext {
-    _parent: Option<Box<BindContext>>,
+    parent: Option<Box<BindContext>>,
     pub columns: Vec<ColumnBinding>,
+
+    /// Common table expressions visible in current scope, maps CTE name to its index in `Metadata`.
+    pub ctes_map: HashMap<String, IndexType>,
+
+    /// Grouping columns of `GROUPING SETS`, `ROLLUP` or `CUBE` in current scope(if exists).
+    pub grouping_sets: Option<GroupingSetsInfo>,
 }
 
 impl BindContext {
@@ -60,8 +69,10 @@ impl BindContext {
 
     pub fn with_parent(parent: Box<BindContext>) -> Self {
         BindContext {
-            _parent: Some(parent),
+            parent: Some(parent),
             columns: vec![],
+            ctes_map: HashMap::new(),
+            grouping_sets: None,
         }
     }
 
@@ -70,6 +81,14 @@ impl BindContext {
         Self::with_parent(Box::new(self))
     }
 
+    pub fn parent(&self) -> Option<Box<BindContext>> {
+        self.parent.clone()
+    }
+
+    pub fn set_parent(&mut self, parent: Option<Box<BindContext>>) {
+        self.parent = parent;
+    }
+
     /// Returns all column bindings in current scope.
     pub fn all_column_bindings(&self) -> &[ColumnBinding] {
         &self.columns
@@ -79,6 +98,21 @@ impl BindContext {
         self.columns.push(column_binding);
     }
 
+    pub fn add_cte(&mut self, name: String, cte_index: IndexType) {
+        self.ctes_map.insert(name, cte_index);
+    }
+
+    /// Try to find a common table expression with given name in current scope and its parents.
+    pub fn resolve_cte(&self, name: &str) -> Option<IndexType> {
+        match self.ctes_map.get(name) {
+            Some(cte_index) => Some(*cte_index),
+            None => self
+                .parent
+                .as_ref()
+                .and_then(|parent| parent.resolve_cte(name)),
+        }
+    }
+
     /// Apply table alias like `SELECT * FROM t AS t1(a, b, c)`.
     /// This method will rename column bindings according to table alias.
     pub fn apply_table_alias(&mut self, alias: &TableAlias) -> Result<()> {
@@ -106,12 +140,11 @@ impl BindContext {
         table: Option<String>,
         column: &Identifier,
     ) -> Result<ColumnBinding> {
-        // TODO: lookup parent context to support correlated subquery
         let mut result = vec![];
-        if let Some(table) = table {
+        if let Some(table) = &table {
             for column_binding in self.columns.iter() {
                 if let Some(table_name) = &column_binding.table_name {
-                    if table_name == &table
+                    if table_name == table
                         && column_binding.column_name == column.name
                         && column_binding.visible
                     {
@@ -128,6 +161,10 @@ impl BindContext {
         }
 
         if result.is_empty() {
+            // Lookup parent context to resolve outer columns of a correlated subquery
+            if let Some(parent) = &self.parent {
+                return parent.resolve_column(table, column);
+            }
             Err(ErrorCode::SemanticError(
                 column
                     .span
@@ -144,6 +181,20 @@ impl BindContext {
         }
     }
 
+    /// Replace a grouping column of grouping sets with its nullable counterpart, which is the
+    /// column visible out of aggregate functions. Other columns are returned as they are.
+    pub fn resolve_grouping_column(&self, column: ColumnBinding) -> ColumnBinding {
+        match &self.grouping_sets {
+            Some(grouping_sets) => grouping_sets
+                .columns
+                .iter()
+                .find(|(grouping_column, _)| grouping_column.index == column.index)
+                .map(|(_, nullable_column)| nullable_column.clone())
+                .unwrap_or(column),
+            None => column,
+        }
+    }
+
     /// Get result columns of current context in order.
     /// For example, a query `SELECT b, a AS b FROM t` has `[(index_of(b), "b"), index_of(a), "b"]` as
     /// its result columns.
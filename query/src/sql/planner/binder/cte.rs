@@ -0,0 +1,248 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+
+use common_ast::ast::Cte;
+use common_ast::ast::Query;
+use common_ast::ast::SetExpr;
+use common_ast::ast::SetOperator;
+use common_ast::ast::TableAlias;
+use common_ast::ast::With;
+use common_ast::parser::error::DisplayError as _;
+use common_exception::ErrorCode;
+use common_exception::Result;
+
+use crate::sql::binder::Binder;
+use crate::sql::optimizer::SExpr;
+use crate::sql::planner::binder::BindContext;
+use crate::sql::planner::binder::ColumnBinding;
+use crate::sql::plans::CteScanPlan;
+use crate::sql::plans::RecursiveCtePlan;
+use crate::sql::IndexType;
+
+impl<'a> Binder {
+    /// Bind a `WITH` clause, returns the `BindContext` in which the common table expressions
+    /// are visible.
+    ///
+    /// A CTE can reference the CTEs declared before it, and itself if `RECURSIVE` is specified.
+    pub(super) async fn bind_with(
+        &mut self,
+        bind_context: &BindContext,
+        with: &With,
+    ) -> Result<BindContext> {
+        let mut with_context = bind_context.clone();
+        let mut names = HashSet::with_capacity(with.ctes.len());
+        for cte in with.ctes.iter() {
+            let name = cte.alias.name.name.to_lowercase();
+            if !names.insert(name.clone()) {
+                return Err(ErrorCode::SemanticError(cte.alias.name.span.display_error(
+                    format!("WITH query name \"{}\" specified more than once", name),
+                )));
+            }
+
+            let cte_index = if with.recursive {
+                self.bind_recursive_cte(&with_context, &name, cte).await?
+            } else {
+                self.bind_cte(&with_context, &name, &cte.alias, &cte.query)
+                    .await?
+            };
+            with_context.add_cte(name, cte_index);
+        }
+
+        Ok(with_context)
+    }
+
+    async fn bind_cte(
+        &mut self,
+        bind_context: &BindContext,
+        name: &str,
+        alias: &TableAlias,
+        query: &Query,
+    ) -> Result<IndexType> {
+        let (s_expr, cte_context) = self.bind_query(bind_context, query).await?;
+        let columns = cte_context
+            .all_column_bindings()
+            .iter()
+            .map(|column| column.index)
+            .collect();
+        let column_names = Self::cte_column_names(cte_context, alias)?;
+
+        Ok(self
+            .metadata
+            .write()
+            .add_cte(name.to_string(), Some(s_expr), columns, column_names))
+    }
+
+    /// Bind a recursive CTE like `t(n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM t WHERE n < 10)`.
+    ///
+    /// The left side of the `UNION` is the anchor, the right side is the recursive term, which
+    /// reads the rows returned by its previous iteration. The output columns are typed after
+    /// the anchor. A CTE that doesn't reference itself is bound as a non-recursive CTE.
+    async fn bind_recursive_cte(
+        &mut self,
+        bind_context: &BindContext,
+        name: &str,
+        cte: &Cte,
+    ) -> Result<IndexType> {
+        let (all, left, right) = match &cte.query {
+            Query {
+                with: None,
+                body:
+                    SetExpr::SetOperation {
+                        op: SetOperator::Union,
+                        all,
+                        left,
+                        right,
+                    },
+                order_by,
+                limit,
+                offset: None,
+                ..
+            } if order_by.is_empty() && limit.is_empty() => (*all, left, right),
+            _ => {
+                return self
+                    .bind_cte(bind_context, name, &cte.alias, &cte.query)
+                    .await;
+            }
+        };
+
+        let (anchor_expr, anchor_context) = self.bind_set_expr(bind_context, left, &[]).await?;
+        let anchor_bindings = anchor_context.all_column_bindings().to_vec();
+        let column_names = Self::cte_column_names(anchor_context, &cte.alias)?;
+
+        let mut output_columns = Vec::with_capacity(anchor_bindings.len());
+        for (binding, column_name) in anchor_bindings.iter().zip(column_names.iter()) {
+            output_columns.push(self.metadata.write().add_column(
+                column_name.clone(),
+                binding.data_type.clone(),
+                None,
+            ));
+        }
+        let cte_index = self.metadata.write().add_cte(
+            name.to_string(),
+            None,
+            output_columns.clone(),
+            column_names,
+        );
+
+        let mut recursive_context = bind_context.clone();
+        recursive_context.add_cte(name.to_string(), cte_index);
+        let (recursive_expr, recursive_context) =
+            self.bind_set_expr(&recursive_context, right, &[]).await?;
+        let recursive_references = self.metadata.read().cte(cte_index).recursive_references;
+        if recursive_references == 0 {
+            return self
+                .bind_cte(bind_context, name, &cte.alias, &cte.query)
+                .await;
+        }
+
+        let recursive_bindings = recursive_context.all_column_bindings();
+        if recursive_bindings.len() != anchor_bindings.len() {
+            return Err(ErrorCode::SemanticError(format!(
+                "Each side of a recursive query must have the same number of columns, but got {} and {}",
+                anchor_bindings.len(),
+                recursive_bindings.len()
+            )));
+        }
+
+        let mut recursive_items = vec![];
+        let mut recursive_columns = Vec::with_capacity(recursive_bindings.len());
+        for (recursive_binding, anchor_binding) in
+            recursive_bindings.iter().zip(anchor_bindings.iter())
+        {
+            recursive_columns.push(self.coerce_column(
+                recursive_binding,
+                &anchor_binding.data_type,
+                &mut recursive_items,
+            ));
+        }
+
+        let recursive_cte = RecursiveCtePlan {
+            cte_index,
+            all,
+            anchor_columns: anchor_bindings.iter().map(|column| column.index).collect(),
+            recursive_columns,
+            output_columns,
+        };
+        let definition = SExpr::create_binary(
+            recursive_cte.into(),
+            anchor_expr,
+            Self::project_casts(recursive_expr, recursive_items),
+        );
+        self.metadata
+            .write()
+            .set_cte_definition(cte_index, definition);
+
+        Ok(cte_index)
+    }
+
+    /// Bind a reference to a CTE, every reference has its own output columns.
+    pub(super) fn bind_cte_scan(
+        &mut self,
+        cte_index: IndexType,
+        alias: &Option<TableAlias>,
+    ) -> Result<(SExpr, BindContext)> {
+        let mut metadata = self.metadata.write();
+        metadata.add_cte_reference(cte_index);
+
+        let entry = metadata.cte(cte_index);
+        let cte_name = entry.name.clone();
+        let cte_columns = entry
+            .columns
+            .iter()
+            .zip(entry.column_names.iter())
+            .map(|(column, column_name)| {
+                (
+                    column_name.clone(),
+                    metadata.column(*column).data_type.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mut bind_context = BindContext::new();
+        let mut columns = Vec::with_capacity(cte_columns.len());
+        for (column_name, data_type) in cte_columns {
+            let index = metadata.add_column(column_name.clone(), data_type.clone(), None);
+            columns.push(index);
+            bind_context.add_column_binding(ColumnBinding {
+                table_name: Some(cte_name.clone()),
+                column_name,
+                index,
+                data_type,
+                visible: true,
+                scalar: None,
+            });
+        }
+
+        if let Some(alias) = alias {
+            bind_context.apply_table_alias(alias)?;
+        }
+
+        Ok((
+            SExpr::create_leaf(CteScanPlan { cte_index, columns }.into()),
+            bind_context,
+        ))
+    }
+
+    /// Returns the output column names of a CTE, renamed by the column aliases if specified.
+    fn cte_column_names(mut cte_context: BindContext, alias: &TableAlias) -> Result<Vec<String>> {
+        cte_context.apply_table_alias(alias)?;
+        Ok(cte_context
+            .all_column_bindings()
+            .iter()
+            .map(|column| column.column_name.clone())
+            .collect())
+    }
+}
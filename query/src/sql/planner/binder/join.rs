@@ -31,7 +31,9 @@ use crate::sql::optimizer::ColumnSet;
 use crate::sql::optimizer::SExpr;
 use crate::sql::planner::binder::scalar::ScalarBinder;
 use crate::sql::planner::binder::Binder;
+use crate::sql::planner::metadata::MetadataRef;
 use crate::sql::plans::FilterPlan;
+use crate::sql::plans::JoinType;
 use crate::sql::plans::LogicalInnerJoin;
 use crate::sql::plans::Scalar;
 use crate::sql::plans::ScalarExpr;
@@ -46,9 +48,8 @@ impl<'a> Binder {
     ) -> Result<(SExpr, BindContext)> {
         let (left_child, left_context) =
             self.bind_table_reference(bind_context, &join.left).await?;
-        let (right_child, right_context) = self
-            .bind_table_reference(&left_context, &join.right)
-            .await?;
+        let (right_child, right_context) =
+            self.bind_table_reference(bind_context, &join.right).await?;
 
         check_duplicate_join_tables(&left_context, &right_context)?;
 
@@ -65,6 +66,7 @@ impl<'a> Binder {
         let mut other_conditions: Vec<Scalar> = vec![];
         let join_condition_resolver = JoinConditionResolver::new(
             self.ctx.clone(),
+            self.metadata.clone(),
             &left_context,
             &right_context,
             &bind_context,
@@ -120,6 +122,7 @@ impl<'a> Binder {
         let inner_join = LogicalInnerJoin {
             left_conditions,
             right_conditions,
+            join_type: JoinType::Inner,
         };
         let expr = SExpr::create_binary(inner_join.into(), left_child, right_child);
 
@@ -160,6 +163,7 @@ pub fn check_duplicate_join_tables(
 
 struct JoinConditionResolver<'a> {
     ctx: Arc<QueryContext>,
+    metadata: MetadataRef,
 
     left_context: &'a BindContext,
     right_context: &'a BindContext,
@@ -170,6 +174,7 @@ struct JoinConditionResolver<'a> {
 impl<'a> JoinConditionResolver<'a> {
     pub fn new(
         ctx: Arc<QueryContext>,
+        metadata: MetadataRef,
         left_context: &'a BindContext,
         right_context: &'a BindContext,
         join_context: &'a BindContext,
@@ -177,6 +182,7 @@ impl<'a> JoinConditionResolver<'a> {
     ) -> Self {
         Self {
             ctx,
+            metadata,
             left_context,
             right_context,
             join_context,
@@ -220,7 +226,8 @@ impl<'a> JoinConditionResolver<'a> {
         right_join_conditions: &mut Vec<Scalar>,
         other_join_conditions: &mut Vec<Scalar>,
     ) -> Result<()> {
-        let scalar_binder = ScalarBinder::new(self.join_context, self.ctx.clone());
+        let scalar_binder =
+            ScalarBinder::new(self.join_context, self.ctx.clone(), self.metadata.clone());
         let (scalar, _) = scalar_binder.bind_expr(condition).await?;
         let conjunctions = split_conjunctions(&scalar);
 
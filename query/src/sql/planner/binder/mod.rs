@@ -23,17 +23,20 @@ use crate::catalogs::CatalogManager;
 use crate::sessions::QueryContext;
 use crate::sql::optimizer::SExpr;
 use crate::sql::planner::metadata::Metadata;
+use crate::sql::planner::metadata::MetadataRef;
 use crate::storages::Table;
 
 mod aggregate;
 mod bind_context;
+mod cte;
 mod join;
 mod limit;
 mod project;
 mod scalar;
-mod scalar_common;
+pub(crate) mod scalar_common;
 mod scalar_visitor;
 mod select;
+mod set_operation;
 mod sort;
 
 /// Binder is responsible to transform AST of a query into a canonical logical SExpr.
@@ -46,22 +49,27 @@ mod sort;
 pub struct Binder {
     ctx: Arc<QueryContext>,
     catalogs: Arc<CatalogManager>,
-    metadata: Metadata,
+    metadata: MetadataRef,
 }
 
 impl<'a> Binder {
-    pub fn new(ctx: Arc<QueryContext>, catalogs: Arc<CatalogManager>) -> Self {
+    pub fn new(
+        ctx: Arc<QueryContext>,
+        catalogs: Arc<CatalogManager>,
+        metadata: MetadataRef,
+    ) -> Self {
         Binder {
             ctx,
             catalogs,
-            metadata: Metadata::create(),
+            metadata,
         }
     }
 
     pub async fn bind(mut self, stmt: &Statement<'a>) -> Result<BindResult> {
         let init_bind_context = BindContext::new();
         let (s_expr, bind_context) = self.bind_statement(&init_bind_context, stmt).await?;
-        Ok(BindResult::create(s_expr, bind_context, self.metadata))
+        let metadata = self.metadata.read().clone();
+        Ok(BindResult::create(s_expr, bind_context, metadata))
     }
 
     async fn bind_statement(
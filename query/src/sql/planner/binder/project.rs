@@ -85,8 +85,9 @@ impl<'a> Binder {
                         let indirection = &names[0];
                         match indirection {
                             Indirection::Identifier(ident) => {
-                                let mut column_binding =
-                                    input_context.resolve_column(None, ident)?;
+                                let mut column_binding = input_context.resolve_grouping_column(
+                                    input_context.resolve_column(None, ident)?,
+                                );
                                 column_binding.column_name = ident.name.clone();
                                 output_context.add_column_binding(column_binding);
                             }
@@ -94,7 +95,10 @@ impl<'a> Binder {
                                 // Expands wildcard star, for example we have a table `t(a INT, b INT)`:
                                 // The query `SELECT * FROM t` will be expanded into `SELECT t.a, t.b FROM t`
                                 for column_binding in input_context.all_column_bindings() {
-                                    output_context.add_column_binding(column_binding.clone());
+                                    output_context.add_column_binding(
+                                        input_context
+                                            .resolve_grouping_column(column_binding.clone()),
+                                    );
                                 }
                             }
                         }
@@ -104,7 +108,8 @@ impl<'a> Binder {
                     }
                 }
                 SelectTarget::AliasedExpr { expr, alias } => {
-                    let scalar_binder = ScalarBinder::new(input_context, self.ctx.clone());
+                    let scalar_binder =
+                        ScalarBinder::new(input_context, self.ctx.clone(), self.metadata.clone());
                     let (bound_expr, data_type) = scalar_binder.bind_expr(expr).await?;
 
                     // If alias is not specified, we will generate a name for the scalar expression.
@@ -123,7 +128,7 @@ impl<'a> Binder {
                             scalar: Some(Box::new(bound_expr.clone())),
                         },
                         _ => {
-                            let index = self.metadata.add_column(
+                            let index = self.metadata.write().add_column(
                                 expr_name.clone(),
                                 data_type.clone(),
                                 None,
@@ -16,6 +16,7 @@ use std::sync::Arc;
 
 use async_recursion::async_recursion;
 use common_ast::ast::Expr;
+use common_ast::ast::GroupBy;
 use common_ast::ast::Indirection;
 use common_ast::ast::OrderByExpr;
 use common_ast::ast::Query;
@@ -51,14 +52,18 @@ impl<'a> Binder {
         bind_context: &BindContext,
         query: &Query,
     ) -> Result<(SExpr, BindContext)> {
-        let (mut s_expr, bind_context) = match &query.body {
-            SetExpr::Select(stmt) => {
-                self.bind_select_stmt(bind_context, stmt, &query.order_by)
-                    .await
+        let with_context;
+        let bind_context = match &query.with {
+            Some(with) => {
+                with_context = self.bind_with(bind_context, with).await?;
+                &with_context
             }
-            SetExpr::Query(stmt) => self.bind_query(bind_context, stmt).await,
-            _ => Err(ErrorCode::UnImplement("Unsupported query type")),
-        }?;
+            None => bind_context,
+        };
+
+        let (mut s_expr, bind_context) = self
+            .bind_set_expr(bind_context, &query.body, &query.order_by)
+            .await?;
 
         if !query.limit.is_empty() {
             if query.limit.len() == 1 {
@@ -84,35 +89,86 @@ impl<'a> Binder {
         Ok((s_expr, bind_context))
     }
 
+    #[async_recursion]
+    pub(super) async fn bind_set_expr(
+        &mut self,
+        bind_context: &BindContext,
+        set_expr: &SetExpr<'a>,
+        order_by: &[OrderByExpr<'a>],
+    ) -> Result<(SExpr, BindContext)> {
+        let (mut s_expr, output_context) = match set_expr {
+            SetExpr::Select(stmt) => {
+                return self.bind_select_stmt(bind_context, stmt, order_by).await;
+            }
+            SetExpr::Query(query) => self.bind_query(bind_context, query).await?,
+            SetExpr::SetOperation {
+                op,
+                all,
+                left,
+                right,
+            } => {
+                self.bind_set_operation(bind_context, op, *all, left, right)
+                    .await?
+            }
+        };
+
+        // The `ORDER BY` of a parenthesized query or a set operation can only refer to the
+        // output columns.
+        if !order_by.is_empty() {
+            s_expr = self
+                .bind_order_by(&output_context, &output_context, s_expr, order_by)
+                .await?;
+        }
+
+        Ok((s_expr, output_context))
+    }
+
     pub(super) async fn bind_select_stmt(
         &mut self,
         bind_context: &BindContext,
         stmt: &SelectStmt<'a>,
         order_by: &[OrderByExpr<'a>],
     ) -> Result<(SExpr, BindContext)> {
-        let (mut s_expr, from_context) = if let Some(from) = &stmt.from {
+        let (mut s_expr, mut from_context) = if let Some(from) = &stmt.from {
             self.bind_table_reference(bind_context, from).await?
         } else {
             self.bind_one_table(stmt).await?
         };
+        // Outer columns referenced by a correlated subquery are resolved with the parent context.
+        from_context.set_parent(bind_context.parent());
+        // The CTEs of current scope are visible to the subqueries.
+        from_context.ctes_map = bind_context.ctes_map.clone();
 
         if let Some(expr) = &stmt.selection {
             s_expr = self.bind_where(&from_context, expr, s_expr, false).await?;
         }
 
+        // The grouping columns of grouping sets are registered before normalizing the select
+        // list, as they are replaced by nullable columns out of aggregate functions.
+        if let Some(group_by) = &stmt.group_by {
+            if !matches!(group_by, GroupBy::Normal(_)) {
+                self.bind_grouping_sets(&mut from_context, &stmt.select_list, group_by)
+                    .await?;
+            }
+        }
+
         // Output of current `SELECT` statement.
         let mut output_context = self
             .normalize_select_list(&from_context, &stmt.select_list)
             .await?;
 
         let agg_info = self.analyze_aggregate(&output_context)?;
-        if !agg_info.aggregate_functions.is_empty() || !stmt.group_by.is_empty() {
+        if !agg_info.aggregate_functions.is_empty() || stmt.group_by.is_some() {
+            let group_by_expr = match &stmt.group_by {
+                Some(GroupBy::Normal(exprs)) => exprs.as_slice(),
+                _ => &[],
+            };
             (s_expr, output_context) = self
                 .bind_group_by(
                     &from_context,
                     output_context,
                     s_expr,
-                    &stmt.group_by,
+                    group_by_expr,
                     &agg_info,
                 )
                 .await?;
@@ -155,7 +211,7 @@ impl<'a> Binder {
             .resolve_data_source(tenant.as_str(), catalog, database, "one")
             .await?;
         let source = table_meta.read_plan(self.ctx.clone(), None).await?;
-        let table_index = self.metadata.add_table(
+        let table_index = self.metadata.write().add_table(
             CATALOG_DEFAULT.to_owned(),
             database.to_string(),
             table_meta,
@@ -177,6 +233,12 @@ impl<'a> Binder {
                 table,
                 alias,
             } => {
+                if catalog.is_none() && database.is_none() {
+                    if let Some(cte_index) = bind_context.resolve_cte(&table.name.to_lowercase()) {
+                        return self.bind_cte_scan(cte_index, alias);
+                    }
+                }
+
                 let database = database
                     .as_ref()
                     .map(|ident| ident.name.clone())
@@ -202,6 +264,7 @@ impl<'a> Binder {
                 let source = table_meta.read_plan(self.ctx.clone(), None).await?;
                 let table_index = self
                     .metadata
+                    .write()
                     .add_table(catalog, database, table_meta, source);
 
                 let (s_expr, mut bind_context) = self.bind_base_table(table_index).await?;
@@ -215,7 +278,8 @@ impl<'a> Binder {
                 params,
                 alias,
             } => {
-                let scalar_binder = ScalarBinder::new(bind_context, self.ctx.clone());
+                let scalar_binder =
+                    ScalarBinder::new(bind_context, self.ctx.clone(), self.metadata.clone());
                 let mut args = Vec::with_capacity(params.len());
                 for arg in params.iter() {
                     args.push(scalar_binder.bind_expr(arg).await?);
@@ -248,7 +312,7 @@ impl<'a> Binder {
                 let table = table_meta.as_table();
 
                 let source = table.read_plan(self.ctx.clone(), None).await?;
-                let table_index = self.metadata.add_table(
+                let table_index = self.metadata.write().add_table(
                     CATALOG_DEFAULT.to_string(),
                     "system".to_string(),
                     table.clone(),
@@ -274,8 +338,9 @@ impl<'a> Binder {
 
     async fn bind_base_table(&mut self, table_index: IndexType) -> Result<(SExpr, BindContext)> {
         let mut bind_context = BindContext::new();
-        let columns = self.metadata.columns_by_table_index(table_index);
-        let table = self.metadata.table(table_index);
+        let metadata = self.metadata.read();
+        let columns = metadata.columns_by_table_index(table_index);
+        let table = metadata.table(table_index);
         for column in columns.iter() {
             let column_binding = ColumnBinding {
                 table_name: Some(table.name.clone()),
@@ -306,7 +371,8 @@ impl<'a> Binder {
         child: SExpr,
         is_having: bool,
     ) -> Result<SExpr> {
-        let scalar_binder = ScalarBinder::new(bind_context, self.ctx.clone());
+        let scalar_binder =
+            ScalarBinder::new(bind_context, self.ctx.clone(), self.metadata.clone());
         let (scalar, _) = scalar_binder.bind_expr(expr).await?;
         let filter_plan = FilterPlan {
             predicates: split_conjunctions(&scalar),
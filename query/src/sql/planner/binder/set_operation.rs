@@ -0,0 +1,140 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_ast::ast::SetExpr;
+use common_ast::ast::SetOperator;
+use common_datavalues::type_coercion::merge_types;
+use common_datavalues::DataTypeImpl;
+use common_exception::ErrorCode;
+use common_exception::Result;
+
+use crate::sql::binder::scalar_common::wrap_cast_if_needed;
+use crate::sql::binder::Binder;
+use crate::sql::optimizer::SExpr;
+use crate::sql::planner::binder::BindContext;
+use crate::sql::planner::binder::ColumnBinding;
+use crate::sql::plans::BoundColumnRef;
+use crate::sql::plans::ProjectItem;
+use crate::sql::plans::ProjectPlan;
+use crate::sql::plans::Scalar;
+use crate::sql::plans::SetOperationPlan;
+use crate::sql::plans::SetOperationType;
+use crate::sql::IndexType;
+
+impl<'a> Binder {
+    /// Bind a set operation like `SELECT a FROM t1 UNION SELECT b FROM t2`.
+    ///
+    /// The columns of both sides are matched by position, and coerced to their least super
+    /// type. The output columns are named after the columns of the left side.
+    pub(super) async fn bind_set_operation(
+        &mut self,
+        bind_context: &BindContext,
+        op: &SetOperator,
+        all: bool,
+        left: &SetExpr<'a>,
+        right: &SetExpr<'a>,
+    ) -> Result<(SExpr, BindContext)> {
+        let (left_expr, left_context) = self.bind_set_expr(bind_context, left, &[]).await?;
+        let (right_expr, right_context) = self.bind_set_expr(bind_context, right, &[]).await?;
+
+        let left_bindings = left_context.all_column_bindings();
+        let right_bindings = right_context.all_column_bindings();
+        if left_bindings.len() != right_bindings.len() {
+            return Err(ErrorCode::SemanticError(format!(
+                "Each side of a set operation must have the same number of columns, but got {} and {}",
+                left_bindings.len(),
+                right_bindings.len()
+            )));
+        }
+
+        let mut output_context = BindContext::new();
+        let mut left_items = vec![];
+        let mut right_items = vec![];
+        let mut left_columns = Vec::with_capacity(left_bindings.len());
+        let mut right_columns = Vec::with_capacity(right_bindings.len());
+        let mut output_columns = Vec::with_capacity(left_bindings.len());
+        for (left_binding, right_binding) in left_bindings.iter().zip(right_bindings.iter()) {
+            let data_type = merge_types(&left_binding.data_type, &right_binding.data_type)?;
+            left_columns.push(self.coerce_column(left_binding, &data_type, &mut left_items));
+            right_columns.push(self.coerce_column(right_binding, &data_type, &mut right_items));
+
+            let index = self.metadata.write().add_column(
+                left_binding.column_name.clone(),
+                data_type.clone(),
+                None,
+            );
+            output_columns.push(index);
+            output_context.add_column_binding(ColumnBinding {
+                table_name: None,
+                column_name: left_binding.column_name.clone(),
+                index,
+                data_type,
+                visible: true,
+                scalar: None,
+            });
+        }
+
+        let set_operation = SetOperationPlan {
+            op: match op {
+                SetOperator::Union => SetOperationType::Union,
+                SetOperator::Intersect => SetOperationType::Intersect,
+                SetOperator::Except => SetOperationType::Except,
+            },
+            all,
+            left_columns,
+            right_columns,
+            output_columns,
+        };
+        let new_expr = SExpr::create_binary(
+            set_operation.into(),
+            Self::project_casts(left_expr, left_items),
+            Self::project_casts(right_expr, right_items),
+        );
+        Ok((new_expr, output_context))
+    }
+
+    /// Returns the index of the column casted to `data_type`, the cast is added to `items`
+    /// if the column is not of the type.
+    pub(super) fn coerce_column(
+        &mut self,
+        column_binding: &ColumnBinding,
+        data_type: &DataTypeImpl,
+        items: &mut Vec<ProjectItem>,
+    ) -> IndexType {
+        if column_binding.data_type == *data_type {
+            return column_binding.index;
+        }
+
+        let index = self.metadata.write().add_column(
+            column_binding.column_name.clone(),
+            data_type.clone(),
+            None,
+        );
+        let column_ref = Scalar::BoundColumnRef(BoundColumnRef {
+            column: column_binding.clone(),
+        });
+        items.push(ProjectItem {
+            expr: wrap_cast_if_needed(column_ref, data_type),
+            index,
+        });
+        index
+    }
+
+    pub(super) fn project_casts(child: SExpr, items: Vec<ProjectItem>) -> SExpr {
+        match items.is_empty() {
+            true => child,
+            false => SExpr::create_unary(ProjectPlan { items }.into(), child),
+        }
+    }
+}
@@ -33,8 +33,10 @@ impl<'a> Binder {
         child: SExpr,
         order_by: &[OrderByExpr<'a>],
     ) -> Result<SExpr> {
-        let select_scalar_binder = ScalarBinder::new(select_context, self.ctx.clone());
-        let from_scalar_binder = ScalarBinder::new(from_context, self.ctx.clone());
+        let select_scalar_binder =
+            ScalarBinder::new(select_context, self.ctx.clone(), self.metadata.clone());
+        let from_scalar_binder =
+            ScalarBinder::new(from_context, self.ctx.clone(), self.metadata.clone());
         let mut order_by_items = vec![];
         for order in order_by {
             // First we try to resolve sort item with `SELECT` context
@@ -16,10 +16,12 @@ use std::sync::Arc;
 
 use common_ast::ast::Expr;
 use common_ast::ast::Literal;
+use common_base::infallible::RwLock;
 use common_datavalues::prelude::*;
 use common_planners::ReadDataSourcePlan;
 
 use crate::sql::common::IndexType;
+use crate::sql::optimizer::SExpr;
 use crate::storages::Table;
 
 #[derive(Clone)]
@@ -80,6 +82,26 @@ impl ColumnEntry {
     }
 }
 
+#[derive(Clone)]
+pub struct CteEntry {
+    pub index: IndexType,
+    pub name: String,
+    // The definition of the CTE, None while the recursive term of a recursive CTE is bound.
+    pub s_expr: Option<SExpr>,
+    // The output columns of the definition.
+    pub columns: Vec<IndexType>,
+    pub column_names: Vec<String>,
+
+    // Number of times the CTE is referenced in the query.
+    pub references: usize,
+    // Number of times the CTE is referenced in its own recursive term.
+    pub recursive_references: usize,
+}
+
+/// Metadata shared by the binders of a query and its subqueries, so that the
+/// indices of tables and columns are unique in the whole query.
+pub type MetadataRef = Arc<RwLock<Metadata>>;
+
 /// Metadata stores information about columns and tables used in a query.
 /// Tables and columns are identified with its unique index, notice that index value of a column can
 /// be same with that of a table.
@@ -87,6 +109,7 @@ impl ColumnEntry {
 pub struct Metadata {
     tables: Vec<TableEntry>,
     columns: Vec<ColumnEntry>,
+    ctes: Vec<CteEntry>,
 }
 
 impl Metadata {
@@ -94,6 +117,7 @@ impl Metadata {
         Self {
             tables: vec![],
             columns: vec![],
+            ctes: vec![],
         }
     }
 
@@ -158,6 +182,48 @@ impl Metadata {
         }
         table_index
     }
+
+    pub fn cte(&self, index: IndexType) -> &CteEntry {
+        self.ctes.get(index).unwrap()
+    }
+
+    pub fn ctes(&self) -> &[CteEntry] {
+        &self.ctes
+    }
+
+    pub fn add_cte(
+        &mut self,
+        name: String,
+        s_expr: Option<SExpr>,
+        columns: Vec<IndexType>,
+        column_names: Vec<String>,
+    ) -> IndexType {
+        let cte_index = self.ctes.len();
+        self.ctes.push(CteEntry {
+            index: cte_index,
+            name,
+            s_expr,
+            columns,
+            column_names,
+            references: 0,
+            recursive_references: 0,
+        });
+        cte_index
+    }
+
+    pub fn set_cte_definition(&mut self, index: IndexType, s_expr: SExpr) {
+        self.ctes[index].s_expr = Some(s_expr);
+    }
+
+    /// Counts a reference to the CTE, the references in a recursive term are counted
+    /// separately since they read the work table instead of the definition.
+    pub fn add_cte_reference(&mut self, index: IndexType) {
+        let entry = &mut self.ctes[index];
+        match entry.s_expr {
+            None => entry.recursive_references += 1,
+            Some(_) => entry.references += 1,
+        }
+    }
 }
 
 pub fn optimize_remove_count_args(name: &str, distinct: bool, args: &[&Expr]) -> bool {
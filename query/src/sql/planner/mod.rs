@@ -14,9 +14,11 @@
 
 use std::sync::Arc;
 
+use common_ast::ast::Statement;
 use common_ast::parser::error::Backtrace;
 use common_ast::parser::parse_sql;
 use common_ast::parser::tokenize_sql;
+use common_base::infallible::RwLock;
 use common_exception::ErrorCode;
 use common_exception::Result;
 pub use plans::ScalarExpr;
@@ -24,6 +26,7 @@ pub use plans::ScalarExpr;
 use crate::sessions::QueryContext;
 use crate::sql::exec::PipelineBuilder;
 use crate::sql::optimizer::optimize;
+use crate::sql::optimizer::resolve_ctes;
 use crate::sql::optimizer::OptimizeContext;
 pub use crate::sql::planner::binder::BindContext;
 use crate::sql::planner::binder::Binder;
@@ -34,7 +37,9 @@ pub mod plans;
 mod semantic;
 
 pub use metadata::ColumnEntry;
+pub use metadata::CteEntry;
 pub use metadata::Metadata;
+pub use metadata::MetadataRef;
 pub use metadata::TableEntry;
 
 use crate::pipelines::new::NewPipeline;
@@ -48,6 +53,19 @@ impl Planner {
         Planner { ctx }
     }
 
+    /// Whether the SQL is a single query statement of the new parser.
+    pub fn is_query(sql: &str) -> bool {
+        let tokens = match tokenize_sql(sql) {
+            Ok(tokens) => tokens,
+            Err(_) => return false,
+        };
+        let backtrace = Backtrace::new();
+        matches!(
+            parse_sql(&tokens, &backtrace).as_deref(),
+            Ok([Statement::Query(_)])
+        )
+    }
+
     pub async fn plan_sql<'a>(&mut self, sql: &'a str) -> Result<(NewPipeline, Vec<NewPipeline>)> {
         // Step 1: parse SQL text into AST
         let tokens = tokenize_sql(sql)?;
@@ -58,12 +76,14 @@ impl Planner {
         }
 
         // Step 2: bind AST with catalog, and generate a pure logical SExpr
-        let binder = Binder::new(self.ctx.clone(), self.ctx.get_catalogs());
+        let metadata = Arc::new(RwLock::new(Metadata::create()));
+        let binder = Binder::new(self.ctx.clone(), self.ctx.get_catalogs(), metadata);
         let bind_result = binder.bind(&stmts[0]).await?;
 
         // Step 3: optimize the SExpr with optimizers, and generate optimized physical SExpr
         let optimize_context = OptimizeContext::create_with_bind_context(&bind_result.bind_context);
         let optimized_expr = optimize(bind_result.s_expr, optimize_context)?;
+        let optimized_expr = resolve_ctes(optimized_expr, &bind_result.metadata)?;
 
         // Step 4: build executable Pipeline with SExpr
         let result_columns = bind_result.bind_context.result_columns();
@@ -22,6 +22,7 @@ use crate::sql::plans::BasePlan;
 use crate::sql::plans::LogicalPlan;
 use crate::sql::plans::PhysicalPlan;
 use crate::sql::plans::PlanType;
+use crate::sql::IndexType;
 
 #[derive(Clone, Debug)]
 pub struct AggregatePlan {
@@ -29,6 +30,22 @@ pub struct AggregatePlan {
     pub group_items: Vec<Scalar>,
     // aggregate scalar expressions, such as: sum(col1), count(*);
     pub aggregate_functions: Vec<Scalar>,
+    // grouping sets of `GROUPING SETS`, `ROLLUP` and `CUBE`
+    pub grouping_sets: Option<GroupingSets>,
+}
+
+/// Grouping sets of an aggregation. Before aggregating, each input row is expanded into one
+/// row per grouping set, in which the grouping columns out of the set are `NULL`, and the
+/// group items are the nullable grouping columns and the grouping id.
+#[derive(Clone, Debug)]
+pub struct GroupingSets {
+    // index of the grouping id column, the i-th bit is set if the i-th grouping column
+    // isn't contained by the grouping set
+    pub grouping_id: IndexType,
+    // pairs of grouping column and its nullable counterpart
+    pub columns: Vec<(IndexType, IndexType)>,
+    // positions of grouping columns of each grouping set
+    pub sets: Vec<Vec<usize>>,
 }
 
 impl BasePlan for AggregatePlan {
@@ -0,0 +1,168 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+
+use crate::sql::optimizer::PhysicalProperty;
+use crate::sql::optimizer::RelationalProperty;
+use crate::sql::optimizer::SExpr;
+use crate::sql::plans::BasePlan;
+use crate::sql::plans::LogicalPlan;
+use crate::sql::plans::PhysicalPlan;
+use crate::sql::plans::PlanType;
+use crate::sql::IndexType;
+
+/// Reads the rows of a materialized CTE, or the work table of a recursive CTE.
+///
+/// Every reference to a CTE has its own output columns, which are matched by position
+/// with the columns of the CTE.
+#[derive(Clone, Debug)]
+pub struct CteScanPlan {
+    pub cte_index: IndexType,
+    pub columns: Vec<IndexType>,
+}
+
+impl BasePlan for CteScanPlan {
+    fn plan_type(&self) -> PlanType {
+        PlanType::CteScan
+    }
+
+    fn is_physical(&self) -> bool {
+        true
+    }
+
+    fn is_logical(&self) -> bool {
+        true
+    }
+
+    fn as_physical(&self) -> Option<&dyn PhysicalPlan> {
+        todo!()
+    }
+
+    fn as_logical(&self) -> Option<&dyn LogicalPlan> {
+        todo!()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl PhysicalPlan for CteScanPlan {
+    fn compute_physical_prop(&self, _expression: &SExpr) -> PhysicalProperty {
+        todo!()
+    }
+}
+
+impl LogicalPlan for CteScanPlan {
+    fn compute_relational_prop(&self, _expression: &SExpr) -> RelationalProperty {
+        todo!()
+    }
+}
+
+/// Materializes the rows of the CTE of the left child before the right child is executed.
+#[derive(Clone, Debug)]
+pub struct MaterializedCtePlan {
+    pub cte_index: IndexType,
+}
+
+impl BasePlan for MaterializedCtePlan {
+    fn plan_type(&self) -> PlanType {
+        PlanType::MaterializedCte
+    }
+
+    fn is_physical(&self) -> bool {
+        true
+    }
+
+    fn is_logical(&self) -> bool {
+        true
+    }
+
+    fn as_physical(&self) -> Option<&dyn PhysicalPlan> {
+        todo!()
+    }
+
+    fn as_logical(&self) -> Option<&dyn LogicalPlan> {
+        todo!()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl PhysicalPlan for MaterializedCtePlan {
+    fn compute_physical_prop(&self, _expression: &SExpr) -> PhysicalProperty {
+        todo!()
+    }
+}
+
+impl LogicalPlan for MaterializedCtePlan {
+    fn compute_relational_prop(&self, _expression: &SExpr) -> RelationalProperty {
+        todo!()
+    }
+}
+
+/// `WITH RECURSIVE`, the left child is the anchor and the right child is the recursive term.
+///
+/// The recursive term reads the rows returned by its previous iteration from the work table of
+/// the CTE, it's executed until no more rows are returned.
+#[derive(Clone, Debug)]
+pub struct RecursiveCtePlan {
+    pub cte_index: IndexType,
+    /// Keep the duplicated rows if `UNION ALL` is specified
+    pub all: bool,
+    pub anchor_columns: Vec<IndexType>,
+    pub recursive_columns: Vec<IndexType>,
+    pub output_columns: Vec<IndexType>,
+}
+
+impl BasePlan for RecursiveCtePlan {
+    fn plan_type(&self) -> PlanType {
+        PlanType::RecursiveCte
+    }
+
+    fn is_physical(&self) -> bool {
+        true
+    }
+
+    fn is_logical(&self) -> bool {
+        true
+    }
+
+    fn as_physical(&self) -> Option<&dyn PhysicalPlan> {
+        todo!()
+    }
+
+    fn as_logical(&self) -> Option<&dyn LogicalPlan> {
+        todo!()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl PhysicalPlan for RecursiveCtePlan {
+    fn compute_physical_prop(&self, _expression: &SExpr) -> PhysicalProperty {
+        todo!()
+    }
+}
+
+impl LogicalPlan for RecursiveCtePlan {
+    fn compute_relational_prop(&self, _expression: &SExpr) -> RelationalProperty {
+        todo!()
+    }
+}
@@ -17,6 +17,7 @@ use std::any::Any;
 use crate::sql::optimizer::PhysicalProperty;
 use crate::sql::optimizer::SExpr;
 use crate::sql::plans::BasePlan;
+use crate::sql::plans::JoinType;
 use crate::sql::plans::LogicalPlan;
 use crate::sql::plans::PhysicalPlan;
 use crate::sql::plans::PlanType;
@@ -26,6 +27,7 @@ use crate::sql::plans::Scalar;
 pub struct PhysicalHashJoin {
     pub build_keys: Vec<Scalar>,
     pub probe_keys: Vec<Scalar>,
+    pub join_type: JoinType,
 }
 
 impl BasePlan for PhysicalHashJoin {
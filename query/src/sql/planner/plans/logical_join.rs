@@ -22,10 +22,20 @@ use crate::sql::plans::PhysicalPlan;
 use crate::sql::plans::PlanType;
 use crate::sql::plans::Scalar;
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum JoinType {
+    Inner,
+    /// Returns the rows of left side which have matched rows in right side
+    Semi,
+    /// Returns the rows of left side which don't have matched rows in right side
+    Anti,
+}
+
 #[derive(Clone, Debug)]
 pub struct LogicalInnerJoin {
     pub left_conditions: Vec<Scalar>,
     pub right_conditions: Vec<Scalar>,
+    pub join_type: JoinType,
 }
 
 impl BasePlan for LogicalInnerJoin {
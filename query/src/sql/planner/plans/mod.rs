@@ -13,6 +13,7 @@
 // limitations under the License.
 
 mod aggregate;
+mod cte;
 mod filter;
 mod hash_join;
 mod limit;
@@ -22,22 +23,30 @@ mod pattern;
 mod physical_scan;
 mod project;
 mod scalar;
+mod set_operation;
 mod sort;
 
 use std::any::Any;
 
 pub use aggregate::AggregatePlan;
+pub use aggregate::GroupingSets;
+pub use cte::CteScanPlan;
+pub use cte::MaterializedCtePlan;
+pub use cte::RecursiveCtePlan;
 use enum_dispatch::enum_dispatch;
 pub use filter::FilterPlan;
 pub use hash_join::PhysicalHashJoin;
 pub use limit::LimitPlan;
 pub use logical_get::LogicalGet;
+pub use logical_join::JoinType;
 pub use logical_join::LogicalInnerJoin;
 pub use pattern::PatternPlan;
 pub use physical_scan::PhysicalScan;
 pub use project::ProjectItem;
 pub use project::ProjectPlan;
 pub use scalar::*;
+pub use set_operation::SetOperationPlan;
+pub use set_operation::SetOperationType;
 pub use sort::SortItem;
 pub use sort::SortPlan;
 
@@ -89,6 +98,10 @@ pub enum PlanType {
     Aggregate,
     Sort,
     Limit,
+    SetOperation,
+    CteScan,
+    MaterializedCte,
+    RecursiveCte,
 
     // Pattern
     Pattern,
@@ -108,6 +121,10 @@ pub enum BasePlanImpl {
     Aggregate(AggregatePlan),
     Sort(SortPlan),
     Limit(LimitPlan),
+    SetOperation(SetOperationPlan),
+    CteScan(CteScanPlan),
+    MaterializedCte(MaterializedCtePlan),
+    RecursiveCte(RecursiveCtePlan),
 
     Pattern(PatternPlan),
 }
@@ -255,12 +255,27 @@ impl ScalarExpr for CastExpr {
     }
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SubqueryType {
+    /// Scalar subquery, e.g. `a = (SELECT max(b) FROM t)`
+    Scalar,
+    /// `EXISTS (SELECT ...)`
+    Exists,
+    /// `a IN (SELECT b FROM t)`, `NOT IN` is represented as `NOT` of it.
+    In,
+}
+
 #[derive(Clone, Debug)]
 pub struct SubqueryExpr {
+    pub typ: SubqueryType,
     pub subquery: SExpr,
+    // The expression compared with the output column of an `IN` subquery
+    pub child_expr: Option<Box<Scalar>>,
     pub data_type: DataTypeImpl,
     pub allow_multi_rows: bool,
     pub output_context: Box<BindContext>,
+    // Columns of the outer queries referenced by the subquery
+    pub outer_columns: ColumnSet,
 }
 
 impl ScalarExpr for SubqueryExpr {
@@ -269,7 +284,11 @@ impl ScalarExpr for SubqueryExpr {
     }
 
     fn used_columns(&self) -> ColumnSet {
-        todo!()
+        let mut result = self.outer_columns.clone();
+        if let Some(child_expr) = &self.child_expr {
+            result = result.union(&child_expr.used_columns()).cloned().collect();
+        }
+        result
     }
 }
 
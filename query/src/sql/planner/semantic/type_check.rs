@@ -28,15 +28,18 @@ use common_datavalues::DataValue;
 use common_datavalues::IntervalKind;
 use common_datavalues::IntervalType;
 use common_datavalues::TimestampType;
+use common_datavalues::ToDataType;
 use common_exception::ErrorCode;
 use common_exception::Result;
 use common_functions::aggregates::AggregateFunctionFactory;
 use common_functions::scalars::CastFunction;
 use common_functions::scalars::FunctionFactory;
+use common_functions::scalars::WasmFunction;
 
 use crate::sessions::QueryContext;
 use crate::sql::binder::Binder;
 use crate::sql::planner::metadata::optimize_remove_count_args;
+use crate::sql::planner::metadata::MetadataRef;
 use crate::sql::plans::AggregateFunction;
 use crate::sql::plans::AndExpr;
 use crate::sql::plans::BoundColumnRef;
@@ -47,7 +50,9 @@ use crate::sql::plans::ConstantExpr;
 use crate::sql::plans::FunctionCall;
 use crate::sql::plans::OrExpr;
 use crate::sql::plans::Scalar;
+use crate::sql::plans::ScalarExpr;
 use crate::sql::plans::SubqueryExpr;
+use crate::sql::plans::SubqueryType;
 use crate::sql::BindContext;
 
 /// A helper for type checking.
@@ -62,6 +67,7 @@ use crate::sql::BindContext;
 pub struct TypeChecker<'a> {
     bind_context: &'a BindContext,
     ctx: Arc<QueryContext>,
+    metadata: MetadataRef,
 
     // true if current expr is inside an aggregate function.
     // This is used to check if there is nested aggregate function.
@@ -69,10 +75,15 @@ pub struct TypeChecker<'a> {
 }
 
 impl<'a> TypeChecker<'a> {
-    pub fn new(bind_context: &'a BindContext, ctx: Arc<QueryContext>) -> Self {
+    pub fn new(
+        bind_context: &'a BindContext,
+        ctx: Arc<QueryContext>,
+        metadata: MetadataRef,
+    ) -> Self {
         Self {
             bind_context,
             ctx,
+            metadata,
             in_aggregate_function: false,
         }
     }
@@ -97,6 +108,13 @@ impl<'a> TypeChecker<'a> {
                 let column = self
                     .bind_context
                     .resolve_column(table.clone().map(|ident| ident.name), column)?;
+                // Out of aggregate functions, the grouping columns of grouping sets are
+                // replaced by their nullable counterparts.
+                let column = if self.in_aggregate_function {
+                    column
+                } else {
+                    self.bind_context.resolve_grouping_column(column)
+                };
                 let data_type = column.data_type.clone();
 
                 Ok((BoundColumnRef { column }.into(), data_type))
@@ -252,6 +270,10 @@ impl<'a> TypeChecker<'a> {
 
                 let args: Vec<&Expr> = args.iter().collect();
 
+                if func_name.eq_ignore_ascii_case("grouping") {
+                    return self.resolve_grouping(expr, &args);
+                }
+
                 if AggregateFunctionFactory::instance().check(func_name) {
                     if self.in_aggregate_function {
                         // Reset the state
@@ -326,7 +348,40 @@ impl<'a> TypeChecker<'a> {
                 ))
             }
 
-            Expr::Subquery { subquery, .. } => self.resolve_subquery(subquery, false, None).await,
+            Expr::Subquery { subquery, .. } => {
+                self.resolve_subquery(SubqueryType::Scalar, subquery, false, None, None)
+                    .await
+            }
+
+            Expr::Exists { subquery, .. } => {
+                self.resolve_subquery(SubqueryType::Exists, subquery, true, None, None)
+                    .await
+            }
+
+            Expr::InSubquery {
+                subquery,
+                expr,
+                not,
+                ..
+            } => {
+                let (scalar, data_type) = self
+                    .resolve_subquery(SubqueryType::In, subquery, true, Some(&**expr), None)
+                    .await?;
+                if *not {
+                    Ok((
+                        FunctionCall {
+                            arguments: vec![scalar],
+                            func_name: "not".to_string(),
+                            arg_types: vec![data_type.clone()],
+                            return_type: data_type.clone(),
+                        }
+                        .into(),
+                        data_type,
+                    ))
+                } else {
+                    Ok((scalar, data_type))
+                }
+            }
 
             Expr::MapAccess {
                 span,
@@ -371,6 +426,20 @@ impl<'a> TypeChecker<'a> {
                 self.resolve_extract_expr(kind, expr, required_type).await
             }
 
+            Expr::AtTimeZone {
+                span,
+                expr,
+                timezone,
+            } => {
+                // `ts AT TIME ZONE 'tz'` converts the session time of `ts` to the time in `tz`
+                let timezone = Expr::Literal {
+                    span,
+                    lit: Literal::String(timezone.clone()),
+                };
+                self.resolve_function("convert_tz", &[&**expr, &timezone], required_type)
+                    .await
+            }
+
             _ => Err(ErrorCode::UnImplement(format!(
                 "Unsupported expr: {:?}",
                 expr
@@ -396,6 +465,7 @@ impl<'a> TypeChecker<'a> {
 
         let arg_types_ref: Vec<&DataTypeImpl> = arg_types.iter().collect();
 
+        WasmFunction::check_tenant(&self.ctx.get_tenant(), func_name)?;
         let func = FunctionFactory::instance().get(func_name, &arg_types_ref)?;
         Ok((
             FunctionCall {
@@ -409,6 +479,93 @@ impl<'a> TypeChecker<'a> {
         ))
     }
 
+    /// Resolve `GROUPING(a, b, ...)` of grouping sets into a bit mask, in which the bit of an
+    /// argument is set if the row isn't grouped by it. The last argument corresponds to the
+    /// least significant bit, e.g. `GROUPING(a, b)` is 2 for a row of grouping set `(b)`.
+    fn resolve_grouping(
+        &self,
+        expr: &Expr<'a>,
+        args: &[&Expr<'a>],
+    ) -> Result<(Scalar, DataTypeImpl)> {
+        let grouping_sets = self.bind_context.grouping_sets.as_ref().ok_or_else(|| {
+            ErrorCode::SemanticError(expr.span().display_error(
+                "GROUPING can only be used with GROUPING SETS, ROLLUP or CUBE".to_string(),
+            ))
+        })?;
+        if args.is_empty() {
+            return Err(ErrorCode::SemanticError(expr.span().display_error(
+                "GROUPING requires at least one argument".to_string(),
+            )));
+        }
+
+        let grouping_id: Scalar = BoundColumnRef {
+            column: grouping_sets.grouping_id.clone(),
+        }
+        .into();
+        let constant = |value: u64| -> Scalar {
+            ConstantExpr {
+                value: DataValue::UInt64(value),
+                data_type: u64::to_data_type(),
+            }
+            .into()
+        };
+
+        let mut result: Option<Scalar> = None;
+        for (i, arg) in args.iter().enumerate() {
+            let position =
+                match arg {
+                    Expr::ColumnRef { table, column, .. } => {
+                        let column = self
+                            .bind_context
+                            .resolve_column(table.clone().map(|ident| ident.name), column)?;
+                        grouping_sets
+                            .columns
+                            .iter()
+                            .position(|(grouping_column, _)| grouping_column.index == column.index)
+                    }
+                    _ => None,
+                }
+                .ok_or_else(|| {
+                    ErrorCode::SemanticError(arg.span().display_error(
+                        "arguments of GROUPING must be grouping columns".to_string(),
+                    ))
+                })?;
+
+            // (grouping_id div 2^position) % 2 * 2^(args.len() - 1 - i)
+            let mut bit = Self::build_function_call("div", vec![
+                grouping_id.clone(),
+                constant(1 << position),
+            ])?;
+            bit = Self::build_function_call("%", vec![bit, constant(2)])?;
+            let shift = args.len() - 1 - i;
+            if shift > 0 {
+                bit = Self::build_function_call("*", vec![bit, constant(1 << shift)])?;
+            }
+            result = Some(match result {
+                Some(result) => Self::build_function_call("+", vec![result, bit])?,
+                None => bit,
+            });
+        }
+
+        let result = result.unwrap();
+        let data_type = result.data_type();
+        Ok((result, data_type))
+    }
+
+    /// Build a scalar function call with bound arguments.
+    fn build_function_call(func_name: &str, arguments: Vec<Scalar>) -> Result<Scalar> {
+        let arg_types: Vec<DataTypeImpl> = arguments.iter().map(|arg| arg.data_type()).collect();
+        let arg_types_ref: Vec<&DataTypeImpl> = arg_types.iter().collect();
+        let func = FunctionFactory::instance().get(func_name, &arg_types_ref)?;
+        Ok(FunctionCall {
+            arguments,
+            func_name: func_name.to_string(),
+            arg_types,
+            return_type: func.return_type(),
+        }
+        .into())
+    }
+
     /// Resolve binary expressions. Most of the binary expressions
     /// would be transformed into `FunctionCall`, except comparison
     /// expressions, conjunction(`AND`) and disjunction(`OR`).
@@ -555,29 +712,49 @@ impl<'a> TypeChecker<'a> {
 
     pub async fn resolve_subquery(
         &mut self,
+        typ: SubqueryType,
         subquery: &Query<'a>,
         allow_multi_rows: bool,
+        child_expr: Option<&Expr<'a>>,
         _required_type: Option<DataTypeImpl>,
     ) -> Result<(Scalar, DataTypeImpl)> {
-        let mut binder = Binder::new(self.ctx.clone(), self.ctx.get_catalogs());
+        // The subquery shares the metadata with the outer query, so the indices of columns are
+        // unique in the whole query.
+        let mut binder = Binder::new(
+            self.ctx.clone(),
+            self.ctx.get_catalogs(),
+            self.metadata.clone(),
+        );
 
         // Create new `BindContext` with current `bind_context` as its parent, so we can resolve outer columns.
         let bind_context = BindContext::with_parent(Box::new(self.bind_context.clone()));
         let (s_expr, output_context) = binder.bind_query(&bind_context, subquery).await?;
 
-        if output_context.columns.len() > 1 {
+        if typ != SubqueryType::Exists && output_context.columns.len() != 1 {
             return Err(ErrorCode::SemanticError(
-                "Scalar subquery must return only one column",
+                "Subquery must return only one column",
             ));
         }
 
-        let data_type = output_context.columns[0].data_type.clone();
+        let child_expr = match child_expr {
+            Some(expr) => Some(Box::new(self.resolve(expr, None).await?.0)),
+            None => None,
+        };
+
+        let data_type = match typ {
+            SubqueryType::Scalar => output_context.columns[0].data_type.clone(),
+            SubqueryType::Exists | SubqueryType::In => BooleanType::new_impl(),
+        };
 
+        let outer_columns = s_expr.outer_columns();
         let subquery_expr = SubqueryExpr {
+            typ,
             subquery: s_expr,
+            child_expr,
             data_type: data_type.clone(),
-            output_context: Box::new(output_context),
             allow_multi_rows,
+            output_context: Box::new(output_context),
+            outer_columns,
         };
 
         Ok((subquery_expr.into(), data_type))
@@ -140,6 +140,23 @@ impl<'a> DfParser<'a> {
             expecting_statement_delimiter = true;
         }
 
+        let hints = Self::parse_hints_with_dialect(sql, dialect)?;
+        Ok((stmts, hints))
+    }
+
+    /// Parse the hints of a SQL statement, even if the statement is not supported.
+    pub fn parse_hints(sql: &'a str, typ: SessionType) -> Result<Vec<DfHint>, ErrorCode> {
+        let hints = match typ {
+            SessionType::MySQL => Self::parse_hints_with_dialect(sql, &MySqlDialect {})?,
+            _ => Self::parse_hints_with_dialect(sql, &GenericDialect {})?,
+        };
+        Ok(hints)
+    }
+
+    fn parse_hints_with_dialect(
+        sql: &'a str,
+        dialect: &'a dyn Dialect,
+    ) -> Result<Vec<DfHint>, ParserError> {
         let mut hints = Vec::new();
 
         let mut parser = DfParser::new_with_dialect(sql, dialect)?;
@@ -153,7 +170,7 @@ impl<'a> DfParser<'a> {
                 _ => continue,
             }
         }
-        Ok((stmts, hints))
+        Ok(hints)
     }
 
     /// Report unexpected token
@@ -228,6 +245,7 @@ impl<'a> DfParser<'a> {
                         "USE" => self.parse_use_database(),
                         "KILL" => self.parse_kill_query(),
                         "OPTIMIZE" => self.parse_optimize(),
+                        "REFRESH" => self.parse_refresh_materialized_view(),
                         _ => self.expected("Keyword", self.parser.peek_token()),
                     },
                     _ => self.expected("an SQL statement", Token::Word(w)),
@@ -239,6 +257,10 @@ impl<'a> DfParser<'a> {
     }
 
     fn parse_create(&mut self) -> Result<DfStatement<'a>, ParserError> {
+        if self.consume_token("MATERIALIZED") {
+            return self.parse_create_materialized_view();
+        }
+
         match self.parser.next_token() {
             Token::Word(w) => {
                 //TODO:make stage to sql parser keyword
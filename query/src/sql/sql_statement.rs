@@ -34,6 +34,7 @@ use crate::sql::statements::DfAlterTable;
 use crate::sql::statements::DfAlterUDF;
 use crate::sql::statements::DfAlterUser;
 use crate::sql::statements::DfCreateDatabase;
+use crate::sql::statements::DfCreateMaterializedView;
 use crate::sql::statements::DfCreateRole;
 use crate::sql::statements::DfCreateTable;
 use crate::sql::statements::DfCreateUDF;
@@ -51,6 +52,7 @@ use crate::sql::statements::DfInsertStatement;
 use crate::sql::statements::DfKillStatement;
 use crate::sql::statements::DfOptimizeTable;
 use crate::sql::statements::DfQueryStatement;
+use crate::sql::statements::DfRefreshMaterializedView;
 use crate::sql::statements::DfRenameTable;
 use crate::sql::statements::DfRevokePrivilegeStatement;
 use crate::sql::statements::DfSetVariable;
@@ -103,6 +105,10 @@ pub enum DfStatement<'a> {
     AlterView(DfAlterView),
     DropView(DfDropView),
 
+    // Materialized views.
+    CreateMaterializedView(DfCreateMaterializedView),
+    RefreshMaterializedView(DfRefreshMaterializedView),
+
     // Settings.
     ShowSettings(DfShowSettings),
 
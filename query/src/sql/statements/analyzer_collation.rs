@@ -0,0 +1,179 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_datavalues::prelude::*;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_functions::scalars::Collation;
+use common_planners::Expression;
+use common_planners::ExpressionRewriter;
+use sqlparser::ast::ObjectName;
+
+use crate::sql::statements::query::QueryASTIR;
+
+/// Returns the name of the collation in `expr COLLATE collation`.
+pub fn collation_name(collation: &ObjectName) -> Result<String> {
+    match collation.0.as_slice() {
+        [name] => {
+            Collation::try_from_name(&name.value)?;
+            Ok(name.value.to_lowercase())
+        }
+        _ => Err(ErrorCode::SyntaxException(format!(
+            "Unknown collation: '{}'",
+            collation
+        ))),
+    }
+}
+
+/// Splits `collate(expr, 'collation')` into the expr and the collation name.
+pub fn unwrap_collate(expr: &Expression) -> Option<(&Expression, String)> {
+    match expr {
+        Expression::ScalarFunction { op, args } if op == "collate" && args.len() == 2 => {
+            match &args[1] {
+                Expression::Literal {
+                    value: DataValue::String(name),
+                    ..
+                } => Some((&args[0], String::from_utf8_lossy(name).to_string())),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Builds `collation_key(expr, 'collation')`.
+pub fn collation_key(expr: &Expression, collation: &str) -> Expression {
+    Expression::ScalarFunction {
+        op: "collation_key".to_string(),
+        args: vec![
+            expr.clone(),
+            Expression::create_literal(DataValue::String(collation.as_bytes().to_vec())),
+        ],
+    }
+}
+
+/// Groups the collated strings by their collation keys. The collated group by expressions
+/// in the projection and the having predicate output the smallest original value of each
+/// group instead, so that `SELECT s COLLATE c ... GROUP BY s COLLATE c` returns a string
+/// of the group rather than its collation key.
+pub fn group_by_collation_keys(ir: &mut QueryASTIR) -> Result<()> {
+    let collated = ir
+        .group_by_expressions
+        .iter()
+        .filter(|expr| unwrap_collate(expr).is_some())
+        .cloned()
+        .collect::<Vec<_>>();
+
+    if collated.is_empty() {
+        return Ok(());
+    }
+
+    let mut representatives = Vec::with_capacity(collated.len());
+    for group_expression in ir.group_by_expressions.iter_mut() {
+        if let Some((inner, collation)) = unwrap_collate(group_expression) {
+            representatives.push(Expression::AggregateFunction {
+                op: "min".to_string(),
+                distinct: false,
+                params: vec![],
+                args: vec![inner.clone()],
+            });
+            *group_expression = collation_key(inner, &collation);
+        }
+    }
+
+    for expr in ir.projection_expressions.iter_mut() {
+        *expr = match &*expr {
+            // Keeps the name of the collated column.
+            Expression::Alias(..) => CollatedGroupRewriter(&collated).mutate(expr)?,
+            _ if collated.contains(expr) => Expression::Alias(
+                expr.column_name(),
+                Box::new(CollatedGroupRewriter(&collated).mutate(expr)?),
+            ),
+            _ => CollatedGroupRewriter(&collated).mutate(expr)?,
+        };
+    }
+
+    if let Some(predicate) = &mut ir.having_predicate {
+        *predicate = CollatedGroupRewriter(&collated).mutate(predicate)?;
+    }
+
+    for representative in representatives {
+        if !ir.aggregate_expressions.contains(&representative) {
+            ir.aggregate_expressions.push(representative);
+        }
+    }
+
+    Ok(())
+}
+
+/// Replaces the collated group by expressions with the smallest original value of the group.
+struct CollatedGroupRewriter<'a>(&'a [Expression]);
+
+impl ExpressionRewriter for CollatedGroupRewriter<'_> {
+    fn mutate_scalar_function(
+        &mut self,
+        name: &str,
+        args: Vec<Expression>,
+        origin_expr: &Expression,
+    ) -> Result<Expression> {
+        match unwrap_collate(origin_expr) {
+            Some((inner, _)) if self.0.contains(origin_expr) => Ok(Expression::AggregateFunction {
+                op: "min".to_string(),
+                distinct: false,
+                params: vec![],
+                args: vec![inner.clone()],
+            }),
+            _ => Ok(Expression::ScalarFunction {
+                op: name.to_string(),
+                args,
+            }),
+        }
+    }
+}
+
+/// If any of the operands of a comparison is collated, compares the collation keys of
+/// all the operands instead. The operands must not be collated differently.
+pub fn apply_collation(args: &[Expression]) -> Result<Option<Vec<Expression>>> {
+    let mut collation: Option<String> = None;
+    for arg in args {
+        if let Some((_, name)) = unwrap_collate(arg) {
+            if let Some(collation) = &collation {
+                if collation != &name {
+                    return Err(ErrorCode::BadArguments(format!(
+                        "Illegal mix of collations ({}) and ({})",
+                        collation, name
+                    )));
+                }
+            }
+            collation = Some(name);
+        }
+    }
+
+    Ok(collation.map(|collation| {
+        args.iter()
+            .map(|arg| match unwrap_collate(arg) {
+                Some((expr, _)) => collation_key(expr, &collation),
+                None => collation_key(arg, &collation),
+            })
+            .collect()
+    }))
+}
+
+/// Whether the binary operator compares its operands.
+pub fn is_collatable_operator(op: &str) -> bool {
+    matches!(
+        op.to_lowercase().as_str(),
+        "=" | "<>" | "!=" | "<" | "<=" | ">" | ">=" | "<=>" | "like" | "not like"
+    )
+}
@@ -20,17 +20,18 @@ use common_ast::udfs::UDFDefinition;
 use common_ast::udfs::UDFExprTraverser;
 use common_ast::udfs::UDFExprVisitor;
 use common_ast::udfs::UDFFetcher;
-use common_ast::udfs::UDFParser;
 use common_ast::udfs::UDFTransformer;
 use common_datavalues::prelude::*;
 use common_exception::ErrorCode;
 use common_exception::Result;
 use common_functions::aggregates::AggregateFunctionFactory;
 use common_functions::is_builtin_function;
+use common_functions::scalars::WasmFunction;
 use common_meta_types::UserDefinedFunction;
 use common_planners::Expression;
 use sqlparser::ast::DateTimeField;
 use sqlparser::ast::Expr;
+use sqlparser::ast::FunctionArg;
 use sqlparser::ast::FunctionArgExpr;
 use sqlparser::ast::Ident;
 use sqlparser::ast::Query;
@@ -40,6 +41,12 @@ use sqlparser::ast::Value;
 use crate::procedures::ContextFunction;
 use crate::sessions::QueryContext;
 use crate::sessions::SessionType;
+use crate::sql::statements::analyzer_collation::apply_collation;
+use crate::sql::statements::analyzer_collation::collation_name;
+use crate::sql::statements::analyzer_collation::is_collatable_operator;
+use crate::sql::statements::analyzer_udf::resolve_udf;
+use crate::sql::statements::analyzer_udf::scalar_udf_definition;
+use crate::sql::statements::analyzer_udf::wasm_udf_definition;
 use crate::sql::statements::analyzer_value_expr::ValueExprAnalyzer;
 use crate::sql::statements::AnalyzableStatement;
 use crate::sql::statements::AnalyzedResult;
@@ -51,6 +58,8 @@ use crate::sql::SQLCommon;
 pub struct ExpressionAnalyzer {
     context: Arc<QueryContext>,
     udfs: Vec<UserDefinedFunction>,
+    // Used to resolve the overloads of typed UDFs by the column types.
+    input_schema: DataSchemaRef,
 }
 
 impl ExpressionAnalyzer {
@@ -58,21 +67,32 @@ impl ExpressionAnalyzer {
         ExpressionAnalyzer {
             context,
             udfs: vec![],
+            input_schema: Arc::new(DataSchema::empty()),
         }
     }
 
     pub fn create_with_udfs_support(
         context: Arc<QueryContext>,
         udfs: Vec<UserDefinedFunction>,
+        input_schema: DataSchemaRef,
     ) -> ExpressionAnalyzer {
-        ExpressionAnalyzer { context, udfs }
+        ExpressionAnalyzer {
+            context,
+            udfs,
+            input_schema,
+        }
     }
 
     pub fn analyze_sync(&self, expr: &Expr) -> Result<Expression> {
         let mut stack = Vec::new();
 
         // Build RPN for expr. Because async function unsupported recursion
-        for rpn_item in &ExprRPNBuilder::build(expr, self.udfs.clone())? {
+        for rpn_item in &ExprRPNBuilder::build(
+            expr,
+            self.context.clone(),
+            self.udfs.clone(),
+            self.input_schema.clone(),
+        )? {
             match rpn_item {
                 ExprRPNItem::Value(v) => Self::analyze_value(
                     v,
@@ -110,7 +130,12 @@ impl ExpressionAnalyzer {
         let mut stack = Vec::new();
 
         // Build RPN for expr. Because async function unsupported recursion
-        for rpn_item in &ExprRPNBuilder::build(expr, self.udfs.clone())? {
+        for rpn_item in &ExprRPNBuilder::build(
+            expr,
+            self.context.clone(),
+            self.udfs.clone(),
+            self.input_schema.clone(),
+        )? {
             match rpn_item {
                 ExprRPNItem::Value(v) => Self::analyze_value(
                     v,
@@ -178,6 +203,9 @@ impl ExpressionAnalyzer {
         } else {
             "IN".to_string()
         };
+        if let Some(collated) = apply_collation(&list)? {
+            list = collated;
+        }
 
         args.push(Expression::ScalarFunction { op, args: list });
         Ok(())
@@ -221,6 +249,11 @@ impl ExpressionAnalyzer {
 
     fn binary_function(info: &FunctionExprInfo, args: &[Expression]) -> Result<Expression> {
         let op = info.name.clone();
+        let collated = match is_collatable_operator(&op) {
+            true => apply_collation(args)?,
+            false => None,
+        };
+        let args = collated.as_deref().unwrap_or(args);
         match args.len() < 2 {
             true => Err(ErrorCode::LogicalError(
                 "Binary operator must be two children.",
@@ -234,6 +267,7 @@ impl ExpressionAnalyzer {
     }
 
     fn other_function(&self, info: &FunctionExprInfo, args: &[Expression]) -> Result<Expression> {
+        WasmFunction::check_tenant(&self.context.get_tenant(), &info.name)?;
         let query_context = self.context.clone();
         let context_args = ContextFunction::build_args_from_ctx(query_context, &info.name)?;
 
@@ -407,7 +441,10 @@ impl ExpressionAnalyzer {
             ));
         }
 
-        let s_args = args.split_off(args.len() - 3);
+        let mut s_args = args.split_off(args.len() - 3);
+        if let Some(collated) = apply_collation(&s_args)? {
+            s_args = collated;
+        }
         let expression = s_args[0].clone();
         let low_expression = s_args[1].clone();
         let high_expression = s_args[2].clone();
@@ -568,14 +605,23 @@ impl ExprRPNItem {
 
 struct ExprRPNBuilder {
     rpn: Vec<ExprRPNItem>,
+    context: Arc<QueryContext>,
     udfs: Vec<UserDefinedFunction>,
+    input_schema: DataSchemaRef,
 }
 
 impl ExprRPNBuilder {
-    pub fn build(expr: &Expr, udfs: Vec<UserDefinedFunction>) -> Result<Vec<ExprRPNItem>> {
+    pub fn build(
+        expr: &Expr,
+        context: Arc<QueryContext>,
+        udfs: Vec<UserDefinedFunction>,
+        input_schema: DataSchemaRef,
+    ) -> Result<Vec<ExprRPNItem>> {
         let mut builder = ExprRPNBuilder {
             rpn: Vec::new(),
+            context,
             udfs,
+            input_schema,
         };
         UDFExprTraverser::accept(expr, &mut builder)?;
         Ok(builder.rpn)
@@ -731,6 +777,13 @@ impl ExprRPNBuilder {
             Expr::Array(exprs) => {
                 self.rpn.push(ExprRPNItem::Array(exprs.len()));
             }
+            Expr::Collate { collation, .. } => {
+                let collation = collation_name(collation)?;
+                self.rpn
+                    .push(ExprRPNItem::Value(Value::SingleQuotedString(collation)));
+                self.rpn
+                    .push(ExprRPNItem::function(String::from("collate"), 2));
+            }
             _ => (),
         }
 
@@ -740,15 +793,16 @@ impl ExprRPNBuilder {
 
 #[async_trait]
 impl UDFFetcher for ExprRPNBuilder {
-    fn get_udf_definition(&self, name: &str) -> Result<UDFDefinition> {
-        let udf = self.udfs.iter().find(|udf| udf.name == name);
-
-        if let Some(udf) = udf {
-            let mut udf_parser = UDFParser::default();
-            let definition = udf_parser.parse(&udf.name, &udf.parameters, &udf.definition)?;
-            return Ok(UDFDefinition::new(udf.parameters.clone(), definition));
+    fn get_udf_definition(&self, name: &str, args: &[FunctionArg]) -> Result<UDFDefinition> {
+        match resolve_udf(&self.udfs, name, args, &self.input_schema, false)? {
+            Some(udf) if udf.is_wasm() => wasm_udf_definition(&self.context, udf),
+            Some(udf) => scalar_udf_definition(udf),
+            // Reports that a table-valued UDF is called as a scalar function.
+            None => match self.udfs.iter().find(|udf| udf.name == name) {
+                Some(udf) => scalar_udf_definition(udf),
+                None => Err(ErrorCode::UnknownUDF(format!("Unknown Function {}", name))),
+            },
         }
-        Err(ErrorCode::UnknownUDF(format!("Unknown Function {}", name)))
     }
 }
 
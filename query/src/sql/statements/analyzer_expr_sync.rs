@@ -28,6 +28,7 @@ use common_meta_types::UserDefinedFunction;
 use common_planners::Expression;
 use sqlparser::ast::DateTimeField;
 use sqlparser::ast::Expr;
+use sqlparser::ast::FunctionArg;
 use sqlparser::ast::FunctionArgExpr;
 use sqlparser::ast::Ident;
 use sqlparser::ast::Query;
@@ -35,6 +36,9 @@ use sqlparser::ast::UnaryOperator;
 use sqlparser::ast::Value;
 
 use crate::sessions::SessionType;
+use crate::sql::statements::analyzer_collation::apply_collation;
+use crate::sql::statements::analyzer_collation::collation_name;
+use crate::sql::statements::analyzer_collation::is_collatable_operator;
 use crate::sql::statements::analyzer_value_expr::ValueExprAnalyzer;
 use crate::sql::SQLCommon;
 
@@ -118,6 +122,9 @@ impl ExpressionSyncAnalyzer {
         } else {
             "IN".to_string()
         };
+        if let Some(collated) = apply_collation(&list)? {
+            list = collated;
+        }
 
         args.push(Expression::ScalarFunction { op, args: list });
         Ok(())
@@ -154,6 +161,13 @@ impl ExpressionSyncAnalyzer {
     }
 
     fn other_function(info: &FunctionExprInfo, args: &[Expression]) -> Result<Expression> {
+        // The WebAssembly functions are scoped by the tenant, which is unknown here.
+        if info.name.to_lowercase().starts_with("wasm::") {
+            return Err(ErrorCode::UnknownFunction(format!(
+                "Unsupported Function: {}",
+                info.name
+            )));
+        }
         let op = info.name.clone();
         let arguments = args.to_owned();
         Ok(Expression::ScalarFunction {
@@ -174,6 +188,11 @@ impl ExpressionSyncAnalyzer {
 
     fn binary_function(info: &FunctionExprInfo, args: &[Expression]) -> Result<Expression> {
         let op = info.name.clone();
+        let collated = match is_collatable_operator(&op) {
+            true => apply_collation(args)?,
+            false => None,
+        };
+        let args = collated.as_deref().unwrap_or(args);
         match args.len() < 2 {
             true => Err(ErrorCode::LogicalError(
                 "Binary operator must be two children.",
@@ -231,7 +250,10 @@ impl ExpressionSyncAnalyzer {
             ));
         }
 
-        let s_args = args.split_off(args.len() - 3);
+        let mut s_args = args.split_off(args.len() - 3);
+        if let Some(collated) = apply_collation(&s_args)? {
+            s_args = collated;
+        }
         let expression = s_args[0].clone();
         let low_expression = s_args[1].clone();
         let high_expression = s_args[2].clone();
@@ -543,6 +565,13 @@ impl ExprRPNBuilder {
             Expr::Array(exprs) => {
                 self.rpn.push(ExprRPNItem::Array(exprs.len()));
             }
+            Expr::Collate { collation, .. } => {
+                let collation = collation_name(collation)?;
+                self.rpn
+                    .push(ExprRPNItem::Value(Value::SingleQuotedString(collation)));
+                self.rpn
+                    .push(ExprRPNItem::function(String::from("collate"), 2));
+            }
             _ => (),
         }
 
@@ -552,7 +581,7 @@ impl ExprRPNBuilder {
 
 #[async_trait]
 impl UDFFetcher for ExprRPNBuilder {
-    fn get_udf_definition(&self, name: &str) -> Result<UDFDefinition> {
+    fn get_udf_definition(&self, name: &str, _args: &[FunctionArg]) -> Result<UDFDefinition> {
         let udf = self.udfs.iter().find(|udf| udf.name == name);
 
         if let Some(udf) = udf {
@@ -0,0 +1,585 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use common_ast::udfs::UDFDefinition;
+use common_ast::udfs::UDFParser;
+use common_ast::udfs::UDFTransformer;
+use common_datavalues::prelude::*;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_functions::scalars::WasmFunction;
+use common_functions::scalars::WasmLimits;
+use common_meta_types::UDFReturnType;
+use common_meta_types::UserDefinedFunction;
+use common_planners::Expression;
+use common_planners::ExpressionVisitor;
+use common_planners::PlanNode;
+use common_planners::PlanVisitor;
+use common_planners::ReadDataSourcePlan;
+use common_planners::Recursion;
+use sqlparser::ast::DataType;
+use sqlparser::ast::Expr;
+use sqlparser::ast::Function;
+use sqlparser::ast::FunctionArg;
+use sqlparser::ast::FunctionArgExpr;
+use sqlparser::ast::Ident;
+use sqlparser::ast::ObjectName;
+use sqlparser::ast::SelectItem;
+use sqlparser::ast::TableFactor;
+use sqlparser::ast::TableWithJoins;
+use sqlparser::ast::Value;
+
+use crate::sessions::QueryContext;
+use crate::sessions::SessionType;
+use crate::sql::statements::DfQueryStatement;
+use crate::sql::DfParser;
+use crate::sql::DfStatement;
+use crate::sql::SQLCommon;
+
+/// Finds the UDF which the call `name(args)` refers to.
+///
+/// Untyped UDFs are identified by name. Typed UDFs can be overloaded by the parameter types,
+/// the overload is chosen by the arity first, then by how well the parameter types match the
+/// argument types inferred from literals, casts and the columns of `input_schema`.
+pub fn resolve_udf<'a>(
+    udfs: &'a [UserDefinedFunction],
+    name: &str,
+    args: &[FunctionArg],
+    input_schema: &DataSchemaRef,
+    table_function: bool,
+) -> Result<Option<&'a UserDefinedFunction>> {
+    let named = udfs
+        .iter()
+        .filter(|udf| udf.name == name && udf.is_table_function() == table_function)
+        .collect::<Vec<_>>();
+
+    if named.is_empty() {
+        return Ok(None);
+    }
+
+    if let Some(udf) = named.iter().find(|udf| !udf.is_typed()) {
+        return Ok(Some(*udf));
+    }
+
+    let candidates = named
+        .into_iter()
+        .filter(|udf| udf.parameter_types.len() == args.len())
+        .collect::<Vec<_>>();
+
+    match candidates.len() {
+        0 => Err(ErrorCode::SyntaxException(format!(
+            "No overload of function {} takes {} arguments",
+            name,
+            args.len()
+        ))),
+        1 => Ok(Some(candidates[0])),
+        _ => {
+            let arg_types = args
+                .iter()
+                .map(|arg| infer_arg_type(arg, input_schema))
+                .collect::<Vec<_>>();
+
+            let mut scored = Vec::with_capacity(candidates.len());
+            for udf in candidates {
+                let mut score = 0;
+                for (parameter_type, arg_type) in udf.parameter_types.iter().zip(&arg_types) {
+                    score += match_score(parameter_type, arg_type)?;
+                }
+                scored.push((score, udf));
+            }
+
+            scored.sort_by(|(left, _), (right, _)| right.cmp(left));
+            if scored[0].0 == scored[1].0 {
+                return Err(ErrorCode::SyntaxException(format!(
+                    "Function call {}({}) is ambiguous between {} and {}, add an explicit CAST to the arguments",
+                    name,
+                    args.iter()
+                        .map(|arg| arg.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    scored[0].1.signature(),
+                    scored[1].1.signature(),
+                )));
+            }
+
+            Ok(Some(scored[0].1))
+        }
+    }
+}
+
+/// The definition of a scalar UDF which the transformer replaces the call with.
+pub fn scalar_udf_definition(udf: &UserDefinedFunction) -> Result<UDFDefinition> {
+    let mut udf_parser = UDFParser::default();
+    match &udf.return_type {
+        UDFReturnType::Untyped => {
+            let definition = udf_parser.parse(&udf.name, &udf.parameters, &udf.definition)?;
+            Ok(UDFDefinition::new(udf.parameters.clone(), definition))
+        }
+        UDFReturnType::Scalar(return_type) => {
+            let definition = udf_parser.parse(&udf.name, &udf.parameters, &udf.definition)?;
+            Ok(UDFDefinition::new_typed(
+                udf.parameters.clone(),
+                udf.parameter_types
+                    .iter()
+                    .map(|t| udf_data_type(t))
+                    .collect(),
+                udf_data_type(return_type),
+                definition,
+            ))
+        }
+        UDFReturnType::Table(_) => Err(ErrorCode::SyntaxException(format!(
+            "Table function {} can only be used in the FROM clause",
+            udf.name
+        ))),
+    }
+}
+
+/// The definition of a WebAssembly UDF, the call is replaced by the call of the function
+/// which the module is registered as in the `FunctionFactory` for the tenant.
+pub fn wasm_udf_definition(ctx: &QueryContext, udf: &UserDefinedFunction) -> Result<UDFDefinition> {
+    let settings = ctx.get_settings();
+    let limits = WasmLimits {
+        max_memory: settings.get_wasm_udf_max_memory_mb()? as usize * 1024 * 1024,
+        timeout: Duration::from_millis(settings.get_wasm_udf_timeout_ms()?),
+    };
+
+    let (parameter_types, return_type, return_type_name) = wasm_udf_types(udf)?;
+    let registered_name = WasmFunction::register(
+        &ctx.get_tenant(),
+        &udf.name,
+        &parameter_types,
+        &return_type,
+        &udf.definition,
+        &limits,
+    )?;
+
+    let definition = Expr::Function(Function {
+        name: ObjectName(vec![Ident::new(registered_name)]),
+        params: vec![],
+        args: udf
+            .parameters
+            .iter()
+            .map(|parameter| {
+                FunctionArg::Unnamed(FunctionArgExpr::Expr(Expr::Identifier(Ident::new(
+                    parameter,
+                ))))
+            })
+            .collect(),
+        over: None,
+        distinct: false,
+    });
+
+    Ok(UDFDefinition::new_typed(
+        udf.parameters.clone(),
+        udf.parameter_types
+            .iter()
+            .map(|t| udf_data_type(t))
+            .collect(),
+        udf_data_type(return_type_name),
+        definition,
+    ))
+}
+
+// The parameter types, the return type and the name of the return type of a WebAssembly UDF.
+fn wasm_udf_types(udf: &UserDefinedFunction) -> Result<(Vec<DataTypeImpl>, DataTypeImpl, &str)> {
+    let return_type = match &udf.return_type {
+        UDFReturnType::Scalar(return_type) => return_type,
+        _ => {
+            return Err(ErrorCode::IllegalUDFFormat(format!(
+                "WebAssembly function {} must return a scalar type",
+                udf.name
+            )))
+        }
+    };
+
+    let type_factory = TypeFactory::instance();
+    let parameter_types = udf
+        .parameter_types
+        .iter()
+        .map(|type_name| Ok(type_factory.get(type_name)?.clone()))
+        .collect::<Result<Vec<_>>>()?;
+    Ok((
+        parameter_types,
+        type_factory.get(return_type)?.clone(),
+        return_type,
+    ))
+}
+
+/// Registers the WebAssembly functions called by a plan which is sent by another node of the
+/// cluster, the modules are loaded from the UDFs of the tenant in the meta service.
+///
+/// The functions that are dropped or changed are unregistered, and the plan fails as the
+/// functions are unknown.
+pub async fn register_plan_wasm_functions(ctx: &QueryContext, plan: &PlanNode) -> Result<()> {
+    let tenant = ctx.get_tenant();
+    let mut visitor = WasmFunctionVisitor {
+        tenant: tenant.clone(),
+        functions: vec![],
+    };
+    visitor.visit_plan_node(plan)?;
+    if visitor.functions.is_empty() {
+        return Ok(());
+    }
+
+    let udfs = ctx.get_user_manager().get_udfs(&tenant).await?;
+    for (registered_name, name, limits) in visitor.functions {
+        let mut registered = false;
+        for udf in udfs.iter().filter(|udf| udf.is_wasm() && udf.name == name) {
+            let (parameter_types, return_type, _) = wasm_udf_types(udf)?;
+            let expected_name = WasmFunction::registered_name(
+                &tenant,
+                &udf.name,
+                &parameter_types,
+                &return_type,
+                &udf.definition,
+                &limits,
+            );
+            if expected_name.eq_ignore_ascii_case(&registered_name) {
+                WasmFunction::register(
+                    &tenant,
+                    &udf.name,
+                    &parameter_types,
+                    &return_type,
+                    &udf.definition,
+                    &limits,
+                )?;
+                registered = true;
+                break;
+            }
+        }
+
+        if !registered {
+            WasmFunction::unregister(&tenant, &name);
+        }
+    }
+    Ok(())
+}
+
+// Collects the WebAssembly functions of the tenant called by a plan.
+struct WasmFunctionVisitor {
+    tenant: String,
+    functions: Vec<(String, String, WasmLimits)>,
+}
+
+impl WasmFunctionVisitor {
+    fn collect(&mut self, expr: &Expression) -> Result<Vec<PlanNode>> {
+        let collector = expr.accept(WasmFunctionCollector {
+            tenant: &self.tenant,
+            functions: vec![],
+            subqueries: vec![],
+        })?;
+        for function in collector.functions {
+            if !self.functions.iter().any(|f| f.0 == function.0) {
+                self.functions.push(function);
+            }
+        }
+        Ok(collector.subqueries)
+    }
+}
+
+impl PlanVisitor for WasmFunctionVisitor {
+    fn visit_expr(&mut self, expr: &Expression) -> Result<()> {
+        for subquery in self.collect(expr)? {
+            self.visit_plan_node(&subquery)?;
+        }
+        Ok(())
+    }
+
+    fn visit_read_data_source(&mut self, plan: &ReadDataSourcePlan) -> Result<()> {
+        if let Some(push_downs) = &plan.push_downs {
+            self.visit_exprs(&push_downs.filters)?;
+        }
+        Ok(())
+    }
+}
+
+struct WasmFunctionCollector<'a> {
+    tenant: &'a str,
+    functions: Vec<(String, String, WasmLimits)>,
+    subqueries: Vec<PlanNode>,
+}
+
+impl<'a> ExpressionVisitor for WasmFunctionCollector<'a> {
+    fn pre_visit(mut self, expr: &Expression) -> Result<Recursion<Self>> {
+        match expr {
+            Expression::ScalarFunction { op, .. } => {
+                if let Some((name, limits)) = WasmFunction::parse_registered_name(self.tenant, op) {
+                    self.functions.push((op.clone(), name, limits));
+                }
+            }
+            Expression::Subquery { query_plan, .. }
+            | Expression::ScalarSubquery { query_plan, .. } => {
+                self.subqueries.push(query_plan.as_ref().clone());
+            }
+            _ => {}
+        }
+        Ok(Recursion::Continue(self))
+    }
+}
+
+/// Expands the call of a table-valued UDF into its query, the parameters are replaced by
+/// the arguments cast to the parameter types.
+pub fn table_udf_query(
+    udf: &UserDefinedFunction,
+    args: &[FunctionArg],
+    session_type: SessionType,
+) -> Result<DfQueryStatement> {
+    if udf.parameters.len() != args.len() {
+        return Err(ErrorCode::BadArguments(format!(
+            "Function {} requires {} arguments, but got: {}",
+            udf.signature(),
+            udf.parameters.len(),
+            args.len()
+        )));
+    }
+
+    let mut args_map = HashMap::with_capacity(args.len());
+    let mut table_args_map = HashMap::with_capacity(args.len());
+    for (index, arg) in args.iter().enumerate() {
+        let arg = match arg {
+            FunctionArg::Named { arg, .. } => arg,
+            FunctionArg::Unnamed(arg) => arg,
+        };
+
+        match arg {
+            FunctionArgExpr::Expr(expr) => {
+                let parameter = &udf.parameters[index];
+                args_map.insert(parameter.clone(), Expr::Cast {
+                    expr: Box::new(expr.clone()),
+                    data_type: udf_data_type(&udf.parameter_types[index]),
+                    pg_style: false,
+                });
+                // Table functions like `numbers(n)` only accept literal arguments.
+                table_args_map.insert(parameter.clone(), expr.clone());
+            }
+            _ => {
+                return Err(ErrorCode::SyntaxException(format!(
+                    "Unsupported argument of table function {}: {}",
+                    udf.name, arg
+                )));
+            }
+        }
+    }
+
+    let (statements, _) = DfParser::parse_sql(&udf.definition, session_type)?;
+    let mut query = match statements.as_slice() {
+        [DfStatement::Query(query)] => query.as_ref().clone(),
+        _ => {
+            return Err(ErrorCode::SyntaxException(format!(
+                "The definition of table function {} must be a query",
+                udf.name
+            )));
+        }
+    };
+
+    replace_query_parameters(&mut query, &args_map, &table_args_map)?;
+    if let UDFReturnType::Table(columns) = &udf.return_type {
+        declare_return_columns(&mut query, columns, &udf.name)?;
+    }
+    Ok(query)
+}
+
+// Casts the projection to the declared columns of the table function, the aliases used in
+// GROUP BY, HAVING and ORDER BY are renamed along with the projection.
+fn declare_return_columns(
+    query: &mut DfQueryStatement,
+    columns: &[(String, String)],
+    name: &str,
+) -> Result<()> {
+    if query.projection.len() != columns.len() {
+        return Err(ErrorCode::SyntaxException(format!(
+            "Table function {} returns {} columns, but its query returns {}",
+            name,
+            columns.len(),
+            query.projection.len()
+        )));
+    }
+
+    let mut renames = HashMap::with_capacity(columns.len());
+    for (item, (column, data_type)) in query.projection.iter_mut().zip(columns) {
+        let expr = match item {
+            SelectItem::UnnamedExpr(expr) => expr.clone(),
+            SelectItem::ExprWithAlias { expr, alias } => {
+                renames.insert(alias.value.clone(), Expr::Identifier(Ident::new(column)));
+                expr.clone()
+            }
+            _ => {
+                return Err(ErrorCode::SyntaxException(format!(
+                    "Table function {} must list the returned columns instead of the wildcard",
+                    name
+                )));
+            }
+        };
+
+        *item = SelectItem::ExprWithAlias {
+            expr: Expr::Cast {
+                expr: Box::new(expr),
+                data_type: udf_data_type(data_type),
+                pg_style: false,
+            },
+            alias: Ident::new(column),
+        };
+    }
+
+    let rename = |expr: &Expr| UDFTransformer::replace_parameters(expr, &renames);
+    for expr in query.group_by.iter_mut() {
+        *expr = rename(expr)?;
+    }
+
+    if let Some(having) = &query.having {
+        query.having = Some(rename(having)?);
+    }
+
+    for order_by in query.order_by.iter_mut() {
+        order_by.expr = rename(&order_by.expr)?;
+    }
+
+    Ok(())
+}
+
+// Replaces the parameters in the clauses of the query with the arguments.
+fn replace_query_parameters(
+    query: &mut DfQueryStatement,
+    args_map: &HashMap<String, Expr>,
+    table_args_map: &HashMap<String, Expr>,
+) -> Result<()> {
+    let replace = |expr: &Expr| UDFTransformer::replace_parameters(expr, args_map);
+
+    for item in query.projection.iter_mut() {
+        match item {
+            SelectItem::UnnamedExpr(expr) => *expr = replace(expr)?,
+            SelectItem::ExprWithAlias { expr, .. } => *expr = replace(expr)?,
+            _ => {}
+        }
+    }
+
+    for table in query.from.iter_mut() {
+        replace_table_parameters(table, table_args_map)?;
+    }
+
+    if let Some(selection) = &query.selection {
+        query.selection = Some(replace(selection)?);
+    }
+
+    for expr in query.group_by.iter_mut() {
+        *expr = replace(expr)?;
+    }
+
+    if let Some(having) = &query.having {
+        query.having = Some(replace(having)?);
+    }
+
+    for order_by in query.order_by.iter_mut() {
+        order_by.expr = replace(&order_by.expr)?;
+    }
+
+    if let Some(limit) = &query.limit {
+        query.limit = Some(replace(limit)?);
+    }
+
+    if let Some(offset) = query.offset.as_mut() {
+        offset.value = replace(&offset.value)?;
+    }
+
+    Ok(())
+}
+
+// The arguments of the table functions in the FROM clause may refer to the parameters too,
+// e.g. `SELECT number FROM numbers(n)`.
+fn replace_table_parameters(
+    table: &mut TableWithJoins,
+    args_map: &HashMap<String, Expr>,
+) -> Result<()> {
+    let mut factors = vec![&mut table.relation];
+    factors.extend(table.joins.iter_mut().map(|join| &mut join.relation));
+
+    for factor in factors {
+        if let TableFactor::Table { args, .. } = factor {
+            for arg in args.iter_mut() {
+                let arg = match arg {
+                    FunctionArg::Named { arg, .. } => arg,
+                    FunctionArg::Unnamed(arg) => arg,
+                };
+
+                if let FunctionArgExpr::Expr(expr) = arg {
+                    *expr = UDFTransformer::replace_parameters(expr, args_map)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Typed UDFs store the data type names, e.g. `Int32`, which the type factory resolves.
+pub fn udf_data_type(name: &str) -> DataType {
+    DataType::Custom(ObjectName(vec![Ident::new(name)]))
+}
+
+fn infer_arg_type(arg: &FunctionArg, input_schema: &DataSchemaRef) -> Option<DataTypeImpl> {
+    match arg {
+        FunctionArg::Named {
+            arg: FunctionArgExpr::Expr(expr),
+            ..
+        } => infer_expr_type(expr, input_schema),
+        FunctionArg::Unnamed(FunctionArgExpr::Expr(expr)) => infer_expr_type(expr, input_schema),
+        _ => None,
+    }
+}
+
+fn infer_expr_type(expr: &Expr, input_schema: &DataSchemaRef) -> Option<DataTypeImpl> {
+    match expr {
+        Expr::Value(Value::Number(number, _)) => match number.parse::<i64>() {
+            Ok(_) => Some(Int64Type::new_impl()),
+            Err(_) => Some(Float64Type::new_impl()),
+        },
+        Expr::Value(Value::SingleQuotedString(_)) => Some(StringType::new_impl()),
+        Expr::Value(Value::Boolean(_)) => Some(BooleanType::new_impl()),
+        Expr::Cast { data_type, .. } | Expr::TryCast { data_type, .. } => {
+            SQLCommon::make_data_type(data_type).ok()
+        }
+        Expr::Nested(expr) => infer_expr_type(expr, input_schema),
+        Expr::Identifier(ident) => input_schema
+            .field_with_name(&ident.value)
+            .ok()
+            .map(|field| field.data_type().clone()),
+        _ => None,
+    }
+}
+
+// 2 if the argument has exactly the parameter type, 1 if they are of the same family,
+// e.g. both are numbers, otherwise 0.
+fn match_score(parameter_type: &str, arg_type: &Option<DataTypeImpl>) -> Result<usize> {
+    let arg_type = match arg_type {
+        None => return Ok(0),
+        Some(arg_type) => remove_nullable(arg_type),
+    };
+
+    let parameter_type = SQLCommon::make_data_type(&udf_data_type(parameter_type))?;
+    let parameter_type = remove_nullable(&parameter_type);
+    if parameter_type.name() == arg_type.name() {
+        return Ok(2);
+    }
+
+    let parameter_id = parameter_type.data_type_id();
+    let arg_id = arg_type.data_type_id();
+    let same_family = (parameter_id.is_numeric() && arg_id.is_numeric())
+        || (parameter_id.is_string() && arg_id.is_string())
+        || (parameter_id.is_date_or_date_time() && arg_id.is_date_or_date_time());
+
+    Ok(same_family as usize)
+}
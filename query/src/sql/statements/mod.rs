@@ -14,9 +14,11 @@
 
 pub mod query;
 
+mod analyzer_collation;
 mod analyzer_expr;
 mod analyzer_expr_sync;
 mod analyzer_statement;
+mod analyzer_udf;
 mod analyzer_value_expr;
 mod statement_alter_database;
 mod statement_alter_table;
@@ -27,6 +29,7 @@ mod statement_call;
 mod statement_common;
 mod statement_copy;
 mod statement_create_database;
+mod statement_create_materialized_view;
 mod statement_create_role;
 mod statement_create_table;
 mod statement_create_udf;
@@ -48,6 +51,7 @@ mod statement_insert;
 mod statement_kill;
 mod statement_list;
 mod statement_optimize_table;
+mod statement_refresh_materialized_view;
 mod statement_rename_table;
 mod statement_revoke;
 mod statement_select;
@@ -77,6 +81,7 @@ pub use analyzer_statement::AnalyzableStatement;
 pub use analyzer_statement::AnalyzedResult;
 pub use analyzer_statement::QueryAnalyzeState;
 pub use analyzer_statement::QueryRelation;
+pub use analyzer_udf::register_plan_wasm_functions;
 pub use query::QueryASTIR;
 pub use statement_alter_database::AlterDatabaseAction;
 pub use statement_alter_database::DfAlterDatabase;
@@ -89,9 +94,12 @@ pub use statement_call::DfCall;
 pub use statement_common::*;
 pub use statement_copy::*;
 pub use statement_create_database::DfCreateDatabase;
+pub use statement_create_materialized_view::DfCreateMaterializedView;
 pub use statement_create_role::DfCreateRole;
 pub use statement_create_table::DfCreateTable;
 pub use statement_create_udf::DfCreateUDF;
+pub use statement_create_udf::DfUDFLanguage;
+pub use statement_create_udf::DfUDFReturnType;
 pub use statement_create_user::DfAuthOption;
 pub use statement_create_user::DfCreateUser;
 pub use statement_create_user::DfUserWithOption;
@@ -115,6 +123,7 @@ pub use statement_insert::InsertSource;
 pub use statement_kill::DfKillStatement;
 pub use statement_list::DfList;
 pub use statement_optimize_table::DfOptimizeTable;
+pub use statement_refresh_materialized_view::DfRefreshMaterializedView;
 pub use statement_rename_table::DfRenameTable;
 pub use statement_revoke::DfRevokePrivilegeStatement;
 pub use statement_revoke::DfRevokeRoleStatement;
@@ -15,6 +15,8 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use common_datavalues::DataSchema;
+use common_datavalues::DataSchemaRef;
 use common_exception::ErrorCode;
 use common_exception::Result;
 use common_planners::extract_aliases;
@@ -39,11 +41,18 @@ pub struct QueryNormalizer {
 
 /// Replace alias in query and collect aggregate functions
 impl QueryNormalizer {
-    async fn try_create(ctx: Arc<QueryContext>) -> Result<QueryNormalizer> {
+    async fn try_create(
+        ctx: Arc<QueryContext>,
+        input_schema: DataSchemaRef,
+    ) -> Result<QueryNormalizer> {
         let tenant = ctx.get_tenant();
         let udfs = ctx.get_user_manager().get_udfs(&tenant).await?;
         Ok(QueryNormalizer {
-            expression_analyzer: ExpressionAnalyzer::create_with_udfs_support(ctx, udfs),
+            expression_analyzer: ExpressionAnalyzer::create_with_udfs_support(
+                ctx,
+                udfs,
+                input_schema,
+            ),
             aliases_map: HashMap::new(),
             query_ast_ir: QueryASTIR {
                 filter_predicate: None,
@@ -60,7 +69,17 @@ impl QueryNormalizer {
     }
 
     pub async fn normalize(ctx: Arc<QueryContext>, v: &DfQueryStatement) -> Result<QueryASTIR> {
-        let query_normalizer = QueryNormalizer::try_create(ctx).await?;
+        Self::normalize_with_schema(ctx, v, Arc::new(DataSchema::empty())).await
+    }
+
+    /// The `input_schema` is the schema of the FROM clause, the overloads of typed UDFs
+    /// are resolved by the types of its columns.
+    pub async fn normalize_with_schema(
+        ctx: Arc<QueryContext>,
+        v: &DfQueryStatement,
+        input_schema: DataSchemaRef,
+    ) -> Result<QueryASTIR> {
+        let query_normalizer = QueryNormalizer::try_create(ctx, input_schema).await?;
         query_normalizer.transform(v).await
     }
 
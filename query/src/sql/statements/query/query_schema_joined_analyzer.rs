@@ -14,6 +14,7 @@
 
 use std::sync::Arc;
 
+use common_datavalues::DataSchema;
 use common_exception::ErrorCode;
 use common_exception::Result;
 use sqlparser::ast::FunctionArg;
@@ -28,6 +29,8 @@ use sqlparser::ast::TableWithJoins;
 use crate::catalogs::CATALOG_DEFAULT;
 use crate::sessions::QueryContext;
 use crate::sql::statements::analyzer_expr::ExpressionAnalyzer;
+use crate::sql::statements::analyzer_udf::resolve_udf;
+use crate::sql::statements::analyzer_udf::table_udf_query;
 use crate::sql::statements::query::query_schema_joined::JoinedSchema;
 use crate::sql::statements::resolve_table;
 use crate::sql::statements::AnalyzableStatement;
@@ -144,6 +147,27 @@ impl JoinedSchemaAnalyzer {
         }
 
         let table_name = item.name.0[0].value.clone();
+
+        // Table-valued UDFs are expanded into their queries, just like views.
+        let tenant = self.ctx.get_tenant();
+        let udfs = self.ctx.get_user_manager().get_udfs(&tenant).await?;
+        let empty_schema = Arc::new(DataSchema::empty());
+        if let Some(udf) = resolve_udf(&udfs, &table_name, &item.args, &empty_schema, true)? {
+            let session_type = self.ctx.get_current_session().get_type();
+            let subquery = table_udf_query(udf, &item.args, session_type)?;
+            if let AnalyzedResult::SelectQuery(state) = subquery.analyze(self.ctx.clone()).await? {
+                let name_prefix = match &item.alias {
+                    None => vec![table_name],
+                    Some(table_alias) => vec![table_alias.name.value.clone()],
+                };
+                return JoinedSchema::from_subquery(state, name_prefix);
+            }
+
+            return Err(ErrorCode::LogicalError(
+                "Logical error, subquery analyzed data must be SelectQuery, it's a bug.",
+            ));
+        }
+
         let mut table_args = Vec::with_capacity(item.args.len());
         let analyzer = ExpressionAnalyzer::create(self.ctx.clone());
 
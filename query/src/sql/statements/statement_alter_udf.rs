@@ -15,36 +15,47 @@
 use std::sync::Arc;
 
 use common_exception::Result;
-use common_meta_types::UserDefinedFunction;
 use common_planners::AlterUserUDFPlan;
 use common_planners::PlanNode;
 use common_tracing::tracing;
+use sqlparser::ast::DataType;
 
 use crate::sessions::QueryContext;
+use crate::sql::statements::statement_create_udf::build_udf;
 use crate::sql::statements::AnalyzableStatement;
 use crate::sql::statements::AnalyzedResult;
+use crate::sql::statements::DfUDFLanguage;
+use crate::sql::statements::DfUDFReturnType;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct DfAlterUDF {
     pub udf_name: String,
     pub parameters: Vec<String>,
+    pub parameter_types: Vec<DataType>,
+    pub return_type: DfUDFReturnType,
+    pub language: DfUDFLanguage,
     pub definition: String,
     pub description: String,
 }
 
 #[async_trait::async_trait]
 impl AnalyzableStatement for DfAlterUDF {
-    #[tracing::instrument(level = "info", skip(self, _ctx), fields(ctx.id = _ctx.get_id().as_str()))]
-    async fn analyze(&self, _ctx: Arc<QueryContext>) -> Result<AnalyzedResult> {
+    #[tracing::instrument(level = "info", skip(self, ctx), fields(ctx.id = ctx.get_id().as_str()))]
+    async fn analyze(&self, ctx: Arc<QueryContext>) -> Result<AnalyzedResult> {
+        let udf = build_udf(
+            ctx,
+            &self.udf_name,
+            &self.parameters,
+            &self.parameter_types,
+            &self.return_type,
+            self.language,
+            &self.definition,
+            &self.description,
+        )
+        .await?;
+
         Ok(AnalyzedResult::SimpleQuery(Box::new(
-            PlanNode::AlterUserUDF(AlterUserUDFPlan {
-                udf: UserDefinedFunction::new(
-                    self.udf_name.as_str(),
-                    self.parameters.clone(),
-                    self.definition.as_str(),
-                    self.description.as_str(),
-                ),
-            }),
+            PlanNode::AlterUserUDF(AlterUserUDFPlan { udf }),
         )))
     }
 }
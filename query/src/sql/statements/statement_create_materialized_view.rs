@@ -0,0 +1,109 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_exception::Result;
+use common_planners::CreateMaterializedViewPlan;
+use common_planners::PlanNode;
+use common_tracing::tracing;
+use sqlparser::ast::Ident;
+use sqlparser::ast::ObjectName;
+use sqlparser::ast::Query;
+use sqlparser::ast::SetExpr;
+use sqlparser::ast::TableFactor;
+
+use crate::sessions::QueryContext;
+use crate::sql::statements::resolve_table;
+use crate::sql::statements::AnalyzableStatement;
+use crate::sql::statements::AnalyzedResult;
+use crate::sql::statements::DfQueryStatement;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DfCreateMaterializedView {
+    pub if_not_exists: bool,
+    /// Materialized view name
+    pub name: ObjectName,
+    /// Original query, stored in the options of the materialized view
+    pub subquery: Query,
+    /// Check and Analyze Select query
+    pub query: DfQueryStatement,
+}
+
+#[async_trait::async_trait]
+impl AnalyzableStatement for DfCreateMaterializedView {
+    #[tracing::instrument(level = "debug", skip(self, ctx), fields(ctx.id = ctx.get_id().as_str()))]
+    async fn analyze(&self, ctx: Arc<QueryContext>) -> Result<AnalyzedResult> {
+        // check whether query is valid
+        let _ = self.query.analyze(ctx.clone()).await?;
+        let if_not_exists = self.if_not_exists;
+        // The query is refreshed by the sessions of other databases, so the tables in it are
+        // qualified with the current database.
+        let mut subquery = self.subquery.clone();
+        qualify_query(&mut subquery, &ctx.get_current_database());
+        let subquery = subquery.to_string();
+        let tenant = ctx.get_tenant();
+        let (catalog, db, viewname) = resolve_table(&ctx, &self.name, "CREATE MATERIALIZED VIEW")?;
+        Ok(AnalyzedResult::SimpleQuery(Box::new(
+            PlanNode::CreateMaterializedView(CreateMaterializedViewPlan {
+                if_not_exists,
+                tenant,
+                catalog,
+                db,
+                viewname,
+                subquery,
+            }),
+        )))
+    }
+}
+
+fn qualify_query(query: &mut Query, database: &str) {
+    qualify_set_expr(&mut query.body, database);
+}
+
+fn qualify_set_expr(expr: &mut SetExpr, database: &str) {
+    match expr {
+        SetExpr::Select(select) => {
+            for table in select.from.iter_mut() {
+                qualify_table_factor(&mut table.relation, database);
+                for join in table.joins.iter_mut() {
+                    qualify_table_factor(&mut join.relation, database);
+                }
+            }
+        }
+        SetExpr::Query(query) => qualify_query(query, database),
+        SetExpr::SetOperation { left, right, .. } => {
+            qualify_set_expr(left, database);
+            qualify_set_expr(right, database);
+        }
+        _ => {}
+    }
+}
+
+fn qualify_table_factor(table: &mut TableFactor, database: &str) {
+    match table {
+        // The table functions are not qualified.
+        TableFactor::Table { name, args, .. } if name.0.len() == 1 && args.is_empty() => {
+            name.0.insert(0, Ident::new(database));
+        }
+        TableFactor::Derived { subquery, .. } => qualify_query(subquery, database),
+        TableFactor::NestedJoin(table) => {
+            qualify_table_factor(&mut table.relation, database);
+            for join in table.joins.iter_mut() {
+                qualify_table_factor(&mut join.relation, database);
+            }
+        }
+        _ => {}
+    }
+}
@@ -14,39 +14,212 @@
 
 use std::sync::Arc;
 
+use common_ast::udfs::UDFParser;
+use common_datavalues::prelude::*;
+use common_exception::ErrorCode;
 use common_exception::Result;
+use common_functions::scalars::WasmModule;
+use common_meta_types::UDFLanguage;
+use common_meta_types::UDFReturnType;
 use common_meta_types::UserDefinedFunction;
 use common_planners::CreateUserUDFPlan;
 use common_planners::PlanNode;
 use common_tracing::tracing;
+use sqlparser::ast::DataType;
+use sqlparser::ast::Expr;
+use sqlparser::ast::FunctionArg;
+use sqlparser::ast::FunctionArgExpr;
+use sqlparser::ast::Value;
 
 use crate::sessions::QueryContext;
+use crate::sql::statements::analyzer_udf::table_udf_query;
 use crate::sql::statements::AnalyzableStatement;
 use crate::sql::statements::AnalyzedResult;
+use crate::sql::statements::ExpressionAnalyzer;
+use crate::sql::SQLCommon;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DfUDFReturnType {
+    /// `AS (<parameter>, ...) -> <definition expr>`
+    Untyped,
+    /// `(<parameter> <type>, ...) RETURNS <type> AS <definition expr>`
+    Scalar(DataType),
+    /// `(<parameter> <type>, ...) RETURNS TABLE (<column> <type>, ...) AS <query>`
+    Table(Vec<(String, DataType)>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DfUDFLanguage {
+    Sql,
+    /// `LANGUAGE WASM AS '<base64 module>'`
+    Wasm,
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct DfCreateUDF {
     pub if_not_exists: bool,
     pub udf_name: String,
     pub parameters: Vec<String>,
+    pub parameter_types: Vec<DataType>,
+    pub return_type: DfUDFReturnType,
+    pub language: DfUDFLanguage,
     pub definition: String,
     pub description: String,
 }
 
 #[async_trait::async_trait]
 impl AnalyzableStatement for DfCreateUDF {
-    #[tracing::instrument(level = "info", skip(self, _ctx), fields(ctx.id = _ctx.get_id().as_str()))]
-    async fn analyze(&self, _ctx: Arc<QueryContext>) -> Result<AnalyzedResult> {
+    #[tracing::instrument(level = "info", skip(self, ctx), fields(ctx.id = ctx.get_id().as_str()))]
+    async fn analyze(&self, ctx: Arc<QueryContext>) -> Result<AnalyzedResult> {
+        let udf = build_udf(
+            ctx,
+            &self.udf_name,
+            &self.parameters,
+            &self.parameter_types,
+            &self.return_type,
+            self.language,
+            &self.definition,
+            &self.description,
+        )
+        .await?;
+
         Ok(AnalyzedResult::SimpleQuery(Box::new(
             PlanNode::CreateUserUDF(CreateUserUDFPlan {
                 if_not_exists: self.if_not_exists,
-                udf: UserDefinedFunction::new(
-                    self.udf_name.as_str(),
-                    self.parameters.clone(),
-                    self.definition.as_str(),
-                    self.description.as_str(),
-                ),
+                udf,
             }),
         )))
     }
 }
+
+/// Builds the UDF from the statement, the definition of a typed UDF is validated
+/// against the parameter types.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn build_udf(
+    ctx: Arc<QueryContext>,
+    name: &str,
+    parameters: &[String],
+    parameter_types: &[DataType],
+    return_type: &DfUDFReturnType,
+    language: DfUDFLanguage,
+    definition: &str,
+    description: &str,
+) -> Result<UserDefinedFunction> {
+    let parameter_types = parameter_types
+        .iter()
+        .map(|data_type| Ok(SQLCommon::make_data_type(data_type)?.name()))
+        .collect::<Result<Vec<_>>>()?;
+
+    let return_type = match return_type {
+        DfUDFReturnType::Untyped => {
+            return Ok(UserDefinedFunction::new(
+                name,
+                parameters.to_vec(),
+                definition,
+                description,
+            ));
+        }
+        DfUDFReturnType::Scalar(data_type) => {
+            UDFReturnType::Scalar(SQLCommon::make_data_type(data_type)?.name())
+        }
+        DfUDFReturnType::Table(columns) => UDFReturnType::Table(
+            columns
+                .iter()
+                .map(|(column, data_type)| {
+                    Ok((column.clone(), SQLCommon::make_data_type(data_type)?.name()))
+                })
+                .collect::<Result<Vec<_>>>()?,
+        ),
+    };
+
+    let udf = UserDefinedFunction::new_typed(
+        name,
+        parameters.to_vec(),
+        parameter_types,
+        return_type,
+        definition,
+        description,
+    );
+
+    let validated = match (language, udf.is_table_function()) {
+        (DfUDFLanguage::Wasm, _) => validate_wasm_udf(&udf),
+        (DfUDFLanguage::Sql, true) => validate_table_udf(ctx, &udf).await,
+        (DfUDFLanguage::Sql, false) => validate_scalar_udf(ctx, &udf).await,
+    };
+
+    let udf = match language {
+        DfUDFLanguage::Sql => udf,
+        DfUDFLanguage::Wasm => udf.with_language(UDFLanguage::Wasm),
+    };
+
+    match validated {
+        Ok(_) => Ok(udf),
+        Err(cause) => Err(cause.add_message_back(format!(
+            " (while in validate the definition of {})",
+            udf.signature()
+        ))),
+    }
+}
+
+// The definition must be a valid expression over the parameters.
+async fn validate_scalar_udf(ctx: Arc<QueryContext>, udf: &UserDefinedFunction) -> Result<()> {
+    let mut fields = Vec::with_capacity(udf.parameters.len());
+    for (parameter, type_name) in udf.parameters.iter().zip(&udf.parameter_types) {
+        let data_type = TypeFactory::instance().get(type_name)?.clone();
+        fields.push(DataField::new(parameter, data_type));
+    }
+    let schema = DataSchemaRefExt::create(fields);
+
+    let mut udf_parser = UDFParser::default();
+    let expr = udf_parser.parse(&udf.name, &udf.parameters, &udf.definition)?;
+
+    let tenant = ctx.get_tenant();
+    let udfs = ctx.get_user_manager().get_udfs(&tenant).await?;
+    let analyzer = ExpressionAnalyzer::create_with_udfs_support(ctx, udfs, schema.clone());
+    analyzer.analyze(&expr).await?.to_data_type(&schema)?;
+    Ok(())
+}
+
+// The module must compile and export the function under the name of the UDF.
+fn validate_wasm_udf(udf: &UserDefinedFunction) -> Result<()> {
+    if udf.parameters.is_empty() {
+        return Err(ErrorCode::IllegalUDFFormat(
+            "WebAssembly function must have at least one parameter",
+        ));
+    }
+
+    WasmModule::try_create_from_base64(&udf.name, &udf.definition)?;
+    Ok(())
+}
+
+// The query must be analyzable with any arguments, so a placeholder value of each
+// parameter type stands in for the arguments.
+async fn validate_table_udf(ctx: Arc<QueryContext>, udf: &UserDefinedFunction) -> Result<()> {
+    let mut args = Vec::with_capacity(udf.parameter_types.len());
+    for type_name in &udf.parameter_types {
+        let data_type = TypeFactory::instance().get(type_name)?;
+        let type_id = remove_nullable(data_type).data_type_id();
+        let value = if type_id.is_numeric() {
+            Value::Number("0".to_string(), false)
+        } else if type_id.is_string() {
+            Value::SingleQuotedString(String::new())
+        } else if type_id == TypeID::Boolean {
+            Value::Boolean(false)
+        } else {
+            Value::Null
+        };
+        args.push(FunctionArg::Unnamed(FunctionArgExpr::Expr(Expr::Value(
+            value,
+        ))));
+    }
+
+    let session_type = ctx.get_current_session().get_type();
+    let query = table_udf_query(udf, &args, session_type)?;
+    match query.analyze(ctx).await? {
+        AnalyzedResult::SelectQuery(_) => Ok(()),
+        _ => Err(ErrorCode::SyntaxException(format!(
+            "The definition of table function {} must be a query",
+            udf.name
+        ))),
+    }
+}
@@ -18,25 +18,39 @@ use common_exception::Result;
 use common_planners::DropUserUDFPlan;
 use common_planners::PlanNode;
 use common_tracing::tracing;
+use sqlparser::ast::DataType;
 
 use crate::sessions::QueryContext;
 use crate::sql::statements::AnalyzableStatement;
 use crate::sql::statements::AnalyzedResult;
+use crate::sql::SQLCommon;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct DfDropUDF {
     pub if_exists: bool,
     pub udf_name: String,
+    pub parameter_types: Option<Vec<DataType>>,
 }
 
 #[async_trait::async_trait]
 impl AnalyzableStatement for DfDropUDF {
     #[tracing::instrument(level = "info", skip(self, _ctx), fields(ctx.id = _ctx.get_id().as_str()))]
     async fn analyze(&self, _ctx: Arc<QueryContext>) -> Result<AnalyzedResult> {
+        let parameter_types = match &self.parameter_types {
+            None => None,
+            Some(parameter_types) => Some(
+                parameter_types
+                    .iter()
+                    .map(|data_type| Ok(SQLCommon::make_data_type(data_type)?.name()))
+                    .collect::<Result<Vec<_>>>()?,
+            ),
+        };
+
         Ok(AnalyzedResult::SimpleQuery(Box::new(
             PlanNode::DropUserUDF(DropUserUDFPlan {
                 if_exists: self.if_exists,
                 name: self.udf_name.clone(),
+                parameter_types,
             }),
         )))
     }
@@ -31,6 +31,9 @@ use sqlparser::ast::SelectItem;
 use sqlparser::ast::TableWithJoins;
 
 use crate::sessions::QueryContext;
+use crate::sql::statements::analyzer_collation::collation_key;
+use crate::sql::statements::analyzer_collation::group_by_collation_keys;
+use crate::sql::statements::analyzer_collation::unwrap_collate;
 use crate::sql::statements::analyzer_statement::QueryAnalyzeState;
 use crate::sql::statements::query::JoinedSchema;
 use crate::sql::statements::query::JoinedSchemaAnalyzer;
@@ -64,7 +67,9 @@ impl AnalyzableStatement for DfQueryStatement {
         let analyzer = JoinedSchemaAnalyzer::create(ctx.clone());
         let mut joined_schema = analyzer.analyze(self).await?;
 
-        let mut ir = QueryNormalizer::normalize(ctx.clone(), self).await?;
+        let input_schema = joined_schema.to_data_schema();
+        let mut ir =
+            QueryNormalizer::normalize_with_schema(ctx.clone(), self, input_schema).await?;
 
         QualifiedRewriter::rewrite(&joined_schema, ctx.clone(), &mut ir)?;
 
@@ -78,7 +83,9 @@ impl AnalyzableStatement for DfQueryStatement {
 }
 
 impl DfQueryStatement {
-    async fn analyze_query(&self, ir: QueryASTIR) -> Result<QueryAnalyzeState> {
+    async fn analyze_query(&self, mut ir: QueryASTIR) -> Result<QueryAnalyzeState> {
+        group_by_collation_keys(&mut ir)?;
+
         let limit = ir.limit;
         let offset = ir.offset;
         let mut analyze_state = QueryAnalyzeState {
@@ -107,9 +114,14 @@ impl DfQueryStatement {
                     nulls_first,
                     origin_expr,
                 } => {
-                    analyze_state.add_expression(expr);
+                    // Sorts by the collation keys.
+                    let expr = match unwrap_collate(expr) {
+                        Some((inner, collation)) => collation_key(inner, &collation),
+                        _ => expr.as_ref().clone(),
+                    };
+                    analyze_state.add_expression(&expr);
                     analyze_state.order_by_expressions.push(Expression::Sort {
-                        expr: Box::new(rebase_expr(expr, &analyze_state.expressions)?),
+                        expr: Box::new(rebase_expr(&expr, &analyze_state.expressions)?),
                         asc: *asc,
                         nulls_first: *nulls_first,
                         origin_expr: Box::new(rebase_expr(
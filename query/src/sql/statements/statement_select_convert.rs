@@ -14,6 +14,7 @@
 
 use std::convert::TryFrom;
 
+use sqlparser::ast::Expr;
 use sqlparser::ast::Query;
 use sqlparser::ast::Select;
 use sqlparser::ast::SetExpr;
@@ -29,7 +30,7 @@ impl TryFrom<Query> for DfQueryStatement {
 
         if query.with.is_some() {
             return Err(ParserError::ParserError(String::from(
-                "CTE is not yet implement",
+                "CTE is only supported by the new planner, set enable_planner_v2 = 1",
             )));
         }
 
@@ -63,6 +64,18 @@ impl TryFrom<Query> for DfQueryStatement {
             )));
         }
 
+        for expr in &query_body.group_by {
+            if let Expr::Function(function) = expr {
+                let name = function.name.to_string().to_uppercase();
+                if name == "ROLLUP" || name == "CUBE" {
+                    return Err(ParserError::ParserError(format!(
+                        "GROUP BY {} is only supported by the new planner, set enable_planner_v2 = 1",
+                        name
+                    )));
+                }
+            }
+        }
+
         Ok(DfQueryStatement {
             distinct: query_body.distinct,
             from: query_body.from.clone(),
@@ -81,6 +94,10 @@ impl DfQueryStatement {
     fn get_body(query: &Query) -> Result<&Select, ParserError> {
         match &query.body {
             SetExpr::Select(query) => Ok(query),
+            SetExpr::SetOperation { op, .. } => Err(ParserError::ParserError(format!(
+                "{} is only supported by the new planner, set enable_planner_v2 = 1",
+                op
+            ))),
             other => Err(ParserError::ParserError(format!(
                 "Query {} is not yet implemented",
                 other
@@ -19,6 +19,13 @@ use lazy_static::lazy_static;
 pub const OPT_KEY_DATABASE_ID: &str = "database_id";
 pub const OPT_KEY_SNAPSHOT_LOCATION: &str = "snapshot_location";
 
+/// The query of a materialized view, kept in the options of its fuse table
+pub const OPT_KEY_MATERIALIZED_VIEW_QUERY: &str = "materialized_view_query";
+/// The id of the table that a materialized view reads
+pub const OPT_KEY_MATERIALIZED_VIEW_SOURCE: &str = "materialized_view_source";
+/// The ids of the materialized views of a table, separated by commas
+pub const OPT_KEY_MATERIALIZED_VIEWS: &str = "materialized_views";
+
 /// Legacy table snapshot location key
 ///
 /// # Deprecated
@@ -37,6 +44,9 @@ lazy_static! {
         let mut r = HashSet::new();
         r.insert(OPT_KEY_DATABASE_ID);
         r.insert(OPT_KEY_LEGACY_SNAPSHOT_LOC);
+        r.insert(OPT_KEY_MATERIALIZED_VIEW_QUERY);
+        r.insert(OPT_KEY_MATERIALIZED_VIEW_SOURCE);
+        r.insert(OPT_KEY_MATERIALIZED_VIEWS);
         r
     };
 
@@ -45,6 +55,9 @@ lazy_static! {
         let mut r = HashSet::new();
         r.insert(OPT_KEY_LEGACY_SNAPSHOT_LOC);
         r.insert(OPT_KEY_DATABASE_ID);
+        r.insert(OPT_KEY_MATERIALIZED_VIEW_QUERY);
+        r.insert(OPT_KEY_MATERIALIZED_VIEW_SOURCE);
+        r.insert(OPT_KEY_MATERIALIZED_VIEWS);
         r
     };
 }
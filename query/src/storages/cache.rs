@@ -12,17 +12,24 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::Arc;
+
 use common_configs::QueryConfig;
 
 use crate::storages::fuse::cache;
+use crate::storages::fuse::cache::ColumnChunkCache;
 use crate::storages::fuse::cache::MemoryCache;
+use crate::storages::fuse::cache::QueryResultCache;
 use crate::storages::fuse::cache::SegmentInfoCache;
 use crate::storages::fuse::cache::TableSnapshotCache;
+use crate::storages::fuse::cache::TenantLabel;
 
 /// Where all the caches reside
 pub struct CacheManager {
     table_snapshot_cache: Option<TableSnapshotCache>,
     segment_info_cache: Option<SegmentInfoCache>,
+    column_chunk_cache: Option<Arc<ColumnChunkCache>>,
+    query_result_cache: Option<Arc<QueryResultCache>>,
     cluster_id: String,
     tenant_id: String,
 }
@@ -36,15 +43,37 @@ impl CacheManager {
             Self {
                 table_snapshot_cache: None,
                 segment_info_cache: None,
+                column_chunk_cache: None,
+                query_result_cache: QueryResultCache::create(
+                    config.query_result_cache_mb_size,
+                    None,
+                ),
                 cluster_id: config.cluster_id.clone(),
                 tenant_id: config.tenant_id.clone(),
             }
         } else {
             let table_snapshot_cache = Self::with_capacity(config.table_cache_snapshot_count);
             let segment_info_cache = Self::with_capacity(config.table_cache_segment_count);
+            // The disk cache is shared by the column chunks and the query results.
+            let disk_cache = cache::new_disk_cache(
+                &config.table_disk_cache_root,
+                config.table_disk_cache_mb_size,
+            );
+            let column_chunk_cache = ColumnChunkCache::create(
+                config.table_memory_cache_mb_size,
+                disk_cache.clone(),
+                TenantLabel {
+                    tenant_id: config.tenant_id.clone(),
+                    cluster_id: config.cluster_id.clone(),
+                },
+            );
+            let query_result_cache =
+                QueryResultCache::create(config.query_result_cache_mb_size, disk_cache);
             Self {
                 table_snapshot_cache,
                 segment_info_cache,
+                column_chunk_cache,
+                query_result_cache,
                 cluster_id: config.cluster_id.clone(),
                 tenant_id: config.tenant_id.clone(),
             }
@@ -59,6 +88,14 @@ impl CacheManager {
         self.segment_info_cache.clone()
     }
 
+    pub fn get_column_chunk_cache(&self) -> Option<Arc<ColumnChunkCache>> {
+        self.column_chunk_cache.clone()
+    }
+
+    pub fn get_query_result_cache(&self) -> Option<Arc<QueryResultCache>> {
+        self.query_result_cache.clone()
+    }
+
     pub fn get_tenant_id(&self) -> &str {
         self.tenant_id.as_str()
     }
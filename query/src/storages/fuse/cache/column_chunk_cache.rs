@@ -0,0 +1,150 @@
+//  Copyright 2022 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+use std::sync::Arc;
+
+use common_base::base::tokio::sync::Semaphore;
+use common_base::base::tokio::task;
+use common_base::infallible::Mutex;
+use common_cache::BytesMeter;
+use common_cache::Cache;
+use common_cache::DefaultHashBuilder;
+use common_cache::LruCache;
+
+use crate::storages::fuse::cache::disk_cache::read_from_disk;
+use crate::storages::fuse::cache::disk_cache::write_to_disk;
+use crate::storages::fuse::cache::metrics::metrics_inc_column_chunk_read;
+use crate::storages::fuse::cache::metrics::ChunkSource;
+use crate::storages::fuse::cache::DiskCacheRef;
+use crate::storages::fuse::cache::TenantLabel;
+
+const MB: u64 = 1024 * 1024;
+// The chunks are not written to the disk cache while this many writes are pending.
+const MAX_PENDING_DISK_WRITES: usize = 16;
+
+type ChunkMemoryCache = LruCache<String, Vec<u8>, DefaultHashBuilder, BytesMeter>;
+
+/// Caches the column chunks of the fuse blocks, which are immutable once written.
+///
+/// A chunk is looked up in the memory LRU first, then in the disk cache, the chunks read
+/// from the disk or the storage are kept in both of them.
+pub struct ColumnChunkCache {
+    memory: Option<Mutex<ChunkMemoryCache>>,
+    disk: Option<DiskCacheRef>,
+    disk_writes: Arc<Semaphore>,
+    tenant_label: TenantLabel,
+}
+
+impl ColumnChunkCache {
+    /// Creates the cache of `memory_mb_size` MB in memory, the memory is disabled if the size
+    /// is zero.
+    ///
+    /// Returns `None` if both the memory and the disk are disabled.
+    pub fn create(
+        memory_mb_size: u64,
+        disk: Option<DiskCacheRef>,
+        tenant_label: TenantLabel,
+    ) -> Option<Arc<ColumnChunkCache>> {
+        let memory = if memory_mb_size > 0 {
+            Some(Mutex::new(LruCache::with_meter(
+                memory_mb_size * MB,
+                BytesMeter,
+            )))
+        } else {
+            None
+        };
+
+        if memory.is_none() && disk.is_none() {
+            return None;
+        }
+
+        Some(Arc::new(ColumnChunkCache {
+            memory,
+            disk,
+            disk_writes: Arc::new(Semaphore::new(MAX_PENDING_DISK_WRITES)),
+            tenant_label,
+        }))
+    }
+
+    /// The key of the chunk at `offset..offset+length` of the block file at `location`, which
+    /// is also the relative path of the chunk in the disk cache.
+    pub fn key(location: &str, offset: u64, length: u64) -> String {
+        format!("{}_{}_{}", location.trim_start_matches('/'), offset, length)
+    }
+
+    pub async fn get(&self, key: &str) -> Option<Vec<u8>> {
+        if let Some(memory) = &self.memory {
+            if let Some(chunk) = memory.lock().get(key) {
+                self.metrics(ChunkSource::Memory, chunk.len());
+                return Some(chunk.clone());
+            }
+        }
+
+        if let Some(disk) = &self.disk {
+            let disk = disk.clone();
+            let disk_key = key.to_string();
+            let chunk = task::spawn_blocking(move || read_from_disk(&disk, &disk_key))
+                .await
+                .unwrap_or(None);
+            if let Some(chunk) = chunk {
+                self.metrics(ChunkSource::Disk, chunk.len());
+                if let Some(memory) = &self.memory {
+                    memory.lock().put(key.to_string(), chunk.clone());
+                }
+                return Some(chunk);
+            }
+        }
+
+        None
+    }
+
+    /// Keeps the chunk read from the storage, the disk cache is written in the background.
+    /// The chunk is only kept in memory if too many writes to the disk are pending.
+    pub fn put(&self, key: String, chunk: &[u8]) {
+        self.metrics(ChunkSource::Storage, chunk.len());
+
+        if let Some(disk) = &self.disk {
+            if let Ok(permit) = self.disk_writes.clone().try_acquire_owned() {
+                let disk = disk.clone();
+                let disk_key = key.clone();
+                let disk_chunk = chunk.to_vec();
+                task::spawn_blocking(move || {
+                    write_to_disk(&disk, &disk_key, &disk_chunk);
+                    drop(permit);
+                });
+            }
+        }
+
+        if let Some(memory) = &self.memory {
+            let mut memory = memory.lock();
+            // A chunk larger than the cache would evict all the others.
+            if chunk.len() as u64 <= memory.capacity() {
+                memory.put(key, chunk.to_vec());
+            }
+        }
+    }
+
+    /// Waits until the pending writes to the disk cache are done.
+    pub async fn flush(&self) {
+        let _ = self
+            .disk_writes
+            .acquire_many(MAX_PENDING_DISK_WRITES as u32)
+            .await;
+    }
+
+    fn metrics(&self, source: ChunkSource, read_bytes: usize) {
+        metrics_inc_column_chunk_read(&self.tenant_label, source, read_bytes as u64);
+    }
+}
@@ -0,0 +1,86 @@
+//  Copyright 2022 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+use std::io::Read;
+use std::sync::Arc;
+
+use common_base::infallible::Mutex;
+use common_cache::LruDiskCache;
+use common_tracing::tracing;
+
+const MB: u64 = 1024 * 1024;
+
+/// The disk cache shared by the caches of the column chunks and the query results.
+pub type DiskCacheRef = Arc<Mutex<LruDiskCache>>;
+
+/// Creates the disk cache of `mb_size` MB under `root`, `None` if the size is zero or the
+/// cache cannot be created.
+pub fn new_disk_cache(root: &str, mb_size: u64) -> Option<DiskCacheRef> {
+    if mb_size == 0 || root.is_empty() {
+        return None;
+    }
+
+    match LruDiskCache::new(root, mb_size * MB) {
+        Ok(disk) => Some(Arc::new(Mutex::new(disk))),
+        Err(cause) => {
+            tracing::error!("Cannot create the disk cache at {}: {}", root, cause);
+            None
+        }
+    }
+}
+
+/// Reads the file of `key`, `None` if it is not cached or cannot be read.
+///
+/// Only the file is opened with the lock held, it is read after the lock is released. The
+/// opened file is still readable if it is evicted in the meantime.
+pub(crate) fn read_from_disk(disk: &Mutex<LruDiskCache>, key: &str) -> Option<Vec<u8>> {
+    let file = {
+        let mut disk = disk.lock();
+        if !disk.contains_key(key) {
+            return None;
+        }
+        disk.get_file(key)
+    };
+
+    let mut data = vec![];
+    let res = file.and_then(|mut file| {
+        file.read_to_end(&mut data)?;
+        Ok(())
+    });
+    match res {
+        Ok(_) => Some(data),
+        Err(cause) => {
+            // The file may be removed outside, it is read from the storage again.
+            tracing::warn!("Cannot read the cached file {}: {}", key, cause);
+            let _ = disk.lock().remove(key);
+            None
+        }
+    }
+}
+
+/// Writes `data` as the file of `key` if it is not cached yet.
+pub(crate) fn write_to_disk(disk: &Mutex<LruDiskCache>, key: &str, data: &[u8]) -> bool {
+    let mut disk = disk.lock();
+    if disk.contains_key(key) {
+        return true;
+    }
+    match disk.insert_bytes(key, data) {
+        Ok(_) => true,
+        Err(cause) => {
+            tracing::warn!("Cannot cache the file {}: {}", key, cause);
+            false
+        }
+    }
+}
@@ -20,6 +20,12 @@ const CACHE_READ_BYTES_FROM_REMOTE: &str = "cache_read_bytes_from_remote";
 const CACHE_READ_BYTES_FROM_LOCAL: &str = "cache_read_bytes_from_local";
 const CACHE_ACCESS_COUNT: &str = "cache_access_count";
 const CACHE_ACCESS_HIT_COUNT: &str = "cache_access_hit_count";
+const COLUMN_CHUNK_CACHE_ACCESS_COUNT: &str = "column_chunk_cache_access_count";
+const COLUMN_CHUNK_CACHE_MEMORY_HIT_COUNT: &str = "column_chunk_cache_memory_hit_count";
+const COLUMN_CHUNK_CACHE_DISK_HIT_COUNT: &str = "column_chunk_cache_disk_hit_count";
+const COLUMN_CHUNK_READ_BYTES_FROM_MEMORY: &str = "column_chunk_read_bytes_from_memory";
+const COLUMN_CHUNK_READ_BYTES_FROM_DISK: &str = "column_chunk_read_bytes_from_disk";
+const COLUMN_CHUNK_READ_BYTES_FROM_REMOTE: &str = "column_chunk_read_bytes_from_remote";
 
 pub struct TenantLabel {
     pub tenant_id: String,
@@ -57,3 +63,30 @@ impl Drop for CacheDeferMetrics {
         }
     }
 }
+
+/// Where a column chunk is read from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChunkSource {
+    Memory,
+    Disk,
+    Storage,
+}
+
+pub fn metrics_inc_column_chunk_read(label: &TenantLabel, source: ChunkSource, read_bytes: u64) {
+    let tenant_id = &label.tenant_id;
+    let cluster_id = &label.cluster_id;
+
+    label_counter(COLUMN_CHUNK_CACHE_ACCESS_COUNT, tenant_id, cluster_id);
+    let bytes_name = match source {
+        ChunkSource::Memory => {
+            label_counter(COLUMN_CHUNK_CACHE_MEMORY_HIT_COUNT, tenant_id, cluster_id);
+            COLUMN_CHUNK_READ_BYTES_FROM_MEMORY
+        }
+        ChunkSource::Disk => {
+            label_counter(COLUMN_CHUNK_CACHE_DISK_HIT_COUNT, tenant_id, cluster_id);
+            COLUMN_CHUNK_READ_BYTES_FROM_DISK
+        }
+        ChunkSource::Storage => COLUMN_CHUNK_READ_BYTES_FROM_REMOTE,
+    };
+    label_counter_with_val(bytes_name, read_bytes, tenant_id, cluster_id);
+}
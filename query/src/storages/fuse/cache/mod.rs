@@ -12,13 +12,24 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod column_chunk_cache;
+mod disk_cache;
 mod memory_cache;
 mod metrics;
+mod query_result_cache;
 
+pub use column_chunk_cache::ColumnChunkCache;
+pub use disk_cache::new_disk_cache;
+pub use disk_cache::DiskCacheRef;
 pub use memory_cache::new_memory_cache;
 pub use memory_cache::MemoryCache;
 pub use memory_cache::SegmentInfoCache;
 pub use memory_cache::TableSnapshotCache;
+pub use query_result_cache::QueryResult;
+pub use query_result_cache::QueryResultCache;
+pub use query_result_cache::QueryResultEntry;
+pub use query_result_cache::QueryResultStorage;
 
 pub use self::metrics::CacheDeferMetrics;
+pub use self::metrics::ChunkSource;
 pub use self::metrics::TenantLabel;
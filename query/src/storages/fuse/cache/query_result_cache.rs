@@ -0,0 +1,266 @@
+//  Copyright 2022 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+use std::borrow::Borrow;
+use std::io::Cursor;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use common_arrow::arrow::chunk::Chunk;
+use common_arrow::arrow::io::ipc::read::read_stream_metadata;
+use common_arrow::arrow::io::ipc::read::StreamReader;
+use common_arrow::arrow::io::ipc::read::StreamState;
+use common_arrow::arrow::io::ipc::write::StreamWriter;
+use common_arrow::arrow::io::ipc::write::WriteOptions;
+use common_base::base::tokio::task;
+use common_base::infallible::Mutex;
+use common_cache::Cache;
+use common_cache::DefaultHashBuilder;
+use common_cache::LruCache;
+use common_cache::Meter;
+use common_datablocks::DataBlock;
+use common_datavalues::DataSchemaRef;
+use common_exception::Result;
+
+use crate::storages::fuse::cache::disk_cache::read_from_disk;
+use crate::storages::fuse::cache::disk_cache::write_to_disk;
+use crate::storages::fuse::cache::DiskCacheRef;
+
+const MB: u64 = 1024 * 1024;
+
+// The directory of the query results in the disk cache.
+const QUERY_RESULT_DISK_PREFIX: &str = "_query_result";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QueryResultStorage {
+    Memory,
+    Disk,
+}
+
+/// A cached result of a query, the result is serialized as an Arrow IPC stream.
+pub struct QueryResultEntry {
+    pub key: String,
+    pub tenant: String,
+    pub sql: String,
+    /// The tables read by the query, as `<database>.<table>@<snapshot id>`.
+    pub tables: Vec<String>,
+    pub storage: QueryResultStorage,
+    pub result_rows: usize,
+    pub result_bytes: usize,
+    pub create_time: SystemTime,
+    pub expire_time: SystemTime,
+    pub hits: AtomicU64,
+    schema: DataSchemaRef,
+    // Only for the results kept in memory.
+    data: Option<Vec<u8>>,
+}
+
+impl QueryResultEntry {
+    pub fn get_hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    fn size(&self) -> usize {
+        self.key.len() + self.sql.len() + self.data.as_ref().map(|data| data.len()).unwrap_or(0)
+    }
+}
+
+/// The entries are measured by their sizes in memory, a result on the disk is measured by its
+/// query text only, and its file is evicted by the disk cache.
+struct EntryMeter;
+
+impl<K> Meter<K, Arc<QueryResultEntry>> for EntryMeter {
+    type Measure = usize;
+    fn measure<Q: ?Sized>(&self, _: &Q, v: &Arc<QueryResultEntry>) -> usize
+    where K: Borrow<Q> {
+        v.size()
+    }
+}
+
+type EntryCache = LruCache<String, Arc<QueryResultEntry>, DefaultHashBuilder, EntryMeter>;
+
+/// Caches the results of the queries by the keys given by the caller, see
+/// `QueryResultCacheKey` of the select interpreter.
+pub struct QueryResultCache {
+    entries: Mutex<EntryCache>,
+    disk: Option<DiskCacheRef>,
+}
+
+pub struct QueryResult {
+    pub key: String,
+    pub tenant: String,
+    pub sql: String,
+    pub tables: Vec<String>,
+    pub schema: DataSchemaRef,
+    pub blocks: Vec<DataBlock>,
+}
+
+impl QueryResultCache {
+    /// Creates the cache of `memory_mb_size` MB in memory, the results are kept on `disk` if
+    /// it is asked and the disk is given.
+    pub fn create(memory_mb_size: u64, disk: Option<DiskCacheRef>) -> Option<Arc<Self>> {
+        if memory_mb_size == 0 {
+            return None;
+        }
+
+        Some(Arc::new(QueryResultCache {
+            entries: Mutex::new(LruCache::with_meter(memory_mb_size * MB, EntryMeter)),
+            disk,
+        }))
+    }
+
+    /// Gets the result blocks of the key, the expired results are removed.
+    pub async fn get(&self, key: &str) -> Result<Option<Vec<DataBlock>>> {
+        let entry = match self.get_entry(key) {
+            None => return Ok(None),
+            Some(entry) => entry,
+        };
+
+        let data = match (&entry.data, &self.disk) {
+            (Some(data), _) => data.clone(),
+            (None, Some(disk)) => {
+                let disk = disk.clone();
+                let disk_key = Self::disk_key(key);
+                match task::spawn_blocking(move || read_from_disk(&disk, &disk_key))
+                    .await
+                    .unwrap_or(None)
+                {
+                    Some(data) => data,
+                    None => {
+                        // evicted by the disk cache
+                        self.entries.lock().pop(key);
+                        return Ok(None);
+                    }
+                }
+            }
+            (None, None) => return Ok(None),
+        };
+
+        let blocks = Self::deserialize(&entry.schema, data)?;
+        entry.hits.fetch_add(1, Ordering::Relaxed);
+        Ok(Some(blocks))
+    }
+
+    /// Keeps the result until `expire_time` if its size is at most `max_bytes`, returns whether
+    /// it is kept.
+    pub async fn put(
+        &self,
+        result: QueryResult,
+        storage: QueryResultStorage,
+        max_bytes: usize,
+        expire_time: SystemTime,
+    ) -> Result<bool> {
+        let result_rows = result.blocks.iter().map(|b| b.num_rows()).sum::<usize>();
+        let data = Self::serialize(&result.schema, &result.blocks)?;
+        if data.len() > max_bytes {
+            return Ok(false);
+        }
+
+        let storage = match (&self.disk, storage) {
+            (Some(disk), QueryResultStorage::Disk) => {
+                let disk = disk.clone();
+                let disk_key = Self::disk_key(&result.key);
+                let disk_data = data.clone();
+                let written =
+                    task::spawn_blocking(move || write_to_disk(&disk, &disk_key, &disk_data))
+                        .await
+                        .unwrap_or(false);
+                if !written {
+                    return Ok(false);
+                }
+                QueryResultStorage::Disk
+            }
+            _ => QueryResultStorage::Memory,
+        };
+
+        let entry = Arc::new(QueryResultEntry {
+            key: result.key.clone(),
+            tenant: result.tenant,
+            sql: result.sql,
+            tables: result.tables,
+            storage,
+            result_rows,
+            result_bytes: data.len(),
+            create_time: SystemTime::now(),
+            expire_time,
+            hits: AtomicU64::new(0),
+            schema: result.schema,
+            data: match storage {
+                QueryResultStorage::Memory => Some(data),
+                QueryResultStorage::Disk => None,
+            },
+        });
+
+        let mut entries = self.entries.lock();
+        // A result larger than the cache would evict all the others.
+        if entry.size() as u64 > entries.capacity() {
+            return Ok(false);
+        }
+        entries.put(result.key, entry);
+        Ok(true)
+    }
+
+    /// The unexpired results of the tenant.
+    pub fn list_entries(&self, tenant: &str) -> Vec<Arc<QueryResultEntry>> {
+        let now = SystemTime::now();
+        let entries = self.entries.lock();
+        entries
+            .iter()
+            .filter(|(_, entry)| entry.tenant == tenant && entry.expire_time > now)
+            .map(|(_, entry)| entry.clone())
+            .collect()
+    }
+
+    fn get_entry(&self, key: &str) -> Option<Arc<QueryResultEntry>> {
+        let mut entries = self.entries.lock();
+        let entry = entries.get(key)?.clone();
+        if entry.expire_time <= SystemTime::now() {
+            entries.pop(key);
+            return None;
+        }
+        Some(entry)
+    }
+
+    fn disk_key(key: &str) -> String {
+        format!("{}/{}", QUERY_RESULT_DISK_PREFIX, key)
+    }
+
+    fn serialize(schema: &DataSchemaRef, blocks: &[DataBlock]) -> Result<Vec<u8>> {
+        let mut data = vec![];
+        let options = WriteOptions { compression: None };
+        let mut writer = StreamWriter::new(&mut data, options);
+        writer.start(&schema.to_arrow(), None)?;
+        for block in blocks {
+            writer.write(&Chunk::try_from(block.clone())?, None)?;
+        }
+        writer.finish()?;
+        Ok(data)
+    }
+
+    fn deserialize(schema: &DataSchemaRef, data: Vec<u8>) -> Result<Vec<DataBlock>> {
+        let mut reader = Cursor::new(data);
+        let metadata = read_stream_metadata(&mut reader)?;
+        let mut blocks = vec![];
+        for state in StreamReader::new(reader, metadata) {
+            match state? {
+                StreamState::Some(chunk) => blocks.push(DataBlock::from_chunk(schema, &chunk)?),
+                StreamState::Waiting => break,
+            }
+        }
+        Ok(blocks)
+    }
+}
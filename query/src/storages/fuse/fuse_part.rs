@@ -22,6 +22,7 @@ use common_planners::PartInfo;
 use common_planners::PartInfoPtr;
 
 use crate::storages::fuse::meta::Compression;
+use crate::storages::index::ColumnsStatistics;
 
 #[derive(serde::Serialize, serde::Deserialize, PartialEq)]
 pub struct ColumnMeta {
@@ -49,6 +50,9 @@ pub struct FusePartInfo {
     pub nums_rows: usize,
     pub columns_meta: HashMap<usize, ColumnMeta>,
     pub compression: Compression,
+    /// Statistics of the read columns, to skip the block by the runtime filters
+    #[serde(default)]
+    pub col_stats: ColumnsStatistics,
 }
 
 #[typetag::serde(name = "fuse")]
@@ -72,6 +76,7 @@ impl FusePartInfo {
         rows_count: u64,
         columns_meta: HashMap<usize, ColumnMeta>,
         compression: Compression,
+        col_stats: ColumnsStatistics,
     ) -> Arc<Box<dyn PartInfo>> {
         Arc::new(Box::new(FusePartInfo {
             location,
@@ -79,6 +84,7 @@ impl FusePartInfo {
             columns_meta,
             nums_rows: rows_count as usize,
             compression,
+            col_stats,
         }))
     }
 
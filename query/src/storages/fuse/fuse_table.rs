@@ -131,6 +131,12 @@ impl FuseTable {
             .cloned()
     }
 
+    /// The id of the current snapshot, `None` if the table has no data yet.
+    pub fn snapshot_id(&self) -> Option<String> {
+        self.snapshot_loc()
+            .map(|loc| TableMetaLocationGenerator::snapshot_id(&loc).to_string())
+    }
+
     pub fn try_from_table(tbl: &dyn Table) -> Result<&FuseTable> {
         tbl.as_any().downcast_ref::<FuseTable>().ok_or_else(|| {
             ErrorCode::LogicalError(format!(
@@ -222,8 +228,22 @@ impl Table for FuseTable {
             .iter()
             .map(AppendOperationLogEntry::try_from)
             .collect::<Result<Vec<AppendOperationLogEntry>>>()?;
-        self.do_commit(ctx, catalog_name, append_log_entries, overwrite)
-            .await
+        let table_info = self
+            .do_commit(
+                ctx.clone(),
+                catalog_name,
+                append_log_entries.clone(),
+                overwrite,
+            )
+            .await?;
+        self.refresh_materialized_views(
+            ctx,
+            catalog_name,
+            &table_info,
+            &append_log_entries,
+            overwrite,
+        )
+        .await
     }
 
     async fn truncate(
@@ -231,7 +251,9 @@ impl Table for FuseTable {
         ctx: Arc<QueryContext>,
         truncate_plan: TruncateTablePlan,
     ) -> Result<()> {
-        self.do_truncate(ctx, truncate_plan).await
+        self.do_truncate(ctx.clone(), truncate_plan.clone()).await?;
+        self.truncate_materialized_views(ctx, &self.table_info, &truncate_plan)
+            .await
     }
 
     async fn optimize(&self, ctx: Arc<QueryContext>, keep_last_snapshot: bool) -> Result<()> {
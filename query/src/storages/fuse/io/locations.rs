@@ -70,6 +70,12 @@ impl TableMetaLocationGenerator {
         Ok(snaphost_version.create(id, &self.prefix))
     }
 
+    /// The id of the snapshot at `location`, which is the uuid in the file name.
+    pub fn snapshot_id(location: &str) -> &str {
+        let file_name = location.rsplit('/').next().unwrap_or(location);
+        file_name.trim_end_matches(SNAPHOST_V1.suffix())
+    }
+
     pub fn snaphost_version(location: impl AsRef<str>) -> u64 {
         if location.as_ref().ends_with(SNAPHOST_V1.suffix()) {
             SNAPHOST_V1.version()
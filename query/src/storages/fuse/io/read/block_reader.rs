@@ -40,6 +40,7 @@ use futures::TryStreamExt;
 use opendal::Object;
 use opendal::Operator;
 
+use crate::storages::fuse::cache::ColumnChunkCache;
 use crate::storages::fuse::fuse_part::ColumnMeta;
 use crate::storages::fuse::fuse_part::FusePartInfo;
 use crate::storages::fuse::meta::Compression;
@@ -51,6 +52,7 @@ pub struct BlockReader {
     arrow_schema: Arc<Schema>,
     projected_schema: DataSchemaRef,
     parquet_schema_descriptor: SchemaDescriptor,
+    chunk_cache: Option<Arc<ColumnChunkCache>>,
 }
 
 impl BlockReader {
@@ -58,6 +60,7 @@ impl BlockReader {
         operator: Operator,
         schema: DataSchemaRef,
         projection: Vec<usize>,
+        chunk_cache: Option<Arc<ColumnChunkCache>>,
     ) -> Result<Arc<BlockReader>> {
         let projected_schema = DataSchemaRef::new(schema.project(projection.clone()));
 
@@ -69,6 +72,7 @@ impl BlockReader {
             projected_schema,
             parquet_schema_descriptor,
             arrow_schema: Arc::new(arrow_schema),
+            chunk_cache,
         }))
     }
 
@@ -114,12 +118,21 @@ impl BlockReader {
         for index in &self.projection {
             let column_meta = &part.columns_meta[index];
             let column_reader = self.operator.object(&part.location);
+            let (offset, length) = (column_meta.offset, column_meta.length);
+            let chunk_cache = self.chunk_cache_of(&part.location, offset, length);
             let fut = async move {
+                if let Some((cache, key)) = &chunk_cache {
+                    if let Some(column_chunk) = cache.get(key).await {
+                        return Ok(column_chunk);
+                    }
+                }
+
                 // NOTE: move chunk inside future so that alloc only
                 // happen when future is ready to go.
-                let column_chunk = column_reader
-                    .range_read(column_meta.offset..column_meta.offset + column_meta.length)
-                    .await?;
+                let column_chunk = column_reader.range_read(offset..offset + length).await?;
+                if let Some((cache, key)) = chunk_cache {
+                    cache.put(key, &column_chunk);
+                }
                 Ok::<_, ErrorCode>(column_chunk)
             }
             .instrument(debug_span!("read_col_chunk"));
@@ -193,17 +206,32 @@ impl BlockReader {
                 self.operator.object(&part.location),
                 column_meta.offset,
                 column_meta.length,
+                self.chunk_cache_of(&part.location, column_meta.offset, column_meta.length),
             ));
         }
 
         futures::future::try_join_all(join_handlers).await
     }
 
-    async fn read_column(o: Object, offset: u64, length: u64) -> Result<Vec<u8>> {
+    async fn read_column(
+        o: Object,
+        offset: u64,
+        length: u64,
+        chunk_cache: Option<(Arc<ColumnChunkCache>, String)>,
+    ) -> Result<Vec<u8>> {
         let handler = common_base::base::tokio::spawn(async move {
+            if let Some((cache, key)) = &chunk_cache {
+                if let Some(chunk) = cache.get(key).await {
+                    return Ok(chunk);
+                }
+            }
+
             let mut chunk = vec![0; length as usize];
             let mut r = o.range_reader(offset..offset + length).await?;
             r.read_exact(&mut chunk).await?;
+            if let Some((cache, key)) = chunk_cache {
+                cache.put(key, &chunk);
+            }
             Ok(chunk)
         });
 
@@ -217,6 +245,19 @@ impl BlockReader {
         }
     }
 
+    // The column chunk cache with the key of the chunk, if the cache is enabled.
+    fn chunk_cache_of(
+        &self,
+        location: &str,
+        offset: u64,
+        length: u64,
+    ) -> Option<(Arc<ColumnChunkCache>, String)> {
+        self.chunk_cache.as_ref().map(|cache| {
+            let key = ColumnChunkCache::key(location, offset, length);
+            (cache.clone(), key)
+        })
+    }
+
     #[tracing::instrument(level = "debug", skip_all)]
     pub async fn read(&self, part: PartInfoPtr) -> Result<DataBlock> {
         let (num_rows, columns_array_iter) = self.read_columns(part).await?;
@@ -51,13 +51,14 @@ const OCC_DEFAULT_BACKOFF_MAX_DELAY_MS: Duration = Duration::from_millis(20 * 10
 const OCC_DEFAULT_BACKOFF_MAX_ELAPSED_MS: Duration = Duration::from_millis(120 * 1000);
 
 impl FuseTable {
+    /// Returns the table info that the operations are committed upon.
     pub async fn do_commit(
         &self,
         ctx: Arc<QueryContext>,
         catalog_name: impl AsRef<str>,
         operation_log: TableOperationLog,
         overwrite: bool,
-    ) -> Result<()> {
+    ) -> Result<TableInfo> {
         let tid = self.table_info.ident.table_id;
 
         let mut tbl = self;
@@ -94,7 +95,7 @@ impl FuseTable {
                 .try_commit(ctx.as_ref(), catalog_name, &operation_log, overwrite)
                 .await
             {
-                Ok(_) => break Ok(()),
+                Ok(_) => break Ok(tbl.table_info.clone()),
                 Err(e) if self::utils::is_error_recoverable(&e) => match backoff.next_backoff() {
                     Some(d) => {
                         let name = tbl.table_info.name.clone();
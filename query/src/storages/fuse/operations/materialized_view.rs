@@ -0,0 +1,423 @@
+//  Copyright 2022 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+use std::sync::Arc;
+
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_meta_types::MatchSeq;
+use common_meta_types::MetaId;
+use common_meta_types::TableInfo;
+use common_meta_types::UpsertTableOptionReq;
+use common_planners::AggregatorPartialPlan;
+use common_planners::Expression;
+use common_planners::Partitions;
+use common_planners::PlanNode;
+use common_planners::PlanRewriter;
+use common_planners::PlanVisitor;
+use common_planners::ReadDataSourcePlan;
+use common_planners::SourceInfo;
+use common_planners::Statistics;
+use common_planners::TruncateTablePlan;
+use common_tracing::tracing;
+use futures::TryStreamExt;
+
+use crate::interpreters::SelectInterpreter;
+use crate::sessions::QueryContext;
+use crate::sql::PlanParser;
+use crate::sql::OPT_KEY_MATERIALIZED_VIEWS;
+use crate::sql::OPT_KEY_MATERIALIZED_VIEW_QUERY;
+use crate::sql::OPT_KEY_MATERIALIZED_VIEW_SOURCE;
+use crate::storages::fuse::meta::BlockMeta;
+use crate::storages::fuse::operations::TableOperationLog;
+use crate::storages::fuse::FuseTable;
+use crate::storages::Table;
+
+// Retries of linking a materialized view to its source table, which fails if the table is
+// committed concurrently.
+const LINK_MAX_RETRIES: usize = 10;
+
+impl FuseTable {
+    /// Plans the query of a materialized view, the query must read exactly one fuse table, which
+    /// is returned as the source of the view.
+    pub async fn plan_materialized_view(
+        ctx: Arc<QueryContext>,
+        query: &str,
+    ) -> Result<(PlanNode, ReadDataSourcePlan)> {
+        let plan = PlanParser::parse(ctx.clone(), query).await?;
+        let mut visitor = SourceVisitor { sources: vec![] };
+        visitor.visit_plan_node(&plan)?;
+
+        let source = match visitor.sources.as_slice() {
+            [source] => source.clone(),
+            _ => {
+                return Err(ErrorCode::BadArguments(
+                    "The query of a materialized view must read exactly one table",
+                ));
+            }
+        };
+        match (&source.source_info, &source.tbl_args) {
+            (SourceInfo::TableSource(_), None) => {}
+            _ => {
+                return Err(ErrorCode::BadArguments(
+                    "The query of a materialized view must read a table",
+                ));
+            }
+        }
+
+        let table = ctx.build_table_from_source_plan(&source)?;
+        if FuseTable::try_from_table(table.as_ref()).is_err() {
+            return Err(ErrorCode::BadArguments(format!(
+                "The materialized view can not read {}, only fuse tables are supported",
+                source.source_info.desc()
+            )));
+        }
+        Ok((plan, source))
+    }
+
+    /// Adds the materialized view `view_id` to the options of the table `table_id`.
+    ///
+    /// Returns the table info that the view is linked upon: the commits after it are kept in
+    /// the view by [FuseTable::refresh_materialized_views], so the view should be filled with
+    /// the snapshot of it.
+    pub async fn link_materialized_view(
+        ctx: &QueryContext,
+        catalog_name: &str,
+        table_id: MetaId,
+        view_id: MetaId,
+    ) -> Result<TableInfo> {
+        Self::update_materialized_view_ids(ctx, catalog_name, table_id, |views| views.push(view_id))
+            .await
+    }
+
+    /// Removes the dropped materialized views `view_ids` from the options of the table
+    /// `table_id`.
+    pub async fn unlink_materialized_views(
+        ctx: &QueryContext,
+        catalog_name: &str,
+        table_id: MetaId,
+        view_ids: &[MetaId],
+    ) -> Result<TableInfo> {
+        Self::update_materialized_view_ids(ctx, catalog_name, table_id, |views| {
+            views.retain(|id| !view_ids.contains(id))
+        })
+        .await
+    }
+
+    async fn update_materialized_view_ids(
+        ctx: &QueryContext,
+        catalog_name: &str,
+        table_id: MetaId,
+        update: impl Fn(&mut Vec<MetaId>),
+    ) -> Result<TableInfo> {
+        let catalog = ctx.get_catalog(catalog_name)?;
+        let mut retry_times = 0;
+        loop {
+            let (ident, meta) = catalog.get_table_meta_by_id(table_id).await?;
+            let table_info = TableInfo {
+                ident,
+                desc: "".to_owned(),
+                name: "".to_owned(),
+                meta: meta.as_ref().clone(),
+            };
+
+            let mut views = Self::materialized_view_ids(&table_info);
+            update(&mut views);
+            let views = views
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+
+            let req = UpsertTableOptionReq {
+                table_id,
+                seq: MatchSeq::Exact(table_info.ident.seq),
+                options: [(OPT_KEY_MATERIALIZED_VIEWS.to_owned(), Some(views))]
+                    .into_iter()
+                    .collect(),
+            };
+            match catalog.upsert_table_option(req).await {
+                Ok(_) => return Ok(table_info),
+                Err(e)
+                    if e.code() == ErrorCode::table_version_mismatched_code()
+                        && retry_times < LINK_MAX_RETRIES =>
+                {
+                    retry_times += 1;
+                    continue;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Returns the materialized views linked to the table in `table_info`, the dropped views
+    /// are unlinked from the table.
+    pub async fn linked_materialized_views(
+        &self,
+        ctx: &QueryContext,
+        catalog_name: &str,
+        table_info: &TableInfo,
+    ) -> Result<Vec<(MetaId, Arc<dyn Table>)>> {
+        let views = Self::materialized_view_ids(table_info);
+        if views.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let catalog = ctx.get_catalog(catalog_name)?;
+        let table_id = self.table_info.ident.table_id;
+        let mut linked = Vec::with_capacity(views.len());
+        let mut dropped = vec![];
+        for view_id in views {
+            let (ident, meta) = match catalog.get_table_meta_by_id(view_id).await {
+                Ok(view) => view,
+                Err(e)
+                    if e.code() == ErrorCode::UnknownTableIdCode()
+                        || e.code() == ErrorCode::UnknownTableCode() =>
+                {
+                    dropped.push(view_id);
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            if meta.options.get(OPT_KEY_MATERIALIZED_VIEW_SOURCE) != Some(&table_id.to_string()) {
+                dropped.push(view_id);
+                continue;
+            }
+
+            let view_info = TableInfo {
+                ident,
+                desc: "".to_owned(),
+                name: "".to_owned(),
+                meta: meta.as_ref().clone(),
+            };
+            linked.push((view_id, catalog.get_table_by_info(&view_info)?));
+        }
+
+        if !dropped.is_empty() {
+            tracing::debug!(
+                "unlink dropped materialized views {:?} of table {}",
+                dropped,
+                self.table_info.ident
+            );
+            Self::unlink_materialized_views(ctx, catalog_name, table_id, &dropped).await?;
+        }
+        Ok(linked)
+    }
+
+    /// Keeps the blocks of `operation_log` in the materialized views of the table, by appending
+    /// the results of the view queries over the blocks.
+    ///
+    /// `table_info` is the table info that the operations are committed upon, which tells the
+    /// views linked before the commit.
+    pub async fn refresh_materialized_views(
+        &self,
+        ctx: Arc<QueryContext>,
+        catalog_name: &str,
+        table_info: &TableInfo,
+        operation_log: &TableOperationLog,
+        overwrite: bool,
+    ) -> Result<()> {
+        if Self::materialized_view_ids(table_info).is_empty() {
+            return Ok(());
+        }
+
+        let blocks = operation_log
+            .iter()
+            .flat_map(|entry| entry.segment_info.blocks.iter().cloned())
+            .collect::<Vec<_>>();
+        if blocks.is_empty() && !overwrite {
+            return Ok(());
+        }
+
+        let views = self
+            .linked_materialized_views(ctx.as_ref(), catalog_name, table_info)
+            .await?;
+        for (view_id, view) in views {
+            self.refresh_materialized_view(
+                ctx.clone(),
+                catalog_name,
+                view.as_ref(),
+                Some(blocks.clone()),
+                overwrite,
+            )
+            .await
+            .map_err(|e| {
+                e.add_message_back(format!(
+                    " (while refreshing the materialized view {}, the rows are committed to {}, \
+                     use `REFRESH MATERIALIZED VIEW` to rebuild the view)",
+                    view_id, self.table_info.name
+                ))
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Truncates the materialized views linked to the table in `table_info`, which is truncated
+    /// by `plan`.
+    pub async fn truncate_materialized_views(
+        &self,
+        ctx: Arc<QueryContext>,
+        table_info: &TableInfo,
+        plan: &TruncateTablePlan,
+    ) -> Result<()> {
+        let views = self
+            .linked_materialized_views(ctx.as_ref(), &plan.catalog, table_info)
+            .await?;
+        for (_, view) in views {
+            let plan = TruncateTablePlan {
+                catalog: plan.catalog.clone(),
+                db: "".to_owned(),
+                table: "".to_owned(),
+                purge: plan.purge,
+            };
+            view.truncate(ctx.clone(), plan).await?;
+        }
+        Ok(())
+    }
+
+    /// Appends the result of the query of `view` over the `blocks` of this table, or over the
+    /// whole table if `blocks` is `None`, the view is overwritten if `overwrite` is set.
+    pub async fn refresh_materialized_view(
+        &self,
+        ctx: Arc<QueryContext>,
+        catalog_name: &str,
+        view: &dyn Table,
+        blocks: Option<Vec<BlockMeta>>,
+        overwrite: bool,
+    ) -> Result<()> {
+        let query = view
+            .get_table_info()
+            .options()
+            .get(OPT_KEY_MATERIALIZED_VIEW_QUERY)
+            .cloned()
+            .ok_or_else(|| {
+                ErrorCode::BadArguments(format!(
+                    "{} is not a materialized view",
+                    view.get_table_info().name
+                ))
+            })?;
+
+        let (plan, source) = Self::plan_materialized_view(ctx.clone(), &query).await?;
+        let (statistics, parts) = match blocks {
+            Some(blocks) => Self::to_partitions(&blocks, source.push_downs.clone()),
+            None => {
+                self.do_read_partitions(ctx.clone(), source.push_downs.clone())
+                    .await?
+            }
+        };
+        let mut rewriter = SourceRewriter {
+            table_id: self.table_info.ident.table_id,
+            statistics,
+            parts,
+        };
+        let plan = match rewriter.rewrite_plan_node(&plan)? {
+            PlanNode::Select(select) => select,
+            _ => {
+                return Err(ErrorCode::LogicalError(
+                    "The query of a materialized view must be a select",
+                ));
+            }
+        };
+
+        tracing::debug!(
+            "refresh materialized view {} of table {}",
+            view.get_table_info().ident,
+            self.table_info.ident
+        );
+        let stream = SelectInterpreter::execute_plan(ctx.clone(), plan).await?;
+        let append_logs = view.append_data(ctx.clone(), stream).await?;
+        view.commit_insertion(
+            ctx,
+            catalog_name,
+            append_logs.try_collect().await?,
+            overwrite,
+        )
+        .await
+    }
+
+    pub fn materialized_view_ids(table_info: &TableInfo) -> Vec<MetaId> {
+        table_info
+            .options()
+            .get(OPT_KEY_MATERIALIZED_VIEWS)
+            .map(|views| {
+                views
+                    .split(',')
+                    .filter_map(|id| id.trim().parse::<MetaId>().ok())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+struct SourceVisitor {
+    sources: Vec<ReadDataSourcePlan>,
+}
+
+impl PlanVisitor for SourceVisitor {
+    fn visit_read_data_source(&mut self, plan: &ReadDataSourcePlan) -> Result<()> {
+        self.sources.push(plan.clone());
+        Ok(())
+    }
+
+    // The view keeps the aggregates of each insertion, so only the aggregates that can be
+    // combined again over the insertions are allowed.
+    fn visit_aggregate_partial(&mut self, plan: &AggregatorPartialPlan) -> Result<()> {
+        for expr in &plan.aggr_expr {
+            let mut expr = expr;
+            while let Expression::Alias(_, inner) = expr {
+                expr = inner.as_ref();
+            }
+            if let Expression::AggregateFunction {
+                op, distinct, args, ..
+            } = expr
+            {
+                let combinable = match op.to_lowercase().as_str() {
+                    "sum" | "count" => !distinct,
+                    "min" | "max" => true,
+                    "any" => args.iter().all(|arg| plan.group_expr.contains(arg)),
+                    _ => false,
+                };
+                if !combinable {
+                    return Err(ErrorCode::BadArguments(format!(
+                        "The aggregate function {} can't be used in a materialized view, only \
+                         sum, count, min, max and any of the group keys are supported",
+                        expr.column_name()
+                    )));
+                }
+            }
+        }
+        self.visit_plan_node(plan.input.as_ref())
+    }
+}
+
+// Reads the given parts of the source table instead of the parts of its snapshot.
+struct SourceRewriter {
+    table_id: MetaId,
+    statistics: Statistics,
+    parts: Partitions,
+}
+
+impl PlanRewriter for SourceRewriter {
+    fn rewrite_read_data_source(&mut self, plan: &ReadDataSourcePlan) -> Result<PlanNode> {
+        let mut plan = plan.clone();
+        if let SourceInfo::TableSource(table_info) = &plan.source_info {
+            if table_info.ident.table_id == self.table_id {
+                plan.statistics = self.statistics.clone();
+                plan.parts = self.parts.clone();
+            }
+        }
+        Ok(PlanNode::ReadSource(plan))
+    }
+}
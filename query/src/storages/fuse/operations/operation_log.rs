@@ -29,6 +29,7 @@ use crate::storages::fuse::meta::SegmentInfo;
 pub type TableOperationLog = Vec<AppendOperationLogEntry>;
 
 // to be wrapped in enum
+#[derive(Clone)]
 pub struct AppendOperationLogEntry {
     pub segment_location: String,
     pub segment_info: Arc<SegmentInfo>,
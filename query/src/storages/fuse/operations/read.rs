@@ -18,12 +18,16 @@ use std::sync::Arc;
 use common_base::base::Progress;
 use common_base::base::ProgressValues;
 use common_datablocks::DataBlock;
+use common_datavalues::DataSchemaRef;
+use common_datavalues::DataValue;
 use common_exception::ErrorCode;
 use common_exception::Result;
+use common_planners::Expression;
 use common_planners::Extras;
 use common_planners::PartInfoPtr;
 use common_planners::ReadDataSourcePlan;
 use common_streams::SendableDataBlockStream;
+use common_tracing::tracing;
 use common_tracing::tracing_futures::Instrument;
 use futures::StreamExt;
 
@@ -34,9 +38,12 @@ use crate::pipelines::new::processors::Processor;
 use crate::pipelines::new::NewPipeline;
 use crate::pipelines::new::SourcePipeBuilder;
 use crate::sessions::QueryContext;
+use crate::storages::fuse::fuse_part::FusePartInfo;
 use crate::storages::fuse::io::BlockReader;
 use crate::storages::fuse::operations::read::State::Generated;
 use crate::storages::fuse::FuseTable;
+use crate::storages::index::ColumnsStatistics;
+use crate::storages::index::RangeFilter;
 
 impl FuseTable {
     #[inline]
@@ -85,7 +92,8 @@ impl FuseTable {
 
         let operator = ctx.get_storage_operator()?;
         let table_schema = self.table_info.schema();
-        BlockReader::create(operator, table_schema, projection)
+        let chunk_cache = ctx.get_storage_cache_manager().get_column_chunk_cache();
+        BlockReader::create(operator, table_schema, projection, chunk_cache)
     }
 
     #[inline]
@@ -102,12 +110,18 @@ impl FuseTable {
         let max_threads = std::cmp::min(parts_len, max_threads);
 
         let mut source_builder = SourcePipeBuilder::create();
+        let table_schema = self.table_info.schema();
 
         for _index in 0..std::cmp::max(1, max_threads) {
             let output = OutputPort::create();
             source_builder.add_source(
                 output.clone(),
-                FuseTableSource::create(ctx.clone(), output, block_reader.clone())?,
+                FuseTableSource::create(
+                    ctx.clone(),
+                    output,
+                    block_reader.clone(),
+                    table_schema.clone(),
+                )?,
             );
         }
 
@@ -123,12 +137,17 @@ enum State {
     Finish,
 }
 
+type Pred = Box<dyn Fn(&ColumnsStatistics) -> Result<bool> + Send + Sync>;
+
 struct FuseTableSource {
     state: State,
     ctx: Arc<QueryContext>,
     scan_progress: Arc<Progress>,
     block_reader: Arc<BlockReader>,
     output: Arc<OutputPort>,
+    table_schema: DataSchemaRef,
+    // Built from the runtime filters of the context before reading the first part.
+    runtime_filter: Option<Pred>,
 }
 
 impl FuseTableSource {
@@ -136,24 +155,75 @@ impl FuseTableSource {
         ctx: Arc<QueryContext>,
         output: Arc<OutputPort>,
         block_reader: Arc<BlockReader>,
+        table_schema: DataSchemaRef,
     ) -> Result<ProcessorPtr> {
         let scan_progress = ctx.get_scan_progress();
         let mut partitions = ctx.try_get_partitions(1)?;
-        match partitions.is_empty() {
-            true => Ok(ProcessorPtr::create(Box::new(FuseTableSource {
-                ctx,
-                output,
-                block_reader,
-                scan_progress,
-                state: State::Finish,
-            }))),
-            false => Ok(ProcessorPtr::create(Box::new(FuseTableSource {
-                ctx,
-                output,
-                block_reader,
-                scan_progress,
-                state: State::ReadData(partitions.remove(0)),
-            }))),
+        let state = match partitions.is_empty() {
+            true => State::Finish,
+            false => State::ReadData(partitions.remove(0)),
+        };
+
+        Ok(ProcessorPtr::create(Box::new(FuseTableSource {
+            ctx,
+            output,
+            block_reader,
+            scan_progress,
+            state,
+            table_schema,
+            runtime_filter: None,
+        })))
+    }
+
+    // The runtime filters are pushed by the hash join build side, which is finished before
+    // the probe side starts to read.
+    fn create_runtime_filter(&self) -> Result<Pred> {
+        let filter = self
+            .ctx
+            .get_runtime_filters()
+            .into_iter()
+            .reduce(|left, right| left.and(right));
+
+        match filter {
+            None => Ok(Box::new(|_: &ColumnsStatistics| Ok(true))),
+            Some(Expression::Literal {
+                value: DataValue::Boolean(false),
+                ..
+            }) => Ok(Box::new(|_: &ColumnsStatistics| Ok(false))),
+            Some(filter) => {
+                let range_filter =
+                    RangeFilter::try_create(self.ctx.clone(), &filter, self.table_schema.clone())?;
+                Ok(Box::new(move |v: &ColumnsStatistics| range_filter.eval(v)))
+            }
+        }
+    }
+
+    // Skips the parts which can't pass the runtime filters, returns the first part to read.
+    fn next_part(&mut self, part: PartInfoPtr) -> Result<Option<PartInfoPtr>> {
+        if self.runtime_filter.is_none() {
+            self.runtime_filter = Some(self.create_runtime_filter()?);
+        }
+
+        let mut part = part;
+        loop {
+            if let Some(runtime_filter) = &self.runtime_filter {
+                let fuse_part = FusePartInfo::from_part(&part)?;
+                if runtime_filter(&fuse_part.col_stats)? {
+                    return Ok(Some(part));
+                }
+
+                tracing::debug!("Skip part {} by runtime filter", fuse_part.location);
+                self.ctx
+                    .get_dal_context()
+                    .get_metrics()
+                    .dec_partitions_scanned(1);
+            }
+
+            let mut partitions = self.ctx.try_get_partitions(1)?;
+            match partitions.is_empty() {
+                true => return Ok(None),
+                false => part = partitions.remove(0),
+            }
         }
     }
 }
@@ -223,8 +293,10 @@ impl Processor for FuseTableSource {
     async fn async_process(&mut self) -> Result<()> {
         match std::mem::replace(&mut self.state, State::Finish) {
             State::ReadData(part) => {
-                let chunks = self.block_reader.read_columns_data(part.clone()).await?;
-                self.state = State::Deserialize(part, chunks);
+                if let Some(part) = self.next_part(part)? {
+                    let chunks = self.block_reader.read_columns_data(part.clone()).await?;
+                    self.state = State::Deserialize(part, chunks);
+                }
                 Ok(())
             }
             _ => Err(ErrorCode::LogicalError("It's a bug.")),
@@ -190,11 +190,13 @@ impl FuseTable {
             rows_count,
             columns_meta,
             meta.compression,
+            meta.col_stats.clone(),
         )
     }
 
     fn projection_part(meta: &BlockMeta, projections: &[usize]) -> PartInfoPtr {
         let mut columns_meta = HashMap::with_capacity(projections.len());
+        let mut col_stats = HashMap::with_capacity(projections.len());
 
         for projection in projections {
             let column_meta = &meta.col_metas[&(*projection as u32)];
@@ -203,6 +205,10 @@ impl FuseTable {
                 *projection,
                 ColumnMeta::create(column_meta.offset, column_meta.len, column_meta.num_values),
             );
+
+            if let Some(column_stats) = meta.col_stats.get(&(*projection as u32)) {
+                col_stats.insert(*projection as u32, column_stats.clone());
+            }
         }
 
         let rows_count = meta.row_count;
@@ -214,6 +220,7 @@ impl FuseTable {
             rows_count,
             columns_meta,
             meta.compression,
+            col_stats,
         )
     }
 
@@ -35,7 +35,7 @@ use crate::sessions::QueryContext;
 
 pub type ColumnsStatistics = HashMap<u32, ColumnStatistics>;
 
-#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
 pub struct ColumnStatistics {
     pub min: DataValue,
     pub max: DataValue,
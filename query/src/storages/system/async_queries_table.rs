@@ -0,0 +1,145 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use common_datablocks::DataBlock;
+use common_datavalues::prelude::*;
+use common_exception::Result;
+use common_meta_types::TableIdent;
+use common_meta_types::TableInfo;
+use common_meta_types::TableMeta;
+
+use crate::sessions::QueryContext;
+use crate::storages::system::table::AsyncOneBlockSystemTable;
+use crate::storages::system::table::AsyncSystemTable;
+use crate::storages::Table;
+
+/// The asynchronous queries submitted by `/v1/async_query` of the tenant, which are kept until
+/// their results expire.
+pub struct AsyncQueriesTable {
+    table_info: TableInfo,
+}
+
+#[async_trait::async_trait]
+impl AsyncSystemTable for AsyncQueriesTable {
+    const NAME: &'static str = "system.async_queries";
+
+    fn get_table_info(&self) -> &TableInfo {
+        &self.table_info
+    }
+
+    async fn get_full_data(&self, ctx: Arc<QueryContext>) -> Result<DataBlock> {
+        let queries = ctx
+            .get_current_session()
+            .get_session_manager()
+            .get_async_query_manager()
+            .list_queries(ctx.get_storage_operator()?, &ctx.get_tenant())
+            .await?;
+
+        let mut ids = Vec::with_capacity(queries.len());
+        let mut users = Vec::with_capacity(queries.len());
+        let mut states = Vec::with_capacity(queries.len());
+        let mut query_texts = Vec::with_capacity(queries.len());
+        let mut error_codes = Vec::with_capacity(queries.len());
+        let mut error_texts = Vec::with_capacity(queries.len());
+        let mut scan_rows = Vec::with_capacity(queries.len());
+        let mut scan_bytes = Vec::with_capacity(queries.len());
+        let mut result_rows = Vec::with_capacity(queries.len());
+        let mut result_bytes = Vec::with_capacity(queries.len());
+        let mut result_parts = Vec::with_capacity(queries.len());
+        let mut create_times = Vec::with_capacity(queries.len());
+        let mut finish_times = Vec::with_capacity(queries.len());
+        let mut expire_times = Vec::with_capacity(queries.len());
+
+        for query in &queries {
+            let state = query.get_state();
+            ids.push(query.id.clone().into_bytes());
+            users.push(query.user.clone().into_bytes());
+            states.push(format!("{:?}", state.state).into_bytes());
+            query_texts.push(query.sql.clone().into_bytes());
+            error_codes.push(state.error.as_ref().map(|e| e.code() as i32));
+            error_texts.push(state.error.as_ref().map(|e| e.message().into_bytes()));
+            let scan_progress = state.scan_progress.unwrap_or_default();
+            scan_rows.push(scan_progress.rows as u64);
+            scan_bytes.push(scan_progress.bytes as u64);
+            result_rows.push(state.result_rows as u64);
+            result_bytes.push(state.result_bytes as u64);
+            result_parts.push(state.result_parts as u64);
+            create_times.push(Self::timestamp(query.create_time));
+            finish_times.push(state.finish_time.map(Self::timestamp));
+            expire_times.push(state.expire_time.map(Self::timestamp));
+        }
+
+        Ok(DataBlock::create(self.table_info.schema(), vec![
+            Series::from_data(ids),
+            Series::from_data(users),
+            Series::from_data(states),
+            Series::from_data(query_texts),
+            Series::from_data(error_codes),
+            Series::from_data(error_texts),
+            Series::from_data(scan_rows),
+            Series::from_data(scan_bytes),
+            Series::from_data(result_rows),
+            Series::from_data(result_bytes),
+            Series::from_data(result_parts),
+            Series::from_data(create_times),
+            Series::from_data(finish_times),
+            Series::from_data(expire_times),
+        ]))
+    }
+}
+
+impl AsyncQueriesTable {
+    pub fn create(table_id: u64) -> Arc<dyn Table> {
+        let schema = DataSchemaRefExt::create(vec![
+            DataField::new("id", Vu8::to_data_type()),
+            DataField::new("user", Vu8::to_data_type()),
+            DataField::new("state", Vu8::to_data_type()),
+            DataField::new("query_text", Vu8::to_data_type()),
+            DataField::new_nullable("exception_code", i32::to_data_type()),
+            DataField::new_nullable("exception_text", Vu8::to_data_type()),
+            DataField::new("scan_rows", u64::to_data_type()),
+            DataField::new("scan_bytes", u64::to_data_type()),
+            DataField::new("result_rows", u64::to_data_type()),
+            DataField::new("result_bytes", u64::to_data_type()),
+            DataField::new("result_parts", u64::to_data_type()),
+            DataField::new("create_time", TimestampType::new_impl(3)),
+            DataField::new_nullable("finish_time", TimestampType::new_impl(3)),
+            DataField::new_nullable("expire_time", TimestampType::new_impl(3)),
+        ]);
+
+        let table_info = TableInfo {
+            desc: "'system'.'async_queries'".to_string(),
+            name: "async_queries".to_string(),
+            ident: TableIdent::new(table_id, 0),
+            meta: TableMeta {
+                schema,
+                engine: "SystemAsyncQueries".to_string(),
+                ..Default::default()
+            },
+        };
+
+        AsyncOneBlockSystemTable::create(AsyncQueriesTable { table_info })
+    }
+
+    // Timestamps are stored in microseconds.
+    fn timestamp(time: SystemTime) -> i64 {
+        time.duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_micros() as i64
+    }
+}
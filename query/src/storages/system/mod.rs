@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod async_queries_table;
 mod clusters_table;
 mod columns_table;
 mod configs_table;
@@ -23,6 +24,7 @@ mod functions_table;
 mod metrics_table;
 mod one_table;
 mod processes_table;
+mod query_cache_table;
 mod query_log_table;
 mod roles_table;
 mod settings_table;
@@ -32,6 +34,7 @@ mod tracing_table;
 mod tracing_table_stream;
 mod users_table;
 
+pub use async_queries_table::AsyncQueriesTable;
 pub use clusters_table::ClustersTable;
 pub use columns_table::ColumnsTable;
 pub use configs_table::ConfigsTable;
@@ -43,6 +46,7 @@ pub use functions_table::FunctionsTable;
 pub use metrics_table::MetricsTable;
 pub use one_table::OneTable;
 pub use processes_table::ProcessesTable;
+pub use query_cache_table::QueryCacheTable;
 pub use query_log_table::QueryLogTable;
 pub use roles_table::RolesTable;
 pub use settings_table::SettingsTable;
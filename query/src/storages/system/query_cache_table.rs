@@ -0,0 +1,124 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use common_datablocks::DataBlock;
+use common_datavalues::prelude::*;
+use common_exception::Result;
+use common_meta_types::TableIdent;
+use common_meta_types::TableInfo;
+use common_meta_types::TableMeta;
+
+use crate::sessions::QueryContext;
+use crate::storages::fuse::cache::QueryResultStorage;
+use crate::storages::system::table::AsyncOneBlockSystemTable;
+use crate::storages::system::table::AsyncSystemTable;
+use crate::storages::Table;
+
+/// The unexpired query results of the tenant kept in the query result cache.
+pub struct QueryCacheTable {
+    table_info: TableInfo,
+}
+
+#[async_trait::async_trait]
+impl AsyncSystemTable for QueryCacheTable {
+    const NAME: &'static str = "system.query_cache";
+
+    fn get_table_info(&self) -> &TableInfo {
+        &self.table_info
+    }
+
+    async fn get_full_data(&self, ctx: Arc<QueryContext>) -> Result<DataBlock> {
+        let entries = match ctx.get_storage_cache_manager().get_query_result_cache() {
+            Some(cache) => cache.list_entries(&ctx.get_tenant()),
+            None => vec![],
+        };
+
+        let mut keys = Vec::with_capacity(entries.len());
+        let mut query_texts = Vec::with_capacity(entries.len());
+        let mut tables = Vec::with_capacity(entries.len());
+        let mut storages = Vec::with_capacity(entries.len());
+        let mut result_rows = Vec::with_capacity(entries.len());
+        let mut result_bytes = Vec::with_capacity(entries.len());
+        let mut hits = Vec::with_capacity(entries.len());
+        let mut create_times = Vec::with_capacity(entries.len());
+        let mut expire_times = Vec::with_capacity(entries.len());
+
+        for entry in &entries {
+            keys.push(entry.key.clone().into_bytes());
+            query_texts.push(entry.sql.clone().into_bytes());
+            tables.push(entry.tables.join(", ").into_bytes());
+            storages.push(match entry.storage {
+                QueryResultStorage::Memory => b"memory".to_vec(),
+                QueryResultStorage::Disk => b"disk".to_vec(),
+            });
+            result_rows.push(entry.result_rows as u64);
+            result_bytes.push(entry.result_bytes as u64);
+            hits.push(entry.get_hits());
+            create_times.push(Self::timestamp(entry.create_time));
+            expire_times.push(Self::timestamp(entry.expire_time));
+        }
+
+        Ok(DataBlock::create(self.table_info.schema(), vec![
+            Series::from_data(keys),
+            Series::from_data(query_texts),
+            Series::from_data(tables),
+            Series::from_data(storages),
+            Series::from_data(result_rows),
+            Series::from_data(result_bytes),
+            Series::from_data(hits),
+            Series::from_data(create_times),
+            Series::from_data(expire_times),
+        ]))
+    }
+}
+
+impl QueryCacheTable {
+    pub fn create(table_id: u64) -> Arc<dyn Table> {
+        let schema = DataSchemaRefExt::create(vec![
+            DataField::new("key", Vu8::to_data_type()),
+            DataField::new("query_text", Vu8::to_data_type()),
+            DataField::new("tables", Vu8::to_data_type()),
+            DataField::new("storage", Vu8::to_data_type()),
+            DataField::new("result_rows", u64::to_data_type()),
+            DataField::new("result_bytes", u64::to_data_type()),
+            DataField::new("hits", u64::to_data_type()),
+            DataField::new("create_time", TimestampType::new_impl(3)),
+            DataField::new("expire_time", TimestampType::new_impl(3)),
+        ]);
+
+        let table_info = TableInfo {
+            desc: "'system'.'query_cache'".to_string(),
+            name: "query_cache".to_string(),
+            ident: TableIdent::new(table_id, 0),
+            meta: TableMeta {
+                schema,
+                engine: "SystemQueryCache".to_string(),
+                ..Default::default()
+            },
+        };
+
+        AsyncOneBlockSystemTable::create(QueryCacheTable { table_info })
+    }
+
+    // Timestamps are stored in microseconds.
+    fn timestamp(time: SystemTime) -> i64 {
+        time.duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_micros() as i64
+    }
+}
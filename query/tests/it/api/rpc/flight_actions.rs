@@ -35,6 +35,7 @@ async fn test_shuffle_action_try_into() -> Result<()> {
         plan: PlanParser::parse(ctx.clone(), "SELECT number FROM numbers(5)").await?,
         sinks: vec![String::from("stream_id")],
         scatters_expression: Expression::create_literal(DataValue::UInt64(1)),
+        new_processor_framework: false,
     };
 
     let from_action = FlightAction::PrepareShuffleAction(shuffle_action);
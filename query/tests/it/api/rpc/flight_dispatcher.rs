@@ -14,6 +14,7 @@
 
 use common_base::base::tokio;
 use common_datablocks::assert_blocks_eq;
+use common_datablocks::assert_blocks_sorted_eq;
 use common_datavalues::DataValue;
 use common_exception::Result;
 use common_planners::Expression;
@@ -64,6 +65,7 @@ async fn test_run_shuffle_action_with_no_scatters() -> Result<()> {
                     plan: PlanParser::parse(ctx.clone(), "SELECT number FROM numbers(5)").await?,
                     sinks: vec![stream_id.clone()],
                     scatters_expression: Expression::create_literal(DataValue::UInt64(1)),
+                    new_processor_framework: false,
                 }),
             )
             .await?;
@@ -109,6 +111,7 @@ async fn test_run_shuffle_action_with_scatter() -> Result<()> {
                     plan: PlanParser::parse(ctx.clone(), "SELECT number FROM numbers(5)").await?,
                     sinks: vec!["stream_1".to_string(), "stream_2".to_string()],
                     scatters_expression: Expression::Column("number".to_string()),
+                    new_processor_framework: false,
                 }),
             )
             .await?;
@@ -150,6 +153,66 @@ async fn test_run_shuffle_action_with_scatter() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_run_shuffle_action_with_exchange() -> Result<()> {
+    if let (Some(query_id), Some(stage_id), None) = generate_uuids(2) {
+        let ctx = create_query_context().await?;
+        let flight_dispatcher = DatabendQueryFlightDispatcher::create();
+
+        let sessions = SessionManagerBuilder::create().build()?;
+        let rpc_session = sessions.create_rpc_session(query_id.clone(), false).await?;
+
+        flight_dispatcher
+            .shuffle_action(
+                rpc_session,
+                FlightAction::PrepareShuffleAction(ShuffleAction {
+                    query_id: query_id.clone(),
+                    stage_id: stage_id.clone(),
+                    plan: PlanParser::parse(ctx.clone(), "SELECT number FROM numbers(5)").await?,
+                    sinks: vec!["stream_1".to_string(), "stream_2".to_string()],
+                    scatters_expression: Expression::Column("number".to_string()),
+                    new_processor_framework: true,
+                }),
+            )
+            .await?;
+
+        let stream_1 = stream_ticket(&query_id, &stage_id, "stream_1");
+        let (receiver, _data_scheme) = flight_dispatcher.get_stream(&stream_1)?;
+        let receiver_stream = ReceiverStream::new(receiver);
+        let collect_data_blocks = receiver_stream.collect::<Result<Vec<_>>>();
+
+        let expect = vec![
+            "+--------+",
+            "| number |",
+            "+--------+",
+            "| 0      |",
+            "| 2      |",
+            "| 4      |",
+            "+--------+",
+        ];
+
+        assert_blocks_sorted_eq(expect, &collect_data_blocks.await?);
+
+        let stream_2 = stream_ticket(&query_id, &stage_id, "stream_2");
+        let (receiver, _data_scheme) = flight_dispatcher.get_stream(&stream_2)?;
+        let receiver_stream = ReceiverStream::new(receiver);
+        let collect_data_blocks = receiver_stream.collect::<Result<Vec<_>>>();
+
+        let expect = vec![
+            "+--------+",
+            "| number |",
+            "+--------+",
+            "| 1      |",
+            "| 3      |",
+            "+--------+",
+        ];
+
+        assert_blocks_sorted_eq(expect, &collect_data_blocks.await?);
+    }
+
+    Ok(())
+}
+
 fn stream_ticket(query_id: &str, stage_id: &str, stream: &str) -> StreamTicket {
     StreamTicket {
         query_id: query_id.to_string(),
@@ -169,6 +169,7 @@ async fn do_action_request(query_id: &str, stage_id: &str) -> Result<Request<Act
         plan: PlanParser::parse(ctx.clone(), "SELECT number FROM numbers(5)").await?,
         sinks: vec![String::from("stream_id")],
         scatters_expression: Expression::create_literal(DataValue::UInt64(1)),
+        new_processor_framework: false,
     });
 
     Ok(Request::new(flight_action.try_into()?))
@@ -36,15 +36,26 @@ mysql_handler_port = 3307
 max_active_sessions = 256
 clickhouse_handler_host = "127.0.0.1"
 clickhouse_handler_port = 9000
+postgres_handler_host = "127.0.0.1"
+postgres_handler_port = 5433
+flight_sql_handler_host = "127.0.0.1"
+flight_sql_handler_port = 8900
 http_handler_host = "127.0.0.1"
 http_handler_port = 8000
 http_handler_result_timeout_millis = 10000
+http_handler_async_result_retention_secs = 86400
 flight_api_address = "127.0.0.1:9090"
 admin_api_address = "127.0.0.1:8080"
 metric_api_address = "127.0.0.1:7070"
 http_handler_tls_server_cert = ""
 http_handler_tls_server_key = ""
 http_handler_tls_server_root_ca_cert = ""
+mysql_handler_tls_server_cert = ""
+mysql_handler_tls_server_key = ""
+mysql_handler_tls_server_root_ca_cert = ""
+clickhouse_handler_tls_server_cert = ""
+clickhouse_handler_tls_server_key = ""
+clickhouse_handler_tls_server_root_ca_cert = ""
 api_tls_server_cert = ""
 api_tls_server_key = ""
 api_tls_server_root_ca_cert = ""
@@ -63,8 +74,10 @@ table_cache_block_meta_count = 102400
 table_memory_cache_mb_size = 256
 table_disk_cache_root = "_cache"
 table_disk_cache_mb_size = 1024
+query_result_cache_mb_size = 64
 management_mode = false
 jwt_key_file = ""
+spill_local_path = ""
 
 [log]
 level = "INFO"
@@ -212,15 +225,26 @@ mysql_handler_port = 3307
 max_active_sessions = 256
 clickhouse_handler_host = "127.0.0.1"
 clickhouse_handler_port = 9000
+postgres_handler_host = "127.0.0.1"
+postgres_handler_port = 5433
+flight_sql_handler_host = "127.0.0.1"
+flight_sql_handler_port = 8900
 http_handler_host = "127.0.0.1"
 http_handler_port = 8000
 http_handler_result_timeout_millis = 10000
+http_handler_async_result_retention_secs = 86400
 flight_api_address = "127.0.0.1:9090"
 admin_api_address = "127.0.0.1:8080"
 metric_api_address = "127.0.0.1:7070"
 http_handler_tls_server_cert = ""
 http_handler_tls_server_key = ""
 http_handler_tls_server_root_ca_cert = ""
+mysql_handler_tls_server_cert = ""
+mysql_handler_tls_server_key = ""
+mysql_handler_tls_server_root_ca_cert = ""
+clickhouse_handler_tls_server_cert = ""
+clickhouse_handler_tls_server_key = ""
+clickhouse_handler_tls_server_root_ca_cert = ""
 api_tls_server_cert = ""
 api_tls_server_key = ""
 api_tls_server_root_ca_cert = ""
@@ -239,8 +263,10 @@ table_cache_block_meta_count = 102400
 table_memory_cache_mb_size = 256
 table_disk_cache_root = "_cache"
 table_disk_cache_mb_size = 1024
+query_result_cache_mb_size = 64
 management_mode = false
 jwt_key_file = ""
+spill_local_path = ""
 
 [log]
 level = "INFO"
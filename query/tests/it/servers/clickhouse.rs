@@ -12,18 +12,32 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::fs::File;
+use std::io::BufReader;
 use std::net::SocketAddr;
+use std::sync::Arc;
 use std::time::Duration;
 
 use clickhouse_driver::prelude::*;
 use common_base::base::tokio;
+use common_base::base::tokio::io::AsyncReadExt;
+use common_base::base::tokio::io::AsyncWriteExt;
+use common_base::base::tokio::net::TcpStream;
 use common_exception::ErrorCode;
 use common_exception::Result;
 use databend_query::servers::ClickHouseHandler;
 use databend_query::servers::Server;
 use tempfile::TempDir;
+use tokio_rustls::rustls::ClientConfig;
+use tokio_rustls::rustls::RootCertStore;
+use tokio_rustls::rustls::ServerName;
+use tokio_rustls::TlsConnector;
 use uuid::Uuid;
 
+use crate::tests::tls_constants::TEST_CA_CERT;
+use crate::tests::tls_constants::TEST_CN_NAME;
+use crate::tests::tls_constants::TEST_SERVER_CERT;
+use crate::tests::tls_constants::TEST_SERVER_KEY;
 use crate::tests::SessionManagerBuilder;
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
@@ -188,6 +202,47 @@ async fn test_abort_clickhouse_server() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_clickhouse_handler_tls() -> Result<()> {
+    let mut handler = ClickHouseHandler::create(
+        SessionManagerBuilder::create()
+            .max_sessions(1)
+            .clickhouse_handler_tls_server_cert(TEST_SERVER_CERT)
+            .clickhouse_handler_tls_server_key(TEST_SERVER_KEY)
+            .build()?,
+    );
+
+    let listening = "127.0.0.1:0".parse::<SocketAddr>()?;
+    let listening = handler.start(listening).await?;
+
+    let mut roots = RootCertStore::empty();
+    let ca_cert = rustls_pemfile::certs(&mut BufReader::new(File::open(TEST_CA_CERT)?))?;
+    roots.add_parsable_certificates(&ca_cert);
+    let config = ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    let connector = TlsConnector::from(Arc::new(config));
+    let stream = TcpStream::connect(listening).await?;
+    let domain = ServerName::try_from(TEST_CN_NAME).unwrap();
+    let mut stream = connector.connect(domain, stream).await?;
+
+    // Client hello: name, version 1.1, revision 54405 as varint, database, user and password.
+    let mut hello = vec![0, 13];
+    hello.extend(b"databend-test");
+    hello.extend([1, 1, 0x85, 0xA9, 0x03]);
+    for value in ["default", "default", ""] {
+        hello.push(value.len() as u8);
+        hello.extend(value.as_bytes());
+    }
+    stream.write_all(&hello).await?;
+    stream.flush().await?;
+
+    // Server hello.
+    assert_eq!(stream.read_u8().await?, 0);
+    Ok(())
+}
+
 async fn start_server(max_sessions: u64) -> Result<(Box<dyn Server>, SocketAddr)> {
     let mut handler = ClickHouseHandler::create(
         SessionManagerBuilder::create()
@@ -0,0 +1,373 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use common_arrow::arrow::array::ArrayRef;
+use common_arrow::arrow::array::Int32Array;
+use common_arrow::arrow::array::Int64Array;
+use common_arrow::arrow::array::Utf8Array;
+use common_arrow::arrow::chunk::Chunk;
+use common_arrow::arrow::datatypes::DataType as ArrowType;
+use common_arrow::arrow::datatypes::Field as ArrowField;
+use common_arrow::arrow::datatypes::Schema as ArrowSchema;
+use common_arrow::arrow::io::flight::deserialize_batch;
+use common_arrow::arrow::io::flight::deserialize_schemas;
+use common_arrow::arrow::io::flight::serialize_batch;
+use common_arrow::arrow::io::flight::serialize_schema;
+use common_arrow::arrow::io::ipc::write::default_ipc_fields;
+use common_arrow::arrow::io::ipc::write::WriteOptions;
+use common_arrow::arrow_format::flight::data::flight_descriptor::DescriptorType;
+use common_arrow::arrow_format::flight::data::Action;
+use common_arrow::arrow_format::flight::data::FlightData;
+use common_arrow::arrow_format::flight::data::FlightDescriptor;
+use common_arrow::arrow_format::flight::data::HandshakeRequest;
+use common_arrow::arrow_format::flight::data::Ticket;
+use common_arrow::arrow_format::flight::service::flight_service_client::FlightServiceClient;
+use common_base::base::tokio;
+use common_datablocks::assert_blocks_eq;
+use common_datablocks::DataBlock;
+use common_datavalues::DataSchema;
+use common_exception::Result;
+use common_grpc::ConnectionFactory;
+use databend_query::servers::flight_sql::*;
+use databend_query::servers::FlightSqlHandler;
+use databend_query::servers::Server;
+use futures::StreamExt;
+use prost::Message;
+use tonic::metadata::MetadataValue;
+use tonic::transport::Channel;
+use tonic::Code;
+use tonic::Request;
+
+use crate::tests::SessionManagerBuilder;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_flight_sql_statement_query() -> Result<()> {
+    let (_handler, mut client) = start_server().await?;
+
+    // Without the authorization header.
+    let descriptor = command_descriptor(&CommandStatementQuery {
+        query: "SELECT 1".to_string(),
+    });
+    let status = client
+        .get_flight_info(Request::new(descriptor))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::Unauthenticated);
+
+    let token = handshake(&mut client).await;
+    let blocks = query(&mut client, &token, &CommandStatementQuery {
+        query: "SELECT number, number * 2 AS double FROM numbers(3)".to_string(),
+    })
+    .await;
+    assert_blocks_eq(
+        vec![
+            "+--------+--------+",
+            "| number | double |",
+            "+--------+--------+",
+            "| 0      | 0      |",
+            "| 1      | 2      |",
+            "| 2      | 4      |",
+            "+--------+--------+",
+        ],
+        &blocks,
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_flight_sql_ingest_and_prepared_statement() -> Result<()> {
+    let (_handler, mut client) = start_server().await?;
+    let token = handshake(&mut client).await;
+
+    let create = CommandStatementUpdate {
+        query: "CREATE TABLE t(a INT, b VARCHAR)".to_string(),
+    };
+    do_put(&mut client, &token, command_descriptor(&create), None).await;
+
+    // The columns are matched by names and cast to the types of the table.
+    let schema = ArrowSchema::from(vec![
+        ArrowField::new("b", ArrowType::Utf8, false),
+        ArrowField::new("a", ArrowType::Int64, false),
+    ]);
+    let chunk = Chunk::new(vec![
+        Arc::new(Utf8Array::<i32>::from_slice(["x", "y", "z"])) as ArrayRef,
+        Arc::new(Int64Array::from_slice([1, 2, 3])) as ArrayRef,
+    ]);
+    let ingest = CommandStatementIngest {
+        table: "t".to_string(),
+        ..Default::default()
+    };
+    let result = do_put(
+        &mut client,
+        &token,
+        command_descriptor(&ingest),
+        Some((schema, chunk)),
+    )
+    .await;
+    assert_eq!(DoPutUpdateResult::decode(&*result).unwrap().record_count, 3);
+
+    // Prepare a query with a placeholder.
+    let request = ActionCreatePreparedStatementRequest {
+        query: "SELECT a, b FROM t WHERE a >= ? ORDER BY a".to_string(),
+    };
+    let mut results = client
+        .do_action(authorized(
+            Action {
+                r#type: "CreatePreparedStatement".to_string(),
+                body: request.to_any_bytes(),
+            },
+            &token,
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    let body = results.next().await.unwrap().unwrap().body;
+    let prepared = ActionCreatePreparedStatementResult::from_any_bytes(&body).unwrap();
+    assert!(!prepared.parameter_schema.is_empty());
+    let handle = prepared.prepared_statement_handle;
+
+    // Bind the parameter and execute.
+    let command = CommandPreparedStatementQuery {
+        prepared_statement_handle: handle.clone(),
+    };
+    let params_schema = ArrowSchema::from(vec![ArrowField::new("$1", ArrowType::Int32, false)]);
+    let params = Chunk::new(vec![Arc::new(Int32Array::from_slice([2])) as ArrayRef]);
+    do_put(
+        &mut client,
+        &token,
+        command_descriptor(&command),
+        Some((params_schema, params)),
+    )
+    .await;
+    let blocks = query(&mut client, &token, &command).await;
+    assert_blocks_eq(
+        vec![
+            "+---+---+",
+            "| a | b |",
+            "+---+---+",
+            "| 2 | y |",
+            "| 3 | z |",
+            "+---+---+",
+        ],
+        &blocks,
+    );
+
+    // The statement is unknown after closed.
+    let request = ActionClosePreparedStatementRequest {
+        prepared_statement_handle: handle,
+    };
+    client
+        .do_action(authorized(
+            Action {
+                r#type: "ClosePreparedStatement".to_string(),
+                body: request.to_any_bytes(),
+            },
+            &token,
+        ))
+        .await
+        .unwrap();
+    let descriptor = command_descriptor(&command);
+    let status = client
+        .get_flight_info(authorized(descriptor, &token))
+        .await
+        .unwrap_err();
+    assert_eq!(status.message(), "Unknown prepared statement handle");
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_flight_sql_metadata() -> Result<()> {
+    let (_handler, mut client) = start_server().await?;
+    let token = handshake(&mut client).await;
+
+    let blocks = query(&mut client, &token, &CommandGetCatalogs {}).await;
+    assert_blocks_eq(
+        vec![
+            "+--------------+",
+            "| catalog_name |",
+            "+--------------+",
+            "| default      |",
+            "+--------------+",
+        ],
+        &blocks,
+    );
+
+    let command = CommandGetDbSchemas {
+        catalog: None,
+        db_schema_filter_pattern: Some("s%".to_string()),
+    };
+    let blocks = query(&mut client, &token, &command).await;
+    assert_blocks_eq(
+        vec![
+            "+--------------+----------------+",
+            "| catalog_name | db_schema_name |",
+            "+--------------+----------------+",
+            "| default      | system         |",
+            "+--------------+----------------+",
+        ],
+        &blocks,
+    );
+
+    let command = CommandGetTables {
+        catalog: Some("default".to_string()),
+        db_schema_filter_pattern: Some("system".to_string()),
+        table_name_filter_pattern: Some("o_e".to_string()),
+        table_types: vec!["BASE TABLE".to_string()],
+        include_schema: false,
+    };
+    let blocks = query(&mut client, &token, &command).await;
+    assert_blocks_eq(
+        vec![
+            "+--------------+----------------+------------+------------+",
+            "| catalog_name | db_schema_name | table_name | table_type |",
+            "+--------------+----------------+------------+------------+",
+            "| default      | system         | one        | BASE TABLE |",
+            "+--------------+----------------+------------+------------+",
+        ],
+        &blocks,
+    );
+
+    let blocks = query(&mut client, &token, &CommandGetTableTypes {}).await;
+    assert_blocks_eq(
+        vec![
+            "+------------+",
+            "| table_type |",
+            "+------------+",
+            "| BASE TABLE |",
+            "| VIEW       |",
+            "+------------+",
+        ],
+        &blocks,
+    );
+
+    Ok(())
+}
+
+async fn start_server() -> Result<(Box<dyn Server>, FlightServiceClient<Channel>)> {
+    let mut handler = FlightSqlHandler::create(SessionManagerBuilder::create().build()?);
+    let listening = "127.0.0.1:0".parse::<SocketAddr>()?;
+    let listening = handler.start(listening).await?;
+
+    let channel = ConnectionFactory::create_rpc_channel(listening, None, None).unwrap();
+    Ok((handler, FlightServiceClient::new(channel)))
+}
+
+fn authorized<T>(message: T, token: &str) -> Request<T> {
+    let mut request = Request::new(message);
+    let authorization = MetadataValue::from_str(&format!("Bearer {}", token)).unwrap();
+    request
+        .metadata_mut()
+        .insert("authorization", authorization);
+    request
+}
+
+// Handshake with the user `root`, the bearer token is returned in the header.
+async fn handshake(client: &mut FlightServiceClient<Channel>) -> String {
+    let request = HandshakeRequest {
+        protocol_version: 0,
+        payload: vec![],
+    };
+    let mut request = Request::new(tokio_stream::once(request));
+    let authorization = MetadataValue::from_str(&format!("Basic {}", base64::encode("root:")));
+    request
+        .metadata_mut()
+        .insert("authorization", authorization.unwrap());
+    let response = client.handshake(request).await.unwrap();
+    let authorization = response.metadata().get("authorization").unwrap();
+    let token = authorization.to_str().unwrap().strip_prefix("Bearer ");
+    token.unwrap().to_string()
+}
+
+fn command_descriptor<T: FlightSqlMessage>(command: &T) -> FlightDescriptor {
+    FlightDescriptor {
+        r#type: DescriptorType::Cmd as i32,
+        cmd: command.to_any_bytes(),
+        path: vec![],
+    }
+}
+
+async fn query<T: FlightSqlMessage>(
+    client: &mut FlightServiceClient<Channel>,
+    token: &str,
+    command: &T,
+) -> Vec<DataBlock> {
+    let descriptor = command_descriptor(command);
+    let info = client
+        .get_flight_info(authorized(descriptor, token))
+        .await
+        .unwrap()
+        .into_inner();
+    let ticket = info.endpoint[0].ticket.clone().unwrap();
+
+    let mut stream = client
+        .do_get(authorized(
+            Ticket {
+                ticket: ticket.ticket,
+            },
+            token,
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    let first = stream.next().await.unwrap().unwrap();
+    let (arrow_schema, ipc_schema) = deserialize_schemas(&first.data_header).unwrap();
+    let schema = Arc::new(DataSchema::from(&arrow_schema));
+    let mut blocks = vec![];
+    while let Some(data) = stream.next().await {
+        let data = data.unwrap();
+        let chunk = deserialize_batch(
+            &data,
+            &arrow_schema.fields,
+            &ipc_schema,
+            &Default::default(),
+        )
+        .unwrap();
+        blocks.push(DataBlock::from_chunk(&schema, &chunk).unwrap());
+    }
+    blocks
+}
+
+async fn do_put(
+    client: &mut FlightServiceClient<Channel>,
+    token: &str,
+    descriptor: FlightDescriptor,
+    batch: Option<(ArrowSchema, Chunk<ArrayRef>)>,
+) -> Vec<u8> {
+    let messages = match batch {
+        None => vec![FlightData {
+            flight_descriptor: Some(descriptor),
+            ..Default::default()
+        }],
+        Some((schema, chunk)) => {
+            let ipc_fields = default_ipc_fields(&schema.fields);
+            let mut first = serialize_schema(&schema, Some(&ipc_fields));
+            first.flight_descriptor = Some(descriptor);
+            let options = WriteOptions { compression: None };
+            let (_, batch) = serialize_batch(&chunk, &ipc_fields, &options);
+            vec![first, batch]
+        }
+    };
+
+    let mut results = client
+        .do_put(authorized(tokio_stream::iter(messages), token))
+        .await
+        .unwrap()
+        .into_inner();
+    results.next().await.unwrap().unwrap().app_metadata
+}
@@ -0,0 +1,309 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use common_base::base::tokio;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use databend_query::servers::http::middleware::HTTPSessionEndpoint;
+use databend_query::servers::http::middleware::HTTPSessionMiddleware;
+use databend_query::servers::http::v1::async_query_route;
+use databend_query::servers::http::v1::make_async_result_uri;
+use databend_query::servers::http::v1::make_async_state_uri;
+use databend_query::servers::http::v1::AsyncQueryManifest;
+use databend_query::servers::http::v1::AsyncQueryResponse;
+use databend_query::servers::http::v1::ExecuteStateKind;
+use databend_query::servers::http::v1::QueryProgress;
+use databend_query::servers::http::v1::HEADER_QUERY_ID;
+use databend_query::sessions::SessionManager;
+use poem::http::header;
+use poem::http::Method;
+use poem::http::StatusCode;
+use poem::Endpoint;
+use poem::EndpointExt;
+use poem::Request;
+use poem::Response;
+use poem::Route;
+use pretty_assertions::assert_eq;
+use tokio::time::sleep;
+
+use crate::tests::SessionManagerBuilder;
+
+type EndpointType = HTTPSessionEndpoint<Route>;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_async_query() -> Result<()> {
+    let ep = create_endpoint();
+    let json = serde_json::json!({"sql": "select number, 'a' from numbers(5)"});
+    let (status, result) = post_async_query(&ep, &json).await?;
+    assert_eq!(status, StatusCode::OK);
+    assert!(result.error.is_none(), "{:?}", result);
+    assert_eq!(result.stats_uri, Some(make_async_state_uri(&result.id)));
+
+    let result = wait_for_query(&ep, &result.id).await?;
+    assert_eq!(result.state, ExecuteStateKind::Succeeded, "{:?}", result);
+    assert_eq!(result.result_rows, 5);
+    assert!(result.stats.scan_progress.is_some());
+    let result_uri = result.result_uri.unwrap();
+    assert_eq!(result_uri, make_async_result_uri(&result.id));
+
+    // the result is read back from the storage.
+    let response = get_uri(&ep, &format!("{}?format=ndjson", result_uri)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get(HEADER_QUERY_ID).unwrap(),
+        result.id.as_str()
+    );
+    let lines = read_ndjson(response).await;
+    assert_eq!(lines[0]["schema"]["fields"][0]["name"], "number");
+    let rows = lines
+        .iter()
+        .filter(|line| line.is_array())
+        .collect::<Vec<_>>();
+    assert_eq!(rows.len(), 5);
+    assert_eq!(rows[4], &serde_json::json!([4, "a"]));
+    let progress: QueryProgress = serde_json::from_value(lines.last().unwrap().clone())?;
+    assert_eq!(progress.state, ExecuteStateKind::Succeeded);
+
+    let uri = format!("{}?page_token=1", result_uri);
+    let response = get_uri(&ep, &uri).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // the state is queryable in the system table.
+    let sql = format!(
+        "select state, result_rows from system.async_queries where id = '{}'",
+        result.id
+    );
+    let (_, list) = post_async_query(&ep, &serde_json::json!({ "sql": sql })).await?;
+    let list = wait_for_query(&ep, &list.id).await?;
+    let response = get_uri(&ep, &list.result_uri.unwrap()).await;
+    let lines = read_ndjson(response).await;
+    assert_eq!(lines[1], serde_json::json!(["Succeeded", 5]));
+
+    // the query is removed with its result.
+    let uri = format!("/v1/async_query/{}/kill?delete=true", result.id);
+    let response = get_uri(&ep, &uri).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let mut status = StatusCode::OK;
+    for _ in 0..100 {
+        status = get_uri(&ep, &make_async_state_uri(&result.id))
+            .await
+            .status();
+        if status == StatusCode::NOT_FOUND {
+            break;
+        }
+        sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_async_query_failed() -> Result<()> {
+    let ep = create_endpoint();
+    let json = serde_json::json!({"sql": "bad sql"});
+    let (status, result) = post_async_query(&ep, &json).await?;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(result.state, ExecuteStateKind::Failed);
+    assert!(result.error.is_some());
+    assert!(result.stats_uri.is_none());
+
+    let response = get_uri(&ep, &make_async_state_uri("not-exists")).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_async_query_kill() -> Result<()> {
+    let ep = create_endpoint();
+    let json = serde_json::json!({"sql": "select sleep(2)"});
+    let (_, result) = post_async_query(&ep, &json).await?;
+    assert_eq!(result.state, ExecuteStateKind::Running, "{:?}", result);
+
+    let uri = format!("/v1/async_query/{}/kill", result.id);
+    let response = get_uri(&ep, &uri).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let result = wait_for_query(&ep, &result.id).await?;
+    assert_eq!(result.state, ExecuteStateKind::Failed, "{:?}", result);
+    assert!(result.error.is_some());
+    assert!(result.result_uri.is_none());
+
+    let uri = make_async_result_uri(&result.id);
+    let response = get_uri(&ep, &uri).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_async_query_manifest() -> Result<()> {
+    let session_manager = SessionManagerBuilder::create().build().unwrap();
+    let ep = create_endpoint_with(session_manager.clone());
+    let json = serde_json::json!({"sql": "select number from numbers(3)"});
+    let (_, result) = post_async_query(&ep, &json).await?;
+    let result = wait_for_query(&ep, &result.id).await?;
+    assert_eq!(result.state, ExecuteStateKind::Succeeded, "{:?}", result);
+
+    // the manifest next to the result is updated once the query is stopped.
+    let mut manifest = None;
+    for _ in 0..100 {
+        manifest = read_manifest(&session_manager, &result.id).await?;
+        if matches!(&manifest, Some(m) if m.state != ExecuteStateKind::Running) {
+            break;
+        }
+        sleep(Duration::from_millis(10)).await;
+    }
+    let manifest = manifest.unwrap();
+    assert_eq!(manifest.state, ExecuteStateKind::Succeeded);
+    assert_eq!(manifest.result_rows, 3);
+    assert_eq!(manifest.result_parts, 1);
+
+    // the stopped query is looked up from the storage, e.g. by another node.
+    let other_ep = create_endpoint();
+    let response = get_uri(&other_ep, &make_async_state_uri(&result.id)).await;
+    let (status, other_result) = check_response(response).await?;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(other_result.state, ExecuteStateKind::Succeeded);
+    assert_eq!(other_result.result_rows, 3);
+
+    let response = get_uri(&other_ep, &make_async_result_uri(&result.id)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let lines = read_ndjson(response).await;
+    assert_eq!(lines.iter().filter(|line| line.is_array()).count(), 3);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_async_query_expired() -> Result<()> {
+    let session_manager = SessionManagerBuilder::create()
+        .http_handler_async_result_retention_secs(0u64)
+        .build()
+        .unwrap();
+    let ep = create_endpoint_with(session_manager.clone());
+    let json = serde_json::json!({"sql": "select number from numbers(3)"});
+    let (_, result) = post_async_query(&ep, &json).await?;
+
+    // the query expires once it is stopped, and its result is removed from the storage.
+    let mut status = StatusCode::OK;
+    let mut manifest = None;
+    for _ in 0..100 {
+        status = get_uri(&ep, &make_async_state_uri(&result.id))
+            .await
+            .status();
+        manifest = read_manifest(&session_manager, &result.id).await?;
+        if status == StatusCode::NOT_FOUND && manifest.is_none() {
+            break;
+        }
+        sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(manifest.is_none(), "{:?}", manifest);
+    Ok(())
+}
+
+async fn read_manifest(
+    session_manager: &Arc<SessionManager>,
+    query_id: &str,
+) -> Result<Option<AsyncQueryManifest>> {
+    let tenant = session_manager.get_conf().query.tenant_id;
+    let location = AsyncQueryManifest::location(&tenant, query_id);
+    let object = session_manager.get_storage_operator().object(&location);
+    match object.range_read(..).await {
+        Ok(data) => Ok(Some(serde_json::from_slice(&data)?)),
+        Err(cause) if cause.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(cause) => Err(cause.into()),
+    }
+}
+
+fn create_endpoint() -> EndpointType {
+    create_endpoint_with(SessionManagerBuilder::create().build().unwrap())
+}
+
+fn create_endpoint_with(session_manager: Arc<SessionManager>) -> EndpointType {
+    Route::new()
+        .nest("/v1/async_query", async_query_route())
+        .with(HTTPSessionMiddleware { session_manager })
+}
+
+// Poll the state of the query until it is stopped.
+async fn wait_for_query(ep: &EndpointType, query_id: &str) -> Result<AsyncQueryResponse> {
+    for _ in 0..500 {
+        let response = get_uri(ep, &make_async_state_uri(query_id)).await;
+        let (status, result) = check_response(response).await?;
+        assert_eq!(status, StatusCode::OK);
+        if result.state != ExecuteStateKind::Running {
+            return Ok(result);
+        }
+        sleep(Duration::from_millis(10)).await;
+    }
+    Err(ErrorCode::Timeout(format!(
+        "query {} is still running",
+        query_id
+    )))
+}
+
+async fn read_ndjson(response: Response) -> Vec<serde_json::Value> {
+    let body = response.into_body().into_string().await.unwrap();
+    body.lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+async fn check_response(response: Response) -> Result<(StatusCode, AsyncQueryResponse)> {
+    let status = response.status();
+    let body = response.into_body().into_string().await.unwrap();
+    let result = serde_json::from_str::<AsyncQueryResponse>(&body);
+    assert!(
+        result.is_ok(),
+        "body ='{}', result='{:?}'",
+        &body,
+        result.err()
+    );
+    Ok((status, result?))
+}
+
+async fn get_uri(ep: &EndpointType, uri: &str) -> Response {
+    let basic = headers::Authorization::basic("root", "");
+    ep.call(
+        Request::builder()
+            .uri(uri.parse().unwrap())
+            .method(Method::GET)
+            .typed_header(basic)
+            .finish(),
+    )
+    .await
+    .unwrap_or_else(|err| err.as_response())
+}
+
+async fn post_async_query(
+    ep: &EndpointType,
+    json: &serde_json::Value,
+) -> Result<(StatusCode, AsyncQueryResponse)> {
+    let body = serde_json::to_vec(&json)?;
+    let basic = headers::Authorization::basic("root", "");
+    let req = Request::builder()
+        .uri("/v1/async_query".parse().unwrap())
+        .method(Method::POST)
+        .header(header::CONTENT_TYPE, "application/json")
+        .typed_header(basic)
+        .body(body);
+    let response = ep
+        .call(req)
+        .await
+        .map_err(|e| ErrorCode::UnexpectedError(e.to_string()))?;
+    check_response(response).await
+}
@@ -203,8 +203,279 @@ async fn test_insert_format_ndjson() -> PoemResult<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_insert_format_csv_and_tsv() -> PoemResult<()> {
+    let server = Server::new();
+    {
+        let (status, body) = server.post("create table t1(a int, b string)", "").await;
+        assert_ok!(status, body);
+    }
+
+    {
+        let (status, body) = server
+            .post("insert into table t1 format CSV", "0,\"a\"\n")
+            .await;
+        assert_ok!(status, body);
+    }
+
+    {
+        let (status, body) = server
+            .post("insert into table t1 format TSVWithNames", "a\tb\n1\tb\n")
+            .await;
+        assert_ok!(status, body);
+    }
+
+    {
+        let (status, body) = server.get(r#"select * from t1 order by a"#).await;
+        assert_ok!(status, body);
+        assert_eq!(&body, "0\ta\n1\tb\n");
+    }
+
+    {
+        let (status, body) = server.post("insert into table t1 format Parquet", "").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_error!(body, "only support: JSONEachRow|CSV|CSVWithNames");
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_output_formats() -> PoemResult<()> {
+    let server = Server::new();
+    let sql = "select number, 'a' as s from numbers(2) order by number";
+
+    {
+        let (status, body) = server
+            .get_with_params(sql, &[("default_format", "TSVWithNamesAndTypes")])
+            .await;
+        assert_ok!(status, body);
+        assert_eq!(&body, "number\ts\nUInt64\tString\n0\ta\n1\ta\n");
+    }
+
+    {
+        let (status, body) = server.get(&format!("{} format CSVWithNames", sql)).await;
+        assert_ok!(status, body);
+        assert_eq!(&body, "\"number\",\"s\"\n0,\"a\"\n1,\"a\"\n");
+    }
+
+    {
+        // the FORMAT clause takes precedence over default_format.
+        let (status, body) = server
+            .get_with_params(&format!("{} FORMAT TSV;", sql), &[(
+                "default_format",
+                "CSV",
+            )])
+            .await;
+        assert_ok!(status, body);
+        assert_eq!(&body, "0\ta\n1\ta\n");
+    }
+
+    {
+        let (status, body) = server.get(&format!("{} format JSON", sql)).await;
+        assert_ok!(status, body);
+        let result: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(
+            result["meta"],
+            serde_json::json!([{"name": "number", "type": "UInt64"}, {"name": "s", "type": "String"}])
+        );
+        assert_eq!(result["rows"], 2);
+        assert_eq!(result["data"][1]["s"], "a");
+        assert!(result["statistics"]["elapsed"].is_number());
+    }
+
+    {
+        let (status, body) = server.get(&format!("{} format JSONCompact", sql)).await;
+        assert_ok!(status, body);
+        let result: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(result["rows"], 2);
+        assert_eq!(result["data"][0][1], "a");
+    }
+
+    {
+        let (status, body) = server
+            .get("select cast(1 as UInt8), 'ab' format RowBinary")
+            .await;
+        assert_ok!(status, body);
+        assert_eq!(body.as_bytes(), &[1, 2, b'a', b'b']);
+    }
+
+    {
+        let (status, body) = server
+            .get_with_params(sql, &[("default_format", "XML")])
+            .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_error!(body, "Unknown output format XML");
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_query_params() -> PoemResult<()> {
+    let server = Server::new();
+
+    {
+        let (status, body) = server
+            .get_with_params("select {x:UInt64} + 1, {s:String}, '{x:UInt64}'", &[
+                ("param_x", "1"),
+                ("param_s", "it's\\tok"),
+            ])
+            .await;
+        assert_ok!(status, body);
+        assert_eq!(&body, "2\tit's\\tok\t{x:UInt64}\n");
+    }
+
+    {
+        let (status, body) = server
+            .get_with_params("select {x:Nullable(Int64)}", &[("param_x", "\\N")])
+            .await;
+        assert_ok!(status, body);
+        assert_eq!(&body, "NULL\n");
+    }
+
+    {
+        let (status, body) = server.get("select {x:UInt64}").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_error!(body, "param_x");
+    }
+
+    {
+        let (status, body) = server
+            .get_with_params("select {x:UInt64}", &[("param_x", "1 or 1=1")])
+            .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_error!(body, "Invalid value of parameter x");
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_database_and_settings() -> PoemResult<()> {
+    let server = Server::new();
+
+    {
+        let (status, body) = server.post("create database db1", "").await;
+        assert_ok!(status, body);
+    }
+
+    {
+        let (status, body) = server
+            .get_with_params("select database()", &[("database", "db1")])
+            .await;
+        assert_ok!(status, body);
+        assert_eq!(&body, "db1\n");
+    }
+
+    {
+        let (status, body) = server
+            .get_with_params("select database()", &[("database", "db2")])
+            .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_error!(body, "db2");
+    }
+
+    {
+        let (status, body) = server
+            .get_with_params(
+                "select value from system.settings where name = 'max_block_size'",
+                &[("max_block_size", "1000"), ("compress", "0")],
+            )
+            .await;
+        assert_ok!(status, body);
+        assert_eq!(&body, "1000\n");
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_response_headers() -> PoemResult<()> {
+    let server = Server::new();
+
+    let req = QueryBuilder::new("select number from numbers(3)")
+        .param("query_id", "test-query-id")
+        .param("default_format", "CSV")
+        .param("wait_end_of_query", "1")
+        .param("send_progress_in_http_headers", "1")
+        .build();
+    let response = server.endpoint.get_response(req).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let header = |name: &str| {
+        response
+            .headers()
+            .get(name)
+            .map(|v| v.to_str().unwrap().to_string())
+            .unwrap_or_default()
+    };
+    assert_eq!(header("X-ClickHouse-Query-Id"), "test-query-id");
+    assert_eq!(header("X-ClickHouse-Format"), "CSV");
+    assert!(header("Content-Type").starts_with("text/csv"));
+
+    let summary: serde_json::Value = serde_json::from_str(&header("X-ClickHouse-Summary")).unwrap();
+    assert_eq!(summary["read_rows"], "3");
+    let progress: serde_json::Value =
+        serde_json::from_str(&header("X-ClickHouse-Progress")).unwrap();
+    assert_eq!(progress["read_rows"], "3");
+
+    let body = response.into_body().into_string().await.unwrap();
+    assert_eq!(&body, "0\n1\n2\n");
+
+    // The summary of a streaming response is unknown when the headers are sent.
+    let req = QueryBuilder::new("select number from numbers(3)").build();
+    let response = server.endpoint.get_response(req).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().get("X-ClickHouse-Summary").is_none());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_clickhouse_credentials() -> PoemResult<()> {
+    let server = Server::new();
+    let sql = "select 1";
+
+    {
+        let req = QueryBuilder::new(sql)
+            .without_basic_auth()
+            .param("user", "root")
+            .param("password", "")
+            .build();
+        let (status, body) = server.get_response(req).await;
+        assert_ok!(status, body);
+        assert_eq!(&body, "1\n");
+    }
+
+    {
+        let req = QueryBuilder::new(sql)
+            .without_basic_auth()
+            .header("X-ClickHouse-User", "root")
+            .header("X-ClickHouse-Key", "")
+            .build();
+        let (status, body) = server.get_response(req).await;
+        assert_ok!(status, body);
+        assert_eq!(&body, "1\n");
+    }
+
+    {
+        let req = QueryBuilder::new(sql)
+            .without_basic_auth()
+            .param("user", "unknown")
+            .build();
+        let (status, _) = server.get_response(req).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    {
+        let req = QueryBuilder::new(sql).without_basic_auth().build();
+        let (status, _) = server.get_response(req).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+    Ok(())
+}
+
 struct QueryBuilder {
     sql: String,
+    params: Vec<(String, String)>,
+    headers: Vec<(String, String)>,
+    basic_auth: bool,
     body: Option<Body>,
 }
 
@@ -212,10 +483,30 @@ impl QueryBuilder {
     pub fn new(sql: &str) -> Self {
         QueryBuilder {
             sql: sql.to_string(),
+            params: vec![],
+            headers: vec![],
+            basic_auth: true,
             body: None,
         }
     }
 
+    pub fn header(mut self, key: &str, value: &str) -> Self {
+        self.headers.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn without_basic_auth(self) -> Self {
+        Self {
+            basic_auth: false,
+            ..self
+        }
+    }
+
+    pub fn param(mut self, key: &str, value: &str) -> Self {
+        self.params.push((key.to_string(), value.to_string()));
+        self
+    }
+
     pub fn body(self, body: impl Into<Body>) -> Self {
         Self {
             body: Some(body.into()),
@@ -226,6 +517,7 @@ impl QueryBuilder {
     pub fn build(self) -> Request {
         let uri = url::form_urlencoded::Serializer::new(String::new())
             .append_pair("query", &self.sql)
+            .extend_pairs(&self.params)
             .finish();
         let uri = "/?".to_string() + &uri;
         let uri = uri.parse::<Uri>().unwrap();
@@ -234,12 +526,14 @@ impl QueryBuilder {
             Some(body) => (Method::POST, body),
         };
 
-        let basic = Authorization::basic("root", "");
-        Request::builder()
-            .uri(uri)
-            .method(method)
-            .typed_header(basic)
-            .body(body)
+        let mut builder = Request::builder().uri(uri).method(method);
+        if self.basic_auth {
+            builder = builder.typed_header(Authorization::basic("root", ""));
+        }
+        for (key, value) in &self.headers {
+            builder = builder.header(key.as_str(), value.as_str());
+        }
+        builder.body(body)
     }
 }
 
@@ -267,6 +561,19 @@ impl Server {
         self.get_response(QueryBuilder::new(sql).build()).await
     }
 
+    pub async fn get_with_params(
+        &self,
+        sql: &str,
+        params: &[(&str, &str)],
+    ) -> (StatusCode, String) {
+        let builder = params
+            .iter()
+            .fold(QueryBuilder::new(sql), |builder, (key, value)| {
+                builder.param(key, value)
+            });
+        self.get_response(builder.build()).await
+    }
+
     pub async fn post(&self, sql: &str, body: &str) -> (StatusCode, String) {
         self.get_response(QueryBuilder::new(sql).body(body.to_string()).build())
             .await
@@ -79,3 +79,27 @@ fn test_data_block_nullable() -> Result<()> {
 fn test_data_block_not_nullable() -> Result<()> {
     test_data_block(false)
 }
+
+#[test]
+fn test_data_block_null() -> Result<()> {
+    let schema = DataSchemaRefExt::create(vec![
+        DataField::new_nullable("c1", i32::to_data_type()),
+        DataField::new("c2", Vu8::to_data_type()),
+    ]);
+
+    let mut validity = MutableBitmap::new();
+    validity.push(true);
+    validity.push(false);
+    validity.push(true);
+    let block = DataBlock::create(schema, vec![
+        NullableColumn::wrap_inner(Series::from_data(vec![1, 2, 3]), Some(validity.into())),
+        Series::from_data(vec!["a", "b", "c"]),
+    ]);
+
+    let format = FormatSettings::default();
+    let tsv_block = String::from_utf8(block_to_tsv(&block, &format)?)?;
+    let expect = "1\ta\n\\N\tb\n3\tc\n";
+
+    assert_eq!(&tsv_block, expect);
+    Ok(())
+}
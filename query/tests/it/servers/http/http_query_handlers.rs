@@ -18,6 +18,9 @@ use std::time::Duration;
 
 use base64::encode_config;
 use base64::URL_SAFE_NO_PAD;
+use common_arrow::arrow::io::ipc::read::read_stream_metadata;
+use common_arrow::arrow::io::ipc::read::StreamReader;
+use common_arrow::arrow::io::ipc::read::StreamState;
 use common_base::base::get_free_tcp_port;
 use common_base::base::tokio;
 use common_exception::ErrorCode;
@@ -28,11 +31,16 @@ use databend_query::servers::http::middleware::HTTPSessionEndpoint;
 use databend_query::servers::http::middleware::HTTPSessionMiddleware;
 use databend_query::servers::http::v1::make_final_uri;
 use databend_query::servers::http::v1::make_page_uri;
+use databend_query::servers::http::v1::make_progress_uri;
 use databend_query::servers::http::v1::make_state_uri;
+use databend_query::servers::http::v1::make_stream_uri;
 use databend_query::servers::http::v1::query_route;
 use databend_query::servers::http::v1::ExecuteStateKind;
 use databend_query::servers::http::v1::HttpSession;
+use databend_query::servers::http::v1::QueryProgress;
 use databend_query::servers::http::v1::QueryResponse;
+use databend_query::servers::http::v1::StreamFormat;
+use databend_query::servers::http::v1::HEADER_QUERY_ID;
 use databend_query::servers::HttpHandler;
 use databend_query::users::auth::jwt::CustomClaims;
 use databend_query::users::auth::jwt::EnsureUser;
@@ -362,6 +370,199 @@ async fn test_multi_page() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_stream_ndjson() -> Result<()> {
+    let ep = create_endpoint();
+    let json =
+        serde_json::json!({"sql": "select number from numbers(3)", "stream_format": "ndjson"});
+    let response = post_json_to_endpoint_raw(&ep, &json).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get(header::CONTENT_TYPE).unwrap(),
+        StreamFormat::NDJson.content_type()
+    );
+    assert!(response.headers().contains_key(HEADER_QUERY_ID));
+
+    let lines = read_ndjson(response).await;
+    assert_eq!(lines[0]["schema"]["fields"][0]["name"], "number");
+    assert_eq!(lines.iter().filter(|line| line.is_array()).count(), 3);
+
+    let progress: QueryProgress = serde_json::from_value(lines.last().unwrap().clone())?;
+    assert_eq!(progress.state, ExecuteStateKind::Succeeded);
+    assert!(progress.error.is_none());
+    assert!(progress.stats.scan_progress.is_some());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_stream_resume() -> Result<()> {
+    let ep = create_endpoint();
+    let total_rows = 25000;
+    let sql = format!("select * from numbers({})", total_rows);
+    let json = serde_json::json!({"sql": sql,  "pagination": {"wait_time_secs": 3}});
+    let (status, result) = post_json_to_endpoint(&ep, &json).await?;
+    assert_eq!(status, StatusCode::OK);
+    let query_id = result.id;
+    assert!(!result.data.is_empty());
+
+    // the received page can be streamed again, e.g. after a disconnection.
+    let uri = format!("{}?format=ndjson&page_token=0", make_stream_uri(&query_id));
+    let response = get_uri(&ep, &uri).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let lines = read_ndjson(response).await;
+    let rows = lines.iter().filter(|line| line.is_array()).count();
+    assert_eq!(rows, total_rows);
+    let tokens = lines
+        .iter()
+        .filter_map(|line| line["next_page_token"].as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(tokens[0], "1");
+    assert!(tokens.len() > 1);
+
+    // the stream can be resumed from an earlier token than the last received one.
+    let first_page_rows = lines
+        .iter()
+        .take_while(|line| line["next_page_token"].is_null())
+        .filter(|line| line.is_array())
+        .count();
+    let uri = format!("{}?page_token=1", make_stream_uri(&query_id));
+    let response = get_uri(&ep, &uri).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let lines = read_ndjson(response).await;
+    let rows = lines.iter().filter(|line| line.is_array()).count();
+    assert_eq!(rows, total_rows - first_page_rows);
+
+    // the pages before the requested token are acknowledged and dropped.
+    let uri = format!("{}?page_token=0", make_stream_uri(&query_id));
+    let response = get_uri(&ep, &uri).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let uri = format!("{}?page_token=x", make_stream_uri(&query_id));
+    let response = get_uri(&ep, &uri).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_stream_kept_pages() -> Result<()> {
+    let session_manager = SessionManagerBuilder::create().build().unwrap();
+    let ep = Route::new()
+        .nest("/v1/query", query_route())
+        .with(HTTPSessionMiddleware {
+            session_manager: session_manager.clone(),
+        });
+
+    let total_rows = 100000;
+    let sql = format!("select * from numbers({})", total_rows);
+    let json = serde_json::json!({"sql": sql, "stream_format": "ndjson"});
+    let response = post_json_to_endpoint_raw(&ep, &json).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let query_id = response
+        .headers()
+        .get(HEADER_QUERY_ID)
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_string();
+    let lines = read_ndjson(response).await;
+    assert_eq!(
+        lines.iter().filter(|line| line.is_array()).count(),
+        total_rows
+    );
+    let tokens = lines
+        .iter()
+        .filter_map(|line| line["next_page_token"].as_str())
+        .collect::<Vec<_>>();
+    assert!(tokens.len() > 2);
+
+    // only the last pages of the stream are kept.
+    let query = session_manager
+        .get_http_query_manager()
+        .get_query(&query_id)
+        .await
+        .unwrap();
+    assert!(query.num_kept_pages().await <= 2);
+
+    // the stream is resumed from the last tokens, the earlier pages are dropped.
+    let token = tokens[tokens.len() - 2];
+    let uri = format!("{}?page_token={}", make_stream_uri(&query_id), token);
+    assert_eq!(get_uri(&ep, &uri).await.status(), StatusCode::OK);
+    let uri = format!("{}?page_token=0", make_stream_uri(&query_id));
+    assert_eq!(get_uri(&ep, &uri).await.status(), StatusCode::NOT_FOUND);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_stream_arrow() -> Result<()> {
+    let ep = create_endpoint();
+    let json =
+        serde_json::json!({"sql": "select number, 'a' from numbers(5)", "stream_format": "arrow"});
+    let response = post_json_to_endpoint_raw(&ep, &json).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get(header::CONTENT_TYPE).unwrap(),
+        StreamFormat::Arrow.content_type()
+    );
+
+    let body = response.into_body().into_vec().await.unwrap();
+    let mut reader = std::io::Cursor::new(body);
+    let metadata = read_stream_metadata(&mut reader)?;
+    assert_eq!(metadata.schema.fields.len(), 2);
+    let mut rows = 0;
+    for state in StreamReader::new(reader, metadata) {
+        if let StreamState::Some(chunk) = state? {
+            rows += chunk.len();
+        }
+    }
+    assert_eq!(rows, 5);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_progress_events() -> Result<()> {
+    let ep = create_endpoint();
+    let sql = "select sleep(0.1)";
+    let json = serde_json::json!({"sql": sql.to_string(), "pagination": {"wait_time_secs": 0}});
+    let (status, result) = post_json_to_endpoint(&ep, &json).await?;
+    assert_eq!(status, StatusCode::OK);
+
+    let uri = format!("{}?interval_ms=10", make_progress_uri(&result.id));
+    let response = get_uri(&ep, &uri).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get(header::CONTENT_TYPE).unwrap(),
+        "text/event-stream"
+    );
+
+    let body = response.into_body().into_string().await.unwrap();
+    let events = body
+        .split("\n\n")
+        .filter(|event| !event.is_empty())
+        .map(|event| {
+            let data = event.strip_prefix("event: progress\ndata: ").unwrap();
+            serde_json::from_str::<QueryProgress>(data).unwrap()
+        })
+        .collect::<Vec<_>>();
+    assert!(!events.is_empty());
+    let last = events.last().unwrap();
+    assert_eq!(last.state, ExecuteStateKind::Succeeded);
+    assert!(events[..events.len() - 1]
+        .iter()
+        .all(|event| event.state == ExecuteStateKind::Running));
+
+    let response = get_uri(&ep, &make_progress_uri("not-exists")).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    Ok(())
+}
+
+async fn read_ndjson(response: Response) -> Vec<serde_json::Value> {
+    let body = response.into_body().into_string().await.unwrap();
+    body.lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
 #[tokio::test]
 async fn test_insert() -> Result<()> {
     let route = create_endpoint();
@@ -542,6 +743,14 @@ async fn post_json_to_endpoint(
     ep: &EndpointType,
     json: &serde_json::Value,
 ) -> Result<(StatusCode, QueryResponse)> {
+    let response = post_json_to_endpoint_raw(ep, json).await?;
+    check_response(response).await
+}
+
+async fn post_json_to_endpoint_raw(
+    ep: &EndpointType,
+    json: &serde_json::Value,
+) -> Result<Response> {
     let uri = "/v1/query";
     let content_type = "application/json";
     let body = serde_json::to_vec(&json)?;
@@ -553,12 +762,9 @@ async fn post_json_to_endpoint(
         .header(header::CONTENT_TYPE, content_type)
         .typed_header(basic)
         .body(body);
-    let response = ep
-        .call(req)
+    ep.call(req)
         .await
-        .map_err(|e| ErrorCode::UnexpectedError(e.to_string()))?;
-
-    check_response(response).await
+        .map_err(|e| ErrorCode::UnexpectedError(e.to_string()))
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
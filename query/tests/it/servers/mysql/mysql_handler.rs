@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -24,10 +25,15 @@ use databend_query::servers::MySQLHandler;
 use mysql_async::prelude::FromRow;
 use mysql_async::prelude::Queryable;
 use mysql_async::FromRowError;
+use mysql_async::OptsBuilder;
 use mysql_async::Row;
+use mysql_async::SslOpts;
 use tokio::sync::Barrier;
 use tokio::task::JoinHandle;
 
+use crate::tests::tls_constants::TEST_CA_CERT;
+use crate::tests::tls_constants::TEST_SERVER_CERT;
+use crate::tests::tls_constants::TEST_SERVER_KEY;
 use crate::tests::SessionManagerBuilder;
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
@@ -45,6 +51,41 @@ async fn test_generic_code_with_on_query() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_prepared_statement() -> Result<()> {
+    let mut handler =
+        MySQLHandler::create(SessionManagerBuilder::create().max_sessions(1).build()?);
+
+    let listening = "127.0.0.1:0".parse::<SocketAddr>()?;
+    let runnable_server = handler.start(listening).await?;
+    let mut connection = create_connection(runnable_server.port()).await?;
+
+    let statement = connection
+        .prep("SELECT ? + 1, ?")
+        .await
+        .map_err_to_code(ErrorCode::UnknownException, || "Prepare failed")?;
+    assert_eq!(statement.num_params(), 2);
+
+    let rows: Vec<(i64, String)> = connection
+        .exec(&statement, (41, "it's a test"))
+        .await
+        .map_err_to_code(ErrorCode::UnknownException, || "Execute failed")?;
+    assert_eq!(rows, vec![(42, "it's a test".to_string())]);
+
+    let rows: Vec<(i64, String)> = connection
+        .exec(&statement, (-2, "?"))
+        .await
+        .map_err_to_code(ErrorCode::UnknownException, || "Execute failed")?;
+    assert_eq!(rows, vec![(-1, "?".to_string())]);
+
+    connection
+        .close(statement)
+        .await
+        .map_err_to_code(ErrorCode::UnknownException, || "Close failed")?;
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_rejected_session_with_sequence() -> Result<()> {
     let mut handler =
@@ -77,6 +118,65 @@ async fn test_rejected_session_with_sequence() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_mysql_handler_tls() -> Result<()> {
+    let mut handler = MySQLHandler::create(
+        SessionManagerBuilder::create()
+            .max_sessions(2)
+            .mysql_handler_tls_server_cert(TEST_SERVER_CERT)
+            .mysql_handler_tls_server_key(TEST_SERVER_KEY)
+            .build()?,
+    );
+
+    let listening = "127.0.0.1:0".parse::<SocketAddr>()?;
+    let runnable_server = handler.start(listening).await?;
+
+    // Plaintext connections are refused.
+    match create_connection(runnable_server.port()).await {
+        Ok(_) => panic!("Expected rejected connection"),
+        Err(error) => assert!(error.message().contains("insecure transport")),
+    };
+
+    let mut connection = create_tls_connection(runnable_server.port()).await?;
+
+    let rows: Vec<(i64, String)> = connection
+        .query("SELECT 1, 'tls'")
+        .await
+        .map_err_to_code(ErrorCode::UnknownException, || "Query failed")?;
+    assert_eq!(rows, vec![(1, "tls".to_string())]);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_rejected_session_with_tls() -> Result<()> {
+    let mut handler = MySQLHandler::create(
+        SessionManagerBuilder::create()
+            .max_sessions(1)
+            .mysql_handler_tls_server_cert(TEST_SERVER_CERT)
+            .mysql_handler_tls_server_key(TEST_SERVER_KEY)
+            .build()?,
+    );
+
+    let listening = "127.0.0.1:0".parse::<SocketAddr>()?;
+    let listening = handler.start(listening).await?;
+
+    // Accepted connection
+    let _conn = create_tls_connection(listening.port()).await?;
+
+    // Rejected connection, the error is sent over TLS
+    match create_tls_connection(listening.port()).await {
+        Ok(_) => panic!("Expected rejected connection"),
+        Err(error) => {
+            assert!(error
+                .message()
+                .contains("The current accept connection has exceeded mysql_handler_thread_num"));
+        }
+    };
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_rejected_session_with_parallel() -> Result<()> {
     enum CreateServerResult {
@@ -147,6 +247,15 @@ async fn create_connection(port: u16) -> Result<mysql_async::Conn> {
         .map_err_to_code(ErrorCode::UnknownException, || "Reject connection")
 }
 
+async fn create_tls_connection(port: u16) -> Result<mysql_async::Conn> {
+    let uri = &format!("mysql://127.0.0.1:{}", port);
+    let ssl_opts = SslOpts::default().with_root_cert_path(Some(PathBuf::from(TEST_CA_CERT)));
+    let opts = OptsBuilder::from_opts(mysql_async::Opts::from_url(uri).unwrap()).ssl_opts(ssl_opts);
+    mysql_async::Conn::new(opts)
+        .await
+        .map_err_to_code(ErrorCode::UnknownException, || "TLS connection failed")
+}
+
 struct EmptyRow;
 
 impl FromRow for EmptyRow {
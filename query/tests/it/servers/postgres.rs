@@ -0,0 +1,180 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::net::SocketAddr;
+
+use common_base::base::tokio;
+use common_base::base::tokio::io::AsyncReadExt;
+use common_base::base::tokio::io::AsyncWriteExt;
+use common_base::base::tokio::net::TcpStream;
+use common_exception::Result;
+use databend_query::servers::PostgresHandler;
+
+use crate::tests::SessionManagerBuilder;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_postgres_simple_query() -> Result<()> {
+    let mut handler =
+        PostgresHandler::create(SessionManagerBuilder::create().max_sessions(1).build()?);
+
+    let listening = "127.0.0.1:0".parse::<SocketAddr>()?;
+    let runnable_server = handler.start(listening).await?;
+    let mut stream = create_connection(runnable_server.port()).await?;
+
+    send_message(&mut stream, b'Q', &cstring("SELECT 1, 'it''s'; SELECT 2")).await?;
+    let messages = read_until_ready(&mut stream).await?;
+    let tags = messages.iter().map(|(tag, _)| *tag).collect::<Vec<_>>();
+    assert_eq!(tags, b"TDCTDCZ".to_vec());
+    assert_eq!(data_row(&messages[1].1), vec![
+        Some("1".to_string()),
+        Some("it's".to_string())
+    ]);
+    assert_eq!(messages[2].1, cstring("SELECT 1"));
+    assert_eq!(data_row(&messages[4].1), vec![Some("2".to_string())]);
+
+    send_message(&mut stream, b'Q', &cstring("SELECT * FROM t_not_exists")).await?;
+    let messages = read_until_ready(&mut stream).await?;
+    let tags = messages.iter().map(|(tag, _)| *tag).collect::<Vec<_>>();
+    assert_eq!(tags, b"EZ".to_vec());
+
+    // The statements can't be rolled back.
+    send_message(&mut stream, b'Q', &cstring("ROLLBACK")).await?;
+    let messages = read_until_ready(&mut stream).await?;
+    let tags = messages.iter().map(|(tag, _)| *tag).collect::<Vec<_>>();
+    assert_eq!(tags, b"EZ".to_vec());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_postgres_extended_query() -> Result<()> {
+    let mut handler =
+        PostgresHandler::create(SessionManagerBuilder::create().max_sessions(1).build()?);
+
+    let listening = "127.0.0.1:0".parse::<SocketAddr>()?;
+    let runnable_server = handler.start(listening).await?;
+    let mut stream = create_connection(runnable_server.port()).await?;
+
+    // Parse: unnamed statement with an `int4` and an unspecified parameter.
+    let mut parse = cstring("");
+    parse.extend(cstring("SELECT $1 + 1, $2, $2"));
+    parse.extend(2i16.to_be_bytes());
+    parse.extend(23i32.to_be_bytes());
+    parse.extend(0i32.to_be_bytes());
+    send_message(&mut stream, b'P', &parse).await?;
+
+    // Bind: text parameters `41` and `NULL`.
+    let mut bind = cstring("");
+    bind.extend(cstring(""));
+    bind.extend(0i16.to_be_bytes());
+    bind.extend(2i16.to_be_bytes());
+    bind.extend(2i32.to_be_bytes());
+    bind.extend(b"41");
+    bind.extend((-1i32).to_be_bytes());
+    bind.extend(0i16.to_be_bytes());
+    send_message(&mut stream, b'B', &bind).await?;
+
+    let mut describe = vec![b'P'];
+    describe.extend(cstring(""));
+    send_message(&mut stream, b'D', &describe).await?;
+
+    let mut execute = cstring("");
+    execute.extend(0i32.to_be_bytes());
+    send_message(&mut stream, b'E', &execute).await?;
+    send_message(&mut stream, b'S', &[]).await?;
+
+    let messages = read_until_ready(&mut stream).await?;
+    let tags = messages.iter().map(|(tag, _)| *tag).collect::<Vec<_>>();
+    assert_eq!(tags, b"12TDCZ".to_vec());
+    assert_eq!(data_row(&messages[3].1), vec![
+        Some("42".to_string()),
+        None,
+        None
+    ]);
+    assert_eq!(messages[4].1, cstring("SELECT 1"));
+
+    // Bind a statement which does not exist, the messages are skipped until `Sync`.
+    let mut bind = cstring("");
+    bind.extend(cstring("s_not_exists"));
+    bind.extend([0; 6]);
+    send_message(&mut stream, b'B', &bind).await?;
+    send_message(&mut stream, b'E', &execute).await?;
+    send_message(&mut stream, b'S', &[]).await?;
+
+    let messages = read_until_ready(&mut stream).await?;
+    let tags = messages.iter().map(|(tag, _)| *tag).collect::<Vec<_>>();
+    assert_eq!(tags, b"EZ".to_vec());
+
+    Ok(())
+}
+
+async fn create_connection(port: u16) -> Result<TcpStream> {
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).await?;
+
+    let mut startup = 196608i32.to_be_bytes().to_vec();
+    for value in ["user", "root", "database", "default", ""] {
+        startup.extend(cstring(value));
+    }
+    stream.write_i32(startup.len() as i32 + 4).await?;
+    stream.write_all(&startup).await?;
+
+    let messages = read_until_ready(&mut stream).await?;
+    assert_eq!(messages[0], (b'R', 0i32.to_be_bytes().to_vec()));
+    Ok(stream)
+}
+
+async fn send_message(stream: &mut TcpStream, tag: u8, body: &[u8]) -> Result<()> {
+    stream.write_u8(tag).await?;
+    stream.write_i32(body.len() as i32 + 4).await?;
+    stream.write_all(body).await?;
+    Ok(())
+}
+
+async fn read_until_ready(stream: &mut TcpStream) -> Result<Vec<(u8, Vec<u8>)>> {
+    let mut messages = vec![];
+    loop {
+        let tag = stream.read_u8().await?;
+        let len = stream.read_i32().await?;
+        let mut body = vec![0; len as usize - 4];
+        stream.read_exact(&mut body).await?;
+        messages.push((tag, body));
+        if tag == b'Z' {
+            return Ok(messages);
+        }
+    }
+}
+
+fn data_row(body: &[u8]) -> Vec<Option<String>> {
+    let num_values = i16::from_be_bytes([body[0], body[1]]);
+    let mut position = 2;
+    let mut values = vec![];
+    for _ in 0..num_values {
+        let len = i32::from_be_bytes(body[position..position + 4].try_into().unwrap());
+        position += 4;
+        if len < 0 {
+            values.push(None);
+        } else {
+            let value = &body[position..position + len as usize];
+            values.push(Some(String::from_utf8(value.to_vec()).unwrap()));
+            position += len as usize;
+        }
+    }
+    values
+}
+
+fn cstring(value: &str) -> Vec<u8> {
+    let mut bytes = value.as_bytes().to_vec();
+    bytes.push(0);
+    bytes
+}
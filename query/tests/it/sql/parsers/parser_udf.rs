@@ -16,7 +16,10 @@ use common_exception::Result;
 use databend_query::sql::statements::DfAlterUDF;
 use databend_query::sql::statements::DfCreateUDF;
 use databend_query::sql::statements::DfDropUDF;
+use databend_query::sql::statements::DfUDFLanguage;
+use databend_query::sql::statements::DfUDFReturnType;
 use databend_query::sql::*;
+use sqlparser::ast::DataType;
 
 use crate::sql::sql_parser::*;
 
@@ -63,6 +66,9 @@ fn test_create_udf() -> Result<()> {
             if_not_exists: false,
             udf_name: "test_udf".to_string(),
             parameters: vec!["p".to_string()],
+            parameter_types: vec![],
+            return_type: DfUDFReturnType::Untyped,
+            language: DfUDFLanguage::Sql,
             definition: "not(is_not_null(p))".to_string(),
             description: "".to_string(),
         }),
@@ -74,6 +80,9 @@ fn test_create_udf() -> Result<()> {
             if_not_exists: false,
             udf_name: "test_udf".to_string(),
             parameters: vec!["p".to_string(), "d".to_string()],
+            parameter_types: vec![],
+            return_type: DfUDFReturnType::Untyped,
+            language: DfUDFLanguage::Sql,
             definition: "not(is_not_null(p,d))".to_string(),
             description: "".to_string(),
         }),
@@ -95,6 +104,9 @@ fn test_create_udf() -> Result<()> {
             if_not_exists: false,
             udf_name: "test_udf".to_string(),
             parameters: vec!["p".to_string(), "d".to_string()],
+            parameter_types: vec![],
+            return_type: DfUDFReturnType::Untyped,
+            language: DfUDFLanguage::Sql,
             definition: "not(is_not_null(p,d))".to_string(),
             description: "this is a description".to_string(),
         }),
@@ -106,6 +118,9 @@ fn test_create_udf() -> Result<()> {
             if_not_exists: false,
             udf_name: "test_udf".to_string(),
             parameters: vec!["p".to_string(), "d".to_string()],
+            parameter_types: vec![],
+            return_type: DfUDFReturnType::Untyped,
+            language: DfUDFLanguage::Sql,
             definition: "not(is_not_null(p,d))".to_string(),
             description: "this is a description".to_string(),
         }),
@@ -114,6 +129,82 @@ fn test_create_udf() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_create_typed_udf() -> Result<()> {
+    expect_parse_ok(
+        "CREATE FUNCTION add_one(a INT) RETURNS BIGINT AS a + 1",
+        DfStatement::CreateUDF(DfCreateUDF {
+            if_not_exists: false,
+            udf_name: "add_one".to_string(),
+            parameters: vec!["a".to_string()],
+            parameter_types: vec![DataType::Int(None)],
+            return_type: DfUDFReturnType::Scalar(DataType::BigInt(None)),
+            language: DfUDFLanguage::Sql,
+            definition: "a + 1".to_string(),
+            description: "".to_string(),
+        }),
+    )?;
+
+    expect_parse_ok(
+        "CREATE FUNCTION IF NOT EXISTS one() RETURNS INT AS 1 DESC = 'constant'",
+        DfStatement::CreateUDF(DfCreateUDF {
+            if_not_exists: true,
+            udf_name: "one".to_string(),
+            parameters: vec![],
+            parameter_types: vec![],
+            return_type: DfUDFReturnType::Scalar(DataType::Int(None)),
+            language: DfUDFLanguage::Sql,
+            definition: "1".to_string(),
+            description: "constant".to_string(),
+        }),
+    )?;
+
+    expect_parse_ok(
+        "CREATE FUNCTION evens(n BIGINT) RETURNS TABLE (x BIGINT) AS SELECT number FROM numbers(n) WHERE number % 2 = 0",
+        DfStatement::CreateUDF(DfCreateUDF {
+            if_not_exists: false,
+            udf_name: "evens".to_string(),
+            parameters: vec!["n".to_string()],
+            parameter_types: vec![DataType::BigInt(None)],
+            return_type: DfUDFReturnType::Table(vec![("x".to_string(), DataType::BigInt(None))]),
+            language: DfUDFLanguage::Sql,
+            definition: "SELECT number FROM numbers(n) WHERE number % 2 = 0".to_string(),
+            description: "".to_string(),
+        }),
+    )?;
+
+    expect_parse_ok(
+        "CREATE FUNCTION parse_ua(ua VARCHAR) RETURNS VARCHAR LANGUAGE wasm AS 'AGFzbQEAAAA='",
+        DfStatement::CreateUDF(DfCreateUDF {
+            if_not_exists: false,
+            udf_name: "parse_ua".to_string(),
+            parameters: vec!["ua".to_string()],
+            parameter_types: vec![DataType::Varchar(None)],
+            return_type: DfUDFReturnType::Scalar(DataType::Varchar(None)),
+            language: DfUDFLanguage::Wasm,
+            definition: "AGFzbQEAAAA=".to_string(),
+            description: "".to_string(),
+        }),
+    )?;
+
+    expect_parse_err_contains(
+        "CREATE FUNCTION parse_ua(ua VARCHAR) RETURNS VARCHAR LANGUAGE python AS 'x'",
+        "Unsupported function language: python, expected SQL or WASM".to_string(),
+    )?;
+
+    expect_parse_err_contains(
+        "CREATE FUNCTION add_one(a INT, a INT) RETURNS INT AS a + 1",
+        "Duplicate parameter is not allowed, keep only one: a".to_string(),
+    )?;
+
+    expect_parse_err_contains(
+        "CREATE FUNCTION add_one(a INT) AS a + 1",
+        "Expected RETURNS or AS, found: AS".to_string(),
+    )?;
+
+    Ok(())
+}
+
 #[test]
 fn test_drop_udf() -> Result<()> {
     expect_parse_ok(
@@ -121,6 +212,7 @@ fn test_drop_udf() -> Result<()> {
         DfStatement::DropUDF(DfDropUDF {
             if_exists: false,
             udf_name: "test_udf".to_string(),
+            parameter_types: None,
         }),
     )?;
 
@@ -129,6 +221,25 @@ fn test_drop_udf() -> Result<()> {
         DfStatement::DropUDF(DfDropUDF {
             if_exists: true,
             udf_name: "test_udf".to_string(),
+            parameter_types: None,
+        }),
+    )?;
+
+    expect_parse_ok(
+        "DROP FUNCTION add_one(INT)",
+        DfStatement::DropUDF(DfDropUDF {
+            if_exists: false,
+            udf_name: "add_one".to_string(),
+            parameter_types: Some(vec![DataType::Int(None)]),
+        }),
+    )?;
+
+    expect_parse_ok(
+        "DROP FUNCTION one()",
+        DfStatement::DropUDF(DfDropUDF {
+            if_exists: false,
+            udf_name: "one".to_string(),
+            parameter_types: Some(vec![]),
         }),
     )?;
 
@@ -177,6 +288,9 @@ fn test_alter_udf() -> Result<()> {
         DfStatement::AlterUDF(DfAlterUDF {
             udf_name: "test_udf".to_string(),
             parameters: vec!["p".to_string()],
+            parameter_types: vec![],
+            return_type: DfUDFReturnType::Untyped,
+            language: DfUDFLanguage::Sql,
             definition: "not(is_not_null(p))".to_string(),
             description: "".to_string(),
         }),
@@ -187,6 +301,9 @@ fn test_alter_udf() -> Result<()> {
         DfStatement::AlterUDF(DfAlterUDF {
             udf_name: "test_udf".to_string(),
             parameters: vec!["p".to_string(), "d".to_string()],
+            parameter_types: vec![],
+            return_type: DfUDFReturnType::Untyped,
+            language: DfUDFLanguage::Sql,
             definition: "not(is_not_null(p,d))".to_string(),
             description: "".to_string(),
         }),
@@ -207,6 +324,9 @@ fn test_alter_udf() -> Result<()> {
         DfStatement::AlterUDF(DfAlterUDF {
             udf_name: "test_udf".to_string(),
             parameters: vec!["p".to_string(), "d".to_string()],
+            parameter_types: vec![],
+            return_type: DfUDFReturnType::Untyped,
+            language: DfUDFLanguage::Sql,
             definition: "not(is_not_null(p,d))".to_string(),
             description: "this is a description".to_string(),
         }),
@@ -0,0 +1,78 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_exception::Result;
+use databend_query::sessions::SessionType;
+use databend_query::sql::statements::DfRefreshMaterializedView;
+use databend_query::sql::*;
+use sqlparser::ast::*;
+
+use crate::sql::sql_parser::*;
+
+#[test]
+fn create_materialized_view() -> Result<()> {
+    let sql =
+        "CREATE MATERIALIZED VIEW IF NOT EXISTS db1.mv1 AS SELECT a, sum(b) FROM t1 GROUP BY a";
+    let (statements, _) = DfParser::parse_sql(sql, SessionType::Test)?;
+    assert_eq!(statements.len(), 1);
+    match &statements[0] {
+        DfStatement::CreateMaterializedView(create) => {
+            assert!(create.if_not_exists);
+            assert_eq!(
+                create.name,
+                ObjectName(vec![Ident::new("db1"), Ident::new("mv1")])
+            );
+            assert_eq!(
+                create.subquery.to_string(),
+                "SELECT a, sum(b) FROM t1 GROUP BY a"
+            );
+        }
+        other => panic!("unexpected statement {:?}", other),
+    }
+
+    expect_synonym_parse_eq(
+        "create materialized view mv1 as select a from t1",
+        "CREATE MATERIALIZED VIEW mv1 AS SELECT a FROM t1",
+    )?;
+    expect_parse_err(
+        "CREATE MATERIALIZED VIEW mv1 SELECT a FROM t1",
+        "sql parser error: need `AS` after MATERIALIZED VIEW NAME".to_string(),
+    )?;
+    expect_parse_err_contains(
+        "CREATE MATERIALIZED mv1 AS SELECT a FROM t1",
+        "Expected VIEW, found: mv1".to_string(),
+    )?;
+    Ok(())
+}
+
+#[test]
+fn refresh_materialized_view() -> Result<()> {
+    expect_parse_ok(
+        "REFRESH MATERIALIZED VIEW db1.mv1",
+        DfStatement::RefreshMaterializedView(DfRefreshMaterializedView {
+            name: ObjectName(vec![Ident::new("db1"), Ident::new("mv1")]),
+        }),
+    )?;
+    expect_parse_ok(
+        "refresh materialized view mv1",
+        DfStatement::RefreshMaterializedView(DfRefreshMaterializedView {
+            name: ObjectName(vec![Ident::new("mv1")]),
+        }),
+    )?;
+    expect_parse_err_contains(
+        "REFRESH VIEW mv1",
+        "Expected MATERIALIZED, found: VIEW".to_string(),
+    )?;
+    Ok(())
+}
@@ -241,7 +241,13 @@ async fn test_plan_parser() -> Result<()> {
             name: "unimplemented-cte",
             sql: "with t as ( select sum(number) n from numbers_mt(1000) )select * from t",
             expect: "",
-            error: "Code: 1005, displayText = sql parser error: CTE is not yet implement.",
+            error: "Code: 1005, displayText = sql parser error: CTE is only supported by the new planner, set enable_planner_v2 = 1.",
+        },
+        Test {
+            name: "unimplemented-union",
+            sql: "select 1 union all select 2",
+            expect: "",
+            error: "Code: 1005, displayText = sql parser error: UNION is only supported by the new planner, set enable_planner_v2 = 1.",
         },
         Test {
             name: "kleene-logic-null",
@@ -0,0 +1,176 @@
+//  Copyright 2022 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+use std::time::Duration;
+use std::time::SystemTime;
+
+use common_base::base::tokio;
+use common_datablocks::DataBlock;
+use common_datavalues::prelude::*;
+use common_exception::Result;
+use databend_query::interpreters::normalize_sql;
+use databend_query::storages::fuse::cache::new_disk_cache;
+use databend_query::storages::fuse::cache::ColumnChunkCache;
+use databend_query::storages::fuse::cache::QueryResult;
+use databend_query::storages::fuse::cache::QueryResultCache;
+use databend_query::storages::fuse::cache::QueryResultStorage;
+use databend_query::storages::fuse::cache::TenantLabel;
+use tempfile::TempDir;
+
+fn tenant_label() -> TenantLabel {
+    TenantLabel {
+        tenant_id: "test_tenant".to_string(),
+        cluster_id: "test_cluster".to_string(),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_column_chunk_cache() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().to_str().unwrap();
+
+    // disabled if neither memory nor disk is configured
+    assert!(ColumnChunkCache::create(0, None, tenant_label()).is_none());
+
+    let disk = new_disk_cache(root, 1);
+    assert!(disk.is_some());
+    let cache = ColumnChunkCache::create(1, disk.clone(), tenant_label()).unwrap();
+    let key = ColumnChunkCache::key("/1/2/_b/block.parquet", 4, 3);
+    assert_eq!(key, "1/2/_b/block.parquet_4_3");
+    assert!(cache.get(&key).await.is_none());
+
+    cache.put(key.clone(), &[1, 2, 3]);
+    assert_eq!(cache.get(&key).await, Some(vec![1, 2, 3]));
+
+    // the chunk is kept on the disk in the background, and read by a cache without memory
+    cache.flush().await;
+    let disk_only = ColumnChunkCache::create(0, disk, tenant_label()).unwrap();
+    assert_eq!(disk_only.get(&key).await, Some(vec![1, 2, 3]));
+
+    // a chunk larger than the memory cache is not kept in it
+    let memory_only = ColumnChunkCache::create(1, None, tenant_label()).unwrap();
+    let large_key = ColumnChunkCache::key("1/2/_b/block.parquet", 0, 2 << 20);
+    memory_only.put(large_key.clone(), &vec![0; 2 << 20]);
+    assert!(memory_only.get(&large_key).await.is_none());
+}
+
+fn query_result(key: &str, tenant: &str) -> QueryResult {
+    let schema = DataSchemaRefExt::create(vec![DataField::new("a", i64::to_data_type())]);
+    let block = DataBlock::create(schema.clone(), vec![Series::from_data(vec![1i64, 2, 3])]);
+    QueryResult {
+        key: key.to_string(),
+        tenant: tenant.to_string(),
+        sql: "select a from t".to_string(),
+        tables: vec!["'default'.'t'@snapshot".to_string()],
+        schema,
+        blocks: vec![block],
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_query_result_cache() -> Result<()> {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().to_str().unwrap();
+
+    assert!(QueryResultCache::create(0, None).is_none());
+
+    let cache = QueryResultCache::create(1, new_disk_cache(root, 1)).unwrap();
+    let expire_time = SystemTime::now() + Duration::from_secs(60);
+    assert!(cache.get("k1").await?.is_none());
+
+    // kept in memory
+    let put = cache
+        .put(
+            query_result("k1", "t1"),
+            QueryResultStorage::Memory,
+            1 << 20,
+            expire_time,
+        )
+        .await?;
+    assert!(put);
+    let blocks = cache.get("k1").await?.unwrap();
+    assert_eq!(blocks.len(), 1);
+    assert_eq!(blocks[0].num_rows(), 3);
+
+    // kept on the disk
+    let put = cache
+        .put(
+            query_result("k2", "t1"),
+            QueryResultStorage::Disk,
+            1 << 20,
+            expire_time,
+        )
+        .await?;
+    assert!(put);
+    let blocks = cache.get("k2").await?.unwrap();
+    assert_eq!(blocks[0].num_rows(), 3);
+
+    // too large
+    let put = cache
+        .put(
+            query_result("k3", "t1"),
+            QueryResultStorage::Memory,
+            1,
+            expire_time,
+        )
+        .await?;
+    assert!(!put);
+    assert!(cache.get("k3").await?.is_none());
+
+    // expired
+    let put = cache
+        .put(
+            query_result("k4", "t1"),
+            QueryResultStorage::Memory,
+            1 << 20,
+            SystemTime::now(),
+        )
+        .await?;
+    assert!(put);
+    assert!(cache.get("k4").await?.is_none());
+
+    // listed by tenant
+    cache
+        .put(
+            query_result("k5", "t2"),
+            QueryResultStorage::Memory,
+            1 << 20,
+            expire_time,
+        )
+        .await?;
+    let mut entries = cache.list_entries("t1");
+    entries.sort_by(|a, b| a.key.cmp(&b.key));
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].key, "k1");
+    assert_eq!(entries[0].storage, QueryResultStorage::Memory);
+    assert_eq!(entries[0].result_rows, 3);
+    assert_eq!(entries[0].get_hits(), 1);
+    assert_eq!(entries[1].key, "k2");
+    assert_eq!(entries[1].storage, QueryResultStorage::Disk);
+
+    Ok(())
+}
+
+#[test]
+fn test_normalize_sql() {
+    assert_eq!(
+        normalize_sql("  select  a,\n\tb from t ;; "),
+        "select a, b from t"
+    );
+    assert_eq!(
+        normalize_sql("select 'a  b', \"c  d\"  from t"),
+        "select 'a  b', \"c  d\" from t"
+    );
+}
@@ -333,6 +333,10 @@ fn test_meta_locations() -> Result<()> {
     let uuid = Uuid::new_v4();
     let snapshot_loc = locs.snapshot_location_from_uuid(&uuid, TableSnapshot::VERSION)?;
     assert!(snapshot_loc.starts_with(test_prefix));
+    assert_eq!(
+        TableMetaLocationGenerator::snapshot_id(&snapshot_loc),
+        uuid.to_simple().to_string()
+    );
     Ok(())
 }
 
@@ -0,0 +1,306 @@
+//  Copyright 2021 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+use std::sync::Arc;
+
+use common_base::base::tokio;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use databend_query::catalogs::CATALOG_DEFAULT;
+use databend_query::sessions::QueryContext;
+use databend_query::storages::fuse::FuseTable;
+
+use crate::storages::fuse::table_test_fixture::execute_command;
+use crate::storages::fuse::table_test_fixture::execute_query;
+use crate::storages::fuse::table_test_fixture::expects_err;
+use crate::storages::fuse::table_test_fixture::expects_ok;
+use crate::storages::fuse::table_test_fixture::TestFixture;
+
+// The tables are cached in the query context, each statement runs in a new one.
+async fn new_ctx(fixture: &TestFixture) -> Result<Arc<QueryContext>> {
+    fixture
+        .ctx()
+        .get_current_session()
+        .create_query_context()
+        .await
+}
+
+async fn run(fixture: &TestFixture, query: &str) -> Result<()> {
+    execute_command(new_ctx(fixture).await?, query).await
+}
+
+#[tokio::test]
+async fn test_fuse_materialized_view() -> Result<()> {
+    let fixture = TestFixture::new().await;
+    let db = fixture.default_db_name();
+
+    run(&fixture, &format!("create table {}.t(a int, b int)", db)).await?;
+    run(
+        &fixture,
+        &format!("insert into {}.t values(1, 1), (1, 2), (2, 3)", db),
+    )
+    .await?;
+    run(
+        &fixture,
+        &format!(
+            "create materialized view {}.mv as select a, sum(b) as s from {}.t group by a",
+            db, db
+        ),
+    )
+    .await?;
+
+    // the view is filled with the rows inserted before it
+    let qry = format!("select a, s from {}.mv", db);
+    expects_ok(
+        "initial fill",
+        execute_query(new_ctx(&fixture).await?, &qry).await,
+        vec![
+            "+---+---+",
+            "| a | s |",
+            "+---+---+",
+            "| 1 | 3 |",
+            "| 2 | 3 |",
+            "+---+---+",
+        ],
+    )
+    .await?;
+
+    // the inserted rows are aggregated and appended to the view
+    run(
+        &fixture,
+        &format!("insert into {}.t values(1, 4), (3, 5)", db),
+    )
+    .await?;
+    expects_ok(
+        "incremental refresh",
+        execute_query(new_ctx(&fixture).await?, &qry).await,
+        vec![
+            "+---+---+",
+            "| a | s |",
+            "+---+---+",
+            "| 1 | 3 |",
+            "| 1 | 4 |",
+            "| 2 | 3 |",
+            "| 3 | 5 |",
+            "+---+---+",
+        ],
+    )
+    .await?;
+
+    let qry_merged = format!("select a, sum(s) as s from {}.mv group by a", db);
+    let merged = vec![
+        "+---+---+",
+        "| a | s |",
+        "+---+---+",
+        "| 1 | 7 |",
+        "| 2 | 3 |",
+        "| 3 | 5 |",
+        "+---+---+",
+    ];
+    expects_ok(
+        "re-aggregate the view",
+        execute_query(new_ctx(&fixture).await?, &qry_merged).await,
+        merged.clone(),
+    )
+    .await?;
+
+    // REFRESH rebuilds the view from the whole table
+    run(&fixture, &format!("refresh materialized view {}.mv", db)).await?;
+    expects_ok(
+        "full refresh",
+        execute_query(new_ctx(&fixture).await?, &qry).await,
+        merged,
+    )
+    .await?;
+
+    // INSERT OVERWRITE overwrites the view
+    run(&fixture, &format!("insert overwrite {}.t values(4, 6)", db)).await?;
+    expects_ok(
+        "overwrite",
+        execute_query(new_ctx(&fixture).await?, &qry).await,
+        vec![
+            "+---+---+",
+            "| a | s |",
+            "+---+---+",
+            "| 4 | 6 |",
+            "+---+---+",
+        ],
+    )
+    .await?;
+
+    // TRUNCATE truncates the view
+    run(&fixture, &format!("truncate table {}.t", db)).await?;
+    expects_ok(
+        "truncate",
+        execute_query(
+            new_ctx(&fixture).await?,
+            &format!("select count(*) as c from {}.mv", db),
+        )
+        .await,
+        vec!["+---+", "| c |", "+---+", "| 0 |", "+---+"],
+    )
+    .await?;
+
+    // the rows are still inserted after the view is dropped, and the view is unlinked
+    run(&fixture, &format!("drop table {}.mv", db)).await?;
+    run(&fixture, &format!("insert into {}.t values(5, 7)", db)).await?;
+    expects_ok(
+        "dropped view",
+        execute_query(
+            new_ctx(&fixture).await?,
+            &format!("select count(*) as c from {}.t", db),
+        )
+        .await,
+        vec!["+---+", "| c |", "+---+", "| 1 |", "+---+"],
+    )
+    .await?;
+    let table = new_ctx(&fixture)
+        .await?
+        .get_table(CATALOG_DEFAULT, &db, "t")
+        .await?;
+    assert!(FuseTable::materialized_view_ids(table.get_table_info()).is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fuse_materialized_view_unqualified_source() -> Result<()> {
+    let fixture = TestFixture::new().await;
+    let db = fixture.default_db_name();
+
+    run(&fixture, &format!("use {}", db)).await?;
+    run(&fixture, "create table t(a int)").await?;
+    run(&fixture, "create materialized view mv as select a from t").await?;
+
+    // the view reads the table of the database it is created in
+    run(&fixture, "create database other").await?;
+    run(&fixture, "create table other.t(a int)").await?;
+    run(&fixture, "use other").await?;
+    run(&fixture, &format!("insert into {}.t values(1), (2)", db)).await?;
+    expects_ok(
+        "insert from another database",
+        execute_query(
+            new_ctx(&fixture).await?,
+            &format!("select a from {}.mv", db),
+        )
+        .await,
+        vec!["+---+", "| a |", "+---+", "| 1 |", "| 2 |", "+---+"],
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_fuse_materialized_view_errors() -> Result<()> {
+    let fixture = TestFixture::new().await;
+    let db = fixture.default_db_name();
+
+    run(&fixture, &format!("create table {}.t1(a int)", db)).await?;
+    run(&fixture, &format!("create table {}.t2(a int)", db)).await?;
+    run(
+        &fixture,
+        &format!("create table {}.m(a int) engine = Memory", db),
+    )
+    .await?;
+
+    let cases = vec![
+        (
+            "two tables",
+            format!(
+                "create materialized view {}.mv as select t1.a from {}.t1, {}.t2",
+                db, db, db
+            ),
+        ),
+        (
+            "not a fuse table",
+            format!(
+                "create materialized view {}.mv as select a from {}.m",
+                db, db
+            ),
+        ),
+        (
+            "avg",
+            format!(
+                "create materialized view {}.mv as select a, avg(a) from {}.t1 group by a",
+                db, db
+            ),
+        ),
+        (
+            "count distinct",
+            format!(
+                "create materialized view {}.mv as select count(distinct a) from {}.t1",
+                db, db
+            ),
+        ),
+        (
+            "any of a non-group key",
+            format!(
+                "create materialized view {}.mv as select any(a) from {}.t1",
+                db, db
+            ),
+        ),
+        (
+            "table function",
+            "create materialized view mv as select * from numbers(10)".to_string(),
+        ),
+        (
+            "not a materialized view",
+            format!("refresh materialized view {}.t1", db),
+        ),
+    ];
+    for (case_name, qry) in cases {
+        expects_err(
+            case_name,
+            ErrorCode::bad_arguments_code(),
+            run(&fixture, &qry).await,
+        );
+    }
+
+    run(
+        &fixture,
+        &format!(
+            "create materialized view {}.mv_aggr as select a, any(a), count(), sum(a), min(a), \
+             max(a) from {}.t1 group by a",
+            db, db
+        ),
+    )
+    .await?;
+    run(
+        &fixture,
+        &format!(
+            "create materialized view {}.mv as select a from {}.t1",
+            db, db
+        ),
+    )
+    .await?;
+    expects_err(
+        "view exists",
+        ErrorCode::view_already_exists_code(),
+        run(
+            &fixture,
+            &format!(
+                "create materialized view {}.mv as select a from {}.t1",
+                db, db
+            ),
+        )
+        .await,
+    );
+    run(
+        &fixture,
+        &format!(
+            "create materialized view if not exists {}.mv as select a from {}.t1",
+            db, db
+        ),
+    )
+    .await
+}
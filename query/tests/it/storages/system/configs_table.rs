@@ -34,75 +34,88 @@ async fn test_configs_table() -> Result<()> {
     assert_eq!(block.num_columns(), 4);
 
     let expected = vec![
-        "+---------+--------------------------------------+--------------------------+-------------+",
-        "| group   | name                                 | value                    | description |",
-        "+---------+--------------------------------------+--------------------------+-------------+",
-        "| log     | dir                                  | ./_logs                  |             |",
-        "| log     | level                                | INFO                     |             |",
-        "| log     | query_enabled                        | false                    |             |",
-        "| meta    | address                              |                          |             |",
-        "| meta    | endpoints                            |                          |             |",
-        "| meta    | client_timeout_in_second             | 10                       |             |",
-        "| meta    | embedded_dir                         | ./_meta_embedded         |             |",
-        "| meta    | password                             |                          |             |",
-        "| meta    | rpc_tls_meta_server_root_ca_cert     |                          |             |",
-        "| meta    | rpc_tls_meta_service_domain_name     | localhost                |             |",
-        "| meta    | username                             | root                     |             |",
-        "| query   | admin_api_address                    | 127.0.0.1:8080           |             |",
-        "| query   | api_tls_server_cert                  |                          |             |",
-        "| query   | api_tls_server_key                   |                          |             |",
-        "| query   | api_tls_server_root_ca_cert          |                          |             |",
-        "| query   | clickhouse_handler_host              | 127.0.0.1                |             |",
-        "| query   | clickhouse_handler_port              | 9000                     |             |",
-        "| query   | cluster_id                           |                          |             |",
-        "| query   | database_engine_github_enabled       | true                     |             |",
-        "| query   | flight_api_address                   | 127.0.0.1:9090           |             |",
-        "| query   | http_handler_host                    | 127.0.0.1                |             |",
-        "| query   | http_handler_port                    | 8000                     |             |",
-        "| query   | http_handler_result_timeout_millis   | 10000                    |             |",
-        "| query   | http_handler_tls_server_cert         |                          |             |",
-        "| query   | http_handler_tls_server_key          |                          |             |",
-        "| query   | http_handler_tls_server_root_ca_cert |                          |             |",
-        "| query   | jwt_key_file                         |                          |             |",
-        "| query   | management_mode                      | false                    |             |",
-        "| query   | max_active_sessions                  | 256                      |             |",
-        "| query   | max_query_log_size                   | 10000                    |             |",
-        "| query   | metric_api_address                   | 127.0.0.1:7070           |             |",
-        "| query   | mysql_handler_host                   | 127.0.0.1                |             |",
-        "| query   | mysql_handler_port                   | 3307                     |             |",
-        "| query   | num_cpus                             | 0                        |             |",
-        "| query   | rpc_tls_query_server_root_ca_cert    |                          |             |",
-        "| query   | rpc_tls_query_service_domain_name    | localhost                |             |",
-        "| query   | rpc_tls_server_cert                  |                          |             |",
-        "| query   | rpc_tls_server_key                   |                          |             |",
-        "| query   | table_cache_block_meta_count         | 102400                   |             |",
-        "| query   | table_cache_enabled                  | false                    |             |",
-        "| query   | table_cache_segment_count            | 10240                    |             |",
-        "| query   | table_cache_snapshot_count           | 256                      |             |",
-        "| query   | table_disk_cache_mb_size             | 1024                     |             |",
-        "| query   | table_disk_cache_root                | _cache                   |             |",
-        "| query   | table_engine_memory_enabled          | true                     |             |",
-        "| query   | table_memory_cache_mb_size           | 256                      |             |",
-        "| query   | tenant_id                            | test                     |             |",
-        "| query   | wait_timeout_mills                   | 5000                     |             |",
-        "| storage | azblob.account_key                   |                          |             |",
-        "| storage | azblob.account_name                  |                          |             |",
-        "| storage | azblob.container                     |                          |             |",
-        "| storage | azblob.endpoint_url                  |                          |             |",
-        "| storage | azblob.root                          |                          |             |",
-        "| storage | fs.data_path                         | _data                    |             |",
-        "| storage | hdfs.name_node                       |                          |             |",
-        "| storage | hdfs.root                            |                          |             |",
-        "| storage | num_cpus                             | 0                        |             |",
-        "| storage | s3.access_key_id                     |                          |             |",
-        "| storage | s3.bucket                            |                          |             |",
-        "| storage | s3.endpoint_url                      | https://s3.amazonaws.com |             |",
-        "| storage | s3.master_key                        |                          |             |",
-        "| storage | s3.region                            |                          |             |",
-        "| storage | s3.root                              |                          |             |",
-        "| storage | s3.secret_access_key                 |                          |             |",
-        "| storage | type                                 | fs                       |             |",
-        "+---------+--------------------------------------+--------------------------+-------------+",
+        "+---------+--------------------------------------------+--------------------------+-------------+",
+        "| group   | name                                       | value                    | description |",
+        "+---------+--------------------------------------------+--------------------------+-------------+",
+        "| log     | dir                                        | ./_logs                  |             |",
+        "| log     | level                                      | INFO                     |             |",
+        "| log     | query_enabled                              | false                    |             |",
+        "| meta    | address                                    |                          |             |",
+        "| meta    | endpoints                                  |                          |             |",
+        "| meta    | client_timeout_in_second                   | 10                       |             |",
+        "| meta    | embedded_dir                               | ./_meta_embedded         |             |",
+        "| meta    | password                                   |                          |             |",
+        "| meta    | rpc_tls_meta_server_root_ca_cert           |                          |             |",
+        "| meta    | rpc_tls_meta_service_domain_name           | localhost                |             |",
+        "| meta    | username                                   | root                     |             |",
+        "| query   | admin_api_address                          | 127.0.0.1:8080           |             |",
+        "| query   | api_tls_server_cert                        |                          |             |",
+        "| query   | api_tls_server_key                         |                          |             |",
+        "| query   | api_tls_server_root_ca_cert                |                          |             |",
+        "| query   | clickhouse_handler_host                    | 127.0.0.1                |             |",
+        "| query   | clickhouse_handler_port                    | 9000                     |             |",
+        "| query   | clickhouse_handler_tls_server_cert         |                          |             |",
+        "| query   | clickhouse_handler_tls_server_key          |                          |             |",
+        "| query   | clickhouse_handler_tls_server_root_ca_cert |                          |             |",
+        "| query   | cluster_id                                 |                          |             |",
+        "| query   | database_engine_github_enabled             | true                     |             |",
+        "| query   | flight_api_address                         | 127.0.0.1:9090           |             |",
+        "| query   | flight_sql_handler_host                    | 127.0.0.1                |             |",
+        "| query   | flight_sql_handler_port                    | 8900                     |             |",
+        "| query   | http_handler_async_result_retention_secs   | 86400                    |             |",
+        "| query   | http_handler_host                          | 127.0.0.1                |             |",
+        "| query   | http_handler_port                          | 8000                     |             |",
+        "| query   | http_handler_result_timeout_millis         | 10000                    |             |",
+        "| query   | http_handler_tls_server_cert               |                          |             |",
+        "| query   | http_handler_tls_server_key                |                          |             |",
+        "| query   | http_handler_tls_server_root_ca_cert       |                          |             |",
+        "| query   | jwt_key_file                               |                          |             |",
+        "| query   | management_mode                            | false                    |             |",
+        "| query   | max_active_sessions                        | 256                      |             |",
+        "| query   | max_query_log_size                         | 10000                    |             |",
+        "| query   | metric_api_address                         | 127.0.0.1:7070           |             |",
+        "| query   | mysql_handler_host                         | 127.0.0.1                |             |",
+        "| query   | mysql_handler_port                         | 3307                     |             |",
+        "| query   | mysql_handler_tls_server_cert              |                          |             |",
+        "| query   | mysql_handler_tls_server_key               |                          |             |",
+        "| query   | mysql_handler_tls_server_root_ca_cert      |                          |             |",
+        "| query   | num_cpus                                   | 0                        |             |",
+        "| query   | postgres_handler_host                      | 127.0.0.1                |             |",
+        "| query   | postgres_handler_port                      | 5433                     |             |",
+        "| query   | query_result_cache_mb_size                 | 64                       |             |",
+        "| query   | rpc_tls_query_server_root_ca_cert          |                          |             |",
+        "| query   | rpc_tls_query_service_domain_name          | localhost                |             |",
+        "| query   | rpc_tls_server_cert                        |                          |             |",
+        "| query   | rpc_tls_server_key                         |                          |             |",
+        "| query   | spill_local_path                           |                          |             |",
+        "| query   | table_cache_block_meta_count               | 102400                   |             |",
+        "| query   | table_cache_enabled                        | false                    |             |",
+        "| query   | table_cache_segment_count                  | 10240                    |             |",
+        "| query   | table_cache_snapshot_count                 | 256                      |             |",
+        "| query   | table_disk_cache_mb_size                   | 1024                     |             |",
+        "| query   | table_disk_cache_root                      | _cache                   |             |",
+        "| query   | table_engine_memory_enabled                | true                     |             |",
+        "| query   | table_memory_cache_mb_size                 | 256                      |             |",
+        "| query   | tenant_id                                  | test                     |             |",
+        "| query   | wait_timeout_mills                         | 5000                     |             |",
+        "| storage | azblob.account_key                         |                          |             |",
+        "| storage | azblob.account_name                        |                          |             |",
+        "| storage | azblob.container                           |                          |             |",
+        "| storage | azblob.endpoint_url                        |                          |             |",
+        "| storage | azblob.root                                |                          |             |",
+        "| storage | fs.data_path                               | _data                    |             |",
+        "| storage | hdfs.name_node                             |                          |             |",
+        "| storage | hdfs.root                                  |                          |             |",
+        "| storage | num_cpus                                   | 0                        |             |",
+        "| storage | s3.access_key_id                           |                          |             |",
+        "| storage | s3.bucket                                  |                          |             |",
+        "| storage | s3.endpoint_url                            | https://s3.amazonaws.com |             |",
+        "| storage | s3.master_key                              |                          |             |",
+        "| storage | s3.region                                  |                          |             |",
+        "| storage | s3.root                                    |                          |             |",
+        "| storage | s3.secret_access_key                       |                          |             |",
+        "| storage | type                                       | fs                       |             |",
+        "+---------+--------------------------------------------+--------------------------+-------------+",
     ];
     common_datablocks::assert_blocks_sorted_eq(expected, result.as_slice());
     Ok(())
@@ -125,75 +138,88 @@ async fn test_configs_table_redact() -> Result<()> {
     assert_eq!(block.num_columns(), 4);
 
     let expected = vec![
-        "+---------+--------------------------------------+--------------------------+-------------+",
-        "| group   | name                                 | value                    | description |",
-        "+---------+--------------------------------------+--------------------------+-------------+",
-        "| log     | dir                                  | ./_logs                  |             |",
-        "| log     | level                                | INFO                     |             |",
-        "| log     | query_enabled                        | false                    |             |",
-        "| meta    | address                              |                          |             |",
-        "| meta    | endpoints                            |                          |             |",
-        "| meta    | client_timeout_in_second             | 10                       |             |",
-        "| meta    | embedded_dir                         | ./_meta_embedded         |             |",
-        "| meta    | password                             |                          |             |",
-        "| meta    | rpc_tls_meta_server_root_ca_cert     |                          |             |",
-        "| meta    | rpc_tls_meta_service_domain_name     | localhost                |             |",
-        "| meta    | username                             | root                     |             |",
-        "| query   | admin_api_address                    | 127.0.0.1:8080           |             |",
-        "| query   | api_tls_server_cert                  |                          |             |",
-        "| query   | api_tls_server_key                   |                          |             |",
-        "| query   | api_tls_server_root_ca_cert          |                          |             |",
-        "| query   | clickhouse_handler_host              | 127.0.0.1                |             |",
-        "| query   | clickhouse_handler_port              | 9000                     |             |",
-        "| query   | cluster_id                           |                          |             |",
-        "| query   | database_engine_github_enabled       | true                     |             |",
-        "| query   | flight_api_address                   | 127.0.0.1:9090           |             |",
-        "| query   | http_handler_host                    | 127.0.0.1                |             |",
-        "| query   | http_handler_port                    | 8000                     |             |",
-        "| query   | http_handler_result_timeout_millis   | 10000                    |             |",
-        "| query   | http_handler_tls_server_cert         |                          |             |",
-        "| query   | http_handler_tls_server_key          |                          |             |",
-        "| query   | http_handler_tls_server_root_ca_cert |                          |             |",
-        "| query   | jwt_key_file                         |                          |             |",
-        "| query   | management_mode                      | false                    |             |",
-        "| query   | max_active_sessions                  | 256                      |             |",
-        "| query   | max_query_log_size                   | 10000                    |             |",
-        "| query   | metric_api_address                   | 127.0.0.1:7070           |             |",
-        "| query   | mysql_handler_host                   | 127.0.0.1                |             |",
-        "| query   | mysql_handler_port                   | 3307                     |             |",
-        "| query   | num_cpus                             | 0                        |             |",
-        "| query   | rpc_tls_query_server_root_ca_cert    |                          |             |",
-        "| query   | rpc_tls_query_service_domain_name    | localhost                |             |",
-        "| query   | rpc_tls_server_cert                  |                          |             |",
-        "| query   | rpc_tls_server_key                   |                          |             |",
-        "| query   | table_cache_block_meta_count         | 102400                   |             |",
-        "| query   | table_cache_enabled                  | false                    |             |",
-        "| query   | table_cache_segment_count            | 10240                    |             |",
-        "| query   | table_cache_snapshot_count           | 256                      |             |",
-        "| query   | table_disk_cache_mb_size             | 1024                     |             |",
-        "| query   | table_disk_cache_root                | _cache                   |             |",
-        "| query   | table_engine_memory_enabled          | true                     |             |",
-        "| query   | table_memory_cache_mb_size           | 256                      |             |",
-        "| query   | tenant_id                            | test                     |             |",
-        "| query   | wait_timeout_mills                   | 5000                     |             |",
-        "| storage | azblob.account_key                   |                          |             |",
-        "| storage | azblob.account_name                  |                          |             |",
-        "| storage | azblob.container                     |                          |             |",
-        "| storage | azblob.endpoint_url                  |                          |             |",
-        "| storage | azblob.root                          |                          |             |",
-        "| storage | fs.data_path                         | _data                    |             |",
-        "| storage | hdfs.name_node                       |                          |             |",
-        "| storage | hdfs.root                            |                          |             |",
-        "| storage | num_cpus                             | 0                        |             |",
-        "| storage | s3.access_key_id                     | ******_id                |             |",
-        "| storage | s3.bucket                            |                          |             |",
-        "| storage | s3.endpoint_url                      | https://s3.amazonaws.com |             |",
-        "| storage | s3.master_key                        |                          |             |",
-        "| storage | s3.region                            |                          |             |",
-        "| storage | s3.root                              |                          |             |",
-        "| storage | s3.secret_access_key                 | ******key                |             |",
-        "| storage | type                                 | fs                       |             |",
-        "+---------+--------------------------------------+--------------------------+-------------+",
+        "+---------+--------------------------------------------+--------------------------+-------------+",
+        "| group   | name                                       | value                    | description |",
+        "+---------+--------------------------------------------+--------------------------+-------------+",
+        "| log     | dir                                        | ./_logs                  |             |",
+        "| log     | level                                      | INFO                     |             |",
+        "| log     | query_enabled                              | false                    |             |",
+        "| meta    | address                                    |                          |             |",
+        "| meta    | endpoints                                  |                          |             |",
+        "| meta    | client_timeout_in_second                   | 10                       |             |",
+        "| meta    | embedded_dir                               | ./_meta_embedded         |             |",
+        "| meta    | password                                   |                          |             |",
+        "| meta    | rpc_tls_meta_server_root_ca_cert           |                          |             |",
+        "| meta    | rpc_tls_meta_service_domain_name           | localhost                |             |",
+        "| meta    | username                                   | root                     |             |",
+        "| query   | admin_api_address                          | 127.0.0.1:8080           |             |",
+        "| query   | api_tls_server_cert                        |                          |             |",
+        "| query   | api_tls_server_key                         |                          |             |",
+        "| query   | api_tls_server_root_ca_cert                |                          |             |",
+        "| query   | clickhouse_handler_host                    | 127.0.0.1                |             |",
+        "| query   | clickhouse_handler_port                    | 9000                     |             |",
+        "| query   | clickhouse_handler_tls_server_cert         |                          |             |",
+        "| query   | clickhouse_handler_tls_server_key          |                          |             |",
+        "| query   | clickhouse_handler_tls_server_root_ca_cert |                          |             |",
+        "| query   | cluster_id                                 |                          |             |",
+        "| query   | database_engine_github_enabled             | true                     |             |",
+        "| query   | flight_api_address                         | 127.0.0.1:9090           |             |",
+        "| query   | flight_sql_handler_host                    | 127.0.0.1                |             |",
+        "| query   | flight_sql_handler_port                    | 8900                     |             |",
+        "| query   | http_handler_async_result_retention_secs   | 86400                    |             |",
+        "| query   | http_handler_host                          | 127.0.0.1                |             |",
+        "| query   | http_handler_port                          | 8000                     |             |",
+        "| query   | http_handler_result_timeout_millis         | 10000                    |             |",
+        "| query   | http_handler_tls_server_cert               |                          |             |",
+        "| query   | http_handler_tls_server_key                |                          |             |",
+        "| query   | http_handler_tls_server_root_ca_cert       |                          |             |",
+        "| query   | jwt_key_file                               |                          |             |",
+        "| query   | management_mode                            | false                    |             |",
+        "| query   | max_active_sessions                        | 256                      |             |",
+        "| query   | max_query_log_size                         | 10000                    |             |",
+        "| query   | metric_api_address                         | 127.0.0.1:7070           |             |",
+        "| query   | mysql_handler_host                         | 127.0.0.1                |             |",
+        "| query   | mysql_handler_port                         | 3307                     |             |",
+        "| query   | mysql_handler_tls_server_cert              |                          |             |",
+        "| query   | mysql_handler_tls_server_key               |                          |             |",
+        "| query   | mysql_handler_tls_server_root_ca_cert      |                          |             |",
+        "| query   | num_cpus                                   | 0                        |             |",
+        "| query   | postgres_handler_host                      | 127.0.0.1                |             |",
+        "| query   | postgres_handler_port                      | 5433                     |             |",
+        "| query   | query_result_cache_mb_size                 | 64                       |             |",
+        "| query   | rpc_tls_query_server_root_ca_cert          |                          |             |",
+        "| query   | rpc_tls_query_service_domain_name          | localhost                |             |",
+        "| query   | rpc_tls_server_cert                        |                          |             |",
+        "| query   | rpc_tls_server_key                         |                          |             |",
+        "| query   | spill_local_path                           |                          |             |",
+        "| query   | table_cache_block_meta_count               | 102400                   |             |",
+        "| query   | table_cache_enabled                        | false                    |             |",
+        "| query   | table_cache_segment_count                  | 10240                    |             |",
+        "| query   | table_cache_snapshot_count                 | 256                      |             |",
+        "| query   | table_disk_cache_mb_size                   | 1024                     |             |",
+        "| query   | table_disk_cache_root                      | _cache                   |             |",
+        "| query   | table_engine_memory_enabled                | true                     |             |",
+        "| query   | table_memory_cache_mb_size                 | 256                      |             |",
+        "| query   | tenant_id                                  | test                     |             |",
+        "| query   | wait_timeout_mills                         | 5000                     |             |",
+        "| storage | azblob.account_key                         |                          |             |",
+        "| storage | azblob.account_name                        |                          |             |",
+        "| storage | azblob.container                           |                          |             |",
+        "| storage | azblob.endpoint_url                        |                          |             |",
+        "| storage | azblob.root                                |                          |             |",
+        "| storage | fs.data_path                               | _data                    |             |",
+        "| storage | hdfs.name_node                             |                          |             |",
+        "| storage | hdfs.root                                  |                          |             |",
+        "| storage | num_cpus                                   | 0                        |             |",
+        "| storage | s3.access_key_id                           | ******_id                |             |",
+        "| storage | s3.bucket                                  |                          |             |",
+        "| storage | s3.endpoint_url                            | https://s3.amazonaws.com |             |",
+        "| storage | s3.master_key                              |                          |             |",
+        "| storage | s3.region                                  |                          |             |",
+        "| storage | s3.root                                    |                          |             |",
+        "| storage | s3.secret_access_key                       | ******key                |             |",
+        "| storage | type                                       | fs                       |             |",
+        "+---------+--------------------------------------------+--------------------------+-------------+",
     ];
     common_datablocks::assert_blocks_sorted_eq(expected, result.as_slice());
     Ok(())
@@ -37,15 +37,26 @@ async fn test_settings_table() -> Result<()> {
         "| empty_as_default               | 1       | 1       | SESSION | Format empty_as_default, default value: 1                                                          | UInt64 |",
         "| enable_new_processor_framework | 1       | 1       | SESSION | Enable new processor framework if value != 0, default value: 1                                     | UInt64 |",
         "| enable_planner_v2              | 0       | 0       | SESSION | Enable planner v2 by setting this variable to 1, default value: 0                                  | UInt64 |",
+        "| enable_query_cache             | 0       | 0       | SESSION | Reuse the cached results of the same SELECT on the same table snapshots, default value: 0          | UInt64 |",
         "| field_delimiter                | ,       | ,       | SESSION | Format field delimiter, default value: ,                                                           | String |",
         "| flight_client_timeout          | 60      | 60      | SESSION | Max duration the flight client request is allowed to take in seconds. By default, it is 60 seconds | UInt64 |",
+        "| group_by_spill_threshold       | 0       | 0       | SESSION | Spill aggregation states to disk above this memory in bytes, 0 disables spilling, default value: 0 | UInt64 |",
         "| group_by_two_level_threshold   | 10000   | 10000   | SESSION | The threshold of keys to open two-level aggregation, default value: 10000                          | UInt64 |",
+        "| join_spill_threshold           | 0       | 0       | SESSION | Spill hash join to disk above this memory in bytes, 0 disables spilling, default value: 0          | UInt64 |",
         "| max_block_size                 | 10000   | 10000   | SESSION | Maximum block size for reading                                                                     | UInt64 |",
+        "| max_memory_usage               | 0       | 0       | SESSION | The maximum memory in bytes of a query, 0 is no limited, default value: 0                          | UInt64 |",
+        "| max_recursive_cte_depth        | 1000    | 1000    | SESSION | The maximum number of iterations of a recursive CTE, 0 is no limited, default value: 1000          | UInt64 |",
         "| max_threads                    | 2       | 16      | SESSION | The maximum number of threads to execute the request. By default, it is determined automatically.  | UInt64 |",
+        "| query_cache_max_result_bytes   | 1048576 | 1048576 | SESSION | The maximum bytes of a query result to cache, default value: 1048576                               | UInt64 |",
+        "| query_cache_storage            | memory  | memory  | SESSION | Where to cache the query results, memory or disk, default value: memory                            | String |",
+        "| query_cache_ttl_secs           | 300     | 300     | SESSION | The seconds to keep a cached query result, default value: 300                                      | UInt64 |",
         "| record_delimiter               |         |         | SESSION | Format record_delimiter, default value:                                                            | String |",
         "| skip_header                    | 0       | 0       | SESSION | Whether to skip the input header, default value: 0                                                 | UInt64 |",
+        "| sort_spill_threshold           | 0       | 0       | SESSION | Spill sorted blocks to disk above this memory in bytes, 0 disables spilling, default value: 0      | UInt64 |",
         "| storage_read_buffer_size       | 1048576 | 1048576 | SESSION | The size of buffer in bytes for buffered reader of dal. By default, it is 1MB.                     | UInt64 |",
         "| timezone                       | UTC     | UTC     | SESSION | Timezone, default value: UTC,                                                                      | String |",
+        "| wasm_udf_max_memory_mb         | 64      | 64      | SESSION | The maximum memory in MB of a WebAssembly UDF call, default value: 64                              | UInt64 |",
+        "| wasm_udf_timeout_ms            | 10000   | 10000   | SESSION | The maximum running time in milliseconds of a WebAssembly UDF call, default value: 10000           | UInt64 |",
         "+--------------------------------+---------+---------+---------+----------------------------------------------------------------------------------------------------+--------+",
     ];
     common_datablocks::assert_blocks_sorted_eq(expected, result.as_slice());
@@ -81,6 +81,15 @@ impl SessionManagerBuilder {
         SessionManagerBuilder::create_with_conf(new_config)
     }
 
+    pub fn http_handler_async_result_retention_secs(
+        self,
+        value: impl Into<u64>,
+    ) -> SessionManagerBuilder {
+        let mut new_config = self.config;
+        new_config.query.http_handler_async_result_retention_secs = value.into();
+        SessionManagerBuilder::create_with_conf(new_config)
+    }
+
     pub fn http_handler_tls_server_key(self, value: impl Into<String>) -> SessionManagerBuilder {
         let mut new_config = self.config;
         new_config.query.http_handler_tls_server_key = value.into();
@@ -102,6 +111,45 @@ impl SessionManagerBuilder {
         SessionManagerBuilder::create_with_conf(new_config)
     }
 
+    pub fn mysql_handler_tls_server_key(self, value: impl Into<String>) -> SessionManagerBuilder {
+        let mut new_config = self.config;
+        new_config.query.mysql_handler_tls_server_key = value.into();
+        SessionManagerBuilder::create_with_conf(new_config)
+    }
+
+    pub fn mysql_handler_tls_server_cert(self, value: impl Into<String>) -> SessionManagerBuilder {
+        let mut new_config = self.config;
+        new_config.query.mysql_handler_tls_server_cert = value.into();
+        SessionManagerBuilder::create_with_conf(new_config)
+    }
+
+    pub fn clickhouse_handler_tls_server_key(
+        self,
+        value: impl Into<String>,
+    ) -> SessionManagerBuilder {
+        let mut new_config = self.config;
+        new_config.query.clickhouse_handler_tls_server_key = value.into();
+        SessionManagerBuilder::create_with_conf(new_config)
+    }
+
+    pub fn clickhouse_handler_tls_server_cert(
+        self,
+        value: impl Into<String>,
+    ) -> SessionManagerBuilder {
+        let mut new_config = self.config;
+        new_config.query.clickhouse_handler_tls_server_cert = value.into();
+        SessionManagerBuilder::create_with_conf(new_config)
+    }
+
+    pub fn clickhouse_handler_tls_server_root_ca_cert(
+        self,
+        value: impl Into<String>,
+    ) -> SessionManagerBuilder {
+        let mut new_config = self.config;
+        new_config.query.clickhouse_handler_tls_server_root_ca_cert = value.into();
+        SessionManagerBuilder::create_with_conf(new_config)
+    }
+
     pub fn api_tls_server_key(self, value: impl Into<String>) -> SessionManagerBuilder {
         let mut new_config = self.config;
         new_config.query.api_tls_server_key = value.into();
@@ -0,0 +1,22 @@
+-- 1619822911999000 = 2021-04-30 22:48:31.999 UTC
+-- 1622505600000000 = 2021-06-01 00:00:00 UTC
+select "==UTC==";
+set timezone = 'UTC';
+select date_format(to_timestamp(1619822911999000), '%Y-%m-%d %H:%i:%s');
+select date_format(to_timestamp(1619822911999000), '%W %M %e %Y');
+select strftime(to_timestamp(1619822911999000), '%Y/%m/%d %H:%M');
+select date_format(date_trunc('month', to_timestamp(1619822911999000)), '%Y-%m-%d %H:%i:%s');
+select date_format(date_trunc('week', to_timestamp(1619822911999000)), '%Y-%m-%d %H:%i:%s');
+select date_diff('day', to_timestamp(1619822911999000), to_timestamp(1622505600000000));
+select date_diff('month', to_timestamp(1619822911999000), to_timestamp(1622505600000000));
+select date_format(str_to_date('2021-04-30 22:48:31', '%Y-%m-%d %H:%i:%s'), '%Y%m%d%H%i%s');
+select str_to_date('not a date', '%Y-%m-%d') is null;
+select to_timestamp('30/04/2021 22:48', '%d/%m/%Y %H:%i') = to_timestamp('2021-04-30 22:48:00');
+select "==Asia/Shanghai==";
+set timezone = 'Asia/Shanghai';
+select date_format(to_timestamp(1619822911999000), '%Y-%m-%d %H:%i:%s');
+select date_format(date_trunc('month', to_timestamp(1619822911999000)), '%Y-%m-%d %H:%i:%s');
+select date_diff('day', to_timestamp(1619822911999000), to_timestamp(1622505600000000));
+select date_diff('month', to_timestamp(1619822911999000), to_timestamp(1622505600000000));
+select date_format(convert_tz(to_timestamp(1619822911999000), 'Asia/Shanghai', 'UTC'), '%Y-%m-%d %H:%i:%s');
+select to_timestamp('30/04/2021 22:48', '%d/%m/%Y %H:%i') = to_timestamp('2021-04-30 22:48:00');
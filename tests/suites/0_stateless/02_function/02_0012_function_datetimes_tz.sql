@@ -75,4 +75,19 @@ select "==Asia/Shanghai==";
 set timezone = 'Asia/Shanghai';
 select addMonths(to_timestamp(1619822911999000), 1);
 select to_timestamp(1583013600000000);
-select addYears(to_timestamp(1583013600000000), 1);
\ No newline at end of file
+select addYears(to_timestamp(1583013600000000), 1);
+select "====DATE_CAST====";
+-- 1619820000000000 = 2021-04-30 22:00:00
+select "==UTC==";
+set timezone = 'UTC';
+select to_date(to_timestamp(1619820000000000));
+select to_timestamp(to_date('2021-05-01'));
+select "==Asia/Shanghai==";
+set timezone = 'Asia/Shanghai';
+select to_date(to_timestamp(1619820000000000));
+select to_timestamp(to_date('2021-05-01'));
+select "==Asia/Kolkata==";
+-- Asia/Kolkata: +5:30
+set timezone = 'Asia/Kolkata';
+select tostartofhour(to_timestamp(1619822911999000));
+select timeslot(to_timestamp(1619822911999000));
@@ -0,0 +1,27 @@
+select 'ABC' = 'abc';
+select 'ABC' collate 'utf8_general_ci' = 'abc';
+select 'École' collate 'utf8_general_ci' = 'ECOLE';
+select 'École' collate 'utf8mb4_0900_as_ci' = 'ecole';
+select 'École' collate 'utf8mb4_0900_as_ci' = 'ÉCOLE';
+select 'b' collate 'utf8_general_ci' in ('A', 'B');
+select 'B' collate 'utf8_general_ci' between 'a' and 'c';
+select 'abc' collate 'utf8_general_ci' like 'A%';
+select 'a' collate 'utf8_general_ci' = 'A' collate 'utf8_bin'; -- {ErrorCode 1006}
+select 'a' collate 'latin1_swedish_ci' = 'A'; -- {ErrorCode 1006}
+
+DROP TABLE IF EXISTS t_collate;
+CREATE TABLE t_collate(name VARCHAR) Engine = Fuse;
+INSERT INTO t_collate VALUES ('banana'), ('Apple'), ('apple'), ('Cherry'), ('ÄPFEL');
+
+SELECT '==ORDER BY==';
+SELECT name FROM t_collate ORDER BY name;
+SELECT name FROM t_collate ORDER BY name COLLATE 'utf8_general_ci', name;
+
+SELECT '==WHERE==';
+SELECT count(*) FROM t_collate WHERE name COLLATE 'utf8_general_ci' = 'APPLE';
+SELECT count(*) FROM t_collate WHERE name COLLATE 'utf8_general_ci' > 'b';
+
+SELECT '==GROUP BY==';
+SELECT name COLLATE 'utf8_general_ci' AS n, count(*) FROM t_collate GROUP BY name COLLATE 'utf8_general_ci' ORDER BY n;
+
+DROP TABLE t_collate;
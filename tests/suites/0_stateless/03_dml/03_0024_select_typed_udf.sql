@@ -0,0 +1,26 @@
+DROP FUNCTION IF EXISTS add_one;
+DROP FUNCTION IF EXISTS evens;
+
+CREATE FUNCTION add_one(a INT) RETURNS BIGINT AS a + 1;
+CREATE FUNCTION add_one(s VARCHAR) RETURNS VARCHAR AS concat(s, '1');
+CREATE FUNCTION add_one(s VARCHAR) RETURNS VARCHAR AS concat(s, '2'); -- {ErrorCode 2603}
+CREATE FUNCTION bad_udf(a INT) RETURNS INT AS not_exists(a); -- {ErrorCode 2602}
+
+SELECT add_one(1);
+SELECT add_one('a');
+SELECT add_one(CAST(2 AS TINYINT));
+SELECT add_one(number) FROM numbers(2) ORDER BY number;
+SELECT add_one(1, 2); -- {ErrorCode 1005}
+
+CREATE FUNCTION evens(n BIGINT) RETURNS TABLE (x BIGINT) AS SELECT number FROM numbers(n) WHERE number % 2 = 0;
+SELECT x FROM evens(5) ORDER BY x;
+SELECT count(*) FROM evens(10);
+SELECT evens(1); -- {ErrorCode 1005}
+
+DROP FUNCTION add_one(INT);
+SELECT add_one(1);
+SELECT add_one('b');
+
+DROP FUNCTION add_one;
+DROP FUNCTION evens;
+DROP FUNCTION add_one; -- {ErrorCode 2602}
@@ -0,0 +1,18 @@
+DROP FUNCTION IF EXISTS wasm_echo;
+
+-- The module returns its input, so the result is the argument.
+CREATE FUNCTION wasm_echo(a BIGINT) RETURNS BIGINT LANGUAGE wasm AS 'AGFzbQEAAAABDAJgAX8Bf2ACf38BfgMDAgABBQMBABAHHgMGbWVtb3J5AgAFYWxsb2MAAAl3YXNtX2VjaG8AAQoUAgUAQYAICwwAIACtQiCGIAGthAs=';
+CREATE FUNCTION wasm_echo(s VARCHAR) RETURNS VARCHAR LANGUAGE wasm AS 'AGFzbQEAAAABDAJgAX8Bf2ACf38BfgMDAgABBQMBABAHHgMGbWVtb3J5AgAFYWxsb2MAAAl3YXNtX2VjaG8AAQoUAgUAQYAICwwAIACtQiCGIAGthAs=';
+
+SELECT wasm_echo(number) AS a FROM numbers(3) ORDER BY a;
+SELECT wasm_echo('databend');
+
+SET wasm_udf_max_memory_mb = 0;
+SELECT wasm_echo(number) FROM numbers(3); -- {ErrorCode 2604}
+SET wasm_udf_max_memory_mb = 64;
+
+CREATE FUNCTION bad_wasm(a BIGINT) RETURNS BIGINT LANGUAGE wasm AS 'not base64!'; -- {ErrorCode 2601}
+CREATE FUNCTION bad_wasm(a BIGINT) RETURNS BIGINT LANGUAGE wasm AS 'AGFzbQEAAAABDAJgAX8Bf2ACf38BfgMDAgABBQMBABAHHgMGbWVtb3J5AgAFYWxsb2MAAAl3YXNtX2VjaG8AAQoUAgUAQYAICwwAIACtQiCGIAGthAs='; -- {ErrorCode 2601}
+
+DROP FUNCTION wasm_echo;
+SELECT wasm_echo(1); -- {ErrorCode 2602}
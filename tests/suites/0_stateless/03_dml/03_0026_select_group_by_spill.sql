@@ -0,0 +1,9 @@
+set enable_new_processor_framework = 1;
+set group_by_spill_threshold = 1;
+
+SELECT number % 3 AS c, count(*), sum(number) FROM numbers(100) GROUP BY c ORDER BY c;
+SELECT count(*), sum(s), max(m) FROM (SELECT number % 10000 AS k, sum(number) AS s, max(number) AS m FROM numbers(100000) GROUP BY k);
+SELECT count(*) FROM (SELECT to_varchar(number % 5000) AS k, count(*) FROM numbers(20000) GROUP BY k);
+SELECT count(*) FROM (SELECT DISTINCT number % 7000 FROM numbers(30000));
+
+set group_by_spill_threshold = 0;
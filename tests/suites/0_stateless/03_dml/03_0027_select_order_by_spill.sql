@@ -0,0 +1,13 @@
+set enable_new_processor_framework = 1;
+set sort_spill_threshold = 1;
+set max_block_size = 1000;
+
+SELECT number FROM numbers(10) ORDER BY number DESC;
+SELECT number, number % 3 AS c FROM numbers(12) ORDER BY c, number DESC;
+SELECT number FROM numbers(100000) ORDER BY number DESC LIMIT 3 OFFSET 2;
+SELECT number FROM numbers(20000) ORDER BY number % 5000 DESC, number LIMIT 4;
+SELECT to_varchar(number) AS s FROM numbers(1000) ORDER BY s LIMIT 3;
+SELECT count(*), sum(number) FROM (SELECT number FROM numbers(100000) ORDER BY number DESC);
+
+set sort_spill_threshold = 0;
+set max_block_size = 10000;
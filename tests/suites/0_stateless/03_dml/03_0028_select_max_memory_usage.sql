@@ -0,0 +1,8 @@
+set enable_new_processor_framework = 1;
+set max_memory_usage = 1;
+
+SELECT count(*) FROM (SELECT number % 1000000 AS k, count(*) FROM numbers(3000000) GROUP BY k); -- {ErrorCode 1104}
+
+set max_memory_usage = 0;
+
+SELECT count(*) FROM (SELECT number % 1000000 AS k, count(*) FROM numbers(3000000) GROUP BY k);
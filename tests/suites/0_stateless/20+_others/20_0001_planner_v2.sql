@@ -175,4 +175,7 @@ select '====Context Function====';
 use default;
 select database();
 
+select '====AT TIME ZONE====';
+select to_timestamp('2021-04-30 22:48:00') at time zone 'Asia/Shanghai';
+
 set enable_planner_v2 = 0;
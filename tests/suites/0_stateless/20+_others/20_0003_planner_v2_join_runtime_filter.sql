@@ -0,0 +1,34 @@
+set enable_planner_v2 = 1;
+
+DROP DATABASE IF EXISTS db_20_0003;
+CREATE DATABASE db_20_0003;
+USE db_20_0003;
+
+CREATE TABLE t(a INT, b INT);
+-- Each insertion is a block with its own statistics.
+INSERT INTO t VALUES(1, 1), (2, 2), (3, 3);
+INSERT INTO t VALUES(11, 11), (12, 12), (13, 13);
+INSERT INTO t VALUES(21, 21), (22, 22), (23, 23);
+
+CREATE TABLE t1(c INT);
+INSERT INTO t1 VALUES(12), (13), (14);
+
+CREATE TABLE t2(c INT NULL);
+INSERT INTO t2 VALUES(NULL);
+
+CREATE TABLE t3(c INT);
+
+SELECT a, b, c FROM t INNER JOIN t1 ON t.a = t1.c ORDER BY a;
+SELECT a, b, c FROM t INNER JOIN t1 ON t.b = t1.c WHERE t.a > 12 ORDER BY a;
+SELECT count(*) FROM t INNER JOIN t2 ON t.a = t2.c;
+SELECT count(*) FROM t INNER JOIN t3 ON t.a = t3.c;
+
+-- The blocks of t out of the key range of the build side are not read:
+-- the block of t1 and the block (11, 12, 13) of t are scanned.
+SELECT scan_partitions, total_partitions, scan_rows FROM system.query_log WHERE query_text = 'SELECT a, b, c FROM t INNER JOIN t1 ON t.a = t1.c ORDER BY a' AND scan_rows != 0;
+-- Only the block of t2 is scanned, the NULL key matches no block of t.
+SELECT scan_partitions, total_partitions, scan_rows FROM system.query_log WHERE query_text = 'SELECT count(*) FROM t INNER JOIN t2 ON t.a = t2.c' AND scan_rows != 0;
+
+DROP DATABASE db_20_0003;
+
+set enable_planner_v2 = 0;
@@ -0,0 +1,12 @@
+set enable_planner_v2 = 1;
+set join_spill_threshold = 1;
+set max_block_size = 1000;
+
+select t.number, t1.number from numbers(5) as t inner join numbers(5) as t1 on t.number = t1.number + 2 order by t.number;
+select count(*) from numbers(10000) as t inner join numbers(10000) as t1 on t.number = t1.number;
+select count(*), sum(t.number) from numbers(10000) as t inner join numbers(1000) as t1 on t.number = t1.number * 2;
+select count(*) from numbers(1000) as t inner join numbers(1000) as t1 on t.number = t1.number + 1000;
+
+set join_spill_threshold = 0;
+set max_block_size = 10000;
+set enable_planner_v2 = 0;
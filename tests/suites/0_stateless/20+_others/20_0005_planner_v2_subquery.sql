@@ -0,0 +1,33 @@
+set enable_planner_v2 = 1;
+
+DROP DATABASE IF EXISTS db_20_0005;
+CREATE DATABASE db_20_0005;
+USE db_20_0005;
+
+CREATE TABLE t(a INT, b INT);
+INSERT INTO t VALUES(1, 10), (2, 20), (3, 30), (4, 40);
+
+CREATE TABLE t1(a INT, b INT);
+INSERT INTO t1 VALUES(1, 5), (1, 15), (2, 25), (3, 1), (5, 50);
+
+-- EXISTS and NOT EXISTS
+SELECT a FROM t WHERE EXISTS (SELECT * FROM t1 WHERE t1.a = t.a) ORDER BY a;
+SELECT a FROM t WHERE NOT EXISTS (SELECT * FROM t1 WHERE t1.a = t.a) ORDER BY a;
+SELECT a FROM t WHERE EXISTS (SELECT * FROM t1 WHERE t1.a = t.a AND t1.b > 10) ORDER BY a;
+SELECT count(*) FROM t WHERE EXISTS (SELECT * FROM t1 WHERE t1.b > 10);
+SELECT count(*) FROM t WHERE EXISTS (SELECT * FROM t1 WHERE t1.b > 100);
+SELECT count(*) FROM t WHERE NOT EXISTS (SELECT * FROM t1 WHERE t1.b > 100);
+
+-- IN and NOT IN
+SELECT a FROM t WHERE a IN (SELECT a FROM t1) ORDER BY a;
+SELECT a FROM t WHERE a NOT IN (SELECT a FROM t1) ORDER BY a;
+SELECT a FROM t WHERE b IN (SELECT b * 2 FROM t1 WHERE t1.a = t.a) ORDER BY a;
+
+-- Scalar subquery
+SELECT a FROM t WHERE b > (SELECT max(b) FROM t1 WHERE t1.a = t.a) ORDER BY a;
+SELECT a FROM t WHERE b < (SELECT sum(b) FROM t1 WHERE t1.a = t.a) ORDER BY a;
+SELECT a FROM t WHERE b > (SELECT avg(b) FROM t1) ORDER BY a;
+
+DROP DATABASE db_20_0005;
+
+set enable_planner_v2 = 0;
@@ -0,0 +1,37 @@
+set enable_planner_v2 = 1;
+
+DROP DATABASE IF EXISTS db_20_0006;
+CREATE DATABASE db_20_0006;
+USE db_20_0006;
+
+CREATE TABLE t(a INT, b INT);
+INSERT INTO t VALUES(1, 10), (2, 20), (3, 30), (4, 40);
+
+CREATE TABLE t1(a INT, b INT);
+INSERT INTO t1 VALUES(1, 5), (1, 15), (2, 25), (3, 1), (5, 50);
+
+-- UNION ALL and UNION
+SELECT a FROM t UNION ALL SELECT a FROM t1 ORDER BY a;
+SELECT a FROM t UNION SELECT a FROM t1 ORDER BY a;
+SELECT a FROM t UNION ALL SELECT a FROM t1 ORDER BY a LIMIT 3;
+
+-- INTERSECT and EXCEPT
+SELECT a FROM t1 INTERSECT SELECT a FROM t ORDER BY a;
+SELECT a FROM t1 INTERSECT ALL SELECT a FROM t ORDER BY a;
+SELECT a FROM t1 EXCEPT SELECT a FROM t ORDER BY a;
+SELECT a FROM t1 EXCEPT ALL SELECT a FROM t ORDER BY a;
+SELECT a FROM t EXCEPT SELECT a FROM t1;
+
+-- INTERSECT binds tighter than UNION
+SELECT a FROM t UNION SELECT a FROM t1 INTERSECT SELECT 5 ORDER BY a;
+(SELECT a FROM t UNION SELECT a FROM t1) EXCEPT SELECT 1 ORDER BY a;
+
+-- Type coercion
+SELECT 1 AS x UNION ALL SELECT 1000 ORDER BY x;
+SELECT a, b FROM t WHERE a = 1 UNION ALL SELECT b, a FROM t1 WHERE a = 5 ORDER BY a;
+
+SELECT a, b FROM t UNION SELECT a FROM t1; -- {ErrorCode 1065}
+
+DROP DATABASE db_20_0006;
+
+set enable_planner_v2 = 0;
@@ -0,0 +1,47 @@
+set enable_planner_v2 = 1;
+
+DROP DATABASE IF EXISTS db_20_0007;
+CREATE DATABASE db_20_0007;
+USE db_20_0007;
+
+CREATE TABLE t(a INT, b INT);
+INSERT INTO t VALUES(1, 10), (2, 20), (3, 30), (4, 40);
+
+CREATE TABLE t1(a INT, b INT);
+INSERT INTO t1 VALUES(1, 5), (1, 15), (2, 25), (3, 1), (5, 50);
+
+-- CTE referenced once
+WITH c AS (SELECT a, b FROM t WHERE a > 1) SELECT a, b FROM c ORDER BY a;
+
+-- CTE referenced more than once
+WITH c AS (SELECT a FROM t) SELECT c1.a, c2.a FROM c AS c1 JOIN c AS c2 ON c1.a = c2.a + 1 ORDER BY c1.a;
+WITH c AS (SELECT a FROM t) SELECT a FROM c UNION ALL SELECT a FROM c ORDER BY a;
+
+-- Column aliases
+WITH c(x, y) AS (SELECT a, b FROM t) SELECT y FROM c WHERE x = 2;
+
+-- A CTE references the CTEs declared before it
+WITH c1 AS (SELECT a FROM t), c2 AS (SELECT a * 2 AS d FROM c1) SELECT d FROM c2 ORDER BY d;
+
+-- CTE referenced by a subquery
+WITH c AS (SELECT a FROM t WHERE a < 3) SELECT a FROM t1 WHERE a IN (SELECT a FROM c) ORDER BY a;
+
+-- Recursive CTE
+WITH RECURSIVE r(n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM r WHERE n < 5) SELECT n FROM r ORDER BY n;
+WITH RECURSIVE r(n) AS (SELECT a FROM t WHERE a = 1 UNION ALL SELECT r.n + 1 FROM r JOIN t ON r.n + 1 = t.a) SELECT n FROM r ORDER BY n;
+
+-- UNION without ALL ends once no new row is found
+WITH RECURSIVE r(n) AS (SELECT 0 UNION SELECT (n + 1) % 3 FROM r) SELECT n FROM r ORDER BY n;
+
+-- The recursion is limited by max_recursive_cte_depth
+set max_recursive_cte_depth = 10;
+WITH RECURSIVE r(n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM r WHERE n < 10) SELECT count(*) FROM r;
+WITH RECURSIVE r(n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM r) SELECT count(*) FROM r; -- {ErrorCode 1105}
+set max_recursive_cte_depth = 1000;
+
+WITH c AS (SELECT 1), c AS (SELECT 2) SELECT * FROM c; -- {ErrorCode 1065}
+WITH c(x, y) AS (SELECT a FROM t) SELECT x FROM c; -- {ErrorCode 1065}
+
+DROP DATABASE db_20_0007;
+
+set enable_planner_v2 = 0;
@@ -0,0 +1,30 @@
+set enable_planner_v2 = 1;
+
+DROP DATABASE IF EXISTS db_20_0008;
+CREATE DATABASE db_20_0008;
+USE db_20_0008;
+
+CREATE TABLE t(a INT, b INT, v INT);
+INSERT INTO t VALUES(1, 1, 10), (1, 2, 20), (2, 1, 30), (2, 2, 40), (2, 2, 50);
+
+-- ROLLUP
+SELECT a, b, SUM(v), GROUPING(a, b) AS g FROM t GROUP BY ROLLUP(a, b) ORDER BY g, a, b;
+
+-- CUBE
+SELECT a, b, COUNT(*), GROUPING(a, b) AS g FROM t GROUP BY CUBE(a, b) ORDER BY g, a, b;
+
+-- GROUPING SETS with an empty grouping set
+SELECT a, b, SUM(v), GROUPING(a) AS ga, GROUPING(b) AS gb FROM t GROUP BY GROUPING SETS ((a), (b), ()) ORDER BY ga, gb, a, b;
+
+-- Alias as grouping item
+SELECT a AS x, SUM(v) AS s FROM t GROUP BY ROLLUP(x) ORDER BY s;
+
+-- HAVING with grouping columns
+SELECT a, SUM(v) AS s FROM t GROUP BY GROUPING SETS ((a), ()) HAVING a IS NULL OR a > 1 ORDER BY s;
+
+-- Errors
+SELECT a + 1, COUNT(*) FROM t GROUP BY ROLLUP(a + 1); -- {ErrorCode 1065}
+SELECT a, GROUPING(a) FROM t GROUP BY a; -- {ErrorCode 1065}
+SELECT a, GROUPING(v) FROM t GROUP BY ROLLUP(a); -- {ErrorCode 1065}
+
+DROP DATABASE db_20_0008;